@@ -0,0 +1,35 @@
+use tokio::sync::oneshot;
+
+use crate::admin::status::EngineStatus;
+use crate::execution::order_history::OrderLifecycle;
+
+/// Requests from the admin server to the engine. The main loop is the only writer of engine
+/// state, so handlers never touch it directly; they send one of these and await the reply.
+#[derive(Debug)]
+pub enum AdminCommand {
+    Status {
+        reply: oneshot::Sender<EngineStatus>,
+    },
+    /// Up to `limit` of the latest orders across instruments, most recent first.
+    RecentOrders {
+        limit: usize,
+        reply: oneshot::Sender<Vec<OrderLifecycle>>,
+    },
+    /// Replies with the switch's new state, or why the cancel-all that engaging sends failed;
+    /// the switch stays engaged either way.
+    SetKillSwitch {
+        engaged: bool,
+        reply: oneshot::Sender<Result<bool, String>>,
+    },
+    CancelAll {
+        reply: oneshot::Sender<Result<(), String>>,
+    },
+    Flatten {
+        reply: oneshot::Sender<Result<(), String>>,
+    },
+    /// Re-reads the config and applies what can change while running; replies with the
+    /// sections that changed.
+    Reload {
+        reply: oneshot::Sender<Result<Vec<&'static str>, String>>,
+    },
+}
@@ -0,0 +1,213 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use anyhow::{Context, Result};
+use axum::extract::{Query, Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::sync::{mpsc, oneshot, watch};
+use tracing::{Instrument as _, error, info};
+
+use crate::admin::command::AdminCommand;
+use crate::config::app_config::redacted;
+use crate::kraken::capture;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    /// Serve the admin API on this localhost port; the API is disabled when unset.
+    pub port: Option<u16>,
+
+    /// When set, every request must carry `Authorization: Bearer <token>`.
+    #[serde(serialize_with = "redacted")]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct AdminState {
+    token: Option<String>,
+    effective_config: watch::Receiver<Value>,
+    commands: mpsc::Sender<AdminCommand>,
+}
+
+/// Entries `GET /orders` and `GET /captures` return when no `limit` is given.
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+struct LimitQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KillSwitchRequest {
+    engaged: bool,
+}
+
+/// Binds the admin API on localhost and serves it in the background. The latest
+/// `effective_config` is served as-is from `GET /config`.
+pub async fn spawn(
+    port: u16,
+    config: &AdminConfig,
+    effective_config: watch::Receiver<Value>,
+    commands: mpsc::Sender<AdminCommand>,
+) -> Result<()> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .with_context(|| format!("failed to bind admin api on {address}"))?;
+
+    let app = router(AdminState {
+        token: config.token.clone(),
+        effective_config,
+        commands,
+    });
+
+    info!(%address, "admin api listening");
+
+    tokio::spawn(
+        async move {
+            if let Err(error) = axum::serve(listener, app).await {
+                error!("admin api stopped with error: {error:?}");
+            }
+        }
+        .in_current_span(),
+    );
+
+    Ok(())
+}
+
+fn router(state: AdminState) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/config", get(config))
+        .route("/orders", get(orders))
+        .route("/captures", get(captures))
+        .route("/kill-switch", post(kill_switch))
+        .route("/cancel-all", post(cancel_all))
+        .route("/flatten", post(flatten))
+        .route("/reload", post(reload))
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state)
+}
+
+async fn authorize(State(state): State<AdminState>, request: Request, next: Next) -> Response {
+    if let Some(token) = &state.token {
+        let expected = format!("Bearer {token}");
+        let provided = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+
+        if provided != Some(expected.as_str()) {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+async fn status(State(state): State<AdminState>) -> Response {
+    match request(&state, |reply| AdminCommand::Status { reply }).await {
+        Ok(status) => Json(status).into_response(),
+        Err(response) => response,
+    }
+}
+
+async fn orders(State(state): State<AdminState>, Query(query): Query<LimitQuery>) -> Response {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    match request(&state, |reply| AdminCommand::RecentOrders { limit, reply }).await {
+        Ok(orders) => Json(orders).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// Served straight from the capture buffer; the engine loop is not involved.
+async fn captures(Query(query): Query<LimitQuery>) -> Response {
+    match capture::recent(query.limit.unwrap_or(DEFAULT_LIMIT)) {
+        Some(entries) => Json(entries).into_response(),
+        None => (StatusCode::NOT_FOUND, "venue capture is disabled").into_response(),
+    }
+}
+
+async fn config(State(state): State<AdminState>) -> Json<Value> {
+    Json(state.effective_config.borrow().clone())
+}
+
+async fn kill_switch(
+    State(state): State<AdminState>,
+    Json(body): Json<KillSwitchRequest>,
+) -> Response {
+    let engaged = body.engaged;
+    match request(&state, |reply| AdminCommand::SetKillSwitch {
+        engaged,
+        reply,
+    })
+    .await
+    {
+        Ok(Ok(engaged)) => Json(json!({ "kill_switch": engaged })).into_response(),
+        Ok(Err(message)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "kill_switch": engaged, "error": message })),
+        )
+            .into_response(),
+        Err(response) => response,
+    }
+}
+
+async fn cancel_all(State(state): State<AdminState>) -> Response {
+    let result = request(&state, |reply| AdminCommand::CancelAll { reply }).await;
+    acknowledge(result)
+}
+
+async fn flatten(State(state): State<AdminState>) -> Response {
+    let result = request(&state, |reply| AdminCommand::Flatten { reply }).await;
+    acknowledge(result)
+}
+
+async fn reload(State(state): State<AdminState>) -> Response {
+    match request(&state, |reply| AdminCommand::Reload { reply }).await {
+        Ok(Ok(changed)) => Json(json!({ "ok": true, "changed": changed })).into_response(),
+        Ok(Err(message)) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "ok": false, "error": message })),
+        )
+            .into_response(),
+        Err(response) => response,
+    }
+}
+
+fn acknowledge(result: Result<Result<(), String>, Response>) -> Response {
+    match result {
+        Ok(Ok(())) => Json(json!({ "ok": true })).into_response(),
+        Ok(Err(message)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "ok": false, "error": message })),
+        )
+            .into_response(),
+        Err(response) => response,
+    }
+}
+
+/// Sends a command to the engine and waits for its reply.
+async fn request<T>(
+    state: &AdminState,
+    command: impl FnOnce(oneshot::Sender<T>) -> AdminCommand,
+) -> Result<T, Response> {
+    let (reply, response) = oneshot::channel();
+
+    state
+        .commands
+        .send(command(reply))
+        .await
+        .map_err(|_| engine_unavailable())?;
+
+    response.await.map_err(|_| engine_unavailable())
+}
+
+fn engine_unavailable() -> Response {
+    (StatusCode::SERVICE_UNAVAILABLE, "engine is not running").into_response()
+}
@@ -0,0 +1,101 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::engine::supervisor::TaskHealth;
+use crate::execution::order_action::Side;
+use crate::execution::order_manager::OrderManager;
+use crate::execution::types::OrderSideState;
+use crate::market::market_state::MarketState;
+use crate::types::inventory::Inventory;
+use crate::types::price::Price;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineStatus {
+    pub kill_switch: bool,
+    pub instruments: Vec<InstrumentStatus>,
+    /// Background tasks under the engine's supervisor.
+    pub tasks: Vec<TaskHealth>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstrumentStatus {
+    pub instrument: String,
+    pub orders: OrdersStatus,
+    pub market: MarketStatus,
+    pub inventory: Inventory,
+    pub exposure_quote: Option<f64>,
+    pub flattening: bool,
+    pub scheduler: SchedulerStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrdersStatus {
+    pub bid: OrderSideState,
+    pub ask: OrderSideState,
+    /// Levels behind `bid` and `ask`, level 1 first, for a layered strategy.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deeper_bids: Vec<OrderSideState>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deeper_asks: Vec<OrderSideState>,
+    pub open_orders: usize,
+}
+
+impl OrdersStatus {
+    pub fn capture(order_manager: &OrderManager) -> Self {
+        Self {
+            bid: order_manager.side(Side::Buy).state().clone(),
+            ask: order_manager.side(Side::Sell).state().clone(),
+            deeper_bids: deeper(order_manager, Side::Buy),
+            deeper_asks: deeper(order_manager, Side::Sell),
+            open_orders: order_manager.open_order_count(),
+        }
+    }
+}
+
+fn deeper(order_manager: &OrderManager, side: Side) -> Vec<OrderSideState> {
+    order_manager.levels(side)[1..]
+        .iter()
+        .map(|level| level.state().clone())
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketStatus {
+    pub best_bid: Option<Price>,
+    pub best_ask: Option<Price>,
+    pub mid: Option<Price>,
+    pub last_trade: Option<Price>,
+    pub stale: bool,
+}
+
+impl MarketStatus {
+    pub fn capture(market_state: &MarketState, max_age: Duration, now: Instant) -> Self {
+        Self {
+            best_bid: market_state.best_bid(),
+            best_ask: market_state.best_ask(),
+            mid: market_state.mid_price(),
+            last_trade: market_state.last_trade_price(),
+            stale: market_state.is_stale(max_age, now),
+        }
+    }
+}
+
+/// Running counts of scheduler outcomes since startup.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct SchedulerStatus {
+    pub evaluations: u64,
+    pub skips: u64,
+    pub last_skip: Option<&'static str>,
+}
+
+impl SchedulerStatus {
+    pub fn on_evaluate(&mut self) {
+        self.evaluations += 1;
+    }
+
+    pub fn on_skip(&mut self, reason: &'static str) {
+        self.skips += 1;
+        self.last_skip = Some(reason);
+    }
+}
@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use crate::execution::order_action::Side;
+use crate::telemetry::metrics::Feed;
+use crate::types::instrument::Instrument;
+use crate::types::price::Price;
+use crate::types::quantity::Quantity;
+
+#[derive(Debug, Clone)]
+pub enum Alert {
+    Fill {
+        order_id: String,
+        instrument: Instrument,
+        side: Side,
+        price: Price,
+        quantity: Quantity,
+    },
+    ConsecutiveRejections {
+        count: u32,
+        last_reason: String,
+    },
+    KillSwitch {
+        engaged: bool,
+    },
+    FeedDown {
+        feed: Feed,
+        silent_for: Duration,
+    },
+    LoopStalled {
+        stalled_for: Duration,
+    },
+}
+
+impl Alert {
+    /// Alerts sharing a key are deduplicated within the alerter's cooldown.
+    pub fn key(&self) -> String {
+        match self {
+            Alert::Fill { order_id, .. } => format!("fill:{order_id}"),
+            Alert::ConsecutiveRejections { .. } => "consecutive_rejections".to_string(),
+            Alert::KillSwitch { engaged } => format!("kill_switch:{engaged}"),
+            Alert::FeedDown { feed, .. } => format!("feed_down:{}", feed.label()),
+            Alert::LoopStalled { .. } => "loop_stalled".to_string(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Alert::Fill {
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+            } => format!("Filled {side} {quantity} {instrument} @ {price} ({order_id})"),
+            Alert::ConsecutiveRejections { count, last_reason } => {
+                format!("{count} consecutive order rejections; last: {last_reason}")
+            }
+            Alert::KillSwitch { engaged: true } => "Kill switch engaged".to_string(),
+            Alert::KillSwitch { engaged: false } => "Kill switch disengaged".to_string(),
+            Alert::FeedDown { feed, silent_for } => {
+                format!("{} feed silent for {}s", feed.label(), silent_for.as_secs())
+            }
+            Alert::LoopStalled { stalled_for } => {
+                format!(
+                    "Engine loop has not completed an iteration for {}s",
+                    stalled_for.as_secs()
+                )
+            }
+        }
+    }
+}
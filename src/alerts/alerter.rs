@@ -0,0 +1,280 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{Instrument as _, warn};
+
+use crate::alerts::alert::Alert;
+use crate::alerts::webhook::{Webhook, WebhookKind};
+use crate::config::app_config::{ensure, redacted};
+use crate::execution::order_report::OrderReport;
+use crate::telemetry::liveness;
+use crate::telemetry::metrics::{self, Feed};
+
+const ALERT_CHANNEL_CAPACITY: usize = 256;
+const LIVENESS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const MAX_ALERTS_PER_WINDOW: usize = 20;
+
+/// Alerting section of the application config; alerts are disabled without a webhook URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertsConfig {
+    /// Slack-compatible webhook or Telegram `sendMessage` URL.
+    #[serde(serialize_with = "redacted")]
+    pub webhook_url: Option<String>,
+    pub webhook_kind: WebhookKind,
+    pub telegram_chat_id: Option<String>,
+    pub reject_threshold: u32,
+    pub feed_down_secs: u64,
+    /// Seconds the engine loop may go without completing an iteration before alerting.
+    pub loop_stall_secs: u64,
+    pub cooldown_secs: u64,
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            webhook_kind: WebhookKind::Slack,
+            telegram_chat_id: None,
+            reject_threshold: 3,
+            feed_down_secs: 30,
+            loop_stall_secs: 15,
+            cooldown_secs: 300,
+        }
+    }
+}
+
+impl AlertsConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.reject_threshold > 0,
+            format!("{path}.reject_threshold"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.loop_stall_secs > 0,
+            format!("{path}.loop_stall_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.webhook_url.is_none()
+                || self.webhook_kind != WebhookKind::Telegram
+                || self.telegram_chat_id.is_some(),
+            format!("{path}.telegram_chat_id"),
+            "is required for telegram webhooks",
+        )?;
+
+        Ok(())
+    }
+
+    /// Runtime alerter settings, or `None` when no webhook is configured.
+    pub fn alerter_config(&self) -> Result<Option<AlerterConfig>> {
+        let Some(url) = &self.webhook_url else {
+            return Ok(None);
+        };
+
+        Ok(Some(AlerterConfig {
+            webhook: Webhook::new(
+                self.webhook_kind,
+                url.clone(),
+                self.telegram_chat_id.clone(),
+            )?,
+            reject_threshold: self.reject_threshold,
+            feed_down_after: Duration::from_secs(self.feed_down_secs),
+            loop_stall_after: Duration::from_secs(self.loop_stall_secs),
+            cooldown: Duration::from_secs(self.cooldown_secs),
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AlerterConfig {
+    pub webhook: Webhook,
+    /// Consecutive `Rejected`/`VenueError` reports before alerting.
+    pub reject_threshold: u32,
+    /// How long a feed may go quiet before it is reported down.
+    pub feed_down_after: Duration,
+    /// How long the engine loop may go without an iteration before it is reported stalled.
+    pub loop_stall_after: Duration,
+    /// Minimum time between two alerts with the same key.
+    pub cooldown: Duration,
+}
+
+/// Engine-side handle. Raising never blocks: if the alerter is disabled or backed up the
+/// alert is dropped, so delivery problems can never stall trading.
+#[derive(Debug, Clone)]
+pub struct AlertHandle {
+    sender: mpsc::Sender<Alert>,
+}
+
+impl AlertHandle {
+    /// Handle for when no webhook is configured; every alert is discarded.
+    pub fn disabled() -> Self {
+        let (sender, _) = mpsc::channel(1);
+        Self { sender }
+    }
+
+    pub fn raise(&self, alert: Alert) {
+        let _ = self.sender.try_send(alert);
+    }
+}
+
+#[derive(Debug)]
+pub struct Alerter {
+    config: AlerterConfig,
+    consecutive_rejections: u32,
+    last_sent: HashMap<String, Instant>,
+    recent: VecDeque<Instant>,
+}
+
+impl Alerter {
+    pub fn new(config: AlerterConfig) -> Self {
+        Self {
+            config,
+            consecutive_rejections: 0,
+            last_sent: HashMap::new(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    pub fn on_report(&mut self, report: &OrderReport) -> Option<Alert> {
+        match report {
+            OrderReport::Filled {
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+                ..
+            } => Some(Alert::Fill {
+                order_id: order_id.clone(),
+                instrument: instrument.clone(),
+                side: *side,
+                price: *price,
+                quantity: *quantity,
+            }),
+            OrderReport::Rejected { .. } | OrderReport::VenueError { .. } => {
+                self.consecutive_rejections += 1;
+
+                (self.consecutive_rejections >= self.config.reject_threshold).then(|| {
+                    Alert::ConsecutiveRejections {
+                        count: self.consecutive_rejections,
+                        last_reason: report.reason().unwrap_or_default().to_string(),
+                    }
+                })
+            }
+            OrderReport::Accepted { .. } => {
+                self.consecutive_rejections = 0;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Feeds silent past the threshold and a stalled engine loop, given how long each
+    /// feed has been silent and how long since the loop last completed an iteration.
+    pub fn liveness_alerts(
+        &self,
+        silent_for: impl Fn(Feed) -> Option<Duration>,
+        loop_idle_for: Option<Duration>,
+    ) -> Vec<Alert> {
+        let feeds_down = Feed::ALL.into_iter().filter_map(|feed| {
+            silent_for(feed)
+                .filter(|silent_for| *silent_for >= self.config.feed_down_after)
+                .map(|silent_for| Alert::FeedDown { feed, silent_for })
+        });
+        let loop_stalled = loop_idle_for
+            .filter(|idle_for| *idle_for >= self.config.loop_stall_after)
+            .map(|stalled_for| Alert::LoopStalled { stalled_for });
+
+        feeds_down.chain(loop_stalled).collect()
+    }
+
+    /// Applies per-key deduplication and the global rate limit; returns whether to deliver.
+    pub fn admit(&mut self, alert: &Alert, now: Instant) -> bool {
+        let key = alert.key();
+        if let Some(sent) = self.last_sent.get(&key)
+            && now.duration_since(*sent) < self.config.cooldown
+        {
+            return false;
+        }
+
+        while let Some(sent) = self.recent.front()
+            && now.duration_since(*sent) >= RATE_WINDOW
+        {
+            self.recent.pop_front();
+        }
+
+        if self.recent.len() >= MAX_ALERTS_PER_WINDOW {
+            return false;
+        }
+
+        self.last_sent
+            .retain(|_, sent| now.duration_since(*sent) < self.config.cooldown);
+        self.last_sent.insert(key, now);
+        self.recent.push_back(now);
+
+        true
+    }
+
+    async fn deliver(&mut self, alert: Alert) {
+        if !self.admit(&alert, Instant::now()) {
+            return;
+        }
+
+        if let Err(error) = self.config.webhook.send(&alert.message()).await {
+            warn!(alert = %alert.key(), "failed to deliver alert: {error:#}");
+        }
+    }
+
+    /// Runs the alerter on its own task, consuming order reports and raised alerts.
+    pub fn spawn(
+        config: AlerterConfig,
+        mut reports: broadcast::Receiver<OrderReport>,
+    ) -> AlertHandle {
+        let (sender, mut alerts) = mpsc::channel(ALERT_CHANNEL_CAPACITY);
+        let handle = AlertHandle { sender };
+
+        tokio::spawn(
+            async move {
+                let mut alerter = Alerter::new(config);
+                let mut liveness_check = tokio::time::interval(LIVENESS_CHECK_INTERVAL);
+
+                loop {
+                    tokio::select! {
+                        report = reports.recv() => match report {
+                            Ok(report) => {
+                                if let Some(alert) = alerter.on_report(&report) {
+                                    alerter.deliver(alert).await;
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Lagged(n)) => {
+                                metrics::reports_lagged("alerts", n);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        },
+                        alert = alerts.recv() => match alert {
+                            Some(alert) => alerter.deliver(alert).await,
+                            // Every handle is gone, e.g. replaced on a config reload.
+                            None => break,
+                        },
+                        _ = liveness_check.tick() => {
+                            let alerts = alerter
+                                .liveness_alerts(liveness::silent_for, liveness::loop_idle_for());
+                            for alert in alerts {
+                                alerter.deliver(alert).await;
+                            }
+                        }
+                    }
+                }
+            }
+            .in_current_span(),
+        );
+
+        handle
+    }
+}
@@ -0,0 +1,3 @@
+pub mod alert;
+pub mod alerter;
+pub mod webhook;
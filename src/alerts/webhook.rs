@@ -0,0 +1,74 @@
+use std::fmt;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// Slack-compatible incoming webhook (`{"text": ...}`).
+    #[default]
+    Slack,
+    /// Telegram bot API `sendMessage` URL; requires a chat id.
+    Telegram,
+}
+
+impl fmt::Display for WebhookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Slack => write!(f, "slack"),
+            Self::Telegram => write!(f, "telegram"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    http: reqwest::Client,
+    kind: WebhookKind,
+    url: String,
+    chat_id: Option<String>,
+}
+
+impl Webhook {
+    pub fn new(kind: WebhookKind, url: String, chat_id: Option<String>) -> Result<Self> {
+        if kind == WebhookKind::Telegram && chat_id.is_none() {
+            bail!("telegram alerts require a chat id");
+        }
+
+        let http = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .context("failed to build webhook client")?;
+
+        Ok(Self {
+            http,
+            kind,
+            url,
+            chat_id,
+        })
+    }
+
+    pub async fn send(&self, text: &str) -> Result<()> {
+        let body = match self.kind {
+            WebhookKind::Slack => json!({ "text": text }),
+            WebhookKind::Telegram => json!({ "chat_id": self.chat_id, "text": text }),
+        };
+
+        self.http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .context("webhook request failed")?
+            .error_for_status()
+            .context("webhook returned an error status")?;
+
+        Ok(())
+    }
+}
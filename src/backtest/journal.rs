@@ -0,0 +1,264 @@
+use std::fs::{self, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+use crate::events::MarketEvent;
+use crate::types::instrument::{Instrument, InstrumentConfig};
+use crate::types::price::Price;
+
+pub const JOURNAL_HEADER: &str = "timestamp_ms,instrument,kind,best_bid,best_ask,price,quantity";
+
+/// Events waiting for the disk in a [`JournalRecorder`].
+const RECORDER_QUEUE: usize = 10_000;
+
+/// One market event of a journal: a CSV row or a JSON line with the same fields. Books
+/// carry `best_bid` and `best_ask`, trades `price` and `quantity`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalRow {
+    pub timestamp_ms: u64,
+    pub instrument: String,
+    /// `book` or `trade`.
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best_bid: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best_ask: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+}
+
+impl JournalRow {
+    /// The row of `event`; depth updates and the feed's own events have none, as the
+    /// replay does not use them.
+    pub fn from_event(event: &MarketEvent) -> Option<Self> {
+        let row = Self {
+            timestamp_ms: event.timestamp_ms(),
+            instrument: event.instrument().to_string(),
+            kind: event.kind().to_string(),
+            best_bid: None,
+            best_ask: None,
+            price: None,
+            quantity: None,
+        };
+
+        match event {
+            MarketEvent::TopOfBook {
+                best_bid, best_ask, ..
+            } => Some(Self {
+                best_bid: Some(best_bid.as_f64()),
+                best_ask: Some(best_ask.as_f64()),
+                ..row
+            }),
+            MarketEvent::Trade {
+                price, quantity, ..
+            } => Some(Self {
+                price: Some(price.as_f64()),
+                quantity: Some(*quantity),
+                ..row
+            }),
+            MarketEvent::BookUpdate { .. }
+            | MarketEvent::SourceDisconnected { .. }
+            | MarketEvent::SourceReconnected { .. } => None,
+        }
+    }
+
+    fn from_csv(line: &str) -> Result<Self> {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [
+            timestamp_ms,
+            instrument,
+            kind,
+            best_bid,
+            best_ask,
+            price,
+            quantity,
+        ] = fields[..]
+        else {
+            bail!("expected 7 fields, found {}", fields.len());
+        };
+
+        let number = |value: &str, field: &str| -> Result<Option<f64>> {
+            if value.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(value.parse().with_context(|| field.to_string())?))
+        };
+
+        Ok(Self {
+            timestamp_ms: timestamp_ms.parse().context("timestamp_ms")?,
+            instrument: instrument.to_string(),
+            kind: kind.to_string(),
+            best_bid: number(best_bid, "best_bid")?,
+            best_ask: number(best_ask, "best_ask")?,
+            price: number(price, "price")?,
+            quantity: number(quantity, "quantity")?,
+        })
+    }
+
+    /// The row's event, or `None` when its instrument is not one of `instruments`.
+    pub fn event(&self, instruments: &[Instrument]) -> Result<Option<MarketEvent>> {
+        let symbol = InstrumentConfig::from_str(&self.instrument)?;
+        let Some(instrument) = instruments.iter().find(|instrument| {
+            instrument.base() == symbol.base && instrument.quote() == symbol.quote
+        }) else {
+            return Ok(None);
+        };
+        let instrument = instrument.clone();
+        let timestamp_ms = self.timestamp_ms;
+
+        let event = match self.kind.as_str() {
+            "book" => MarketEvent::TopOfBook {
+                instrument,
+                best_bid: price(self.best_bid, "best_bid")?,
+                best_ask: price(self.best_ask, "best_ask")?,
+                bid_size: None,
+                ask_size: None,
+                timestamp_ms,
+            },
+            "trade" => MarketEvent::Trade {
+                instrument,
+                price: price(self.price, "price")?,
+                quantity: self.quantity.context("quantity: missing")?,
+                timestamp_ms,
+            },
+            other => bail!("unknown kind {other}, expected book or trade"),
+        };
+
+        Ok(Some(event))
+    }
+}
+
+/// Reads a journal of market events, in file order: JSON lines when the path ends in
+/// `.jsonl`, as [`JournalRecorder`] writes, and CSV otherwise.
+///
+/// ```text
+/// timestamp_ms,instrument,kind,best_bid,best_ask,price,quantity
+/// 1700000000000,SOL/GBP,book,101.10,101.20,,
+/// 1700000000250,SOL/GBP,trade,,,101.15,0.5
+/// ```
+///
+/// Rows for instruments outside `instruments` are skipped, so one recording can drive
+/// backtests of any subset of its instruments. Timestamps must not go backwards.
+pub fn read(path: &Path, instruments: &[Instrument]) -> Result<Vec<MarketEvent>> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read journal {}", path.display()))?;
+    let json = path
+        .extension()
+        .is_some_and(|extension| extension == "jsonl");
+
+    let mut events = Vec::new();
+    let mut last_timestamp_ms = 0;
+
+    for (index, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line == JOURNAL_HEADER {
+            continue;
+        }
+
+        let row = if json {
+            serde_json::from_str(line).map_err(anyhow::Error::from)
+        } else {
+            JournalRow::from_csv(line)
+        };
+        let event = row
+            .and_then(|row| row.event(instruments))
+            .with_context(|| format!("{}:{}: invalid journal row", path.display(), index + 1))?;
+        let Some(event) = event else {
+            continue;
+        };
+
+        let timestamp_ms = event.timestamp_ms();
+        if timestamp_ms < last_timestamp_ms {
+            bail!(
+                "{}:{}: timestamp {timestamp_ms} is before the previous row",
+                path.display(),
+                index + 1
+            );
+        }
+        last_timestamp_ms = timestamp_ms;
+
+        events.push(event);
+    }
+
+    Ok(events)
+}
+
+fn price(value: Option<f64>, field: &str) -> Result<Price> {
+    let Some(value) = value else {
+        bail!("{field}: missing");
+    };
+    if !value.is_finite() || value < 0.0 {
+        bail!("{field}: must be a non-negative number");
+    }
+
+    Ok(Price::new(value))
+}
+
+/// Appends the books and trades a session sees to a JSON lines journal the backtest can
+/// replay. Events are written from a thread of their own; when the disk falls behind,
+/// events are dropped with a warning rather than hold up the engine.
+#[derive(Debug)]
+pub struct JournalRecorder {
+    sender: mpsc::Sender<JournalRow>,
+    dropping: bool,
+}
+
+impl JournalRecorder {
+    pub fn spawn(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create journal dir {}", dir.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open journal {}", path.display()))?;
+
+        let (sender, mut rows) = mpsc::channel::<JournalRow>(RECORDER_QUEUE);
+        let path = path.to_path_buf();
+
+        std::thread::spawn(move || {
+            let mut file = BufWriter::new(file);
+            while let Some(row) = rows.blocking_recv() {
+                let mut result = serde_json::to_string(&row)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|line| Ok(writeln!(file, "{line}")?));
+                if rows.is_empty() {
+                    result = result.and_then(|()| Ok(file.flush()?));
+                }
+                if let Err(error) = result {
+                    error!(path = %path.display(), "failed to write journal row: {error:#}");
+                }
+            }
+        });
+
+        Ok(Self {
+            sender,
+            dropping: false,
+        })
+    }
+
+    pub fn record(&mut self, event: &MarketEvent) {
+        let Some(row) = JournalRow::from_event(event) else {
+            return;
+        };
+
+        match self.sender.try_send(row) {
+            Ok(()) => self.dropping = false,
+            Err(_) if self.dropping => {}
+            Err(_) => {
+                self.dropping = true;
+                warn!("market journal queue full; events not recorded");
+            }
+        }
+    }
+}
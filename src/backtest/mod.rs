@@ -0,0 +1,4 @@
+pub mod journal;
+pub mod report;
+pub mod runner;
+pub mod sim_venue;
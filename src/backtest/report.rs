@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::execution::order_action::Side;
+use crate::stats::session_summary::SessionSummary;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FillRecord {
+    pub timestamp_ms: u64,
+    pub instrument: String,
+    pub order_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub quantity: f64,
+}
+
+/// One sample of an instrument's PnL and exposure curve.
+#[derive(Debug, Clone, Serialize)]
+pub struct CurvePoint {
+    pub timestamp_ms: u64,
+    pub instrument: String,
+    pub mid: f64,
+    pub inventory_base: f64,
+    pub exposure_quote: f64,
+    pub pnl_quote: f64,
+}
+
+/// Results of one backtest: the same session summary a live run writes, plus every fill
+/// and the sampled PnL/exposure curve.
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub journal: String,
+    pub events: usize,
+    pub summary: SessionSummary,
+    pub fills: Vec<FillRecord>,
+    pub curve: Vec<CurvePoint>,
+}
+
+impl BacktestReport {
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("failed to write backtest report {}", path.display()))
+    }
+}
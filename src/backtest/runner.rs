@@ -0,0 +1,245 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use chrono::DateTime;
+use tokio::sync::broadcast;
+
+use crate::backtest::journal;
+use crate::backtest::report::{BacktestReport, CurvePoint, FillRecord};
+use crate::backtest::sim_venue::SimVenue;
+use crate::clock::SimClock;
+use crate::config::app_config::AppConfig;
+use crate::engine::instrument_engine::{InstrumentEngine, SharedContext};
+use crate::engine::supervisor::Supervisor;
+use crate::events::MarketEvent;
+use crate::execution::order_ids::OrderIds;
+use crate::execution::order_report::OrderReport;
+use crate::risk::checks::kill_switch::KillSwitch;
+use crate::risk::checks::portfolio_exposure::PortfolioExposure;
+use crate::scenario::scenario::DynamicVenue;
+use crate::stats::session_stats::{SessionStats, StatsHandle};
+use crate::stats::session_summary::SessionSummary;
+use crate::stats::trading_book::TradingBook;
+use crate::telemetry::cycles::CycleIds;
+use crate::telemetry::decision_log::DecisionLog;
+use crate::types::instrument::Instrument;
+use crate::types::inventory::Inventory;
+use crate::types::price::Price;
+
+const REPORT_CHANNEL_CAPACITY: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct BacktestOptions {
+    /// Starting balances of every instrument.
+    pub initial: Inventory,
+    /// Virtual time between two points of the PnL/exposure curve.
+    pub sample_interval: Duration,
+}
+
+/// One instrument's engine plus the state the replay keeps alongside it.
+struct Lane {
+    engine: InstrumentEngine,
+    stats: Arc<Mutex<SessionStats>>,
+    mid: Option<Price>,
+    next_sample_ms: u64,
+}
+
+/// Replays `journal` through the live pipeline: the same [`InstrumentEngine`] the
+/// engine runs, against a [`SimVenue`]. Time is virtual: a [`SimClock`] moves to each
+/// event's timestamp before it is processed, and reports are applied synchronously
+/// between events, so the results depend only on the journal and the config.
+pub async fn run(
+    config: &AppConfig,
+    instruments: Vec<Instrument>,
+    journal: &Path,
+    options: &BacktestOptions,
+) -> Result<BacktestReport> {
+    let events = journal::read(journal, &instruments)?;
+    let (Some(first), Some(last)) = (events.first(), events.last()) else {
+        bail!(
+            "journal {} has no events for the configured instruments",
+            journal.display()
+        );
+    };
+    let (first_ms, last_ms) = (first.timestamp_ms(), last.timestamp_ms());
+
+    let (report_sender, mut reports) = broadcast::channel(REPORT_CHANNEL_CAPACITY);
+    let sim = SimVenue::new(report_sender.clone(), options.initial);
+    let venue: DynamicVenue = Box::new(sim.clone());
+
+    let clock = SimClock::from_timestamp_ms(first_ms);
+    let shared = SharedContext {
+        kill_switch: KillSwitch::new(config.risk.kill_switch),
+        portfolio: config
+            .risk
+            .max_portfolio_exposure_in_quote
+            .map(|max_exposure| (PortfolioExposure::default(), max_exposure)),
+        scoped_cancels: instruments.len() > 1,
+        clock: clock.shared(),
+        order_ids: OrderIds::sequential(),
+        cycle_ids: CycleIds::default(),
+        supervisor: Supervisor::new().0,
+        fill_report: None,
+        decision_log: config
+            .logging
+            .decision_log
+            .as_deref()
+            .map(DecisionLog::open)
+            .transpose()?,
+    };
+
+    let mut lanes = Vec::with_capacity(instruments.len());
+    for instrument in &instruments {
+        let stats = Arc::new(Mutex::new(SessionStats::new(
+            clock.shared(),
+            TradingBook::default(),
+            config.stats.equity_curve(),
+        )));
+        let engine = InstrumentEngine::build(
+            config,
+            instrument.clone(),
+            &venue,
+            &report_sender,
+            &shared,
+            StatsHandle::inline(instrument, stats.clone()),
+        )
+        .await?;
+
+        lanes.push(Lane {
+            engine,
+            stats,
+            mid: None,
+            next_sample_ms: first_ms,
+        });
+    }
+
+    let mut fills = Vec::new();
+    let mut curve = Vec::new();
+
+    for event in &events {
+        let timestamp_ms = event.timestamp_ms();
+        clock.set_timestamp_ms(timestamp_ms);
+
+        sim.on_market_event(event);
+        drain(&mut reports, &mut lanes, &mut fills, timestamp_ms);
+
+        let instrument = event.instrument();
+        let Some(index) = lanes
+            .iter()
+            .position(|lane| lane.engine.instrument() == instrument)
+        else {
+            continue;
+        };
+
+        let lane = &mut lanes[index];
+        if let MarketEvent::TopOfBook {
+            best_bid, best_ask, ..
+        } = event
+        {
+            lane.mid = Some(Price::new((best_bid.as_f64() + best_ask.as_f64()) / 2.0));
+        }
+
+        lane.engine.on_market_event(event, &venue).await?;
+        drain(&mut reports, &mut lanes, &mut fills, timestamp_ms);
+
+        // Lets background report consumers, like the min-interval policy, catch up.
+        tokio::task::yield_now().await;
+
+        let lane = &mut lanes[index];
+        if timestamp_ms >= lane.next_sample_ms {
+            curve.extend(sample(lane, &sim, timestamp_ms));
+            lane.next_sample_ms = timestamp_ms + options.sample_interval.as_millis() as u64;
+        }
+    }
+
+    let mut summaries = Vec::with_capacity(lanes.len());
+    for lane in &lanes {
+        curve.extend(sample(lane, &sim, last_ms));
+        summaries.push(
+            lane.stats
+                .lock()
+                .unwrap()
+                .instrument_summary(lane.engine.instrument()),
+        );
+    }
+
+    let stem = journal
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "journal".to_string());
+
+    let summary = SessionSummary::new(
+        &format!("backtest-{stem}"),
+        DateTime::from_timestamp_millis(first_ms as i64).unwrap_or_default(),
+        DateTime::from_timestamp_millis(last_ms as i64).unwrap_or_default(),
+        summaries,
+    );
+
+    Ok(BacktestReport {
+        journal: journal.display().to_string(),
+        events: events.len(),
+        summary,
+        fills,
+        curve,
+    })
+}
+
+/// Applies every pending report to the engines and stats it concerns.
+fn drain(
+    reports: &mut broadcast::Receiver<OrderReport>,
+    lanes: &mut [Lane],
+    fills: &mut Vec<FillRecord>,
+    timestamp_ms: u64,
+) {
+    while let Ok(report) = reports.try_recv() {
+        if let OrderReport::PartiallyFilled {
+            order_id,
+            instrument,
+            side,
+            price,
+            quantity,
+            ..
+        }
+        | OrderReport::Filled {
+            order_id,
+            instrument,
+            side,
+            price,
+            quantity,
+            ..
+        } = &report
+        {
+            fills.push(FillRecord {
+                timestamp_ms,
+                instrument: instrument.to_string(),
+                order_id: order_id.clone(),
+                side: *side,
+                price: price.as_f64(),
+                quantity: quantity.as_f64(),
+            });
+        }
+
+        for lane in lanes.iter_mut() {
+            if report.concerns(lane.engine.instrument()) {
+                lane.stats.lock().unwrap().on_report(&report);
+                lane.engine.on_report(report.clone());
+            }
+        }
+    }
+}
+
+fn sample(lane: &Lane, sim: &SimVenue, timestamp_ms: u64) -> Option<CurvePoint> {
+    let mid = lane.mid?;
+    let inventory = sim.inventory(lane.engine.instrument());
+
+    Some(CurvePoint {
+        timestamp_ms,
+        instrument: lane.engine.instrument().to_string(),
+        mid: mid.as_f64(),
+        inventory_base: inventory.base,
+        exposure_quote: inventory.exposure_quote(mid),
+        pnl_quote: lane.stats.lock().unwrap().book().pnl(mid),
+    })
+}
@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::watch;
+
+use crate::engine::supervisor::Supervisor;
+use crate::events::MarketEvent;
+use crate::execution::order_action::{Order, OrderAction, Side};
+use crate::execution::order_report::{OrderReport, RejectKind};
+use crate::execution::types::OpenOrder;
+use crate::execution::{DynamicInventorySource, ExecutionVenue, ReportSender};
+use crate::inventory::InventorySource;
+use crate::types::instrument::Instrument;
+use crate::types::inventory::Inventory;
+use crate::types::price::Price;
+use crate::types::quantity::Quantity;
+
+/// Simulated matching venue for backtests.
+///
+/// Orders are post-only: a placement that would cross the last seen book is rejected,
+/// anything else rests until the market trades through it. A book that moves through a
+/// resting order fills it completely; a trade through it fills up to the trade size.
+/// Fills are always at the order's own price. Everything happens synchronously inside
+/// [`ExecutionVenue::execute`] and [`SimVenue::on_market_event`], so a replay is fully
+/// determined by its input.
+#[derive(Clone)]
+pub struct SimVenue {
+    inner: Arc<Mutex<SimState>>,
+    reports: ReportSender,
+}
+
+struct SimState {
+    initial: Inventory,
+    resting: Vec<RestingOrder>,
+    books: HashMap<Instrument, (Price, Price)>,
+    inventories: HashMap<Instrument, watch::Sender<Inventory>>,
+}
+
+struct RestingOrder {
+    order: Order,
+    filled: Quantity,
+}
+
+impl RestingOrder {
+    fn remaining(&self) -> Quantity {
+        self.order.quantity.saturating_sub(self.filled)
+    }
+}
+
+/// Whether `filled` is all of `order`, to the instrument's quantity step.
+fn is_complete(order: &Order, filled: Quantity) -> bool {
+    filled >= order.quantity
+        || order
+            .instrument
+            .trading_rules()
+            .quantity_eq(order.quantity, filled)
+}
+
+struct SimInventory {
+    sender: watch::Sender<Inventory>,
+}
+
+impl InventorySource for SimInventory {
+    fn subscribe(&self) -> watch::Receiver<Inventory> {
+        self.sender.subscribe()
+    }
+}
+
+impl SimVenue {
+    /// Every instrument starts from `initial` balances.
+    pub fn new(reports: ReportSender, initial: Inventory) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SimState {
+                initial,
+                resting: Vec::new(),
+                books: HashMap::new(),
+                inventories: HashMap::new(),
+            })),
+            reports,
+        }
+    }
+
+    pub fn inventory(&self, instrument: &Instrument) -> Inventory {
+        let state = self.inner.lock().unwrap();
+        state
+            .inventories
+            .get(instrument)
+            .map_or(state.initial, |sender| *sender.borrow())
+    }
+
+    /// Updates the book and fills whatever the event trades through.
+    pub fn on_market_event(&self, event: &MarketEvent) {
+        let mut state = self.inner.lock().unwrap();
+        let instrument = event.instrument();
+
+        let mut fills = Vec::new();
+        match event {
+            MarketEvent::TopOfBook {
+                best_bid, best_ask, ..
+            } => {
+                state
+                    .books
+                    .insert(instrument.clone(), (*best_bid, *best_ask));
+
+                for resting in state.resting.iter_mut() {
+                    let crossed = match resting.order.side {
+                        Side::Buy => *best_ask <= resting.order.price,
+                        Side::Sell => *best_bid >= resting.order.price,
+                    };
+                    if resting.order.instrument == *instrument && crossed {
+                        let quantity = resting.remaining();
+                        resting.filled += quantity;
+                        fills.push((resting.order.clone(), quantity, resting.filled));
+                    }
+                }
+            }
+            MarketEvent::Trade {
+                price, quantity, ..
+            } => {
+                // A trade size that is no number fills nothing.
+                let mut available = Quantity::new(*quantity).unwrap_or_default();
+                for resting in state.resting.iter_mut() {
+                    let traded_through = match resting.order.side {
+                        Side::Buy => *price < resting.order.price,
+                        Side::Sell => *price > resting.order.price,
+                    };
+                    if resting.order.instrument != *instrument || !traded_through {
+                        continue;
+                    }
+                    if available.is_zero() {
+                        break;
+                    }
+
+                    let filled = resting.remaining().min(available);
+                    available = available.saturating_sub(filled);
+                    resting.filled += filled;
+                    fills.push((resting.order.clone(), filled, resting.filled));
+                }
+            }
+            // Fills come from the touch and trades, not levels further out.
+            MarketEvent::BookUpdate { .. }
+            | MarketEvent::SourceDisconnected { .. }
+            | MarketEvent::SourceReconnected { .. } => {}
+        }
+
+        state
+            .resting
+            .retain(|resting| !is_complete(&resting.order, resting.filled));
+
+        for (order, quantity, cum_quantity) in fills {
+            state.apply_fill(&order, quantity);
+
+            let report = if !is_complete(&order, cum_quantity) {
+                OrderReport::PartiallyFilled {
+                    order_id: order.order_id,
+                    instrument: order.instrument,
+                    side: order.side,
+                    price: order.price,
+                    quantity,
+                    cum_quantity,
+                }
+            } else {
+                OrderReport::Filled {
+                    order_id: order.order_id,
+                    instrument: order.instrument,
+                    side: order.side,
+                    price: order.price,
+                    quantity,
+                    cum_quantity,
+                }
+            };
+            let _ = self.reports.send(report);
+        }
+    }
+
+    fn place(&self, order: &Order) {
+        let mut state = self.inner.lock().unwrap();
+
+        let _ = self.reports.send(OrderReport::Placed {
+            order_id: order.order_id.clone(),
+            instrument: order.instrument.clone(),
+            side: order.side,
+            price: order.price,
+            quantity: order.quantity,
+        });
+
+        if state.would_cross(&order.instrument, order.side, order.price) {
+            let _ = self.reports.send(OrderReport::Rejected {
+                order_id: order.order_id.clone(),
+                instrument: order.instrument.clone(),
+                side: order.side,
+                kind: RejectKind::PostOnlyWouldCross,
+                reason: "post only order would cross".to_string(),
+            });
+            return;
+        }
+
+        state.resting.push(RestingOrder {
+            order: order.clone(),
+            filled: Quantity::ZERO,
+        });
+
+        let _ = self.reports.send(OrderReport::Accepted {
+            order_id: order.order_id.clone(),
+            instrument: order.instrument.clone(),
+            side: order.side,
+            price: order.price,
+            quantity: order.quantity,
+        });
+    }
+
+    fn cancel(&self, order_id: &str, instrument: &Instrument, side: Side) {
+        let mut state = self.inner.lock().unwrap();
+
+        let _ = self.reports.send(OrderReport::Cancel {
+            order_id: order_id.to_string(),
+            instrument: instrument.clone(),
+            side,
+        });
+
+        let before = state.resting.len();
+        state
+            .resting
+            .retain(|resting| resting.order.order_id != order_id);
+
+        let report = if state.resting.len() < before {
+            OrderReport::Cancelled {
+                order_id: order_id.to_string(),
+                instrument: instrument.clone(),
+                side,
+            }
+        } else {
+            OrderReport::CancelFailed {
+                order_id: order_id.to_string(),
+                instrument: instrument.clone(),
+                side,
+                reason: "unknown order".to_string(),
+            }
+        };
+        let _ = self.reports.send(report);
+    }
+
+    /// Moves a resting order to `price` and `quantity`, fills included, sending it to the
+    /// back of the queue as a price change does on the venue.
+    fn amend(
+        &self,
+        order_id: &str,
+        instrument: &Instrument,
+        side: Side,
+        price: Price,
+        quantity: Quantity,
+    ) {
+        let mut state = self.inner.lock().unwrap();
+
+        let position = state
+            .resting
+            .iter()
+            .position(|resting| resting.order.order_id == order_id);
+        let refusal = match position {
+            None => Some("unknown order"),
+            Some(index) if quantity <= state.resting[index].filled => {
+                Some("quantity not above what already filled")
+            }
+            Some(_) if state.would_cross(instrument, side, price) => {
+                Some("post only order would cross")
+            }
+            Some(index) => {
+                let mut resting = state.resting.remove(index);
+                resting.order.price = price;
+                resting.order.quantity = quantity;
+                state.resting.push(resting);
+                None
+            }
+        };
+
+        let report = match refusal {
+            None => OrderReport::Amended {
+                order_id: order_id.to_string(),
+                instrument: instrument.clone(),
+                side,
+                price,
+                quantity,
+            },
+            Some(reason) => OrderReport::AmendRejected {
+                order_id: order_id.to_string(),
+                instrument: instrument.clone(),
+                side,
+                reason: reason.to_string(),
+            },
+        };
+        let _ = self.reports.send(report);
+    }
+
+    fn cancel_all(&self) {
+        let mut state = self.inner.lock().unwrap();
+        let count = state.resting.len() as i64;
+        state.resting.clear();
+
+        let _ = self.reports.send(OrderReport::CancelledAll { count });
+    }
+}
+
+impl SimState {
+    /// Whether a post-only order at `price` would take liquidity on the current book.
+    fn would_cross(&self, instrument: &Instrument, side: Side, price: Price) -> bool {
+        self.books
+            .get(instrument)
+            .is_some_and(|(best_bid, best_ask)| match side {
+                Side::Buy => price >= *best_ask,
+                Side::Sell => price <= *best_bid,
+            })
+    }
+
+    fn apply_fill(&mut self, order: &Order, quantity: Quantity) {
+        let initial = self.initial;
+        let sender = self
+            .inventories
+            .entry(order.instrument.clone())
+            .or_insert_with(|| watch::channel(initial).0);
+
+        sender.send_modify(|inventory| {
+            inventory.base += order.side.signed(quantity.as_f64());
+            inventory.quote -= order.side.signed(quantity.as_f64()) * order.price.as_f64();
+        });
+    }
+}
+
+#[async_trait]
+impl ExecutionVenue for SimVenue {
+    async fn execute(&self, actions: &[OrderAction]) -> Result<()> {
+        for action in actions {
+            match action {
+                OrderAction::CancelAll => self.cancel_all(),
+                OrderAction::Cancel {
+                    order_id,
+                    instrument,
+                    side,
+                } => self.cancel(order_id, instrument, *side),
+                OrderAction::Place(order) => self.place(order),
+                OrderAction::Amend {
+                    order_id,
+                    instrument,
+                    side,
+                    new_price,
+                    new_quantity,
+                } => self.amend(order_id, instrument, *side, *new_price, *new_quantity),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn open_orders(&self, instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        let state = self.inner.lock().unwrap();
+
+        Ok(state
+            .resting
+            .iter()
+            .filter(|resting| resting.order.instrument == *instrument)
+            .map(|resting| OpenOrder::resting(&resting.order, resting.filled))
+            .collect())
+    }
+
+    async fn spawn_reports(
+        &self,
+        _on_report: ReportSender,
+        _supervisor: &Supervisor,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        _supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        let mut state = self.inner.lock().unwrap();
+        let initial = state.initial;
+        let sender = state
+            .inventories
+            .entry(instrument.clone())
+            .or_insert_with(|| watch::channel(initial).0)
+            .clone();
+
+        Ok(Box::new(SimInventory { sender }))
+    }
+
+    fn on_market_event(&self, event: &MarketEvent) {
+        SimVenue::on_market_event(self, event);
+    }
+}
@@ -0,0 +1,321 @@
+use std::ffi::OsString;
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+
+use crate::alerts::webhook::WebhookKind;
+use crate::cli::output::OutputFormat;
+use crate::config::app_config::AppConfig;
+use crate::execution::order_action::Side;
+use crate::market::market_source::MarketSourceKind;
+use crate::scenario::strategies::StrategyKind;
+use crate::scenario::venues::VenueKind;
+use crate::telemetry::logging::LogFormat;
+use crate::types::instrument::InstrumentConfig;
+
+/// Maker-only market making engine, plus one-shot operations against the venue.
+///
+/// Without a subcommand the engine runs, as with `run`.
+#[derive(Debug, Clone, Parser)]
+#[command(name = "accumulator")]
+pub struct Cli {
+    /// Application config file; `accumulator.yml` is used when present.
+    #[arg(long, env = "ACCUMULATOR_CONFIG", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// How one-shot commands print their results.
+    #[arg(long, value_enum, default_value_t, global = true)]
+    pub output: OutputFormat,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+impl Cli {
+    /// Parses `args` like [`Parser::try_parse_from`], but also rejects engine flags given
+    /// together with a subcommand, where they would otherwise be ignored.
+    pub fn try_parse_args<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let mut command = Self::command();
+        let matches = command.try_get_matches_from_mut(args)?;
+
+        if let Some((name, _)) = matches.subcommand() {
+            let run_flags = RunArgs::augment_args(clap::Command::new("run"));
+            for arg in run_flags.get_arguments() {
+                if matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine) {
+                    let flag = arg.get_long().unwrap_or(arg.get_id().as_str());
+                    let message = match name {
+                        "run" | "cleanup" => format!("--{flag} must come after `{name}`"),
+                        _ => format!("--{flag} only applies to `run`, not `{name}`"),
+                    };
+                    return Err(command.error(ErrorKind::ArgumentConflict, message));
+                }
+            }
+        }
+
+        Self::from_arg_matches(&matches).map_err(|error| error.format(&mut command))
+    }
+
+    /// The subcommand to run; `run` with the top-level flags when none was given.
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or(Command::Run(self.run))
+    }
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Run the engine (the default).
+    Run(RunArgs),
+
+    /// Cancel every open order on the configured instruments, print what became of each
+    /// and the balances, and exit without quoting. Takes the same flags as `run`.
+    Cleanup(RunArgs),
+
+    #[command(flatten)]
+    Venue(VenueCommand),
+
+    /// Inspect the application config.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+/// One-shot operations against the configured venue's account.
+#[derive(Debug, Clone, Subcommand)]
+pub enum VenueCommand {
+    /// Cancel every open order.
+    CancelAll,
+
+    /// Show account balances.
+    Balances,
+
+    /// List open orders.
+    OpenOrders,
+
+    /// Place or cancel a single order.
+    #[command(subcommand)]
+    Order(OrderCommand),
+
+    /// Export the account's fills.
+    #[command(subcommand)]
+    Fills(FillsCommand),
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum OrderCommand {
+    /// Place one post-only limit order, rounded to the instrument's trading rules.
+    Place {
+        /// Instrument symbol, e.g. `SOL/GBP`.
+        #[arg(long)]
+        instrument: InstrumentConfig,
+
+        #[arg(long, value_enum)]
+        side: Side,
+
+        #[arg(long)]
+        price: f64,
+
+        /// Quantity in the base currency.
+        #[arg(long)]
+        quantity: f64,
+
+        /// Client order id; a random UUID when unset.
+        #[arg(long)]
+        order_id: Option<String>,
+    },
+
+    /// Cancel one order by client order id.
+    Cancel {
+        #[arg(long)]
+        order_id: String,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum FillsCommand {
+    /// Write every fill in the venue's trade history to CSV, with fees and quote values
+    /// at fixed precision per currency.
+    Export {
+        /// First UTC day to include, e.g. `2024-04-06`.
+        #[arg(long)]
+        from: Option<NaiveDate>,
+
+        /// Last UTC day to include.
+        #[arg(long)]
+        to: Option<NaiveDate>,
+
+        /// CSV file to write, or with `--by-month` the directory for one
+        /// `fills-YYYY-MM.csv` per month.
+        #[arg(long)]
+        out: PathBuf,
+
+        #[arg(long)]
+        by_month: bool,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigCommand {
+    /// Load and validate the application config, then print a summary.
+    Check,
+}
+
+/// Flags of `run`. Each overrides the matching field of the application config file.
+#[derive(Debug, Clone, Default, Args)]
+pub struct RunArgs {
+    #[arg(long, value_enum)]
+    pub venue: Option<VenueKind>,
+
+    /// Where market data comes from, whichever venue orders go to.
+    #[arg(long, value_enum)]
+    pub market_source: Option<MarketSourceKind>,
+
+    #[arg(long, value_enum)]
+    pub strategy: Option<StrategyKind>,
+
+    /// Also run this strategy on the same inputs, without trading, for comparison.
+    /// Repeat for several.
+    #[arg(long, value_enum)]
+    pub shadow_strategy: Vec<StrategyKind>,
+
+    /// Comma-separated instruments to quote, e.g. `SOL/GBP,ETH/GBP`.
+    #[arg(long, value_delimiter = ',')]
+    pub instruments: Option<Vec<InstrumentConfig>>,
+
+    /// Serve Prometheus metrics on this port.
+    #[arg(long)]
+    pub metrics_port: Option<u16>,
+
+    /// Address the Prometheus endpoint listens on; localhost by default.
+    #[arg(long)]
+    pub metrics_bind: Option<IpAddr>,
+
+    #[arg(long, value_enum, env = "ACCUMULATOR_LOG_FORMAT")]
+    pub log_format: Option<LogFormat>,
+
+    /// Seed for every random choice; a random seed is drawn and logged when unset.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Seconds between periodic session statistics lines.
+    #[arg(long)]
+    pub stats_interval_secs: Option<u64>,
+
+    /// Persist engine state to this JSON file and restore it at startup.
+    #[arg(long)]
+    pub state_path: Option<PathBuf>,
+
+    /// Starting base balance of a dry-run.
+    #[arg(long, env = "ACCUMULATOR_PAPER_BASE")]
+    pub paper_base: Option<f64>,
+
+    /// Starting quote balance of a dry-run.
+    #[arg(long, env = "ACCUMULATOR_PAPER_QUOTE")]
+    pub paper_quote: Option<f64>,
+
+    /// Append order intents to this file and recover them after a crash.
+    #[arg(long)]
+    pub intent_log: Option<PathBuf>,
+
+    /// Lead every client order id with this, in place of the session id: 1 to 4
+    /// lowercase letters or digits.
+    #[arg(long, env = "ACCUMULATOR_ORDER_ID_PREFIX")]
+    pub order_id_prefix: Option<String>,
+
+    /// Serve the admin API on this localhost port.
+    #[arg(long)]
+    pub admin_port: Option<u16>,
+
+    /// Bearer token required by every admin API request.
+    #[arg(long, env = "ACCUMULATOR_ADMIN_TOKEN", hide_env_values = true)]
+    pub admin_token: Option<String>,
+
+    /// Slack-compatible webhook or Telegram `sendMessage` URL.
+    #[arg(long, env = "ACCUMULATOR_ALERT_WEBHOOK_URL", hide_env_values = true)]
+    pub alert_webhook_url: Option<String>,
+
+    #[arg(long, value_enum)]
+    pub alert_webhook_kind: Option<WebhookKind>,
+
+    #[arg(long, env = "ACCUMULATOR_ALERT_TELEGRAM_CHAT_ID")]
+    pub alert_telegram_chat_id: Option<String>,
+
+    /// Consecutive rejected orders or venue errors before alerting.
+    #[arg(long)]
+    pub alert_reject_threshold: Option<u32>,
+
+    /// Seconds without market data before alerting that the feed is down.
+    #[arg(long)]
+    pub alert_feed_down_secs: Option<u64>,
+
+    /// Minimum seconds between two alerts of the same kind.
+    #[arg(long)]
+    pub alert_cooldown_secs: Option<u64>,
+}
+
+impl RunArgs {
+    pub fn apply(self, config: &mut AppConfig) {
+        fn set<T>(field: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+
+        set(&mut config.venue.kind, self.venue);
+        set(&mut config.market.source, self.market_source);
+        set(&mut config.strategy.kind, self.strategy);
+        set(&mut config.instruments, self.instruments);
+        set(&mut config.logging.format, self.log_format);
+        set(&mut config.metrics.bind, self.metrics_bind);
+        set(&mut config.stats.interval_secs, self.stats_interval_secs);
+        set(&mut config.venue.inventory.paper_base, self.paper_base);
+        set(&mut config.venue.inventory.paper_quote, self.paper_quote);
+        set(&mut config.alerts.webhook_kind, self.alert_webhook_kind);
+        set(
+            &mut config.alerts.reject_threshold,
+            self.alert_reject_threshold,
+        );
+        set(&mut config.alerts.feed_down_secs, self.alert_feed_down_secs);
+        set(&mut config.alerts.cooldown_secs, self.alert_cooldown_secs);
+
+        if self.metrics_port.is_some() {
+            config.metrics.port = self.metrics_port;
+        }
+        if !self.shadow_strategy.is_empty() {
+            config.shadow_strategies = self.shadow_strategy;
+        }
+        if self.seed.is_some() {
+            config.seed = self.seed;
+        }
+        if self.state_path.is_some() {
+            config.state.path = self.state_path;
+        }
+        if self.intent_log.is_some() {
+            config.state.intent_log = self.intent_log;
+        }
+        if self.order_id_prefix.is_some() {
+            config.venue.order_id_prefix = self.order_id_prefix;
+        }
+        if self.admin_port.is_some() {
+            config.admin.port = self.admin_port;
+        }
+        if self.admin_token.is_some() {
+            config.admin.token = self.admin_token;
+        }
+        if self.alert_webhook_url.is_some() {
+            config.alerts.webhook_url = self.alert_webhook_url;
+        }
+        if self.alert_telegram_chat_id.is_some() {
+            config.alerts.telegram_chat_id = self.alert_telegram_chat_id;
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::cli::output::{CancelOutcome, CleanedOrder, CleanupSummary, InstrumentInventory};
+use crate::engine::supervisor::Supervisor;
+use crate::execution::ReportSender;
+use crate::execution::order_action::OrderAction;
+use crate::execution::order_report::OrderReport;
+use crate::execution::report_wait::await_reports;
+use crate::inventory::readiness::await_first_snapshot;
+use crate::scenario::scenario::DynamicVenue;
+use crate::scenario::venues::VenueConfig;
+use crate::types::instrument::Instrument;
+
+/// How long `cleanup` waits for the venue to answer its cancels.
+pub const CLEANUP_CANCEL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Cancels every open order on `instruments` one by one, waits up to `timeout` for the
+/// venue to answer each, then reads the balances, without ever quoting. Orders the venue
+/// did not confirm gone, and balances it did not send, are in the summary as failures.
+pub async fn cleanup(
+    venue: &DynamicVenue,
+    reports: &ReportSender,
+    instruments: &[Instrument],
+    config: &VenueConfig,
+    timeout: Duration,
+) -> Result<CleanupSummary> {
+    let (supervisor, _escalations) = Supervisor::new();
+    let mut receiver = reports.subscribe();
+    venue.spawn_reports(reports.clone(), &supervisor).await?;
+
+    let mut open = Vec::new();
+    for instrument in instruments {
+        open.extend(venue.open_orders(instrument).await?);
+    }
+
+    let mut outcomes: HashMap<String, CancelOutcome> = HashMap::new();
+    let mut errors = Vec::new();
+    if !open.is_empty() {
+        let cancels: Vec<_> = open
+            .iter()
+            .map(|order| OrderAction::Cancel {
+                order_id: order.order_id.clone(),
+                instrument: order.instrument.clone(),
+                side: order.side,
+            })
+            .collect();
+
+        match venue.execute(&cancels).await {
+            Ok(()) => {
+                // Every cancel ends in one outcome, or a venue error that names no order.
+                let mut venue_errors = 0;
+                await_reports(&mut receiver, timeout, |report| {
+                    let outcome = match report {
+                        OrderReport::Cancelled { .. } => Some(CancelOutcome::Cancelled),
+                        OrderReport::Filled { .. } => Some(CancelOutcome::Filled),
+                        OrderReport::CancelFailed { reason, .. } => Some(CancelOutcome::Failed {
+                            reason: reason.clone(),
+                        }),
+                        OrderReport::VenueError { message } => {
+                            venue_errors += 1;
+                            errors.push(message.clone());
+                            None
+                        }
+                        _ => None,
+                    };
+                    if let (Some(outcome), Some(order_id)) = (outcome, report.order_id())
+                        && open.iter().any(|order| order.order_id == order_id)
+                    {
+                        outcomes.insert(order_id.to_string(), outcome);
+                    }
+                    outcomes.len() + venue_errors >= open.len()
+                })
+                .await;
+            }
+            Err(error) => errors.push(format!("cancelling failed: {error:#}")),
+        }
+    }
+
+    let orders = open
+        .into_iter()
+        .map(|order| CleanedOrder {
+            outcome: outcomes
+                .remove(&order.order_id)
+                .unwrap_or(CancelOutcome::Unconfirmed),
+            order_id: order.order_id,
+            instrument: order.instrument.to_string(),
+            side: order.side,
+            price: order.price.as_f64(),
+            remaining: order.remaining.as_f64(),
+        })
+        .collect();
+
+    let mut inventories = Vec::new();
+    for instrument in instruments {
+        let inventory = match venue.spawn_inventory(instrument, &supervisor).await {
+            Ok(source) => await_first_snapshot(
+                source.as_ref(),
+                instrument,
+                config.kind,
+                config.inventory.ready_timeout(),
+                config.inventory.paper(),
+            )
+            .await
+            .map(|paper| paper.unwrap_or_else(|| *source.subscribe().borrow())),
+            Err(error) => Err(error),
+        };
+        match inventory {
+            Ok(inventory) => inventories.push(InstrumentInventory {
+                instrument: instrument.to_string(),
+                base: inventory.base,
+                quote: inventory.quote,
+            }),
+            Err(error) => errors.push(format!("{instrument} balances: {error:#}")),
+        }
+    }
+    supervisor.shutdown();
+
+    Ok(CleanupSummary {
+        orders,
+        inventories,
+        errors,
+    })
+}
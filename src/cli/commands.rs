@@ -0,0 +1,275 @@
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use chrono::DateTime;
+use uuid::Uuid;
+
+use crate::cli::args::{FillsCommand, OrderCommand, VenueCommand};
+use crate::cli::output::{Balance, ExportedFile, OpenOrderInfo, Output, PlacedOrder};
+use crate::config::app_config::AppConfig;
+use crate::execution::order_action::{Order, OrderType, Side};
+use crate::kraken::kraken_client::KrakenClient;
+use crate::kraken::symbols::split_kraken_pair;
+use crate::stats::fill_ledger::{DateRange, FillLedger, LedgerFill};
+use crate::types::instrument::InstrumentConfig;
+use crate::types::quantity::Quantity;
+
+/// The venue calls the one-shot commands need, so they can run against a stub.
+#[async_trait]
+pub trait VenueOps: Send + Sync {
+    /// Cancels every open order and returns how many were cancelled.
+    async fn cancel_all(&self) -> Result<i64>;
+
+    async fn balances(&self) -> Result<Vec<Balance>>;
+
+    async fn open_orders(&self) -> Result<Vec<OpenOrderInfo>>;
+
+    async fn place(&self, order: &Order) -> Result<PlacedOrder>;
+
+    /// Cancels by client order id and returns how many orders were cancelled.
+    async fn cancel(&self, order_id: &str) -> Result<i64>;
+
+    /// The account's fills, at least those within `range`.
+    async fn fills(&self, range: DateRange) -> Result<FillLedger>;
+}
+
+#[async_trait]
+impl VenueOps for KrakenClient {
+    async fn cancel_all(&self) -> Result<i64> {
+        Ok(self.cancel_all_orders().await?.count)
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        let mut balances = self
+            .balance()
+            .await?
+            .into_iter()
+            .map(|(asset, balance)| {
+                let balance = balance
+                    .parse()
+                    .with_context(|| format!("invalid {asset} balance: {balance}"))?;
+                Ok(Balance { asset, balance })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        balances.sort_by(|a, b| a.asset.cmp(&b.asset));
+
+        Ok(balances)
+    }
+
+    async fn open_orders(&self) -> Result<Vec<OpenOrderInfo>> {
+        let mut orders = self
+            .open_orders()
+            .await?
+            .open
+            .into_iter()
+            .map(|(txid, order)| {
+                let number = |field: &str, value: &str| -> Result<f64> {
+                    value
+                        .parse()
+                        .with_context(|| format!("order {txid}: invalid {field}: {value}"))
+                };
+
+                Ok(OpenOrderInfo {
+                    side: order.descr.side.parse()?,
+                    price: number("price", &order.descr.price)?,
+                    quantity: number("vol", &order.vol)?,
+                    filled: number("vol_exec", &order.vol_exec)?,
+                    order_id: order.cl_ord_id,
+                    pair: order.descr.pair,
+                    txid,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        orders.sort_by(|a, b| a.txid.cmp(&b.txid));
+
+        Ok(orders)
+    }
+
+    async fn place(&self, order: &Order) -> Result<PlacedOrder> {
+        let result = self
+            .limit_order(
+                &order.instrument,
+                order.side,
+                order.price,
+                order.quantity,
+                order.order_type,
+                &order.order_id,
+            )
+            .await?;
+
+        Ok(PlacedOrder {
+            order_id: order.order_id.clone(),
+            txid: result.txid,
+            description: result.descr.order,
+        })
+    }
+
+    async fn cancel(&self, order_id: &str) -> Result<i64> {
+        Ok(self.cancel_order(order_id).await?.count)
+    }
+
+    /// Pages through the trade history. Fees are in the quote currency: orders are never
+    /// placed with Kraken's pay-fees-in-base flag.
+    async fn fills(&self, range: DateRange) -> Result<FillLedger> {
+        let start = range.start().map(|start| start.timestamp());
+        let end = range.end().map(|end| end.timestamp());
+
+        let mut fills = Vec::new();
+        loop {
+            let page = self.trades_history(start, end, fills.len()).await?;
+            if page.trades.is_empty() {
+                break;
+            }
+
+            for (trade_id, trade) in page.trades {
+                let number = |field: &str, value: &str| -> Result<f64> {
+                    value
+                        .parse()
+                        .with_context(|| format!("trade {trade_id}: invalid {field}: {value}"))
+                };
+                let (base, quote) = split_kraken_pair(&trade.pair)
+                    .with_context(|| format!("trade {trade_id}: unknown pair {}", trade.pair))?;
+                let time = DateTime::from_timestamp_millis((trade.time * 1_000.0).round() as i64)
+                    .with_context(|| {
+                    format!("trade {trade_id}: invalid time {}", trade.time)
+                })?;
+
+                fills.push(LedgerFill {
+                    time,
+                    side: trade.side.parse()?,
+                    quantity: number("vol", &trade.vol)?,
+                    price: number("price", &trade.price)?,
+                    fee: number("fee", &trade.fee)?,
+                    fee_currency: quote.clone(),
+                    value: number("cost", &trade.cost)?,
+                    base,
+                    quote,
+                    order_id: trade.ordertxid,
+                    trade_id,
+                });
+            }
+
+            if fills.len() >= page.count {
+                break;
+            }
+        }
+
+        Ok(FillLedger::new(fills))
+    }
+}
+
+pub async fn execute(command: &VenueCommand, venue: &dyn VenueOps) -> Result<Output> {
+    Ok(match command {
+        VenueCommand::CancelAll => Output::CancelledAll {
+            cancelled: venue.cancel_all().await?,
+        },
+        VenueCommand::Balances => Output::Balances(venue.balances().await?),
+        VenueCommand::OpenOrders => Output::OpenOrders(venue.open_orders().await?),
+        VenueCommand::Order(OrderCommand::Place {
+            instrument,
+            side,
+            price,
+            quantity,
+            order_id,
+        }) => {
+            let order = manual_order(instrument, *side, *price, *quantity, order_id.clone())?;
+            Output::Placed(venue.place(&order).await?)
+        }
+        VenueCommand::Order(OrderCommand::Cancel { order_id }) => Output::Cancelled {
+            order_id: order_id.clone(),
+            cancelled: venue.cancel(order_id).await?,
+        },
+        VenueCommand::Fills(FillsCommand::Export {
+            from,
+            to,
+            out,
+            by_month,
+        }) => {
+            if let (Some(from), Some(to)) = (from, to)
+                && from > to
+            {
+                bail!("--from {from} is after --to {to}");
+            }
+
+            let range = DateRange {
+                from: *from,
+                to: *to,
+            };
+            let ledger = venue.fills(range).await?.within(range);
+            let files = if *by_month {
+                ledger.write_monthly_csv(out)?
+            } else {
+                ledger.write_csv(out)?;
+                vec![(out.clone(), ledger.fills().len())]
+            };
+
+            Output::FillsExported(
+                files
+                    .into_iter()
+                    .map(|(path, fills)| ExportedFile {
+                        path: path.display().to_string(),
+                        fills,
+                    })
+                    .collect(),
+            )
+        }
+    })
+}
+
+/// A post-only order rounded to the instrument's trading rules, as the engine would send
+/// it.
+fn manual_order(
+    instrument: &InstrumentConfig,
+    side: Side,
+    price: f64,
+    quantity: f64,
+    order_id: Option<String>,
+) -> Result<Order> {
+    let instrument = instrument.load()?;
+    let rules = instrument.trading_rules();
+
+    let price = rules.round_price_to_tick(price);
+    let quantity = rules.round_quantity_to_step(Quantity::new(quantity)?);
+    if price.as_f64() <= 0.0 {
+        bail!("price rounds to zero at tick {}", rules.price_tick);
+    }
+    if quantity.is_zero() {
+        bail!(
+            "quantity rounds to zero at step {} and minimum {}",
+            rules.quantity_step,
+            rules.min_order_quantity
+        );
+    }
+
+    Ok(Order {
+        order_id: order_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+        instrument,
+        side,
+        price,
+        quantity,
+        order_type: OrderType::POST_ONLY,
+        cycle_id: None,
+    })
+}
+
+/// Validates `config`, including that every instrument has trading rules.
+pub fn check_config(config: &AppConfig) -> Result<Output> {
+    config.validate()?;
+
+    let instruments = config
+        .instruments
+        .iter()
+        .map(|instrument| {
+            instrument
+                .load()
+                .map(|instrument| instrument.to_string())
+                .with_context(|| format!("instrument {}", instrument.symbol()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Output::ConfigValid {
+        venue: config.venue.kind.to_string(),
+        strategy: config.strategy.kind.to_string(),
+        instruments,
+        config: config.effective(),
+    })
+}
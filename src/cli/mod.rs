@@ -0,0 +1,4 @@
+pub mod args;
+pub mod cleanup;
+pub mod commands;
+pub mod output;
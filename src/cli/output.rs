@@ -0,0 +1,279 @@
+use std::fmt::{self, Write as _};
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::execution::order_action::Side;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Balance {
+    pub asset: String,
+    pub balance: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenOrderInfo {
+    /// Venue transaction id.
+    pub txid: String,
+    /// Client order id, when the order was placed with one.
+    pub order_id: Option<String>,
+    pub pair: String,
+    pub side: Side,
+    pub price: f64,
+    pub quantity: f64,
+    pub filled: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlacedOrder {
+    pub order_id: String,
+    pub txid: Vec<String>,
+    pub description: String,
+}
+
+/// What became of one open order `cleanup` cancelled.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum CancelOutcome {
+    Cancelled,
+    /// Filled before the cancel reached it; gone all the same.
+    Filled,
+    Failed {
+        reason: String,
+    },
+    /// The venue did not answer in time; the order may still be resting.
+    Unconfirmed,
+}
+
+impl fmt::Display for CancelOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CancelOutcome::Cancelled => write!(f, "cancelled"),
+            CancelOutcome::Filled => write!(f, "filled"),
+            CancelOutcome::Failed { reason } => write!(f, "failed: {reason}"),
+            CancelOutcome::Unconfirmed => write!(f, "unconfirmed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CleanedOrder {
+    pub order_id: String,
+    pub instrument: String,
+    pub side: Side,
+    pub price: f64,
+    pub remaining: f64,
+    #[serde(flatten)]
+    pub outcome: CancelOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InstrumentInventory {
+    pub instrument: String,
+    pub base: f64,
+    pub quote: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CleanupSummary {
+    pub orders: Vec<CleanedOrder>,
+    pub inventories: Vec<InstrumentInventory>,
+    pub errors: Vec<String>,
+}
+
+impl CleanupSummary {
+    /// Whether an order may be left resting or something else went wrong.
+    pub fn failed(&self) -> bool {
+        !self.errors.is_empty()
+            || self.orders.iter().any(|order| {
+                !matches!(
+                    order.outcome,
+                    CancelOutcome::Cancelled | CancelOutcome::Filled
+                )
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportedFile {
+    pub path: String,
+    pub fills: usize,
+}
+
+/// Result of a one-shot command. Serializes as just its payload.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Output {
+    CancelledAll {
+        cancelled: i64,
+    },
+    Balances(Vec<Balance>),
+    OpenOrders(Vec<OpenOrderInfo>),
+    Placed(PlacedOrder),
+    Cancelled {
+        order_id: String,
+        cancelled: i64,
+    },
+    FillsExported(Vec<ExportedFile>),
+    Cleanup(CleanupSummary),
+    ConfigValid {
+        venue: String,
+        strategy: String,
+        instruments: Vec<String>,
+        /// Effective configuration with secrets redacted.
+        config: Value,
+    },
+}
+
+impl Output {
+    pub fn render(&self, format: OutputFormat) -> Result<String> {
+        Ok(match format {
+            OutputFormat::Json => serde_json::to_string_pretty(self)?,
+            OutputFormat::Table => self.table(),
+        })
+    }
+
+    fn table(&self) -> String {
+        match self {
+            Output::CancelledAll { cancelled } => format!("cancelled {cancelled} orders"),
+            Output::Balances(balances) => table(
+                &["ASSET", "BALANCE"],
+                balances
+                    .iter()
+                    .map(|balance| vec![balance.asset.clone(), balance.balance.to_string()])
+                    .collect(),
+            ),
+            Output::OpenOrders(orders) => table(
+                &[
+                    "TXID", "ORDER ID", "PAIR", "SIDE", "PRICE", "QUANTITY", "FILLED",
+                ],
+                orders
+                    .iter()
+                    .map(|order| {
+                        vec![
+                            order.txid.clone(),
+                            order.order_id.clone().unwrap_or_else(|| "-".to_string()),
+                            order.pair.clone(),
+                            order.side.to_string(),
+                            order.price.to_string(),
+                            order.quantity.to_string(),
+                            order.filled.to_string(),
+                        ]
+                    })
+                    .collect(),
+            ),
+            Output::Placed(order) => format!(
+                "placed {} ({}): {}",
+                order.order_id,
+                order.txid.join(","),
+                order.description
+            ),
+            Output::Cancelled {
+                order_id,
+                cancelled,
+            } => format!("cancelled {cancelled} orders for {order_id}"),
+            Output::FillsExported(files) => table(
+                &["FILE", "FILLS"],
+                files
+                    .iter()
+                    .map(|file| vec![file.path.clone(), file.fills.to_string()])
+                    .collect(),
+            ),
+            Output::Cleanup(summary) => {
+                let mut sections = Vec::new();
+                if summary.orders.is_empty() {
+                    sections.push("no open orders".to_string());
+                } else {
+                    sections.push(table(
+                        &[
+                            "ORDER ID",
+                            "INSTRUMENT",
+                            "SIDE",
+                            "PRICE",
+                            "REMAINING",
+                            "OUTCOME",
+                        ],
+                        summary
+                            .orders
+                            .iter()
+                            .map(|order| {
+                                vec![
+                                    order.order_id.clone(),
+                                    order.instrument.clone(),
+                                    order.side.to_string(),
+                                    order.price.to_string(),
+                                    order.remaining.to_string(),
+                                    order.outcome.to_string(),
+                                ]
+                            })
+                            .collect(),
+                    ));
+                }
+                if !summary.inventories.is_empty() {
+                    sections.push(table(
+                        &["INSTRUMENT", "BASE", "QUOTE"],
+                        summary
+                            .inventories
+                            .iter()
+                            .map(|inventory| {
+                                vec![
+                                    inventory.instrument.clone(),
+                                    inventory.base.to_string(),
+                                    inventory.quote.to_string(),
+                                ]
+                            })
+                            .collect(),
+                    ));
+                }
+                sections.extend(summary.errors.iter().map(|error| format!("error: {error}")));
+                sections.join("\n\n")
+            }
+            Output::ConfigValid {
+                venue,
+                strategy,
+                instruments,
+                ..
+            } => table(
+                &["CONFIG", "VALUE"],
+                vec![
+                    vec!["venue".to_string(), venue.clone()],
+                    vec!["strategy".to_string(), strategy.clone()],
+                    vec!["instruments".to_string(), instruments.join(",")],
+                ],
+            ),
+        }
+    }
+}
+
+/// Left-aligned columns, each as wide as its longest cell.
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut out = String::new();
+    let header = headers.iter().map(|header| header.to_string()).collect();
+    for row in std::iter::once(header).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        let _ = writeln!(out, "{}", line.trim_end());
+    }
+
+    out.trim_end().to_string()
+}
@@ -0,0 +1,99 @@
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+/// Source of the current time. Components take time from a clock (or from a `now` their
+/// caller read from one) instead of sampling the wall clock, so a replay can drive them
+/// on historical timestamps.
+pub trait Clock: Debug + Send + Sync {
+    /// Monotonic time, for intervals and ages.
+    fn now_instant(&self) -> Instant;
+
+    /// Calendar time, for trading hours and per-day accounting.
+    fn now_utc(&self) -> DateTime<Utc>;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    pub fn shared() -> SharedClock {
+        Arc::new(Self)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Manually advanced clock. Clones share the same time, so a replay can hold one handle
+/// and move it to each event's timestamp while the components read another.
+#[derive(Debug, Clone)]
+pub struct SimClock {
+    inner: Arc<Mutex<SimTime>>,
+}
+
+#[derive(Debug)]
+struct SimTime {
+    /// An arbitrary real instant standing in for `start`; instants are only compared.
+    origin: Instant,
+    start: DateTime<Utc>,
+    elapsed: Duration,
+}
+
+impl SimClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SimTime {
+                origin: Instant::now(),
+                start,
+                elapsed: Duration::ZERO,
+            })),
+        }
+    }
+
+    /// Clock starting at a market event timestamp.
+    pub fn from_timestamp_ms(timestamp_ms: u64) -> Self {
+        Self::new(DateTime::from_timestamp_millis(timestamp_ms as i64).unwrap_or_default())
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.inner.lock().unwrap().elapsed += by;
+    }
+
+    /// Moves to `timestamp_ms`. Time never goes backwards, so an earlier timestamp
+    /// leaves the clock where it is.
+    pub fn set_timestamp_ms(&self, timestamp_ms: u64) {
+        let mut time = self.inner.lock().unwrap();
+        let target = DateTime::from_timestamp_millis(timestamp_ms as i64).unwrap_or_default();
+        let elapsed = (target - time.start).to_std().unwrap_or_default();
+        time.elapsed = time.elapsed.max(elapsed);
+    }
+
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+}
+
+impl Clock for SimClock {
+    fn now_instant(&self) -> Instant {
+        let time = self.inner.lock().unwrap();
+        time.origin + time.elapsed
+    }
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        let time = self.inner.lock().unwrap();
+        time.start + time.elapsed
+    }
+}
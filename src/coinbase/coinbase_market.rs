@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::Sender;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
+use tracing::{error, info, warn};
+
+use crate::events::MarketEvent;
+use crate::market::market_source::{MarketDataSource, SubscriptionRejected};
+use crate::types::instrument::Instrument;
+use crate::types::price::Price;
+
+pub const COINBASE_WS_URL: &str = "wss://advanced-trade-ws.coinbase.com";
+
+/// Market data from Coinbase Advanced Trade's public websocket: the `ticker` channel for
+/// the top of the book and `market_trades` for trades, with `heartbeats` keeping a quiet
+/// product's connection open.
+#[derive(Debug)]
+pub struct CoinbaseMarket {
+    websocket_url: String,
+    /// Quiet this long, the feed is pinged; quiet `pong_timeout` more, it is dropped.
+    idle_timeout: Duration,
+    pong_timeout: Duration,
+}
+
+impl Default for CoinbaseMarket {
+    fn default() -> Self {
+        Self::new(COINBASE_WS_URL)
+    }
+}
+
+impl CoinbaseMarket {
+    pub fn new(websocket_url: impl Into<String>) -> Self {
+        Self {
+            websocket_url: websocket_url.into(),
+            idle_timeout: Duration::from_secs(10),
+            pong_timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Pings the feed after `idle` without a message, and gives up on the connection
+    /// when nothing comes back within `pong`.
+    pub fn with_idle_timeout(mut self, idle: Duration, pong: Duration) -> Self {
+        self.idle_timeout = idle;
+        self.pong_timeout = pong;
+        self
+    }
+
+    /// Coinbase's name for the pair, e.g. `SOL-GBP`.
+    pub fn product_id(instrument: &Instrument) -> String {
+        format!("{}-{}", instrument.base(), instrument.quote())
+    }
+
+    /// One subscribe message per channel, as Coinbase takes them.
+    pub fn subscriptions(&self, instrument: &Instrument) -> Vec<Value> {
+        ["ticker", "market_trades", "heartbeats"]
+            .into_iter()
+            .map(|channel| {
+                json!({
+                    "type": "subscribe",
+                    "product_ids": [Self::product_id(instrument)],
+                    "channel": channel,
+                })
+            })
+            .collect()
+    }
+
+    /// Subscribes over a connected `stream` and forwards its events until it closes,
+    /// fails, goes quiet or skips a message.
+    async fn stream(
+        &self,
+        instrument: &Instrument,
+        stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        channel: &Sender<MarketEvent>,
+    ) -> Result<()> {
+        let (mut writer, mut reader) = stream.split();
+        let mut messages = CoinbaseStream::new();
+
+        for subscription in self.subscriptions(instrument) {
+            writer.send(Message::Text(subscription.to_string())).await?;
+        }
+
+        info!("Coinbase websocket connected");
+        let _ = channel
+            .send(MarketEvent::SourceReconnected {
+                instrument: instrument.clone(),
+                timestamp_ms: now_ms(),
+            })
+            .await;
+
+        let mut pinged = false;
+        loop {
+            let wait = if pinged {
+                self.pong_timeout
+            } else {
+                self.idle_timeout
+            };
+            let message = match tokio::time::timeout(wait, reader.next()).await {
+                Ok(Some(message)) => message?,
+                Ok(None) => break,
+                Err(_) if pinged => {
+                    bail!("Coinbase websocket sent nothing back within {wait:?} of a ping")
+                }
+                Err(_) => {
+                    warn!(idle = ?wait, "Coinbase websocket quiet; pinging");
+                    writer.send(Message::Ping(Vec::new())).await?;
+                    pinged = true;
+                    continue;
+                }
+            };
+            pinged = false;
+
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Binary(binary) => match String::from_utf8(binary) {
+                    Ok(text) => text,
+                    Err(_) => continue,
+                },
+                Message::Close(frame) => {
+                    error!("Coinbase websocket closed: {:?}", frame);
+                    break;
+                }
+                _ => continue,
+            };
+
+            for market_event in messages.parse(instrument, &text)? {
+                if channel.send(market_event).await.is_err() {
+                    error!("Failed to send market event");
+                    return Ok(());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for CoinbaseMarket {
+    /// Streams until the connection ends, then sends
+    /// [`MarketEvent::SourceDisconnected`] so the engine stops quoting on the book it had.
+    async fn subscribe(&self, instrument: &Instrument, channel: Sender<MarketEvent>) -> Result<()> {
+        let (stream, _http_response) = connect_async(&self.websocket_url).await?;
+        let result = self.stream(instrument, stream, &channel).await;
+
+        let _ = channel
+            .send(MarketEvent::SourceDisconnected {
+                instrument: instrument.clone(),
+                timestamp_ms: now_ms(),
+            })
+            .await;
+        result
+    }
+}
+
+/// One connection's messages, in the order they arrived. Every message carries the
+/// connection's next `sequence_num`, so a gap means messages were lost.
+#[derive(Debug, Default)]
+pub struct CoinbaseStream {
+    last_sequence: Option<u64>,
+}
+
+impl CoinbaseStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The market events in `text` for `instrument`, oldest first. Fails on a rejected
+    /// subscription, which no reconnect will fix, and on a gap in the sequence, which a
+    /// reconnect will.
+    pub fn parse(&mut self, instrument: &Instrument, text: &str) -> Result<Vec<MarketEvent>> {
+        let Ok(message) = serde_json::from_str::<Value>(text) else {
+            return Ok(Vec::new());
+        };
+        let product_id = CoinbaseMarket::product_id(instrument);
+
+        if message.get("type").and_then(Value::as_str) == Some("error") {
+            return Err(SubscriptionRejected {
+                pair: product_id,
+                channel: "market data".to_string(),
+                reason: message
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            }
+            .into());
+        }
+
+        if let Some(sequence) = message.get("sequence_num").and_then(Value::as_u64) {
+            if let Some(last) = self.last_sequence
+                && sequence != last + 1
+            {
+                bail!(
+                    "Coinbase websocket skipped messages: expected sequence {}, received {sequence}",
+                    last + 1
+                );
+            }
+            self.last_sequence = Some(sequence);
+        }
+
+        let events = message
+            .get("events")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        Ok(match message.get("channel").and_then(Value::as_str) {
+            Some("ticker") => {
+                let timestamp_ms = message
+                    .get("timestamp")
+                    .and_then(Value::as_str)
+                    .map_or(0, timestamp_ms);
+                events
+                    .iter()
+                    .filter_map(|event| event.get("tickers")?.as_array())
+                    .flatten()
+                    .filter(|ticker| ticker["product_id"] == product_id.as_str())
+                    .filter_map(|ticker| Self::parse_ticker(instrument, ticker, timestamp_ms))
+                    .collect()
+            }
+            // The snapshot replays trades from before the connection; only updates are new.
+            Some("market_trades") => events
+                .iter()
+                .filter(|event| event["type"] == "update")
+                .filter_map(|event| event.get("trades")?.as_array())
+                .flat_map(|trades| trades.iter().rev())
+                .filter(|trade| trade["product_id"] == product_id.as_str())
+                .filter_map(|trade| Self::parse_trade(instrument, trade))
+                .collect(),
+            Some("subscriptions") => {
+                let subscriptions = events.first().map(ToString::to_string);
+                info!(subscriptions, "Coinbase websocket subscribed");
+                Vec::new()
+            }
+            _ => Vec::new(),
+        })
+    }
+
+    fn parse_ticker(
+        instrument: &Instrument,
+        ticker: &Value,
+        timestamp_ms: u64,
+    ) -> Option<MarketEvent> {
+        let best_bid: f64 = ticker.get("best_bid")?.as_str()?.parse().ok()?;
+        let best_ask: f64 = ticker.get("best_ask")?.as_str()?.parse().ok()?;
+
+        Some(MarketEvent::TopOfBook {
+            instrument: instrument.clone(),
+            best_bid: Price::new(best_bid),
+            best_ask: Price::new(best_ask),
+            bid_size: None,
+            ask_size: None,
+            timestamp_ms,
+        })
+    }
+
+    fn parse_trade(instrument: &Instrument, trade: &Value) -> Option<MarketEvent> {
+        let price: f64 = trade.get("price")?.as_str()?.parse().ok()?;
+        let quantity: f64 = trade.get("size")?.as_str()?.parse().ok()?;
+        let timestamp_ms = trade
+            .get("time")
+            .and_then(Value::as_str)
+            .map_or(0, timestamp_ms);
+
+        Some(MarketEvent::Trade {
+            instrument: instrument.clone(),
+            price: Price::new(price),
+            quantity,
+            timestamp_ms,
+        })
+    }
+}
+
+/// Milliseconds since the epoch of an RFC 3339 time, e.g. `2024-01-03T12:00:00.25Z`;
+/// 0 when it does not parse.
+fn timestamp_ms(time: &str) -> u64 {
+    DateTime::parse_from_rfc3339(time)
+        .map(|time| time.timestamp_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn now_ms() -> u64 {
+    Utc::now().timestamp_millis() as u64
+}
@@ -0,0 +1 @@
+pub mod coinbase_market;
@@ -0,0 +1,224 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::admin::server::AdminConfig;
+use crate::alerts::alerter::AlertsConfig;
+use crate::engine::channels::ChannelsConfig;
+use crate::engine::watchdog::WatchdogConfig;
+use crate::execution::fill_recorder::FillLogConfig;
+use crate::execution::order_history::OrderHistoryConfig;
+use crate::execution::order_ids::is_valid_prefix;
+use crate::market::market_source::MarketConfig;
+use crate::risk::config::RiskConfig;
+use crate::scenario::strategies::StrategyKind;
+use crate::scenario::venues::{VenueConfig, VenueKind};
+use crate::scheduling::config::SchedulingConfig;
+use crate::signals::config::SignalsConfig;
+use crate::state::store::StateConfig;
+use crate::stats::session_stats::StatsConfig;
+use crate::strategy::config::StrategyConfig;
+use crate::telemetry::logging::LoggingConfig;
+use crate::telemetry::metrics::MetricsConfig;
+use crate::types::instrument::InstrumentConfig;
+
+/// Application configuration, loaded from `accumulator.yml`.
+///
+/// Every section defaults to the values the engine used before the file existed, so an
+/// empty (or missing) file reproduces the built-in behaviour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    pub venue: VenueConfig,
+    /// Instruments quoted by this process; each gets its own engine state.
+    pub instruments: Vec<InstrumentConfig>,
+    pub market: MarketConfig,
+    pub strategy: StrategyConfig,
+    /// Candidate strategies run on the same market, signals and inventory as `strategy`
+    /// for comparison. Their quotes only fill hypothetically and never reach the venue.
+    pub shadow_strategies: Vec<StrategyKind>,
+    pub signals: SignalsConfig,
+    pub risk: RiskConfig,
+    pub scheduling: SchedulingConfig,
+    pub logging: LoggingConfig,
+    pub metrics: MetricsConfig,
+    pub stats: StatsConfig,
+    pub state: StateConfig,
+    pub watchdog: WatchdogConfig,
+    pub channels: ChannelsConfig,
+    pub order_history: OrderHistoryConfig,
+    pub fill_log: FillLogConfig,
+    pub admin: AdminConfig,
+    pub alerts: AlertsConfig,
+    /// Seed for every random choice, such as dry-run rejections. A random seed
+    /// is drawn and logged when unset.
+    pub seed: Option<u64>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            venue: VenueConfig::default(),
+            instruments: vec![InstrumentConfig::default()],
+            market: MarketConfig::default(),
+            strategy: StrategyConfig::default(),
+            shadow_strategies: Vec::new(),
+            signals: SignalsConfig::default(),
+            risk: RiskConfig::default(),
+            scheduling: SchedulingConfig::default(),
+            logging: LoggingConfig::default(),
+            metrics: MetricsConfig::default(),
+            stats: StatsConfig::default(),
+            state: StateConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            channels: ChannelsConfig::default(),
+            order_history: OrderHistoryConfig::default(),
+            fill_log: FillLogConfig::default(),
+            admin: AdminConfig::default(),
+            alerts: AlertsConfig::default(),
+            seed: None,
+        }
+    }
+}
+
+impl AppConfig {
+    pub const DEFAULT_PATH: &'static str = "accumulator.yml";
+
+    /// Loads `path`, or `accumulator.yml` when no path is given. Only an explicitly
+    /// requested file is required to exist.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(path) => (path, true),
+            None => (Path::new(Self::DEFAULT_PATH), false),
+        };
+
+        if !required && !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read config {}", path.display()))?;
+
+        Self::from_yaml(&raw).with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    pub fn from_yaml(raw: &str) -> Result<Self> {
+        if raw.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        // serde_yaml prefixes its errors with the path of the offending field.
+        let config: Self = serde_yaml::from_str(raw)?;
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        ensure(
+            !self.instruments.is_empty(),
+            "instruments",
+            "must not be empty",
+        )?;
+
+        let mut symbols = HashSet::new();
+        for (index, instrument) in self.instruments.iter().enumerate() {
+            let path = format!("instruments[{index}]");
+            instrument.validate(&path)?;
+            ensure(
+                symbols.insert(instrument.symbol()),
+                path,
+                "duplicate instrument",
+            )?;
+        }
+
+        ensure(
+            self.stats.fill_report.is_none() || self.venue.kind == VenueKind::DryRun,
+            "stats.fill_report",
+            "is only available with the dry-run venue",
+        )?;
+
+        let mut shadows = BTreeSet::new();
+        for (index, kind) in self.shadow_strategies.iter().enumerate() {
+            ensure(
+                shadows.insert(kind),
+                format!("shadow_strategies[{index}]"),
+                "duplicate strategy",
+            )?;
+        }
+
+        self.venue.kraken.validate("venue.kraken")?;
+        self.venue.capture.validate("venue.capture")?;
+        self.venue.inventory.validate("venue.inventory")?;
+        self.venue.dry_run.validate("venue.dry_run")?;
+        ensure(
+            self.venue.reconcile_secs != Some(0),
+            "venue.reconcile_secs",
+            "must be > 0",
+        )?;
+        ensure(
+            self.venue
+                .order_id_prefix
+                .as_deref()
+                .is_none_or(is_valid_prefix),
+            "venue.order_id_prefix",
+            "must be 1 to 4 lowercase letters or digits",
+        )?;
+        ensure(
+            self.venue.max_places_per_minute != Some(0),
+            "venue.max_places_per_minute",
+            "must be > 0",
+        )?;
+        self.venue.guard.validate("venue.guard")?;
+        self.market.validate("market")?;
+        self.strategy.validate("strategy")?;
+        self.signals.validate("signals")?;
+        self.risk.validate("risk")?;
+        self.scheduling.validate("scheduling")?;
+        self.stats.validate("stats")?;
+        self.state.validate("state")?;
+        self.watchdog.validate("watchdog")?;
+        self.channels.validate("channels")?;
+        self.order_history.validate("order_history")?;
+        self.fill_log.validate("fill_log")?;
+        self.alerts.validate("alerts")?;
+        ensure(
+            self.alerts.loop_stall_secs > self.watchdog.heartbeat_secs,
+            "alerts.loop_stall_secs",
+            "must be > watchdog.heartbeat_secs",
+        )?;
+
+        Ok(())
+    }
+
+    /// The resolved configuration with secrets replaced, for logging and the admin API.
+    pub fn effective(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+/// Fails with the YAML path of the offending field, e.g. `risk.market_max_age_ms: must be > 0`.
+pub(crate) fn ensure(condition: bool, path: impl fmt::Display, message: &str) -> Result<()> {
+    if !condition {
+        bail!("{path}: {message}");
+    }
+
+    Ok(())
+}
+
+/// Serializes secrets as a placeholder so they never appear in logs or the admin API.
+pub(crate) fn redacted<S: Serializer>(
+    value: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(_) => serializer.serialize_str("<redacted>"),
+        None => serializer.serialize_none(),
+    }
+}
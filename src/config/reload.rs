@@ -0,0 +1,119 @@
+use anyhow::{Context, Result, bail};
+
+use crate::config::app_config::AppConfig;
+use crate::types::instrument::{Instrument, InstrumentConfig};
+use crate::types::trading_rules::TradingRules;
+
+/// Re-reads the application config the way it was first resolved, e.g. the config file
+/// plus the CLI flags the process was started with.
+pub type ConfigLoader = Box<dyn Fn() -> Result<AppConfig> + Send + Sync>;
+
+/// The sections that differ between `current` and `new`, all of which a running engine
+/// applies in place: strategy parameters, risk thresholds, scheduling and alerting.
+///
+/// Fails, naming every offending field, when anything else changed: those fields are only
+/// read at startup, so applying them would mean rebuilding the engine.
+pub fn changed_sections(current: &AppConfig, new: &AppConfig) -> Result<Vec<&'static str>> {
+    let startup_only = [
+        ("venue", current.venue != new.venue),
+        // Inline trading rules reload with `trading_rules.yml`, in
+        // [`reloaded_trading_rules`].
+        (
+            "instruments",
+            symbols(&current.instruments) != symbols(&new.instruments),
+        ),
+        ("market", current.market != new.market),
+        ("strategy.kind", current.strategy.kind != new.strategy.kind),
+        (
+            "shadow_strategies",
+            current.shadow_strategies != new.shadow_strategies,
+        ),
+        ("signals", current.signals != new.signals),
+        (
+            "risk.kill_switch",
+            current.risk.kill_switch != new.risk.kill_switch,
+        ),
+        (
+            // Caps can move, but adding or removing one changes the risk checks.
+            "risk.max_portfolio_exposure_in_quote",
+            current.risk.max_portfolio_exposure_in_quote.is_some()
+                != new.risk.max_portfolio_exposure_in_quote.is_some(),
+        ),
+        // Handed to the strategies and risk checks once, when they are built.
+        (
+            "risk.exposure_currency",
+            current.risk.exposure_currency != new.risk.exposure_currency,
+        ),
+        ("risk.fx_rates", current.risk.fx_rates != new.risk.fx_rates),
+        ("logging", current.logging != new.logging),
+        ("metrics", current.metrics != new.metrics),
+        ("stats", current.stats != new.stats),
+        ("state", current.state != new.state),
+        ("watchdog", current.watchdog != new.watchdog),
+        ("channels", current.channels != new.channels),
+        ("order_history", current.order_history != new.order_history),
+        ("fill_log", current.fill_log != new.fill_log),
+        ("admin", current.admin != new.admin),
+        ("seed", current.seed != new.seed),
+    ];
+
+    let rejected: Vec<_> = startup_only
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect();
+    if !rejected.is_empty() {
+        bail!(
+            "{}: only read at startup; restart to apply",
+            rejected.join(", ")
+        );
+    }
+
+    Ok([
+        ("strategy", current.strategy != new.strategy),
+        ("risk", current.risk != new.risk),
+        ("scheduling", current.scheduling != new.scheduling),
+        ("alerts", current.alerts != new.alerts),
+    ]
+    .into_iter()
+    .filter_map(|(section, changed)| changed.then_some(section))
+    .collect())
+}
+
+fn symbols(instruments: &[InstrumentConfig]) -> Vec<String> {
+    instruments.iter().map(InstrumentConfig::symbol).collect()
+}
+
+/// The trading rules `new` gives each of `instruments`, inline or from
+/// `trading_rules.yml` read again, where they differ from the rules in use.
+///
+/// Fails, with nothing to apply, when any instrument's new rules cannot be read or are
+/// invalid.
+pub fn reloaded_trading_rules<'a>(
+    instruments: impl IntoIterator<Item = &'a Instrument>,
+    new: &AppConfig,
+) -> Result<Vec<(Instrument, TradingRules)>> {
+    let mut changed = Vec::new();
+    for instrument in instruments {
+        let symbol = instrument.to_string();
+        let Some(config) = new
+            .instruments
+            .iter()
+            .find(|config| config.symbol() == symbol)
+        else {
+            continue;
+        };
+
+        let rules = config
+            .load()
+            .and_then(|loaded| {
+                let rules = loaded.trading_rules();
+                rules.validate()?;
+                Ok(rules)
+            })
+            .with_context(|| format!("trading rules for {symbol} not reloaded"))?;
+        if rules != instrument.trading_rules() {
+            changed.push((instrument.clone(), rules));
+        }
+    }
+    Ok(changed)
+}
@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::config::app_config::ensure;
+use crate::events::MarketEvent;
+
+/// Sizes of the engine's internal queues and what happens when they back up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChannelsConfig {
+    /// Market events buffered between the feeds and the engine loop. A full queue makes
+    /// the feeds wait.
+    pub market_events: usize,
+    /// Queued market events at which the engine coalesces the backlog, keeping every
+    /// trade and depth update but only the latest book per instrument.
+    pub coalesce_market_depth: usize,
+    /// Order reports each consumer may fall behind by. When the engine falls further
+    /// behind, it resyncs its orders from the venue, and quotes nothing until it has.
+    pub order_reports: usize,
+}
+
+impl Default for ChannelsConfig {
+    fn default() -> Self {
+        Self {
+            market_events: 10_000,
+            coalesce_market_depth: 1_000,
+            order_reports: 10_000,
+        }
+    }
+}
+
+impl ChannelsConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.market_events > 0,
+            format!("{path}.market_events"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.coalesce_market_depth > 0 && self.coalesce_market_depth <= self.market_events,
+            format!("{path}.coalesce_market_depth"),
+            "must be > 0 and <= market_events",
+        )?;
+        ensure(
+            self.order_reports > 0,
+            format!("{path}.order_reports"),
+            "must be > 0",
+        )
+    }
+}
+
+/// Drains the events queued behind `first` and coalesces them: trades and depth updates
+/// are kept, and each instrument's books collapse into its latest one, in place of the
+/// last of them. Returns the events to process, in order, and how many books were dropped.
+pub fn coalesce(
+    first: MarketEvent,
+    queued: &mut mpsc::Receiver<MarketEvent>,
+) -> (Vec<MarketEvent>, usize) {
+    let mut backlog = vec![first];
+    while let Ok(event) = queued.try_recv() {
+        backlog.push(event);
+    }
+
+    let total = backlog.len();
+    let mut has_later_book = HashSet::new();
+    let mut kept: Vec<_> = backlog
+        .into_iter()
+        .rev()
+        .filter(|event| match event {
+            MarketEvent::TopOfBook { instrument, .. } => has_later_book.insert(instrument.clone()),
+            // Depth updates build on each other, so none can be dropped, nor can the
+            // feed's own disconnects and reconnects.
+            MarketEvent::Trade { .. }
+            | MarketEvent::BookUpdate { .. }
+            | MarketEvent::SourceDisconnected { .. }
+            | MarketEvent::SourceReconnected { .. } => true,
+        })
+        .collect();
+    kept.reverse();
+
+    let dropped = total - kept.len();
+    (kept, dropped)
+}
@@ -0,0 +1,1074 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio::sync::{broadcast, mpsc, watch};
+use tracing::Instrument as _;
+use tracing::{error, info, info_span, warn};
+
+use crate::admin::command::AdminCommand;
+use crate::admin::server as admin_server;
+use crate::admin::status::EngineStatus;
+use crate::alerts::alert::Alert;
+use crate::alerts::alerter::{AlertHandle, Alerter};
+use crate::backtest::journal::JournalRecorder;
+use crate::clock::{SharedClock, SystemClock};
+use crate::config::app_config::AppConfig;
+use crate::config::reload::{self, ConfigLoader};
+use crate::engine::channels;
+use crate::engine::instrument_engine::{InstrumentEngine, SharedContext};
+use crate::engine::supervisor::{Escalation, RestartPolicy, Supervisor};
+use crate::engine::watchdog::{DeadFeed, Watchdog};
+use crate::events::MarketEvent;
+use crate::execution::ReportSender;
+use crate::execution::fill_recorder::FillRecorder;
+use crate::execution::logged_venue::IntentLoggedVenue;
+use crate::execution::order_action::OrderAction;
+use crate::execution::order_ids::OrderIds;
+use crate::execution::order_report::OrderReport;
+use crate::execution::rate_limited_venue::RateLimitedVenue;
+use crate::execution::report_wait::await_reports;
+use crate::execution::trade_checkpoint::TradeCheckpoint;
+use crate::kraken::{capture, symbols};
+use crate::market::market_source::{MarketDataSource, SubscriptionRejected};
+use crate::random::SeededRng;
+use crate::risk::checks::kill_switch::KillSwitch;
+use crate::risk::checks::portfolio_exposure::PortfolioExposure;
+use crate::scenario::scenario::{DynamicVenue, Scenario};
+use crate::scenario::venues::VenueKind;
+use crate::signals::reference_price::ReferenceFairPrice;
+use crate::state::intent_log::IntentLog;
+use crate::state::store::{EngineState, StateStore};
+use crate::stats::fill_annotator::FillReport;
+use crate::stats::session_stats::SessionStats;
+use crate::stats::session_summary::SessionSummary;
+use crate::telemetry::cycles::CycleIds;
+use crate::telemetry::decision_log::DecisionLog;
+use crate::telemetry::liveness;
+use crate::telemetry::logging;
+use crate::telemetry::metrics::{self, Feed};
+use crate::types::instrument::Instrument;
+
+const STARTUP_ACTIONS: &[OrderAction] = &[OrderAction::CancelAll];
+
+/// How long shutdown waits for the venue to confirm its cancel-all.
+const SHUTDOWN_CANCEL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The venue and market data an engine trades against. Built from the config for the
+/// binary, or by hand to embed the engine against another venue or feed.
+pub struct Connections {
+    pub venue: DynamicVenue,
+    /// The channel `venue` publishes its order reports on.
+    pub reports: ReportSender,
+    pub market: Arc<dyn MarketDataSource>,
+}
+
+impl Connections {
+    /// The configured execution venue and market data source.
+    pub async fn from_config(config: &AppConfig, rng: &SeededRng) -> Result<Self> {
+        let (venue, reports) = Self::venue_from_config(config, rng).await?;
+
+        Ok(Self {
+            venue,
+            reports,
+            market: Scenario::market_source(&config.market),
+        })
+    }
+
+    /// The configured execution venue alone, and the channel it publishes its order
+    /// reports on, for work against the venue outside the engine.
+    pub async fn venue_from_config(
+        config: &AppConfig,
+        rng: &SeededRng,
+    ) -> Result<(DynamicVenue, ReportSender)> {
+        capture::install(&config.venue.capture)?;
+        symbols::install(&config.venue.kraken.pairs);
+
+        let (reports, _) = broadcast::channel(config.channels.order_reports);
+        let venue = Scenario::execution_venue(&config.venue, reports.clone(), rng).await?;
+
+        Ok((venue, reports))
+    }
+}
+
+/// Starts an engine for `instruments` and runs it until shutdown.
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use accumulator::backtest::sim_venue::SimVenue;
+/// use accumulator::config::app_config::AppConfig;
+/// use accumulator::engine::engine::{self, Connections};
+/// use accumulator::kraken::kraken_market::KrakenMarket;
+/// use accumulator::types::instrument::InstrumentConfig;
+/// use accumulator::types::inventory::Inventory;
+/// use tokio::sync::broadcast;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let config = AppConfig::default();
+/// let instruments = vec![InstrumentConfig::default().load()?];
+///
+/// // Live Kraken prices against a simulated venue instead of the configured one.
+/// let (reports, _) = broadcast::channel(10_000);
+/// let initial = Inventory { base: 0.0, quote: 1_000.0 };
+/// let connections = Connections {
+///     venue: Box::new(SimVenue::new(reports.clone(), initial)),
+///     reports,
+///     market: Arc::new(KrakenMarket::default()),
+/// };
+///
+/// let shutdown = engine::run("embedded", config, instruments, connections).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn run(
+    session_id: &str,
+    config: AppConfig,
+    instruments: Vec<Instrument>,
+    connections: Connections,
+) -> Result<Shutdown> {
+    Engine::start(session_id, config, instruments, connections)
+        .await?
+        .run()
+        .await
+}
+
+/// Why the engine stopped, which decides the process exit status.
+#[derive(Debug, Clone, Copy)]
+pub enum Shutdown {
+    Signal,
+    /// A critical background task stopped, or the report channel closed.
+    TaskFailed,
+    FeedDead(DeadFeed),
+}
+
+impl Shutdown {
+    /// Distinct status for a watchdog exit so a supervisor can tell it from a crash.
+    pub const FEED_DEAD_EXIT_CODE: u8 = 3;
+
+    pub fn exit_code(self) -> ExitCode {
+        match self {
+            Shutdown::Signal => ExitCode::SUCCESS,
+            Shutdown::TaskFailed => ExitCode::FAILURE,
+            Shutdown::FeedDead(_) => ExitCode::from(Self::FEED_DEAD_EXIT_CODE),
+        }
+    }
+}
+
+/// Owns one [`InstrumentEngine`] per configured instrument plus the venue, report channel
+/// and admin command channel they share. Market events and order reports are routed to the
+/// engine of their instrument; venue-wide reports go to all of them.
+pub struct Engine {
+    session_id: String,
+    config: AppConfig,
+    config_loader: Option<ConfigLoader>,
+    effective_config: watch::Sender<Value>,
+    clock: SharedClock,
+    started_at: DateTime<Utc>,
+    reports_dir: PathBuf,
+    venue_kind: VenueKind,
+    state_store: Option<StateStore>,
+    trade_checkpoint: Option<TradeCheckpoint>,
+    state_save_interval: Duration,
+    heartbeat_interval: Duration,
+    reconcile_interval: Option<Duration>,
+    summary_write_interval: Option<Duration>,
+    timer_interval: Duration,
+    instruments: HashMap<Instrument, InstrumentEngine>,
+    venue: DynamicVenue,
+    kill_switch: KillSwitch,
+    alerts: AlertHandle,
+    order_reports: broadcast::Receiver<OrderReport>,
+    market_events: mpsc::Receiver<MarketEvent>,
+    journal: Option<JournalRecorder>,
+    coalesce_market_depth: usize,
+    admin_commands: mpsc::Receiver<AdminCommand>,
+    dead_feeds: mpsc::Receiver<DeadFeed>,
+    supervisor: Supervisor,
+    escalations: mpsc::UnboundedReceiver<Escalation>,
+    /// Keeps the admin channel open when the admin API is disabled.
+    _admin_sender: mpsc::Sender<AdminCommand>,
+}
+
+impl Engine {
+    /// Spawns the feeds, venue and background tasks, and builds an engine per instrument.
+    pub async fn start(
+        session_id: &str,
+        config: AppConfig,
+        instruments: Vec<Instrument>,
+        connections: Connections,
+    ) -> Result<Self> {
+        Self::start_with_clock(
+            session_id,
+            config,
+            instruments,
+            connections,
+            SystemClock::shared(),
+        )
+        .await
+    }
+
+    /// Like [`start`](Self::start), with the engines reading the time from `clock`
+    /// rather than the wall clock, so a test can hold it inside trading hours.
+    pub async fn start_with_clock(
+        session_id: &str,
+        config: AppConfig,
+        instruments: Vec<Instrument>,
+        connections: Connections,
+        clock: SharedClock,
+    ) -> Result<Self> {
+        info!(config = %config.effective(), "effective configuration");
+
+        if let Some(port) = config.metrics.port {
+            metrics::install(config.metrics.bind, port)?;
+        }
+
+        let (market_event_sender, market_events) =
+            mpsc::channel::<MarketEvent>(config.channels.market_events);
+        let Connections {
+            venue,
+            reports: order_report_sender,
+            market,
+        } = connections;
+        let order_reports = order_report_sender.subscribe();
+        let (supervisor, escalations) = Supervisor::new();
+        metrics::spawn_report_metrics(&supervisor, &order_report_sender);
+
+        if config.fill_log.enabled {
+            FillRecorder::spawn(&config.fill_log, order_report_sender.subscribe());
+        }
+
+        let alerts = match config.alerts.alerter_config()? {
+            Some(alerter_config) => Alerter::spawn(alerter_config, order_report_sender.subscribe()),
+            None => AlertHandle::disabled(),
+        };
+
+        supervisor.spawn("report_logger", RestartPolicy::restart(), {
+            let order_report_sender = order_report_sender.clone();
+            move || {
+                let mut receiver = order_report_sender.subscribe();
+                async move {
+                    loop {
+                        match receiver.recv().await {
+                            Ok(report) => logging::order_report(&report),
+                            Err(broadcast::error::RecvError::Lagged(n)) => {
+                                warn!(lagged = n, "order report logger lagged; dropped messages");
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                }
+            }
+        });
+
+        // Reference and FX pairs are subscribed alongside the traded ones, once each.
+        let mut watched: Vec<Instrument> = Vec::new();
+        for instrument in &instruments {
+            if let Some(reference) = ReferenceFairPrice::for_instrument(&config.signals, instrument)
+                .with_context(|| format!("reference price for {instrument}"))?
+            {
+                for pair in reference.instruments() {
+                    if !instruments.contains(pair) && !watched.contains(pair) {
+                        watched.push(pair.clone());
+                    }
+                }
+            }
+        }
+
+        for instrument in &instruments {
+            venue.check_instrument(instrument)?;
+        }
+        for instrument in instruments.iter().chain(&watched) {
+            market.check_instrument(instrument)?;
+        }
+
+        for instrument in instruments.iter().chain(&watched) {
+            supervisor.spawn(format!("market {instrument}"), RestartPolicy::restart(), {
+                let instrument = instrument.clone();
+                let market_event_sender = market_event_sender.clone();
+                let market = Arc::clone(&market);
+                let supervisor = supervisor.clone();
+                move || {
+                    let instrument = instrument.clone();
+                    let market_event_sender = market_event_sender.clone();
+                    let market = Arc::clone(&market);
+                    let supervisor = supervisor.clone();
+                    async move {
+                        loop {
+                            if let Err(error) = market
+                                .subscribe(&instrument, market_event_sender.clone())
+                                .await
+                            {
+                                if error.downcast_ref::<SubscriptionRejected>().is_some() {
+                                    supervisor.escalate(
+                                        &format!("market {instrument}"),
+                                        format!("{error:#}"),
+                                    );
+                                    return;
+                                }
+                                error!("market data source stopped with error: {error:?}");
+                            }
+
+                            tokio::time::sleep(Duration::from_secs(1)).await;
+                            liveness::feed_reconnect(Feed::Market);
+                        }
+                    }
+                }
+            });
+        }
+
+        let (admin_sender, admin_commands) = mpsc::channel::<AdminCommand>(64);
+        let (effective_config, _) = watch::channel(config.effective());
+        if let Some(port) = config.admin.port {
+            admin_server::spawn(
+                port,
+                &config.admin,
+                effective_config.subscribe(),
+                admin_sender.clone(),
+            )
+            .await?;
+        }
+
+        let venue: DynamicVenue = Box::new(RateLimitedVenue::new(
+            venue,
+            config.venue.place_limit(),
+            clock.clone(),
+        ));
+
+        let state_store = config.state.path.as_ref().map(StateStore::new);
+        let mut restored = state_store
+            .as_ref()
+            .and_then(|store| store.load(config.venue.kind));
+        // Before the stream starts, so trades it replays from before the restart are
+        // skipped and the ones made since are caught up.
+        let trade_checkpoint = venue.trade_checkpoint();
+        if let (Some(checkpoint), Some(state)) = (&trade_checkpoint, &mut restored) {
+            checkpoint.restore(std::mem::take(&mut state.last_trade_ids));
+        }
+        // Subscribed before the stream starts too, so the stats see those caught-up trades.
+        let mut stats_reports: HashMap<Instrument, broadcast::Receiver<OrderReport>> = instruments
+            .iter()
+            .map(|instrument| (instrument.clone(), order_report_sender.subscribe()))
+            .collect();
+        venue
+            .spawn_reports(order_report_sender.clone(), &supervisor)
+            .await?;
+
+        let intent_log = config
+            .state
+            .intent_log
+            .as_ref()
+            .map(IntentLog::open)
+            .transpose()?;
+        let first_seq = intent_log.as_ref().map_or(1, IntentLog::next_seq);
+        // Places a previous run logged and never saw finish: its own, whatever leads them.
+        let logged: Option<HashSet<String>> = intent_log.as_ref().map(|log| {
+            log.unresolved()
+                .into_iter()
+                .map(|intent| intent.order_id)
+                .collect()
+        });
+        let order_ids = match &config.venue.order_id_prefix {
+            Some(prefix) => OrderIds::with_prefix(prefix, first_seq),
+            None => OrderIds::structured(session_id, first_seq),
+        };
+        order_ids.skip_open_orders(&venue, &instruments).await?;
+
+        let venue: DynamicVenue = match intent_log {
+            Some(log) => {
+                let venue = IntentLoggedVenue::new(venue, log);
+                venue.spawn_resolver(order_report_sender.subscribe());
+                venue
+                    .recover(&instruments, &order_ids, !config.state.adopt_open_orders)
+                    .await?;
+                Box::new(venue)
+            }
+            None => venue,
+        };
+
+        // A kill switch engaged before the restart stays engaged until someone releases it.
+        let kill_switch_engaged =
+            config.risk.kill_switch || restored.as_ref().is_some_and(|state| state.kill_switch);
+
+        let shared = SharedContext {
+            kill_switch: KillSwitch::new(kill_switch_engaged),
+            portfolio: config
+                .risk
+                .max_portfolio_exposure_in_quote
+                .map(|max_exposure| (PortfolioExposure::default(), max_exposure)),
+            scoped_cancels: instruments.len() > 1,
+            clock,
+            order_ids,
+            cycle_ids: CycleIds::default(),
+            supervisor: supervisor.clone(),
+            fill_report: config
+                .stats
+                .fill_report
+                .as_deref()
+                .map(FillReport::open)
+                .transpose()?,
+            decision_log: config
+                .logging
+                .decision_log
+                .as_deref()
+                .map(DecisionLog::open)
+                .transpose()?,
+        };
+
+        let mut engines = HashMap::new();
+        for instrument in instruments {
+            let span = info_span!("instrument", instrument = %instrument);
+            let book = restored
+                .as_mut()
+                .and_then(|state| state.books.remove(&instrument.to_string()))
+                .unwrap_or_default();
+            let reports = stats_reports
+                .remove(&instrument)
+                .unwrap_or_else(|| order_report_sender.subscribe());
+            let stats = SessionStats::spawn(
+                &config.stats,
+                &instrument,
+                book,
+                shared.clock.clone(),
+                reports,
+            );
+            let mut engine = InstrumentEngine::build(
+                &config,
+                instrument.clone(),
+                &venue,
+                &order_report_sender,
+                &shared,
+                stats,
+            )
+            .instrument(span)
+            .await?;
+            if let Some(day) = restored
+                .as_mut()
+                .and_then(|state| state.daily_loss.remove(&instrument.to_string()))
+            {
+                engine.restore_daily_loss(day);
+            }
+            if let Some(pnl) = restored
+                .as_mut()
+                .and_then(|state| state.pnl.remove(&instrument.to_string()))
+            {
+                engine.restore_pnl(pnl);
+            }
+
+            engines.insert(instrument, engine);
+        }
+
+        if config.state.adopt_open_orders {
+            for engine in engines.values_mut() {
+                engine.adopt(&venue, logged.as_ref()).await?;
+            }
+        } else {
+            venue.execute(STARTUP_ACTIONS).await?;
+        }
+
+        let dead_feeds = Watchdog::new(&config.watchdog, config.venue.kind, Instant::now())
+            .spawn(config.watchdog.exit);
+
+        Ok(Self {
+            session_id: session_id.to_string(),
+            config_loader: None,
+            effective_config,
+            started_at: shared.clock.now_utc(),
+            clock: shared.clock,
+            reports_dir: config.stats.reports_dir.clone(),
+            venue_kind: config.venue.kind,
+            state_store,
+            trade_checkpoint,
+            state_save_interval: config.state.save_interval(),
+            heartbeat_interval: config.watchdog.heartbeat_interval(),
+            reconcile_interval: config.venue.reconcile_interval(),
+            summary_write_interval: config.stats.summary_write_interval(),
+            timer_interval: config.scheduling.timer_interval(),
+            instruments: engines,
+            venue,
+            kill_switch: shared.kill_switch,
+            alerts,
+            order_reports,
+            market_events,
+            journal: config
+                .market
+                .record
+                .as_deref()
+                .map(JournalRecorder::spawn)
+                .transpose()?,
+            coalesce_market_depth: config.channels.coalesce_market_depth,
+            admin_commands,
+            dead_feeds,
+            supervisor,
+            escalations,
+            _admin_sender: admin_sender,
+            config,
+        })
+    }
+
+    /// Enables config reloads, on SIGHUP and `POST /reload`, from `loader`.
+    pub fn with_config_reload(mut self, loader: ConfigLoader) -> Self {
+        self.config_loader = Some(loader);
+        self
+    }
+
+    /// Runs until a shutdown signal, a dead feed or a fatal error, then flushes the fill
+    /// report, saves state and logs and writes the session summary either way, and stops
+    /// the background tasks.
+    pub async fn run(mut self) -> Result<Shutdown> {
+        let result = self.event_loop().await;
+        for engine in self.instruments.values_mut() {
+            engine.finish_fill_report();
+        }
+        self.save_state().await;
+        self.summarize().await;
+        self.supervisor.shutdown();
+        info!("shutdown complete");
+        result
+    }
+
+    async fn event_loop(&mut self) -> Result<Shutdown> {
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
+
+        let mut reload_signal = ReloadSignal::listen(self.config_loader.is_some());
+        let mut reports_open = true;
+
+        let mut state_ticker = tokio::time::interval_at(
+            tokio::time::Instant::now() + self.state_save_interval,
+            self.state_save_interval,
+        );
+        // Also wakes an idle loop, so the watchdog sees it complete iterations.
+        let mut heartbeat = tokio::time::interval_at(
+            tokio::time::Instant::now() + self.heartbeat_interval,
+            self.heartbeat_interval,
+        );
+        // Only ticks when reconciliation is enabled; the period is then the configured one.
+        let reconcile_period = self.reconcile_interval.unwrap_or(Duration::from_secs(30));
+        let mut reconcile_ticker = tokio::time::interval_at(
+            tokio::time::Instant::now() + reconcile_period,
+            reconcile_period,
+        );
+        let summary_period = self
+            .summary_write_interval
+            .unwrap_or(Duration::from_secs(60));
+        let mut summary_ticker =
+            tokio::time::interval_at(tokio::time::Instant::now() + summary_period, summary_period);
+        // Evaluates instruments whose books have gone quiet; each skips it while its
+        // market events keep cycles running.
+        let mut evaluation_timer = tokio::time::interval_at(
+            tokio::time::Instant::now() + self.timer_interval,
+            self.timer_interval,
+        );
+
+        loop {
+            liveness::loop_completed();
+
+            // Biased, so order reports are applied before market events when both are
+            // waiting, and each evaluation plans against the latest order state.
+            tokio::select! {
+                biased;
+
+                _ = &mut shutdown => {
+                    info!("shutdown signal received");
+                    self.cancel_all_before_exit("signal").await;
+                    return Ok(Shutdown::Signal);
+                }
+
+                Some(dead) = self.dead_feeds.recv() => {
+                    error!(feed = dead.feed.label(), "shutting down: critical feed dead");
+                    self.cancel_all_before_exit("watchdog").await;
+                    return Ok(Shutdown::FeedDead(dead));
+                }
+
+                Some(escalation) = self.escalations.recv() => {
+                    error!(task = %escalation.task, failure = %escalation.failure, "shutting down: critical task failed");
+                    self.cancel_all_before_exit("task failure").await;
+                    return Ok(Shutdown::TaskFailed);
+                }
+
+                report = self.order_reports.recv(), if reports_open => {
+                    match report {
+                        Ok(report) => {
+                            metrics::channel_depth("order_reports", self.order_reports.len());
+                            self.on_report(report);
+                            self.on_reprice().await?;
+                        }
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            metrics::reports_lagged("engine", n);
+                            warn!(lagged = n, "engine lagged on order reports; resyncing orders from the venue");
+                            self.resync_orders().await;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            reports_open = false;
+                            self.supervisor.escalate("order_reports", "report channel closed");
+                        }
+                    }
+                }
+
+                _ = reload_signal.recv() => {
+                    info!("SIGHUP received; reloading config");
+                    if let Err(error) = self.reload() {
+                        warn!("config reload rejected: {error:#}");
+                    }
+                }
+
+                _ = state_ticker.tick(), if self.state_store.is_some() => {
+                    self.save_state().await;
+                }
+
+                _ = reconcile_ticker.tick(), if self.reconcile_interval.is_some() => {
+                    self.reconcile_orders().await;
+                }
+
+                _ = summary_ticker.tick(), if self.summary_write_interval.is_some() => {
+                    self.write_summary(&self.session_summary().await);
+                }
+
+                _ = heartbeat.tick() => {
+                    for engine in self.instruments.values() {
+                        engine.heartbeat().log();
+                    }
+                }
+
+                Some(command) = self.admin_commands.recv() => {
+                    self.on_admin_command(command).await?;
+                }
+
+                _ = evaluation_timer.tick() => {
+                    self.on_timer().await?;
+                }
+
+                Some(event) = self.market_events.recv() => {
+                    let depth = self.market_events.len();
+                    metrics::channel_depth("market_events", depth);
+                    if depth < self.coalesce_market_depth {
+                        self.on_market_event(event).await?;
+                    } else {
+                        let (events, dropped) = channels::coalesce(event, &mut self.market_events);
+                        metrics::market_events_coalesced(dropped);
+                        warn!(depth, dropped, kept = events.len(), "market events backed up; coalesced books");
+                        for event in events {
+                            self.on_market_event(event).await?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn on_market_event(&mut self, event: MarketEvent) -> Result<()> {
+        // Reports that arrived while the last evaluation ran, including between
+        // coalesced books.
+        self.drain_reports().await;
+
+        if !matches!(event, MarketEvent::SourceDisconnected { .. }) {
+            liveness::feed_event(Feed::Market);
+        }
+        if let Some(journal) = &mut self.journal {
+            journal.record(&event);
+        }
+        self.venue.on_market_event(&event);
+
+        let mut referenced = false;
+        for engine in self.instruments.values_mut() {
+            referenced |= engine.on_reference_event(&event);
+        }
+
+        // Called by path: `tracing::Instrument::instrument` is also in scope.
+        let instrument = MarketEvent::instrument(&event);
+        let Some(engine) = self.instruments.get_mut(instrument) else {
+            if !referenced {
+                warn!(%instrument, "market event for unknown instrument");
+            }
+            return Ok(());
+        };
+
+        // Runs in its own `cycle` span, which carries the instrument.
+        engine.on_market_event(&event, &self.venue).await
+    }
+
+    /// Re-plans at once the instruments whose post-only places the venue just refused
+    /// for crossing.
+    async fn on_reprice(&mut self) -> Result<()> {
+        for engine in self.instruments.values_mut() {
+            engine.on_reprice(&self.venue).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_timer(&mut self) -> Result<()> {
+        self.drain_reports().await;
+        for engine in self.instruments.values_mut() {
+            engine.on_timer(&self.venue).await?;
+        }
+        Ok(())
+    }
+
+    /// Cancels every order on the venue and waits up to [`SHUTDOWN_CANCEL_TIMEOUT`] for the
+    /// venue to confirm, applying reports meanwhile, so the engine does not exit with
+    /// orders left on the book. No more market events are handled.
+    async fn cancel_all_before_exit(&mut self, reason: &str) {
+        if let Err(error) = self.venue.execute(&[OrderAction::CancelAll]).await {
+            error!("cancel all on {reason} shutdown failed: {error:#}");
+            return;
+        }
+
+        let awaited = await_reports(&mut self.order_reports, SHUTDOWN_CANCEL_TIMEOUT, |report| {
+            matches!(
+                report,
+                OrderReport::CancelledAll { .. } | OrderReport::VenueError { .. }
+            )
+        })
+        .await;
+        let confirmation = awaited.last().cloned();
+        for report in awaited.reports {
+            self.on_report(report);
+        }
+
+        match confirmation {
+            Some(OrderReport::CancelledAll { count }) => {
+                info!(count, "all orders cancelled before exit");
+            }
+            Some(report) => {
+                error!(
+                    reason = report.reason(),
+                    "cancel all on {reason} shutdown failed; orders may be left on the venue"
+                );
+            }
+            None => warn!(
+                timeout = ?SHUTDOWN_CANCEL_TIMEOUT,
+                "cancel all on {reason} shutdown not confirmed; orders may be left on the venue"
+            ),
+        }
+    }
+
+    /// Applies every report already queued, without waiting for more. A closed channel
+    /// is left for the event loop to notice.
+    async fn drain_reports(&mut self) {
+        loop {
+            match self.order_reports.try_recv() {
+                Ok(report) => self.on_report(report),
+                Err(broadcast::error::TryRecvError::Lagged(n)) => {
+                    metrics::reports_lagged("engine", n);
+                    warn!(
+                        lagged = n,
+                        "engine lagged on order reports; resyncing orders from the venue"
+                    );
+                    self.resync_orders().await;
+                }
+                Err(
+                    broadcast::error::TryRecvError::Empty | broadcast::error::TryRecvError::Closed,
+                ) => {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Rebuilds every instrument's order state from the venue, after the engine missed
+    /// reports. A failed resync is retried by the instrument's next cycle.
+    async fn resync_orders(&mut self) {
+        for (instrument, engine) in &mut self.instruments {
+            if let Err(error) = engine.resync(&self.venue).await {
+                error!(%instrument, "order resync failed: {error:#}");
+            }
+        }
+    }
+
+    /// Compares every instrument's orders with the venue's open orders and corrects
+    /// drift. A failed reconciliation is retried at the next tick.
+    async fn reconcile_orders(&mut self) {
+        self.drain_reports().await;
+        for (instrument, engine) in &mut self.instruments {
+            if let Err(error) = engine.reconcile(&self.venue).await {
+                error!(%instrument, "order reconciliation failed: {error:#}");
+            }
+        }
+    }
+
+    /// Re-reads the config and applies it if only hot-swappable sections changed;
+    /// otherwise nothing is applied. Returns the sections that changed.
+    fn reload(&mut self) -> Result<Vec<&'static str>> {
+        let loader = self
+            .config_loader
+            .as_ref()
+            .ok_or_else(|| anyhow!("config reload is not enabled for this engine"))?;
+        let config = loader()?;
+        let mut changed = reload::changed_sections(&self.config, &config)?;
+        let trading_rules = reload::reloaded_trading_rules(self.instruments.keys(), &config)?;
+        if !trading_rules.is_empty() {
+            changed.push("trading_rules");
+        }
+        if changed.is_empty() {
+            info!("config reloaded; nothing changed");
+            return Ok(changed);
+        }
+
+        // Built first so a bad webhook rejects the reload before anything is applied.
+        let alerts = if changed.contains(&"alerts") {
+            Some(match config.alerts.alerter_config()? {
+                Some(alerter_config) => {
+                    Alerter::spawn(alerter_config, self.order_reports.resubscribe())
+                }
+                None => AlertHandle::disabled(),
+            })
+        } else {
+            None
+        };
+
+        // Rules first: the risk limits and strategies read them as they reload.
+        for (instrument, rules) in trading_rules {
+            info!(%instrument, ?rules, "trading rules reloaded");
+            instrument.set_trading_rules(rules);
+        }
+        for engine in self.instruments.values_mut() {
+            engine.reload(&config);
+        }
+        if let Some(alerts) = alerts {
+            // Dropping the old handle stops its alerter.
+            self.alerts = alerts;
+        }
+
+        self.config = config;
+        self.effective_config.send_replace(self.config.effective());
+        info!(changed = %changed.join(","), "config reloaded");
+
+        Ok(changed)
+    }
+
+    async fn save_state(&self) {
+        let Some(store) = &self.state_store else {
+            return;
+        };
+
+        let mut books = BTreeMap::new();
+        let mut daily_loss = BTreeMap::new();
+        let mut pnl = BTreeMap::new();
+        for (instrument, engine) in &self.instruments {
+            pnl.insert(instrument.to_string(), engine.pnl().state());
+            if let Some(book) = engine.book().await {
+                books.insert(instrument.to_string(), book);
+            }
+            if let Some(day) = engine.daily_loss() {
+                daily_loss.insert(instrument.to_string(), day);
+            }
+        }
+
+        let state = EngineState {
+            venue: self.venue_kind,
+            saved_at: self.clock.now_utc(),
+            kill_switch: self.kill_switch.is_engaged(),
+            books,
+            daily_loss,
+            pnl,
+            last_trade_ids: self
+                .trade_checkpoint
+                .as_ref()
+                .map(TradeCheckpoint::saved)
+                .unwrap_or_default(),
+        };
+
+        if let Err(error) = store.save(&state) {
+            error!(path = %store.path().display(), "failed to save engine state: {error:#}");
+        }
+    }
+
+    async fn summarize(&self) {
+        let summary = self.session_summary().await;
+        summary.log();
+        if let Some(path) = self.write_summary(&summary) {
+            info!(path = %path.display(), "session summary written");
+        }
+    }
+
+    /// The session so far, from each instrument's stats task.
+    async fn session_summary(&self) -> SessionSummary {
+        let mut instruments = Vec::with_capacity(self.instruments.len());
+        for engine in self.instruments.values() {
+            match engine.summary().await {
+                Some(summary) => instruments.push(summary),
+                None => warn!(instrument = %engine.instrument(), "stats task stopped; no summary"),
+            }
+        }
+
+        SessionSummary::new(
+            &self.session_id,
+            self.started_at,
+            self.clock.now_utc(),
+            instruments,
+        )
+    }
+
+    fn write_summary(&self, summary: &SessionSummary) -> Option<PathBuf> {
+        summary
+            .write(&self.reports_dir)
+            .inspect_err(|error| error!("failed to write session summary: {error:#}"))
+            .ok()
+    }
+
+    fn on_report(&mut self, report: OrderReport) {
+        match OrderReport::instrument(&report) {
+            Some(instrument) => match self.instruments.get_mut(instrument) {
+                Some(engine) => engine.on_report(report),
+                None => warn!(%instrument, "order report for unknown instrument"),
+            },
+            None => {
+                for engine in self.instruments.values_mut() {
+                    engine.on_report(report.clone());
+                }
+            }
+        }
+    }
+
+    async fn on_admin_command(&mut self, command: AdminCommand) -> Result<()> {
+        match command {
+            AdminCommand::Status { reply } => {
+                let mut instruments: Vec<_> = self
+                    .instruments
+                    .values()
+                    .map(InstrumentEngine::status)
+                    .collect();
+                instruments.sort_by(|a, b| a.instrument.cmp(&b.instrument));
+
+                let _ = reply.send(EngineStatus {
+                    kill_switch: self.kill_switch.is_engaged(),
+                    instruments,
+                    tasks: self.supervisor.health(),
+                });
+            }
+            AdminCommand::RecentOrders { limit, reply } => {
+                let mut orders: Vec<_> = self
+                    .instruments
+                    .values()
+                    .flat_map(|engine| engine.recent_orders(limit))
+                    .collect();
+                orders.sort_by_key(|order| Reverse(order.first_seen));
+                orders.truncate(limit);
+
+                let _ = reply.send(orders);
+            }
+            AdminCommand::SetKillSwitch { engaged, reply } => {
+                self.kill_switch.set(engaged);
+                self.alerts.raise(Alert::KillSwitch { engaged });
+                warn!(engaged, "kill switch set via admin api");
+
+                let result = if engaged {
+                    self.venue
+                        .execute(&[OrderAction::CancelAll])
+                        .await
+                        .map_err(|error| format!("{error:#}"))
+                } else {
+                    Ok(())
+                };
+
+                let _ = reply.send(result.map(|()| self.kill_switch.is_engaged()));
+            }
+            AdminCommand::CancelAll { reply } => {
+                warn!("cancel all requested via admin api");
+                let result = self
+                    .venue
+                    .execute(&[OrderAction::CancelAll])
+                    .await
+                    .map_err(|error| format!("{error:#}"));
+
+                let _ = reply.send(result);
+            }
+            AdminCommand::Flatten { reply } => {
+                warn!("flatten requested via admin api");
+                for engine in self.instruments.values_mut() {
+                    engine.start_flatten();
+                }
+
+                let _ = reply.send(Ok(()));
+            }
+            AdminCommand::Reload { reply } => {
+                warn!("config reload requested via admin api");
+                let result = self.reload().map_err(|error| {
+                    warn!("config reload rejected: {error:#}");
+                    format!("{error:#}")
+                });
+
+                let _ = reply.send(result);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// SIGHUP, where the platform has it, as a request to reload the config.
+struct ReloadSignal {
+    #[cfg(unix)]
+    hangup: Option<tokio::signal::unix::Signal>,
+}
+
+impl ReloadSignal {
+    /// Leaves SIGHUP alone unless `enabled`, so it keeps its default meaning.
+    fn listen(enabled: bool) -> Self {
+        #[cfg(unix)]
+        let hangup = enabled
+            .then(|| tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()))
+            .and_then(|signal| {
+                signal
+                    .map_err(|error| error!("failed to listen for SIGHUP: {error}"))
+                    .ok()
+            });
+
+        #[cfg(not(unix))]
+        let _ = enabled;
+
+        Self {
+            #[cfg(unix)]
+            hangup,
+        }
+    }
+
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(hangup) = &mut self.hangup
+            && hangup.recv().await.is_some()
+        {
+            return;
+        }
+
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Resolves on Ctrl-C, or on SIGTERM where the platform has it.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(error) = tokio::signal::ctrl_c().await {
+            error!("failed to listen for ctrl-c: {error}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(error) => {
+                error!("failed to listen for SIGTERM: {error}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use tracing::info;
+
+/// One instrument's view of the engine loop, logged every few seconds: how recently it
+/// saw market data and evaluated a quote, why it last skipped, and how long its orders
+/// have been waiting on the venue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heartbeat {
+    pub instrument: String,
+    pub since_market_event: Option<Duration>,
+    pub since_evaluation: Option<Duration>,
+    /// Reason code of the last skipped schedule.
+    pub last_skip: Option<&'static str>,
+    /// How long an order of the instrument has been placing or cancelling.
+    pub in_flight_for: Option<Duration>,
+}
+
+impl Heartbeat {
+    pub fn log(&self) {
+        info!(
+            instrument = %self.instrument,
+            market_ms = millis(self.since_market_event),
+            evaluation_ms = millis(self.since_evaluation),
+            last_skip = self.last_skip,
+            in_flight_ms = millis(self.in_flight_for),
+            "heartbeat"
+        );
+    }
+}
+
+fn millis(duration: Option<Duration>) -> Option<u64> {
+    duration.map(|duration| duration.as_millis() as u64)
+}
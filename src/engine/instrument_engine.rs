@@ -0,0 +1,1192 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tokio::sync::watch;
+use tracing::{Instrument as _, debug, info, info_span, warn};
+
+use crate::admin::status::{InstrumentStatus, MarketStatus, OrdersStatus, SchedulerStatus};
+use crate::clock::SharedClock;
+use crate::config::app_config::AppConfig;
+use crate::engine::heartbeat::Heartbeat;
+use crate::engine::supervisor::Supervisor;
+use crate::events::{BookLevel, MarketEvent};
+use crate::execution::known_orders::KnownOrders;
+use crate::execution::order_action::{OrderAction, Side};
+use crate::execution::order_history::{OrderHistory, OrderLifecycle};
+use crate::execution::order_ids::{ClientOrderId, OrderIds};
+use crate::execution::order_manager::OrderManager;
+use crate::execution::order_report::OrderReport;
+use crate::execution::types::OpenOrder;
+use crate::execution::{DynamicInventorySource, ReportSender};
+use crate::inventory::readiness;
+use crate::market::market_state::MarketState;
+use crate::risk::checks::{
+    kill_switch::KillSwitch,
+    max_daily_loss::{DailyLoss, DailyLossState},
+    portfolio_exposure::PortfolioExposure,
+};
+use crate::risk::context::RiskContext;
+use crate::risk::decision::{RiskApproval, RiskDecision};
+use crate::risk::engine::RiskEngine;
+use crate::scenario::scenario::{DynamicVenue, Scenario};
+use crate::scheduling::policies::in_flight_policy::InFlightPolicy;
+use crate::scheduling::policies::min_interval_policy::MinIntervalPolicy;
+use crate::scheduling::policies::rejection_backoff_policy::RejectionBackoffPolicy;
+use crate::scheduling::policies::top_of_book_tick_move_policy::TopOfBookTickMovePolicy;
+use crate::scheduling::policies::trading_hours_policy::TradingHoursPolicy;
+use crate::scheduling::policies::venue_latency_policy::VenueLatencyPolicy;
+use crate::scheduling::policies::warmup_policy::WarmupPolicy;
+use crate::scheduling::quote_scheduler::QuoteScheduler;
+use crate::scheduling::schedule_context::{ScheduleContext, Trigger};
+use crate::scheduling::types::ScheduleDecision;
+use crate::signals::reference_price::ReferenceFairPrice;
+use crate::signals::signal_state::SignalState;
+use crate::stats::fill_annotator::{DecisionSignals, FillAnnotator, FillReport};
+use crate::stats::session_stats::{StatsEvent, StatsHandle};
+use crate::stats::session_summary::InstrumentSummary;
+use crate::stats::trading_book::TradingBook;
+use crate::strategy::exit::ExitManager;
+use crate::strategy::flatten::flatten_target;
+use crate::strategy::shadow::ShadowStrategy;
+use crate::strategy::strategy::Strategy;
+use crate::telemetry::cycles::{self, CycleIds};
+use crate::telemetry::decision_log::{DecisionLog, DecisionRecord, ShadowDecision};
+use crate::telemetry::latency::{EventTrace, LatencyTracker, Stage};
+use crate::telemetry::logging;
+use crate::telemetry::metrics;
+use crate::types::instrument::Instrument;
+use crate::types::inventory::Inventory;
+use crate::types::pnl::{PnlState, PnlTracker};
+use crate::types::price::Price;
+use crate::types::quote_target::{NoQuoteReason, QuoteTarget};
+
+/// The `event` of a cycle the evaluation timer started.
+const TIMER_EVENT: &str = "timer";
+const REPRICE_EVENT: &str = "reprice";
+
+/// Skip reason of a cycle held because lost reports left the order state unknown.
+const ORDERS_UNSYNCED: &str = "orders_unsynced";
+
+/// Depth ladder levels a side summed in the debug log of each depth update.
+const DEPTH_LOG_LEVELS: usize = 5;
+
+/// Process-wide state every instrument engine is built against.
+pub struct SharedContext {
+    pub kill_switch: KillSwitch,
+    pub portfolio: Option<(PortfolioExposure, f64)>,
+    /// Cancel only this instrument's orders on a hard risk rejection instead of everything
+    /// on the venue. Set when more than one instrument trades on the same venue.
+    pub scoped_cancels: bool,
+    pub clock: SharedClock,
+    pub order_ids: OrderIds,
+    pub cycle_ids: CycleIds,
+    /// Runs the venue's inventory tasks.
+    pub supervisor: Supervisor,
+    pub fill_report: Option<FillReport>,
+    pub decision_log: Option<DecisionLog>,
+}
+
+/// Everything needed to quote one instrument: market and signal state, strategy, order
+/// manager, risk engine and scheduler. The engine routes events here by instrument.
+pub struct InstrumentEngine {
+    instrument: Instrument,
+    market_state: MarketState,
+    signal_state: SignalState,
+    reference: Option<ReferenceFairPrice>,
+    strategy: Box<dyn Strategy>,
+    shadows: Vec<ShadowStrategy>,
+    order_manager: OrderManager,
+    order_history: OrderHistory,
+    known_orders: KnownOrders,
+    order_ids: OrderIds,
+    risk_engine: RiskEngine,
+    daily_loss: DailyLoss,
+    quote_scheduler: QuoteScheduler,
+    inventory_source: watch::Receiver<Inventory>,
+    inventory_feed: DynamicInventorySource,
+    /// Quoted from instead of `inventory_source` until its first snapshot, when a
+    /// dry-run gave up waiting for it at startup.
+    paper_inventory: Option<Inventory>,
+    stats: StatsHandle,
+    latency: LatencyTracker,
+    cycle_ids: CycleIds,
+    clock: SharedClock,
+    market_max_age: Duration,
+    scoped_cancels: bool,
+    scheduler_status: SchedulerStatus,
+    last_evaluation: Option<Instant>,
+    /// When the last cycle ran, evaluated or not, so the timer only runs on a quiet book.
+    last_cycle: Option<Instant>,
+    timer_interval: Duration,
+    /// Since when an order has been placing or cancelling, for the heartbeat.
+    in_flight_since: Option<Instant>,
+    /// Reports were lost and no resync has rebuilt the order state since. Each cycle
+    /// retries it first, and evaluates nothing until it succeeds.
+    resync_pending: bool,
+    flattening: bool,
+    exits: ExitManager,
+    fill_annotator: Option<FillAnnotator>,
+    decision_log: Option<DecisionLog>,
+    /// This cycle's record for the decision log, written once the cycle ends.
+    decision: Option<DecisionRecord>,
+    pnl: PnlTracker,
+    pnl_log_interval: Duration,
+    last_pnl_log: Option<Instant>,
+    orders_log_interval: Duration,
+    last_orders_log: Option<Instant>,
+}
+
+impl InstrumentEngine {
+    pub async fn build(
+        config: &AppConfig,
+        instrument: Instrument,
+        venue: &DynamicVenue,
+        reports: &ReportSender,
+        shared: &SharedContext,
+        stats: StatsHandle,
+    ) -> Result<Self> {
+        let inventory_feed = venue
+            .spawn_inventory(&instrument, &shared.supervisor)
+            .await?;
+        let inventory_source = inventory_feed.subscribe();
+        let paper_inventory = readiness::await_first_snapshot(
+            inventory_feed.as_ref(),
+            &instrument,
+            config.venue.kind,
+            config.venue.inventory.ready_timeout(),
+            config.venue.inventory.paper(),
+        )
+        .await?;
+
+        let strategy_config = config.strategy.for_instrument(&instrument)?;
+        let exposure_currency = config.risk.exposure_currency();
+        let mut strategy = Scenario::strategy(&strategy_config, &instrument);
+        strategy.set_exposure_currency(&exposure_currency);
+        let signal_state = Scenario::signals_for(&strategy_config, &config.signals, &instrument);
+        let reference = ReferenceFairPrice::for_instrument(&config.signals, &instrument)?;
+
+        let limits = config.risk.limits(&instrument);
+
+        let daily_loss = DailyLoss::default();
+        let risk_engine = RiskEngine::with_default_checks(
+            &limits,
+            shared.kill_switch.clone(),
+            shared.portfolio.clone(),
+            exposure_currency.clone(),
+            daily_loss.clone(),
+            shared.clock.clone(),
+        );
+
+        let min_interval_policy =
+            MinIntervalPolicy::new(config.scheduling.min_interval(), shared.clock.clone());
+        min_interval_policy.on_report(&instrument, reports.subscribe());
+        let rejection_backoff_policy =
+            RejectionBackoffPolicy::new(&config.scheduling.rejection_backoff, shared.clock.clone());
+        rejection_backoff_policy.on_report(&instrument, reports.subscribe());
+        let venue_latency_policy =
+            VenueLatencyPolicy::new(&config.scheduling.venue_latency, shared.clock.clone());
+        venue_latency_policy.on_report(&instrument, reports.subscribe());
+
+        let quote_scheduler = QuoteScheduler::new(vec![
+            Box::new(InFlightPolicy),
+            // Ahead of the policies that wait on the market, so a quiet book still has
+            // its quotes pulled when trading hours end.
+            Box::new(TradingHoursPolicy::for_instrument(&instrument)),
+            Box::new(WarmupPolicy::new(&config.scheduling.warmup)),
+            Box::new(TopOfBookTickMovePolicy::new(
+                config.scheduling.min_tick_move,
+            )),
+            Box::new(min_interval_policy),
+            Box::new(rejection_backoff_policy),
+            Box::new(venue_latency_policy),
+        ]);
+
+        let fill_annotator = shared
+            .fill_report
+            .clone()
+            .map(|report| FillAnnotator::new(instrument.clone(), report));
+        let shadows = config
+            .shadow_strategies
+            .iter()
+            .map(|kind| {
+                ShadowStrategy::new(
+                    *kind,
+                    &strategy_config,
+                    &instrument,
+                    &exposure_currency,
+                    stats.clone(),
+                )
+            })
+            .collect();
+
+        let order_history = OrderHistory::new(instrument.clone(), config.order_history.clone());
+        let mut order_manager = OrderManager::new(shared.order_ids.clone());
+        order_manager.set_in_flight_timeout(config.scheduling.in_flight_timeout());
+        order_manager.set_amend_max_ticks(config.scheduling.amend_max_ticks);
+        order_manager.set_reprice_attempts(config.scheduling.reprice_attempts);
+
+        Ok(Self {
+            instrument,
+            market_state: MarketState::new(),
+            signal_state,
+            reference,
+            strategy,
+            shadows,
+            order_manager,
+            order_history,
+            known_orders: KnownOrders::default(),
+            order_ids: shared.order_ids.clone(),
+            risk_engine,
+            daily_loss,
+            quote_scheduler,
+            inventory_source,
+            inventory_feed,
+            paper_inventory,
+            latency: LatencyTracker::new(config.metrics.port.is_some(), stats.clone()),
+            stats,
+            cycle_ids: shared.cycle_ids.clone(),
+            clock: shared.clock.clone(),
+            market_max_age: limits.market_max_age,
+            scoped_cancels: shared.scoped_cancels,
+            scheduler_status: SchedulerStatus::default(),
+            last_evaluation: None,
+            last_cycle: None,
+            timer_interval: config.scheduling.timer_interval(),
+            in_flight_since: None,
+            resync_pending: false,
+            flattening: false,
+            exits: ExitManager::new(&strategy_config.exit),
+            fill_annotator,
+            decision_log: shared.decision_log.clone(),
+            decision: None,
+            pnl: PnlTracker::default(),
+            pnl_log_interval: config.stats.pnl_log_interval(),
+            last_pnl_log: None,
+            orders_log_interval: config.stats.orders_log_interval(),
+            last_orders_log: None,
+        })
+    }
+
+    pub fn instrument(&self) -> &Instrument {
+        &self.instrument
+    }
+
+    /// Applies `event` to the reference price if it is for the reference or FX pair.
+    /// Returns whether it was; it never starts a cycle.
+    pub fn on_reference_event(&mut self, event: &MarketEvent) -> bool {
+        let now = self.clock.now_instant();
+        self.reference
+            .as_mut()
+            .is_some_and(|reference| reference.on_market_event(event, now))
+    }
+
+    pub fn on_report(&mut self, report: OrderReport) {
+        // External fills move the position too, as they do the trading book.
+        self.daily_loss
+            .open_day(self.clock.now_utc().date_naive(), self.pnl.realized());
+        self.pnl.on_report(&report);
+        self.order_history
+            .on_report(&report, self.clock.now_instant(), self.clock.now_utc());
+        if let Some(order_id) = report.order_id()
+            && !self.known_orders.contains(order_id)
+        {
+            self.on_external_report(&report, order_id);
+            return;
+        }
+
+        self.latency.on_report(&report);
+        if let Some(annotator) = &mut self.fill_annotator {
+            annotator.on_report(
+                &report,
+                self.market_state.mid_price(),
+                self.clock.now_instant(),
+                self.clock.now_utc(),
+            );
+        }
+        self.order_manager
+            .on_report(report, self.clock.now_instant());
+        self.track_in_flight();
+        metrics::open_orders(&self.instrument, self.order_manager.open_order_count());
+    }
+
+    /// A report for an order placed outside this engine, e.g. by hand on the same account.
+    /// It never touches order state; its fills still move the trading book, which sees
+    /// every report, and are counted as external.
+    fn on_external_report(&self, report: &OrderReport, order_id: &str) {
+        metrics::external_report(&self.instrument, report.kind());
+        if matches!(
+            report,
+            OrderReport::Filled { .. } | OrderReport::PartiallyFilled { .. }
+        ) {
+            self.stats.record(StatsEvent::ExternalFill);
+        }
+        warn!(
+            kind = report.kind(),
+            order_id,
+            side = ?report.side(),
+            "report for an order this engine did not place; ignored"
+        );
+    }
+
+    pub fn pnl(&self) -> &PnlTracker {
+        &self.pnl
+    }
+
+    /// The daily loss limit's count of the day, to save with the engine state.
+    pub fn daily_loss(&self) -> Option<DailyLossState> {
+        self.daily_loss.snapshot()
+    }
+
+    /// Carries on the position and PnL a previous run saved, before the first report.
+    pub fn restore_pnl(&mut self, state: PnlState) {
+        self.pnl = PnlTracker::restore(state);
+    }
+
+    /// Carries on the day's count a previous run saved, before the first evaluation.
+    pub fn restore_daily_loss(&self, state: DailyLossState) {
+        self.daily_loss.restore(state);
+    }
+
+    /// Hands the strategy the reference price, logging when it goes stale and the local
+    /// book prices instead, and when it comes back.
+    fn update_reference_fair(&mut self, now: Instant) {
+        let Some(reference) = &self.reference else {
+            return;
+        };
+        let fair = reference.fair_price(now);
+        match (self.signal_state.reference_fair().is_some(), fair.is_some()) {
+            (true, false) => warn!(
+                instrument = %self.instrument,
+                "reference price stale; pricing from the local book"
+            ),
+            (false, true) => info!(instrument = %self.instrument, "pricing from the reference"),
+            _ => {}
+        }
+        self.signal_state.set_reference_fair(fair);
+    }
+
+    /// Logs the PnL line once `pnl_log_interval` has passed since the last.
+    fn log_pnl(&mut self, now: Instant) {
+        if self
+            .last_pnl_log
+            .is_some_and(|last| now.saturating_duration_since(last) < self.pnl_log_interval)
+        {
+            return;
+        }
+        self.last_pnl_log = Some(now);
+
+        let mid = self.market_state.mid_price();
+        info!(
+            instrument = %self.instrument,
+            position = self.pnl.position(),
+            average_entry = ?self.pnl.average_entry().map(Price::as_f64),
+            realized = self.pnl.realized(),
+            unrealized = ?mid.map(|mid| self.pnl.unrealized(mid)),
+            "pnl"
+        );
+    }
+
+    /// Logs what the order manager believes is working once `orders_log_interval` has
+    /// passed since the last.
+    fn log_orders(&mut self, now: Instant) {
+        if self
+            .last_orders_log
+            .is_some_and(|last| now.saturating_duration_since(last) < self.orders_log_interval)
+        {
+            return;
+        }
+        self.last_orders_log = Some(now);
+
+        info!(
+            instrument = %self.instrument,
+            open_orders = self.order_manager.open_order_count(),
+            levels = %self.order_manager.snapshot(now),
+            "orders"
+        );
+    }
+
+    /// Starts the in-flight timer when an order starts waiting on the venue and clears it
+    /// once nothing is waiting.
+    fn track_in_flight(&mut self) {
+        if !self.order_manager.has_inflight_actions() {
+            self.in_flight_since = None;
+        } else if self.in_flight_since.is_none() {
+            self.in_flight_since = Some(self.clock.now_instant());
+        }
+    }
+
+    /// Writes the fills still waiting on mark-outs, for shutdown.
+    pub fn finish_fill_report(&mut self) {
+        if let Some(annotator) = &mut self.fill_annotator {
+            annotator.finish(self.market_state.mid_price(), self.clock.now_instant());
+        }
+    }
+
+    /// Applies the strategy parameters, risk thresholds and scheduling of a reloaded
+    /// `config`. Signal warm-up, open orders and strategy state carry over.
+    pub fn reload(&mut self, config: &AppConfig) {
+        match config.strategy.for_instrument(&self.instrument) {
+            Ok(strategy) => {
+                self.strategy.update_params(&strategy);
+                self.exits.update_params(&strategy.exit);
+                self.signal_state.set_mid_kind(strategy.mid_kind);
+                for shadow in &mut self.shadows {
+                    shadow.update_params(&strategy);
+                }
+            }
+            Err(error) => warn!("strategy parameters not reloaded: {error:#}"),
+        }
+
+        let limits = config.risk.limits(&self.instrument);
+        self.market_max_age = limits.market_max_age;
+        self.risk_engine.update_limits(&limits);
+
+        self.quote_scheduler.update_config(&config.scheduling);
+        self.order_manager
+            .set_in_flight_timeout(config.scheduling.in_flight_timeout());
+        self.order_manager
+            .set_amend_max_ticks(config.scheduling.amend_max_ticks);
+        self.order_manager
+            .set_reprice_attempts(config.scheduling.reprice_attempts);
+        self.timer_interval = config.scheduling.timer_interval();
+    }
+
+    /// Rebuilds the order state from the venue's open orders, after reports were lost.
+    /// Orders open on the venue that neither side tracks are logged, not cancelled. Until
+    /// a resync succeeds, cycles retry it instead of planning on the stale state.
+    pub async fn resync(&mut self, venue: &DynamicVenue) -> Result<()> {
+        self.resync_pending = true;
+        let open: HashSet<String> = venue
+            .open_orders(&self.instrument)
+            .await?
+            .into_iter()
+            .map(|order| order.order_id)
+            .collect();
+
+        let untracked = self.order_manager.resync(&open, self.clock.now_instant());
+        self.track_in_flight();
+        if !untracked.is_empty() {
+            warn!(instrument = %self.instrument, ?untracked, "untracked orders open on the venue");
+        }
+
+        info!(
+            instrument = %self.instrument,
+            open = open.len(),
+            bid = ?self.order_manager.side(Side::Buy).state(),
+            ask = ?self.order_manager.side(Side::Sell).state(),
+            "resynced orders from the venue"
+        );
+        metrics::open_orders(&self.instrument, self.order_manager.open_order_count());
+        self.resync_pending = false;
+
+        Ok(())
+    }
+
+    /// Retries the resync a lag left failed, ahead of a cycle. Whether the cycle may go
+    /// on: a side still waiting on reports that were lost would otherwise stay in flight
+    /// until it timed out, and a side whose fill was lost would be amended as if resting.
+    async fn retry_resync(&mut self, venue: &DynamicVenue) -> bool {
+        match self.resync(venue).await {
+            Ok(()) => true,
+            Err(error) => {
+                self.scheduler_status.on_skip(ORDERS_UNSYNCED);
+                self.stats.record(StatsEvent::Skipped {
+                    reason: ORDERS_UNSYNCED,
+                });
+                warn!(
+                    reason_code = ORDERS_UNSYNCED,
+                    "order resync failed again; cycle skipped: {error:#}"
+                );
+                false
+            }
+        }
+    }
+
+    /// Brings the order state in line with the venue's open orders, for drift the reports
+    /// did not show: a live order gone from the venue is forgotten, so the next evaluation
+    /// quotes again, and the engine's own orders that neither side tracks are cancelled.
+    /// Its own are those it sent, and those led by its order id prefix or session; orders
+    /// placed by hand or by another deployment are left alone. Skipped while an order
+    /// waits on the venue.
+    pub async fn reconcile(&mut self, venue: &DynamicVenue) -> Result<()> {
+        if self.order_manager.has_inflight_actions() {
+            debug!(instrument = %self.instrument, "orders in flight; reconciliation skipped");
+            return Ok(());
+        }
+
+        let own: Vec<OpenOrder> = venue
+            .open_orders(&self.instrument)
+            .await?
+            .into_iter()
+            .filter(|order| {
+                self.known_orders.contains(&order.order_id)
+                    || self.order_ids.is_own(&order.order_id)
+            })
+            .collect();
+
+        let cancels = self.order_manager.reconcile(&self.instrument, &own);
+        for cancel in &cancels {
+            if let OrderAction::Cancel { order_id, side, .. } = cancel {
+                warn!(
+                    instrument = %self.instrument,
+                    %order_id,
+                    %side,
+                    "cancelling own order open on the venue but not tracked"
+                );
+            }
+        }
+        if !cancels.is_empty() {
+            venue.execute(&cancels).await?;
+        }
+        metrics::open_orders(&self.instrument, self.order_manager.open_order_count());
+
+        Ok(())
+    }
+
+    /// Takes over the engine's own orders left resting on the venue by a previous run,
+    /// instead of cancelling them: the newest on each side becomes that side's live
+    /// order, and any older ones are cancelled. Its own are those in `logged`, the
+    /// intent log's unresolved places, and those led by its configured order id prefix;
+    /// without a prefix a previous run's session is unknown, so only logged orders are.
+    /// Orders placed by hand or by another deployment are logged and left alone.
+    ///
+    /// `logged` is `None` without an intent log. Sequence numbers then restart each run,
+    /// so the newest is the one the venue opened last rather than the highest sequence.
+    pub async fn adopt(
+        &mut self,
+        venue: &DynamicVenue,
+        logged: Option<&HashSet<String>>,
+    ) -> Result<()> {
+        let mut own = Vec::new();
+        for order in venue.open_orders(&self.instrument).await? {
+            let order_id = &order.order_id;
+            if self.known_orders.contains(order_id)
+                || logged.is_some_and(|logged| logged.contains(order_id))
+                || self.order_ids.is_own_from_any_run(order_id)
+            {
+                own.push(order);
+            } else {
+                info!(
+                    instrument = %self.instrument,
+                    order_id = %order.order_id,
+                    "leaving order not placed by the engine"
+                );
+            }
+        }
+        match logged {
+            Some(_) => own.sort_by_key(|order| {
+                Reverse(ClientOrderId::parse(&order.order_id).map_or(0, |id| id.seq))
+            }),
+            None => own.sort_by_key(|order| Reverse(order.opened_at)),
+        }
+
+        let now = self.clock.now_instant();
+        let mut cancels = Vec::new();
+        for order in own {
+            if self
+                .order_manager
+                .side(order.side)
+                .state()
+                .order_id()
+                .is_none()
+            {
+                info!(
+                    instrument = %self.instrument,
+                    order_id = %order.order_id,
+                    side = %order.side,
+                    price = %order.price,
+                    remaining = %order.remaining,
+                    "adopting order left resting by a previous run"
+                );
+                self.known_orders.insert(&order.order_id);
+                self.order_manager.adopt(&order, now);
+            } else {
+                warn!(
+                    instrument = %self.instrument,
+                    order_id = %order.order_id,
+                    side = %order.side,
+                    "cancelling older order left resting by a previous run"
+                );
+                cancels.push(OrderAction::Cancel {
+                    order_id: order.order_id,
+                    instrument: self.instrument.clone(),
+                    side: order.side,
+                });
+            }
+        }
+
+        if !cancels.is_empty() {
+            venue.execute(&cancels).await?;
+        }
+        self.track_in_flight();
+        metrics::open_orders(&self.instrument, self.order_manager.open_order_count());
+
+        Ok(())
+    }
+
+    pub fn start_flatten(&mut self) {
+        self.flattening = true;
+    }
+
+    /// The stats task's summary, with the orders working as of now.
+    pub async fn summary(&self) -> Option<InstrumentSummary> {
+        let mut summary = self.stats.summary().await?;
+        summary.orders = Some(self.order_manager.snapshot(self.clock.now_instant()));
+        Some(summary)
+    }
+
+    pub async fn book(&self) -> Option<TradingBook> {
+        self.stats.book().await
+    }
+
+    /// Up to `limit` of the latest orders and their lifecycles, most recent first.
+    pub fn recent_orders(&self, limit: usize) -> Vec<OrderLifecycle> {
+        self.order_history.recent(limit)
+    }
+
+    /// The latest balances, or the paper ones until a dry-run's first snapshot arrives.
+    fn inventory(&self) -> Inventory {
+        match self.paper_inventory {
+            Some(paper) if !self.inventory_feed.is_ready() => paper,
+            _ => *self.inventory_source.borrow(),
+        }
+    }
+
+    pub fn heartbeat(&self) -> Heartbeat {
+        let now = self.clock.now_instant();
+        let since = |at: Option<Instant>| at.map(|at| now.saturating_duration_since(at));
+
+        Heartbeat {
+            instrument: self.instrument.to_string(),
+            since_market_event: since(self.market_state.last_event_instant()),
+            since_evaluation: since(self.last_evaluation),
+            last_skip: self.scheduler_status.last_skip,
+            in_flight_for: since(self.in_flight_since),
+        }
+    }
+
+    pub fn status(&self) -> InstrumentStatus {
+        let inventory = self.inventory();
+
+        InstrumentStatus {
+            instrument: self.instrument.to_string(),
+            orders: OrdersStatus::capture(&self.order_manager),
+            market: MarketStatus::capture(
+                &self.market_state,
+                self.market_max_age,
+                self.clock.now_instant(),
+            ),
+            inventory,
+            exposure_quote: self
+                .market_state
+                .mid_price()
+                .map(|mid| inventory.exposure_quote(mid)),
+            flattening: self.flattening,
+            scheduler: self.scheduler_status,
+        }
+    }
+
+    /// Runs one market event through scheduling, strategy, risk and execution, at the
+    /// engine clock's current time. Everything logged on the way is inside a `cycle` span
+    /// carrying the instrument, a process-wide `cycle_id` and the `event` kind, and the
+    /// orders placed carry the same id.
+    ///
+    /// Depth updates only update the ladder: the top-of-book events that follow them
+    /// drive the evaluation. A feed disconnect forgets the book and cancels the
+    /// instrument's resting orders, which are not quoted blind.
+    pub async fn on_market_event(
+        &mut self,
+        event: &MarketEvent,
+        venue: &DynamicVenue,
+    ) -> Result<()> {
+        match event {
+            MarketEvent::SourceDisconnected { .. } => return self.on_feed_lost(event, venue).await,
+            MarketEvent::SourceReconnected { .. } => {
+                info!(instrument = %self.instrument, "market feed reconnected");
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        if let MarketEvent::BookUpdate { .. } = event {
+            self.market_state
+                .on_market_event(event, self.clock.now_instant());
+            let (bids, asks) = self.market_state.top_n_levels(DEPTH_LOG_LEVELS);
+            let depth =
+                |levels: &[BookLevel]| levels.iter().map(|level| level.quantity).sum::<f64>();
+            debug!(
+                instrument = %self.instrument,
+                bid_levels = bids.len(),
+                ask_levels = asks.len(),
+                bid_depth = depth(bids),
+                ask_depth = depth(asks),
+                best_bid_depth = bids.first().map(|level| level.quantity),
+                best_ask_depth = asks.first().map(|level| level.quantity),
+                "depth ladder updated"
+            );
+            return Ok(());
+        }
+
+        let cycle_id = self.cycle_ids.next_id();
+        let span = info_span!(
+            "cycle",
+            instrument = %self.instrument,
+            cycle_id,
+            event = event.kind()
+        );
+
+        let result = cycles::scope(
+            cycle_id,
+            self.run_cycle(cycle_id, event, venue).instrument(span),
+        )
+        .await;
+
+        if let Some(decision) = self.decision.take()
+            && let Some(log) = &self.decision_log
+        {
+            log.write(&decision);
+        }
+
+        result
+    }
+
+    /// Runs a cycle on the last book seen, as [`on_market_event`](Self::on_market_event)
+    /// would, when no cycle has run for the configured timer interval. Lets time-driven
+    /// policies and risk checks act in a quiet market: quotes are pulled when trading
+    /// hours end or the book goes stale. Nothing runs before the first book or after a
+    /// feed loss, which has already cancelled the quotes.
+    pub async fn on_timer(&mut self, venue: &DynamicVenue) -> Result<()> {
+        let now = self.clock.now_instant();
+        if self.market_state.mid_price().is_none()
+            || self
+                .last_cycle
+                .is_some_and(|last| now.saturating_duration_since(last) < self.timer_interval)
+        {
+            return Ok(());
+        }
+
+        self.evaluate_on_last_book(Trigger::Timer, TIMER_EVENT, venue)
+            .await
+    }
+
+    /// Runs a cycle on the last book seen as soon as the venue has refused a post-only
+    /// place for crossing, so the side re-plans a tick behind the touch instead of
+    /// waiting for the next market event. Does nothing otherwise.
+    pub async fn on_reprice(&mut self, venue: &DynamicVenue) -> Result<()> {
+        if !self.order_manager.take_reprice() || self.market_state.mid_price().is_none() {
+            return Ok(());
+        }
+
+        self.evaluate_on_last_book(Trigger::Reprice, REPRICE_EVENT, venue)
+            .await
+    }
+
+    async fn evaluate_on_last_book(
+        &mut self,
+        trigger: Trigger,
+        event: &'static str,
+        venue: &DynamicVenue,
+    ) -> Result<()> {
+        let cycle_id = self.cycle_ids.next_id();
+        let span = info_span!(
+            "cycle",
+            instrument = %self.instrument,
+            cycle_id,
+            event
+        );
+
+        let trace = self.latency.begin();
+        let result = cycles::scope(
+            cycle_id,
+            self.evaluate(cycle_id, trigger, event, trace, venue)
+                .instrument(span),
+        )
+        .await;
+
+        if let Some(decision) = self.decision.take()
+            && let Some(log) = &self.decision_log
+        {
+            log.write(&decision);
+        }
+
+        result
+    }
+
+    async fn on_feed_lost(&mut self, event: &MarketEvent, venue: &DynamicVenue) -> Result<()> {
+        self.market_state
+            .on_market_event(event, self.clock.now_instant());
+
+        let cancels = self.order_manager.cancel_actions(&self.instrument);
+        warn!(
+            instrument = %self.instrument,
+            cancels = cancels.len(),
+            "market feed lost; cancelling resting orders until it is back"
+        );
+        if !cancels.is_empty() {
+            venue.execute(&cancels).await?;
+        }
+        Ok(())
+    }
+
+    /// Runs each shadow strategy on the inputs the live one just decided on, and notes
+    /// how its decision compares with `live`.
+    fn evaluate_shadows(
+        &mut self,
+        live: &Result<QuoteTarget, NoQuoteReason>,
+        inventory: Inventory,
+    ) {
+        let price_tick = self.instrument.trading_rules().price_tick;
+        for shadow in &mut self.shadows {
+            let decision = shadow.evaluate(&self.market_state, &self.signal_state, inventory);
+            if let Some(record) = &mut self.decision {
+                record.shadows.push(ShadowDecision::compare(
+                    shadow.kind(),
+                    &decision,
+                    live,
+                    price_tick,
+                ));
+            }
+        }
+    }
+
+    /// Adds to this cycle's decision record, when the decision log is on.
+    fn note_decision(&mut self, note: impl FnOnce(&mut DecisionRecord)) {
+        if let Some(decision) = &mut self.decision {
+            note(decision);
+        }
+    }
+
+    async fn run_cycle(
+        &mut self,
+        cycle_id: u64,
+        event: &MarketEvent,
+        venue: &DynamicVenue,
+    ) -> Result<()> {
+        let trace = self.latency.begin();
+        debug!(trace_id = trace.map(|trace| trace.id), ?event);
+
+        let now = self.clock.now_instant();
+
+        self.stats.record(StatsEvent::MarketEvent);
+
+        if let Some(annotator) = &mut self.fill_annotator {
+            annotator.sample(self.market_state.mid_price(), now);
+        }
+        self.market_state.on_market_event(event, now);
+        self.signal_state.update(&self.market_state, now);
+        self.update_reference_fair(now);
+        self.log_pnl(now);
+        self.log_orders(now);
+        metrics::market(&self.instrument, self.market_state.mid_price());
+
+        for shadow in &mut self.shadows {
+            shadow.on_market_event(event);
+        }
+
+        self.evaluate(cycle_id, Trigger::MarketEvent, event.kind(), trace, venue)
+            .await
+    }
+
+    /// Schedules, then computes, risk-checks and executes the target on the current
+    /// market state.
+    async fn evaluate(
+        &mut self,
+        cycle_id: u64,
+        trigger: Trigger,
+        event: &'static str,
+        mut trace: Option<EventTrace>,
+        venue: &DynamicVenue,
+    ) -> Result<()> {
+        let now = self.clock.now_instant();
+        self.last_cycle = Some(now);
+
+        if self.resync_pending && !self.retry_resync(venue).await {
+            return Ok(());
+        }
+
+        let scheduler_context = ScheduleContext {
+            now,
+            trigger,
+            now_utc: self.clock.now_utc(),
+            instrument: &self.instrument,
+            market_state: &self.market_state,
+            signal_state: &self.signal_state,
+            order_manager: &self.order_manager,
+        };
+
+        let schedule_decision = self.quote_scheduler.decide(&scheduler_context);
+        self.latency.stage(&mut trace, Stage::Schedule);
+
+        if self.decision_log.is_some() {
+            self.decision = Some(
+                DecisionRecord::new(
+                    self.clock.now_utc(),
+                    &self.instrument,
+                    cycle_id,
+                    event,
+                    &self.market_state,
+                    &self.signal_state,
+                    schedule_decision,
+                )
+                .with_orders(self.order_manager.snapshot(now)),
+            );
+        }
+
+        let cancels_only = match schedule_decision {
+            ScheduleDecision::Evaluate => {
+                self.scheduler_status.on_evaluate();
+                self.last_evaluation = Some(now);
+                false
+            }
+            // Counted as a skip, of the new orders; the orders the target no longer wants
+            // are still cancelled.
+            ScheduleDecision::CancelsOnly(reason) => {
+                self.scheduler_status.on_skip(reason.code());
+                metrics::schedule_skip(&reason);
+                self.stats.record(StatsEvent::Skipped {
+                    reason: reason.code(),
+                });
+                debug!(reason_code = reason.code(), "evaluating for cancels only");
+                true
+            }
+            ScheduleDecision::Skip(reason) => {
+                self.scheduler_status.on_skip(reason.code());
+                if reason.pulls_quotes() {
+                    self.pull_quotes(now, venue).await?;
+                }
+                // The timer asks again every interval while the book is quiet, so its
+                // skips are neither counted nor warned of.
+                if trigger == Trigger::Timer {
+                    debug!(reason_code = reason.code(), ?reason, "scheduling skipped");
+                    return Ok(());
+                }
+                metrics::schedule_skip(&reason);
+                self.stats.record(StatsEvent::Skipped {
+                    reason: reason.code(),
+                });
+                warn!(reason_code = reason.code(), ?reason, "scheduling skipped");
+
+                return Ok(());
+            }
+        };
+
+        let inventory = self.inventory();
+        metrics::inventory(&self.instrument, inventory, self.market_state.mid_price());
+        let mid = self.market_state.mid_price();
+        self.stats.record(StatsEvent::Snapshot {
+            inventory,
+            mid,
+            realized_pnl: self.pnl.realized(),
+            unrealized_pnl: mid.map(|mid| self.pnl.unrealized(mid)),
+        });
+
+        let target_result = if self.flattening {
+            flatten_target(&self.instrument, &self.market_state, inventory)
+        } else {
+            let target =
+                self.strategy
+                    .compute_target(&self.market_state, &self.signal_state, inventory);
+            self.exits
+                .apply(&self.instrument, &self.market_state, &self.pnl, target)
+        };
+
+        self.latency.stage(&mut trace, Stage::Strategy);
+        self.note_decision(|decision| match &target_result {
+            Ok(target) => decision.target = Some(target.clone()),
+            Err(reason) => decision.no_quote = Some(reason.clone()),
+        });
+        self.evaluate_shadows(&target_result, inventory);
+
+        let target = match target_result {
+            Err(NoQuoteReason::AlreadyFlat) if self.flattening => {
+                self.flattening = false;
+                info!("flatten complete; resuming strategy");
+                return Ok(());
+            }
+            Err(reason) => {
+                self.stats.record(StatsEvent::NoQuote {
+                    reason: reason.code(),
+                });
+                warn!(reason_code = reason.code(), ?reason, "no quote");
+                return Ok(());
+            }
+            Ok(target) => target,
+        };
+
+        self.stats.record(StatsEvent::TargetComputed {
+            bid: target.bid.map(|quote| quote.price),
+            ask: target.ask.map(|quote| quote.price),
+        });
+
+        let context = RiskContext {
+            instrument: &self.instrument,
+            market_state: &self.market_state,
+            target: &target,
+            inventory,
+            orders: &self.order_manager.snapshot(now),
+            pnl: &self.pnl,
+            now,
+        };
+
+        let decision = self.risk_engine.evaluate(&context, target.clone());
+        metrics::risk_decision(&decision);
+        self.latency.stage(&mut trace, Stage::Risk);
+        self.note_decision(|record| record.risk = Some(decision.clone()));
+
+        match decision {
+            RiskDecision::Approved(RiskApproval {
+                target: approved_target,
+                stripped,
+            }) => {
+                if !stripped.is_empty() {
+                    for reason in &stripped {
+                        self.stats.record(StatsEvent::RiskHold {
+                            reason: reason.code(),
+                        });
+                    }
+                    info!(
+                        reason_code = %logging::reason_codes(&stripped),
+                        bid_price = approved_target.bid.map(|quote| quote.price.as_f64()),
+                        ask_price = approved_target.ask.map(|quote| quote.price.as_f64()),
+                        reasons = ?stripped,
+                        "risk held one side"
+                    );
+                }
+
+                self.order_manager
+                    .set_touch(self.market_state.best_bid(), self.market_state.best_ask());
+                let mut actions = if cancels_only {
+                    self.order_manager
+                        .cancels_for_target(&self.instrument, &approved_target, now)
+                        .await?
+                } else {
+                    self.order_manager
+                        .actions_for_target(&self.instrument, &approved_target, now)
+                        .await?
+                };
+                for action in &mut actions {
+                    if let OrderAction::Place(order) = action {
+                        order.cycle_id = Some(cycle_id);
+                    }
+                }
+                self.note_decision(|decision| decision.actions = actions.clone());
+
+                if !actions.is_empty() {
+                    self.known_orders.register(&actions);
+                    venue.execute(&actions).await?;
+                    self.latency.executed(&mut trace, &actions);
+                    self.record_places(&actions);
+                    self.risk_engine
+                        .commit(&placed_target(&approved_target, &actions), now);
+
+                    if let Some(annotator) = &mut self.fill_annotator {
+                        let signals =
+                            DecisionSignals::capture(&self.market_state, &self.signal_state);
+                        annotator.on_actions(&actions, signals, now);
+                    }
+                }
+
+                metrics::open_orders(&self.instrument, self.order_manager.open_order_count());
+            }
+            RiskDecision::Hold(hold) => {
+                for reason in &hold.reasons {
+                    self.stats.record(StatsEvent::RiskHold {
+                        reason: reason.code(),
+                    });
+                }
+                info!(
+                    reason_code = %logging::reason_codes(&hold.reasons),
+                    bid_price = target.bid.map(|quote| quote.price.as_f64()),
+                    ask_price = target.ask.map(|quote| quote.price.as_f64()),
+                    reasons = ?hold.reasons,
+                    "risk hold"
+                );
+            }
+            RiskDecision::Rejected(rejection) => {
+                for reason in &rejection.reasons {
+                    self.stats.record(StatsEvent::RiskRejected {
+                        reason: reason.code(),
+                    });
+                }
+                warn!(
+                    reason_code = %logging::reason_codes(&rejection.reasons),
+                    bid_price = target.bid.map(|quote| quote.price.as_f64()),
+                    ask_price = target.ask.map(|quote| quote.price.as_f64()),
+                    reasons = ?rejection.reasons,
+                    required_actions = rejection.required_actions.len(),
+                    "risk rejection"
+                );
+
+                let actions = self.scope_actions(rejection.required_actions);
+                self.note_decision(|decision| decision.actions = actions.clone());
+                if !actions.is_empty() {
+                    self.known_orders.register(&actions);
+                    venue.execute(&actions).await?;
+                    self.latency.executed(&mut trace, &actions);
+                    self.record_places(&actions);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cancels every order this instrument has resting or placing, e.g. once trading
+    /// hours end.
+    async fn pull_quotes(&mut self, now: Instant, venue: &DynamicVenue) -> Result<()> {
+        let cancels = self
+            .order_manager
+            .cancels_for_target(&self.instrument, &QuoteTarget::default(), now)
+            .await?;
+        self.note_decision(|decision| decision.actions = cancels.clone());
+        if cancels.is_empty() {
+            return Ok(());
+        }
+
+        info!(cancels = cancels.len(), "pulling quotes");
+        self.known_orders.register(&cancels);
+        venue.execute(&cancels).await
+    }
+
+    fn record_places(&mut self, actions: &[OrderAction]) {
+        let (now, at) = (self.clock.now_instant(), self.clock.now_utc());
+        for action in actions {
+            if let OrderAction::Place(order) = action {
+                self.order_history.on_place(order, now, at);
+            }
+        }
+    }
+
+    /// Narrows venue-wide cancels to this instrument's own orders when sharing the venue.
+    fn scope_actions(&self, actions: Vec<OrderAction>) -> Vec<OrderAction> {
+        if !self.scoped_cancels {
+            return actions;
+        }
+
+        actions
+            .into_iter()
+            .flat_map(|action| match action {
+                OrderAction::CancelAll => self.order_manager.cancel_actions(&self.instrument),
+                action => vec![action],
+            })
+            .collect()
+    }
+}
+
+/// The sides of `approved` that `actions` placed or amended.
+fn placed_target(approved: &QuoteTarget, actions: &[OrderAction]) -> QuoteTarget {
+    let placed = |side: Side| {
+        actions.iter().any(|action| match action {
+            OrderAction::Place(order) => order.side == side,
+            OrderAction::Amend { side: amended, .. } => *amended == side,
+            _ => false,
+        })
+    };
+
+    QuoteTarget::new(
+        approved.bid.filter(|_| placed(Side::Buy)),
+        approved.ask.filter(|_| placed(Side::Sell)),
+    )
+}
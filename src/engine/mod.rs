@@ -0,0 +1,7 @@
+pub mod channels;
+#[allow(clippy::module_inception)]
+pub mod engine;
+pub mod heartbeat;
+pub mod instrument_engine;
+pub mod supervisor;
+pub mod watchdog;
@@ -0,0 +1,220 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinError;
+use tokio::time::Instant;
+use tracing::{Instrument as _, error, info, warn};
+
+use crate::telemetry::metrics;
+
+/// What the supervisor does when a task stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Restart after a panic, waiting `initial` and doubling up to `max` while it keeps
+    /// failing. A task that returns has nothing left to do and is not restarted.
+    Restart { initial: Duration, max: Duration },
+    /// The engine cannot run without the task: any exit shuts it down.
+    Escalate,
+}
+
+impl RestartPolicy {
+    /// Restarts after 1s, backing off to a minute.
+    pub const fn restart() -> Self {
+        Self::Restart {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+        }
+    }
+}
+
+/// A critical task stopped; the engine shuts down on receiving one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Escalation {
+    pub task: String,
+    pub failure: String,
+}
+
+/// One supervised task as reported on `/status`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskHealth {
+    pub task: String,
+    pub critical: bool,
+    pub running: bool,
+    pub restarts: u64,
+    pub last_restart: Option<DateTime<Utc>>,
+    /// How the task last stopped, e.g. `panicked: ...`.
+    pub last_failure: Option<String>,
+}
+
+/// Owns the engine's long-running background tasks. Each runs under a monitor that waits
+/// on its join handle, so a panic or an unexpected exit is noticed, counted and handled
+/// by the task's [`RestartPolicy`] instead of going unseen.
+#[derive(Debug, Clone)]
+pub struct Supervisor {
+    health: Arc<Mutex<BTreeMap<String, TaskHealth>>>,
+    escalations: mpsc::UnboundedSender<Escalation>,
+    /// Set once at shutdown; every task is then aborted and none restarts or escalates.
+    stop: Arc<watch::Sender<bool>>,
+}
+
+impl Supervisor {
+    /// A supervisor and the channel its escalations arrive on.
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<Escalation>) {
+        let (escalations, receiver) = mpsc::unbounded_channel();
+
+        (
+            Self {
+                health: Arc::default(),
+                escalations,
+                stop: Arc::new(watch::Sender::new(false)),
+            },
+            receiver,
+        )
+    }
+
+    /// Runs `task()` as `name` under `policy`; restarts call `task` again for a fresh
+    /// future. The task inherits the current span.
+    pub fn spawn<F, Fut>(&self, name: impl Into<String>, policy: RestartPolicy, task: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        self.health.lock().unwrap().insert(
+            name.clone(),
+            TaskHealth {
+                task: name.clone(),
+                critical: policy == RestartPolicy::Escalate,
+                running: true,
+                restarts: 0,
+                last_restart: None,
+                last_failure: None,
+            },
+        );
+        metrics::task_running(&name, true);
+
+        let supervisor = self.clone();
+        let mut stop = self.stop.subscribe();
+        tokio::spawn(
+            async move {
+                let mut backoff = None;
+
+                loop {
+                    let started = Instant::now();
+                    let mut handle = tokio::spawn(task().in_current_span());
+                    let failure = tokio::select! {
+                        result = &mut handle => result.err().map(describe),
+                        _ = stop.wait_for(|stop| *stop) => {
+                            handle.abort();
+                            supervisor.stopped(&name, None);
+                            break;
+                        }
+                    };
+
+                    let (initial, max, failure) = match (policy, failure) {
+                        (RestartPolicy::Escalate, failure) => {
+                            supervisor.escalate(&name, failure.unwrap_or("returned".into()));
+                            break;
+                        }
+                        (RestartPolicy::Restart { .. }, None) => {
+                            supervisor.stopped(&name, None);
+                            info!(task = %name, "task finished");
+                            break;
+                        }
+                        (RestartPolicy::Restart { initial, max }, Some(failure)) => {
+                            (initial, max, failure)
+                        }
+                    };
+
+                    // A task that ran past the longest backoff starts over from the shortest.
+                    let delay = match backoff {
+                        Some(previous) if started.elapsed() < max => max.min(previous * 2),
+                        _ => initial,
+                    };
+                    backoff = Some(delay);
+
+                    supervisor.stopped(&name, Some(failure.clone()));
+                    warn!(task = %name, %failure, delay_ms = delay.as_millis() as u64, "task failed; restarting");
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => supervisor.restarted(&name),
+                        _ = stop.wait_for(|stop| *stop) => break,
+                    }
+                }
+            }
+            .in_current_span(),
+        );
+    }
+
+    /// Reports `task` as failed to the engine, which shuts down. For failures the engine
+    /// notices itself, like a closed channel, as well as for critical tasks.
+    pub fn escalate(&self, task: &str, failure: impl Into<String>) {
+        let failure = failure.into();
+        self.stopped(task, Some(failure.clone()));
+        error!(task, %failure, "critical task failed");
+
+        let _ = self.escalations.send(Escalation {
+            task: task.to_string(),
+            failure,
+        });
+    }
+
+    /// Aborts every task, for a clean exit once the engine has stopped using them. Tasks
+    /// spawned afterwards are aborted straight away.
+    pub fn shutdown(&self) {
+        self.stop.send_replace(true);
+    }
+
+    /// Every task spawned so far, by name.
+    pub fn health(&self) -> Vec<TaskHealth> {
+        self.health.lock().unwrap().values().cloned().collect()
+    }
+
+    fn stopped(&self, task: &str, failure: Option<String>) {
+        let mut health = self.health.lock().unwrap();
+        let entry = health
+            .entry(task.to_string())
+            .or_insert_with(|| TaskHealth {
+                task: task.to_string(),
+                critical: true,
+                running: false,
+                restarts: 0,
+                last_restart: None,
+                last_failure: None,
+            });
+        entry.running = false;
+        if failure.is_some() {
+            entry.last_failure = failure;
+        }
+        metrics::task_running(task, false);
+    }
+
+    fn restarted(&self, task: &str) {
+        if let Some(entry) = self.health.lock().unwrap().get_mut(task) {
+            entry.running = true;
+            entry.restarts += 1;
+            entry.last_restart = Some(Utc::now());
+        }
+        metrics::task_restart(task);
+    }
+}
+
+fn describe(error: JoinError) -> String {
+    if !error.is_panic() {
+        return "cancelled".to_string();
+    }
+
+    let payload: Box<dyn Any + Send> = error.into_panic();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string payload".to_string());
+
+    format!("panicked: {message}")
+}
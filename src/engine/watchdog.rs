@@ -0,0 +1,204 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::{Instrument as _, error, info, warn};
+
+use crate::config::app_config::ensure;
+use crate::scenario::venues::VenueKind;
+use crate::telemetry::liveness;
+use crate::telemetry::metrics::{self, Feed};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatchdogConfig {
+    /// Seconds without market data before the market feed is considered dead; unset
+    /// disables the check.
+    pub market_dead_secs: Option<u64>,
+    /// Seconds without an executions message before that feed is considered dead. Only
+    /// checked on venues that stream executions.
+    pub executions_dead_secs: Option<u64>,
+    /// Shut down and exit when a feed dies; when false the watchdog only logs.
+    pub exit: bool,
+    /// Seconds between heartbeat lines. The engine loop also wakes this often when idle,
+    /// so an idle loop still completes iterations.
+    pub heartbeat_secs: u64,
+    /// Seconds the engine loop may go without completing an iteration before it is
+    /// considered stalled, e.g. by a blocking call; unset disables the check. Must be
+    /// longer than `heartbeat_secs`.
+    pub loop_stall_secs: Option<u64>,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            market_dead_secs: Some(120),
+            executions_dead_secs: Some(120),
+            exit: true,
+            heartbeat_secs: 5,
+            loop_stall_secs: Some(15),
+        }
+    }
+}
+
+impl WatchdogConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.market_dead_secs != Some(0),
+            format!("{path}.market_dead_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.executions_dead_secs != Some(0),
+            format!("{path}.executions_dead_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.heartbeat_secs > 0,
+            format!("{path}.heartbeat_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.loop_stall_secs
+                .is_none_or(|secs| secs > self.heartbeat_secs),
+            format!("{path}.loop_stall_secs"),
+            "must be > heartbeat_secs",
+        )
+    }
+
+    pub fn heartbeat_interval(&self) -> Duration {
+        Duration::from_secs(self.heartbeat_secs)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadFeed {
+    pub feed: Feed,
+    pub silent_for: Duration,
+}
+
+/// Watches the critical feeds and reports the first one that stays silent past its
+/// threshold. A feed that has never delivered a message counts as silent since start.
+///
+/// Also watches the engine loop itself, which cannot notice that it is stuck: a loop that
+/// goes past its threshold without completing an iteration is logged and counted.
+#[derive(Debug)]
+pub struct Watchdog {
+    thresholds: Vec<(Feed, Duration)>,
+    loop_stall: Option<Duration>,
+    started: Instant,
+}
+
+impl Watchdog {
+    pub fn new(config: &WatchdogConfig, venue: VenueKind, started: Instant) -> Self {
+        let mut thresholds = Vec::new();
+        if let Some(secs) = config.market_dead_secs {
+            thresholds.push((Feed::Market, Duration::from_secs(secs)));
+        }
+        if let Some(secs) = config.executions_dead_secs
+            && venue == VenueKind::Kraken
+        {
+            thresholds.push((Feed::Executions, Duration::from_secs(secs)));
+        }
+
+        Self {
+            thresholds,
+            loop_stall: config.loop_stall_secs.map(Duration::from_secs),
+            started,
+        }
+    }
+
+    /// `silent_for` reports how long a feed has been quiet, `None` if it never spoke.
+    pub fn check(
+        &self,
+        now: Instant,
+        silent_for: impl Fn(Feed) -> Option<Duration>,
+    ) -> Option<DeadFeed> {
+        self.thresholds.iter().find_map(|&(feed, threshold)| {
+            let silent_for = silent_for(feed).unwrap_or_else(|| now - self.started);
+            (silent_for >= threshold).then_some(DeadFeed { feed, silent_for })
+        })
+    }
+
+    /// How long the engine loop has been stalled, given the time since it last completed
+    /// an iteration, if that is past the threshold. A loop that has not started yet is
+    /// not stalled.
+    pub fn check_loop(&self, idle_for: Option<Duration>) -> Option<Duration> {
+        let threshold = self.loop_stall?;
+        idle_for.filter(|idle_for| *idle_for >= threshold)
+    }
+
+    /// Checks the process-wide feed and loop liveness every second. With `exit` set, the
+    /// first dead feed is sent to the engine, which shuts down; otherwise it is logged
+    /// once per outage. A stalled loop is logged once per stall, and never shuts down.
+    pub fn spawn(self, exit: bool) -> mpsc::Receiver<DeadFeed> {
+        let (sender, receiver) = mpsc::channel(1);
+
+        if self.thresholds.is_empty() && self.loop_stall.is_none() {
+            info!("watchdog disabled");
+            return receiver;
+        }
+
+        tokio::spawn(
+            async move {
+                let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+                let mut reported = None;
+                let mut loop_stalled = false;
+
+                loop {
+                    ticker.tick().await;
+
+                    let idle_for = liveness::loop_idle_for();
+                    if let Some(idle_for) = idle_for {
+                        metrics::loop_idle(idle_for);
+                    }
+                    match (self.check_loop(idle_for), loop_stalled) {
+                        (Some(stalled_for), false) => {
+                            loop_stalled = true;
+                            metrics::loop_stalled();
+                            error!(
+                                stalled_secs = stalled_for.as_secs(),
+                                "engine loop stalled; no iteration completed"
+                            );
+                        }
+                        (None, true) => {
+                            loop_stalled = false;
+                            warn!("engine loop recovered");
+                        }
+                        _ => {}
+                    }
+
+                    let dead = self.check(Instant::now(), liveness::silent_for);
+                    let Some(dead) = dead else {
+                        reported = None;
+                        continue;
+                    };
+
+                    if reported == Some(dead.feed) {
+                        continue;
+                    }
+                    reported = Some(dead.feed);
+
+                    error!(
+                        feed = dead.feed.label(),
+                        silent_secs = dead.silent_for.as_secs(),
+                        reconnects = liveness::reconnects(dead.feed),
+                        exit,
+                        "critical feed dead"
+                    );
+
+                    if exit {
+                        let _ = sender.send(dead).await;
+                        break;
+                    }
+                }
+            }
+            .in_current_span(),
+        );
+
+        receiver
+    }
+}
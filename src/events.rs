@@ -1,5 +1,13 @@
+use crate::execution::order_action::Side;
 use crate::types::{instrument::Instrument, price::Price};
 
+/// A price level of an order book ladder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookLevel {
+    pub price: Price,
+    pub quantity: f64,
+}
+
 #[derive(Debug, Clone)]
 pub enum MarketEvent {
     Trade {
@@ -12,6 +20,84 @@ pub enum MarketEvent {
         instrument: Instrument,
         best_bid: Price,
         best_ask: Price,
+        /// Quantity resting at the best bid and ask, when the feed sends them.
+        bid_size: Option<f64>,
+        ask_size: Option<f64>,
+        timestamp_ms: u64,
+    },
+    /// Levels of the order book below the touch, from a depth subscription.
+    BookUpdate {
+        instrument: Instrument,
+        /// Levels to set. A zero quantity removes the level.
+        bids: Vec<BookLevel>,
+        asks: Vec<BookLevel>,
+        /// Whether the levels replace the whole ladder rather than update it.
+        snapshot: bool,
+        timestamp_ms: u64,
+    },
+    /// The feed stopped: the socket dropped or went quiet. Prices held until it is back
+    /// are not to be quoted on.
+    SourceDisconnected {
+        instrument: Instrument,
+        /// Local wall clock; the venue sent nothing.
         timestamp_ms: u64,
     },
+    /// The feed connected, or connected again, and fresh prices follow.
+    SourceReconnected {
+        instrument: Instrument,
+        timestamp_ms: u64,
+    },
+}
+
+impl MarketEvent {
+    pub fn instrument(&self) -> &Instrument {
+        match self {
+            MarketEvent::Trade { instrument, .. }
+            | MarketEvent::TopOfBook { instrument, .. }
+            | MarketEvent::BookUpdate { instrument, .. }
+            | MarketEvent::SourceDisconnected { instrument, .. }
+            | MarketEvent::SourceReconnected { instrument, .. } => instrument,
+        }
+    }
+
+    /// Short name for logs: `book` or `trade`, as in the backtest journal, `depth`, or
+    /// `disconnected` and `reconnected` for the feed itself.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MarketEvent::Trade { .. } => "trade",
+            MarketEvent::TopOfBook { .. } => "book",
+            MarketEvent::BookUpdate { .. } => "depth",
+            MarketEvent::SourceDisconnected { .. } => "disconnected",
+            MarketEvent::SourceReconnected { .. } => "reconnected",
+        }
+    }
+
+    /// Exchange timestamp of the event.
+    pub fn timestamp_ms(&self) -> u64 {
+        match self {
+            MarketEvent::Trade { timestamp_ms, .. }
+            | MarketEvent::TopOfBook { timestamp_ms, .. }
+            | MarketEvent::BookUpdate { timestamp_ms, .. }
+            | MarketEvent::SourceDisconnected { timestamp_ms, .. }
+            | MarketEvent::SourceReconnected { timestamp_ms, .. } => *timestamp_ms,
+        }
+    }
+
+    /// Whether a maker order resting at `price` on `side` would be filled by this event:
+    /// the opposite touch reaches it, or a trade prints through it. Depth updates and
+    /// the feed's own events never do; the touch comes from the top-of-book events.
+    pub fn crosses(&self, side: Side, price: Price) -> bool {
+        match (self, side) {
+            (MarketEvent::TopOfBook { best_ask, .. }, Side::Buy) => *best_ask <= price,
+            (MarketEvent::TopOfBook { best_bid, .. }, Side::Sell) => *best_bid >= price,
+            (MarketEvent::Trade { price: traded, .. }, Side::Buy) => *traded < price,
+            (MarketEvent::Trade { price: traded, .. }, Side::Sell) => *traded > price,
+            (
+                MarketEvent::BookUpdate { .. }
+                | MarketEvent::SourceDisconnected { .. }
+                | MarketEvent::SourceReconnected { .. },
+                _,
+            ) => false,
+        }
+    }
 }
@@ -1,40 +1,277 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
-use rand::Rng;
+use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
 use anyhow::Result;
-use tracing::info;
+use tracing::{debug, info};
 
 use crate::{
+    config::app_config::ensure,
+    engine::supervisor::Supervisor,
+    events::MarketEvent,
     execution::{
-        DynamicInventorySource, ExecutionVenue, ReportSender, order_action::OrderAction,
-        order_report::OrderReport, types::OpenOrder,
+        DynamicInventorySource, ExecutionVenue, ReportSender,
+        order_action::{Order, OrderAction, OrderType, Side},
+        order_report::{OrderReport, RejectKind},
+        types::OpenOrder,
     },
-    kraken::kraken_inventory::KrakenInventory,
-    types::instrument::Instrument,
+    inventory::simulated::SimulatedInventory,
+    random::SeededRng,
+    types::{instrument::Instrument, inventory::Inventory, price::Price, quantity::Quantity},
 };
 
-#[derive(Debug)]
-pub struct DryRunExecutionVenue {
-    on_report: Option<broadcast::Sender<OrderReport>>,
+/// What happens to dry-run orders besides the market trading through them. Everything is
+/// off by default, leaving fills to the market alone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DryRunConfig {
+    /// Chance that an order fills once it has rested `fill_delay_ms`, decided once per
+    /// order on the first market event after the delay.
+    pub fill_probability: f64,
+    pub fill_delay_ms: u64,
+    /// Chance that such a fill takes half the order, rounded down to the quantity step.
+    /// The rest is decided again after another delay.
+    pub partial_fill_probability: f64,
+    /// Resting orders are cancelled by the venue after this long; never when unset.
+    pub expiry_ms: Option<u64>,
 }
 
-impl Default for DryRunExecutionVenue {
+impl Default for DryRunConfig {
     fn default() -> Self {
-        Self { on_report: None }
+        Self {
+            fill_probability: 0.0,
+            fill_delay_ms: 5_000,
+            partial_fill_probability: 0.0,
+            expiry_ms: None,
+        }
+    }
+}
+
+impl DryRunConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            (0.0..=1.0).contains(&self.fill_probability),
+            format!("{path}.fill_probability"),
+            "must be between 0 and 1",
+        )?;
+        ensure(
+            (0.0..=1.0).contains(&self.partial_fill_probability),
+            format!("{path}.partial_fill_probability"),
+            "must be between 0 and 1",
+        )?;
+        ensure(
+            self.expiry_ms != Some(0),
+            format!("{path}.expiry_ms"),
+            "must be > 0",
+        )
+    }
+
+    fn fill_delay(&self) -> Duration {
+        Duration::from_millis(self.fill_delay_ms)
+    }
+}
+
+/// An order resting on the paper venue.
+#[derive(Debug, Clone)]
+struct Resting {
+    order: Order,
+    placed_at: Instant,
+    /// When a simulated fill is next decided; `None` once it was decided against.
+    decide_at: Option<Instant>,
+    filled: Quantity,
+}
+
+impl Resting {
+    fn remaining(&self) -> Quantity {
+        self.order.quantity.saturating_sub(self.filled)
+    }
+
+    /// Whether `filled` is all of the order, to the instrument's quantity step.
+    fn complete_at(&self, filled: Quantity) -> bool {
+        filled >= self.order.quantity
+            || self
+                .order
+                .instrument
+                .trading_rules()
+                .quantity_eq(self.order.quantity, filled)
+    }
+
+    fn fill(&self, quantity: Quantity) -> OrderReport {
+        let order = &self.order;
+        let cum_quantity = self.filled + quantity;
+        if !self.complete_at(cum_quantity) {
+            OrderReport::PartiallyFilled {
+                order_id: order.order_id.clone(),
+                instrument: order.instrument.clone(),
+                side: order.side,
+                price: order.price,
+                quantity,
+                cum_quantity,
+            }
+        } else {
+            OrderReport::Filled {
+                order_id: order.order_id.clone(),
+                instrument: order.instrument.clone(),
+                side: order.side,
+                price: order.price,
+                quantity,
+                cum_quantity: order.quantity,
+            }
+        }
     }
 }
 
+/// Paper venue: accepts most placements and rests them until the market trades through
+/// them, then reports a hypothetical fill at the order's own price: of as much as the trade
+/// printed, or of the rest of the order when the touch moves through it. An order that
+/// would take liquidity at the last touch seen is refused when post-only, and otherwise
+/// fills whole at that touch; an immediate-or-cancel order that would not is cancelled.
+/// [`DryRunConfig`] adds fills and expiries the market did not cause. Balances are a
+/// [`SimulatedInventory`] per instrument, so no Kraken credentials are needed.
+///
+/// Reports are sent with the book locked, so a cancel racing a fill sees either the
+/// order still resting or a `CancelFailed` after the fill, never both.
+#[derive(Debug)]
+pub struct DryRunExecutionVenue {
+    on_report: Option<broadcast::Sender<OrderReport>>,
+    /// Starting balances of every instrument's paper inventory.
+    paper: Inventory,
+    lifecycle: DryRunConfig,
+    /// Decides which placements are rejected and which orders fill.
+    rng: SeededRng,
+    resting: Mutex<Vec<Resting>>,
+    /// Last best bid and ask of each instrument, for orders that would take liquidity.
+    touch: Mutex<HashMap<Instrument, (Price, Price)>>,
+}
+
 impl DryRunExecutionVenue {
-    pub fn new(on_report: broadcast::Sender<OrderReport>) -> Self {
+    pub fn new(on_report: broadcast::Sender<OrderReport>, rng: SeededRng) -> Self {
         Self {
             on_report: Some(on_report),
+            paper: Inventory::default(),
+            lifecycle: DryRunConfig::default(),
+            rng,
+            resting: Mutex::new(Vec::new()),
+            touch: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_lifecycle(mut self, lifecycle: DryRunConfig) -> Self {
+        self.lifecycle = lifecycle;
+        self
+    }
+
+    pub fn with_paper_inventory(mut self, paper: Inventory) -> Self {
+        self.paper = paper;
+        self
+    }
+
+    /// Rolls for a simulated fill of `resting` if one is due. Returns whether the order
+    /// is still resting.
+    fn decide_fill(
+        &self,
+        resting: &mut Resting,
+        now: Instant,
+        reports: &mut Vec<OrderReport>,
+    ) -> bool {
+        if resting.decide_at.is_none_or(|at| now < at) {
+            return true;
+        }
+
+        if !self.rng.random_bool(self.lifecycle.fill_probability) {
+            resting.decide_at = None;
+            return true;
+        }
+
+        let rules = resting.order.instrument.trading_rules();
+        let half = Quantity::new(resting.remaining().as_f64() / 2.0)
+            .map_or(Quantity::ZERO, |half| rules.round_quantity_to_step(half));
+        if !half.is_zero()
+            && self
+                .rng
+                .random_bool(self.lifecycle.partial_fill_probability)
+        {
+            reports.push(resting.fill(half));
+            resting.filled += half;
+            resting.decide_at = Some(now + self.lifecycle.fill_delay());
+            return true;
+        }
+
+        reports.push(resting.fill(resting.remaining()));
+        false
+    }
+
+    /// The opposite touch `order` would trade against on arrival, if it reaches it.
+    fn takes_at(&self, order: &Order) -> Option<Price> {
+        let (best_bid, best_ask) = *self.touch.lock().unwrap().get(&order.instrument)?;
+        match order.side {
+            Side::Buy => (best_ask <= order.price).then_some(best_ask),
+            Side::Sell => (best_bid >= order.price).then_some(best_bid),
+        }
+    }
+
+    /// Reports for a placement the venue took: it rests, fills at once, or is cancelled.
+    fn arrive(&self, place: &Order) -> Vec<OrderReport> {
+        let accepted = OrderReport::Accepted {
+            order_id: place.order_id.clone(),
+            instrument: place.instrument.clone(),
+            side: place.side,
+            price: place.price,
+            quantity: place.quantity,
+        };
+
+        match (place.order_type, self.takes_at(place)) {
+            (OrderType::Limit { post_only: true }, Some(_)) => vec![OrderReport::Rejected {
+                order_id: place.order_id.clone(),
+                instrument: place.instrument.clone(),
+                side: place.side,
+                kind: RejectKind::PostOnlyWouldCross,
+                reason: "post-only order would take liquidity".to_string(),
+            }],
+            (_, Some(price)) => vec![
+                accepted,
+                OrderReport::Filled {
+                    order_id: place.order_id.clone(),
+                    instrument: place.instrument.clone(),
+                    side: place.side,
+                    price,
+                    quantity: place.quantity,
+                    cum_quantity: place.quantity,
+                },
+            ],
+            (OrderType::ImmediateOrCancel, None) => vec![
+                accepted,
+                OrderReport::Cancelled {
+                    order_id: place.order_id.clone(),
+                    instrument: place.instrument.clone(),
+                    side: place.side,
+                },
+            ],
+            (OrderType::Limit { .. }, None) => {
+                let now = Instant::now();
+                let decides = self.lifecycle.fill_probability > 0.0;
+                self.resting.lock().unwrap().push(Resting {
+                    order: place.clone(),
+                    placed_at: now,
+                    decide_at: decides.then(|| now + self.lifecycle.fill_delay()),
+                    filled: Quantity::ZERO,
+                });
+                vec![accepted]
+            }
         }
     }
 
-    async fn emit(&self, report: OrderReport) {
+    fn emit(&self, report: OrderReport) {
         if let Some(sender) = &self.on_report {
-            info!(?report);
+            debug!(
+                kind = report.kind(),
+                order_id = report.order_id(),
+                "dry-run report"
+            );
             let _ = sender.send(report);
         };
     }
@@ -42,16 +279,34 @@ impl DryRunExecutionVenue {
 
 #[async_trait]
 impl ExecutionVenue for DryRunExecutionVenue {
-    async fn open_orders(&self, _instrument: &Instrument) -> Result<Vec<OpenOrder>> {
-        Ok(Vec::new())
+    async fn open_orders(&self, instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        Ok(self
+            .resting
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|resting| resting.order.instrument == *instrument)
+            .map(|resting| OpenOrder::resting(&resting.order, resting.filled))
+            .collect())
     }
 
-    async fn spawn_reports(&self, on_report: ReportSender) -> Result<()> {
+    async fn spawn_reports(
+        &self,
+        _on_report: ReportSender,
+        _supervisor: &Supervisor,
+    ) -> Result<()> {
         Ok(())
     }
 
-    async fn spawn_inventory(&self, instrument: &Instrument) -> Result<DynamicInventorySource> {
-        let inventory = KrakenInventory::spawn(instrument).await?;
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        let inventory = match &self.on_report {
+            Some(reports) => SimulatedInventory::spawn(instrument, self.paper, reports, supervisor),
+            None => SimulatedInventory::new(instrument, self.paper),
+        };
 
         Ok(Box::new(inventory))
     }
@@ -62,34 +317,53 @@ impl ExecutionVenue for DryRunExecutionVenue {
                 OrderAction::CancelAll => {
                     info!("cancelling all orders");
 
-                    /* NOTE: nothing to be done right now, all orders fill immediately in a dry run */
+                    let mut resting = self.resting.lock().unwrap();
+                    for resting in resting.iter() {
+                        self.emit(OrderReport::Cancelled {
+                            order_id: resting.order.order_id.clone(),
+                            instrument: resting.order.instrument.clone(),
+                            side: resting.order.side,
+                        });
+                    }
+
+                    let count = resting.len() as i64;
+                    resting.clear();
+                    self.emit(OrderReport::CancelledAll { count });
                 }
                 OrderAction::Cancel {
                     order_id,
                     instrument,
                     side,
                 } => {
-                    let cancel = OrderReport::Cancel {
-                        order_id: order_id.clone(),
-                        instrument: instrument.clone(),
-                        side: *side,
-                    };
-
-                    self.emit(cancel).await;
+                    let mut resting = self.resting.lock().unwrap();
 
-                    let cancelled = OrderReport::Cancelled {
+                    self.emit(OrderReport::Cancel {
                         order_id: order_id.clone(),
                         instrument: instrument.clone(),
                         side: *side,
-                    };
+                    });
+
+                    let before = resting.len();
+                    resting.retain(|resting| resting.order.order_id != *order_id);
 
-                    self.emit(cancelled).await;
+                    // Gone already: filled, expired or never accepted.
+                    self.emit(if resting.len() < before {
+                        OrderReport::Cancelled {
+                            order_id: order_id.clone(),
+                            instrument: instrument.clone(),
+                            side: *side,
+                        }
+                    } else {
+                        OrderReport::CancelFailed {
+                            order_id: order_id.clone(),
+                            instrument: instrument.clone(),
+                            side: *side,
+                            reason: "unknown order".to_string(),
+                        }
+                    });
                 }
                 OrderAction::Place(place) => {
-                    let will_reject = {
-                        let mut rng = rand::rng();
-                        rng.random_range(0..10)
-                    };
+                    let will_reject = self.rng.random_range(0..10);
 
                     let placed = OrderReport::Placed {
                         order_id: place.order_id.clone(),
@@ -99,28 +373,140 @@ impl ExecutionVenue for DryRunExecutionVenue {
                         quantity: place.quantity,
                     };
 
-                    self.emit(placed).await;
+                    self.emit(placed);
 
                     let outcome = match will_reject {
-                        0 => OrderReport::Rejected {
+                        0 => vec![OrderReport::Rejected {
                             order_id: place.order_id.clone(),
                             instrument: place.instrument.clone(),
                             side: place.side,
+                            kind: RejectKind::Other,
                             reason: "rejected".to_string(),
-                        },
-                        _ => OrderReport::Accepted {
-                            order_id: place.order_id.clone(),
-                            instrument: place.instrument.clone(),
-                            side: place.side,
-                            price: place.price,
-                            quantity: place.quantity,
-                        },
+                        }],
+                        _ => self.arrive(place),
+                    };
+
+                    for report in outcome {
+                        self.emit(report);
+                    }
+                }
+                // A price change loses the order's place in the queue, as on the venue.
+                OrderAction::Amend {
+                    order_id,
+                    instrument,
+                    side,
+                    new_price,
+                    new_quantity,
+                } => {
+                    let mut resting = self.resting.lock().unwrap();
+
+                    let position = resting
+                        .iter()
+                        .position(|resting| resting.order.order_id == *order_id);
+                    let refusal = match position {
+                        None => Some("unknown order"),
+                        Some(index) if *new_quantity <= resting[index].filled => {
+                            Some("quantity not above what already filled")
+                        }
+                        Some(index) => {
+                            let mut amended = resting.remove(index);
+                            amended.order.price = *new_price;
+                            amended.order.quantity = *new_quantity;
+                            resting.push(amended);
+                            None
+                        }
                     };
 
-                    self.emit(outcome).await;
+                    self.emit(match refusal {
+                        None => OrderReport::Amended {
+                            order_id: order_id.clone(),
+                            instrument: instrument.clone(),
+                            side: *side,
+                            price: *new_price,
+                            quantity: *new_quantity,
+                        },
+                        Some(reason) => OrderReport::AmendRejected {
+                            order_id: order_id.clone(),
+                            instrument: instrument.clone(),
+                            side: *side,
+                            reason: reason.to_string(),
+                        },
+                    });
                 }
             };
         }
         Ok(())
     }
+
+    fn on_market_event(&self, event: &MarketEvent) {
+        // Fills are decided on the touch and trades, not once per depth update too, nor
+        // on the feed's own events.
+        if let MarketEvent::BookUpdate { .. }
+        | MarketEvent::SourceDisconnected { .. }
+        | MarketEvent::SourceReconnected { .. } = event
+        {
+            return;
+        }
+
+        let instrument = event.instrument();
+        if let MarketEvent::TopOfBook {
+            best_bid, best_ask, ..
+        } = event
+        {
+            self.touch
+                .lock()
+                .unwrap()
+                .insert(instrument.clone(), (*best_bid, *best_ask));
+        }
+        let now = Instant::now();
+        let mut resting = self.resting.lock().unwrap();
+        let mut reports = Vec::new();
+        // A trade fills no more than it printed, shared in placement order.
+        let mut traded = match event {
+            // A trade size that is no number fills nothing.
+            MarketEvent::Trade { quantity, .. } => {
+                Some(Quantity::new(*quantity).unwrap_or_default())
+            }
+            _ => None,
+        };
+
+        resting.retain_mut(|resting| {
+            if resting.order.instrument != *instrument {
+                return true;
+            }
+
+            if event.crosses(resting.order.side, resting.order.price) {
+                let quantity = match &mut traded {
+                    Some(available) => {
+                        let quantity = resting.remaining().min(*available);
+                        *available = available.saturating_sub(quantity);
+                        quantity
+                    }
+                    None => resting.remaining(),
+                };
+                if !quantity.is_zero() {
+                    reports.push(resting.fill(quantity));
+                    resting.filled += quantity;
+                }
+                return !resting.complete_at(resting.filled);
+            }
+
+            if let Some(expiry_ms) = self.lifecycle.expiry_ms
+                && now.duration_since(resting.placed_at) >= Duration::from_millis(expiry_ms)
+            {
+                reports.push(OrderReport::Cancelled {
+                    order_id: resting.order.order_id.clone(),
+                    instrument: resting.order.instrument.clone(),
+                    side: resting.order.side,
+                });
+                return false;
+            }
+
+            self.decide_fill(resting, now, &mut reports)
+        });
+
+        for report in reports {
+            self.emit(report);
+        }
+    }
 }
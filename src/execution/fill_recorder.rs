@@ -0,0 +1,197 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{Instrument as _, error, warn};
+
+use crate::config::app_config::ensure;
+use crate::execution::order_action::Side;
+use crate::execution::order_report::OrderReport;
+use crate::telemetry::metrics;
+
+/// Where fills are appended as they happen, one JSON object a line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FillLogConfig {
+    pub enabled: bool,
+    /// Base name of the daily files: `fills.jsonl` writes `fills-2024-01-03.jsonl`.
+    pub path: PathBuf,
+    /// Fills waiting for the disk. When it is full, further fills are dropped with a
+    /// warning rather than hold up the engine.
+    pub queue: usize,
+}
+
+impl Default for FillLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: PathBuf::from("fills.jsonl"),
+            queue: 1_024,
+        }
+    }
+}
+
+impl FillLogConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.path.file_stem().is_some(),
+            format!("{path}.path"),
+            "must name a file",
+        )?;
+        ensure(self.queue > 0, format!("{path}.queue"), "must be > 0")
+    }
+}
+
+/// One fill, as written to the log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FillRecord {
+    /// When the fill report arrived, by the wall clock.
+    pub at: DateTime<Utc>,
+    pub instrument: String,
+    pub side: Side,
+    pub price: f64,
+    pub quantity: f64,
+    pub cum_quantity: f64,
+    pub order_id: String,
+    /// Whether the order has filled completely.
+    pub complete: bool,
+}
+
+impl FillRecord {
+    /// The fill in `report`, if it is one, stamped `at`.
+    pub fn from_report(report: &OrderReport, at: DateTime<Utc>) -> Option<Self> {
+        let (order_id, instrument, side, price, quantity, cum_quantity, complete) = match report {
+            OrderReport::PartiallyFilled {
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+                cum_quantity,
+            } => (
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+                cum_quantity,
+                false,
+            ),
+            OrderReport::Filled {
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+                cum_quantity,
+            } => (
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+                cum_quantity,
+                true,
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            at,
+            instrument: instrument.to_string(),
+            side: *side,
+            price: price.as_f64(),
+            quantity: quantity.as_f64(),
+            cum_quantity: cum_quantity.as_f64(),
+            order_id: order_id.clone(),
+            complete,
+        })
+    }
+}
+
+/// Appends every fill on the order report channel to a daily file, e.g.
+/// `fills-2024-01-03.jsonl`, so fills outlive the process.
+pub struct FillRecorder;
+
+impl FillRecorder {
+    /// Starts recording. Reports are read on a task, and written from a thread of its
+    /// own through a queue of `config.queue` fills, so a slow disk never holds up the
+    /// report channel or the engine.
+    pub fn spawn(config: &FillLogConfig, mut reports: broadcast::Receiver<OrderReport>) {
+        let (sender, mut fills) = mpsc::channel::<FillRecord>(config.queue);
+        let path = config.path.clone();
+
+        std::thread::spawn(move || {
+            let mut file = None;
+            while let Some(fill) = fills.blocking_recv() {
+                if let Err(error) = write(&path, &mut file, &fill) {
+                    error!(path = %path.display(), "failed to write fill: {error:#}");
+                }
+            }
+        });
+
+        tokio::spawn(
+            async move {
+                loop {
+                    let report = match reports.recv().await {
+                        Ok(report) => report,
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            metrics::reports_lagged("fill_recorder", n);
+                            warn!(lagged = n, "fill recorder lagged; fills may be missing");
+                            continue;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    };
+
+                    if let Some(fill) = FillRecord::from_report(&report, Utc::now())
+                        && let Err(error) = sender.try_send(fill)
+                    {
+                        metrics::fills_dropped();
+                        warn!(
+                            order_id = %error.into_inner().order_id,
+                            "fill log queue full; fill not written"
+                        );
+                    }
+                }
+            }
+            .in_current_span(),
+        );
+    }
+
+    /// The file fills on `date` go to: `path` with the date after its stem.
+    pub fn dated_path(path: &Path, date: NaiveDate) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match path.extension() {
+            Some(extension) => format!("{stem}-{date}.{}", extension.to_string_lossy()),
+            None => format!("{stem}-{date}"),
+        };
+        path.with_file_name(name)
+    }
+}
+
+/// Appends `fill` to the file for its date, moving `file` on to a new one at midnight.
+fn write(path: &Path, file: &mut Option<(NaiveDate, File)>, fill: &FillRecord) -> Result<()> {
+    let date = fill.at.date_naive();
+    if file.as_ref().is_none_or(|(open, _)| *open != date) {
+        let dated = FillRecorder::dated_path(path, date);
+        if let Some(dir) = dated.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create fill log dir {}", dir.display()))?;
+        }
+        let opened = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&dated)
+            .with_context(|| format!("failed to open fill log {}", dated.display()))?;
+        *file = Some((date, opened));
+    }
+
+    let (_, file) = file.as_mut().expect("opened above");
+    writeln!(file, "{}", serde_json::to_string(fill)?)?;
+    file.flush()?;
+    Ok(())
+}
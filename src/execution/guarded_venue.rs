@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::config::app_config::ensure;
+use crate::engine::supervisor::Supervisor;
+use crate::events::MarketEvent;
+use crate::execution::{
+    DynamicInventorySource, ExecutionVenue, ReportSender,
+    order_action::{Order, OrderAction},
+    order_report::{OrderReport, RejectKind},
+    types::OpenOrder,
+};
+use crate::market::market_state::MarketState;
+use crate::types::instrument::Instrument;
+
+/// Bounds on the places a live venue is sent, whatever the strategy asked for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrderGuardConfig {
+    /// Largest notional placed, as a multiple of the instrument's `max_order_notional`.
+    pub notional_factor: f64,
+    /// Furthest a place's price may be from the mid, in percent of it.
+    pub max_mid_deviation_pct: f64,
+}
+
+impl Default for OrderGuardConfig {
+    fn default() -> Self {
+        Self {
+            notional_factor: 2.0,
+            max_mid_deviation_pct: 10.0,
+        }
+    }
+}
+
+impl OrderGuardConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.notional_factor >= 1.0,
+            format!("{path}.notional_factor"),
+            "must be >= 1",
+        )?;
+        ensure(
+            self.max_mid_deviation_pct > 0.0,
+            format!("{path}.max_mid_deviation_pct"),
+            "must be > 0",
+        )
+    }
+}
+
+/// Venue wrapper that drops any place no sane strategy would send: one with a quantity
+/// that is not a positive number, a notional beyond the instrument's
+/// `max_order_notional` times the guard's factor, or a price too far from the mid, or
+/// with no mid to compare it with. Dropped places are reported rejected; everything
+/// else goes through.
+pub struct GuardedVenue<V> {
+    inner: V,
+    config: OrderGuardConfig,
+    reports: ReportSender,
+    /// Each instrument's book as the venue last saw it, for its mid.
+    markets: Mutex<HashMap<Instrument, MarketState>>,
+}
+
+impl<V: ExecutionVenue> GuardedVenue<V> {
+    pub fn new(inner: V, config: OrderGuardConfig, reports: ReportSender) -> Self {
+        Self {
+            inner,
+            config,
+            reports,
+            markets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Why `order` must not reach the venue, if it must not.
+    fn refusal(&self, order: &Order) -> Option<String> {
+        let price = order.price.as_f64();
+        if order.quantity.is_zero() {
+            return Some(format!(
+                "quantity {} is not a positive number",
+                order.quantity
+            ));
+        }
+        if !price.is_finite() || price <= 0.0 {
+            return Some(format!("price {price} is not a positive number"));
+        }
+
+        let max_notional =
+            order.instrument.trading_rules().max_order_notional * self.config.notional_factor;
+        let notional = price * order.quantity.as_f64();
+        if notional > max_notional {
+            return Some(format!(
+                "notional {notional:.2} exceeds {max_notional:.2} ({}x max_order_notional)",
+                self.config.notional_factor
+            ));
+        }
+
+        let mid = self
+            .markets
+            .lock()
+            .unwrap()
+            .get(&order.instrument)
+            .and_then(MarketState::mid_price);
+        let Some(mid) = mid else {
+            return Some("no mid price to check the price against".to_string());
+        };
+        let deviation_pct = (price - mid.as_f64()).abs() / mid.as_f64() * 100.0;
+        if deviation_pct > self.config.max_mid_deviation_pct {
+            return Some(format!(
+                "price {price} is {deviation_pct:.1}% from the mid {mid}, beyond {}%",
+                self.config.max_mid_deviation_pct
+            ));
+        }
+
+        None
+    }
+
+    fn reject(&self, order: &Order, refusal: &str) {
+        let reason = format!("fat-finger guard: {refusal}");
+        error!(
+            order_id = %order.order_id,
+            instrument = %order.instrument,
+            side = %order.side,
+            price = %order.price,
+            quantity = %order.quantity,
+            "{reason}"
+        );
+        let _ = self.reports.send(OrderReport::Rejected {
+            order_id: order.order_id.clone(),
+            instrument: order.instrument.clone(),
+            side: order.side,
+            kind: RejectKind::Other,
+            reason,
+        });
+    }
+}
+
+#[async_trait]
+impl<V: ExecutionVenue + Send + Sync> ExecutionVenue for GuardedVenue<V> {
+    async fn execute(&self, actions: &[OrderAction]) -> Result<()> {
+        let allowed: Vec<OrderAction> = actions
+            .iter()
+            .filter(|action| match action {
+                OrderAction::Place(order) => match self.refusal(order) {
+                    Some(refusal) => {
+                        self.reject(order, &refusal);
+                        false
+                    }
+                    None => true,
+                },
+                _ => true,
+            })
+            .cloned()
+            .collect();
+
+        if allowed.is_empty() {
+            return Ok(());
+        }
+        self.inner.execute(&allowed).await
+    }
+
+    async fn open_orders(&self, instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        self.inner.open_orders(instrument).await
+    }
+
+    fn check_instrument(&self, instrument: &Instrument) -> Result<()> {
+        self.inner.check_instrument(instrument)
+    }
+
+    async fn spawn_reports(&self, on_report: ReportSender, supervisor: &Supervisor) -> Result<()> {
+        self.inner.spawn_reports(on_report, supervisor).await
+    }
+
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        self.inner.spawn_inventory(instrument, supervisor).await
+    }
+
+    fn on_market_event(&self, event: &MarketEvent) {
+        self.markets
+            .lock()
+            .unwrap()
+            .entry(event.instrument().clone())
+            .or_default()
+            .on_market_event(event, Instant::now());
+        self.inner.on_market_event(event);
+    }
+}
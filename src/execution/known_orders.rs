@@ -0,0 +1,44 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::execution::order_action::OrderAction;
+
+/// Ids remembered; far more than can be working at once, so only long-finished orders
+/// are forgotten.
+const CAPACITY: usize = 10_000;
+
+/// Client order ids this engine has sent to the venue, so reports for orders placed
+/// elsewhere on the account, by hand or by another bot, can be told apart.
+#[derive(Debug, Default)]
+pub struct KnownOrders {
+    ids: HashSet<String>,
+    /// Insertion order, for forgetting the oldest.
+    order: VecDeque<String>,
+}
+
+impl KnownOrders {
+    /// Remembers every order `actions` places. Called before the actions are sent, so
+    /// no report can arrive for an order not yet known.
+    pub fn register(&mut self, actions: &[OrderAction]) {
+        for action in actions {
+            if let OrderAction::Place(order) = action {
+                self.insert(&order.order_id);
+            }
+        }
+    }
+
+    /// Remembers `order_id`, for an order placed before this process started.
+    pub fn insert(&mut self, order_id: &str) {
+        if self.ids.insert(order_id.to_string()) {
+            self.order.push_back(order_id.to_string());
+            if self.order.len() > CAPACITY
+                && let Some(oldest) = self.order.pop_front()
+            {
+                self.ids.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn contains(&self, order_id: &str) -> bool {
+        self.ids.contains(order_id)
+    }
+}
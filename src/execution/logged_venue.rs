@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+use tracing::{Instrument as _, error, info, warn};
+
+use crate::engine::supervisor::Supervisor;
+use crate::events::MarketEvent;
+use crate::execution::{
+    DynamicInventorySource, ExecutionVenue, ReportSender,
+    order_action::OrderAction,
+    order_ids::{ClientOrderId, OrderIds},
+    order_report::OrderReport,
+    trade_checkpoint::TradeCheckpoint,
+    types::OpenOrder,
+};
+use crate::scenario::scenario::DynamicVenue;
+use crate::state::intent_log::IntentLog;
+use crate::telemetry::metrics;
+use crate::types::instrument::Instrument;
+
+/// Venue wrapper that writes every place and cancel to the [`IntentLog`] before passing
+/// it on. If the log cannot be written the actions are not sent.
+pub struct IntentLoggedVenue {
+    inner: DynamicVenue,
+    log: Arc<Mutex<IntentLog>>,
+}
+
+impl IntentLoggedVenue {
+    pub fn new(inner: DynamicVenue, log: IntentLog) -> Self {
+        Self {
+            inner,
+            log: Arc::new(Mutex::new(log)),
+        }
+    }
+
+    /// Resolves intents as their orders reach a terminal state.
+    pub fn spawn_resolver(&self, mut reports: broadcast::Receiver<OrderReport>) {
+        let log = Arc::clone(&self.log);
+
+        tokio::spawn(
+            async move {
+                loop {
+                    let report = match reports.recv().await {
+                        Ok(report) => report,
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            metrics::reports_lagged("intent_log", n);
+                            warn!(
+                                lagged = n,
+                                "intent log lagged; unresolved orders are rechecked at next start"
+                            );
+                            continue;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    };
+
+                    let result = match &report {
+                        OrderReport::Rejected { order_id, .. }
+                        | OrderReport::Cancelled { order_id, .. }
+                        | OrderReport::Filled { order_id, .. } => {
+                            log.lock().unwrap().resolve(order_id)
+                        }
+                        OrderReport::CancelledAll { .. } => log.lock().unwrap().resolve_all(),
+                        _ => Ok(()),
+                    };
+
+                    if let Err(error) = result {
+                        error!("failed to resolve order intent: {error:#}");
+                    }
+                }
+            }
+            .in_current_span(),
+        );
+    }
+
+    /// Checks the intents a previous run left unresolved against the venue's open orders.
+    /// With `cancel_resting`, orders still resting are cancelled, since the engine starts
+    /// without any; the rest are resolved. Open orders led by the deployment's prefix in
+    /// `order_ids` are its own even when the log lost track of them, and are cancelled
+    /// too; any other order was placed by hand, by another deployment or by another
+    /// client and is left alone. Without it, resting orders stay open and unresolved for
+    /// the engine to adopt. Call after [`spawn_resolver`](Self::spawn_resolver) so the
+    /// cancels resolve too.
+    pub async fn recover(
+        &self,
+        instruments: &[Instrument],
+        order_ids: &OrderIds,
+        cancel_resting: bool,
+    ) -> Result<()> {
+        let unresolved = self.log.lock().unwrap().unresolved();
+
+        for instrument in instruments {
+            let symbol = instrument.to_string();
+            let intents: Vec<_> = unresolved
+                .iter()
+                .filter(|intent| intent.instrument == symbol)
+                .collect();
+
+            let open: HashSet<String> = match self.inner.open_orders(instrument).await {
+                Ok(orders) => orders.into_iter().map(|order| order.order_id).collect(),
+                Err(error) => {
+                    warn!(%instrument, "cannot check unresolved order intents: {error:#}");
+                    continue;
+                }
+            };
+
+            let mut cancels = Vec::new();
+            for intent in &intents {
+                if open.contains(&intent.order_id) {
+                    if !cancel_resting {
+                        continue;
+                    }
+
+                    warn!(
+                        %instrument,
+                        order_id = %intent.order_id,
+                        side = %intent.side,
+                        "cancelling order left resting by a previous run"
+                    );
+                    cancels.push(OrderAction::Cancel {
+                        order_id: intent.order_id.clone(),
+                        instrument: instrument.clone(),
+                        side: intent.side,
+                    });
+                } else {
+                    info!(
+                        %instrument,
+                        order_id = %intent.order_id,
+                        "order intent no longer open; resolved"
+                    );
+                    self.log.lock().unwrap().resolve(&intent.order_id)?;
+                }
+            }
+
+            for order_id in open.iter().filter(|_| cancel_resting) {
+                if intents.iter().any(|intent| &intent.order_id == order_id) {
+                    continue;
+                }
+
+                match ClientOrderId::parse(order_id)
+                    .filter(|_| order_ids.is_own_from_any_run(order_id))
+                {
+                    Some(id) => {
+                        warn!(
+                            %instrument,
+                            %order_id,
+                            side = %id.side,
+                            "cancelling unlogged order left resting by a previous run"
+                        );
+                        cancels.push(OrderAction::Cancel {
+                            order_id: order_id.clone(),
+                            instrument: instrument.clone(),
+                            side: id.side,
+                        });
+                    }
+                    None => info!(%instrument, %order_id, "leaving order not placed by the engine"),
+                }
+            }
+
+            if !cancels.is_empty() {
+                self.execute(&cancels).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExecutionVenue for IntentLoggedVenue {
+    async fn execute(&self, actions: &[OrderAction]) -> Result<()> {
+        self.log.lock().unwrap().record_actions(actions)?;
+        self.inner.execute(actions).await
+    }
+
+    async fn open_orders(&self, instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        self.inner.open_orders(instrument).await
+    }
+
+    async fn spawn_reports(&self, on_report: ReportSender, supervisor: &Supervisor) -> Result<()> {
+        self.inner.spawn_reports(on_report, supervisor).await
+    }
+
+    fn trade_checkpoint(&self) -> Option<TradeCheckpoint> {
+        self.inner.trade_checkpoint()
+    }
+
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        self.inner.spawn_inventory(instrument, supervisor).await
+    }
+
+    fn on_market_event(&self, event: &MarketEvent) {
+        self.inner.on_market_event(event);
+    }
+}
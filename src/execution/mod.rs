@@ -1,16 +1,30 @@
 pub mod dry_run;
+pub mod fill_recorder;
+pub mod guarded_venue;
+pub mod known_orders;
+pub mod logged_venue;
 pub mod order_action;
+pub mod order_history;
+pub mod order_ids;
 pub mod order_manager;
 pub mod order_report;
 pub mod order_side_manager;
+pub mod rate_limited_venue;
+pub mod report_sequencer;
+pub mod report_wait;
+pub mod reprice_on_reject;
+pub mod trade_checkpoint;
 pub mod types;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use tokio::sync::broadcast;
 
+use crate::engine::supervisor::Supervisor;
+use crate::events::MarketEvent;
 use crate::execution::order_action::OrderAction;
 use crate::execution::order_report::OrderReport;
+use crate::execution::trade_checkpoint::TradeCheckpoint;
 use crate::execution::types::OpenOrder;
 use crate::inventory::InventorySource;
 use crate::types::instrument::Instrument;
@@ -23,6 +37,26 @@ pub type DynamicInventorySource = Box<dyn InventorySource + Send + Sync>;
 pub trait ExecutionVenue {
     async fn execute(&self, actions: &[OrderAction]) -> Result<()>;
     async fn open_orders(&self, instrument: &Instrument) -> Result<Vec<OpenOrder>>;
-    async fn spawn_reports(&self, on_report: ReportSender) -> Result<()>;
-    async fn spawn_inventory(&self, instrument: &Instrument) -> Result<DynamicInventorySource>;
+    /// Fails when the venue cannot name `instrument`. Checked at startup, before any
+    /// order is looked up or placed.
+    fn check_instrument(&self, _instrument: &Instrument) -> Result<()> {
+        Ok(())
+    }
+    /// Starts streaming order reports to `on_report`, with any background tasks under
+    /// `supervisor`.
+    async fn spawn_reports(&self, on_report: ReportSender, supervisor: &Supervisor) -> Result<()>;
+    /// The last trade ids its report stream passed on, for venues whose trades carry one.
+    /// Restored before [`spawn_reports`](Self::spawn_reports) and saved with the state.
+    fn trade_checkpoint(&self) -> Option<TradeCheckpoint> {
+        None
+    }
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource>;
+
+    /// Sees every market event before the engine does, so simulated venues can fill
+    /// resting orders. Live venues ignore it.
+    fn on_market_event(&self, _event: &MarketEvent) {}
 }
@@ -1,15 +1,49 @@
 use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 
-use crate::types::{instrument::Instrument, price::Price};
+use crate::types::{instrument::Instrument, price::Price, quantity::Quantity};
 use std::{fmt, str::FromStr};
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
 pub enum Side {
     #[default]
     Buy,
     Sell,
 }
 
+impl Side {
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+
+    /// Direction of the base asset flow: +1 for a buy, -1 for a sell.
+    pub fn sign(self) -> f64 {
+        match self {
+            Side::Buy => 1.0,
+            Side::Sell => -1.0,
+        }
+    }
+
+    /// Signed change in base position for a fill of `quantity` on this side.
+    pub fn signed(self, quantity: f64) -> f64 {
+        self.sign() * quantity
+    }
+
+    /// Whether trading on this side moves `inventory_base` towards flat.
+    /// A flat position has nothing to reduce, so neither side counts.
+    pub fn is_reducing_for(self, inventory_base: f64) -> bool {
+        match self {
+            Side::Buy => inventory_base < 0.0,
+            Side::Sell => inventory_base > 0.0,
+        }
+    }
+}
+
 impl fmt::Display for Side {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -31,22 +65,40 @@ impl FromStr for Side {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum OrderType {
-    PostOnlyLimit,
+    /// Rests at its price until filled or cancelled. A post-only order that would take
+    /// liquidity is refused by the venue instead.
+    Limit { post_only: bool },
+    /// Takes what it can at its price or better, crossing the spread, and cancels the
+    /// rest rather than resting it.
+    ImmediateOrCancel,
 }
 
-#[derive(Debug, Clone)]
+impl OrderType {
+    /// What quotes are placed as: resting, and never taking liquidity.
+    pub const POST_ONLY: Self = Self::Limit { post_only: true };
+
+    pub fn is_post_only(self) -> bool {
+        self == Self::POST_ONLY
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Order {
     pub order_id: String,
     pub instrument: Instrument,
     pub side: Side,
     pub price: Price,
-    pub quantity: f64,
+    pub quantity: Quantity,
     pub order_type: OrderType,
+    /// Engine cycle that decided the order; `None` for orders placed by hand.
+    pub cycle_id: Option<u64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
 pub enum OrderAction {
     CancelAll,
     Cancel {
@@ -55,4 +107,13 @@ pub enum OrderAction {
         side: Side,
     },
     Place(Order),
+    /// Moves a resting order to `new_price` in place, keeping its id. `new_quantity` is
+    /// the whole order's, what already filled included.
+    Amend {
+        order_id: String,
+        instrument: Instrument,
+        side: Side,
+        new_price: Price,
+        new_quantity: Quantity,
+    },
 }
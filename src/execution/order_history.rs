@@ -0,0 +1,364 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config::app_config::ensure;
+use crate::execution::order_action::{Order, Side};
+use crate::execution::order_report::OrderReport;
+use crate::types::instrument::Instrument;
+use crate::types::price::Price;
+use crate::types::quantity::Quantity;
+
+/// Reports kept per order; a long run of partial fills keeps the first and latest ones.
+const MAX_EVENTS_PER_ORDER: usize = 32;
+
+/// How many order lifecycles each instrument keeps in memory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrderHistoryConfig {
+    /// Finished orders kept; the oldest is dropped when another finishes.
+    pub capacity: usize,
+    /// Orders kept in total. Unfinished orders are only dropped past this, oldest first.
+    pub max_entries: usize,
+}
+
+impl Default for OrderHistoryConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 200,
+            max_entries: 1_000,
+        }
+    }
+}
+
+impl OrderHistoryConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(self.capacity > 0, format!("{path}.capacity"), "must be > 0")?;
+        ensure(
+            self.max_entries >= self.capacity,
+            format!("{path}.max_entries"),
+            "must be >= capacity",
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleState {
+    /// Sent to the venue, not yet acknowledged.
+    Placing,
+    Open,
+    PartiallyFilled,
+    Cancelling,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+impl LifecycleState {
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            LifecycleState::Filled | LifecycleState::Cancelled | LifecycleState::Rejected
+        )
+    }
+}
+
+/// One report received for an order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LifecycleEvent {
+    /// The report kind, e.g. `accepted`, or `place` for the engine's own action.
+    pub kind: &'static str,
+    pub at: DateTime<Utc>,
+    /// Milliseconds since the order was first seen.
+    pub elapsed_ms: f64,
+    pub price: Option<Price>,
+    pub quantity: Option<Quantity>,
+    pub reason: Option<String>,
+}
+
+/// Everything seen of one order, from the place action to its final report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OrderLifecycle {
+    pub order_id: String,
+    pub instrument: String,
+    pub side: Option<Side>,
+    /// Price and quantity the order was placed with; `None` for orders first seen in a
+    /// report, e.g. ones placed before a restart.
+    pub price: Option<Price>,
+    pub quantity: Option<Quantity>,
+    pub cycle_id: Option<u64>,
+    pub state: LifecycleState,
+    pub filled_quantity: Quantity,
+    pub first_seen: DateTime<Utc>,
+    /// Milliseconds from first seen to the venue's acknowledgement.
+    pub ack_ms: Option<f64>,
+    /// Milliseconds from first seen to the final report.
+    pub resolved_ms: Option<f64>,
+    pub events: Vec<LifecycleEvent>,
+    /// Reports left out of `events` to bound its size.
+    pub events_dropped: usize,
+    #[serde(skip)]
+    seen_at: Instant,
+}
+
+impl OrderLifecycle {
+    fn new(order_id: &str, instrument: &Instrument, now: Instant, at: DateTime<Utc>) -> Self {
+        Self {
+            order_id: order_id.to_string(),
+            instrument: instrument.to_string(),
+            side: None,
+            price: None,
+            quantity: None,
+            cycle_id: None,
+            state: LifecycleState::Placing,
+            filled_quantity: Quantity::ZERO,
+            first_seen: at,
+            ack_ms: None,
+            resolved_ms: None,
+            events: Vec::new(),
+            events_dropped: 0,
+            seen_at: now,
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: &'static str,
+        report: Option<&OrderReport>,
+        now: Instant,
+        at: DateTime<Utc>,
+    ) {
+        if self.events.len() >= MAX_EVENTS_PER_ORDER {
+            // Keeps the first event, usually the place, and makes room at the end.
+            self.events.remove(1);
+            self.events_dropped += 1;
+        }
+
+        self.events.push(LifecycleEvent {
+            kind,
+            at,
+            elapsed_ms: self.elapsed_ms(now),
+            price: report.and_then(OrderReport::price),
+            quantity: report.and_then(OrderReport::quantity),
+            reason: report.and_then(OrderReport::reason).map(str::to_string),
+        });
+    }
+
+    fn elapsed_ms(&self, now: Instant) -> f64 {
+        now.saturating_duration_since(self.seen_at).as_secs_f64() * 1_000.0
+    }
+
+    fn apply(&mut self, report: &OrderReport, now: Instant) {
+        if self.state.is_terminal() {
+            return;
+        }
+
+        let state = match report {
+            OrderReport::Placed { .. } => return,
+            OrderReport::Accepted { .. } => {
+                self.ack_ms.get_or_insert(self.elapsed_ms(now));
+                LifecycleState::Open
+            }
+            OrderReport::PartiallyFilled { cum_quantity, .. } => {
+                self.ack_ms.get_or_insert(self.elapsed_ms(now));
+                self.filled_quantity = *cum_quantity;
+                LifecycleState::PartiallyFilled
+            }
+            OrderReport::Filled { cum_quantity, .. } => {
+                self.filled_quantity = *cum_quantity;
+                LifecycleState::Filled
+            }
+            // Still open on the venue; a cancel in flight is not undone by it.
+            OrderReport::Snapshot { cum_quantity, .. } => {
+                self.ack_ms.get_or_insert(self.elapsed_ms(now));
+                self.filled_quantity = self.filled_quantity.max(*cum_quantity);
+                match self.state {
+                    LifecycleState::Cancelling => return,
+                    _ if !self.filled_quantity.is_zero() => LifecycleState::PartiallyFilled,
+                    _ => LifecycleState::Open,
+                }
+            }
+            OrderReport::Cancel { .. } => LifecycleState::Cancelling,
+            OrderReport::CancelFailed { .. } => match self.state {
+                LifecycleState::Cancelling if !self.filled_quantity.is_zero() => {
+                    LifecycleState::PartiallyFilled
+                }
+                LifecycleState::Cancelling => LifecycleState::Open,
+                state => state,
+            },
+            OrderReport::Cancelled { .. } | OrderReport::CancelledAll { .. } => {
+                LifecycleState::Cancelled
+            }
+            OrderReport::Rejected { .. } => LifecycleState::Rejected,
+            // The event records the new price; the order stays as open as it was.
+            OrderReport::Amended { .. }
+            | OrderReport::AmendRejected { .. }
+            | OrderReport::VenueError { .. } => return,
+        };
+
+        self.state = state;
+        if state.is_terminal() {
+            self.resolved_ms = Some(self.elapsed_ms(now));
+        }
+    }
+}
+
+/// The last orders of one instrument and what happened to them, fed the place actions
+/// the engine sends and the reports its order manager consumes.
+///
+/// Finished orders are dropped oldest first past `capacity`. Unfinished ones are kept
+/// until they finish, unless `max_entries` is reached, which drops the oldest order
+/// whatever its state, with a warning.
+#[derive(Debug)]
+pub struct OrderHistory {
+    instrument: Instrument,
+    config: OrderHistoryConfig,
+    /// Lifecycles by the order they were first seen in.
+    entries: BTreeMap<u64, OrderLifecycle>,
+    by_order_id: HashMap<String, u64>,
+    next_seq: u64,
+    terminal: usize,
+}
+
+impl OrderHistory {
+    pub fn new(instrument: Instrument, config: OrderHistoryConfig) -> Self {
+        Self {
+            instrument,
+            config,
+            entries: BTreeMap::new(),
+            by_order_id: HashMap::new(),
+            next_seq: 0,
+            terminal: 0,
+        }
+    }
+
+    pub fn on_place(&mut self, order: &Order, now: Instant, at: DateTime<Utc>) {
+        let lifecycle = self.entry(&order.order_id, now, at);
+        lifecycle.side = Some(order.side);
+        lifecycle.price = Some(order.price);
+        lifecycle.quantity = Some(order.quantity);
+        lifecycle.cycle_id = order.cycle_id;
+        lifecycle.push("place", None, now, at);
+        self.evict();
+    }
+
+    pub fn on_report(&mut self, report: &OrderReport, now: Instant, at: DateTime<Utc>) {
+        if !report.concerns(&self.instrument) {
+            return;
+        }
+
+        match report.order_id() {
+            Some(order_id) => {
+                let lifecycle = self.entry(order_id, now, at);
+                let was_terminal = lifecycle.state.is_terminal();
+                lifecycle.side = lifecycle.side.or(report.side());
+                if lifecycle.price.is_none() {
+                    lifecycle.price = report.price();
+                    lifecycle.quantity = report.quantity();
+                }
+                lifecycle.push(report.kind(), Some(report), now, at);
+                lifecycle.apply(report, now);
+
+                if !was_terminal && lifecycle.state.is_terminal() {
+                    self.terminal += 1;
+                }
+            }
+            // Venue-wide: every order still working was cancelled.
+            None if matches!(report, OrderReport::CancelledAll { .. }) => {
+                for lifecycle in self.entries.values_mut() {
+                    if !lifecycle.state.is_terminal() {
+                        lifecycle.push(report.kind(), Some(report), now, at);
+                        lifecycle.apply(report, now);
+                        self.terminal += 1;
+                    }
+                }
+            }
+            None => {}
+        }
+
+        self.evict();
+    }
+
+    fn entry(&mut self, order_id: &str, now: Instant, at: DateTime<Utc>) -> &mut OrderLifecycle {
+        let seq = match self.by_order_id.get(order_id) {
+            Some(seq) => *seq,
+            None => {
+                let seq = self.next_seq;
+                self.next_seq += 1;
+                self.by_order_id.insert(order_id.to_string(), seq);
+                self.entries.insert(
+                    seq,
+                    OrderLifecycle::new(order_id, &self.instrument, now, at),
+                );
+                seq
+            }
+        };
+
+        self.entries
+            .get_mut(&seq)
+            .expect("indexed lifecycles exist")
+    }
+
+    fn evict(&mut self) {
+        while self.terminal > self.config.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .find(|(_, lifecycle)| lifecycle.state.is_terminal())
+                .map(|(seq, _)| *seq);
+            match oldest {
+                Some(seq) => self.remove(seq),
+                None => break,
+            }
+        }
+
+        while self.entries.len() > self.config.max_entries {
+            let Some((&seq, lifecycle)) = self.entries.first_key_value() else {
+                break;
+            };
+            if !lifecycle.state.is_terminal() {
+                warn!(
+                    instrument = %self.instrument,
+                    order_id = %lifecycle.order_id,
+                    state = ?lifecycle.state,
+                    max_entries = self.config.max_entries,
+                    "order history full; dropping an unfinished order"
+                );
+            }
+            self.remove(seq);
+        }
+    }
+
+    fn remove(&mut self, seq: u64) {
+        if let Some(lifecycle) = self.entries.remove(&seq) {
+            self.by_order_id.remove(&lifecycle.order_id);
+            if lifecycle.state.is_terminal() {
+                self.terminal -= 1;
+            }
+        }
+    }
+
+    pub fn get(&self, order_id: &str) -> Option<&OrderLifecycle> {
+        self.by_order_id
+            .get(order_id)
+            .and_then(|seq| self.entries.get(seq))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Up to `limit` lifecycles, most recently placed first.
+    pub fn recent(&self, limit: usize) -> Vec<OrderLifecycle> {
+        self.entries.values().rev().take(limit).cloned().collect()
+    }
+}
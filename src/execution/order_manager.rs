@@ -1,67 +1,302 @@
 use anyhow::Result;
-use std::time::Instant;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
 use crate::{
     execution::{
         order_action::{OrderAction, Side},
+        order_ids::OrderIds,
         order_report::OrderReport,
         order_side_manager::{OrderSideManager, SideInputs},
-        types::OrderSideState,
+        types::{OpenOrder, OrderManagerSnapshot, OrderSideState},
     },
-    types::{instrument::Instrument, quote_target::QuoteTarget},
+    types::{instrument::Instrument, price::Price, quote::Quote, quote_target::QuoteTarget},
 };
 
+/// One order per level on each side, level 0 nearest the touch. A single bid and ask use
+/// level 0 alone; a layered target adds levels behind it. Each level is planned towards
+/// its own quote, so only the levels that moved are touched.
 #[derive(Debug)]
 pub struct OrderManager {
-    bid_side: OrderSideManager,
-    ask_side: OrderSideManager,
+    /// Never empty: level 0 is kept even while unquoted.
+    bids: Vec<OrderSideManager>,
+    asks: Vec<OrderSideManager>,
+    /// Best bid and ask as of the next plan, which places refused for crossing are
+    /// repriced against.
+    touch: (Option<Price>, Option<Price>),
 }
 
-impl Default for OrderManager {
-    fn default() -> Self {
+impl OrderManager {
+    pub fn new(order_ids: OrderIds) -> Self {
         Self {
-            bid_side: OrderSideManager::for_side(Side::Buy),
-            ask_side: OrderSideManager::for_side(Side::Sell),
+            bids: vec![OrderSideManager::for_side(Side::Buy, order_ids.clone())],
+            asks: vec![OrderSideManager::for_side(Side::Sell, order_ids)],
+            touch: (None, None),
         }
     }
-}
 
-impl OrderManager {
-    pub fn on_report(&mut self, report: OrderReport) {
-        self.bid_side.on_report(&report);
-        self.ask_side.on_report(&report);
+    /// How long any level waits on the venue before giving its order up.
+    pub fn set_in_flight_timeout(&mut self, timeout: Duration) {
+        for level in self.all_levels_mut() {
+            level.set_in_flight_timeout(timeout);
+        }
+    }
+
+    /// Largest price move, in ticks, any level makes by amending its order rather than
+    /// replacing it; `None` always replaces.
+    pub fn set_amend_max_ticks(&mut self, max_ticks: Option<u32>) {
+        for level in self.all_levels_mut() {
+            level.set_amend_max_ticks(max_ticks);
+        }
+    }
+
+    /// Reprices each level makes of a target whose post-only places the venue refused
+    /// for crossing, before holding it until the target moves; 0 never reprices.
+    pub fn set_reprice_attempts(&mut self, max_attempts: u32) {
+        for level in self.all_levels_mut() {
+            level.set_reprice_attempts(max_attempts);
+        }
+    }
+
+    /// The book the next plan is made on.
+    pub fn set_touch(&mut self, best_bid: Option<Price>, best_ask: Option<Price>) {
+        self.touch = (best_bid, best_ask);
+    }
+
+    /// Whether any level had a place refused for crossing waiting to be re-planned; none
+    /// has afterwards, so a re-plan that is skipped leaves it to the next market event.
+    pub fn take_reprice(&mut self) -> bool {
+        let mut wanted = false;
+        for level in self.all_levels_mut() {
+            wanted |= level.take_reprice();
+        }
+        wanted
+    }
+
+    /// Applies `report`, received at `now`, to every level on its side; each acts only
+    /// on reports for the order it tracks.
+    pub fn on_report(&mut self, report: OrderReport, now: Instant) {
+        match report.side() {
+            Some(side) => {
+                for level in self.levels_mut(side) {
+                    level.on_report(&report, now);
+                }
+            }
+            None => {
+                for level in self.all_levels_mut() {
+                    level.on_report(&report, now);
+                }
+            }
+        }
+    }
+
+    /// Level 0 on `side`, the only level a single-quote target uses.
+    pub fn side(&self, side: Side) -> &OrderSideManager {
+        &self.levels(side)[0]
+    }
+
+    /// Every level on `side` so far, level 0 first.
+    pub fn levels(&self, side: Side) -> &[OrderSideManager] {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    fn levels_mut(&mut self, side: Side) -> &mut Vec<OrderSideManager> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    fn all_levels(&self) -> impl Iterator<Item = &OrderSideManager> {
+        self.bids.iter().chain(&self.asks)
+    }
+
+    fn all_levels_mut(&mut self) -> impl Iterator<Item = &mut OrderSideManager> {
+        self.bids.iter_mut().chain(&mut self.asks)
     }
 
     pub fn has_live_orders(&self) -> bool {
-        matches!(self.bid_side.state(), OrderSideState::Live { .. })
-            || matches!(self.ask_side.state(), OrderSideState::Live { .. })
+        self.all_levels()
+            .any(|level| matches!(level.state(), OrderSideState::Live { .. }))
+    }
+
+    pub fn open_order_count(&self) -> usize {
+        self.all_levels()
+            .filter(|level| !matches!(level.state(), OrderSideState::NoOrder))
+            .count()
+    }
+
+    /// Cancels for whatever this manager has resting or in flight, one per level.
+    pub fn cancel_actions(&self, instrument: &Instrument) -> Vec<OrderAction> {
+        self.all_levels()
+            .filter_map(|level| match level.state() {
+                OrderSideState::Placing { order_id, .. }
+                | OrderSideState::Live { order_id, .. }
+                | OrderSideState::Amending { order_id, .. } => Some(OrderAction::Cancel {
+                    order_id: order_id.clone(),
+                    instrument: instrument.clone(),
+                    side: level.side(),
+                }),
+                OrderSideState::NoOrder | OrderSideState::Cancelling { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Whether any level tracks `order_id`.
+    fn tracks(&self, order_id: &str) -> bool {
+        self.all_levels()
+            .any(|level| level.state().order_id() == Some(order_id))
+    }
+
+    /// Reconciles every level with the order ids open on the venue for this instrument.
+    /// Returns the open ids no level tracks.
+    pub fn resync(&mut self, open: &HashSet<String>, now: Instant) -> Vec<String> {
+        let untracked = open
+            .iter()
+            .filter(|order_id| !self.tracks(order_id))
+            .cloned()
+            .collect();
+
+        for level in self.all_levels_mut() {
+            level.resync(open, now);
+        }
+
+        untracked
+    }
+
+    /// Compares every level with `open`, the venue's open orders for `instrument` that
+    /// this engine placed: levels whose live order is gone are reset, and cancels are
+    /// returned for open orders no level tracks.
+    pub fn reconcile(&mut self, instrument: &Instrument, open: &[OpenOrder]) -> Vec<OrderAction> {
+        let ids: HashSet<String> = open.iter().map(|order| order.order_id.clone()).collect();
+        for level in self.all_levels_mut() {
+            level.reconcile(&ids);
+        }
+
+        open.iter()
+            .filter(|order| !self.tracks(&order.order_id))
+            .map(|order| OrderAction::Cancel {
+                order_id: order.order_id.clone(),
+                instrument: instrument.clone(),
+                side: order.side,
+            })
+            .collect()
+    }
+
+    /// The order level 0 tracks on `side`, placing, resting or cancelling.
+    pub fn order_id(&self, side: Side) -> Option<&str> {
+        self.side(side).state().order_id()
+    }
+
+    /// Takes over `order`, found resting on the venue at startup, as level 0 on its side.
+    pub fn adopt(&mut self, order: &OpenOrder, now: Instant) {
+        self.levels_mut(order.side)[0].adopt(order, now);
+    }
+
+    /// Every level of both sides as of `now`.
+    pub fn snapshot(&self, now: Instant) -> OrderManagerSnapshot {
+        OrderManagerSnapshot {
+            bids: self.bids.iter().map(|level| level.snapshot(now)).collect(),
+            asks: self.asks.iter().map(|level| level.snapshot(now)).collect(),
+        }
     }
 
     pub fn has_inflight_actions(&self) -> bool {
-        self.bid_side.has_inflight_actions() || self.ask_side.has_inflight_actions()
+        self.all_levels()
+            .any(OrderSideManager::has_inflight_actions)
+    }
+
+    pub fn in_flight_expired(&self, now: Instant) -> bool {
+        self.all_levels().any(|level| level.in_flight_expired(now))
     }
 
+    /// Plans each level of each side towards its quote in `target`; a level the target
+    /// no longer has is cancelled. A quote the venue would refuse under the instrument's
+    /// rules is dropped with a warning, leaving its level unquoted, rather than sent as a
+    /// doomed order.
     pub async fn actions_for_target(
         &mut self,
         instrument: &Instrument,
         target: &QuoteTarget,
         now: Instant,
     ) -> Result<Vec<OrderAction>> {
+        Ok(self.plan_target(instrument, target, now, false))
+    }
+
+    /// Like [`actions_for_target`](Self::actions_for_target), but only the cancels: a
+    /// level the target moved is cancelled rather than replaced, and nothing is placed.
+    pub async fn cancels_for_target(
+        &mut self,
+        instrument: &Instrument,
+        target: &QuoteTarget,
+        now: Instant,
+    ) -> Result<Vec<OrderAction>> {
+        Ok(self.plan_target(instrument, target, now, true))
+    }
+
+    fn plan_target(
+        &mut self,
+        instrument: &Instrument,
+        target: &QuoteTarget,
+        now: Instant,
+        cancels_only: bool,
+    ) -> Vec<OrderAction> {
         let price_tick = instrument.trading_rules().price_tick;
 
         let mut actions = Vec::new();
 
-        let bid_actions = self
-            .bid_side
-            .actions_for_target(SideInputs::new(instrument, now, price_tick, target.bid));
+        for side in [Side::Buy, Side::Sell] {
+            // A post-only order must stay short of the best price on the other side.
+            let touch = match side {
+                Side::Buy => self.touch.1,
+                Side::Sell => self.touch.0,
+            };
+            let levels = self.levels_mut(side);
+            while levels.len() < target.depth(side) {
+                let next = levels[0].for_next_level();
+                levels.push(next);
+            }
 
-        let ask_actions = self
-            .ask_side
-            .actions_for_target(SideInputs::new(instrument, now, price_tick, target.ask));
+            for (level, manager) in levels.iter_mut().enumerate() {
+                let quote = placeable(instrument, side, target.level(side, level));
+                let mut inputs = SideInputs::new(instrument, now, price_tick, quote)
+                    .with_order_type(target.order_type(side, level))
+                    .with_touch(touch);
+                if cancels_only {
+                    inputs = inputs.cancels_only();
+                }
+                if target.is_held(side) {
+                    inputs = inputs.held();
+                }
+                actions.extend(manager.actions_for_target(inputs));
+            }
+        }
 
-        actions.extend(bid_actions);
-        actions.extend(ask_actions);
+        actions
+    }
+}
 
-        Ok(actions)
+/// `quote`, unless the instrument's rules would have the venue refuse it.
+fn placeable(instrument: &Instrument, side: Side, quote: Option<Quote>) -> Option<Quote> {
+    let quote = quote?;
+    match instrument
+        .trading_rules()
+        .check_order(quote.price, quote.quantity)
+    {
+        Ok(()) => Some(quote),
+        Err(error) => {
+            tracing::warn!(
+                %instrument,
+                %side,
+                price = %quote.price,
+                quantity = %quote.quantity,
+                reason = %error,
+                "dropping quote the venue would refuse"
+            );
+            None
+        }
     }
 }
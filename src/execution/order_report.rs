@@ -1,6 +1,7 @@
 use crate::execution::order_action::Side;
 use crate::types::instrument::Instrument;
 use crate::types::price::Price;
+use crate::types::quantity::Quantity;
 
 #[derive(Debug, Clone)]
 pub enum OrderReport {
@@ -9,7 +10,7 @@ pub enum OrderReport {
         instrument: Instrument,
         side: Side,
         price: Price,
-        quantity: f64,
+        quantity: Quantity,
     },
 
     Accepted {
@@ -17,13 +18,14 @@ pub enum OrderReport {
         instrument: Instrument,
         side: Side,
         price: Price,
-        quantity: f64,
+        quantity: Quantity,
     },
 
     Rejected {
         order_id: String,
         instrument: Instrument,
         side: Side,
+        kind: RejectKind,
         reason: String,
     },
 
@@ -32,8 +34,8 @@ pub enum OrderReport {
         instrument: Instrument,
         side: Side,
         price: Price,
-        quantity: f64,
-        cum_quantity: f64,
+        quantity: Quantity,
+        cum_quantity: Quantity,
     },
 
     Filled {
@@ -41,8 +43,8 @@ pub enum OrderReport {
         instrument: Instrument,
         side: Side,
         price: Price,
-        quantity: f64,
-        cum_quantity: f64,
+        quantity: Quantity,
+        cum_quantity: Quantity,
     },
 
     Cancel {
@@ -68,7 +70,162 @@ pub enum OrderReport {
         count: i64,
     },
 
+    /// The venue's account of an order still open, replayed when its report stream
+    /// reconnects: resting at `price`, for `quantity` in all, `cum_quantity` of it filled.
+    /// It restates the order rather than moving it on.
+    Snapshot {
+        order_id: String,
+        instrument: Instrument,
+        side: Side,
+        price: Price,
+        quantity: Quantity,
+        cum_quantity: Quantity,
+    },
+
+    /// The order now rests at `price`, for `quantity` in all, fills included.
+    Amended {
+        order_id: String,
+        instrument: Instrument,
+        side: Side,
+        price: Price,
+        quantity: Quantity,
+    },
+
+    /// The amend was refused; the order, if still resting, is unchanged.
+    AmendRejected {
+        order_id: String,
+        instrument: Instrument,
+        side: Side,
+        reason: String,
+    },
+
     VenueError {
         message: String,
     },
 }
+
+/// Why a place was refused, as the venue layer read the venue's answer. The engine acts
+/// on this rather than on the wording of `reason`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectKind {
+    /// A post-only order would have crossed the book and taken liquidity.
+    PostOnlyWouldCross,
+    InsufficientFunds,
+    RateLimited,
+    /// Expired before it could rest.
+    Expired,
+    Other,
+}
+
+impl OrderReport {
+    /// Stable, low-cardinality identifier of the report variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OrderReport::Placed { .. } => "placed",
+            OrderReport::Accepted { .. } => "accepted",
+            OrderReport::Rejected { .. } => "rejected",
+            OrderReport::PartiallyFilled { .. } => "partially_filled",
+            OrderReport::Filled { .. } => "filled",
+            OrderReport::Cancel { .. } => "cancel",
+            OrderReport::Cancelled { .. } => "cancelled",
+            OrderReport::CancelFailed { .. } => "cancel_failed",
+            OrderReport::CancelledAll { .. } => "cancelled_all",
+            OrderReport::Snapshot { .. } => "snapshot",
+            OrderReport::Amended { .. } => "amended",
+            OrderReport::AmendRejected { .. } => "amend_rejected",
+            OrderReport::VenueError { .. } => "venue_error",
+        }
+    }
+
+    /// `None` for venue-wide reports, which concern every instrument.
+    pub fn instrument(&self) -> Option<&Instrument> {
+        match self {
+            OrderReport::Placed { instrument, .. }
+            | OrderReport::Accepted { instrument, .. }
+            | OrderReport::Rejected { instrument, .. }
+            | OrderReport::PartiallyFilled { instrument, .. }
+            | OrderReport::Filled { instrument, .. }
+            | OrderReport::Cancel { instrument, .. }
+            | OrderReport::Cancelled { instrument, .. }
+            | OrderReport::CancelFailed { instrument, .. }
+            | OrderReport::Amended { instrument, .. }
+            | OrderReport::AmendRejected { instrument, .. }
+            | OrderReport::Snapshot { instrument, .. } => Some(instrument),
+            OrderReport::CancelledAll { .. } | OrderReport::VenueError { .. } => None,
+        }
+    }
+
+    /// Whether this report affects `instrument`'s order state.
+    pub fn concerns(&self, instrument: &Instrument) -> bool {
+        self.instrument()
+            .is_none_or(|reported| reported == instrument)
+    }
+
+    pub fn order_id(&self) -> Option<&str> {
+        match self {
+            OrderReport::Placed { order_id, .. }
+            | OrderReport::Accepted { order_id, .. }
+            | OrderReport::Rejected { order_id, .. }
+            | OrderReport::PartiallyFilled { order_id, .. }
+            | OrderReport::Filled { order_id, .. }
+            | OrderReport::Cancel { order_id, .. }
+            | OrderReport::Cancelled { order_id, .. }
+            | OrderReport::CancelFailed { order_id, .. }
+            | OrderReport::Amended { order_id, .. }
+            | OrderReport::AmendRejected { order_id, .. }
+            | OrderReport::Snapshot { order_id, .. } => Some(order_id),
+            OrderReport::CancelledAll { .. } | OrderReport::VenueError { .. } => None,
+        }
+    }
+
+    pub fn price(&self) -> Option<Price> {
+        match self {
+            OrderReport::Placed { price, .. }
+            | OrderReport::Accepted { price, .. }
+            | OrderReport::Amended { price, .. }
+            | OrderReport::Snapshot { price, .. }
+            | OrderReport::PartiallyFilled { price, .. }
+            | OrderReport::Filled { price, .. } => Some(*price),
+            _ => None,
+        }
+    }
+
+    pub fn quantity(&self) -> Option<Quantity> {
+        match self {
+            OrderReport::Placed { quantity, .. }
+            | OrderReport::Accepted { quantity, .. }
+            | OrderReport::Amended { quantity, .. }
+            | OrderReport::Snapshot { quantity, .. }
+            | OrderReport::PartiallyFilled { quantity, .. }
+            | OrderReport::Filled { quantity, .. } => Some(*quantity),
+            _ => None,
+        }
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            OrderReport::Rejected { reason, .. }
+            | OrderReport::CancelFailed { reason, .. }
+            | OrderReport::AmendRejected { reason, .. } => Some(reason),
+            OrderReport::VenueError { message } => Some(message),
+            _ => None,
+        }
+    }
+
+    pub fn side(&self) -> Option<Side> {
+        match self {
+            OrderReport::Placed { side, .. }
+            | OrderReport::Accepted { side, .. }
+            | OrderReport::Rejected { side, .. }
+            | OrderReport::PartiallyFilled { side, .. }
+            | OrderReport::Filled { side, .. }
+            | OrderReport::Cancel { side, .. }
+            | OrderReport::Cancelled { side, .. }
+            | OrderReport::CancelFailed { side, .. }
+            | OrderReport::Amended { side, .. }
+            | OrderReport::AmendRejected { side, .. }
+            | OrderReport::Snapshot { side, .. } => Some(*side),
+            OrderReport::CancelledAll { .. } | OrderReport::VenueError { .. } => None,
+        }
+    }
+}
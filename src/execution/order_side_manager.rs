@@ -1,14 +1,15 @@
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
-use uuid::Uuid;
-
 use crate::{
     execution::{
         order_action::{Order, OrderAction, OrderType, Side},
-        order_report::OrderReport,
-        types::{OrderSideState, SidePlan},
+        order_ids::OrderIds,
+        order_report::{OrderReport, RejectKind},
+        reprice_on_reject::RepriceOnReject,
+        types::{OpenOrder, OrderSideSnapshot, OrderSideState, SidePlan},
     },
-    types::{instrument::Instrument, quote::Quote},
+    types::{instrument::Instrument, price::Price, quantity::Quantity, quote::Quote},
 };
 
 #[derive(Debug, Clone)]
@@ -17,6 +18,14 @@ pub struct SideInputs<'a> {
     now: Instant,
     price_tick: f64,
     target: Option<Quote>,
+    /// Best price on the other side, which a post-only order on this side must not reach.
+    touch: Option<Price>,
+    /// What the target is placed as.
+    order_type: OrderType,
+    /// Only cancel towards the target; place nothing.
+    cancels_only: bool,
+    /// Leave a resting order as it is whatever the target.
+    held: bool,
 }
 
 impl<'a> SideInputs<'a> {
@@ -31,13 +40,37 @@ impl<'a> SideInputs<'a> {
             now,
             price_tick,
             target,
+            touch: None,
+            order_type: OrderType::POST_ONLY,
+            cancels_only: false,
+            held: false,
         }
     }
-}
 
-impl Default for OrderSideState {
-    fn default() -> Self {
-        OrderSideState::NoOrder
+    /// Reprices places the venue refused for crossing against `touch`, the best price on
+    /// the other side.
+    pub fn with_touch(mut self, touch: Option<Price>) -> Self {
+        self.touch = touch;
+        self
+    }
+
+    /// Places the target as `order_type` rather than post-only.
+    pub fn with_order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = order_type;
+        self
+    }
+
+    /// Plans only the cancels towards the target, holding any new order.
+    pub fn cancels_only(mut self) -> Self {
+        self.cancels_only = true;
+        self
+    }
+
+    /// Keeps the order resting on this side, or the lack of one, rather than moving it
+    /// towards the target.
+    pub fn held(mut self) -> Self {
+        self.held = true;
+        self
     }
 }
 
@@ -45,6 +78,10 @@ impl Default for OrderSideState {
 pub struct ReplacePolicy {
     replace_threshold_ticks: i64,
     min_lifetime: Duration,
+    /// How long a place or cancel may wait on the venue before its report is presumed lost.
+    in_flight_timeout: Duration,
+    /// Largest price move made by amending the live order instead of replacing it.
+    amend_max_ticks: Option<i64>,
 }
 
 impl Default for ReplacePolicy {
@@ -52,16 +89,24 @@ impl Default for ReplacePolicy {
         Self {
             replace_threshold_ticks: 3,
             min_lifetime: Duration::from_millis(500),
+            in_flight_timeout: Duration::from_secs(10),
+            amend_max_ticks: None,
         }
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct OrderSideManager {
     side: Side,
     state: OrderSideState,
     last_update: Option<Instant>,
+    /// When the side last started waiting on the venue, while it still is.
+    in_flight_since: Option<Instant>,
     policy: ReplacePolicy,
+    order_ids: OrderIds,
+    /// Order whose amend the venue refused; it is replaced instead.
+    amend_refused: Option<String>,
+    reprice: RepriceOnReject,
 }
 
 impl OrderSideManager {
@@ -69,23 +114,99 @@ impl OrderSideManager {
         &self.state
     }
 
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn snapshot(&self, now: Instant) -> OrderSideSnapshot {
+        OrderSideSnapshot {
+            state: self.state.name(),
+            order_id: self.state.order_id().map(String::from),
+            quote: self.state.working_quote(),
+            age_ms: self
+                .last_update
+                .or(self.in_flight_since)
+                .filter(|_| self.state.order_id().is_some())
+                .map(|since| now.saturating_duration_since(since).as_secs_f64() * 1_000.0),
+        }
+    }
+
     pub fn has_inflight_actions(&self) -> bool {
         match &self.state {
             OrderSideState::Placing { .. } => true,
             OrderSideState::Cancelling { .. } => true,
+            OrderSideState::Amending { .. } => true,
             OrderSideState::Live { .. } => false,
             OrderSideState::NoOrder => false,
         }
     }
 
-    pub fn for_side(side: Side) -> Self {
+    pub fn for_side(side: Side, order_ids: OrderIds) -> Self {
         Self {
             side,
-            ..Default::default()
+            state: OrderSideState::default(),
+            last_update: None,
+            in_flight_since: None,
+            policy: ReplacePolicy::default(),
+            order_ids,
+            amend_refused: None,
+            reprice: RepriceOnReject::new(0),
+        }
+    }
+
+    /// A manager for another order on the same side, with the same policy and no order.
+    pub fn for_next_level(&self) -> Self {
+        Self {
+            policy: self.policy.clone(),
+            reprice: RepriceOnReject::new(self.reprice.max_attempts()),
+            ..Self::for_side(self.side, self.order_ids.clone())
+        }
+    }
+
+    pub fn set_in_flight_timeout(&mut self, timeout: Duration) {
+        self.policy.in_flight_timeout = timeout;
+    }
+
+    pub fn set_amend_max_ticks(&mut self, max_ticks: Option<u32>) {
+        self.policy.amend_max_ticks = max_ticks.map(i64::from);
+    }
+
+    /// Reprices of a target whose post-only places the venue refused for crossing, before
+    /// giving up on it; 0 never reprices.
+    pub fn set_reprice_attempts(&mut self, max_attempts: u32) {
+        self.reprice.set_max_attempts(max_attempts);
+    }
+
+    /// Whether a place the venue refused for crossing was waiting to be re-planned; it
+    /// no longer is either way.
+    pub fn take_reprice(&mut self) -> bool {
+        let pending = self.reprice.is_pending();
+        self.reprice.on_planned();
+        pending
+    }
+
+    /// Whether the side has waited on the venue past the in-flight timeout at `now`, so
+    /// the next evaluation gives the order up.
+    pub fn in_flight_expired(&self, now: Instant) -> bool {
+        self.in_flight_since.is_some_and(|since| {
+            now.saturating_duration_since(since) >= self.policy.in_flight_timeout
+        })
+    }
+
+    fn track_in_flight(&mut self, now: Instant) {
+        if !self.has_inflight_actions() {
+            self.in_flight_since = None;
+        } else if self.in_flight_since.is_none() {
+            self.in_flight_since = Some(now);
         }
     }
 
-    pub fn on_report(&mut self, report: &OrderReport) {
+    pub fn on_report(&mut self, report: &OrderReport, now: Instant) {
+        self.apply_report(report, now);
+        self.track_in_flight(now);
+    }
+
+    fn apply_report(&mut self, report: &OrderReport, now: Instant) {
         match report {
             OrderReport::Placed {
                 order_id,
@@ -93,7 +214,7 @@ impl OrderSideManager {
                 price,
                 quantity,
                 ..
-            } if *side == self.side => {
+            } if *side == self.side && self.matches_current_order(order_id) => {
                 self.state = OrderSideState::Placing {
                     order_id: order_id.clone(),
                     requested: Quote {
@@ -109,121 +230,402 @@ impl OrderSideManager {
                 price,
                 quantity,
                 ..
-            } if *side == self.side => {
+            } if *side == self.side && self.matches_current_order(order_id) => {
+                // A repeated accept keeps the fills already seen.
+                let (ordered, filled) = match &self.state {
+                    OrderSideState::Placing { requested, .. } => {
+                        (requested.quantity, Quantity::ZERO)
+                    }
+                    OrderSideState::Live {
+                        resting, filled, ..
+                    }
+                    | OrderSideState::Cancelling {
+                        resting, filled, ..
+                    }
+                    | OrderSideState::Amending {
+                        resting, filled, ..
+                    } => (resting.quantity + *filled, *filled),
+                    OrderSideState::NoOrder => (*quantity, Quantity::ZERO),
+                };
+
                 self.state = OrderSideState::Live {
                     order_id: order_id.clone(),
                     resting: Quote {
                         price: *price,
-                        quantity: *quantity,
+                        quantity: ordered.saturating_sub(filled),
                     },
+                    filled,
                 };
-                self.last_update = Some(Instant::now());
+                self.last_update = Some(now);
             }
 
-            OrderReport::Rejected { order_id, side, .. } if *side == self.side => {
-                if self.matches_current_order(order_id) {
-                    self.state = OrderSideState::NoOrder;
-                    self.last_update = None;
+            OrderReport::Rejected {
+                order_id,
+                side,
+                kind,
+                ..
+            } if *side == self.side && self.matches_current_order(order_id) => {
+                if *kind == RejectKind::PostOnlyWouldCross
+                    && matches!(self.state, OrderSideState::Placing { .. })
+                {
+                    self.reprice.on_crossed();
                 }
+                self.state = OrderSideState::NoOrder;
+                self.last_update = None;
             }
 
             OrderReport::Cancel { order_id, side, .. } if *side == self.side => {
                 if let OrderSideState::Live {
                     order_id: live_id,
                     resting,
+                    filled,
                 } = self.state.clone()
+                    && *order_id == live_id
                 {
-                    if *order_id == live_id {
-                        self.state = OrderSideState::Cancelling {
-                            order_id: order_id.clone(),
-                            resting,
-                        };
-                    }
+                    self.state = OrderSideState::Cancelling {
+                        order_id: order_id.clone(),
+                        resting,
+                        filled,
+                    };
                 }
             }
 
-            OrderReport::Cancelled { order_id, side, .. } if *side == self.side => {
-                if self.matches_current_order(order_id) {
-                    self.state = OrderSideState::NoOrder;
-                    self.last_update = None;
-                }
+            OrderReport::Cancelled { order_id, side, .. }
+                if *side == self.side && self.matches_current_order(order_id) =>
+            {
+                self.state = OrderSideState::NoOrder;
+                self.last_update = None;
             }
 
+            // The cumulative quantity is authoritative: a missed report is caught up by
+            // the next, and one that does not advance it is a duplicate.
             OrderReport::PartiallyFilled {
                 order_id,
+                instrument,
                 side,
                 price,
                 quantity,
+                cum_quantity,
                 ..
             } if *side == self.side => {
                 if let OrderSideState::Live {
                     order_id: live_id,
                     resting,
+                    filled,
+                }
+                | OrderSideState::Amending {
+                    order_id: live_id,
+                    resting,
+                    filled,
+                    ..
                 } = self.state.clone()
+                    && *order_id == live_id
                 {
-                    if *order_id == live_id {
-                        let remaining = (resting.quantity - *quantity).max(0.0);
-
-                        self.state = OrderSideState::Live {
-                            order_id: live_id,
-                            resting: Quote {
-                                price: resting.price,
-                                quantity: remaining,
-                            },
-                        };
-
-                        self.last_update = Some(Instant::now());
-
-                        tracing::info!(
+                    let rules = instrument.trading_rules();
+                    if *cum_quantity <= filled || rules.quantity_eq(*cum_quantity, filled) {
+                        tracing::debug!(
                             side = %self.side,
                             order_id = %order_id,
-                            fill_price = %price,
-                            fill_quantity = *quantity,
-                            remaining_quantity = remaining,
-                            "order partially filled"
+                            cum_quantity = cum_quantity.as_f64(),
+                            filled = filled.as_f64(),
+                            "ignoring a partial fill that does not advance the order"
                         );
+                        return;
                     }
+
+                    let remaining = (resting.quantity + filled).saturating_sub(*cum_quantity);
+                    let resting = Quote {
+                        price: resting.price,
+                        quantity: remaining,
+                    };
+
+                    self.state = match &self.state {
+                        OrderSideState::Amending { requested, .. } => OrderSideState::Amending {
+                            order_id: live_id,
+                            resting,
+                            filled: *cum_quantity,
+                            requested: *requested,
+                        },
+                        _ => OrderSideState::Live {
+                            order_id: live_id,
+                            resting,
+                            filled: *cum_quantity,
+                        },
+                    };
+
+                    self.last_update = Some(now);
+
+                    tracing::info!(
+                        side = %self.side,
+                        order_id = %order_id,
+                        fill_price = %price,
+                        fill_quantity = quantity.as_f64(),
+                        cum_quantity = cum_quantity.as_f64(),
+                        remaining_quantity = remaining.as_f64(),
+                        "order partially filled"
+                    );
                 }
             }
 
-            OrderReport::Filled {
+            // A restatement after the report stream reconnected: it settles an accept
+            // that was missed and catches up missed fills, but leaves a cancel or amend
+            // in flight to its own report.
+            OrderReport::Snapshot {
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+                cum_quantity,
+            } if *side == self.side && self.matches_current_order(order_id) => {
+                let remaining = quantity.saturating_sub(*cum_quantity);
+                let rules = instrument.trading_rules();
+
+                self.state = match self.state.clone() {
+                    OrderSideState::Placing { .. } => OrderSideState::Live {
+                        order_id: order_id.clone(),
+                        resting: Quote {
+                            price: *price,
+                            quantity: remaining,
+                        },
+                        filled: *cum_quantity,
+                    },
+                    OrderSideState::Live { filled, .. }
+                    | OrderSideState::Cancelling { filled, .. }
+                    | OrderSideState::Amending { filled, .. }
+                        if *cum_quantity <= filled || rules.quantity_eq(*cum_quantity, filled) =>
+                    {
+                        return;
+                    }
+                    OrderSideState::Live {
+                        order_id, resting, ..
+                    } => OrderSideState::Live {
+                        order_id,
+                        resting: Quote {
+                            price: resting.price,
+                            quantity: remaining,
+                        },
+                        filled: *cum_quantity,
+                    },
+                    OrderSideState::Cancelling {
+                        order_id, resting, ..
+                    } => OrderSideState::Cancelling {
+                        order_id,
+                        resting: Quote {
+                            price: resting.price,
+                            quantity: remaining,
+                        },
+                        filled: *cum_quantity,
+                    },
+                    OrderSideState::Amending {
+                        order_id,
+                        resting,
+                        requested,
+                        ..
+                    } => OrderSideState::Amending {
+                        order_id,
+                        resting: Quote {
+                            price: resting.price,
+                            quantity: remaining,
+                        },
+                        filled: *cum_quantity,
+                        requested,
+                    },
+                    OrderSideState::NoOrder => return,
+                };
+                self.last_update = Some(now);
+
+                tracing::info!(
+                    side = %self.side,
+                    order_id = %order_id,
+                    cum_quantity = cum_quantity.as_f64(),
+                    remaining_quantity = remaining.as_f64(),
+                    "order reconciled from a venue snapshot"
+                );
+            }
+
+            OrderReport::Amended {
                 order_id,
                 side,
                 price,
                 quantity,
                 ..
+            } if *side == self.side && self.matches_current_order(order_id) => {
+                if let OrderSideState::Live { filled, .. }
+                | OrderSideState::Amending { filled, .. } = self.state
+                {
+                    self.state = OrderSideState::Live {
+                        order_id: order_id.clone(),
+                        resting: Quote {
+                            price: *price,
+                            quantity: quantity.saturating_sub(filled),
+                        },
+                        filled,
+                    };
+                    self.last_update = Some(now);
+                }
+            }
+
+            // The order rests as it was; the next evaluation replaces it instead.
+            OrderReport::AmendRejected {
+                order_id,
+                side,
+                reason,
+                ..
             } if *side == self.side => {
-                if self.matches_current_order(order_id) {
-                    tracing::info!(
+                if let OrderSideState::Amending {
+                    order_id: amending_id,
+                    resting,
+                    filled,
+                    ..
+                } = self.state.clone()
+                    && *order_id == amending_id
+                {
+                    tracing::warn!(
                         side = %self.side,
                         order_id = %order_id,
-                        fill_price = %price,
-                        fill_quantity = *quantity,
-                        "order filled"
+                        reason = %reason,
+                        "amend refused; replacing the order instead"
                     );
-
-                    self.state = OrderSideState::NoOrder;
-                    self.last_update = None;
+                    self.state = OrderSideState::Live {
+                        order_id: amending_id,
+                        resting,
+                        filled,
+                    };
+                    self.amend_refused = Some(order_id.clone());
                 }
             }
 
+            // Venue-wide: whatever this side had resting is gone.
+            OrderReport::CancelledAll { .. } => {
+                self.state = OrderSideState::NoOrder;
+                self.last_update = None;
+            }
+
+            OrderReport::Filled {
+                order_id,
+                side,
+                price,
+                quantity,
+                ..
+            } if *side == self.side && self.matches_current_order(order_id) => {
+                tracing::info!(
+                    side = %self.side,
+                    order_id = %order_id,
+                    fill_price = %price,
+                    fill_quantity = quantity.as_f64(),
+                    "order filled"
+                );
+
+                self.state = OrderSideState::NoOrder;
+                self.last_update = None;
+            }
+
             _ => {}
         }
     }
 
+    /// Reconciles the state with the order ids open on the venue, for when reports were
+    /// lost: an order no longer open is gone, and one still open is resting, even if its
+    /// accept or a cancel was in flight.
+    pub fn resync(&mut self, open: &HashSet<String>, now: Instant) {
+        use crate::execution::types::OrderSideState::*;
+
+        self.state = match std::mem::take(&mut self.state) {
+            NoOrder => NoOrder,
+            Placing {
+                order_id,
+                requested,
+            } if open.contains(&order_id) => {
+                self.last_update = Some(now);
+                Live {
+                    order_id,
+                    resting: requested,
+                    filled: Quantity::ZERO,
+                }
+            }
+            Live {
+                order_id,
+                resting,
+                filled,
+            }
+            | Cancelling {
+                order_id,
+                resting,
+                filled,
+            }
+            | Amending {
+                order_id,
+                resting,
+                filled,
+                ..
+            } if open.contains(&order_id) => Live {
+                order_id,
+                resting,
+                filled,
+            },
+            _ => {
+                self.last_update = None;
+                NoOrder
+            }
+        };
+        self.track_in_flight(now);
+    }
+
+    /// Forgets a live order that is no longer open on the venue, e.g. one cancelled by
+    /// hand whose report never arrived. Orders waiting on the venue are left to the
+    /// in-flight timeout, since a place may not be listed as open yet.
+    pub fn reconcile(&mut self, open: &HashSet<String>) {
+        if let OrderSideState::Live { order_id, .. } = &self.state
+            && !open.contains(order_id)
+        {
+            tracing::warn!(
+                side = %self.side,
+                order_id = %order_id,
+                "live order no longer open on the venue; forgetting it"
+            );
+            self.state = OrderSideState::NoOrder;
+            self.last_update = None;
+        }
+    }
+
+    /// Takes over `order`, found resting on the venue at startup, as this side's live
+    /// order. Its age is unknown, so it counts from `now` towards the minimum lifetime.
+    pub fn adopt(&mut self, order: &OpenOrder, now: Instant) {
+        self.state = OrderSideState::Live {
+            order_id: order.order_id.clone(),
+            resting: Quote {
+                price: order.price,
+                quantity: order.remaining,
+            },
+            filled: order.filled,
+        };
+        self.last_update = Some(now);
+        self.track_in_flight(now);
+    }
+
     fn matches_current_order(&self, order_id: &str) -> bool {
         match &self.state {
             OrderSideState::Placing { order_id: id, .. } => id == order_id,
             OrderSideState::Live { order_id: id, .. } => id == order_id,
             OrderSideState::Cancelling { order_id: id, .. } => id == order_id,
+            OrderSideState::Amending { order_id: id, .. } => id == order_id,
             OrderSideState::NoOrder => false,
         }
     }
 
     pub fn actions_for_target(&mut self, inputs: SideInputs<'_>) -> Vec<OrderAction> {
-        let plan = self.plan(&inputs);
-        let actions = self.get_actions(inputs.instrument, &plan);
+        let quantity_step = inputs.instrument.trading_rules().quantity_step;
+        self.reprice
+            .on_target(inputs.target, inputs.price_tick, quantity_step);
+
+        let mut plan = self.plan(&inputs);
+        if inputs.cancels_only {
+            plan = plan.cancels_only();
+        }
+        let actions = self.get_actions(&inputs, &plan);
         self.apply_optimistic(plan, inputs.now);
+        self.reprice.on_planned();
         actions
     }
 
@@ -231,46 +633,107 @@ impl OrderSideManager {
         use crate::execution::types::OrderSideState::*;
         use crate::execution::types::SidePlan::*;
 
-        match (&self.state, inputs.target.clone()) {
+        // The target as repriced after the venue refused it for crossing, if it did.
+        let target = inputs.target.and_then(|desired| {
+            self.reprice
+                .quote(self.side, desired, inputs.touch, inputs.price_tick)
+        });
+
+        match (&self.state, target) {
+            (NoOrder | Live { .. }, _) if inputs.held => NoAction,
             (NoOrder, None) => NoAction,
             (NoOrder, Some(desired)) => Place {
-                order_id: generate_order_id(inputs.instrument, self.side),
+                order_id: self.order_ids.next_id(inputs.instrument, self.side),
                 desired,
             },
 
+            (
+                Placing { order_id, .. } | Cancelling { order_id, .. } | Amending { order_id, .. },
+                _,
+            ) if self.in_flight_expired(inputs.now) => Abandon {
+                order_id: order_id.clone(),
+            },
             (Placing { .. }, _) => WaitForVenue,
             (Cancelling { .. }, _) => WaitForVenue,
+            (Amending { .. }, _) => WaitForVenue,
 
             (Live { order_id, .. }, None) => Cancel {
                 order_id: order_id.clone(),
             },
 
-            (Live { order_id, resting }, Some(desired)) => {
-                if self.is_stale(&resting, &desired, inputs.now, inputs.price_tick) {
+            (
+                Live {
+                    order_id, resting, ..
+                },
+                Some(desired),
+            ) => {
+                if !self.is_stale(resting, &desired, inputs) {
+                    NoAction
+                } else if self.amendable(order_id, resting, &desired, inputs) {
+                    Amend {
+                        order_id: order_id.clone(),
+                        desired,
+                    }
+                } else {
                     Replace {
                         old_order_id: order_id.clone(),
-                        new_order_id: generate_order_id(inputs.instrument, self.side),
+                        new_order_id: self.order_ids.next_id(inputs.instrument, self.side),
                         desired,
                     }
-                } else {
-                    NoAction
                 }
             }
         }
     }
 
-    fn is_stale(&self, current: &Quote, desired: &Quote, now: Instant, price_tick: f64) -> bool {
-        if let Some(last_update) = self.last_update {
-            if now.duration_since(last_update) < self.policy.min_lifetime {
-                return false;
-            }
+    /// Whether `current` can be moved to `desired` by amending: a price move within the
+    /// policy's bound at the same quantity, on an order whose amend was not refused.
+    /// Amends are post-only, so a target placed otherwise is always replaced.
+    fn amendable(
+        &self,
+        order_id: &str,
+        current: &Quote,
+        desired: &Quote,
+        inputs: &SideInputs<'_>,
+    ) -> bool {
+        let Some(max_ticks) = self.policy.amend_max_ticks else {
+            return false;
+        };
+        if self.amend_refused.as_deref() == Some(order_id) || !inputs.order_type.is_post_only() {
+            return false;
         }
 
-        let current_ticks = price_to_ticks(current.price.as_f64(), price_tick);
-        let desired_ticks = price_to_ticks(desired.price.as_f64(), price_tick);
+        let rules = inputs.instrument.trading_rules();
+        let diff_ticks = (price_to_ticks(current.price.as_f64(), inputs.price_tick)
+            - price_to_ticks(desired.price.as_f64(), inputs.price_tick))
+        .abs();
+
+        rules.quantity_eq(current.quantity, desired.quantity)
+            && diff_ticks > 0
+            && diff_ticks <= max_ticks
+    }
+
+    fn is_stale(&self, current: &Quote, desired: &Quote, inputs: &SideInputs<'_>) -> bool {
+        let age = self
+            .last_update
+            .map(|last_update| inputs.now.duration_since(last_update));
+        if age.is_some_and(|age| age < self.policy.min_lifetime) {
+            return false;
+        }
+
+        let rules = inputs.instrument.trading_rules();
+        if let (Some(age), Some(max_lifetime)) = (age, rules.max_order_lifetime())
+            && age >= max_lifetime
+        {
+            tracing::info!(current = ?current, desired = ?desired, age_ms = age.as_millis() as u64, "order rested too long");
+
+            return true;
+        }
+
+        let current_ticks = price_to_ticks(current.price.as_f64(), inputs.price_tick);
+        let desired_ticks = price_to_ticks(desired.price.as_f64(), inputs.price_tick);
         let diff_ticks = (current_ticks - desired_ticks).abs();
 
-        let quantity_changed = (current.quantity - desired.quantity).abs() > 1e-12;
+        let quantity_changed = !rules.quantity_eq(current.quantity, desired.quantity);
         if quantity_changed {
             tracing::info!(current = ?current, desired = ?desired, "quantity changed");
 
@@ -287,26 +750,36 @@ impl OrderSideManager {
         false
     }
 
-    fn get_actions(&self, instrument: &Instrument, plan: &SidePlan) -> Vec<OrderAction> {
+    fn get_actions(&self, inputs: &SideInputs<'_>, plan: &SidePlan) -> Vec<OrderAction> {
         use crate::execution::types::SidePlan::*;
 
+        let instrument = inputs.instrument;
         let mut actions = Vec::new();
 
         match plan {
             NoAction => {}
             WaitForVenue => {}
             Place { order_id, desired } => {
-                actions.push(self.place_action(order_id.clone(), instrument, desired))
+                actions.push(self.place_action(order_id.clone(), inputs, desired))
+            }
+            Cancel { order_id } | Abandon { order_id } => {
+                actions.push(self.cancel_action(order_id.clone(), instrument))
             }
-            Cancel { order_id } => actions.push(self.cancel_action(order_id.clone(), instrument)),
             Replace {
                 old_order_id,
                 new_order_id,
                 desired,
             } => {
                 actions.push(self.cancel_action(old_order_id.clone(), instrument));
-                actions.push(self.place_action(new_order_id.clone(), instrument, desired));
+                actions.push(self.place_action(new_order_id.clone(), inputs, desired));
             }
+            Amend { order_id, desired } => actions.push(OrderAction::Amend {
+                order_id: order_id.clone(),
+                instrument: instrument.clone(),
+                side: self.side,
+                new_price: desired.price,
+                new_quantity: desired.quantity + self.filled(),
+            }),
         }
 
         actions
@@ -315,19 +788,30 @@ impl OrderSideManager {
     fn place_action(
         &self,
         order_id: String,
-        instrument: &Instrument,
+        inputs: &SideInputs<'_>,
         desired: &Quote,
     ) -> OrderAction {
         OrderAction::Place(Order {
             order_id,
-            instrument: instrument.clone(),
+            instrument: inputs.instrument.clone(),
             side: self.side,
             price: desired.price,
             quantity: desired.quantity,
-            order_type: OrderType::PostOnlyLimit,
+            order_type: inputs.order_type,
+            cycle_id: None,
         })
     }
 
+    /// What the current order has filled so far.
+    fn filled(&self) -> Quantity {
+        match &self.state {
+            OrderSideState::Live { filled, .. }
+            | OrderSideState::Cancelling { filled, .. }
+            | OrderSideState::Amending { filled, .. } => *filled,
+            OrderSideState::NoOrder | OrderSideState::Placing { .. } => Quantity::ZERO,
+        }
+    }
+
     fn cancel_action(&self, order_id: String, instrument: &Instrument) -> OrderAction {
         OrderAction::Cancel {
             order_id,
@@ -341,6 +825,17 @@ impl OrderSideManager {
             (_, SidePlan::NoAction) => {}
             (_, SidePlan::WaitForVenue) => {}
 
+            (_, SidePlan::Abandon { order_id }) => {
+                tracing::warn!(
+                    side = %self.side,
+                    order_id = %order_id,
+                    state = ?self.state,
+                    "no report from the venue in time; cancelling defensively and moving on"
+                );
+                self.state = OrderSideState::NoOrder;
+                self.last_update = None;
+            }
+
             (OrderSideState::NoOrder, SidePlan::Place { order_id, desired }) => {
                 self.state = OrderSideState::Placing {
                     order_id,
@@ -349,11 +844,34 @@ impl OrderSideManager {
                 self.last_update = Some(now);
             }
 
-            (OrderSideState::Live { resting, .. }, SidePlan::Cancel { order_id }) => {
-                self.state = OrderSideState::Cancelling { order_id, resting };
+            (
+                OrderSideState::Live {
+                    resting, filled, ..
+                },
+                SidePlan::Cancel { order_id },
+            ) => {
+                self.state = OrderSideState::Cancelling {
+                    order_id,
+                    resting,
+                    filled,
+                };
                 self.last_update = Some(now);
             }
 
+            (
+                OrderSideState::Live {
+                    resting, filled, ..
+                },
+                SidePlan::Amend { order_id, desired },
+            ) => {
+                self.state = OrderSideState::Amending {
+                    order_id,
+                    resting,
+                    filled,
+                    requested: desired,
+                };
+            }
+
             // keep your existing “cancel + place immediately” behavior
             (
                 _,
@@ -372,6 +890,7 @@ impl OrderSideManager {
 
             _ => {}
         }
+        self.track_in_flight(now);
     }
 }
 
@@ -381,7 +900,3 @@ fn price_to_ticks(price: f64, tick: f64) -> i64 {
     }
     (price / tick).round() as i64
 }
-
-fn generate_order_id(instrument: &Instrument, side: Side) -> String {
-    Uuid::new_v4().to_string()
-}
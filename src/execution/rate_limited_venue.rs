@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::error;
+
+use crate::clock::SharedClock;
+use crate::engine::supervisor::Supervisor;
+use crate::events::MarketEvent;
+use crate::execution::{
+    DynamicInventorySource, ExecutionVenue, ReportSender,
+    order_action::OrderAction,
+    order_report::{OrderReport, RejectKind},
+    trade_checkpoint::TradeCheckpoint,
+    types::OpenOrder,
+};
+use crate::scenario::scenario::DynamicVenue;
+use crate::types::instrument::Instrument;
+
+/// The window places are counted over.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Venue wrapper that passes on at most `max_places_per_minute` places in any 60 seconds,
+/// bounding the damage of an engine stuck placing in a loop. Places beyond it are
+/// dropped and reported rejected; cancels, amends and cancel alls always go through.
+pub struct RateLimitedVenue {
+    inner: DynamicVenue,
+    max_places_per_minute: usize,
+    clock: SharedClock,
+    /// Where the rejections of dropped places are sent, once reports are streaming.
+    reports: OnceLock<ReportSender>,
+    window: Mutex<PlaceWindow>,
+}
+
+#[derive(Debug, Default)]
+struct PlaceWindow {
+    /// When each place still in the window was passed on, oldest first.
+    placed: VecDeque<Instant>,
+    /// Whether places have been dropped since the last one passed, so the venue error
+    /// is sent once per breach.
+    limited: bool,
+}
+
+impl RateLimitedVenue {
+    pub fn new(inner: DynamicVenue, max_places_per_minute: u32, clock: SharedClock) -> Self {
+        Self {
+            inner,
+            max_places_per_minute: max_places_per_minute as usize,
+            clock,
+            reports: OnceLock::new(),
+            window: Mutex::new(PlaceWindow::default()),
+        }
+    }
+
+    /// Splits `actions` into those to pass on and the places to drop, counting the
+    /// places passed on. Also returns whether these drops start a breach.
+    fn admit(&self, actions: &[OrderAction]) -> (Vec<OrderAction>, Vec<OrderAction>, bool) {
+        let now = self.clock.now_instant();
+        let mut window = self.window.lock().unwrap();
+        while window
+            .placed
+            .front()
+            .is_some_and(|placed| now.saturating_duration_since(*placed) >= WINDOW)
+        {
+            window.placed.pop_front();
+        }
+
+        let (mut allowed, mut dropped) = (Vec::new(), Vec::new());
+        for action in actions {
+            match action {
+                OrderAction::Place(_) if window.placed.len() >= self.max_places_per_minute => {
+                    dropped.push(action.clone());
+                }
+                OrderAction::Place(_) => {
+                    window.placed.push_back(now);
+                    allowed.push(action.clone());
+                }
+                _ => allowed.push(action.clone()),
+            }
+        }
+
+        let newly_limited = !dropped.is_empty() && !window.limited;
+        if !dropped.is_empty() {
+            window.limited = true;
+        } else if allowed
+            .iter()
+            .any(|action| matches!(action, OrderAction::Place(_)))
+        {
+            window.limited = false;
+        }
+        (allowed, dropped, newly_limited)
+    }
+
+    fn reject(&self, dropped: &[OrderAction], newly_limited: bool) {
+        let message = format!(
+            "order rate limit: {} places in the last minute; dropping further places",
+            self.max_places_per_minute
+        );
+        error!(
+            dropped = dropped.len(),
+            limit = self.max_places_per_minute,
+            "{message}"
+        );
+
+        let Some(reports) = self.reports.get() else {
+            return;
+        };
+        if newly_limited {
+            let _ = reports.send(OrderReport::VenueError {
+                message: message.clone(),
+            });
+        }
+        for action in dropped {
+            if let OrderAction::Place(order) = action {
+                let _ = reports.send(OrderReport::Rejected {
+                    order_id: order.order_id.clone(),
+                    instrument: order.instrument.clone(),
+                    side: order.side,
+                    kind: RejectKind::RateLimited,
+                    reason: message.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionVenue for RateLimitedVenue {
+    async fn execute(&self, actions: &[OrderAction]) -> Result<()> {
+        let (allowed, dropped, newly_limited) = self.admit(actions);
+        if !dropped.is_empty() {
+            self.reject(&dropped, newly_limited);
+        }
+
+        if allowed.is_empty() {
+            return Ok(());
+        }
+        self.inner.execute(&allowed).await
+    }
+
+    async fn open_orders(&self, instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        self.inner.open_orders(instrument).await
+    }
+
+    async fn spawn_reports(&self, on_report: ReportSender, supervisor: &Supervisor) -> Result<()> {
+        let _ = self.reports.set(on_report.clone());
+        self.inner.spawn_reports(on_report, supervisor).await
+    }
+
+    fn trade_checkpoint(&self) -> Option<TradeCheckpoint> {
+        self.inner.trade_checkpoint()
+    }
+
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        self.inner.spawn_inventory(instrument, supervisor).await
+    }
+
+    fn on_market_event(&self, event: &MarketEvent) {
+        self.inner.on_market_event(event);
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::execution::order_report::OrderReport;
+use crate::types::quantity::Quantity;
+use crate::types::trading_rules::qty_eq;
+
+/// Finished orders remembered, oldest forgotten first, so their replays are still known.
+const MAX_FINISHED_ORDERS: usize = 1_024;
+
+/// What a venue's report stream has said about one order so far.
+#[derive(Debug, Clone, Default)]
+struct Seen {
+    accepted: bool,
+    cum_quantity: Quantity,
+    finished: bool,
+}
+
+/// Sits between a venue's report stream and the engine, passing each report on at most
+/// once: a repeated accept, a fill that does not advance the cumulative quantity, and
+/// anything after an order finished are dropped. Snapshots pass only when they tell
+/// the engine something new, so a reconnect's replay cannot resurrect a finished order
+/// or count a fill twice.
+#[derive(Debug, Default)]
+pub struct ReportSequencer {
+    orders: HashMap<String, Seen>,
+    finished: VecDeque<String>,
+}
+
+impl ReportSequencer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `report`, unless the stream already said as much.
+    pub fn admit(&mut self, report: OrderReport) -> Option<OrderReport> {
+        let Some(order_id) = report.order_id() else {
+            return Some(report);
+        };
+        let step = report
+            .instrument()
+            .map(|instrument| instrument.trading_rules().quantity_step)
+            .unwrap_or_default();
+        let seen = self.orders.entry(order_id.to_string()).or_default();
+        let advances = |cum_quantity: Quantity| {
+            cum_quantity > seen.cum_quantity && !qty_eq(cum_quantity, seen.cum_quantity, step)
+        };
+
+        let admitted = match &report {
+            _ if seen.finished => false,
+            OrderReport::Accepted { .. } => !seen.accepted,
+            OrderReport::PartiallyFilled { cum_quantity, .. } => advances(*cum_quantity),
+            OrderReport::Snapshot { cum_quantity, .. } => !seen.accepted || advances(*cum_quantity),
+            _ => true,
+        };
+        if !admitted {
+            tracing::debug!(
+                order_id,
+                kind = report.kind(),
+                cum_quantity = seen.cum_quantity.as_f64(),
+                finished = seen.finished,
+                "dropping a report the stream already gave"
+            );
+            return None;
+        }
+
+        match &report {
+            OrderReport::Accepted { .. } => seen.accepted = true,
+            OrderReport::PartiallyFilled { cum_quantity, .. }
+            | OrderReport::Snapshot { cum_quantity, .. } => {
+                seen.accepted = true;
+                seen.cum_quantity = *cum_quantity;
+            }
+            OrderReport::Filled { cum_quantity, .. } => {
+                seen.cum_quantity = *cum_quantity;
+                seen.finished = true;
+            }
+            OrderReport::Cancelled { .. } | OrderReport::Rejected { .. } => seen.finished = true,
+            _ => {}
+        }
+        if seen.finished {
+            self.finish(order_id.to_string());
+        }
+
+        Some(report)
+    }
+
+    fn finish(&mut self, order_id: String) {
+        self.finished.push_back(order_id);
+        if self.finished.len() > MAX_FINISHED_ORDERS
+            && let Some(oldest) = self.finished.pop_front()
+        {
+            self.orders.remove(&oldest);
+        }
+    }
+}
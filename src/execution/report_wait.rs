@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Instant;
+
+use crate::execution::order_report::OrderReport;
+
+/// What came back while waiting on the venue.
+#[derive(Debug, Clone, Default)]
+pub struct AwaitedReports {
+    /// Every report received, oldest first.
+    pub reports: Vec<OrderReport>,
+    /// Whether the wait ended on a report it was waiting for, rather than the timeout or
+    /// the channel closing.
+    pub done: bool,
+}
+
+impl AwaitedReports {
+    /// The report the wait ended on, if it ended on one.
+    pub fn last(&self) -> Option<&OrderReport> {
+        self.reports.last().filter(|_| self.done)
+    }
+}
+
+/// Receives reports until `done` returns true for one, `timeout` passes or the channel
+/// closes. Reports lost to lagging are skipped.
+pub async fn await_reports(
+    receiver: &mut broadcast::Receiver<OrderReport>,
+    timeout: Duration,
+    mut done: impl FnMut(&OrderReport) -> bool,
+) -> AwaitedReports {
+    let deadline = Instant::now() + timeout;
+    let mut awaited = AwaitedReports::default();
+
+    loop {
+        match tokio::time::timeout_at(deadline, receiver.recv()).await {
+            Ok(Ok(report)) => {
+                let finished = done(&report);
+                awaited.reports.push(report);
+                if finished {
+                    awaited.done = true;
+                    return awaited;
+                }
+            }
+            Ok(Err(RecvError::Lagged(_))) => {}
+            Ok(Err(RecvError::Closed)) | Err(_) => return awaited,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::execution::order_action::Side;
+use crate::types::price::Price;
+use crate::types::quote::Quote;
+use crate::types::trading_rules::qty_eq;
+
+/// Answers the venue refusing a post-only place for crossing the book, as it does when
+/// the book moved on while the place was on its way. Rather than sending the same
+/// doomed price on the next market event, the side re-plans straight away, a tick
+/// behind the touch for each refusal so far. After `max_attempts` reprices of one
+/// target it gives up, and places nothing until the strategy asks for something else.
+#[derive(Debug, Clone)]
+pub struct RepriceOnReject {
+    max_attempts: u32,
+    /// The strategy's quote, as it was when it last moved.
+    target: Option<Quote>,
+    /// Places for `target` refused for crossing.
+    crossed: u32,
+    /// A refusal the next plan has yet to answer.
+    pending: bool,
+}
+
+impl RepriceOnReject {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            target: None,
+            crossed: 0,
+            pending: false,
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub fn set_max_attempts(&mut self, max_attempts: u32) {
+        self.max_attempts = max_attempts;
+    }
+
+    /// Follows the strategy's quote. One that moved by a tick, or by a quantity step,
+    /// is a new target and starts over.
+    pub fn on_target(&mut self, target: Option<Quote>, price_tick: f64, quantity_step: f64) {
+        let unchanged = match (self.target, target) {
+            (Some(current), Some(target)) => {
+                (current.price.as_f64() - target.price.as_f64()).abs() < price_tick / 2.0
+                    && qty_eq(current.quantity, target.quantity, quantity_step)
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return;
+        }
+
+        self.target = target;
+        self.crossed = 0;
+        self.pending = false;
+    }
+
+    /// The venue refused a place for the current target for crossing the book. Ignored
+    /// while repricing is off.
+    pub fn on_crossed(&mut self) {
+        if self.max_attempts == 0 {
+            return;
+        }
+        self.crossed += 1;
+        self.pending = !self.gave_up();
+        if self.gave_up() {
+            tracing::warn!(
+                target = ?self.target,
+                attempts = self.max_attempts,
+                "post-only place still crossing after repricing; holding until the target moves"
+            );
+        }
+    }
+
+    /// Whether a refusal is waiting to be answered by a re-plan.
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// The re-plan answering the last refusal has been made.
+    pub fn on_planned(&mut self) {
+        self.pending = false;
+    }
+
+    pub fn gave_up(&self) -> bool {
+        self.crossed > self.max_attempts
+    }
+
+    /// What to place for `desired` on `side`: `desired` itself until a place for it
+    /// crosses, then as many ticks behind the touch as places have crossed. The touch is
+    /// `touch`, the best price on the other side as last seen, or `desired` when that is
+    /// nearer: a refused place shows the venue's book had already reached it. `None`
+    /// once the attempts are spent.
+    pub fn quote(
+        &self,
+        side: Side,
+        desired: Quote,
+        touch: Option<Price>,
+        price_tick: f64,
+    ) -> Option<Quote> {
+        if self.crossed == 0 {
+            return Some(desired);
+        }
+        if self.gave_up() {
+            return None;
+        }
+
+        let desired_price = desired.price.as_f64();
+        let away = f64::from(self.crossed) * price_tick;
+        let price = match (side, touch.map(Price::as_f64)) {
+            (Side::Buy, Some(touch)) => touch.min(desired_price) - away,
+            (Side::Sell, Some(touch)) => touch.max(desired_price) + away,
+            (Side::Buy, None) => desired_price - away,
+            (Side::Sell, None) => desired_price + away,
+        };
+
+        Some(Quote {
+            price: Price::new((price / price_tick).round() * price_tick),
+            ..desired
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// The last venue trade id passed on to the engine per pair, as the venue names it. Saved
+/// with the engine state, so after a restart the trades a venue replays are told apart
+/// from the ones it made while the engine was down. Clones share the same ids.
+#[derive(Debug, Clone, Default)]
+pub struct TradeCheckpoint {
+    last: Arc<Mutex<BTreeMap<String, u64>>>,
+}
+
+impl TradeCheckpoint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Picks up from the ids a previous run saved.
+    pub fn restore(&self, saved: BTreeMap<String, u64>) {
+        *self.last.lock().unwrap() = saved;
+    }
+
+    /// The ids as they stand, for the state store.
+    pub fn saved(&self) -> BTreeMap<String, u64> {
+        self.last.lock().unwrap().clone()
+    }
+
+    /// The last trade id on `symbol`; `None` before any was seen or restored.
+    pub fn last(&self, symbol: &str) -> Option<u64> {
+        self.last.lock().unwrap().get(symbol).copied()
+    }
+
+    /// Records trade `trade_id` on `symbol`. Returns whether it is past the checkpoint,
+    /// i.e. not yet passed on.
+    pub fn advance(&self, symbol: &str, trade_id: u64) -> bool {
+        let mut last = self.last.lock().unwrap();
+        match last.get(symbol) {
+            Some(&seen) if seen >= trade_id => false,
+            _ => {
+                last.insert(symbol.to_string(), trade_id);
+                true
+            }
+        }
+    }
+}
@@ -1,4 +1,10 @@
-use crate::types::quote::Quote;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::execution::order_action::{Order, Side};
+use crate::types::{instrument::Instrument, price::Price, quantity::Quantity, quote::Quote};
 
 #[derive(Debug, Clone)]
 pub enum SidePlan {
@@ -11,22 +17,183 @@ pub enum SidePlan {
     Cancel {
         order_id: String,
     },
+    /// Gives up on an order whose place or cancel the venue never answered: cancels it in
+    /// case it rests after all and forgets it.
+    Abandon {
+        order_id: String,
+    },
     Replace {
         old_order_id: String,
         new_order_id: String,
         desired: Quote,
     },
+    /// Moves the live order to `desired` in place, keeping its id and whatever filled.
+    Amend {
+        order_id: String,
+        desired: Quote,
+    },
 }
 
-#[derive(Debug, Clone)]
+impl SidePlan {
+    /// This plan with new orders held back: a replace only cancels, and a place or
+    /// amend waits.
+    pub fn cancels_only(self) -> Self {
+        match self {
+            SidePlan::Place { .. } | SidePlan::Amend { .. } => SidePlan::NoAction,
+            SidePlan::Replace { old_order_id, .. } => SidePlan::Cancel {
+                order_id: old_order_id,
+            },
+            plan => plan,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
 pub enum OrderSideState {
+    #[default]
     NoOrder,
-    Placing { order_id: String, requested: Quote },
-    Live { order_id: String, resting: Quote },
-    Cancelling { order_id: String, resting: Quote },
+    Placing {
+        order_id: String,
+        requested: Quote,
+    },
+    /// `resting` holds what is left of the order: its placed quantity less `filled`,
+    /// the venue's cumulative fill.
+    Live {
+        order_id: String,
+        resting: Quote,
+        filled: Quantity,
+    },
+    Cancelling {
+        order_id: String,
+        resting: Quote,
+        filled: Quantity,
+    },
+    /// A live order waiting on the venue to move it to `requested`; `resting` is what it
+    /// was before.
+    Amending {
+        order_id: String,
+        resting: Quote,
+        filled: Quantity,
+        requested: Quote,
+    },
+}
+
+impl OrderSideState {
+    pub fn order_id(&self) -> Option<&str> {
+        match self {
+            OrderSideState::NoOrder => None,
+            OrderSideState::Placing { order_id, .. }
+            | OrderSideState::Live { order_id, .. }
+            | OrderSideState::Cancelling { order_id, .. }
+            | OrderSideState::Amending { order_id, .. } => Some(order_id),
+        }
+    }
+
+    /// Stable, low-cardinality name of the state, as serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OrderSideState::NoOrder => "no_order",
+            OrderSideState::Placing { .. } => "placing",
+            OrderSideState::Live { .. } => "live",
+            OrderSideState::Cancelling { .. } => "cancelling",
+            OrderSideState::Amending { .. } => "amending",
+        }
+    }
+
+    /// What may still fill: the requested quote while placing, otherwise what rests.
+    pub fn working_quote(&self) -> Option<Quote> {
+        match self {
+            OrderSideState::NoOrder => None,
+            OrderSideState::Placing { requested, .. } => Some(*requested),
+            OrderSideState::Live { resting, .. }
+            | OrderSideState::Cancelling { resting, .. }
+            | OrderSideState::Amending { resting, .. } => Some(*resting),
+        }
+    }
+}
+
+/// What one level of one side holds, as the engine believes it.
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderSideSnapshot {
+    pub state: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<Quote>,
+    /// Since the order was accepted, last filled or moved; while placing, since it was
+    /// sent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_ms: Option<f64>,
 }
 
+/// Every level of both sides, level 0 first, for risk checks, logs, the decision log
+/// and the session summary.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OrderManagerSnapshot {
+    pub bids: Vec<OrderSideSnapshot>,
+    pub asks: Vec<OrderSideSnapshot>,
+}
+
+impl OrderManagerSnapshot {
+    pub fn levels(&self, side: Side) -> &[OrderSideSnapshot] {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+}
+
+/// One level per entry, e.g. `bid live 0.05@93.00 1200ms, ask placing 0.05@93.10 40ms`.
+impl fmt::Display for OrderManagerSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let levels = self
+            .bids
+            .iter()
+            .map(|level| ("bid", level))
+            .chain(self.asks.iter().map(|level| ("ask", level)));
+        for (index, (side, level)) in levels.enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{side} {}", level.state)?;
+            if let Some(quote) = level.quote {
+                write!(f, " {}@{}", quote.quantity, quote.price)?;
+            }
+            if let Some(age_ms) = level.age_ms {
+                write!(f, " {age_ms:.0}ms")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An order resting on the venue, as its open orders list reports it.
 #[derive(Debug, Clone)]
 pub struct OpenOrder {
     pub order_id: String,
+    pub instrument: Instrument,
+    pub side: Side,
+    pub price: Price,
+    /// Quantity still resting.
+    pub remaining: Quantity,
+    /// Quantity filled so far.
+    pub filled: Quantity,
+    /// When the venue took the order, where it says.
+    pub opened_at: Option<DateTime<Utc>>,
+}
+
+impl OpenOrder {
+    /// `order` with `filled` of it already executed.
+    pub fn resting(order: &Order, filled: Quantity) -> Self {
+        Self {
+            order_id: order.order_id.clone(),
+            instrument: order.instrument.clone(),
+            side: order.side,
+            price: order.price,
+            remaining: order.quantity.saturating_sub(filled),
+            filled,
+            opened_at: None,
+        }
+    }
 }
@@ -1,3 +1,6 @@
+pub mod readiness;
+pub mod simulated;
+
 use async_trait::async_trait;
 use tokio::sync::watch;
 
@@ -7,4 +10,10 @@ use crate::types::inventory::Inventory;
 pub trait InventorySource: Send + Sync {
     /// Returns a receiver that always holds the latest inventory snapshot.
     fn subscribe(&self) -> watch::Receiver<Inventory>;
+
+    /// Whether the receiver holds balances from the venue yet, rather than the zeros it
+    /// starts with. Sources seeded with known balances are always ready.
+    fn is_ready(&self) -> bool {
+        true
+    }
 }
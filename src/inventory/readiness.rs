@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::app_config::ensure;
+use crate::inventory::InventorySource;
+use crate::scenario::venues::VenueKind;
+use crate::types::instrument::Instrument;
+use crate::types::inventory::Inventory;
+
+/// How long the engine waits for a venue's first balances before it quotes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InventoryConfig {
+    pub ready_timeout_secs: u64,
+    /// Starting balances of the dry-run's paper inventory, which then moves with its fills.
+    pub paper_base: f64,
+    pub paper_quote: f64,
+}
+
+impl Default for InventoryConfig {
+    fn default() -> Self {
+        Self {
+            ready_timeout_secs: 15,
+            paper_base: 0.0,
+            paper_quote: 0.0,
+        }
+    }
+}
+
+impl InventoryConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.ready_timeout_secs > 0,
+            format!("{path}.ready_timeout_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.paper_base.is_finite() && self.paper_base >= 0.0,
+            format!("{path}.paper_base"),
+            "must be >= 0",
+        )?;
+        ensure(
+            self.paper_quote.is_finite() && self.paper_quote >= 0.0,
+            format!("{path}.paper_quote"),
+            "must be >= 0",
+        )
+    }
+
+    pub fn ready_timeout(&self) -> Duration {
+        Duration::from_secs(self.ready_timeout_secs)
+    }
+
+    pub fn paper(&self) -> Inventory {
+        Inventory::new(self.paper_base, self.paper_quote)
+    }
+}
+
+/// Waits up to `timeout` for `source`'s first snapshot and logs it.
+///
+/// Until then the source reads as zero balances, which skews every quote and fails the
+/// funds check. A live venue therefore refuses to start without one; a dry-run gets the
+/// `paper` balances back, to quote from until the venue's arrive.
+pub async fn await_first_snapshot(
+    source: &dyn InventorySource,
+    instrument: &Instrument,
+    venue: VenueKind,
+    timeout: Duration,
+    paper: Inventory,
+) -> Result<Option<Inventory>> {
+    // Subscribed before the first check, so a snapshot landing in between still wakes us.
+    let mut receiver = source.subscribe();
+    let started = Instant::now();
+
+    let ready = tokio::time::timeout(timeout, async {
+        while !source.is_ready() {
+            if receiver.changed().await.is_err() {
+                return false;
+            }
+        }
+        true
+    })
+    .await
+    .unwrap_or(false);
+
+    if ready {
+        let inventory = *receiver.borrow();
+        info!(
+            %instrument,
+            base = inventory.base,
+            quote = inventory.quote,
+            waited_ms = started.elapsed().as_millis() as u64,
+            "first inventory snapshot"
+        );
+        return Ok(None);
+    }
+
+    match venue {
+        VenueKind::Kraken => bail!(
+            "no inventory snapshot for {instrument} within {timeout:?}; not trading on zero balances"
+        ),
+        VenueKind::DryRun => {
+            warn!(
+                %instrument,
+                base = paper.base,
+                quote = paper.quote,
+                ?timeout,
+                "no inventory snapshot yet; quoting from paper balances until one arrives"
+            );
+            Ok(Some(paper))
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, broadcast, watch};
+use tracing::warn;
+
+use crate::engine::supervisor::{RestartPolicy, Supervisor};
+use crate::execution::order_report::OrderReport;
+use crate::inventory::InventorySource;
+use crate::types::instrument::Instrument;
+use crate::types::inventory::Inventory;
+
+/// Paper balances for one instrument: start from configured amounts and move with the
+/// fills reported for it, at the fill price and without fees.
+#[derive(Clone)]
+pub struct SimulatedInventory {
+    instrument: Instrument,
+    tx: watch::Sender<Inventory>,
+}
+
+impl SimulatedInventory {
+    pub fn new(instrument: &Instrument, initial: Inventory) -> Self {
+        Self {
+            instrument: instrument.clone(),
+            tx: watch::Sender::new(initial),
+        }
+    }
+
+    /// Follows `reports` from now on. The receiver is taken before this returns, so no
+    /// fill reported afterwards is missed while the task starts.
+    pub fn spawn(
+        instrument: &Instrument,
+        initial: Inventory,
+        reports: &broadcast::Sender<OrderReport>,
+        supervisor: &Supervisor,
+    ) -> Self {
+        let inventory = Self::new(instrument, initial);
+        let follower = inventory.clone();
+        let receiver = Arc::new(Mutex::new(reports.subscribe()));
+
+        supervisor.spawn(
+            format!("inventory {instrument}"),
+            RestartPolicy::restart(),
+            move || {
+                let follower = follower.clone();
+                let receiver = receiver.clone();
+
+                async move {
+                    let mut receiver = receiver.lock().await;
+                    loop {
+                        match receiver.recv().await {
+                            Ok(report) => follower.on_report(&report),
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                warn!(
+                                    instrument = %follower.instrument,
+                                    skipped,
+                                    "paper inventory missed order reports; balances are off"
+                                );
+                            }
+                            Err(broadcast::error::RecvError::Closed) => return,
+                        }
+                    }
+                }
+            },
+        );
+
+        inventory
+    }
+
+    /// Applies a fill of this instrument; every other report is ignored.
+    pub fn on_report(&self, report: &OrderReport) {
+        let (OrderReport::PartiallyFilled {
+            instrument,
+            side,
+            price,
+            quantity,
+            ..
+        }
+        | OrderReport::Filled {
+            instrument,
+            side,
+            price,
+            quantity,
+            ..
+        }) = report
+        else {
+            return;
+        };
+        if *instrument != self.instrument {
+            return;
+        }
+
+        self.tx.send_modify(|inventory| {
+            inventory.base += side.signed(quantity.as_f64());
+            inventory.quote -= side.signed(quantity.as_f64()) * price.as_f64();
+        });
+    }
+}
+
+impl InventorySource for SimulatedInventory {
+    fn subscribe(&self) -> watch::Receiver<Inventory> {
+        self.tx.subscribe()
+    }
+}
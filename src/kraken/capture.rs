@@ -0,0 +1,358 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::app_config::ensure;
+use crate::telemetry::cycles;
+
+const REDACTED: &str = "<redacted>";
+/// Headers whose values are never captured.
+const SECRET_HEADERS: &[&str] = &["api-key", "api-sign"];
+/// JSON keys and form fields whose values are never captured.
+const SECRET_FIELDS: &[&str] = &["token"];
+
+/// Process-wide, like the metrics recorder, so the REST client and every websocket
+/// reader capture into one buffer without it being threaded through the venues.
+static CAPTURE: OnceLock<Capture> = OnceLock::new();
+
+/// Debug capture of the raw payloads exchanged with Kraken.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CaptureConfig {
+    pub enabled: bool,
+    /// Latest payloads kept in memory, served from `GET /captures` on the admin API.
+    pub capacity: usize,
+    /// Also append every payload to this file, as JSON lines.
+    pub path: Option<PathBuf>,
+    /// Size at which the capture file is moved to `<path>.1`, replacing the previous one,
+    /// and a new one started.
+    pub max_file_bytes: u64,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: 1_000,
+            path: None,
+            max_file_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl CaptureConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(self.capacity > 0, format!("{path}.capacity"), "must be > 0")?;
+        ensure(
+            self.max_file_bytes > 0,
+            format!("{path}.max_file_bytes"),
+            "must be > 0",
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+/// One payload as it went over the wire, with secrets redacted.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CaptureEntry {
+    pub at: DateTime<Utc>,
+    pub direction: Direction,
+    /// REST path, e.g. `/0/private/AddOrder`, or websocket channel, e.g. `executions`.
+    pub endpoint: String,
+    /// Engine cycle the payload was sent from; `None` outside a cycle and for
+    /// websocket messages.
+    pub cycle_id: Option<u64>,
+    /// Request headers, for REST requests.
+    pub headers: Vec<(String, String)>,
+    pub payload: String,
+}
+
+/// A bounded buffer of captured payloads, optionally mirrored to a size-capped file.
+/// Every header, payload and known secret is redacted before it is stored.
+#[derive(Debug)]
+pub struct Capture {
+    inner: Mutex<CaptureState>,
+}
+
+#[derive(Debug)]
+struct CaptureState {
+    capacity: usize,
+    entries: VecDeque<CaptureEntry>,
+    file: Option<CaptureFile>,
+    /// Literal values scrubbed from every payload: credentials and websocket tokens.
+    secrets: Vec<String>,
+}
+
+#[derive(Debug)]
+struct CaptureFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl Capture {
+    pub fn new(config: &CaptureConfig) -> Result<Self> {
+        let file = config
+            .path
+            .as_deref()
+            .map(|path| CaptureFile::open(path, config.max_file_bytes))
+            .transpose()?;
+
+        Ok(Self {
+            inner: Mutex::new(CaptureState {
+                capacity: config.capacity,
+                entries: VecDeque::with_capacity(config.capacity),
+                file,
+                secrets: Vec::new(),
+            }),
+        })
+    }
+
+    /// Never lets `secret` into a capture, wherever it appears.
+    pub fn add_secret(&self, secret: &str) {
+        let mut state = self.inner.lock().unwrap();
+        if !secret.is_empty() && !state.secrets.iter().any(|known| known == secret) {
+            state.secrets.push(secret.to_string());
+        }
+    }
+
+    pub fn record<'a>(
+        &self,
+        direction: Direction,
+        endpoint: &str,
+        headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+        payload: &str,
+    ) {
+        let mut state = self.inner.lock().unwrap();
+
+        let headers = headers
+            .into_iter()
+            .map(|(name, value)| {
+                let value = if SECRET_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                    REDACTED.to_string()
+                } else {
+                    scrub(value, &state.secrets)
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+
+        let entry = CaptureEntry {
+            at: Utc::now(),
+            direction,
+            endpoint: endpoint.to_string(),
+            cycle_id: cycles::current(),
+            headers,
+            payload: scrub(&redact_fields(payload), &state.secrets),
+        };
+
+        if let Some(file) = &mut state.file
+            && let Err(error) = file.append(&entry)
+        {
+            warn!(path = %file.path.display(), "failed to write capture: {error:?}");
+        }
+
+        if state.entries.len() == state.capacity {
+            state.entries.pop_front();
+        }
+        state.entries.push_back(entry);
+    }
+
+    /// Up to `limit` of the latest entries, most recent first.
+    pub fn recent(&self, limit: usize) -> Vec<CaptureEntry> {
+        let state = self.inner.lock().unwrap();
+        state.entries.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+impl CaptureFile {
+    fn open(path: &Path, max_bytes: u64) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create capture dir {}", dir.display()))?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open capture file {}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            written: file.metadata()?.len(),
+            file,
+            max_bytes,
+        })
+    }
+
+    fn append(&mut self, entry: &CaptureEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            let mut rotated = self.path.clone().into_os_string();
+            rotated.push(".1");
+            fs::rename(&self.path, &rotated)?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            self.written = 0;
+        }
+
+        self.file.write_all(line.as_bytes())?;
+        self.written += line.len() as u64;
+
+        Ok(())
+    }
+}
+
+/// Replaces the values of [`SECRET_FIELDS`] in a JSON or form-encoded payload, leaving
+/// every other byte as it was.
+pub fn redact_fields(payload: &str) -> String {
+    let mut redacted = payload.to_string();
+    for field in SECRET_FIELDS {
+        redacted = redact_json_field(&redacted, field);
+        redacted = redact_form_field(&redacted, field);
+    }
+
+    redacted
+}
+
+/// Replaces every occurrence of any of `secrets` in `text`.
+pub fn scrub(text: &str, secrets: &[String]) -> String {
+    secrets.iter().fold(text.to_string(), |text, secret| {
+        text.replace(secret, REDACTED)
+    })
+}
+
+/// `"field": "value"` becomes `"field": "<redacted>"`, for string values.
+fn redact_json_field(payload: &str, field: &str) -> String {
+    let key = format!("\"{field}\"");
+    let mut redacted = String::with_capacity(payload.len());
+    let mut rest = payload;
+
+    while let Some(found) = rest.find(&key) {
+        let after_key = found + key.len();
+        redacted.push_str(&rest[..after_key]);
+        rest = &rest[after_key..];
+
+        let value_start = rest.len() - rest.trim_start().len();
+        let Some(after_colon) = rest[value_start..].strip_prefix(':') else {
+            continue;
+        };
+        let quote = after_colon.len() - after_colon.trim_start().len();
+        let Some(value) = after_colon[quote..].strip_prefix('"') else {
+            continue;
+        };
+        let Some(end) = closing_quote(value) else {
+            continue;
+        };
+
+        let prefix_len = rest.len() - value.len();
+        redacted.push_str(&rest[..prefix_len]);
+        redacted.push_str(REDACTED);
+        rest = &value[end..];
+    }
+
+    redacted.push_str(rest);
+    redacted
+}
+
+/// Index of the quote closing a JSON string whose contents start `value`.
+fn closing_quote(value: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, character) in value.char_indices() {
+        match character {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return Some(index),
+            _ => escaped = false,
+        }
+    }
+
+    None
+}
+
+/// `field=value` becomes `field=<redacted>` in an `a=1&b=2` payload.
+fn redact_form_field(payload: &str, field: &str) -> String {
+    if payload.contains(char::is_whitespace) || !payload.contains('=') {
+        return payload.to_string();
+    }
+
+    payload
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if name == field => format!("{name}={REDACTED}"),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Starts capturing. Until this is called every capture function below is a no-op that
+/// formats and copies nothing, so payloads cost nothing when capture is off.
+pub fn install(config: &CaptureConfig) -> Result<()> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    if CAPTURE.set(Capture::new(config)?).is_err() {
+        warn!("venue capture already installed");
+        return Ok(());
+    }
+
+    info!(
+        capacity = config.capacity,
+        path = ?config.path,
+        "capturing raw venue payloads"
+    );
+
+    Ok(())
+}
+
+pub fn is_enabled() -> bool {
+    CAPTURE.get().is_some()
+}
+
+/// Keeps a credential or token out of every later capture.
+pub fn secret(value: &str) {
+    if let Some(capture) = CAPTURE.get() {
+        capture.add_secret(value);
+    }
+}
+
+pub fn sent<'a>(
+    endpoint: &str,
+    headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+    payload: &str,
+) {
+    if let Some(capture) = CAPTURE.get() {
+        capture.record(Direction::Sent, endpoint, headers, payload);
+    }
+}
+
+pub fn received(endpoint: &str, payload: &str) {
+    if let Some(capture) = CAPTURE.get() {
+        capture.record(Direction::Received, endpoint, [], payload);
+    }
+}
+
+/// Up to `limit` of the latest captures, most recent first; `None` when capture is off.
+pub fn recent(limit: usize) -> Option<Vec<CaptureEntry>> {
+    CAPTURE.get().map(|capture| capture.recent(limit))
+}
@@ -0,0 +1,206 @@
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+use crate::events::{BookLevel, MarketEvent};
+use crate::types::{instrument::Instrument, price::Price};
+
+/// Levels of each side Kraken's book checksum covers.
+const CHECKSUM_LEVELS: usize = 10;
+
+/// A level as Kraken sent it. The checksum is over the decimal strings, so they are kept.
+#[derive(Debug, Clone)]
+struct Level {
+    price: f64,
+    price_text: String,
+    volume_text: String,
+}
+
+/// The ladder of a Kraken `book` subscription, kept to turn its frames into
+/// [`MarketEvent::BookUpdate`]s and to check them against Kraken's checksum.
+#[derive(Debug, Clone)]
+pub struct KrakenBook {
+    depth: usize,
+    /// Best first.
+    bids: Vec<Level>,
+    asks: Vec<Level>,
+}
+
+impl KrakenBook {
+    /// A book for a subscription of `depth` levels a side.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            bids: Vec::new(),
+            asks: Vec::new(),
+        }
+    }
+
+    /// Applies the payloads of one `book` frame: a snapshot (`as`/`bs`), or updates
+    /// (`a`/`b`) with a checksum (`c`). Levels pushed out of the subscribed depth are
+    /// removed, as Kraken does not send those removals.
+    ///
+    /// Fails when the book no longer matches the checksum, after a missed or misapplied
+    /// update; resubscribing starts again from a snapshot.
+    pub fn apply(&mut self, instrument: &Instrument, payloads: &[Value]) -> Result<MarketEvent> {
+        let snapshot = payloads
+            .iter()
+            .any(|payload| payload.get("as").is_some() || payload.get("bs").is_some());
+        if snapshot {
+            self.bids.clear();
+            self.asks.clear();
+        }
+
+        let mut bids = Vec::new();
+        let mut asks = Vec::new();
+        let mut checksum = None;
+        let mut timestamp_ms = 0;
+        for payload in payloads {
+            let Some(fields) = payload.as_object() else {
+                bail!("book payload is not an object: {payload}");
+            };
+
+            for (key, value) in fields {
+                let (ladder, changed, bid) = match key.as_str() {
+                    "b" | "bs" => (&mut self.bids, &mut bids, true),
+                    "a" | "as" => (&mut self.asks, &mut asks, false),
+                    "c" => {
+                        checksum = value.as_str().map(str::to_string);
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                for entry in value.as_array().into_iter().flatten() {
+                    let (level, level_ms) =
+                        parse_level(entry).with_context(|| format!("book level {entry}"))?;
+                    timestamp_ms = timestamp_ms.max(level_ms);
+                    changed.push(BookLevel {
+                        price: Price::new(level.price),
+                        quantity: level.volume_text.parse()?,
+                    });
+                    set_level(ladder, bid, level);
+                }
+                for removed in ladder.drain(self.depth.min(ladder.len())..) {
+                    changed.push(BookLevel {
+                        price: Price::new(removed.price),
+                        quantity: 0.0,
+                    });
+                }
+            }
+        }
+
+        if let Some(expected) = checksum {
+            let actual = self.checksum();
+            if expected.parse::<u32>().ok() != Some(actual) {
+                bail!(
+                    "{instrument} book checksum mismatch: expected {expected}, computed {actual}"
+                );
+            }
+        }
+
+        Ok(MarketEvent::BookUpdate {
+            instrument: instrument.clone(),
+            bids,
+            asks,
+            snapshot,
+            timestamp_ms,
+        })
+    }
+
+    /// CRC32 of the top asks, best first, then the top bids, each as price then volume
+    /// with the decimal point and leading zeros dropped.
+    fn checksum(&self) -> u32 {
+        let digits = |decimal: &str| decimal.replace('.', "").trim_start_matches('0').to_string();
+
+        let mut text = String::new();
+        for level in self
+            .asks
+            .iter()
+            .take(CHECKSUM_LEVELS)
+            .chain(self.bids.iter().take(CHECKSUM_LEVELS))
+        {
+            text.push_str(&digits(&level.price_text));
+            text.push_str(&digits(&level.volume_text));
+        }
+
+        crc32(text.as_bytes())
+    }
+}
+
+/// `[price, volume, timestamp]`, with a trailing `"r"` on republished levels, and the
+/// timestamp in milliseconds.
+fn parse_level(entry: &Value) -> Result<(Level, u64)> {
+    let field = |index: usize| -> Result<&str> {
+        entry
+            .get(index)
+            .and_then(Value::as_str)
+            .with_context(|| format!("missing field {index}"))
+    };
+
+    let price_text = field(0)?;
+    let price: f64 = price_text.parse()?;
+    if !price.is_finite() || price < 0.0 {
+        bail!("invalid price {price_text}");
+    }
+    let volume_text = field(1)?;
+    let volume: f64 = volume_text.parse()?;
+    if !volume.is_finite() || volume < 0.0 {
+        bail!("invalid volume {volume_text}");
+    }
+    let seconds: f64 = field(2)?.parse()?;
+
+    Ok((
+        Level {
+            price,
+            price_text: price_text.to_string(),
+            volume_text: volume_text.to_string(),
+        },
+        (seconds * 1000.0) as u64,
+    ))
+}
+
+/// Sets `level` in `ladder`, kept best first, or removes it for a zero volume.
+fn set_level(ladder: &mut Vec<Level>, bid: bool, level: Level) {
+    let index = ladder
+        .iter()
+        .position(|existing| {
+            if bid {
+                existing.price <= level.price
+            } else {
+                existing.price >= level.price
+            }
+        })
+        .unwrap_or(ladder.len());
+    let exists = ladder
+        .get(index)
+        .is_some_and(|existing| existing.price == level.price);
+    let removed = level
+        .volume_text
+        .parse::<f64>()
+        .is_ok_and(|volume| volume == 0.0);
+
+    match (exists, removed) {
+        (true, true) => {
+            ladder.remove(index);
+        }
+        (true, false) => ladder[index] = level,
+        (false, false) => ladder.insert(index, level),
+        (false, true) => {}
+    }
+}
+
+/// CRC-32 (IEEE), as Kraken uses for book checksums.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
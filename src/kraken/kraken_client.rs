@@ -1,21 +1,167 @@
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Result, anyhow};
 use base64::{Engine as _, engine::general_purpose};
 use hmac::{Hmac, Mac};
+use reqwest::StatusCode;
 use reqwest::header::{HeaderMap, HeaderValue};
-use serde::Deserialize;
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use url::form_urlencoded;
 
-use crate::execution::order_action::Side;
+use crate::config::app_config::ensure;
+use crate::execution::order_action::{OrderType, Side};
+use crate::execution::order_report::RejectKind;
+use crate::kraken::capture;
 use crate::kraken::kraken_config::KrakenConfig;
-use crate::types::{instrument::Instrument, price::Price};
+use crate::kraken::rate_limit::{RateLimitState, RateLimiter};
+use crate::kraken::symbols::kraken_pair;
+use crate::random::SeededRng;
+use crate::types::{instrument::Instrument, price::Price, quantity::Quantity};
 
 type HmacSha512 = Hmac<Sha512>;
 
+const ADD_ORDER: &str = "/0/private/AddOrder";
+
+/// Kraken errors that say the request was not acted on and may succeed later.
+const TRANSIENT_ERRORS: [&str; 5] = [
+    "EAPI:Rate limit exceeded",
+    "EOrder:Rate limit exceeded",
+    "EGeneral:Temporary lockout",
+    "EService:Unavailable",
+    "EService:Busy",
+];
+
+/// Kraken errors that say why a place was refused, by the start of their code.
+const REJECT_KINDS: [(&str, RejectKind); 4] = [
+    ("EOrder:Post only order", RejectKind::PostOnlyWouldCross),
+    ("EOrder:Insufficient funds", RejectKind::InsufficientFunds),
+    ("EOrder:Rate limit exceeded", RejectKind::RateLimited),
+    ("EAPI:Rate limit exceeded", RejectKind::RateLimited),
+];
+
+/// What the Kraken error `error`, as it answers REST or the order socket, says about a
+/// refused place.
+pub fn reject_kind(error: &str) -> RejectKind {
+    REJECT_KINDS
+        .iter()
+        .find(|(known, _)| error.starts_with(known))
+        .map_or(RejectKind::Other, |(_, kind)| *kind)
+}
+
+/// Retries of transient failures, waiting `initial_backoff_ms` and doubling up to
+/// `max_backoff_ms`, each wait jittered down by up to half.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+    /// Attempts per call, the first included.
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 250,
+            max_backoff_ms: 4_000,
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.max_attempts >= 1,
+            format!("{path}.max_attempts"),
+            "must be >= 1",
+        )?;
+        ensure(
+            self.initial_backoff_ms <= self.max_backoff_ms,
+            format!("{path}.initial_backoff_ms"),
+            "must be <= max_backoff_ms",
+        )
+    }
+
+    /// Wait before the attempt after `attempt`, counting from 1, jittered from `rng`.
+    fn backoff(&self, attempt: u32, rng: &SeededRng) -> Duration {
+        let doubled = self
+            .initial_backoff_ms
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16));
+        let ceiling = doubled.min(self.max_backoff_ms) as f64;
+        Duration::from_millis((ceiling * (0.5 + rng.random_fraction() / 2.0)) as u64)
+    }
+}
+
+/// Why a private call failed, for deciding whether to try it again.
+#[derive(Debug)]
+pub enum RequestError {
+    /// The request failed in transit, possibly after reaching Kraken.
+    Transport(reqwest::Error),
+    Http {
+        status: StatusCode,
+        body: String,
+    },
+    /// Kraken answered with errors.
+    Api(Vec<String>),
+    /// Anything else: signing, or a response that could not be read.
+    Other(anyhow::Error),
+}
+
+impl RequestError {
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Transport(_) => true,
+            Self::Http { status, .. } => status.is_server_error(),
+            Self::Api(errors) => errors.iter().any(|error| {
+                TRANSIENT_ERRORS
+                    .iter()
+                    .any(|known| error.starts_with(known))
+            }),
+            Self::Other(_) => false,
+        }
+    }
+
+    /// Why Kraken refused a place, when it answered at all.
+    pub fn reject_kind(&self) -> RejectKind {
+        match self {
+            Self::Api(errors) => errors
+                .iter()
+                .map(|error| reject_kind(error))
+                .find(|kind| *kind != RejectKind::Other)
+                .unwrap_or(RejectKind::Other),
+            Self::Transport(_) | Self::Http { .. } | Self::Other(_) => RejectKind::Other,
+        }
+    }
+
+    /// Whether Kraken may have acted on the request despite the failure.
+    pub fn is_ambiguous(&self) -> bool {
+        match self {
+            Self::Transport(error) => !error.is_connect(),
+            Self::Http { status, .. } => status.is_server_error(),
+            Self::Api(_) | Self::Other(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(error) => write!(f, "kraken private POST failed: {error}"),
+            Self::Http { status, body } => write!(f, "kraken http error {status}: {body}"),
+            Self::Api(errors) => write!(f, "kraken api error: {errors:?}"),
+            Self::Other(error) => write!(f, "{error:#}"),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
 #[derive(Clone, Debug)]
 pub struct KrakenClient {
     http: reqwest::Client,
@@ -23,46 +169,169 @@ pub struct KrakenClient {
     api_key: String,
     api_secret_b64: String,
     last_nonce: Arc<AtomicU64>,
+    limiter: RateLimiter,
+    retry: RetryConfig,
+    /// Jitters retry backoffs.
+    rng: SeededRng,
 }
 
 impl KrakenClient {
-    pub fn new(config: KrakenConfig) -> Self {
+    /// A client for `config`, jittering retries from `rng`, which should be the
+    /// `kraken_retry` stream of the run's seed.
+    pub fn new(config: KrakenConfig, rng: SeededRng) -> Self {
         Self {
             http: reqwest::Client::new(),
             base_url: "https://api.kraken.com".to_string(),
             api_key: config.api_key,
             api_secret_b64: config.api_secret,
             last_nonce: Arc::new(AtomicU64::new(0)),
+            limiter: RateLimiter::new(&config.rate_limit),
+            retry: config.retry,
+            rng,
         }
     }
 
+    /// Sends requests to `base_url` instead of Kraken, e.g. a local stand-in.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Where the rate limiter has Kraken's counters now.
+    pub fn rate_limits(&self) -> RateLimitState {
+        self.limiter.state()
+    }
+
+    /// Places a limit order of `order_type`: post-only, plain, or immediate-or-cancel.
+    /// The price and volume are written to `instrument`'s tick and step precision.
     pub async fn limit_order(
         &self,
         instrument: &Instrument,
         side: Side,
         price: Price,
-        quantity: f64,
+        quantity: Quantity,
+        order_type: OrderType,
         client_order_id: &str,
     ) -> Result<AddOrderResult> {
-        let uri_path = "/0/private/AddOrder";
-        let pair = instrument_to_kraken_pair(instrument);
+        let uri_path = ADD_ORDER;
+        let pair = kraken_pair(instrument)?;
+        let rules = instrument.trading_rules();
 
         let side_str = match side {
             Side::Buy => "buy",
             Side::Sell => "sell",
         };
 
-        let params = vec![
+        let mut params = vec![
             ("ordertype".to_string(), "limit".to_string()),
             ("type".to_string(), side_str.to_string()),
             ("pair".to_string(), pair),
-            ("price".to_string(), format_price(price.as_f64())),
-            ("volume".to_string(), format_volume(quantity)),
-            ("oflags".to_string(), "post".to_string()),
+            ("price".to_string(), rules.format_price(price)),
+            ("volume".to_string(), rules.format_quantity(quantity)),
+        ];
+        match order_type {
+            OrderType::Limit { post_only: true } => {
+                params.push(("oflags".to_string(), "post".to_string()));
+            }
+            OrderType::Limit { post_only: false } => {}
+            OrderType::ImmediateOrCancel => {
+                params.push(("timeinforce".to_string(), "IOC".to_string()));
+            }
+        }
+        params.push(("cl_ord_id".to_string(), client_order_id.to_string()));
+
+        let error = match self.private_post_form(uri_path, &params).await {
+            Ok(result) => return Ok(result),
+            Err(error) => error,
+        };
+        if !error
+            .downcast_ref::<RequestError>()
+            .is_some_and(RequestError::is_ambiguous)
+        {
+            return Err(error);
+        }
+
+        // Placing again could double the order, so it is looked up instead.
+        tracing::warn!(
+            client_order_id,
+            "add order outcome unknown; looking the order up: {error:#}"
+        );
+        match self.find_order(client_order_id).await {
+            Ok(Some((txid, order))) => {
+                tracing::info!(
+                    client_order_id,
+                    %txid,
+                    status = order.status.as_deref().unwrap_or("unknown"),
+                    filled = %order.vol_exec,
+                    "order was placed despite the failure"
+                );
+                Ok(AddOrderResult {
+                    txid: vec![txid],
+                    descr: AddOrderDescr {
+                        order: format!(
+                            "{} {} {} @ limit {}",
+                            order.descr.side, order.vol, order.descr.pair, order.descr.price
+                        ),
+                    },
+                    status: order.status,
+                })
+            }
+            Ok(None) => Err(anyhow!("{error:#}; the order was not found")),
+            Err(lookup) => Err(anyhow!(
+                "{error:#}; looking the order up failed: {lookup:#}"
+            )),
+        }
+    }
+
+    /// The order placed with `client_order_id`, and its transaction id: open, or else
+    /// already closed, as an order that filled straight away or an immediate-or-cancel
+    /// order always is.
+    pub async fn find_order(
+        &self,
+        client_order_id: &str,
+    ) -> Result<Option<(String, KrakenOpenOrder)>> {
+        let params = vec![("cl_ord_id".to_string(), client_order_id.to_string())];
+        let placed_with_id = |(_, order): &(String, KrakenOpenOrder)| {
+            order.cl_ord_id.as_deref() == Some(client_order_id)
+        };
+
+        let result: OpenOrdersResult = self
+            .private_post_form("/0/private/OpenOrders", &params)
+            .await?;
+        if let Some(open) = result.open.into_iter().find(placed_with_id) {
+            return Ok(Some(open));
+        }
+
+        let result: ClosedOrdersResult = self
+            .private_post_form("/0/private/ClosedOrders", &params)
+            .await?;
+        Ok(result.closed.into_iter().find(placed_with_id))
+    }
+
+    /// Moves the order placed with `client_order_id` to `price`, for `quantity` in all,
+    /// fills included. Post-only, so an amend that would take liquidity is refused.
+    /// Both are written to `instrument`'s precision.
+    pub async fn amend_order(
+        &self,
+        instrument: &Instrument,
+        client_order_id: &str,
+        price: Price,
+        quantity: Quantity,
+    ) -> Result<AmendOrderResult> {
+        let uri_path = "/0/private/AmendOrder";
+        let rules = instrument.trading_rules();
+
+        let params = vec![
             ("cl_ord_id".to_string(), client_order_id.to_string()),
+            ("limit_price".to_string(), rules.format_price(price)),
+            ("order_qty".to_string(), rules.format_quantity(quantity)),
+            ("post_only".to_string(), "true".to_string()),
         ];
 
-        let result: AddOrderResult = self.private_post_form(uri_path, &params).await?;
+        let result: AmendOrderResult = self.private_post_form(uri_path, &params).await?;
+
+        tracing::info!(client_order_id = %client_order_id, amend_id = %result.amend_id, "amend order result");
+
         Ok(result)
     }
 
@@ -87,18 +356,121 @@ impl KrakenClient {
         Ok(result)
     }
 
+    pub async fn open_orders(&self) -> Result<OpenOrdersResult> {
+        let uri_path = "/0/private/OpenOrders";
+
+        let params: Vec<(String, String)> = Vec::new();
+
+        let result: OpenOrdersResult = self.private_post_form(uri_path, &params).await?;
+        Ok(result)
+    }
+
+    /// A token for the authenticated websocket. It must be used within 15 minutes, and
+    /// then lasts as long as the connection it was used on.
+    pub async fn websocket_token(&self) -> Result<String> {
+        let uri_path = "/0/private/GetWebSocketsToken";
+
+        let params: Vec<(String, String)> = Vec::new();
+
+        let result: WebSocketsTokenResult = self.private_post_form(uri_path, &params).await?;
+        capture::secret(&result.token);
+        Ok(result.token)
+    }
+
+    /// Waits until the rate limiter allows the call to `uri_path` with `params`, made
+    /// some other way than REST, and counts it.
+    pub(crate) async fn pace(&self, uri_path: &str, params: &[(String, String)]) {
+        self.limiter.acquire(uri_path, params).await;
+    }
+
+    /// Balances keyed by Kraken asset code, e.g. `ZGBP`.
+    pub async fn balance(&self) -> Result<BalanceResult> {
+        let uri_path = "/0/private/Balance";
+
+        let params: Vec<(String, String)> = Vec::new();
+
+        let result: BalanceResult = self.private_post_form(uri_path, &params).await?;
+        Ok(result)
+    }
+
+    /// One page of the account's trades, newest first, between `start` and `end` (unix
+    /// seconds, either open) and from the `offset`th trade.
+    pub async fn trades_history(
+        &self,
+        start: Option<i64>,
+        end: Option<i64>,
+        offset: usize,
+    ) -> Result<TradesHistoryResult> {
+        let uri_path = "/0/private/TradesHistory";
+
+        let mut params = vec![("ofs".to_string(), offset.to_string())];
+        if let Some(start) = start {
+            params.push(("start".to_string(), start.to_string()));
+        }
+        if let Some(end) = end {
+            params.push(("end".to_string(), end.to_string()));
+        }
+
+        let result: TradesHistoryResult = self.private_post_form(uri_path, &params).await?;
+        Ok(result)
+    }
+
+    /// Posts `params` to `uri_path` once the rate limiter allows, retrying transient
+    /// failures. A place is not retried after a failure Kraken may have acted on.
     async fn private_post_form<T: DeserializeOwned>(
         &self,
         uri_path: &str,
         params: &[(String, String)],
     ) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            self.limiter.acquire(uri_path, params).await;
+
+            let error = match self.post_once(uri_path, params).await {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+            let retry = error.is_transient()
+                && !(uri_path == ADD_ORDER && error.is_ambiguous())
+                && attempt < self.retry.max_attempts;
+            if !retry {
+                return Err(error.into());
+            }
+
+            let backoff = self.retry.backoff(attempt, &self.rng);
+            tracing::warn!(
+                uri_path,
+                attempt,
+                backoff_ms = backoff.as_millis() as u64,
+                "kraken request failed; retrying: {error}"
+            );
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    async fn post_once<T: DeserializeOwned>(
+        &self,
+        uri_path: &str,
+        params: &[(String, String)],
+    ) -> Result<T, RequestError> {
         let nonce = self.next_nonce();
         let mut all_params: Vec<(String, String)> = Vec::with_capacity(params.len() + 1);
         all_params.push(("nonce".to_string(), nonce.to_string()));
         all_params.extend_from_slice(params);
 
         let encoded_payload = encode_form(&all_params);
-        let headers = self.signed_headers(uri_path, nonce, &encoded_payload)?;
+        let headers = self
+            .signed_headers(uri_path, nonce, &encoded_payload)
+            .map_err(RequestError::Other)?;
+
+        capture::sent(
+            uri_path,
+            headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.to_str().unwrap_or_default())),
+            &encoded_payload,
+        );
 
         let resp = self
             .http
@@ -108,32 +480,35 @@ impl KrakenClient {
             .body(encoded_payload)
             .send()
             .await
-            .context("kraken private POST failed")?;
+            .map_err(RequestError::Transport)?;
 
         let status = resp.status();
-        let text = resp.text().await.context("read response body failed")?;
+        let text = resp.text().await.map_err(RequestError::Transport)?;
+        capture::received(uri_path, &text);
 
         if !status.is_success() {
-            anyhow::bail!("kraken http error {status}: {text}");
+            return Err(RequestError::Http { status, body: text });
         }
 
         let parsed: KrakenResponse<T> = match serde_json::from_str(&text) {
             Ok(p) => p,
             Err(e) => {
                 tracing::error!(error = %e, %text, "failed to parse kraken JSON response");
-                anyhow::bail!("parse kraken response JSON failed: {e}; raw={text}");
+                return Err(RequestError::Other(anyhow!(
+                    "parse kraken response JSON failed: {e}; raw={text}"
+                )));
             }
         };
 
         if !parsed.error.is_empty() {
-            anyhow::bail!("kraken api error: {:?}", parsed.error);
+            return Err(RequestError::Api(parsed.error));
         }
 
         match parsed.result {
             Some(result) => Ok(result),
-            None => {
-                anyhow::bail!("kraken response missing `result` but `error` was empty; raw={text}")
-            }
+            None => Err(RequestError::Other(anyhow!(
+                "kraken response missing `result` but `error` was empty; raw={text}"
+            ))),
         }
     }
 
@@ -206,6 +581,10 @@ struct KrakenResponse<T> {
 pub struct AddOrderResult {
     pub txid: Vec<String>,
     pub descr: AddOrderDescr,
+    /// The order's status when a place whose outcome was unknown had to be looked up,
+    /// e.g. `closed` for one that filled; `None` when Kraken answered the place.
+    #[serde(skip)]
+    pub status: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -213,6 +592,16 @@ pub struct AddOrderDescr {
     pub order: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct AmendOrderResult {
+    pub amend_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebSocketsTokenResult {
+    pub token: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CancelOrderResult {
     pub count: i64,
@@ -223,36 +612,75 @@ pub struct CancelAllResult {
     pub count: i64,
 }
 
-fn encode_form(params: &[(String, String)]) -> String {
-    let mut ser = form_urlencoded::Serializer::new(String::new());
-    for (k, v) in params {
-        ser.append_pair(k, v);
-    }
-    ser.finish()
+/// Decimal strings keyed by Kraken asset code.
+pub type BalanceResult = HashMap<String, String>;
+
+#[derive(Debug, Deserialize)]
+pub struct OpenOrdersResult {
+    /// Keyed by Kraken transaction id.
+    pub open: HashMap<String, KrakenOpenOrder>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClosedOrdersResult {
+    /// Keyed by Kraken transaction id.
+    pub closed: HashMap<String, KrakenOpenOrder>,
 }
 
-fn format_volume(qty: f64) -> String {
-    format!("{:.12}", qty)
-        .trim_end_matches('0')
-        .trim_end_matches('.')
-        .to_string()
+/// An order as OpenOrders lists it, and ClosedOrders once it is done.
+#[derive(Debug, Deserialize)]
+pub struct KrakenOpenOrder {
+    pub cl_ord_id: Option<String>,
+    /// `pending`, `open`, `closed`, `canceled` or `expired`.
+    pub status: Option<String>,
+    pub descr: OpenOrderDescr,
+    /// Order volume, as a decimal string.
+    pub vol: String,
+    /// Volume executed so far, as a decimal string.
+    pub vol_exec: String,
+    /// Unix seconds the order was opened at.
+    pub opentm: Option<f64>,
 }
 
-fn format_price(p: f64) -> String {
-    format!("{:.10}", p)
-        .trim_end_matches('0')
-        .trim_end_matches('.')
-        .to_string()
+#[derive(Debug, Deserialize)]
+pub struct OpenOrderDescr {
+    pub pair: String,
+    /// `buy` or `sell`.
+    #[serde(rename = "type")]
+    pub side: String,
+    pub price: String,
 }
 
-fn instrument_to_kraken_pair(instrument: &Instrument) -> String {
-    let base = instrument.base().to_uppercase();
-    let quote = instrument.quote().to_uppercase();
+#[derive(Debug, Deserialize)]
+pub struct TradesHistoryResult {
+    /// Keyed by Kraken trade id.
+    pub trades: HashMap<String, KrakenTrade>,
+    /// Trades matching the query across every page.
+    pub count: usize,
+}
 
-    let base = match base.as_str() {
-        "BTC" => "XBT".to_string(),
-        _ => base,
-    };
+#[derive(Debug, Deserialize)]
+pub struct KrakenTrade {
+    /// Kraken transaction id of the order that filled.
+    pub ordertxid: String,
+    /// REST pair name, e.g. `SOLGBP` or `XXBTZGBP`.
+    pub pair: String,
+    /// Unix seconds.
+    pub time: f64,
+    /// `buy` or `sell`.
+    #[serde(rename = "type")]
+    pub side: String,
+    /// Decimal strings: price and cost in the quote currency, volume in the base.
+    pub price: String,
+    pub cost: String,
+    pub fee: String,
+    pub vol: String,
+}
 
-    format!("{base}{quote}")
+fn encode_form(params: &[(String, String)]) -> String {
+    let mut ser = form_urlencoded::Serializer::new(String::new());
+    for (k, v) in params {
+        ser.append_pair(k, v);
+    }
+    ser.finish()
 }
@@ -1,21 +1,106 @@
+use std::collections::BTreeMap;
 use std::env;
+use std::fmt;
 
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::app_config::{ensure, redacted};
+use crate::kraken::capture;
+use crate::kraken::kraken_client::RetryConfig;
+use crate::kraken::kraken_order_socket::OrderSocketConfig;
+use crate::kraken::rate_limit::RateLimitConfig;
+use crate::kraken::symbols::KrakenPairNames;
+use crate::types::instrument::InstrumentConfig;
+
+/// Credentials from the application config. Either may be left unset to fall back to the
+/// `KRAKEN_API_KEY` / `KRAKEN_API_SECRET` environment variables.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KrakenSettings {
+    #[serde(serialize_with = "redacted")]
+    pub api_key: Option<String>,
+    #[serde(serialize_with = "redacted")]
+    pub api_secret: Option<String>,
+    /// Pacing of REST calls to stay under Kraken's rate limits.
+    pub rate_limit: RateLimitConfig,
+    /// Retries of REST calls that failed transiently.
+    pub retry: RetryConfig,
+    /// Placing and cancelling over the authenticated websocket.
+    pub order_socket: OrderSocketConfig,
+    /// Kraken's names for pairs missing from, or spelt differently than, the built-in
+    /// table, by `BASE/QUOTE` as in the trading rules.
+    pub pairs: BTreeMap<String, KrakenPairNames>,
+}
+
+impl KrakenSettings {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        self.rate_limit.validate(&format!("{path}.rate_limit"))?;
+        self.retry.validate(&format!("{path}.retry"))?;
+        self.order_socket
+            .validate(&format!("{path}.order_socket"))?;
+        for (pair, names) in &self.pairs {
+            let path = format!("{path}.pairs.{pair}");
+            ensure(
+                pair.parse::<InstrumentConfig>().is_ok(),
+                &path,
+                "must be a BASE/QUOTE pair",
+            )?;
+            names.validate(&path)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
 pub struct KrakenConfig {
     pub api_key: String,
     pub api_secret: String,
+    pub rate_limit: RateLimitConfig,
+    pub retry: RetryConfig,
+    pub order_socket: OrderSocketConfig,
 }
 
 impl KrakenConfig {
     pub fn from_env() -> anyhow::Result<Self> {
-        let api_key =
-            env::var("KRAKEN_API_KEY").map_err(|_| anyhow::anyhow!("KRAKEN_API_KEY not set"))?;
+        Self::resolve(&KrakenSettings::default())
+    }
+
+    pub fn resolve(settings: &KrakenSettings) -> anyhow::Result<Self> {
+        let api_key = match &settings.api_key {
+            Some(api_key) => api_key.clone(),
+            None => {
+                env::var("KRAKEN_API_KEY").map_err(|_| anyhow::anyhow!("KRAKEN_API_KEY not set"))?
+            }
+        };
+
+        let api_secret = match &settings.api_secret {
+            Some(api_secret) => api_secret.clone(),
+            None => env::var("KRAKEN_API_SECRET")
+                .map_err(|_| anyhow::anyhow!("KRAKEN_API_SECRET not set"))?,
+        };
 
-        let api_secret = env::var("KRAKEN_API_SECRET")
-            .map_err(|_| anyhow::anyhow!("KRAKEN_API_SECRET not set"))?;
+        capture::secret(&api_key);
+        capture::secret(&api_secret);
 
         Ok(Self {
             api_key,
             api_secret,
+            rate_limit: settings.rate_limit.clone(),
+            retry: settings.retry.clone(),
+            order_socket: settings.order_socket.clone(),
         })
     }
 }
+
+impl fmt::Debug for KrakenConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KrakenConfig")
+            .field("api_key", &"<redacted>")
+            .field("api_secret", &"<redacted>")
+            .field("rate_limit", &self.rate_limit)
+            .field("retry", &self.retry)
+            .field("order_socket", &self.order_socket)
+            .finish()
+    }
+}
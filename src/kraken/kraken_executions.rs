@@ -7,39 +7,71 @@ use serde::Deserialize;
 use tokio::sync::broadcast;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
+use crate::engine::supervisor::{RestartPolicy, Supervisor};
 use crate::execution::ReportSender;
 use crate::execution::order_action::Side;
-use crate::execution::order_report::OrderReport;
+use crate::execution::order_ids::ClientOrderId;
+use crate::execution::order_report::{OrderReport, RejectKind};
+use crate::execution::report_sequencer::ReportSequencer;
+use crate::execution::trade_checkpoint::TradeCheckpoint;
+use crate::kraken::capture;
 use crate::kraken::kraken_config::KrakenConfig;
+use crate::kraken::symbols::{WsVersion, ws_instrument};
 use crate::kraken::utils::get_websocket_token;
-use crate::types::{instrument::Instrument, price::Price};
+use crate::telemetry::liveness;
+use crate::telemetry::metrics::Feed;
+use crate::types::price::Price;
+use crate::types::quantity::Quantity;
 
-pub struct KrakenExecutions {
-    _task: tokio::task::JoinHandle<()>,
-}
+pub struct KrakenExecutions;
 
 impl KrakenExecutions {
-    pub async fn spawn(on_report: ReportSender) -> Result<Self> {
-        let config = KrakenConfig::from_env()?;
-        let ws_token = get_websocket_token(&config).await?;
+    /// Streams executions into `on_report`, reconnecting on errors, with each trade
+    /// recorded in `trades`. The engine cannot track its orders without it, so the task
+    /// escalates if it ever stops.
+    pub async fn spawn(
+        config: &KrakenConfig,
+        on_report: ReportSender,
+        trades: TradeCheckpoint,
+        supervisor: &Supervisor,
+    ) -> Result<()> {
+        let ws_token = get_websocket_token(config).await?;
 
-        let task = tokio::spawn(async move {
-            let url = "wss://ws-auth.kraken.com/v2";
+        supervisor.spawn("executions", RestartPolicy::Escalate, move || {
+            let ws_token = ws_token.clone();
+            let on_report = on_report.clone();
+            let trades = trades.clone();
 
-            loop {
-                if let Err(e) = run_once(url, &ws_token, on_report.clone()).await {
-                    tracing::error!(error = %e, "kraken executions stream failed");
-                }
+            async move {
+                let url = "wss://ws-auth.kraken.com/v2";
+                // Kept across reconnects, so each one's snapshot is checked against what
+                // was already reported.
+                let mut sequencer = ReportSequencer::new();
 
-                tokio::time::sleep(Duration::from_secs(2)).await;
+                loop {
+                    if let Err(e) =
+                        run_once(url, &ws_token, &on_report, &mut sequencer, &trades).await
+                    {
+                        tracing::error!(error = %e, "kraken executions stream failed");
+                    }
+
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                    liveness::feed_reconnect(Feed::Executions);
+                }
             }
         });
 
-        Ok(Self { _task: task })
+        Ok(())
     }
 }
 
-async fn run_once(url: &str, token: &str, report_tx: broadcast::Sender<OrderReport>) -> Result<()> {
+async fn run_once(
+    url: &str,
+    token: &str,
+    report_tx: &broadcast::Sender<OrderReport>,
+    sequencer: &mut ReportSequencer,
+    trades: &TradeCheckpoint,
+) -> Result<()> {
     let (mut ws, _) = connect_async(url)
         .await
         .with_context(|| format!("connect_async({url}) failed"))?;
@@ -56,41 +88,154 @@ async fn run_once(url: &str, token: &str, report_tx: broadcast::Sender<OrderRepo
             "order_status": true
         }
     });
-    ws.send(Message::Text(sub.to_string())).await?;
+    let sub = sub.to_string();
+    capture::sent("executions", [], &sub);
+    ws.send(Message::Text(sub)).await?;
 
     while let Some(msg) = ws.next().await {
         let msg = msg?;
+        liveness::feed_event(Feed::Executions);
+
         let Ok(text) = msg.into_text() else { continue };
+        capture::received("executions", &text);
+
+        for report in frame_reports(&text, sequencer, trades) {
+            let _ = report_tx.send(report);
+        }
+    }
 
-        let frame: WsFrame = match serde_json::from_str(&text) {
-            Ok(f) => f,
-            Err(_) => continue, // ignore heartbeats/acks/unrelated
-        };
+    Ok(())
+}
+
+/// The reports in one executions-channel message, less any `sequencer` has already
+/// passed on. A snapshot's open orders become [`OrderReport::Snapshot`]s. Its trades are
+/// history, already reported or caught up by their order's snapshot, except those past
+/// a checkpoint restored into `trades`: those the venue made while the engine was down,
+/// which are passed on as fills, oldest first. Every trade id seen advances `trades`.
+pub fn frame_reports(
+    text: &str,
+    sequencer: &mut ReportSequencer,
+    trades: &TradeCheckpoint,
+) -> Vec<OrderReport> {
+    let frame: WsFrame = match serde_json::from_str(text) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(), // ignore heartbeats/acks/unrelated
+    };
+
+    if frame.channel.as_deref() != Some("executions") {
+        return Vec::new();
+    }
 
-        if frame.channel.as_deref() != Some("executions") {
-            continue;
+    let data = frame.data.unwrap_or_default();
+    let reports: Vec<OrderReport> = match frame.kind.as_deref() {
+        Some("snapshot") => {
+            let missed = missed_trades(&data, trades);
+            data.iter()
+                .filter_map(to_snapshot_report)
+                .chain(missed.into_iter().filter_map(to_order_report))
+                .collect()
         }
+        _ => data
+            .iter()
+            .inspect(|entry| {
+                if let Some((symbol, trade_id)) = trade_id(entry) {
+                    trades.advance(symbol, trade_id);
+                }
+            })
+            .filter_map(to_order_report)
+            .collect(),
+    };
 
-        let Some(reports) = frame.data else { continue };
+    reports
+        .into_iter()
+        .filter_map(|report| sequencer.admit(report))
+        .collect()
+}
 
-        for report in reports {
-            if let Some(or) = to_order_report(&report) {
-                let _ = report_tx.send(or);
-            }
+/// The snapshot's trades past the checkpoint of their pair, oldest first. A pair with no
+/// checkpoint yet has none missed; its trades only set one.
+fn missed_trades<'a>(
+    data: &'a [serde_json::Value],
+    trades: &TradeCheckpoint,
+) -> Vec<&'a serde_json::Value> {
+    let mut missed: Vec<(u64, &serde_json::Value)> = data
+        .iter()
+        .filter_map(|entry| {
+            let (symbol, trade_id) = trade_id(entry)?;
+            trades
+                .last(symbol)
+                .is_some_and(|last| trade_id > last)
+                .then_some((trade_id, entry))
+        })
+        .collect();
+
+    for entry in data {
+        if let Some((symbol, trade_id)) = trade_id(entry) {
+            trades.advance(symbol, trade_id);
         }
     }
 
-    Ok(())
+    missed.sort_by_key(|(trade_id, _)| *trade_id);
+    missed.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// The pair and venue trade id of a trade execution; ids are only unique per pair.
+fn trade_id(v: &serde_json::Value) -> Option<(&str, u64)> {
+    if v.get("exec_type").and_then(|x| x.as_str()) != Some("trade") {
+        return None;
+    }
+    let symbol = v.get("symbol")?.as_str()?;
+    let trade_id = v.get("trade_id")?;
+    let trade_id = trade_id
+        .as_u64()
+        .or_else(|| trade_id.as_str()?.parse().ok())?;
+
+    Some((symbol, trade_id))
 }
 
 #[derive(Debug, Deserialize)]
 struct WsFrame {
     #[serde(default)]
     channel: Option<String>,
+    /// `snapshot` for the replay on subscribing, `update` after.
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
     #[serde(default)]
     data: Option<Vec<serde_json::Value>>,
 }
 
+fn to_snapshot_report(v: &serde_json::Value) -> Option<OrderReport> {
+    if v.get("exec_type").and_then(|x| x.as_str()) == Some("trade") {
+        return None;
+    }
+    let cl_ord_id = v.get("cl_ord_id")?.as_str()?.to_string();
+    let side = match v.get("side").and_then(|x| x.as_str()) {
+        Some(side) => Side::from_str(side).ok()?,
+        None => ClientOrderId::parse(&cl_ord_id)?.side,
+    };
+    let symbol = v.get("symbol").and_then(|x| x.as_str()).unwrap_or_default();
+    let instrument = match ws_instrument(symbol, WsVersion::V2) {
+        Ok(instrument) => instrument,
+        Err(error) => {
+            tracing::warn!(
+                symbol,
+                cl_ord_id,
+                "dropping an order snapshot for an unreadable symbol: {error:#}"
+            );
+            return None;
+        }
+    };
+
+    Some(OrderReport::Snapshot {
+        order_id: cl_ord_id,
+        instrument,
+        side,
+        price: Price::new(parse_f64(v.get("limit_price").or_else(|| v.get("price")))?),
+        quantity: parse_quantity(v.get("order_qty"))?,
+        cum_quantity: parse_quantity(v.get("cum_qty")).unwrap_or_default(),
+    })
+}
+
 fn to_order_report(v: &serde_json::Value) -> Option<OrderReport> {
     let exec_type = v.get("exec_type")?.as_str()?.to_string();
     let cl_ord_id = v
@@ -98,17 +243,36 @@ fn to_order_report(v: &serde_json::Value) -> Option<OrderReport> {
         .and_then(|x| x.as_str())
         .map(|s| s.to_string())?;
     let symbol = v.get("symbol").and_then(|x| x.as_str()).unwrap_or_default();
-    let side = Side::from_str(v.get("side")?.as_str()?).ok()?;
+    // Status updates may leave the side out; the engine's own ids carry it.
+    let side = match v.get("side").and_then(|x| x.as_str()) {
+        Some(side) => Side::from_str(side).ok()?,
+        None => ClientOrderId::parse(&cl_ord_id)?.side,
+    };
 
-    let instrument = Instrument::from_str(symbol).ok()?;
+    let instrument = match ws_instrument(symbol, WsVersion::V2) {
+        Ok(instrument) => instrument,
+        Err(error) => {
+            tracing::warn!(
+                symbol,
+                cl_ord_id,
+                exec_type,
+                "dropping execution report for an unreadable symbol: {error:#}"
+            );
+            return None;
+        }
+    };
 
     let price = parse_f64(v.get("price").or_else(|| v.get("avg_price")))?;
-    let last_qty = parse_f64(
+    let last_qty = parse_quantity(
         v.get("last_qty")
             .or_else(|| v.get("qty"))
             .or_else(|| v.get("order_qty")),
     )?;
-    let cum_qty = parse_f64(v.get("cum_qty")).unwrap_or(0.0);
+    // Order state is tracked off the cumulative quantity. Should a fill ever arrive
+    // without one, a filled order's is its whole quantity, and a trade's is only known
+    // to be at least its own.
+    let cum_qty = parse_quantity(v.get("cum_qty"));
+    let order_qty = parse_quantity(v.get("order_qty"));
 
     match exec_type.as_str() {
         "new" => Some(OrderReport::Accepted {
@@ -125,7 +289,7 @@ fn to_order_report(v: &serde_json::Value) -> Option<OrderReport> {
             side,
             price: Price::new(price),
             quantity: last_qty,
-            cum_quantity: cum_qty.max(last_qty),
+            cum_quantity: cum_qty.unwrap_or(last_qty),
         }),
 
         "filled" => Some(OrderReport::Filled {
@@ -134,7 +298,7 @@ fn to_order_report(v: &serde_json::Value) -> Option<OrderReport> {
             side,
             price: Price::new(price),
             quantity: last_qty,
-            cum_quantity: cum_qty.max(last_qty),
+            cum_quantity: cum_qty.or(order_qty).unwrap_or(last_qty),
         }),
 
         "canceled" => Some(OrderReport::Cancelled {
@@ -147,10 +311,12 @@ fn to_order_report(v: &serde_json::Value) -> Option<OrderReport> {
             order_id: cl_ord_id,
             instrument,
             side,
+            kind: RejectKind::Expired,
             reason: "expired".to_string(),
         }),
 
-        //  "pending_new", "status", "restated"
+        //  "pending_new", "status", "restated", and "amended", reported from the REST
+        //  reply instead
         _ => None,
     }
 }
@@ -163,3 +329,8 @@ fn parse_f64(v: Option<&serde_json::Value>) -> Option<f64> {
 
     v.as_str()?.parse::<f64>().ok()
 }
+
+/// A size the venue reported, or `None` when it is no size at all.
+fn parse_quantity(v: Option<&serde_json::Value>) -> Option<Quantity> {
+    Quantity::new(parse_f64(v)?).ok()
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
@@ -6,41 +8,94 @@ use serde::Deserialize;
 use tokio::sync::watch;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
+use crate::engine::supervisor::{RestartPolicy, Supervisor};
 use crate::inventory::InventorySource;
-use crate::kraken::kraken_config::KrakenConfig;
-use crate::kraken::utils::get_websocket_token;
+use crate::kraken::capture;
+use crate::kraken::kraken_client::{BalanceResult, KrakenClient};
+use crate::kraken::kraken_order_socket::KRAKEN_WS_AUTH_URL;
+use crate::telemetry::liveness;
+use crate::telemetry::metrics::Feed;
 use crate::types::instrument::Instrument;
 use crate::types::inventory::Inventory;
 
 pub struct KrakenInventory {
     tx: watch::Sender<Inventory>,
-    _task: tokio::task::JoinHandle<()>,
+    /// Set once balances are known, from the REST snapshot or the first frame.
+    ready: Arc<AtomicBool>,
 }
 
 impl KrakenInventory {
-    pub async fn spawn(instrument: &Instrument) -> Result<Self> {
-        let config = KrakenConfig::from_env()?;
-        let ws_token = get_websocket_token(&config).await?;
-
-        let (tx, _rx) = watch::channel(Inventory::default());
-        let tx_task = tx.clone();
+    /// Streams balances for `instrument`, reconnecting on errors. Risk checks would run on
+    /// a frozen inventory without it, so the task escalates if it ever stops.
+    pub async fn spawn(
+        client: &KrakenClient,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<Self> {
+        Self::spawn_at(client, KRAKEN_WS_AUTH_URL, instrument, supervisor).await
+    }
 
+    /// Streams balances from the websocket at `url`. The balances are first read over
+    /// REST, so the inventory is real from the start rather than zero until the first
+    /// frame, which can take seconds.
+    pub async fn spawn_at(
+        client: &KrakenClient,
+        url: &str,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<Self> {
         let base_codes = kraken_balance_codes(instrument.base());
         let quote_codes = kraken_balance_codes(instrument.quote());
 
-        let task = tokio::spawn(async move {
-            let url = "wss://ws-auth.kraken.com/v2";
-
-            loop {
-                match run_once(url, &ws_token, &base_codes, &quote_codes, &tx_task).await {
-                    Ok(()) => {}
-                    Err(e) => eprintln!("[kraken_inventory] {e:?}"),
+        let balances = client
+            .balance()
+            .await
+            .context("reading starting balances")?;
+        let (tx, _rx) = watch::channel(Inventory::new(
+            rest_balance(&balances, &base_codes),
+            rest_balance(&balances, &quote_codes),
+        ));
+        let tx_task = tx.clone();
+        let ready = Arc::new(AtomicBool::new(true));
+        let ready_task = ready.clone();
+
+        let ws_token = client.websocket_token().await?;
+        let url = url.to_string();
+
+        supervisor.spawn(
+            format!("inventory {instrument}"),
+            RestartPolicy::Escalate,
+            move || {
+                let ws_token = ws_token.clone();
+                let base_codes = base_codes.clone();
+                let quote_codes = quote_codes.clone();
+                let tx_task = tx_task.clone();
+                let ready_task = ready_task.clone();
+                let url = url.clone();
+
+                async move {
+                    loop {
+                        match run_once(
+                            &url,
+                            &ws_token,
+                            &base_codes,
+                            &quote_codes,
+                            &tx_task,
+                            &ready_task,
+                        )
+                        .await
+                        {
+                            Ok(()) => {}
+                            Err(e) => eprintln!("[kraken_inventory] {e:?}"),
+                        }
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        liveness::feed_reconnect(Feed::Inventory);
+                    }
                 }
-                tokio::time::sleep(Duration::from_secs(2)).await;
-            }
-        });
+            },
+        );
 
-        Ok(Self { tx, _task: task })
+        Ok(Self { tx, ready })
     }
 }
 
@@ -48,6 +103,10 @@ impl InventorySource for KrakenInventory {
     fn subscribe(&self) -> watch::Receiver<Inventory> {
         self.tx.subscribe()
     }
+
+    fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
 }
 
 async fn run_once(
@@ -56,6 +115,7 @@ async fn run_once(
     base_codes: &[String],
     quote_codes: &[String],
     tx: &watch::Sender<Inventory>,
+    ready: &AtomicBool,
 ) -> anyhow::Result<()> {
     let (mut ws, _) = connect_async(url)
         .await
@@ -68,11 +128,16 @@ async fn run_once(
             "token": ws_token
         }
     });
-    ws.send(Message::Text(sub.to_string())).await?;
+    let sub = sub.to_string();
+    capture::sent("balances", [], &sub);
+    ws.send(Message::Text(sub)).await?;
 
     while let Some(msg) = ws.next().await {
         let msg = msg?;
+        liveness::feed_event(Feed::Inventory);
+
         let Ok(text) = msg.into_text() else { continue };
+        capture::received("balances", &text);
 
         let frame: WsFrame = match serde_json::from_str(&text) {
             Ok(message) => message,
@@ -97,6 +162,7 @@ async fn run_once(
             inventory.quote = quote;
         }
 
+        ready.store(true, Ordering::Release);
         let _ = tx.send(inventory);
     }
 
@@ -144,6 +210,19 @@ fn kraken_balance_codes(sym: &str) -> Vec<String> {
     }
 }
 
+/// The balance under the first of `codes` in a REST snapshot, zero if none is held.
+fn rest_balance(balances: &BalanceResult, codes: &[String]) -> f64 {
+    codes
+        .iter()
+        .find_map(|code| {
+            balances
+                .iter()
+                .find(|(asset, _)| asset.eq_ignore_ascii_case(code))
+        })
+        .and_then(|(_, balance)| balance.parse().ok())
+        .unwrap_or(0.0)
+}
+
 fn pick_balance(entries: &[BalanceEntry], codes: &[String]) -> Option<f64> {
     for code in codes {
         if let Some(e) = entries.iter().find(|e| e.asset.eq_ignore_ascii_case(code)) {
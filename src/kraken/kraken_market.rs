@@ -1,19 +1,31 @@
-use anyhow::Result;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
 use async_trait::async_trait;
+use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::{Value, json};
+use tokio::net::TcpStream;
 use tokio::sync::mpsc::Sender;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
-use tracing::{error, info};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
+use tracing::{error, info, warn};
 
 use crate::events::MarketEvent;
-use crate::market::market_source::MarketDataSource;
+use crate::kraken::capture;
+use crate::kraken::kraken_book::KrakenBook;
+use crate::kraken::symbols::{WsVersion, ws_pair};
+use crate::market::market_source::{MarketDataSource, SubscriptionRejected};
 use crate::types::instrument::Instrument;
 use crate::types::price::Price;
 
 #[derive(Debug)]
 pub struct KrakenMarket {
     websocket_url: String,
+    /// Levels a side of the `book` subscription; no depth is subscribed to when unset.
+    book_depth: Option<u32>,
+    /// Quiet this long, the feed is pinged; quiet `pong_timeout` more, it is dropped.
+    idle_timeout: Duration,
+    pong_timeout: Duration,
 }
 
 impl Default for KrakenMarket {
@@ -26,57 +38,163 @@ impl KrakenMarket {
     pub fn new(websocket_url: impl Into<String>) -> Self {
         Self {
             websocket_url: websocket_url.into(),
+            book_depth: None,
+            idle_timeout: Duration::from_secs(10),
+            pong_timeout: Duration::from_secs(5),
         }
     }
 
-    fn subscription_for_trades(&self, instrument: &Instrument) -> Value {
+    /// Also subscribes to `depth` levels of the order book, when set.
+    pub fn with_book_depth(mut self, depth: Option<u32>) -> Self {
+        self.book_depth = depth;
+        self
+    }
+
+    /// Pings the feed after `idle` without a message, and gives up on the connection
+    /// when nothing comes back within `pong`.
+    pub fn with_idle_timeout(mut self, idle: Duration, pong: Duration) -> Self {
+        self.idle_timeout = idle;
+        self.pong_timeout = pong;
+        self
+    }
+
+    fn subscription_for_trades(&self, pair: &str) -> Value {
         json!({
             "event": "subscribe",
-            "pair": [instrument.to_string()],
+            "pair": [pair],
             "subscription": { "name": "trade" }
         })
     }
 
-    fn subscription_for_spread(&self, instrument: &Instrument) -> Value {
+    fn subscription_for_spread(&self, pair: &str) -> Value {
         json!({
             "event": "subscribe",
-            "pair": [instrument.to_string()],
+            "pair": [pair],
             "subscription": { "name": "spread" }
         })
     }
 
-    fn subscriptions(&self, instrument: &Instrument) -> Vec<Value> {
-        vec![
-            self.subscription_for_trades(instrument),
-            self.subscription_for_spread(instrument),
-        ]
+    fn subscription_for_book(&self, pair: &str, depth: u32) -> Value {
+        json!({
+            "event": "subscribe",
+            "pair": [pair],
+            "subscription": { "name": "book", "depth": depth }
+        })
+    }
+
+    /// Fails for a pair Kraken's name is not known for.
+    pub fn subscriptions(&self, instrument: &Instrument) -> Result<Vec<Value>> {
+        let pair = ws_pair(instrument, WsVersion::V1)?;
+        let mut subscriptions = vec![
+            self.subscription_for_trades(&pair),
+            self.subscription_for_spread(&pair),
+        ];
+        if let Some(depth) = self.book_depth {
+            subscriptions.push(self.subscription_for_book(&pair, depth));
+        }
+        Ok(subscriptions)
+    }
+
+    /// The market event in a websocket message for `instrument`, if any. Fails on a
+    /// rejected subscription, which no reconnect will fix. Book frames only update the
+    /// ladder they belong to, so are skipped; see
+    /// [`parse_market_event_with_book`](Self::parse_market_event_with_book).
+    pub fn parse_market_event_from_text(
+        instrument: &Instrument,
+        text: &str,
+    ) -> Result<Option<MarketEvent>> {
+        Self::parse_message(instrument, text, None)
     }
 
-    fn parse_market_event_from_text(instrument: &Instrument, text: &str) -> Option<MarketEvent> {
-        let parsed: Value = serde_json::from_str(text).ok()?;
+    /// As [`parse_market_event_from_text`](Self::parse_market_event_from_text), applying
+    /// book frames to `book`. Also fails when the book no longer matches its checksum.
+    pub fn parse_market_event_with_book(
+        instrument: &Instrument,
+        text: &str,
+        book: &mut KrakenBook,
+    ) -> Result<Option<MarketEvent>> {
+        Self::parse_message(instrument, text, Some(book))
+    }
+
+    fn parse_message(
+        instrument: &Instrument,
+        text: &str,
+        book: Option<&mut KrakenBook>,
+    ) -> Result<Option<MarketEvent>> {
+        let Ok(parsed) = serde_json::from_str::<Value>(text) else {
+            return Ok(None);
+        };
+        let pair = ws_pair(instrument, WsVersion::V1)?;
 
-        /* Ignore object messages like subscription_status or system_status */
+        /* Object messages: subscriptionStatus, systemStatus and heartbeats */
         if parsed.is_object() {
-            return None;
+            Self::check_subscription_status(&pair, &parsed)?;
+            return Ok(None);
         }
 
-        /* [channel_id, payload, channel_name, pair] */
-        let array = parsed.as_array()?;
-        if array.len() < 4 {
-            return None;
+        /* [channel_id, payload, ..., channel_name, pair]; book updates may carry two payloads */
+        let Some(array) = parsed.as_array().filter(|array| array.len() >= 4) else {
+            return Ok(None);
+        };
+        let (pair_field, channel_field) = (&array[array.len() - 1], &array[array.len() - 2]);
+
+        if pair_field.as_str() != Some(pair.as_str()) {
+            warn!(expected = %pair, received = %pair_field, "Kraken websocket frame for another pair; ignoring");
+            return Ok(None);
         }
 
-        let channel_name = array[2].as_str()?;
-        let payload = &array[1];
+        let Some(channel_name) = channel_field.as_str() else {
+            return Ok(None);
+        };
+        let payloads = &array[1..array.len() - 2];
+        let payload = &payloads[0];
 
-        match channel_name {
+        Ok(match channel_name {
             "trade" => Self::parse_trade(instrument, payload),
             "spread" => Self::parse_spread_top_of_book(instrument, payload),
+            name if name.starts_with("book-") => match book {
+                Some(book) => Some(book.apply(instrument, payloads)?),
+                None => None,
+            },
             _ => {
                 error!("Kraken websocket received unknown channel: {channel_name}");
 
                 None
             }
+        })
+    }
+
+    fn check_subscription_status(pair: &str, message: &Value) -> Result<()> {
+        if message.get("event").and_then(Value::as_str) != Some("subscriptionStatus") {
+            return Ok(());
+        }
+
+        let field = |name: &str| {
+            message
+                .get(name)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        };
+        let channel = message
+            .pointer("/subscription/name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        match field("status") {
+            "error" => Err(SubscriptionRejected {
+                pair: match field("pair") {
+                    "" => pair.to_string(),
+                    rejected => rejected.to_string(),
+                },
+                channel: channel.to_string(),
+                reason: field("errorMessage").to_string(),
+            }
+            .into()),
+            "subscribed" => {
+                info!(pair = field("pair"), channel, "Kraken websocket subscribed");
+                Ok(())
+            }
+            _ => Ok(()),
         }
     }
 
@@ -84,7 +202,7 @@ impl KrakenMarket {
         let trades = payload.as_array()?;
         let first_trade = trades.first()?.as_array()?;
 
-        let price_str = first_trade.get(0)?.as_str()?;
+        let price_str = first_trade.first()?.as_str()?;
         let quantity_str = first_trade.get(1)?.as_str()?;
         let time_str = first_trade.get(2)?.as_str()?;
 
@@ -108,7 +226,7 @@ impl KrakenMarket {
     fn parse_spread_top_of_book(instrument: &Instrument, payload: &Value) -> Option<MarketEvent> {
         let fields = payload.as_array()?;
 
-        let bid_str = fields.get(0)?.as_str()?;
+        let bid_str = fields.first()?.as_str()?;
         let ask_str = fields.get(1)?.as_str()?;
 
         let best_bid: f64 = bid_str.parse().ok()?;
@@ -120,34 +238,76 @@ impl KrakenMarket {
             .map(|seconds| (seconds * 1000.0) as u64)
             .unwrap_or(0);
 
+        // Volumes at the touch follow the timestamp; older frames may leave them out.
+        let size = |index: usize| {
+            fields
+                .get(index)
+                .and_then(|v| v.as_str().and_then(|s| s.parse::<f64>().ok()))
+        };
+
         Some(MarketEvent::TopOfBook {
             instrument: instrument.clone(),
             best_bid: Price::new(best_bid),
             best_ask: Price::new(best_ask),
+            bid_size: size(3),
+            ask_size: size(4),
             timestamp_ms,
         })
     }
-}
 
-#[async_trait]
-impl MarketDataSource for KrakenMarket {
-    async fn subscribe(&self, instrument: &Instrument, channel: Sender<MarketEvent>) -> Result<()> {
-        let (stream, _http_response) = connect_async(&self.websocket_url).await?;
+    /// Subscribes over a connected `stream` and forwards its events until it closes,
+    /// fails or goes quiet.
+    async fn stream(
+        &self,
+        instrument: &Instrument,
+        stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        channel: &Sender<MarketEvent>,
+    ) -> Result<()> {
         let (mut writer, mut reader) = stream.split();
+        let mut book = KrakenBook::new(self.book_depth.unwrap_or_default() as usize);
 
-        for subscription in self.subscriptions(instrument) {
-            writer.send(Message::Text(subscription.to_string())).await?;
+        for subscription in self.subscriptions(instrument)? {
+            let subscription = subscription.to_string();
+            capture::sent("market", [], &subscription);
+            writer.send(Message::Text(subscription)).await?;
         }
 
         info!("Kraken websocket connected");
+        let _ = channel
+            .send(MarketEvent::SourceReconnected {
+                instrument: instrument.clone(),
+                timestamp_ms: now_ms(),
+            })
+            .await;
 
-        while let Some(message) = reader.next().await {
-            let message_text: Option<String> = match message? {
+        let mut pinged = false;
+        loop {
+            let wait = if pinged {
+                self.pong_timeout
+            } else {
+                self.idle_timeout
+            };
+            let message = match tokio::time::timeout(wait, reader.next()).await {
+                Ok(Some(message)) => message?,
+                Ok(None) => break,
+                Err(_) if pinged => {
+                    bail!("Kraken websocket sent nothing back within {wait:?} of a ping")
+                }
+                Err(_) => {
+                    warn!(idle = ?wait, "Kraken websocket quiet; pinging");
+                    let ping = json!({ "event": "ping" }).to_string();
+                    capture::sent("market", [], &ping);
+                    writer.send(Message::Text(ping)).await?;
+                    pinged = true;
+                    continue;
+                }
+            };
+            // Anything at all, pong or not, shows the connection is alive.
+            pinged = false;
+
+            let message_text: Option<String> = match message {
                 Message::Text(text) => Some(text),
-                Message::Binary(binary) => match String::from_utf8(binary) {
-                    Ok(text) => Some(text),
-                    Err(_) => None,
-                },
+                Message::Binary(binary) => String::from_utf8(binary).ok(),
                 Message::Ping(_) | Message::Pong(_) => None,
                 Message::Close(frame) => {
                     error!("Kraken websocket closed: {:?}", frame);
@@ -156,19 +316,47 @@ impl MarketDataSource for KrakenMarket {
                 _ => None,
             };
 
-            if let Some(text) = message_text {
-                if let Some(market_event) =
-                    KrakenMarket::parse_market_event_from_text(instrument, &text)
-                {
-                    if channel.send(market_event).await.is_err() {
-                        error!("Failed to send market event");
+            if let Some(text) = &message_text {
+                capture::received("market", text);
+            }
 
-                        break;
-                    }
-                }
+            if let Some(text) = message_text
+                && let Some(market_event) =
+                    KrakenMarket::parse_market_event_with_book(instrument, &text, &mut book)?
+                && channel.send(market_event).await.is_err()
+            {
+                error!("Failed to send market event");
+
+                break;
             }
         }
 
         Ok(())
     }
 }
+
+#[async_trait]
+impl MarketDataSource for KrakenMarket {
+    /// Streams until the connection ends, then sends
+    /// [`MarketEvent::SourceDisconnected`] so the engine stops quoting on the book it had.
+    async fn subscribe(&self, instrument: &Instrument, channel: Sender<MarketEvent>) -> Result<()> {
+        let (stream, _http_response) = connect_async(&self.websocket_url).await?;
+        let result = self.stream(instrument, stream, &channel).await;
+
+        let _ = channel
+            .send(MarketEvent::SourceDisconnected {
+                instrument: instrument.clone(),
+                timestamp_ms: now_ms(),
+            })
+            .await;
+        result
+    }
+
+    fn check_instrument(&self, instrument: &Instrument) -> Result<()> {
+        ws_pair(instrument, WsVersion::V1).map(drop)
+    }
+}
+
+fn now_ms() -> u64 {
+    Utc::now().timestamp_millis() as u64
+}
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::config::app_config::ensure;
+use crate::execution::order_action::{OrderType, Side};
+use crate::kraken::capture;
+use crate::kraken::kraken_client::KrakenClient;
+use crate::kraken::symbols::{WsVersion, ws_pair};
+use crate::types::{instrument::Instrument, price::Price, quantity::Quantity};
+
+pub const KRAKEN_WS_AUTH_URL: &str = "wss://ws-auth.kraken.com/v2";
+
+/// Frames waiting for the socket.
+const SOCKET_QUEUE: usize = 256;
+
+/// Errors Kraken answers a request carrying an expired or revoked token with.
+const TOKEN_ERRORS: [&str; 2] = ["EAPI:Invalid token", "ESession:Invalid session"];
+
+/// Places and cancels over the authenticated v2 websocket rather than REST, saving a
+/// round trip of signing and HTTP per order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrderSocketConfig {
+    /// Off, every order goes over REST. On, REST is still used while the socket is down.
+    pub enabled: bool,
+    /// Milliseconds to wait for Kraken to answer a request before its outcome is unknown.
+    pub ack_timeout_ms: u64,
+    /// Milliseconds between attempts to reconnect a dropped socket.
+    pub reconnect_delay_ms: u64,
+}
+
+impl Default for OrderSocketConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ack_timeout_ms: 5_000,
+            reconnect_delay_ms: 2_000,
+        }
+    }
+}
+
+impl OrderSocketConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.ack_timeout_ms > 0,
+            format!("{path}.ack_timeout_ms"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.reconnect_delay_ms > 0,
+            format!("{path}.reconnect_delay_ms"),
+            "must be > 0",
+        )
+    }
+}
+
+/// Why a request over the socket failed, for deciding what to do instead.
+#[derive(Debug)]
+pub enum SocketError {
+    /// The socket is not connected, so the request was never sent.
+    Down,
+    /// The request was sent but not answered in time, or the socket dropped first.
+    /// Kraken may have acted on it.
+    NoAck,
+    /// Kraken answered with an error.
+    Refused(String),
+}
+
+impl fmt::Display for SocketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Down => write!(f, "kraken order socket is down"),
+            Self::NoAck => write!(f, "kraken order socket request went unanswered"),
+            Self::Refused(error) => write!(f, "kraken api error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SocketError {}
+
+/// Kraken's answer to one request, matched to it by `req_id`.
+#[derive(Debug, Deserialize)]
+struct Ack {
+    req_id: u64,
+    success: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    result: Option<Value>,
+}
+
+#[derive(Debug, Default)]
+struct SocketState {
+    /// Frames for the current connection; `None` while disconnected.
+    frames: Mutex<Option<mpsc::Sender<String>>>,
+    token: Mutex<Option<String>>,
+    next_req_id: AtomicU64,
+    /// Requests sent and waiting on their ack, by `req_id`.
+    pending: Mutex<HashMap<u64, oneshot::Sender<Ack>>>,
+}
+
+impl SocketState {
+    fn acknowledge(&self, ack: Ack) {
+        match self.pending.lock().unwrap().remove(&ack.req_id) {
+            Some(waiting) => {
+                let _ = waiting.send(ack);
+            }
+            None => tracing::debug!(req_id = ack.req_id, "ack for no pending request"),
+        }
+    }
+
+    /// Drops the connection's queue and every request waiting on it, which then fail
+    /// as unanswered.
+    fn disconnected(&self) {
+        *self.frames.lock().unwrap() = None;
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+/// The authenticated websocket orders are placed and cancelled over. Connects, and
+/// reconnects after a drop, in a task of its own; requests made while it is down fail
+/// at once with [`SocketError::Down`]. Clones share the connection.
+#[derive(Debug, Clone)]
+pub struct KrakenOrderSocket {
+    state: Arc<SocketState>,
+    client: KrakenClient,
+    ack_timeout: Duration,
+}
+
+impl KrakenOrderSocket {
+    /// Connects to `url` with tokens from `client`, which also paces requests against
+    /// the same trading counter as REST.
+    pub fn spawn(url: &str, client: &KrakenClient, config: &OrderSocketConfig) -> Self {
+        let socket = Self {
+            state: Arc::new(SocketState::default()),
+            client: client.clone(),
+            ack_timeout: Duration::from_millis(config.ack_timeout_ms),
+        };
+
+        let url = url.to_string();
+        let state = Arc::clone(&socket.state);
+        let client = client.clone();
+        let reconnect_delay = Duration::from_millis(config.reconnect_delay_ms);
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(error) = run_once(&url, &state, &client).await {
+                    tracing::warn!(
+                        "kraken order socket failed; orders go over REST until it reconnects: {error:#}"
+                    );
+                }
+                state.disconnected();
+                tokio::time::sleep(reconnect_delay).await;
+            }
+        });
+
+        socket
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.state.frames.lock().unwrap().is_some()
+    }
+
+    /// Places a limit order of `order_type`.
+    pub async fn add_order(
+        &self,
+        instrument: &Instrument,
+        side: Side,
+        price: Price,
+        quantity: Quantity,
+        order_type: OrderType,
+        client_order_id: &str,
+    ) -> Result<(), SocketError> {
+        // Checked first, so an order that goes over REST instead is not paced twice.
+        if !self.is_connected() {
+            return Err(SocketError::Down);
+        }
+        let symbol = ws_pair(instrument, WsVersion::V2)
+            .map_err(|error| SocketError::Refused(format!("{error:#}")))?;
+        self.client
+            .pace(
+                "/0/private/AddOrder",
+                &client_order_id_param(client_order_id),
+            )
+            .await;
+
+        let side = match side {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        };
+        let mut params = json!({
+            "order_type": "limit",
+            "side": side,
+            "limit_price": price.as_f64(),
+            "order_qty": quantity.as_f64(),
+            "symbol": symbol,
+            "post_only": order_type.is_post_only(),
+            "cl_ord_id": client_order_id,
+        });
+        if order_type == OrderType::ImmediateOrCancel {
+            params["time_in_force"] = json!("ioc");
+        }
+        let result = self.call("add_order", params).await?;
+
+        tracing::info!(
+            client_order_id,
+            order_id = result.get("order_id").and_then(serde_json::Value::as_str),
+            "add order acknowledged"
+        );
+        Ok(())
+    }
+
+    pub async fn cancel_order(&self, client_order_id: &str) -> Result<(), SocketError> {
+        if !self.is_connected() {
+            return Err(SocketError::Down);
+        }
+        self.client
+            .pace(
+                "/0/private/CancelOrder",
+                &client_order_id_param(client_order_id),
+            )
+            .await;
+
+        self.call("cancel_order", json!({ "cl_ord_id": [client_order_id] }))
+            .await?;
+
+        tracing::info!(client_order_id, "cancel order acknowledged");
+        Ok(())
+    }
+
+    /// Sends `method` and waits for its result. A request refused for its token is sent
+    /// once more with a fresh one; the refusal means Kraken did not act on it.
+    async fn call(&self, method: &str, params: Value) -> Result<Value, SocketError> {
+        match self.request(method, params.clone()).await {
+            Err(SocketError::Refused(error))
+                if TOKEN_ERRORS.iter().any(|known| error.starts_with(known)) =>
+            {
+                tracing::warn!(
+                    method,
+                    "kraken order socket token expired; renewing: {error}"
+                );
+                let token = self.client.websocket_token().await.map_err(|error| {
+                    SocketError::Refused(format!("token expired and renewal failed: {error:#}"))
+                })?;
+                *self.state.token.lock().unwrap() = Some(token);
+                self.request(method, params).await
+            }
+            outcome => outcome,
+        }
+    }
+
+    async fn request(&self, method: &str, mut params: Value) -> Result<Value, SocketError> {
+        let frames = self
+            .state
+            .frames
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(SocketError::Down)?;
+        params["token"] = json!(self.state.token.lock().unwrap().clone());
+
+        let req_id = self.state.next_req_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (waiting, ack) = oneshot::channel();
+        self.state.pending.lock().unwrap().insert(req_id, waiting);
+
+        let frame = json!({ "method": method, "params": params, "req_id": req_id });
+        if frames.send(frame.to_string()).await.is_err() {
+            self.state.pending.lock().unwrap().remove(&req_id);
+            return Err(SocketError::Down);
+        }
+
+        let ack = match tokio::time::timeout(self.ack_timeout, ack).await {
+            Ok(Ok(ack)) => ack,
+            Ok(Err(_)) => return Err(SocketError::NoAck),
+            Err(_) => {
+                self.state.pending.lock().unwrap().remove(&req_id);
+                tracing::warn!(method, req_id, "no ack from kraken order socket");
+                return Err(SocketError::NoAck);
+            }
+        };
+
+        if !ack.success {
+            return Err(SocketError::Refused(
+                ack.error.unwrap_or_else(|| "request failed".to_string()),
+            ));
+        }
+        Ok(ack.result.unwrap_or(Value::Null))
+    }
+}
+
+fn client_order_id_param(client_order_id: &str) -> [(String, String); 1] {
+    [("cl_ord_id".to_string(), client_order_id.to_string())]
+}
+
+/// One connection: authenticates, then sends queued frames and routes acks until the
+/// socket drops.
+async fn run_once(url: &str, state: &SocketState, client: &KrakenClient) -> Result<()> {
+    let token = client.websocket_token().await?;
+    *state.token.lock().unwrap() = Some(token);
+
+    let (mut ws, _) = connect_async(url)
+        .await
+        .with_context(|| format!("connect_async({url}) failed"))?;
+
+    let (frames, mut outgoing) = mpsc::channel::<String>(SOCKET_QUEUE);
+    *state.frames.lock().unwrap() = Some(frames);
+    tracing::info!("kraken order socket connected");
+
+    loop {
+        tokio::select! {
+            Some(frame) = outgoing.recv() => {
+                capture::sent("orders", [], &frame);
+                ws.send(Message::Text(frame)).await?;
+            }
+            message = ws.next() => {
+                let Some(message) = message else {
+                    bail!("closed by kraken");
+                };
+                let Ok(text) = message?.into_text() else {
+                    continue;
+                };
+                capture::received("orders", &text);
+
+                // Heartbeats and status updates carry no `req_id`.
+                if let Ok(ack) = serde_json::from_str::<Ack>(&text) {
+                    state.acknowledge(ack);
+                }
+            }
+        }
+    }
+}
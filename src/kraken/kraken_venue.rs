@@ -1,59 +1,198 @@
 use async_trait::async_trait;
+use chrono::DateTime;
 use tokio::sync::broadcast;
 
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow};
 
 use crate::{
+    engine::supervisor::Supervisor,
     execution::{
         DynamicInventorySource, ExecutionVenue, ReportSender,
-        order_action::{OrderAction, OrderType},
-        order_report::OrderReport,
+        order_action::{Order, OrderAction},
+        order_report::{OrderReport, RejectKind},
+        trade_checkpoint::TradeCheckpoint,
         types::OpenOrder,
     },
     kraken::{
-        kraken_client::KrakenClient, kraken_config::KrakenConfig,
-        kraken_executions::KrakenExecutions, kraken_inventory::KrakenInventory,
+        kraken_client::{self, KrakenClient, KrakenOpenOrder, RequestError},
+        kraken_config::KrakenConfig,
+        kraken_executions::KrakenExecutions,
+        kraken_inventory::KrakenInventory,
+        kraken_order_socket::{KRAKEN_WS_AUTH_URL, KrakenOrderSocket, SocketError},
+        symbols::{kraken_pair, pair_names},
     },
-    types::instrument::Instrument,
+    random::SeededRng,
+    types::{instrument::Instrument, price::Price, quantity::Quantity},
 };
 
 #[derive(Debug, Clone)]
 pub struct KrakenExecutionVenue {
+    config: KrakenConfig,
     client: KrakenClient,
+    /// Places and cancels go over this when it is connected, and over REST otherwise.
+    order_socket: Option<KrakenOrderSocket>,
     on_report: Option<broadcast::Sender<OrderReport>>,
+    trades: TradeCheckpoint,
 }
 
 impl KrakenExecutionVenue {
-    pub fn new(config: KrakenConfig, on_report: broadcast::Sender<OrderReport>) -> Self {
-        Self {
-            client: KrakenClient::new(config),
+    /// Connects the order socket when it is enabled, so must be called on a runtime.
+    /// REST retries are jittered from `rng`.
+    pub fn new(
+        config: KrakenConfig,
+        on_report: broadcast::Sender<OrderReport>,
+        rng: SeededRng,
+    ) -> Self {
+        let enabled = config.order_socket.enabled;
+        let venue = Self {
+            client: KrakenClient::new(config.clone(), rng),
+            config,
+            order_socket: None,
             on_report: Some(on_report),
+            trades: TradeCheckpoint::new(),
+        };
+
+        if enabled {
+            venue.with_order_socket(KRAKEN_WS_AUTH_URL)
+        } else {
+            venue
         }
     }
 
+    /// Sends REST calls to `base_url` instead of Kraken, e.g. a local stand-in. Comes
+    /// before [`Self::with_order_socket`], whose tokens are fetched over REST.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.client = self.client.with_base_url(base_url);
+        self
+    }
+
+    /// Places and cancels over the websocket at `url`, whether or not the config enables
+    /// it, with the config's timeouts.
+    pub fn with_order_socket(mut self, url: &str) -> Self {
+        self.order_socket = Some(KrakenOrderSocket::spawn(
+            url,
+            &self.client,
+            &self.config.order_socket,
+        ));
+        self
+    }
+
+    pub fn order_socket(&self) -> Option<&KrakenOrderSocket> {
+        self.order_socket.as_ref()
+    }
+
     async fn emit(&self, report: OrderReport) {
         if let Some(sender) = &self.on_report {
             let _ = sender.send(report);
         }
     }
+
+    /// Places over the socket, or over REST when it is down. An order the socket sent
+    /// but got no answer for is looked up rather than sent again, as it may be resting.
+    async fn place(&self, place: &Order) -> Result<()> {
+        if let Some(socket) = &self.order_socket {
+            let outcome = socket
+                .add_order(
+                    &place.instrument,
+                    place.side,
+                    place.price,
+                    place.quantity,
+                    place.order_type,
+                    &place.order_id,
+                )
+                .await;
+
+            match outcome {
+                Ok(()) => return Ok(()),
+                Err(SocketError::Down) => {
+                    tracing::debug!(client_order_id = %place.order_id, "order socket down; placing over REST");
+                }
+                Err(SocketError::NoAck) => {
+                    tracing::warn!(client_order_id = %place.order_id, "add order unanswered; looking the order up");
+                    return match self.client.find_order(&place.order_id).await {
+                        Ok(Some(_)) => Ok(()),
+                        Ok(None) => Err(anyhow!("{}; the order was not found", SocketError::NoAck)),
+                        Err(lookup) => Err(anyhow!(
+                            "{}; looking the order up failed: {lookup:#}",
+                            SocketError::NoAck
+                        )),
+                    };
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        self.client
+            .limit_order(
+                &place.instrument,
+                place.side,
+                place.price,
+                place.quantity,
+                place.order_type,
+                &place.order_id,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Cancels over the socket, or over REST when it is down or did not answer; a
+    /// second cancel of the same order does no harm. Returns the orders cancelled.
+    async fn cancel(&self, order_id: &str) -> Result<i64> {
+        if let Some(socket) = &self.order_socket {
+            match socket.cancel_order(order_id).await {
+                Ok(()) => return Ok(1),
+                Err(error @ SocketError::Refused(_)) => return Err(error.into()),
+                Err(error) => {
+                    tracing::debug!(client_order_id = order_id, "cancelling over REST: {error}");
+                }
+            }
+        }
+
+        Ok(self.client.cancel_order(order_id).await?.count)
+    }
 }
 
 #[async_trait]
 impl ExecutionVenue for KrakenExecutionVenue {
-    async fn open_orders(&self, _instrument: &Instrument) -> Result<Vec<OpenOrder>> {
-        todo!()
+    /// Resting orders on `instrument` placed with a client order id.
+    async fn open_orders(&self, instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        let result = self.client.open_orders().await?;
+        let pair = kraken_pair(instrument)?;
+
+        Ok(result
+            .open
+            .into_iter()
+            .filter(|(_, order)| order.descr.pair == pair)
+            .filter_map(|(txid, order)| match open_order(instrument, &order) {
+                Ok(open) => open,
+                Err(error) => {
+                    tracing::warn!(%instrument, %txid, "skipping unreadable open order: {error:#}");
+                    None
+                }
+            })
+            .collect())
     }
 
-    async fn spawn_inventory(&self, instrument: &Instrument) -> Result<DynamicInventorySource> {
-        let inventory = KrakenInventory::spawn(instrument).await?;
+    fn check_instrument(&self, instrument: &Instrument) -> Result<()> {
+        pair_names(instrument).map(drop)
+    }
+
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        let inventory = KrakenInventory::spawn(&self.client, instrument, supervisor).await?;
 
         Ok(Box::new(inventory))
     }
 
-    async fn spawn_reports(&self, on_report: ReportSender) -> Result<()> {
-        KrakenExecutions::spawn(on_report).await?;
+    async fn spawn_reports(&self, on_report: ReportSender, supervisor: &Supervisor) -> Result<()> {
+        KrakenExecutions::spawn(&self.config, on_report, self.trades.clone(), supervisor).await
+    }
 
-        Ok(())
+    fn trade_checkpoint(&self) -> Option<TradeCheckpoint> {
+        Some(self.trades.clone())
     }
 
     async fn execute(&self, actions: &[OrderAction]) -> Result<()> {
@@ -93,8 +232,8 @@ impl ExecutionVenue for KrakenExecutionVenue {
 
                     self.emit(cancel).await;
 
-                    let outcome = match self.client.cancel_order(order_id).await {
-                        Ok(res) if res.count > 0 => OrderReport::Cancelled {
+                    let outcome = match self.cancel(order_id).await {
+                        Ok(count) if count > 0 => OrderReport::Cancelled {
                             order_id: order_id.clone(),
                             instrument: instrument.clone(),
                             side: *side,
@@ -135,21 +274,7 @@ impl ExecutionVenue for KrakenExecutionVenue {
 
                     self.emit(placed).await;
 
-                    let result = match place.order_type {
-                        OrderType::PostOnlyLimit => {
-                            self.client
-                                .limit_order(
-                                    &place.instrument,
-                                    place.side,
-                                    place.price,
-                                    place.quantity,
-                                    &place.order_id,
-                                )
-                                .await
-                        }
-                    };
-
-                    let outcome = match result {
+                    let outcome = match self.place(place).await {
                         Ok(_) => OrderReport::Accepted {
                             order_id: place.order_id.clone(),
                             instrument: place.instrument.clone(),
@@ -161,6 +286,37 @@ impl ExecutionVenue for KrakenExecutionVenue {
                             order_id: place.order_id.clone(),
                             instrument: place.instrument.clone(),
                             side: place.side,
+                            kind: reject_kind(&error),
+                            reason: error.to_string(),
+                        },
+                    };
+
+                    self.emit(outcome).await;
+                }
+
+                OrderAction::Amend {
+                    order_id,
+                    instrument,
+                    side,
+                    new_price,
+                    new_quantity,
+                } => {
+                    let outcome = match self
+                        .client
+                        .amend_order(instrument, order_id, *new_price, *new_quantity)
+                        .await
+                    {
+                        Ok(_) => OrderReport::Amended {
+                            order_id: order_id.clone(),
+                            instrument: instrument.clone(),
+                            side: *side,
+                            price: *new_price,
+                            quantity: *new_quantity,
+                        },
+                        Err(error) => OrderReport::AmendRejected {
+                            order_id: order_id.clone(),
+                            instrument: instrument.clone(),
+                            side: *side,
                             reason: error.to_string(),
                         },
                     };
@@ -173,3 +329,45 @@ impl ExecutionVenue for KrakenExecutionVenue {
         Ok(())
     }
 }
+
+/// Why Kraken refused a place that failed with `error`.
+fn reject_kind(error: &anyhow::Error) -> RejectKind {
+    if let Some(error) = error.downcast_ref::<RequestError>() {
+        return error.reject_kind();
+    }
+    match error.downcast_ref::<SocketError>() {
+        Some(SocketError::Refused(error)) => kraken_client::reject_kind(error),
+        _ => RejectKind::Other,
+    }
+}
+
+/// `order` as an [`OpenOrder`] on `instrument`; `None` for orders without a client order
+/// id, which the engine cannot have placed.
+fn open_order(instrument: &Instrument, order: &KrakenOpenOrder) -> Result<Option<OpenOrder>> {
+    let Some(order_id) = order.cl_ord_id.clone() else {
+        return Ok(None);
+    };
+
+    let decimal = |field: &str, value: &str| -> Result<f64> {
+        value
+            .parse()
+            .with_context(|| format!("invalid {field} {value:?}"))
+    };
+    let quantity = |field: &str, value: &str| -> Result<Quantity> {
+        Quantity::new(decimal(field, value)?).with_context(|| format!("invalid {field} {value:?}"))
+    };
+    let volume = quantity("vol", &order.vol)?;
+    let filled = quantity("vol_exec", &order.vol_exec)?;
+
+    Ok(Some(OpenOrder {
+        order_id,
+        instrument: instrument.clone(),
+        side: order.descr.side.parse()?,
+        price: Price::new(decimal("price", &order.descr.price)?),
+        remaining: volume.saturating_sub(filled),
+        filled,
+        opened_at: order
+            .opentm
+            .and_then(|opentm| DateTime::from_timestamp_millis((opentm * 1_000.0).round() as i64)),
+    }))
+}
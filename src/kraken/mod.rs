@@ -1,7 +1,12 @@
+pub mod capture;
+pub mod kraken_book;
 pub mod kraken_client;
 pub mod kraken_config;
 pub mod kraken_executions;
 pub mod kraken_inventory;
 pub mod kraken_market;
+pub mod kraken_order_socket;
 pub mod kraken_venue;
-pub mod utils;
+pub mod rate_limit;
+pub mod symbols;
+pub(crate) mod utils;
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::config::app_config::ensure;
+
+/// Kraken's REST rate limits: counters that rise with each call and decay over time,
+/// rejecting calls that would take them over their ceiling. Defaults are the starter
+/// tier's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Ceiling of the counter every private call other than placing and cancelling
+    /// counts against: 15 on starter accounts, 20 on intermediate and pro.
+    pub api_capacity: f64,
+    /// Points that counter loses a second: 0.33 on starter, 0.5 intermediate, 1 pro.
+    pub api_decay_per_sec: f64,
+    /// Ceiling of the counter places and cancels count against: 60 on starter, 125
+    /// intermediate, 180 pro. Kraken keeps one per pair; this is one for all of them.
+    pub trading_capacity: f64,
+    /// Points that counter loses a second: 1 on starter, 2.34 intermediate, 3.75 pro.
+    pub trading_decay_per_sec: f64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            api_capacity: 15.0,
+            api_decay_per_sec: 0.33,
+            trading_capacity: 60.0,
+            trading_decay_per_sec: 1.0,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        for (field, value) in [
+            ("api_capacity", self.api_capacity),
+            ("api_decay_per_sec", self.api_decay_per_sec),
+            ("trading_capacity", self.trading_capacity),
+            ("trading_decay_per_sec", self.trading_decay_per_sec),
+        ] {
+            ensure(value > 0.0, format!("{path}.{field}"), "must be > 0")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Current level of each counter, as the limiter tracks it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitState {
+    pub api: f64,
+    pub trading: f64,
+}
+
+/// Paces private calls so Kraken's counters stay under their ceilings: a call that would
+/// overflow its counter waits for it to decay instead of being rejected. Clones share
+/// the counters.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    api: Arc<Mutex<Counter>>,
+    trading: Arc<Mutex<Counter>>,
+    /// When each order still young enough to cost a cancel penalty was placed, by client
+    /// order id.
+    placed: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            api: Arc::new(Mutex::new(Counter::new(
+                config.api_capacity,
+                config.api_decay_per_sec,
+            ))),
+            trading: Arc::new(Mutex::new(Counter::new(
+                config.trading_capacity,
+                config.trading_decay_per_sec,
+            ))),
+            placed: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Waits until the call to `uri_path` with `params` fits under its counter, then
+    /// counts it.
+    pub async fn acquire(&self, uri_path: &str, params: &[(String, String)]) {
+        let (counter, cost) = self.cost(uri_path, params, Instant::now());
+        if cost <= 0.0 {
+            return;
+        }
+
+        loop {
+            let wait = counter.lock().unwrap().take(cost, Instant::now());
+            match wait {
+                None => return,
+                Some(wait) => {
+                    tracing::debug!(
+                        uri_path,
+                        wait_ms = wait.as_millis() as u64,
+                        "pacing kraken call"
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+    }
+
+    pub fn state(&self) -> RateLimitState {
+        let now = Instant::now();
+        RateLimitState {
+            api: self.api.lock().unwrap().level(now),
+            trading: self.trading.lock().unwrap().level(now),
+        }
+    }
+
+    /// The counter a call goes against and what it costs there, per Kraken's rules:
+    /// history queries cost 2, other queries 1; a place costs 1, and a cancel or an amend
+    /// a penalty that falls with the order's age. Cancel all is not counted.
+    fn cost(
+        &self,
+        uri_path: &str,
+        params: &[(String, String)],
+        now: Instant,
+    ) -> (&Arc<Mutex<Counter>>, f64) {
+        let client_order_id = params
+            .iter()
+            .find(|(name, _)| name == "cl_ord_id")
+            .map(|(_, value)| value.as_str());
+
+        match uri_path {
+            "/0/private/AddOrder" => {
+                let mut placed = self.placed.lock().unwrap();
+                placed.retain(|_, at| now.duration_since(*at) < CANCEL_PENALTY_AGE);
+                if let Some(client_order_id) = client_order_id {
+                    placed.insert(client_order_id.to_string(), now);
+                }
+                (&self.trading, 1.0)
+            }
+            "/0/private/CancelOrder" => {
+                let placed_at =
+                    client_order_id.and_then(|id| self.placed.lock().unwrap().remove(id));
+                let penalty = placed_at.map_or(0.0, |at| cancel_penalty(now.duration_since(at)));
+                (&self.trading, penalty)
+            }
+            "/0/private/AmendOrder" => {
+                let placed_at =
+                    client_order_id.and_then(|id| self.placed.lock().unwrap().get(id).copied());
+                let penalty = placed_at.map_or(0.0, |at| amend_penalty(now.duration_since(at)));
+                (&self.trading, 1.0 + penalty)
+            }
+            "/0/private/CancelAll" => (&self.trading, 0.0),
+            "/0/private/TradesHistory"
+            | "/0/private/Ledgers"
+            | "/0/private/QueryLedgers"
+            | "/0/private/QueryTrades" => (&self.api, 2.0),
+            _ => (&self.api, 1.0),
+        }
+    }
+}
+
+/// Orders older than this cancel without a penalty.
+const CANCEL_PENALTY_AGE: Duration = Duration::from_secs(300);
+
+/// Kraken's penalty for cancelling an order `age` after placing it.
+fn cancel_penalty(age: Duration) -> f64 {
+    match age.as_secs() {
+        0..5 => 8.0,
+        5..10 => 6.0,
+        10..15 => 5.0,
+        15..45 => 4.0,
+        45..90 => 2.0,
+        90..300 => 1.0,
+        _ => 0.0,
+    }
+}
+
+/// Kraken's penalty, on top of the fixed point, for amending an order `age` after
+/// placing it.
+fn amend_penalty(age: Duration) -> f64 {
+    match age.as_secs() {
+        0..5 => 3.0,
+        5..10 => 2.0,
+        10..15 => 1.0,
+        _ => 0.0,
+    }
+}
+
+#[derive(Debug)]
+struct Counter {
+    capacity: f64,
+    decay_per_sec: f64,
+    level: f64,
+    updated: Instant,
+}
+
+impl Counter {
+    fn new(capacity: f64, decay_per_sec: f64) -> Self {
+        Self {
+            capacity,
+            decay_per_sec,
+            level: 0.0,
+            updated: Instant::now(),
+        }
+    }
+
+    fn level(&mut self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.level = (self.level - elapsed * self.decay_per_sec).max(0.0);
+        self.updated = now;
+        self.level
+    }
+
+    /// Counts `cost` if it fits, or returns how long until it will. A call costing more
+    /// than the whole capacity goes through once the counter is empty.
+    fn take(&mut self, cost: f64, now: Instant) -> Option<Duration> {
+        let level = self.level(now);
+        if level + cost <= self.capacity || level == 0.0 {
+            self.level += cost;
+            return None;
+        }
+
+        let excess = level + cost - self.capacity;
+        Some(Duration::from_secs_f64(excess / self.decay_per_sec))
+    }
+}
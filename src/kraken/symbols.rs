@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::app_config::ensure;
+use crate::types::instrument::Instrument;
+
+/// A Kraken websocket API version. v1 spells bitcoin `XBT`, like the REST API; v2 spells
+/// it `BTC`, like the trading rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsVersion {
+    /// `wss://ws.kraken.com`: `XBT/GBP`.
+    V1,
+    /// `wss://ws.kraken.com/v2` and `wss://ws-auth.kraken.com/v2`: `BTC/GBP`.
+    V2,
+}
+
+/// Process-wide, like the venue capture, so the REST client and every websocket reader
+/// spell pairs alike without the table being threaded through the venues.
+static SYMBOLS: OnceLock<BTreeMap<String, KrakenPairNames>> = OnceLock::new();
+
+/// Kraken's names for the pairs it is most often asked for, keyed as in the trading rules:
+/// the REST pair, the v1 websocket pair and the v2 websocket symbol.
+const BUILT_IN: &[(&str, &str, &str, &str)] = &[
+    ("BTC/GBP", "XBTGBP", "XBT/GBP", "BTC/GBP"),
+    ("BTC/USD", "XBTUSD", "XBT/USD", "BTC/USD"),
+    ("BTC/EUR", "XBTEUR", "XBT/EUR", "BTC/EUR"),
+    ("ETH/GBP", "ETHGBP", "ETH/GBP", "ETH/GBP"),
+    ("ETH/USD", "ETHUSD", "ETH/USD", "ETH/USD"),
+    ("ETH/EUR", "ETHEUR", "ETH/EUR", "ETH/EUR"),
+    ("ETH/BTC", "ETHXBT", "ETH/XBT", "ETH/BTC"),
+    ("SOL/GBP", "SOLGBP", "SOL/GBP", "SOL/GBP"),
+    ("SOL/USD", "SOLUSD", "SOL/USD", "SOL/USD"),
+    ("SOL/EUR", "SOLEUR", "SOL/EUR", "SOL/EUR"),
+    ("SOL/BTC", "SOLXBT", "SOL/XBT", "SOL/BTC"),
+    ("XRP/GBP", "XRPGBP", "XRP/GBP", "XRP/GBP"),
+    ("XRP/USD", "XRPUSD", "XRP/USD", "XRP/USD"),
+    ("XRP/EUR", "XRPEUR", "XRP/EUR", "XRP/EUR"),
+    ("ADA/GBP", "ADAGBP", "ADA/GBP", "ADA/GBP"),
+    ("ADA/USD", "ADAUSD", "ADA/USD", "ADA/USD"),
+    ("ADA/EUR", "ADAEUR", "ADA/EUR", "ADA/EUR"),
+    ("LTC/GBP", "LTCGBP", "LTC/GBP", "LTC/GBP"),
+    ("LTC/USD", "LTCUSD", "LTC/USD", "LTC/USD"),
+    ("LTC/EUR", "LTCEUR", "LTC/EUR", "LTC/EUR"),
+    ("DOGE/USD", "XDGUSD", "XDG/USD", "DOGE/USD"),
+    ("DOGE/EUR", "XDGEUR", "XDG/EUR", "DOGE/EUR"),
+    ("GBP/USD", "GBPUSD", "GBP/USD", "GBP/USD"),
+    ("EUR/USD", "EURUSD", "EUR/USD", "EUR/USD"),
+    ("EUR/GBP", "EURGBP", "EUR/GBP", "EUR/GBP"),
+    ("USDT/USD", "USDTUSD", "USDT/USD", "USDT/USD"),
+    ("USDC/USD", "USDCUSD", "USDC/USD", "USDC/USD"),
+];
+
+/// What Kraken calls one pair on each of its APIs, e.g. `XBTGBP`, `XBT/GBP` and
+/// `BTC/GBP` for BTC/GBP.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KrakenPairNames {
+    pub rest: String,
+    pub ws_v1: String,
+    pub ws_v2: String,
+}
+
+impl KrakenPairNames {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        for (field, name) in [
+            ("rest", &self.rest),
+            ("ws_v1", &self.ws_v1),
+            ("ws_v2", &self.ws_v2),
+        ] {
+            ensure(!name.is_empty(), format!("{path}.{field}"), "must be set")?;
+        }
+        Ok(())
+    }
+
+    fn ws(&self, version: WsVersion) -> &str {
+        match version {
+            WsVersion::V1 => &self.ws_v1,
+            WsVersion::V2 => &self.ws_v2,
+        }
+    }
+}
+
+/// Adds `pairs`, keyed `BASE/QUOTE`, to the built-in table, replacing any built-in entry
+/// for the same pair. Until this is called the built-in table alone is used.
+pub fn install(pairs: &BTreeMap<String, KrakenPairNames>) {
+    if pairs.is_empty() {
+        return;
+    }
+
+    let mut table = built_in();
+    table.extend(pairs.iter().map(|(pair, names)| (key(pair), names.clone())));
+    if SYMBOLS.set(table).is_err() {
+        warn!("Kraken pair names already installed");
+        return;
+    }
+    info!(pairs = pairs.len(), "Kraken pair names added");
+}
+
+fn built_in() -> BTreeMap<String, KrakenPairNames> {
+    BUILT_IN
+        .iter()
+        .map(|&(pair, rest, ws_v1, ws_v2)| {
+            let names = KrakenPairNames {
+                rest: rest.to_string(),
+                ws_v1: ws_v1.to_string(),
+                ws_v2: ws_v2.to_string(),
+            };
+            (pair.to_string(), names)
+        })
+        .collect()
+}
+
+fn table() -> &'static BTreeMap<String, KrakenPairNames> {
+    static BUILT_IN_ONLY: OnceLock<BTreeMap<String, KrakenPairNames>> = OnceLock::new();
+    SYMBOLS
+        .get()
+        .unwrap_or_else(|| BUILT_IN_ONLY.get_or_init(built_in))
+}
+
+fn key(pair: &str) -> String {
+    pair.to_uppercase()
+}
+
+/// Kraken's names for `instrument`. Fails for a pair in neither the built-in table nor
+/// `venue.kraken.pairs`.
+pub fn pair_names(instrument: &Instrument) -> Result<&'static KrakenPairNames> {
+    table().get(&key(&instrument.to_string())).ok_or_else(|| {
+        anyhow!("Kraken has no known names for {instrument}; add them under venue.kraken.pairs")
+    })
+}
+
+/// Websocket pair name of `instrument`, e.g. `XBT/GBP` on v1 and `BTC/GBP` on v2.
+pub fn ws_pair(instrument: &Instrument, version: WsVersion) -> Result<String> {
+    Ok(pair_names(instrument)?.ws(version).to_string())
+}
+
+/// REST pair name of `instrument`, e.g. `XBTGBP`.
+pub fn kraken_pair(instrument: &Instrument) -> Result<String> {
+    Ok(pair_names(instrument)?.rest.clone())
+}
+
+/// The pair a websocket `symbol` names, e.g. BTC/GBP for `BTC/GBP` on v2.
+pub fn ws_instrument(symbol: &str, version: WsVersion) -> Result<Instrument> {
+    let (pair, _) = table()
+        .iter()
+        .find(|(_, names)| names.ws(version) == symbol)
+        .ok_or_else(|| anyhow!("no known Kraken pair is called {symbol} on the websocket"))?;
+    pair.parse()
+}
+
+/// Base and quote currency of a REST pair name such as `SOLGBP` or the legacy `XXBTZGBP`,
+/// named as in the trading rules: `BTC` rather than `XBT`.
+pub fn split_kraken_pair(pair: &str) -> Option<(String, String)> {
+    const QUOTES: [&str; 13] = [
+        "ZGBP", "ZUSD", "ZEUR", "ZCAD", "ZJPY", "USDT", "USDC", "XXBT", "GBP", "USD", "EUR", "XBT",
+        "ETH",
+    ];
+
+    QUOTES.iter().find_map(|quote| {
+        let base = pair.strip_suffix(quote).filter(|base| !base.is_empty())?;
+        Some((kraken_asset(base), kraken_asset(quote)))
+    })
+}
+
+/// Trading-rules name of a Kraken asset code, dropping the `X`/`Z` prefix of legacy codes.
+fn kraken_asset(code: &str) -> String {
+    const LEGACY: [&str; 12] = [
+        "XXBT", "XETH", "XLTC", "XXRP", "XXLM", "XXMR", "XXDG", "XETC", "ZGBP", "ZUSD", "ZEUR",
+        "ZCAD",
+    ];
+
+    let code = if LEGACY.contains(&code) || code == "ZJPY" {
+        &code[1..]
+    } else {
+        code
+    };
+
+    match code {
+        "XBT" => "BTC".to_string(),
+        "XDG" => "DOGE".to_string(),
+        code => code.to_string(),
+    }
+}
@@ -1,3 +1,4 @@
+use crate::kraken::capture;
 use crate::kraken::kraken_config::KrakenConfig;
 use base64::{Engine as _, engine::general_purpose};
 use hmac::{Hmac, Mac};
@@ -32,8 +33,14 @@ pub async fn get_websocket_token(config: &KrakenConfig) -> anyhow::Result<String
 
     let sign = sign_request(path, &postdata, &nonce, &config.api_secret)?;
 
+    capture::sent(
+        path,
+        [("API-Key", config.api_key.as_str()), ("API-Sign", &sign)],
+        &postdata,
+    );
+
     let client = reqwest::Client::new();
-    let resp = client
+    let text = client
         .post(format!("https://api.kraken.com{}", path))
         .header("API-Key", &config.api_key)
         .header("API-Sign", sign)
@@ -42,12 +49,16 @@ pub async fn get_websocket_token(config: &KrakenConfig) -> anyhow::Result<String
         .send()
         .await?
         .error_for_status()?
-        .json::<serde_json::Value>()
+        .text()
         .await?;
 
+    let resp: serde_json::Value = serde_json::from_str(&text)?;
     let token = resp["result"]["token"]
         .as_str()
         .ok_or_else(|| anyhow::anyhow!("No token in response: {:?}", resp))?;
 
+    capture::secret(token);
+    capture::received(path, &text);
+
     Ok(token.to_string())
 }
@@ -0,0 +1,32 @@
+//! Maker-only market making engine.
+//!
+//! The binary is a thin wrapper over [`cli`] and [`engine::engine::run`], which takes the venue and
+//! market data source as [`engine::engine::Connections`], so the engine can be embedded
+//! or driven by tests against other implementations of
+//! [`execution::ExecutionVenue`] and [`market::market_source::MarketDataSource`]. The
+//! pipeline it runs (scheduling, strategy, risk and order management) is public module
+//! by module, and `backtest` replays recorded market data through it.
+
+pub mod admin;
+pub mod alerts;
+pub mod backtest;
+pub mod cli;
+pub mod clock;
+pub mod coinbase;
+pub mod config;
+pub mod engine;
+pub mod events;
+pub mod execution;
+pub mod inventory;
+pub mod kraken;
+pub mod market;
+pub mod random;
+pub mod risk;
+pub mod scenario;
+pub mod scheduling;
+pub mod signals;
+pub mod state;
+pub mod stats;
+pub mod strategy;
+pub mod telemetry;
+pub mod types;
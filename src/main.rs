@@ -1,236 +1,141 @@
-mod events;
-mod execution;
-mod inventory;
-mod kraken;
-mod market;
-mod risk;
-mod scenario;
-mod scheduling;
-mod signals;
-mod strategy;
-mod types;
-
-use std::time::{Duration, Instant};
-
-use anyhow::Result;
-use clap::Parser;
+use std::path::Path;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
 use dotenvy::dotenv;
-use tokio::sync::{broadcast, mpsc};
-use tracing::{error, info, warn};
-use tracing_subscriber::EnvFilter;
-
-use crate::events::MarketEvent;
-use crate::execution::order_action::OrderAction;
-use crate::execution::order_manager::OrderManager;
-use crate::execution::order_report::OrderReport;
-use crate::kraken::kraken_market::KrakenMarket;
-use crate::market::market_source::MarketDataSource;
-use crate::market::market_state::MarketState;
-use crate::risk::checks::min_edge::MinEdgeCheck;
-use crate::risk::checks::{
-    churn_throttle::ChurnThrottleCheck, exposure_limit::ExposureLimitCheck,
-    inventory_available::InventoryAvailableCheck, kill_switch::KillSwitchCheck,
-    market_freshness::MarketFreshnessCheck, market_sanity::MarketSanityCheck,
-};
-use crate::risk::context::RiskContext;
-use crate::risk::decision::RiskDecision;
-use crate::risk::engine::RiskEngine;
-use crate::scenario::scenario::Scenario;
-use crate::scenario::strategies::StrategyKind;
-use crate::scenario::venues::VenueKind;
-use crate::scheduling::policies::in_flight_policy::InFlightPolicy;
-use crate::scheduling::policies::min_interval_policy::{self, MinIntervalPolicy};
-use crate::scheduling::policies::top_of_book_tick_move_policy::TopOfBookTickMovePolicy;
-use crate::scheduling::policies::trading_hours_policy::TradingHoursPolicy;
-use crate::scheduling::quote_scheduler::QuoteScheduler;
-use crate::scheduling::schedule_context::ScheduleContext;
-use crate::scheduling::types::ScheduleDecision;
-use crate::types::instrument::Instrument;
-
-const STARTUP_ACTIONS: &[OrderAction] = &[OrderAction::CancelAll];
-
-#[derive(Debug, Clone, Parser)]
-struct Args {
-    #[arg(long, value_enum, default_value = "dry-run")]
-    pub venue: VenueKind,
-
-    #[arg(long, value_enum, default_value = "regime-switch")]
-    pub strategy: StrategyKind,
-
-    #[arg(long, default_value = "SOL")]
-    pub base: String,
-
-    #[arg(long, default_value = "GBP")]
-    pub quote: String,
-}
+use tracing::Instrument as _;
+use tracing::info_span;
+use uuid::Uuid;
+
+use accumulator::cli::args::{Cli, Command, ConfigCommand, RunArgs};
+use accumulator::cli::output::{Output, OutputFormat};
+use accumulator::cli::{cleanup, commands};
+use accumulator::config::app_config::AppConfig;
+use accumulator::config::reload::ConfigLoader;
+use accumulator::engine::engine::{Connections, Engine};
+use accumulator::kraken::kraken_client::KrakenClient;
+use accumulator::kraken::kraken_config::KrakenConfig;
+use accumulator::random::SeededRng;
+use accumulator::telemetry::logging;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     dotenv().ok();
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::from_default_env()
-                .add_directive("accumulator=info".parse().unwrap())
-                .add_directive("market=info".parse().unwrap())
-                .add_directive("execution=info".parse().unwrap()),
-        )
-        .with_target(false)
-        .with_thread_ids(true)
-        .init();
-
-    let args = Args::parse();
-    let instrument = Instrument::load(args.base, args.quote)?;
-
-    let (market_event_sender, mut market_event_receiver) = mpsc::channel::<MarketEvent>(10_000);
-    let (order_report_sender, _) = broadcast::channel::<OrderReport>(10_000);
-    let mut order_report_receiver = order_report_sender.subscribe();
-    let mut order_report_log_receiver = order_report_sender.subscribe();
-
-    tokio::spawn(async move {
-        loop {
-            match order_report_log_receiver.recv().await {
-                Ok(report) => info!(?report),
-                Err(broadcast::error::RecvError::Lagged(n)) => {
-                    warn!(lagged = n, "order report logger lagged; dropped messages");
-                }
-                Err(broadcast::error::RecvError::Closed) => break,
-            }
-        }
-    });
-
-    tokio::spawn({
-        let instrument = instrument.clone();
-        let kraken_source = KrakenMarket::default();
-        async move {
-            loop {
-                if let Err(error) = kraken_source
-                    .subscribe(&instrument, market_event_sender.clone())
-                    .await
-                {
-                    error!("KrakenMarket stopped with error: {error:?}");
-                }
-
-                tokio::time::sleep(Duration::from_secs(1)).await;
-            }
+
+    let cli = Cli::try_parse_args(std::env::args_os()).unwrap_or_else(|error| error.exit());
+    let (config_path, output) = (cli.config.clone(), cli.output);
+
+    let result = match cli.into_command() {
+        Command::Run(args) => return run(config_path.as_deref(), args).await,
+        Command::Cleanup(args) => return cleanup(config_path.as_deref(), args, output).await,
+        Command::Config(ConfigCommand::Check) => {
+            let config =
+                AppConfig::load(config_path.as_deref()).context("invalid configuration")?;
+            commands::check_config(&config)
         }
-    });
-
-    let mut order_manager = OrderManager::default();
-
-    let venue = Scenario::execution_venue(args.venue, order_report_sender.clone()).await?;
-    venue.spawn_reports(order_report_sender.clone()).await?;
-
-    let inventory_source = venue.spawn_inventory(&instrument).await?.subscribe();
-
-    venue.execute(STARTUP_ACTIONS).await?;
-
-    let strategy = Scenario::strategy(args.strategy, &instrument);
-    let max_exposure_in_quote = instrument.trading_rules().max_exposure_in_quote;
-
-    let mut risk_engine = RiskEngine::new(vec![
-        Box::new(KillSwitchCheck::new(false)),
-        Box::new(MarketFreshnessCheck::new(Duration::from_secs(3))),
-        Box::new(MarketSanityCheck::new()),
-        Box::new(ChurnThrottleCheck::new(Duration::from_millis(800))),
-        Box::new(MinEdgeCheck::for_instrument(&instrument)),
-        Box::new(ExposureLimitCheck::new(max_exposure_in_quote)),
-        Box::new(InventoryAvailableCheck::new()),
-    ]);
-
-    let mut market_state = MarketState::new();
-    let mut signal_state = Scenario::signals(args.strategy);
-
-    let min_interval_policy = MinIntervalPolicy::new(Duration::from_millis(200));
-    min_interval_policy.on_report(order_report_sender.subscribe());
-
-    let mut quote_scheduler = QuoteScheduler::new(vec![
-        Box::new(InFlightPolicy),
-        Box::new(TopOfBookTickMovePolicy::new(1.0)),
-        Box::new(TradingHoursPolicy::for_instrument(&instrument)),
-        Box::new(min_interval_policy),
-    ]);
-
-    loop {
-        tokio::select! {
-            report = order_report_receiver.recv() => {
-                match report {
-                    Ok(report) => order_manager.on_report(report),
-                    Err(broadcast::error::RecvError::Lagged(n)) => {
-                        warn!(lagged = n, "engine lagged on order reports; state may be stale until next report");
-                    }
-                    Err(broadcast::error::RecvError::Closed) => {
-                        error!("order report channel closed");
-                        break;
-                    }
-                }
-            }
-
-            Some(event) = market_event_receiver.recv() => {
-                tracing::debug!(?event);
-                let now = Instant::now();
-
-                market_state.on_market_event(&event);
-                signal_state.update(&market_state, now);
-
-                let scheduler_context = ScheduleContext {
-                    now,
-                    instrument: &instrument,
-                    market_state: &market_state,
-                    order_manager: &order_manager,
-                };
-
-                match quote_scheduler.decide(&scheduler_context) {
-                    ScheduleDecision::Evaluate => {}
-                    ScheduleDecision::Skip(reason) => {
-                        warn!(?reason, "scheduling skipped");
-
-                        continue;
-                    }
-                }
-
-                let inventory = *inventory_source.borrow();
-
-                let target_result = strategy.compute_target(&market_state, &signal_state, inventory);
-                match target_result {
-                    Err(reason) => warn!(?reason, "no quote"),
-                    Ok(target) => {
-                        let context = RiskContext {
-                            instrument: &instrument,
-                            market_state: &market_state,
-                            target: &target,
-                            inventory,
-                            now,
-                        };
-
-                        match risk_engine.evaluate(&context, target.clone()) {
-                            RiskDecision::Approved(approved_target) => {
-                                let actions = order_manager
-                                    .actions_for_target(&instrument, &approved_target, now)
-                                    .await?;
-
-                                if !actions.is_empty() {
-                                    venue.execute(&actions).await?;
-                                }
-                            }
-                            RiskDecision::Hold(hold) => {
-                                info!(reasons = ?hold.reasons, target = ?target,  "risk hold");
-                            }
-                            RiskDecision::Rejected(rejection) => {
-                                warn!(
-                                    reasons = ?rejection.reasons,
-                                    required_actions = ?rejection.required_actions,
-                                    target = ?target,
-                                    "risk rejection"
-                                );
-
-                                venue.execute(&rejection.required_actions).await?;
-                            }
-                        }
-                    }
-                }
-            }
+        Command::Venue(command) => {
+            let config = AppConfig::load(config_path.as_deref())?;
+            let rng = SeededRng::resolve(config.seed);
+            let client = KrakenClient::new(
+                KrakenConfig::resolve(&config.venue.kraken)?,
+                rng.stream("kraken_retry"),
+            );
+            commands::execute(&command, &client).await
         }
+    }?;
+
+    println!("{}", result.render(output)?);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The config file with the `run` flags applied on top.
+fn resolve_config(config_path: Option<&Path>, args: &RunArgs) -> Result<AppConfig> {
+    let mut config = AppConfig::load(config_path)?;
+    args.clone().apply(&mut config);
+    config.validate().context("invalid configuration")?;
+
+    Ok(config)
+}
+
+/// Cancels every open order on the configured instruments and prints the outcome,
+/// failing the exit code if any may be left resting.
+async fn cleanup(
+    config_path: Option<&Path>,
+    args: RunArgs,
+    output: OutputFormat,
+) -> Result<ExitCode> {
+    let config = resolve_config(config_path, &args)?;
+    let instruments = config
+        .instruments
+        .iter()
+        .map(InstrumentConfig::load)
+        .collect::<Result<Vec<_>>>()?;
+
+    let rng = SeededRng::resolve(config.seed);
+    let (venue, reports) = Connections::venue_from_config(&config, &rng).await?;
+    let summary = cleanup::cleanup(
+        &venue,
+        &reports,
+        &instruments,
+        &config.venue,
+        cleanup::CLEANUP_CANCEL_TIMEOUT,
+    )
+    .await?;
+
+    let failed = summary.failed();
+    println!("{}", Output::Cleanup(summary).render(output)?);
+
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+async fn run(config_path: Option<&Path>, args: RunArgs) -> Result<ExitCode> {
+    let config = resolve_config(config_path, &args)?;
+
+    logging::init(config.logging.format);
+
+    let session_id = Uuid::new_v4().simple().to_string()[..8].to_string();
+    let instruments = config
+        .instruments
+        .iter()
+        .map(InstrumentConfig::load)
+        .collect::<Result<Vec<_>>>()?;
+
+    let symbols = instruments
+        .iter()
+        .map(Instrument::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let span = info_span!(
+        "session",
+        session_id = %session_id,
+        instruments = %symbols,
+        venue = %config.venue.kind,
+        strategy = %config.strategy.kind,
+    );
+
+    let shutdown = async {
+        let rng = SeededRng::resolve(config.seed);
+        let connections = Connections::from_config(&config, &rng).await?;
+
+        // Reloads re-read the same file and re-apply the same flags.
+        let config_path = config_path.map(Path::to_path_buf);
+        let loader: ConfigLoader = Box::new(move || resolve_config(config_path.as_deref(), &args));
+
+        Engine::start(&session_id, config, instruments, connections)
+            .await?
+            .with_config_reload(loader)
+            .run()
+            .await
     }
+    .instrument(span)
+    .await?;
 
-    Ok(())
+    Ok(shutdown.exit_code())
 }
@@ -1,11 +1,122 @@
+use std::fmt;
+use std::path::PathBuf;
+
 use anyhow::Result;
 use async_trait::async_trait;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 
+use crate::config::app_config::ensure;
 use crate::events::MarketEvent;
 use crate::types::instrument::Instrument;
 
+/// Depths Kraken offers for a `book` subscription.
+const BOOK_DEPTHS: [u32; 5] = [10, 25, 100, 500, 1000];
+
+/// Where market data comes from, independently of the venue orders go to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarketSourceKind {
+    #[default]
+    Kraken,
+    /// Coinbase Advanced Trade's public websocket.
+    Coinbase,
+}
+
+impl fmt::Display for MarketSourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Kraken => write!(f, "kraken"),
+            Self::Coinbase => write!(f, "coinbase"),
+        }
+    }
+}
+
+/// What the market data feed subscribes to beyond trades and the top of the book.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketConfig {
+    pub source: MarketSourceKind,
+    /// Levels a side of the order book to follow, for strategies sizing against visible
+    /// liquidity; the depth feed is off when unset. Kraken only.
+    pub book_depth: Option<u32>,
+    /// Append every book and trade the engine handles to this JSON lines journal, e.g.
+    /// `journals/sol.jsonl`, for replaying with `backtest`.
+    pub record: Option<PathBuf>,
+    /// Seconds without a message before the feed is pinged to check it is still there.
+    /// Both sources send a heartbeat every second on a quiet market.
+    pub idle_timeout_secs: u64,
+    /// Seconds to wait for anything back after a ping before reconnecting.
+    pub pong_timeout_secs: u64,
+}
+
+impl Default for MarketConfig {
+    fn default() -> Self {
+        Self {
+            source: MarketSourceKind::default(),
+            book_depth: None,
+            record: None,
+            idle_timeout_secs: 10,
+            pong_timeout_secs: 5,
+        }
+    }
+}
+
+impl MarketConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.book_depth
+                .is_none_or(|depth| BOOK_DEPTHS.contains(&depth)),
+            format!("{path}.book_depth"),
+            "must be one of 10, 25, 100, 500 or 1000",
+        )?;
+        ensure(
+            self.source == MarketSourceKind::Kraken || self.book_depth.is_none(),
+            format!("{path}.book_depth"),
+            "only available with the kraken source",
+        )?;
+        ensure(
+            self.idle_timeout_secs > 0,
+            format!("{path}.idle_timeout_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.pong_timeout_secs > 0,
+            format!("{path}.pong_timeout_secs"),
+            "must be > 0",
+        )
+    }
+}
+
 #[async_trait]
 pub trait MarketDataSource: Send + Sync {
     async fn subscribe(&self, instrument: &Instrument, channel: Sender<MarketEvent>) -> Result<()>;
+
+    /// Fails when the source cannot name `instrument`. Checked at startup, before any
+    /// subscription.
+    fn check_instrument(&self, _instrument: &Instrument) -> Result<()> {
+        Ok(())
+    }
 }
+
+/// The venue refused a market data subscription. Reconnecting sends the same request, so
+/// the engine shuts down rather than run on without market data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionRejected {
+    pub pair: String,
+    pub channel: String,
+    pub reason: String,
+}
+
+impl fmt::Display for SubscriptionRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} subscription for {} rejected: {}",
+            self.channel, self.pair, self.reason
+        )
+    }
+}
+
+impl std::error::Error for SubscriptionRejected {}
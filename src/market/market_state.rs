@@ -1,41 +1,110 @@
 use std::fmt;
 use std::time::{Duration, Instant};
 
-use crate::events::MarketEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::events::{BookLevel, MarketEvent};
+use crate::execution::order_action::Side;
 use crate::types::price::Price;
 
-#[derive(Clone)]
+/// One kind of market data, whose freshness is judged on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketFeed {
+    /// The top of the book.
+    Book,
+    Trades,
+}
+
+/// The price at the touch strategies and signals anchor on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MidKind {
+    /// Halfway between the best bid and ask.
+    #[default]
+    Mid,
+    /// The mid pulled towards the thinner side of the touch, by size.
+    Microprice,
+}
+
+#[derive(Clone, Default)]
 pub struct MarketState {
     best_bid: Option<Price>,
     best_ask: Option<Price>,
+    /// Quantities at the touch, from the last book that carried them.
+    bid_size: Option<f64>,
+    ask_size: Option<f64>,
     last_trade_price: Option<Price>,
     last_event_instant: Option<Instant>,
+    last_book_instant: Option<Instant>,
+    last_trade_instant: Option<Instant>,
+    /// Depth ladders, best first; empty without a depth subscription.
+    bids: Vec<BookLevel>,
+    asks: Vec<BookLevel>,
+    /// Market events applied since the state was created or last invalidated.
+    event_count: u64,
 }
 
 impl MarketState {
     pub fn new() -> Self {
-        Self {
-            best_bid: None,
-            best_ask: None,
-            last_trade_price: None,
-            last_event_instant: None,
-        }
+        Self::default()
     }
 
-    pub fn on_market_event(&mut self, event: &MarketEvent) {
-        self.last_event_instant = Some(Instant::now());
-
+    pub fn on_market_event(&mut self, event: &MarketEvent, now: Instant) {
         match event {
             MarketEvent::TopOfBook {
-                best_bid, best_ask, ..
+                best_bid,
+                best_ask,
+                bid_size,
+                ask_size,
+                ..
             } => {
                 self.best_bid = Some(*best_bid);
                 self.best_ask = Some(*best_ask);
+                self.bid_size = *bid_size;
+                self.ask_size = *ask_size;
+                self.last_book_instant = Some(now);
             }
             MarketEvent::Trade { price, .. } => {
                 self.last_trade_price = Some(*price);
+                self.last_trade_instant = Some(now);
+            }
+            MarketEvent::BookUpdate {
+                bids,
+                asks,
+                snapshot,
+                ..
+            } => {
+                if *snapshot {
+                    self.bids.clear();
+                    self.asks.clear();
+                }
+                for level in bids {
+                    apply_level(&mut self.bids, Side::Buy, *level);
+                }
+                for level in asks {
+                    apply_level(&mut self.asks, Side::Sell, *level);
+                }
             }
+            MarketEvent::SourceDisconnected { .. } => {
+                self.invalidate();
+                return;
+            }
+            // Nothing is known until the feed's first book.
+            MarketEvent::SourceReconnected { .. } => return,
         }
+        self.last_event_instant = Some(now);
+        self.event_count += 1;
+    }
+
+    pub fn event_count(&self) -> u64 {
+        self.event_count
+    }
+
+    /// Forgets the book and when it was last heard from, so the state reads as stale
+    /// until the feed sends another, rather than once `max_age` has passed.
+    pub fn invalidate(&mut self) {
+        *self = Self::default();
     }
 
     pub fn best_bid(&self) -> Option<Price> {
@@ -52,21 +121,121 @@ impl MarketState {
         Some(Price::new((bid + ask) / 2.0))
     }
 
+    /// `(bid * ask_size + ask * bid_size) / (bid_size + ask_size)`, nearer the side
+    /// with less resting. The mid when the feed sent no sizes, or both are zero.
+    pub fn microprice(&self) -> Option<Price> {
+        let bid = self.best_bid?.as_f64();
+        let ask = self.best_ask?.as_f64();
+        let (Some(bid_size), Some(ask_size)) = (self.bid_size, self.ask_size) else {
+            return self.mid_price();
+        };
+        let (bid_size, ask_size) = (bid_size.max(0.0), ask_size.max(0.0));
+        let total = bid_size + ask_size;
+        if total <= 0.0 {
+            return self.mid_price();
+        }
+        Some(Price::new((bid * ask_size + ask * bid_size) / total))
+    }
+
+    /// The mid or microprice, as `kind` picks.
+    pub fn anchor(&self, kind: MidKind) -> Option<Price> {
+        match kind {
+            MidKind::Mid => self.mid_price(),
+            MidKind::Microprice => self.microprice(),
+        }
+    }
+
     pub fn spread(&self) -> Option<f64> {
         let bid = self.best_bid?.as_f64();
         let ask = self.best_ask?.as_f64();
         Some(ask - bid)
     }
 
+    /// `(bid_size - ask_size) / (bid_size + ask_size)` at the touch, from -1 with only
+    /// asks resting to 1 with only bids. `None` when the feed sent no sizes, or both are
+    /// zero.
+    pub fn book_imbalance(&self) -> Option<f64> {
+        let bid = self.bid_size?.max(0.0);
+        let ask = self.ask_size?.max(0.0);
+        let total = bid + ask;
+        (total > 0.0).then(|| (bid - ask) / total)
+    }
+
+    /// Quantity resting at `price` on either side of the depth ladder; zero for a price
+    /// with no level, or without a depth subscription.
+    pub fn depth_at(&self, price: Price) -> f64 {
+        self.bids
+            .iter()
+            .chain(&self.asks)
+            .find(|level| level.price == price)
+            .map_or(0.0, |level| level.quantity)
+    }
+
+    /// Up to `n` levels of each side of the depth ladder, best first, as bids and asks.
+    pub fn top_n_levels(&self, n: usize) -> (&[BookLevel], &[BookLevel]) {
+        (
+            &self.bids[..n.min(self.bids.len())],
+            &self.asks[..n.min(self.asks.len())],
+        )
+    }
+
+    /// When the last market event arrived, by the engine clock.
+    pub fn last_event_instant(&self) -> Option<Instant> {
+        self.last_event_instant
+    }
+
     pub fn last_trade_price(&self) -> Option<Price> {
         self.last_trade_price
     }
 
-    pub fn is_stale(&self, max_age: Duration) -> bool {
-        match self.last_event_instant {
-            Some(last) => last.elapsed() > max_age,
-            None => true,
+    /// How long before `now` the top of the book last changed; `None` before the first.
+    pub fn book_age(&self, now: Instant) -> Option<Duration> {
+        self.last_book_instant
+            .map(|last| now.saturating_duration_since(last))
+    }
+
+    /// How long before `now` the last trade printed; `None` before the first.
+    pub fn trade_age(&self, now: Instant) -> Option<Duration> {
+        self.last_trade_instant
+            .map(|last| now.saturating_duration_since(last))
+    }
+
+    /// How long before `now` `feed` was last heard from.
+    pub fn age(&self, feed: MarketFeed, now: Instant) -> Option<Duration> {
+        match feed {
+            MarketFeed::Book => self.book_age(now),
+            MarketFeed::Trades => self.trade_age(now),
+        }
+    }
+
+    /// Whether more than `max_age` has passed by `now` since the top of the book last
+    /// changed. Trades and depth updates do not keep a frozen top of book fresh.
+    pub fn is_stale(&self, max_age: Duration, now: Instant) -> bool {
+        self.book_age(now).is_none_or(|age| age > max_age)
+    }
+}
+
+/// Sets `level` in `ladder`, kept best first for `side`, or removes it for a zero quantity.
+fn apply_level(ladder: &mut Vec<BookLevel>, side: Side, level: BookLevel) {
+    let better = |a: Price, b: Price| match side {
+        Side::Buy => a > b,
+        Side::Sell => a < b,
+    };
+    let index = ladder
+        .iter()
+        .position(|existing| !better(existing.price, level.price))
+        .unwrap_or(ladder.len());
+
+    let exists = ladder
+        .get(index)
+        .is_some_and(|existing| existing.price == level.price);
+    match (exists, level.quantity > 0.0) {
+        (true, true) => ladder[index].quantity = level.quantity,
+        (true, false) => {
+            ladder.remove(index);
         }
+        (false, true) => ladder.insert(index, level),
+        (false, false) => {}
     }
 }
 
@@ -78,7 +247,9 @@ impl fmt::Debug for MarketState {
             .field("best_ask", &self.best_ask)
             .field("last_trade_price", &self.last_trade_price)
             .field("last_event_instant", &self.last_event_instant)
-            .field("is_stale", &self.is_stale(Duration::from_secs(60)))
+            .field("last_book_instant", &self.last_book_instant)
+            .field("last_trade_instant", &self.last_trade_instant)
+            .field("depth_levels", &(self.bids.len(), self.asks.len()))
             .finish()
     }
 }
@@ -0,0 +1,71 @@
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use uuid::{Builder, Uuid};
+
+/// Seeded source of randomness. Every random choice the engine makes draws from one of
+/// these, so a run is reproducible from its seed.
+///
+/// Components take their own [`stream`](Self::stream) rather than sharing one sequence,
+/// so adding draws to one component does not shift the numbers another sees.
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    seed: u64,
+    rng: Arc<Mutex<StdRng>>,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+        }
+    }
+
+    /// Uses `seed`, or draws a fresh one when none is configured. The seed is logged
+    /// either way so any run can be repeated.
+    pub fn resolve(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(rand::random);
+        tracing::info!(seed, "random seed");
+
+        Self::new(seed)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Independent generator for the component called `name`, derived from this seed.
+    pub fn stream(&self, name: &str) -> Self {
+        Self::new(self.seed ^ fnv1a(name.as_bytes()))
+    }
+
+    pub fn random_range(&self, range: Range<u32>) -> u32 {
+        self.rng.lock().unwrap().random_range(range)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn random_fraction(&self) -> f64 {
+        self.rng.lock().unwrap().random()
+    }
+
+    /// `true` with probability `p`, clamped to `[0, 1]`.
+    pub fn random_bool(&self, p: f64) -> bool {
+        self.rng.lock().unwrap().random_bool(p.clamp(0.0, 1.0))
+    }
+
+    /// Random (version 4) UUID built from this generator's bytes.
+    pub fn uuid_v4(&self) -> Uuid {
+        let bytes = self.rng.lock().unwrap().random();
+        Builder::from_random_bytes(bytes).into_uuid()
+    }
+}
+
+/// Stable across builds and platforms, unlike the std hasher.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -1,8 +1,10 @@
 use std::time::{Duration, Instant};
 
+use crate::risk::config::RiskLimits;
 use crate::risk::context::RiskContext;
 use crate::risk::decision::RiskReason;
 use crate::risk::engine::RiskCheck;
+use crate::types::quote_target::QuoteTarget;
 
 #[derive(Debug)]
 pub struct ChurnThrottleCheck {
@@ -48,6 +50,10 @@ impl RiskCheck for ChurnThrottleCheck {
         "ChurnThrottleCheck"
     }
 
+    fn update_limits(&mut self, limits: &RiskLimits) {
+        self.min_update_interval = limits.churn_min_interval;
+    }
+
     fn evaluate(&mut self, context: &RiskContext) -> Result<(), Vec<RiskReason>> {
         let now = context.now;
         let tick = context.instrument.trading_rules().price_tick;
@@ -61,9 +67,6 @@ impl RiskCheck for ChurnThrottleCheck {
             if bid_changed && Self::is_too_soon(now, self.last_bid_update, self.min_update_interval)
             {
                 reasons.push(RiskReason::ChurnThrottleBid);
-            } else if bid_changed {
-                self.last_bid_update = Some(now);
-                self.last_bid_price = Some(bid_price);
             }
         }
 
@@ -74,9 +77,6 @@ impl RiskCheck for ChurnThrottleCheck {
             if ask_changed && Self::is_too_soon(now, self.last_ask_update, self.min_update_interval)
             {
                 reasons.push(RiskReason::ChurnThrottleAsk);
-            } else if ask_changed {
-                self.last_ask_update = Some(now);
-                self.last_ask_price = Some(ask_price);
             }
         }
 
@@ -86,4 +86,16 @@ impl RiskCheck for ChurnThrottleCheck {
             Err(reasons)
         }
     }
+
+    /// The window restarts only from prices that were placed, not merely approved.
+    fn commit(&mut self, placed: &QuoteTarget, now: Instant) {
+        if let Some(bid) = &placed.bid {
+            self.last_bid_update = Some(now);
+            self.last_bid_price = Some(bid.price.as_f64());
+        }
+        if let Some(ask) = &placed.ask {
+            self.last_ask_update = Some(now);
+            self.last_ask_price = Some(ask.price.as_f64());
+        }
+    }
 }
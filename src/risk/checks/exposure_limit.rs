@@ -1,18 +1,28 @@
 use crate::{
     execution::order_action::Side,
-    risk::{context::RiskContext, decision::RiskReason, engine::RiskCheck},
+    risk::{config::RiskLimits, context::RiskContext, decision::RiskReason, engine::RiskCheck},
+    types::{fx::ExposureCurrency, quantity::Quantity},
 };
 
 pub struct ExposureLimitCheck {
     max_exposure_in_quote: f64,
+    exposure_currency: ExposureCurrency,
 }
 
 impl ExposureLimitCheck {
     pub fn new(max_exposure_in_quote: f64) -> Self {
         Self {
             max_exposure_in_quote,
+            exposure_currency: ExposureCurrency::default(),
         }
     }
+
+    /// Measures exposure, and the limit, in `exposure_currency` rather than the pair's
+    /// quote currency.
+    pub fn with_exposure_currency(mut self, exposure_currency: ExposureCurrency) -> Self {
+        self.exposure_currency = exposure_currency;
+        self
+    }
 }
 
 impl RiskCheck for ExposureLimitCheck {
@@ -20,34 +30,55 @@ impl RiskCheck for ExposureLimitCheck {
         "ExposureLimitCheck"
     }
 
+    fn update_limits(&mut self, limits: &RiskLimits) {
+        self.max_exposure_in_quote = limits.max_exposure_in_quote;
+    }
+
     fn evaluate(&mut self, ctx: &RiskContext) -> Result<(), Vec<RiskReason>> {
         let mid = ctx
             .market_state
             .mid_price()
             .ok_or_else(|| vec![RiskReason::MissingMarketData])?;
+        let rate = self
+            .exposure_currency
+            .rate(ctx.instrument)
+            .map_err(|missing| {
+                vec![RiskReason::MissingFxRate {
+                    from: missing.from,
+                    to: missing.to,
+                }]
+            })?;
 
         let mut reasons = Vec::new();
 
-        if let Some(bid) = ctx.target.bid {
-            let projected_base = ctx.inventory.base + bid.quantity;
-            let exposure_quote = projected_base * mid.as_f64();
-            if exposure_quote > self.max_exposure_in_quote {
-                reasons.push(RiskReason::ExposureLimit {
-                    side: Side::Buy,
-                    exposure_quote,
-                    max_exposure_in_quote: self.max_exposure_in_quote,
-                });
+        for side in [Side::Buy, Side::Sell] {
+            // Every level on the side filling at once: the target's, and any order left
+            // resting at a level the target does not quote, as it may fill before its
+            // cancel lands.
+            let resting: Quantity = ctx
+                .orders
+                .levels(side)
+                .iter()
+                .enumerate()
+                .filter(|(level, _)| ctx.target.level(side, *level).is_none())
+                .filter_map(|(_, order)| order.quote)
+                .map(|quote| quote.quantity)
+                .sum();
+            let proposed = ctx.target.total_quantity(side);
+            if (resting + proposed).is_zero() {
+                continue;
             }
-        }
 
-        if let Some(ask) = ctx.target.ask {
-            let projected_base = ctx.inventory.base - ask.quantity;
-            let exposure_quote = projected_base * mid.as_f64();
-            if exposure_quote < -self.max_exposure_in_quote {
+            let projected_base = ctx.inventory.base + side.signed((resting + proposed).as_f64());
+            let exposure_quote = projected_base * mid.as_f64() * rate;
+            if side.sign() * exposure_quote > self.max_exposure_in_quote {
                 reasons.push(RiskReason::ExposureLimit {
-                    side: Side::Sell,
+                    side,
                     exposure_quote,
                     max_exposure_in_quote: self.max_exposure_in_quote,
+                    inventory: ctx.inventory.base,
+                    resting,
+                    proposed,
                 });
             }
         }
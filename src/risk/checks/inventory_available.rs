@@ -1,5 +1,7 @@
+use crate::execution::order_action::Side;
 use crate::risk::{context::RiskContext, decision::RiskReason, engine::RiskCheck};
 
+#[derive(Default)]
 pub struct InventoryAvailableCheck;
 
 impl InventoryAvailableCheck {
@@ -16,10 +18,15 @@ impl RiskCheck for InventoryAvailableCheck {
     fn evaluate(&mut self, ctx: &RiskContext) -> Result<(), Vec<RiskReason>> {
         let mut reasons = Vec::new();
 
-        if let Some(bid) = ctx.target.bid {
-            let required = bid.price.as_f64() * bid.quantity;
+        if ctx.target.bid.is_some() {
+            let required: f64 = ctx
+                .target
+                .quotes(Side::Buy)
+                .map(|bid| bid.price.as_f64() * bid.quantity.as_f64())
+                .sum();
             if required > ctx.inventory.quote {
                 reasons.push(RiskReason::InsufficientInventory {
+                    side: Side::Buy,
                     asset: ctx.instrument.quote().to_string(),
                     required,
                     available: ctx.inventory.quote,
@@ -27,10 +34,11 @@ impl RiskCheck for InventoryAvailableCheck {
             }
         }
 
-        if let Some(ask) = ctx.target.ask {
-            let required = ask.quantity;
+        if ctx.target.ask.is_some() {
+            let required = ctx.target.total_quantity(Side::Sell).as_f64();
             if required > ctx.inventory.base {
                 reasons.push(RiskReason::InsufficientInventory {
+                    side: Side::Sell,
                     asset: ctx.instrument.base().to_string(),
                     required,
                     available: ctx.inventory.base,
@@ -1,15 +1,49 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use crate::risk::context::RiskContext;
 use crate::risk::decision::RiskReason;
 use crate::risk::engine::RiskCheck;
 
+/// Shared switch the engine can flip at runtime while the check is owned by the risk engine.
+#[derive(Debug, Clone, Default)]
+pub struct KillSwitch {
+    engaged: Arc<AtomicBool>,
+}
+
+impl KillSwitch {
+    pub fn new(engaged: bool) -> Self {
+        Self {
+            engaged: Arc::new(AtomicBool::new(engaged)),
+        }
+    }
+
+    pub fn set(&self, engaged: bool) {
+        self.engaged.store(engaged, Ordering::SeqCst);
+    }
+
+    pub fn is_engaged(&self) -> bool {
+        self.engaged.load(Ordering::SeqCst)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct KillSwitchCheck {
-    pub enabled: bool,
+    switch: KillSwitch,
 }
 
 impl KillSwitchCheck {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self::with_switch(KillSwitch::new(enabled))
+    }
+
+    /// Check bound to an existing switch, so several risk engines can share one.
+    pub fn with_switch(switch: KillSwitch) -> Self {
+        Self { switch }
+    }
+
+    pub fn handle(&self) -> KillSwitch {
+        self.switch.clone()
     }
 }
 
@@ -19,7 +53,7 @@ impl RiskCheck for KillSwitchCheck {
     }
 
     fn evaluate(&mut self, _context: &RiskContext) -> Result<(), Vec<RiskReason>> {
-        if self.enabled {
+        if self.switch.is_engaged() {
             return Err(vec![RiskReason::KillSwitchEnabled]);
         }
         Ok(())
@@ -1,17 +1,26 @@
 use std::time::Duration;
 
+use crate::market::market_state::MarketFeed;
+use crate::risk::config::RiskLimits;
 use crate::risk::context::RiskContext;
 use crate::risk::decision::RiskReason;
 use crate::risk::engine::RiskCheck;
 
+/// Judges the top of the book and the trades each by their own age, so a live feed of
+/// one does not hide the other going quiet. A stale book is a hard stop; stale trades,
+/// when checked at all, only hold.
 #[derive(Debug, Clone)]
 pub struct MarketFreshnessCheck {
-    pub max_staleness: Duration,
+    pub max_book_age: Duration,
+    pub max_trade_age: Option<Duration>,
 }
 
 impl MarketFreshnessCheck {
-    pub fn new(max_staleness: Duration) -> Self {
-        Self { max_staleness }
+    pub fn new(max_book_age: Duration, max_trade_age: Option<Duration>) -> Self {
+        Self {
+            max_book_age,
+            max_trade_age,
+        }
     }
 }
 
@@ -20,10 +29,34 @@ impl RiskCheck for MarketFreshnessCheck {
         "MarketFreshnessCheck"
     }
 
+    fn update_limits(&mut self, limits: &RiskLimits) {
+        self.max_book_age = limits.market_max_age;
+        self.max_trade_age = limits.trade_max_age;
+    }
+
     fn evaluate(&mut self, context: &RiskContext) -> Result<(), Vec<RiskReason>> {
-        if context.market_state.is_stale(self.max_staleness) {
-            return Err(vec![RiskReason::MarketDataStale]);
+        let limits = [
+            (MarketFeed::Book, Some(self.max_book_age)),
+            (MarketFeed::Trades, self.max_trade_age),
+        ];
+
+        let reasons: Vec<_> = limits
+            .into_iter()
+            .filter_map(|(feed, max_age)| {
+                let max_age = max_age?;
+                let age = context.market_state.age(feed, context.now);
+                age.is_none_or(|age| age > max_age)
+                    .then(|| RiskReason::MarketDataStale {
+                        feed,
+                        age_ms: age.map(|age| age.as_millis() as u64),
+                    })
+            })
+            .collect();
+
+        if reasons.is_empty() {
+            Ok(())
+        } else {
+            Err(reasons)
         }
-        Ok(())
     }
 }
@@ -2,7 +2,7 @@ use crate::risk::context::RiskContext;
 use crate::risk::decision::RiskReason;
 use crate::risk::engine::RiskCheck;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct MarketSanityCheck;
 
 impl MarketSanityCheck {
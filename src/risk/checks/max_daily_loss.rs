@@ -0,0 +1,124 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::clock::SharedClock;
+use crate::risk::{
+    config::RiskLimits, context::RiskContext, decision::RiskReason, engine::RiskCheck,
+};
+
+/// The UTC day a [`MaxDailyLossCheck`] is counting.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DailyLossState {
+    pub day: NaiveDate,
+    /// Realized PnL the day opened at.
+    pub open: f64,
+    /// The loss that tripped the check, until the day rolls over.
+    pub tripped: Option<f64>,
+}
+
+/// The day's count, shared with the engine while the check is owned by the risk engine,
+/// so it can be saved and restored and a restart does not start the day's losses again.
+#[derive(Debug, Clone, Default)]
+pub struct DailyLoss {
+    state: Arc<Mutex<Option<DailyLossState>>>,
+}
+
+impl DailyLoss {
+    /// The day's count. Its opening PnL is on the engine's realized PnL, which is saved
+    /// and restored along with it.
+    pub fn snapshot(&self) -> Option<DailyLossState> {
+        *self.state.lock().unwrap()
+    }
+
+    /// Carries on a count saved by [`snapshot`](Self::snapshot). A saved day that has
+    /// passed is dropped when the next one opens.
+    pub fn restore(&self, state: DailyLossState) {
+        *self.state.lock().unwrap() = Some(state);
+    }
+
+    /// Opens `today` at `realized` unless it is already open. The engine calls this before
+    /// applying each report, so a fill just after midnight counts against the new day
+    /// rather than being taken into its opening PnL by the next evaluation.
+    pub fn open_day(&self, today: NaiveDate, realized: f64) {
+        Self::open(&mut self.state.lock().unwrap(), today, realized);
+    }
+
+    fn open(
+        state: &mut Option<DailyLossState>,
+        today: NaiveDate,
+        realized: f64,
+    ) -> &mut DailyLossState {
+        if !matches!(state, Some(day) if day.day == today) {
+            *state = None;
+        }
+        state.get_or_insert(DailyLossState {
+            day: today,
+            open: realized,
+            tripped: None,
+        })
+    }
+}
+
+/// Stops quoting for the rest of the UTC day once the day's realized losses pass the
+/// limit. Realized PnL comes from the engine's [`PnlTracker`](crate::types::pnl::PnlTracker),
+/// which follows the fill reports; the engine saves the day's count with its state, so
+/// a restart carries on with it.
+pub struct MaxDailyLossCheck {
+    max_daily_loss_in_quote: Option<f64>,
+    clock: SharedClock,
+    day: DailyLoss,
+}
+
+impl MaxDailyLossCheck {
+    /// Days roll over by `clock`, which must be the engine's.
+    pub fn new(max_daily_loss_in_quote: Option<f64>, clock: SharedClock) -> Self {
+        Self::with_state(max_daily_loss_in_quote, clock, DailyLoss::default())
+    }
+
+    /// Check counting into `day`, so the engine can save and restore it.
+    pub fn with_state(
+        max_daily_loss_in_quote: Option<f64>,
+        clock: SharedClock,
+        day: DailyLoss,
+    ) -> Self {
+        Self {
+            max_daily_loss_in_quote,
+            clock,
+            day,
+        }
+    }
+}
+
+impl RiskCheck for MaxDailyLossCheck {
+    fn name(&self) -> &'static str {
+        "MaxDailyLossCheck"
+    }
+
+    fn update_limits(&mut self, limits: &RiskLimits) {
+        self.max_daily_loss_in_quote = limits.max_daily_loss_in_quote;
+    }
+
+    fn evaluate(&mut self, ctx: &RiskContext) -> Result<(), Vec<RiskReason>> {
+        let today = self.clock.now_utc().date_naive();
+        let realized = ctx.pnl.realized();
+
+        let mut state = self.day.state.lock().unwrap();
+        let day = DailyLoss::open(&mut state, today, realized);
+
+        let Some(limit) = self.max_daily_loss_in_quote else {
+            return Ok(());
+        };
+
+        let loss = day.open - realized;
+        if day.tripped.is_none() && loss > limit {
+            day.tripped = Some(loss);
+        }
+
+        match day.tripped {
+            Some(loss) => Err(vec![RiskReason::DailyLossLimitBreached { loss, limit }]),
+            None => Ok(()),
+        }
+    }
+}
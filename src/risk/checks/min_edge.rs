@@ -1,19 +1,26 @@
 use crate::{
-    risk::{context::RiskContext, decision::RiskReason, engine::RiskCheck},
+    risk::{config::RiskLimits, context::RiskContext, decision::RiskReason, engine::RiskCheck},
     types::instrument::Instrument,
 };
 
+/// Holds quoting while the half-spread would not cover `min_half_spread` plus the maker
+/// fee on a fill at the mid.
 pub struct MinEdgeCheck {
     pub min_half_spread: f64,
+    pub maker_fee_bps: f64,
 }
 
 impl MinEdgeCheck {
-    pub fn new(min_half_spread: f64) -> Self {
-        Self { min_half_spread }
+    pub fn new(min_half_spread: f64, maker_fee_bps: f64) -> Self {
+        Self {
+            min_half_spread,
+            maker_fee_bps,
+        }
     }
 
     pub fn for_instrument(instrument: &Instrument) -> Self {
-        Self::new(instrument.trading_rules().min_half_spread)
+        let rules = instrument.trading_rules();
+        Self::new(rules.min_half_spread, rules.maker_fee_bps)
     }
 }
 
@@ -22,6 +29,11 @@ impl RiskCheck for MinEdgeCheck {
         "MinEdgeCheck"
     }
 
+    fn update_limits(&mut self, limits: &RiskLimits) {
+        self.min_half_spread = limits.min_half_spread;
+        self.maker_fee_bps = limits.maker_fee_bps;
+    }
+
     fn evaluate(&mut self, ctx: &RiskContext) -> Result<(), Vec<RiskReason>> {
         let best_bid = ctx
             .market_state
@@ -35,11 +47,15 @@ impl RiskCheck for MinEdgeCheck {
 
         let spread = best_ask - best_bid;
         let half = spread / 2.0;
+        let mid = (best_bid.as_f64() + best_ask.as_f64()) / 2.0;
+        let fee = mid * self.maker_fee_bps / 10_000.0;
+        let required = self.min_half_spread + fee;
 
-        if half < self.min_half_spread {
+        if half < required {
             return Err(vec![RiskReason::InsufficientEdge {
                 half_spread: half,
-                required: self.min_half_spread,
+                required,
+                fee,
             }]);
         }
 
@@ -4,4 +4,6 @@ pub mod inventory_available;
 pub mod kill_switch;
 pub mod market_freshness;
 pub mod market_sanity;
+pub mod max_daily_loss;
 pub mod min_edge;
+pub mod portfolio_exposure;
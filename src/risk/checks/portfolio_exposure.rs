@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    execution::order_action::Side,
+    risk::{config::RiskLimits, context::RiskContext, decision::RiskReason, engine::RiskCheck},
+    types::instrument::Instrument,
+};
+
+/// Latest base exposure, in quote currency, of every instrument sharing the cap.
+/// Exposures are summed as-is, so every instrument is expected to share a quote currency.
+#[derive(Debug, Clone, Default)]
+pub struct PortfolioExposure {
+    exposures: Arc<Mutex<HashMap<Instrument, f64>>>,
+}
+
+impl PortfolioExposure {
+    pub fn update(&self, instrument: &Instrument, exposure_quote: f64) {
+        self.exposures
+            .lock()
+            .unwrap()
+            .insert(instrument.clone(), exposure_quote);
+    }
+
+    pub fn total_excluding(&self, instrument: &Instrument) -> f64 {
+        self.exposures
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(other, _)| *other != instrument)
+            .map(|(_, exposure)| exposure)
+            .sum()
+    }
+}
+
+/// Caps the combined exposure across instruments. Each instrument's risk engine owns one of
+/// these, all sharing the same [`PortfolioExposure`].
+pub struct PortfolioExposureCheck {
+    portfolio: PortfolioExposure,
+    max_exposure_in_quote: f64,
+}
+
+impl PortfolioExposureCheck {
+    pub fn new(portfolio: PortfolioExposure, max_exposure_in_quote: f64) -> Self {
+        Self {
+            portfolio,
+            max_exposure_in_quote,
+        }
+    }
+}
+
+impl RiskCheck for PortfolioExposureCheck {
+    fn name(&self) -> &'static str {
+        "PortfolioExposureCheck"
+    }
+
+    fn update_limits(&mut self, limits: &RiskLimits) {
+        if let Some(max_exposure) = limits.max_portfolio_exposure_in_quote {
+            self.max_exposure_in_quote = max_exposure;
+        }
+    }
+
+    fn evaluate(&mut self, ctx: &RiskContext) -> Result<(), Vec<RiskReason>> {
+        let mid = ctx
+            .market_state
+            .mid_price()
+            .ok_or_else(|| vec![RiskReason::MissingMarketData])?;
+
+        self.portfolio
+            .update(ctx.instrument, ctx.inventory.exposure_quote(mid));
+        let others = self.portfolio.total_excluding(ctx.instrument);
+
+        let mut reasons = Vec::new();
+
+        for side in [Side::Buy, Side::Sell] {
+            // Every level on the side filling at once.
+            let quantity = ctx.target.total_quantity(side);
+            if quantity.is_zero() {
+                continue;
+            }
+
+            let projected_base = ctx.inventory.base + side.signed(quantity.as_f64());
+            let exposure_quote = others + projected_base * mid.as_f64();
+            if side.sign() * exposure_quote > self.max_exposure_in_quote {
+                reasons.push(RiskReason::PortfolioExposureLimit {
+                    side,
+                    exposure_quote,
+                    max_exposure_in_quote: self.max_exposure_in_quote,
+                });
+            }
+        }
+
+        if reasons.is_empty() {
+            Ok(())
+        } else {
+            Err(reasons)
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::app_config::ensure;
+use crate::types::fx::{ExposureCurrency, StaticFxRates};
+use crate::types::instrument::{Instrument, InstrumentConfig};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RiskConfig {
+    /// Start with the kill switch engaged.
+    pub kill_switch: bool,
+
+    /// A top of book older than this is treated as stale, and every order cancelled.
+    pub market_max_age_ms: u64,
+
+    /// Trades older than this hold quoting, without cancelling; unchecked when unset,
+    /// as a quiet market may go a while without one.
+    pub trade_max_age_ms: Option<u64>,
+
+    /// Minimum time between quote updates on one side.
+    pub churn_min_interval_ms: u64,
+
+    /// Overrides the instrument's `max_exposure_in_quote` trading rule.
+    pub max_exposure_in_quote: Option<f64>,
+
+    /// Currency every instrument's exposure is converted into before it is compared
+    /// with `max_exposure_in_quote`, which is then in this currency too. Each pair's
+    /// own quote currency when unset.
+    pub exposure_currency: Option<String>,
+
+    /// Fixed exchange rates for `exposure_currency`, keyed `FROM/TO`, e.g. `EUR/GBP:
+    /// 0.85`; each also gives the inverse. A pair without a rate is not quoted.
+    pub fx_rates: BTreeMap<String, f64>,
+
+    /// Overrides the instrument's `min_half_spread` trading rule.
+    pub min_half_spread: Option<f64>,
+
+    /// Cap on the combined exposure of all instruments; uncapped when unset.
+    pub max_portfolio_exposure_in_quote: Option<f64>,
+
+    /// Realized loss in a UTC day, in the quote currency, past which an instrument stops
+    /// quoting and cancels its orders until the next day; no limit when unset.
+    pub max_daily_loss_in_quote: Option<f64>,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            kill_switch: false,
+            market_max_age_ms: 3_000,
+            trade_max_age_ms: None,
+            churn_min_interval_ms: 800,
+            max_exposure_in_quote: None,
+            exposure_currency: None,
+            fx_rates: BTreeMap::new(),
+            min_half_spread: None,
+            max_portfolio_exposure_in_quote: None,
+            max_daily_loss_in_quote: None,
+        }
+    }
+}
+
+/// One instrument's risk thresholds: the config with trading-rule fallbacks applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskLimits {
+    pub market_max_age: Duration,
+    pub trade_max_age: Option<Duration>,
+    pub churn_min_interval: Duration,
+    pub max_exposure_in_quote: f64,
+    pub min_half_spread: f64,
+    pub maker_fee_bps: f64,
+    pub max_portfolio_exposure_in_quote: Option<f64>,
+    pub max_daily_loss_in_quote: Option<f64>,
+}
+
+impl RiskConfig {
+    pub fn limits(&self, instrument: &Instrument) -> RiskLimits {
+        let rules = instrument.trading_rules();
+
+        RiskLimits {
+            market_max_age: self.market_max_age(),
+            trade_max_age: self.trade_max_age_ms.map(Duration::from_millis),
+            churn_min_interval: self.churn_min_interval(),
+            max_exposure_in_quote: self
+                .max_exposure_in_quote
+                .unwrap_or(rules.max_exposure_in_quote),
+            min_half_spread: self.min_half_spread.unwrap_or(rules.min_half_spread),
+            maker_fee_bps: rules.maker_fee_bps,
+            max_portfolio_exposure_in_quote: self.max_portfolio_exposure_in_quote,
+            max_daily_loss_in_quote: self.max_daily_loss_in_quote,
+        }
+    }
+
+    pub fn exposure_currency(&self) -> ExposureCurrency {
+        match &self.exposure_currency {
+            Some(currency) => {
+                ExposureCurrency::new(currency, Arc::new(StaticFxRates::new(&self.fx_rates)))
+            }
+            None => ExposureCurrency::default(),
+        }
+    }
+
+    pub fn market_max_age(&self) -> Duration {
+        Duration::from_millis(self.market_max_age_ms)
+    }
+
+    pub fn churn_min_interval(&self) -> Duration {
+        Duration::from_millis(self.churn_min_interval_ms)
+    }
+
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.market_max_age_ms > 0,
+            format!("{path}.market_max_age_ms"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.trade_max_age_ms != Some(0),
+            format!("{path}.trade_max_age_ms"),
+            "must be > 0",
+        )?;
+        if let Some(max_exposure) = self.max_exposure_in_quote {
+            ensure(
+                max_exposure > 0.0,
+                format!("{path}.max_exposure_in_quote"),
+                "must be > 0",
+            )?;
+        }
+        if let Some(currency) = &self.exposure_currency {
+            ensure(
+                !currency.is_empty() && currency.chars().all(|c| c.is_ascii_alphanumeric()),
+                format!("{path}.exposure_currency"),
+                "must be a currency code",
+            )?;
+        }
+        for (pair, rate) in &self.fx_rates {
+            ensure(
+                pair.parse::<InstrumentConfig>().is_ok(),
+                format!("{path}.fx_rates.{pair}"),
+                "must be keyed FROM/TO",
+            )?;
+            ensure(
+                rate.is_finite() && *rate > 0.0,
+                format!("{path}.fx_rates.{pair}"),
+                "must be > 0",
+            )?;
+        }
+        if let Some(max_exposure) = self.max_portfolio_exposure_in_quote {
+            ensure(
+                max_exposure > 0.0,
+                format!("{path}.max_portfolio_exposure_in_quote"),
+                "must be > 0",
+            )?;
+        }
+        if let Some(max_loss) = self.max_daily_loss_in_quote {
+            ensure(
+                max_loss > 0.0,
+                format!("{path}.max_daily_loss_in_quote"),
+                "must be > 0",
+            )?;
+        }
+        if let Some(min_half_spread) = self.min_half_spread {
+            ensure(
+                min_half_spread >= 0.0,
+                format!("{path}.min_half_spread"),
+                "must be >= 0",
+            )?;
+        }
+
+        Ok(())
+    }
+}
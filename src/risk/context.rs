@@ -1,8 +1,10 @@
 use std::time::Instant;
 
+use crate::execution::types::OrderManagerSnapshot;
 use crate::market::market_state::MarketState;
-use crate::types::inventory::Inventory;
 use crate::types::instrument::Instrument;
+use crate::types::inventory::Inventory;
+use crate::types::pnl::PnlTracker;
 use crate::types::quote_target::QuoteTarget;
 
 #[derive(Debug)]
@@ -11,5 +13,10 @@ pub struct RiskContext<'a> {
     pub market_state: &'a MarketState,
     pub target: &'a QuoteTarget,
     pub inventory: Inventory,
+    /// Every order level working or being placed. The target's quote at a level replaces
+    /// the order there; an order at a level the target leaves empty is cancelled, and
+    /// may fill first.
+    pub orders: &'a OrderManagerSnapshot,
+    pub pnl: &'a PnlTracker,
     pub now: Instant,
 }
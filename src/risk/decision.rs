@@ -1,41 +1,154 @@
+use serde::Serialize;
+
 use crate::execution::order_action::{OrderAction, Side};
+use crate::market::market_state::MarketFeed;
+use crate::types::quantity::Quantity;
 use crate::types::quote_target::QuoteTarget;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
 pub enum RiskDecision {
-    Approved(QuoteTarget),
+    Approved(RiskApproval),
     Hold(RiskHold),
     Rejected(RiskRejection),
 }
 
-#[derive(Debug, Clone)]
+/// The target to quote. When some reasons were confined to one side, that side is
+/// left out of `target` and the reasons are kept in `stripped`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskApproval {
+    #[serde(flatten)]
+    pub target: QuoteTarget,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stripped: Vec<RiskReason>,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct RiskHold {
     pub reasons: Vec<RiskReason>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RiskRejection {
     pub reasons: Vec<RiskReason>,
     pub required_actions: Vec<OrderAction>,
 }
 
-#[derive(Debug, Clone)]
+/// Serialized tagged with its [`code`](Self::code).
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
 pub enum RiskReason {
     KillSwitchEnabled,
-    MarketDataStale,
+    /// `feed` was last heard from `age_ms` ago, past its limit; `None` if never.
+    MarketDataStale {
+        feed: MarketFeed,
+        age_ms: Option<u64>,
+    },
     MissingMarketData,
     CrossedOrInvalidBook,
     ChurnThrottleBid,
     ChurnThrottleAsk,
-    InsufficientEdge { half_spread: f64, required: f64 },
+    /// `required` is the configured minimum plus `fee`, the maker fee at the mid.
+    InsufficientEdge {
+        half_spread: f64,
+        required: f64,
+        fee: f64,
+    },
+    /// Should every order on `side` fill: `inventory` held, `resting` in orders the
+    /// target does not replace and `proposed` by the target, all in base. The exposure
+    /// and its limit are in the exposure currency when one is configured.
     ExposureLimit {
         side: Side,
         exposure_quote: f64,
         max_exposure_in_quote: f64,
+        inventory: f64,
+        resting: Quantity,
+        proposed: Quantity,
+    },
+    PortfolioExposureLimit {
+        side: Side,
+        exposure_quote: f64,
+        max_exposure_in_quote: f64,
     },
+    /// Not enough of `asset` to back every quote on `side`.
     InsufficientInventory {
+        side: Side,
         asset: String,
         required: f64,
         available: f64,
     },
+    /// Realized losses today, in the quote currency, passed the daily limit. Holds until
+    /// the UTC day rolls over.
+    DailyLossLimitBreached {
+        loss: f64,
+        limit: f64,
+    },
+    /// No rate converts the pair's quote currency into the exposure currency, so the
+    /// exposure cannot be measured against its limit.
+    MissingFxRate {
+        from: String,
+        to: String,
+    },
+}
+
+impl RiskReason {
+    /// Stable, low-cardinality identifier used for metric labels and log fields.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RiskReason::KillSwitchEnabled => "kill_switch_enabled",
+            RiskReason::MarketDataStale { .. } => "market_data_stale",
+            RiskReason::MissingMarketData => "missing_market_data",
+            RiskReason::CrossedOrInvalidBook => "crossed_or_invalid_book",
+            RiskReason::ChurnThrottleBid => "churn_throttle_bid",
+            RiskReason::ChurnThrottleAsk => "churn_throttle_ask",
+            RiskReason::InsufficientEdge { .. } => "insufficient_edge",
+            RiskReason::ExposureLimit { .. } => "exposure_limit",
+            RiskReason::PortfolioExposureLimit { .. } => "portfolio_exposure_limit",
+            RiskReason::InsufficientInventory { .. } => "insufficient_inventory",
+            RiskReason::DailyLossLimitBreached { .. } => "daily_loss_limit_breached",
+            RiskReason::MissingFxRate { .. } => "missing_fx_rate",
+        }
+    }
+
+    /// Whether the reason cancels every order rather than holding the target.
+    pub fn is_hard(&self) -> bool {
+        matches!(
+            self,
+            RiskReason::KillSwitchEnabled
+                | RiskReason::MarketDataStale {
+                    feed: MarketFeed::Book,
+                    ..
+                }
+                | RiskReason::CrossedOrInvalidBook
+                | RiskReason::DailyLossLimitBreached { .. }
+                | RiskReason::MissingFxRate { .. }
+        )
+    }
+
+    /// Whether the reason only defers new quotes on its [`side`](Self::side), keeping the
+    /// order resting there; other side-scoped reasons cancel it.
+    pub fn keeps_resting(&self) -> bool {
+        matches!(
+            self,
+            RiskReason::ChurnThrottleBid | RiskReason::ChurnThrottleAsk
+        )
+    }
+
+    /// The one side the reason objects to, if it leaves the other side free to quote.
+    pub fn side(&self) -> Option<Side> {
+        match self {
+            RiskReason::ChurnThrottleBid => Some(Side::Buy),
+            RiskReason::ChurnThrottleAsk => Some(Side::Sell),
+            RiskReason::ExposureLimit { side, .. }
+            | RiskReason::PortfolioExposureLimit { side, .. }
+            | RiskReason::InsufficientInventory { side, .. } => Some(*side),
+            RiskReason::KillSwitchEnabled
+            | RiskReason::MarketDataStale { .. }
+            | RiskReason::MissingMarketData
+            | RiskReason::CrossedOrInvalidBook
+            | RiskReason::InsufficientEdge { .. }
+            | RiskReason::DailyLossLimitBreached { .. }
+            | RiskReason::MissingFxRate { .. } => None,
+        }
+    }
 }
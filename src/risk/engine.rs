@@ -1,13 +1,37 @@
 use std::fmt;
+use std::time::Instant;
 
+use crate::clock::SharedClock;
 use crate::execution::order_action::OrderAction;
+use crate::risk::checks::{
+    churn_throttle::ChurnThrottleCheck,
+    exposure_limit::ExposureLimitCheck,
+    inventory_available::InventoryAvailableCheck,
+    kill_switch::{KillSwitch, KillSwitchCheck},
+    market_freshness::MarketFreshnessCheck,
+    market_sanity::MarketSanityCheck,
+    max_daily_loss::{DailyLoss, MaxDailyLossCheck},
+    min_edge::MinEdgeCheck,
+    portfolio_exposure::{PortfolioExposure, PortfolioExposureCheck},
+};
+use crate::risk::config::RiskLimits;
 use crate::risk::context::RiskContext;
-use crate::risk::decision::{RiskDecision, RiskHold, RiskReason, RiskRejection};
+use crate::risk::decision::{RiskApproval, RiskDecision, RiskHold, RiskReason, RiskRejection};
+use crate::types::fx::ExposureCurrency;
 use crate::types::quote_target::QuoteTarget;
 
 pub trait RiskCheck: Send + Sync {
     fn name(&self) -> &'static str;
+    /// Judges the proposed target. Only reads state: the evaluation may still be held,
+    /// rejected by another check or fail at the venue.
     fn evaluate(&mut self, context: &RiskContext) -> Result<(), Vec<RiskReason>>;
+
+    /// Records the sides of an approved target that were actually sent to the venue, for
+    /// checks whose state follows real placements.
+    fn commit(&mut self, _placed: &QuoteTarget, _now: Instant) {}
+
+    /// Takes new thresholds on a config reload, keeping any state the check has built up.
+    fn update_limits(&mut self, _limits: &RiskLimits) {}
 }
 
 pub struct RiskEngine {
@@ -19,6 +43,64 @@ impl RiskEngine {
         Self { checks }
     }
 
+    /// The checks every instrument engine runs, in order: kill switch, market freshness
+    /// and sanity, churn, edge, exposure, daily loss, the shared portfolio exposure when
+    /// `portfolio` is set, and available inventory. The instrument's exposure is measured
+    /// in `exposure_currency`, and the day's losses are counted into `daily_loss`.
+    pub fn with_default_checks(
+        limits: &RiskLimits,
+        kill_switch: KillSwitch,
+        portfolio: Option<(PortfolioExposure, f64)>,
+        exposure_currency: ExposureCurrency,
+        daily_loss: DailyLoss,
+        clock: SharedClock,
+    ) -> Self {
+        let mut checks: Vec<Box<dyn RiskCheck>> = vec![
+            Box::new(KillSwitchCheck::with_switch(kill_switch)),
+            Box::new(MarketFreshnessCheck::new(
+                limits.market_max_age,
+                limits.trade_max_age,
+            )),
+            Box::new(MarketSanityCheck::new()),
+            Box::new(ChurnThrottleCheck::new(limits.churn_min_interval)),
+            Box::new(MinEdgeCheck::new(
+                limits.min_half_spread,
+                limits.maker_fee_bps,
+            )),
+            Box::new(
+                ExposureLimitCheck::new(limits.max_exposure_in_quote)
+                    .with_exposure_currency(exposure_currency),
+            ),
+            Box::new(MaxDailyLossCheck::with_state(
+                limits.max_daily_loss_in_quote,
+                clock,
+                daily_loss,
+            )),
+        ];
+        if let Some((portfolio, max_exposure)) = portfolio {
+            checks.push(Box::new(PortfolioExposureCheck::new(
+                portfolio,
+                max_exposure,
+            )));
+        }
+        checks.push(Box::new(InventoryAvailableCheck::new()));
+
+        Self::new(checks)
+    }
+
+    pub fn update_limits(&mut self, limits: &RiskLimits) {
+        for check in &mut self.checks {
+            check.update_limits(limits);
+        }
+    }
+
+    /// Advances every check after `placed` reached the venue.
+    pub fn commit(&mut self, placed: &QuoteTarget, now: Instant) {
+        for check in &mut self.checks {
+            check.commit(placed, now);
+        }
+    }
+
     pub fn evaluate(
         &mut self,
         context: &RiskContext,
@@ -33,24 +115,45 @@ impl RiskEngine {
         }
 
         if reasons.is_empty() {
-            return RiskDecision::Approved(proposed_target);
+            return RiskDecision::Approved(RiskApproval {
+                target: proposed_target,
+                stripped: Vec::new(),
+            });
         }
 
-        let is_hard_rule = reasons.iter().any(|reason| match reason {
-            RiskReason::KillSwitchEnabled => true,
-            RiskReason::MarketDataStale => true,
-            RiskReason::CrossedOrInvalidBook => true,
-            _ => false,
-        });
-
-        if is_hard_rule {
+        if reasons.iter().any(RiskReason::is_hard) {
             return RiskDecision::Rejected(RiskRejection {
                 reasons,
                 required_actions: vec![OrderAction::CancelAll],
             });
         }
 
-        RiskDecision::Hold(RiskHold { reasons })
+        match Self::strip_sides(proposed_target, &reasons) {
+            Some(target) => RiskDecision::Approved(RiskApproval {
+                target,
+                stripped: reasons,
+            }),
+            None => RiskDecision::Hold(RiskHold { reasons }),
+        }
+    }
+
+    /// `target` without the sides `reasons` object to, when every reason is confined to
+    /// one side and a side is left to quote; `None` holds the whole target. A side only
+    /// throttled is held, keeping its resting order; any other reason clears it, which
+    /// cancels the order.
+    fn strip_sides(mut target: QuoteTarget, reasons: &[RiskReason]) -> Option<QuoteTarget> {
+        for reason in reasons {
+            let side = reason.side()?;
+            let cancels = reasons
+                .iter()
+                .any(|other| other.side() == Some(side) && !other.keeps_resting());
+            if cancels {
+                target.replace_side(side, None);
+            } else if !target.is_held(side) {
+                target.hold_side(side);
+            }
+        }
+        (target.bid.is_some() || target.ask.is_some()).then_some(target)
     }
 }
 
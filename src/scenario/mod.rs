@@ -1,4 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod scenario;
 pub mod strategies;
 pub mod venues;
-
@@ -1,15 +1,34 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::Result;
 
 use crate::{
-    execution::{dry_run::DryRunExecutionVenue, ExecutionVenue, ReportSender},
-    kraken::{kraken_config::KrakenConfig, kraken_venue::KrakenExecutionVenue},
-    scenario::{strategies::StrategyKind, venues::VenueKind},
-    signals::signal_state::SignalState,
+    coinbase::coinbase_market::CoinbaseMarket,
+    execution::{
+        ExecutionVenue, ReportSender, dry_run::DryRunExecutionVenue, guarded_venue::GuardedVenue,
+    },
+    kraken::{
+        kraken_config::KrakenConfig, kraken_market::KrakenMarket,
+        kraken_venue::KrakenExecutionVenue,
+    },
+    market::market_source::{MarketConfig, MarketDataSource, MarketSourceKind},
+    random::SeededRng,
+    scenario::{
+        strategies::StrategyKind,
+        venues::{VenueConfig, VenueKind},
+    },
+    signals::{
+        config::{SignalParams, SignalsConfig},
+        signal_state::SignalState,
+    },
     strategy::{
+        config::StrategyConfig,
         strategies::{
+            avellaneda_stoikov::AvellanedaStoikovStrategy,
+            inventory_target::InventoryTargetStrategy, layered_mm::LayeredMarketMakerStrategy,
             mean_reversion::MakerOnlyMeanReversionStrategy, regime_switch::RegimeSwitchStrategy,
-            simple_mm::SimpleMarketMakerStrategy,
-            trend_following::MakerOnlyTrendFollowingStrategy,
+            simple_mm::SimpleMarketMakerStrategy, trend_following::MakerOnlyTrendFollowingStrategy,
         },
         strategy::Strategy,
     },
@@ -18,47 +37,132 @@ use crate::{
 
 pub struct Scenario;
 
-type DynamicVenue = Box<dyn ExecutionVenue + Send + Sync>;
+pub type DynamicVenue = Box<dyn ExecutionVenue + Send + Sync>;
 
 impl Scenario {
-    pub async fn execution_venue(kind: VenueKind, on_report: ReportSender) -> Result<DynamicVenue> {
-        tracing::info!(venue = %kind, "creating execution venue");
+    pub async fn execution_venue(
+        config: &VenueConfig,
+        on_report: ReportSender,
+        rng: &SeededRng,
+    ) -> Result<DynamicVenue> {
+        tracing::info!(venue = %config.kind, "creating execution venue");
 
-        let venue: Box<dyn ExecutionVenue + Send + Sync> = match kind {
-            VenueKind::DryRun => Box::new(DryRunExecutionVenue::new(on_report)),
-            VenueKind::Kraken => {
-                let config = KrakenConfig::from_env()?;
-
-                Box::new(KrakenExecutionVenue::new(config, on_report))
-            }
+        let venue: Box<dyn ExecutionVenue + Send + Sync> = match config.kind {
+            VenueKind::DryRun => Box::new(
+                DryRunExecutionVenue::new(on_report, rng.stream("dry_run"))
+                    .with_lifecycle(config.dry_run.clone())
+                    .with_paper_inventory(config.inventory.paper()),
+            ),
+            VenueKind::Kraken => Box::new(GuardedVenue::new(
+                KrakenExecutionVenue::new(
+                    KrakenConfig::resolve(&config.kraken)?,
+                    on_report.clone(),
+                    rng.stream("kraken_retry"),
+                ),
+                config.guard.clone(),
+                on_report,
+            )),
         };
 
         Ok(venue)
     }
 
-    pub fn strategy(kind: StrategyKind, instrument: &Instrument) -> Box<dyn Strategy> {
-        tracing::info!(strategy = %kind, "creating strategy");
+    pub fn market_source(config: &MarketConfig) -> Arc<dyn MarketDataSource> {
+        tracing::info!(market_source = %config.source, "creating market data source");
 
-        match kind {
-            StrategyKind::SimpleMarketMaker => {
-                Box::new(SimpleMarketMakerStrategy::for_instrument(instrument))
-            }
-            StrategyKind::MeanReversion => {
-                Box::new(MakerOnlyMeanReversionStrategy::for_instrument(instrument))
+        let idle = Duration::from_secs(config.idle_timeout_secs);
+        let pong = Duration::from_secs(config.pong_timeout_secs);
+        match config.source {
+            MarketSourceKind::Kraken => Arc::new(
+                KrakenMarket::default()
+                    .with_book_depth(config.book_depth)
+                    .with_idle_timeout(idle, pong),
+            ),
+            MarketSourceKind::Coinbase => {
+                Arc::new(CoinbaseMarket::default().with_idle_timeout(idle, pong))
             }
-            StrategyKind::TrendFollowing => {
-                Box::new(MakerOnlyTrendFollowingStrategy::for_instrument(instrument))
-            }
-            StrategyKind::RegimeSwitch => Box::new(RegimeSwitchStrategy::for_instrument(instrument)),
         }
     }
 
-    pub fn signals(kind: StrategyKind) -> SignalState {
-        match kind {
-            StrategyKind::SimpleMarketMaker => SignalState::new(3.0, 3.0, 10.0),
-            StrategyKind::MeanReversion => SignalState::new(60.0, 600.0, 60.0),
-            StrategyKind::TrendFollowing => SignalState::new(60.0, 600.0, 60.0),
-            StrategyKind::RegimeSwitch => SignalState::new(60.0, 600.0, 60.0),
+    pub fn strategy(config: &StrategyConfig, instrument: &Instrument) -> Box<dyn Strategy> {
+        tracing::info!(strategy = %config.kind, "creating strategy");
+
+        match config.kind {
+            StrategyKind::SimpleMarketMaker => Box::new(SimpleMarketMakerStrategy::from_params(
+                instrument,
+                &config.simple_mm,
+            )),
+            StrategyKind::MeanReversion => Box::new(MakerOnlyMeanReversionStrategy::new(
+                instrument,
+                &config.mean_reversion,
+            )),
+            StrategyKind::TrendFollowing => Box::new(MakerOnlyTrendFollowingStrategy::new(
+                instrument,
+                &config.trend_following,
+            )),
+            StrategyKind::RegimeSwitch => Box::new(RegimeSwitchStrategy::new(
+                instrument,
+                &config.regime_switch,
+                &config.mean_reversion,
+                &config.trend_following,
+            )),
+            StrategyKind::AvellanedaStoikov => Box::new(AvellanedaStoikovStrategy::new(
+                instrument,
+                &config.avellaneda_stoikov,
+            )),
+            StrategyKind::LayeredMarketMaker => Box::new(LayeredMarketMakerStrategy::new(
+                instrument,
+                &config.layered_mm,
+            )),
+            StrategyKind::Accumulate => Box::new(InventoryTargetStrategy::new(
+                instrument,
+                &config.inventory_target,
+            )),
         }
     }
+
+    /// The signal settings `kind` was tuned with.
+    ///
+    /// Like `strategy`, this matches every kind without a wildcard, so a new kind does
+    /// not compile until it has both a strategy and its signals.
+    pub fn signal_params(kind: StrategyKind) -> SignalParams {
+        let (fast_tau_secs, slow_tau_secs, vol_tau_secs) = match kind {
+            StrategyKind::SimpleMarketMaker => (3.0, 3.0, 10.0),
+            StrategyKind::MeanReversion => (60.0, 600.0, 60.0),
+            StrategyKind::TrendFollowing => (60.0, 600.0, 60.0),
+            StrategyKind::RegimeSwitch => (60.0, 600.0, 60.0),
+            StrategyKind::AvellanedaStoikov => (3.0, 3.0, 30.0),
+            StrategyKind::LayeredMarketMaker => (3.0, 3.0, 10.0),
+            StrategyKind::Accumulate => (3.0, 3.0, 10.0),
+        };
+
+        SignalParams {
+            fast_tau_secs,
+            slow_tau_secs,
+            vol_tau_secs,
+            min_update_interval: Duration::from_millis(350),
+        }
+    }
+
+    /// Signals for `kind`, with the overrides `config` has for every pair or for `kind`.
+    pub fn signals(kind: StrategyKind, config: &SignalsConfig) -> SignalState {
+        SignalState::new(config.params(Self::signal_params(kind), kind, None))
+    }
+
+    /// The signals `strategy` trades `instrument` on, following the touch price it
+    /// anchors to.
+    pub fn signals_for(
+        strategy: &StrategyConfig,
+        config: &SignalsConfig,
+        instrument: &Instrument,
+    ) -> SignalState {
+        let params = config.params(
+            Self::signal_params(strategy.kind),
+            strategy.kind,
+            Some(instrument),
+        );
+        let mut signals = SignalState::new(params);
+        signals.set_mid_kind(strategy.mid_kind);
+        signals
+    }
 }
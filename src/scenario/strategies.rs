@@ -3,17 +3,34 @@ use std::str::FromStr;
 
 use anyhow::{Result, anyhow};
 use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize,
+)]
 pub enum StrategyKind {
     #[clap(name = "simple-mm")]
+    #[serde(rename = "simple-mm")]
     SimpleMarketMaker,
     #[clap(name = "mean-reversion")]
+    #[serde(rename = "mean-reversion")]
     MeanReversion,
     #[clap(name = "trend-following")]
+    #[serde(rename = "trend-following")]
     TrendFollowing,
+    #[default]
     #[clap(name = "regime-switch")]
+    #[serde(rename = "regime-switch")]
     RegimeSwitch,
+    #[clap(name = "avellaneda-stoikov")]
+    #[serde(rename = "avellaneda-stoikov")]
+    AvellanedaStoikov,
+    #[clap(name = "layered-mm")]
+    #[serde(rename = "layered-mm")]
+    LayeredMarketMaker,
+    #[clap(name = "accumulate")]
+    #[serde(rename = "accumulate")]
+    Accumulate,
 }
 
 impl fmt::Display for StrategyKind {
@@ -23,6 +40,9 @@ impl fmt::Display for StrategyKind {
             Self::MeanReversion => write!(f, "mean-reversion"),
             Self::TrendFollowing => write!(f, "trend-following"),
             Self::RegimeSwitch => write!(f, "regime-switch"),
+            Self::AvellanedaStoikov => write!(f, "avellaneda-stoikov"),
+            Self::LayeredMarketMaker => write!(f, "layered-mm"),
+            Self::Accumulate => write!(f, "accumulate"),
         }
     }
 }
@@ -36,6 +56,9 @@ impl FromStr for StrategyKind {
             "mean-reversion" => Ok(Self::MeanReversion),
             "trend-following" => Ok(Self::TrendFollowing),
             "regime-switch" => Ok(Self::RegimeSwitch),
+            "avellaneda-stoikov" => Ok(Self::AvellanedaStoikov),
+            "layered-mm" => Ok(Self::LayeredMarketMaker),
+            "accumulate" => Ok(Self::Accumulate),
             other => Err(anyhow!("unknown strategy kind: {other}")),
         }
     }
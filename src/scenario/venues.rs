@@ -1,21 +1,83 @@
 use std::fmt;
 use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::{Result, anyhow};
 use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 
-use crate::{
-    execution::{ExecutionVenue, ReportSender, dry_run::DryRunExecutionVenue},
-    kraken::{kraken_config::KrakenConfig, kraken_venue::KrakenExecutionVenue},
-};
+use crate::execution::dry_run::DryRunConfig;
+use crate::execution::guarded_venue::OrderGuardConfig;
+use crate::inventory::readiness::InventoryConfig;
+use crate::kraken::capture::CaptureConfig;
+use crate::kraken::kraken_config::KrakenSettings;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum VenueKind {
+    #[default]
     #[clap(name = "dry-run")]
     DryRun,
     Kraken,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VenueConfig {
+    pub kind: VenueKind,
+    pub kraken: KrakenSettings,
+    /// Debug capture of the raw Kraken REST and websocket payloads; off by default.
+    pub capture: CaptureConfig,
+    /// Waiting for the first balances before quoting.
+    pub inventory: InventoryConfig,
+    /// Simulated fills and expiries on the dry-run venue.
+    pub dry_run: DryRunConfig,
+    /// Seconds between comparisons of the engine's orders with the venue's open orders;
+    /// unset disables them.
+    pub reconcile_secs: Option<u64>,
+    /// Leads every client order id in place of the session id, e.g. to tell the orders
+    /// of two deployments on one account apart: 1 to 4 lowercase letters or digits.
+    /// Orders an earlier run left on the venue are only known as the engine's own by
+    /// this prefix or the intent log.
+    pub order_id_prefix: Option<String>,
+    /// Most places passed to the venue in any minute; unset uses the kind's default.
+    /// Further places are dropped and rejected, cancels always go through.
+    pub max_places_per_minute: Option<u32>,
+    /// Bounds on each place sent to a live venue; places beyond them are rejected.
+    pub guard: OrderGuardConfig,
+}
+
+impl Default for VenueConfig {
+    fn default() -> Self {
+        Self {
+            kind: VenueKind::default(),
+            kraken: KrakenSettings::default(),
+            capture: CaptureConfig::default(),
+            inventory: InventoryConfig::default(),
+            dry_run: DryRunConfig::default(),
+            reconcile_secs: Some(30),
+            order_id_prefix: None,
+            max_places_per_minute: None,
+            guard: OrderGuardConfig::default(),
+        }
+    }
+}
+
+impl VenueConfig {
+    pub fn reconcile_interval(&self) -> Option<Duration> {
+        self.reconcile_secs.map(Duration::from_secs)
+    }
+
+    /// [`max_places_per_minute`](Self::max_places_per_minute), or the default for the
+    /// kind: generous on the dry-run venue, where placing costs nothing.
+    pub fn place_limit(&self) -> u32 {
+        self.max_places_per_minute.unwrap_or(match self.kind {
+            VenueKind::DryRun => 1_200,
+            VenueKind::Kraken => 120,
+        })
+    }
+}
+
 impl fmt::Display for VenueKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::app_config::ensure;
+use crate::scheduling::policies::rejection_backoff_policy::RejectionBackoffConfig;
+use crate::scheduling::policies::venue_latency_policy::VenueLatencyConfig;
+use crate::scheduling::policies::warmup_policy::WarmupConfig;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulingConfig {
+    /// Minimum time between order placements.
+    pub min_interval_ms: u64,
+
+    /// Top-of-book move, in ticks, that triggers a re-evaluation.
+    pub min_tick_move: f64,
+
+    /// How long a place or cancel may go unanswered before the order is cancelled
+    /// defensively and forgotten.
+    pub in_flight_timeout_ms: u64,
+
+    /// Price moves of up to this many ticks, at an unchanged quantity, amend the resting
+    /// order in place rather than cancelling and placing anew. Off when unset.
+    pub amend_max_ticks: Option<u32>,
+
+    /// Times a target whose post-only place the venue refused for crossing is re-planned
+    /// straight away, a tick further behind the touch each time, before it is held
+    /// until the strategy's target moves. 0 waits for the next market event instead.
+    pub reprice_attempts: u32,
+
+    /// Pauses quoting while the venue keeps rejecting orders.
+    pub rejection_backoff: RejectionBackoffConfig,
+
+    /// Pauses quoting while the venue is slow to acknowledge placements.
+    pub venue_latency: VenueLatencyConfig,
+
+    /// Holds the first quote until the signals are warm.
+    pub warmup: WarmupConfig,
+
+    /// How often an instrument whose book has gone quiet is evaluated anyway, so
+    /// time-driven policies act without market data: quotes are pulled when trading
+    /// hours end, or when the book goes stale.
+    pub timer_interval_ms: u64,
+}
+
+impl Default for SchedulingConfig {
+    fn default() -> Self {
+        Self {
+            min_interval_ms: 200,
+            min_tick_move: 1.0,
+            in_flight_timeout_ms: 10_000,
+            amend_max_ticks: None,
+            reprice_attempts: 2,
+            rejection_backoff: RejectionBackoffConfig::default(),
+            venue_latency: VenueLatencyConfig::default(),
+            warmup: WarmupConfig::default(),
+            timer_interval_ms: 1_000,
+        }
+    }
+}
+
+impl SchedulingConfig {
+    pub fn min_interval(&self) -> Duration {
+        Duration::from_millis(self.min_interval_ms)
+    }
+
+    pub fn in_flight_timeout(&self) -> Duration {
+        Duration::from_millis(self.in_flight_timeout_ms)
+    }
+
+    pub fn timer_interval(&self) -> Duration {
+        Duration::from_millis(self.timer_interval_ms)
+    }
+
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.min_tick_move >= 0.0,
+            format!("{path}.min_tick_move"),
+            "must be >= 0",
+        )?;
+        ensure(
+            self.in_flight_timeout_ms > 0,
+            format!("{path}.in_flight_timeout_ms"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.timer_interval_ms > 0,
+            format!("{path}.timer_interval_ms"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.amend_max_ticks != Some(0),
+            format!("{path}.amend_max_ticks"),
+            "must be > 0",
+        )?;
+        self.rejection_backoff
+            .validate(&format!("{path}.rejection_backoff"))?;
+        self.venue_latency
+            .validate(&format!("{path}.venue_latency"))
+    }
+}
@@ -1,3 +1,4 @@
+pub mod config;
 pub mod policies;
 pub mod quote_scheduler;
 pub mod schedule_context;
@@ -2,11 +2,14 @@ use crate::scheduling::{
     schedule_context::ScheduleContext, schedule_policy::SchedulePolicy, types::SkipReason,
 };
 
+/// Holds new orders while an order waits on the venue, until it has waited past the
+/// in-flight timeout and the evaluation gives it up. Cancels still go out.
 pub struct InFlightPolicy;
 
 impl SchedulePolicy for InFlightPolicy {
     fn should_evaluate(&mut self, ctx: &ScheduleContext<'_>) -> Option<SkipReason> {
-        if ctx.order_manager.has_inflight_actions() {
+        if ctx.order_manager.has_inflight_actions() && !ctx.order_manager.in_flight_expired(ctx.now)
+        {
             Some(SkipReason::InFlight)
         } else {
             None
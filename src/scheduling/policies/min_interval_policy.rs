@@ -6,15 +6,22 @@ use std::{
 use tokio::sync::broadcast;
 
 use crate::{
+    clock::SharedClock,
     execution::order_report::OrderReport,
     scheduling::{
-        schedule_context::ScheduleContext, schedule_policy::SchedulePolicy, types::SkipReason,
+        config::SchedulingConfig,
+        schedule_context::{ScheduleContext, Trigger},
+        schedule_policy::SchedulePolicy,
+        types::SkipReason,
     },
+    telemetry::metrics,
+    types::instrument::Instrument,
 };
 
 pub struct MinIntervalPolicy {
     min_interval: Duration,
     last_order: Arc<Mutex<Option<Instant>>>,
+    clock: SharedClock,
 }
 
 impl Clone for MinIntervalPolicy {
@@ -22,29 +29,45 @@ impl Clone for MinIntervalPolicy {
         Self {
             min_interval: self.min_interval,
             last_order: Arc::clone(&self.last_order),
+            clock: Arc::clone(&self.clock),
         }
     }
 }
 
 impl MinIntervalPolicy {
-    pub fn new(min_interval: Duration) -> Self {
+    /// Placements are timed on `clock`, which must be the one driving `ctx.now`.
+    pub fn new(min_interval: Duration, clock: SharedClock) -> Self {
         Self {
             min_interval,
             last_order: Arc::new(Mutex::new(None)),
+            clock,
         }
     }
 
-    pub fn on_report(&self, mut receiver: broadcast::Receiver<OrderReport>) {
+    /// Tracks placements for `instrument` only, so instruments do not throttle each other.
+    pub fn on_report(
+        &self,
+        instrument: &Instrument,
+        mut receiver: broadcast::Receiver<OrderReport>,
+    ) {
         let last_eval = Arc::clone(&self.last_order);
+        let instrument = instrument.clone();
+        let clock = Arc::clone(&self.clock);
 
         tokio::spawn(async move {
             loop {
                 match receiver.recv().await {
                     Err(broadcast::error::RecvError::Closed) => break,
-                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        metrics::reports_lagged("scheduler", n);
+                    }
                     Ok(report) => {
-                        if let OrderReport::Placed { .. } = report {
-                            *last_eval.lock().unwrap() = Some(Instant::now());
+                        if let OrderReport::Placed {
+                            instrument: placed, ..
+                        } = &report
+                            && *placed == instrument
+                        {
+                            *last_eval.lock().unwrap() = Some(clock.now_instant());
                         }
                     }
                 }
@@ -55,6 +78,10 @@ impl MinIntervalPolicy {
 
 impl SchedulePolicy for MinIntervalPolicy {
     fn should_evaluate(&mut self, ctx: &ScheduleContext<'_>) -> Option<SkipReason> {
+        // A reprice follows the venue's refusal of the last place, not a new decision.
+        if ctx.trigger == Trigger::Reprice {
+            return None;
+        }
         let last = *self.last_order.lock().unwrap();
 
         if let Some(last) = last {
@@ -68,4 +95,8 @@ impl SchedulePolicy for MinIntervalPolicy {
 
         None
     }
+
+    fn update_config(&mut self, config: &SchedulingConfig) {
+        self.min_interval = config.min_interval();
+    }
 }
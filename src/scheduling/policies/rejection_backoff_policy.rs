@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::{
+    clock::SharedClock,
+    config::app_config::ensure,
+    execution::order_report::OrderReport,
+    scheduling::{
+        config::SchedulingConfig, schedule_context::ScheduleContext,
+        schedule_policy::SchedulePolicy, types::SkipReason,
+    },
+    telemetry::metrics,
+    types::instrument::Instrument,
+};
+
+/// Pauses evaluation after `threshold` rejected places or failed cancels within
+/// `window_ms`, for `initial_backoff_ms` doubling with each further pause up to
+/// `max_backoff_ms`. An accepted order ends the pause and resets the doubling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RejectionBackoffConfig {
+    pub threshold: usize,
+    pub window_ms: u64,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RejectionBackoffConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 3,
+            window_ms: 10_000,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 60_000,
+        }
+    }
+}
+
+impl RejectionBackoffConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.threshold >= 1,
+            format!("{path}.threshold"),
+            "must be >= 1",
+        )?;
+        ensure(
+            self.window_ms > 0,
+            format!("{path}.window_ms"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.initial_backoff_ms <= self.max_backoff_ms,
+            format!("{path}.initial_backoff_ms"),
+            "must be <= max_backoff_ms",
+        )
+    }
+
+    /// Length of the `pause`th pause in a row, counting from 1.
+    fn backoff(&self, pause: u32) -> Duration {
+        let doubled = self
+            .initial_backoff_ms
+            .saturating_mul(1 << pause.saturating_sub(1).min(16));
+        Duration::from_millis(doubled.min(self.max_backoff_ms))
+    }
+}
+
+#[derive(Debug, Default)]
+struct Backoff {
+    config: RejectionBackoffConfig,
+    /// When each rejection still inside the window arrived.
+    recent: VecDeque<Instant>,
+    /// Rejections since the last accept.
+    count: usize,
+    /// Pauses since the last accept.
+    pauses: u32,
+    until: Option<Instant>,
+}
+
+impl Backoff {
+    fn on_rejection(&mut self, now: Instant) {
+        let window = Duration::from_millis(self.config.window_ms);
+        self.recent.push_back(now);
+        while self
+            .recent
+            .front()
+            .is_some_and(|at| now.saturating_duration_since(*at) >= window)
+        {
+            self.recent.pop_front();
+        }
+        self.count += 1;
+
+        if self.recent.len() >= self.config.threshold {
+            self.pauses += 1;
+            let backoff = self.config.backoff(self.pauses);
+            self.until = Some(now + backoff);
+            self.recent.clear();
+            warn!(
+                rejections = self.count,
+                pauses = self.pauses,
+                backoff_ms = backoff.as_millis() as u64,
+                "repeated rejections; pausing quoting"
+            );
+        }
+    }
+
+    fn on_accept(&mut self) {
+        if self.pauses > 0 {
+            info!(
+                rejections = self.count,
+                "order accepted; rejection backoff reset"
+            );
+        }
+        self.recent.clear();
+        self.count = 0;
+        self.pauses = 0;
+        self.until = None;
+    }
+}
+
+/// Holds evaluation while the venue keeps refusing this instrument's orders, e.g. post
+/// only places that would cross or ones without the funds, instead of retrying them on
+/// every market tick.
+pub struct RejectionBackoffPolicy {
+    state: Arc<Mutex<Backoff>>,
+    clock: SharedClock,
+}
+
+impl RejectionBackoffPolicy {
+    /// Reports are timed on `clock`, which must be the one driving `ctx.now`.
+    pub fn new(config: &RejectionBackoffConfig, clock: SharedClock) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(Backoff {
+                config: config.clone(),
+                ..Backoff::default()
+            })),
+            clock,
+        }
+    }
+
+    /// Counts reports for `instrument` only, so one instrument's rejections do not pause
+    /// another.
+    pub fn on_report(
+        &self,
+        instrument: &Instrument,
+        mut receiver: broadcast::Receiver<OrderReport>,
+    ) {
+        let state = Arc::clone(&self.state);
+        let instrument = instrument.clone();
+        let clock = Arc::clone(&self.clock);
+
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Err(broadcast::error::RecvError::Closed) => break,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        metrics::reports_lagged("scheduler", n);
+                    }
+                    Ok(report) => {
+                        if report.instrument() != Some(&instrument) {
+                            continue;
+                        }
+                        match report {
+                            OrderReport::Rejected { .. } | OrderReport::CancelFailed { .. } => {
+                                state.lock().unwrap().on_rejection(clock.now_instant());
+                            }
+                            OrderReport::Accepted { .. } => state.lock().unwrap().on_accept(),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl SchedulePolicy for RejectionBackoffPolicy {
+    fn should_evaluate(&mut self, ctx: &ScheduleContext<'_>) -> Option<SkipReason> {
+        let state = self.state.lock().unwrap();
+        match state.until {
+            Some(until) if ctx.now < until => Some(SkipReason::RejectionBackoff {
+                count: state.count,
+                until,
+            }),
+            _ => None,
+        }
+    }
+
+    fn update_config(&mut self, config: &SchedulingConfig) {
+        self.state.lock().unwrap().config = config.rejection_backoff.clone();
+    }
+}
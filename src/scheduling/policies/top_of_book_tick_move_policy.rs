@@ -1,7 +1,10 @@
 use std::time::{Duration, Instant};
 
 use crate::scheduling::{
-    schedule_context::ScheduleContext, schedule_policy::SchedulePolicy, types::SkipReason,
+    config::SchedulingConfig,
+    schedule_context::{ScheduleContext, Trigger},
+    schedule_policy::SchedulePolicy,
+    types::SkipReason,
 };
 
 pub struct TopOfBookTickMovePolicy {
@@ -29,6 +32,10 @@ impl SchedulePolicy for TopOfBookTickMovePolicy {
                 Some((b, a)) => (b.as_f64(), a.as_f64()),
                 None => return Some(SkipReason::NoBook),
             };
+        // The book has not moved, which is why the refused place is repriced.
+        if ctx.trigger == Trigger::Reprice {
+            return None;
+        }
 
         let tick = ctx.instrument.trading_rules().price_tick;
         let min_move = self.min_ticks * tick;
@@ -51,16 +58,17 @@ impl SchedulePolicy for TopOfBookTickMovePolicy {
         if let Some(last_eval) = self.last_eval {
             if now.duration_since(last_eval) >= self.max_stale {
                 self.last_eval = Some(now);
-                return None;
+                None
             } else {
-                return Some(SkipReason::NoMeaningfulChange {
-                    best_bid: best_bid,
-                    best_ask: best_ask,
-                });
+                Some(SkipReason::NoMeaningfulChange { best_bid, best_ask })
             }
         } else {
             self.last_eval = Some(now);
-            return None;
+            None
         }
     }
+
+    fn update_config(&mut self, config: &SchedulingConfig) {
+        self.min_ticks = config.min_tick_move;
+    }
 }
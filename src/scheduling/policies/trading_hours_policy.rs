@@ -2,9 +2,9 @@ use crate::scheduling::{
     schedule_context::ScheduleContext, schedule_policy::SchedulePolicy, types::SkipReason,
 };
 use crate::types::instrument::Instrument;
-use crate::types::trading_hours::TradingHours;
+use crate::types::trading_hours::{TimeOfDay, TradingHours};
 use chrono::Datelike;
-use chrono::{Timelike, Utc, Weekday};
+use chrono::Weekday;
 
 pub struct TradingHoursPolicy {
     pub trading_hours: TradingHours,
@@ -16,27 +16,29 @@ impl TradingHoursPolicy {
     }
 
     pub fn for_instrument(instrument: &Instrument) -> Self {
-        let trading_hours = instrument.trading_rules().trading_hours.clone();
+        let trading_hours = instrument.trading_rules().trading_hours;
 
         Self::new(trading_hours.unwrap_or_default())
     }
 
-    fn is_within_hours(&self, hour: u32) -> bool {
-        let start = self.trading_hours.start_hour as u32;
-        let end = self.trading_hours.end_hour as u32;
-        if start <= end {
-            (hour >= start) && (hour < end)
-        } else {
-            (hour >= start) || (hour < end)
-        }
+    fn is_within_hours(&self, minute_of_day: u16) -> bool {
+        self.trading_hours.is_open(minute_of_day)
+    }
+
+    /// Minutes left in the session open at `minute_of_day`, when that is within the
+    /// close buffer.
+    fn closing_in(&self, minute_of_day: u16) -> Option<u16> {
+        self.trading_hours
+            .minutes_to_close(minute_of_day)
+            .filter(|&left| left <= self.trading_hours.close_buffer_mins)
     }
 }
 
 impl SchedulePolicy for TradingHoursPolicy {
-    fn should_evaluate(&mut self, _ctx: &ScheduleContext<'_>) -> Option<SkipReason> {
-        let now = Utc::now();
-        let hour = now.hour();
-        let weekday = now.weekday();
+    fn should_evaluate(&mut self, ctx: &ScheduleContext<'_>) -> Option<SkipReason> {
+        let time = TimeOfDay::of(&ctx.now_utc);
+        let minute_of_day = time.minutes();
+        let weekday = ctx.now_utc.weekday();
 
         if self.trading_hours.weekend_pause {
             match weekday {
@@ -46,11 +48,12 @@ impl SchedulePolicy for TradingHoursPolicy {
             }
         }
 
-        if !self.is_within_hours(hour) {
-            return Some(SkipReason::OutOfTradingHours {
-                start_hour: self.trading_hours.start_hour,
-                end_hour: self.trading_hours.end_hour,
-            });
+        if !self.is_within_hours(minute_of_day) {
+            return Some(SkipReason::OutOfTradingHours { time });
+        }
+
+        if let Some(minutes_to_close) = self.closing_in(minute_of_day) {
+            return Some(SkipReason::ClosingSoon { minutes_to_close });
         }
 
         None
@@ -0,0 +1,156 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::{
+    clock::SharedClock,
+    config::app_config::ensure,
+    execution::order_report::OrderReport,
+    scheduling::{
+        config::SchedulingConfig, schedule_context::ScheduleContext,
+        schedule_policy::SchedulePolicy, types::SkipReason,
+    },
+    telemetry::{
+        ack_latency::{AckLatency, AckLatencyTracker},
+        metrics,
+    },
+    types::instrument::Instrument,
+};
+
+/// Skips evaluation while the p95 time from placing an order to the venue accepting or
+/// rejecting it, over the last `window` placements, is above `max_ack_p95_ms`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VenueLatencyConfig {
+    /// Off when unset.
+    pub max_ack_p95_ms: Option<u64>,
+    pub window: usize,
+    /// Acks needed in the window before the venue is judged.
+    pub min_samples: usize,
+    /// Acks older than this stop counting, so a pause ends once a slow spell has aged
+    /// out even though nothing new was placed.
+    pub max_sample_age_ms: u64,
+}
+
+impl Default for VenueLatencyConfig {
+    fn default() -> Self {
+        Self {
+            max_ack_p95_ms: None,
+            window: 50,
+            min_samples: 5,
+            max_sample_age_ms: 60_000,
+        }
+    }
+}
+
+impl VenueLatencyConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.max_ack_p95_ms != Some(0),
+            format!("{path}.max_ack_p95_ms"),
+            "must be > 0",
+        )?;
+        ensure(self.window >= 1, format!("{path}.window"), "must be >= 1")?;
+        ensure(
+            (1..=self.window).contains(&self.min_samples),
+            format!("{path}.min_samples"),
+            "must be between 1 and window",
+        )?;
+        ensure(
+            self.max_sample_age_ms > 0,
+            format!("{path}.max_sample_age_ms"),
+            "must be > 0",
+        )
+    }
+}
+
+/// Holds evaluation while the venue is slow to acknowledge placements: quotes sent into
+/// a lagging venue rest at stale prices for longer than the engine thinks.
+pub struct VenueLatencyPolicy {
+    config: VenueLatencyConfig,
+    tracker: Arc<Mutex<AckLatencyTracker>>,
+    clock: SharedClock,
+}
+
+impl VenueLatencyPolicy {
+    /// Reports are timed on `clock`, which must be the one driving `ctx.now`.
+    pub fn new(config: &VenueLatencyConfig, clock: SharedClock) -> Self {
+        Self {
+            config: config.clone(),
+            tracker: Arc::new(Mutex::new(AckLatencyTracker::new(config.window))),
+            clock,
+        }
+    }
+
+    /// Times placements for `instrument` only, so one instrument's slow acks do not pause
+    /// another.
+    pub fn on_report(
+        &self,
+        instrument: &Instrument,
+        mut receiver: broadcast::Receiver<OrderReport>,
+    ) {
+        let tracker = Arc::clone(&self.tracker);
+        let instrument = instrument.clone();
+        let clock = Arc::clone(&self.clock);
+
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Err(broadcast::error::RecvError::Closed) => break,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        metrics::reports_lagged("scheduler", n);
+                    }
+                    Ok(report) => {
+                        if report.instrument() != Some(&instrument) {
+                            continue;
+                        }
+                        let mut tracker = tracker.lock().unwrap();
+                        if tracker.on_report(&report, clock.now_instant()).is_some()
+                            && let Some(latency) = tracker.summary()
+                        {
+                            metrics::ack_latency_p95(&instrument, latency.p95);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Ack latency over the window as it stands.
+    pub fn ack_latency(&self) -> Option<AckLatency> {
+        self.tracker.lock().unwrap().summary()
+    }
+}
+
+impl SchedulePolicy for VenueLatencyPolicy {
+    fn should_evaluate(&mut self, ctx: &ScheduleContext<'_>) -> Option<SkipReason> {
+        let max_ack_p95 = Duration::from_millis(self.config.max_ack_p95_ms?);
+
+        let mut tracker = self.tracker.lock().unwrap();
+        if let Some(cutoff) = ctx
+            .now
+            .checked_sub(Duration::from_millis(self.config.max_sample_age_ms))
+        {
+            tracker.forget_before(cutoff);
+        }
+        let latency = tracker.summary()?;
+        if latency.samples < self.config.min_samples || latency.p95 <= max_ack_p95 {
+            return None;
+        }
+
+        warn!(%latency, max_ack_p95_ms = self.config.max_ack_p95_ms, "venue slow to acknowledge; skipping");
+        Some(SkipReason::VenueDegraded {
+            ack_p95: latency.p95,
+            max_ack_p95,
+        })
+    }
+
+    fn update_config(&mut self, config: &SchedulingConfig) {
+        self.config = config.venue_latency.clone();
+        self.tracker.lock().unwrap().set_window(self.config.window);
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::scheduling::{
+    config::SchedulingConfig, schedule_context::ScheduleContext, schedule_policy::SchedulePolicy,
+    types::SkipReason,
+};
+
+/// Holds the first quote until both mid EMAs are warm and `min_market_events` events or
+/// `min_elapsed_ms` of the market have been seen, whichever comes first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WarmupConfig {
+    pub enabled: bool,
+    pub min_market_events: u64,
+    pub min_elapsed_ms: u64,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_market_events: 50,
+            min_elapsed_ms: 30_000,
+        }
+    }
+}
+
+impl WarmupConfig {
+    pub fn min_elapsed(&self) -> Duration {
+        Duration::from_millis(self.min_elapsed_ms)
+    }
+}
+
+/// Keeps a freshly started engine from quoting off a single book: an EMA of one sample
+/// is the mid itself, and routinely trips entry conditions. Once warm it stays so.
+pub struct WarmupPolicy {
+    config: WarmupConfig,
+    /// When the first market event was seen.
+    started: Option<Instant>,
+    warm: bool,
+}
+
+impl WarmupPolicy {
+    pub fn new(config: &WarmupConfig) -> Self {
+        Self {
+            config: config.clone(),
+            started: None,
+            warm: false,
+        }
+    }
+}
+
+impl SchedulePolicy for WarmupPolicy {
+    fn should_evaluate(&mut self, ctx: &ScheduleContext<'_>) -> Option<SkipReason> {
+        if !self.config.enabled || self.warm {
+            return None;
+        }
+
+        let market_events = ctx.market_state.event_count();
+        if market_events > 0 && self.started.is_none() {
+            self.started = Some(ctx.now);
+        }
+        let seen_enough = market_events >= self.config.min_market_events
+            || self.started.is_some_and(|started| {
+                ctx.now.saturating_duration_since(started) >= self.config.min_elapsed()
+            });
+        let signals_warm = ctx.signal_state.is_warm();
+
+        if seen_enough && signals_warm {
+            info!(market_events, "warmed up; quoting");
+            self.warm = true;
+            return None;
+        }
+        Some(SkipReason::WarmingUp {
+            market_events,
+            signals_warm,
+        })
+    }
+
+    fn update_config(&mut self, config: &SchedulingConfig) {
+        self.config = config.warmup.clone();
+    }
+}
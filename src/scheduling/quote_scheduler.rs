@@ -1,5 +1,6 @@
 use crate::scheduling::{
-    schedule_context::ScheduleContext, schedule_policy::SchedulePolicy, types::ScheduleDecision,
+    config::SchedulingConfig, schedule_context::ScheduleContext, schedule_policy::SchedulePolicy,
+    types::ScheduleDecision,
 };
 
 pub struct QuoteScheduler {
@@ -11,13 +12,29 @@ impl QuoteScheduler {
         Self { policies }
     }
 
+    pub fn update_config(&mut self, config: &SchedulingConfig) {
+        for policy in self.policies.iter_mut() {
+            policy.update_config(config);
+        }
+    }
+
+    /// Skips on the first policy that objects, unless it only holds new orders: then
+    /// the rest still have their say, and if none objects the evaluation runs to cancel.
     pub fn decide(&mut self, context: &ScheduleContext<'_>) -> ScheduleDecision {
+        let mut held = None;
         for policy in self.policies.iter_mut() {
-            if let Some(reason) = policy.should_evaluate(&context) {
-                return ScheduleDecision::Skip(reason);
+            match policy.should_evaluate(context) {
+                Some(reason) if reason.holds_only_places() => {
+                    held.get_or_insert(reason);
+                }
+                Some(reason) => return ScheduleDecision::Skip(reason),
+                None => {}
             }
         }
 
-        ScheduleDecision::Evaluate
+        match held {
+            Some(reason) => ScheduleDecision::CancelsOnly(reason),
+            None => ScheduleDecision::Evaluate,
+        }
     }
 }
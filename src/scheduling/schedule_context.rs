@@ -1,11 +1,30 @@
 use std::time::Instant;
 
+use chrono::{DateTime, Utc};
+
+use crate::signals::signal_state::SignalState;
 use crate::types::instrument::Instrument;
 use crate::{execution::order_manager::OrderManager, market::market_state::MarketState};
 
+/// What started an evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    MarketEvent,
+    /// The engine's evaluation timer, on a book that has gone quiet: the market state
+    /// is the last one seen.
+    Timer,
+    /// The venue refused a post-only place for crossing, and the side re-plans at once
+    /// rather than waiting for the book to move.
+    Reprice,
+}
+
 pub struct ScheduleContext<'a> {
     pub now: Instant,
+    pub trigger: Trigger,
+    /// Calendar time at `now`, from the same clock.
+    pub now_utc: DateTime<Utc>,
     pub instrument: &'a Instrument,
     pub market_state: &'a MarketState,
+    pub signal_state: &'a SignalState,
     pub order_manager: &'a OrderManager,
 }
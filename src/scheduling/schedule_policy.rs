@@ -1,5 +1,10 @@
-use crate::scheduling::{schedule_context::ScheduleContext, types::SkipReason};
+use crate::scheduling::{
+    config::SchedulingConfig, schedule_context::ScheduleContext, types::SkipReason,
+};
 
 pub trait SchedulePolicy {
     fn should_evaluate(&mut self, ctx: &ScheduleContext<'_>) -> Option<SkipReason>;
+
+    /// Takes new settings on a config reload, keeping what the policy has observed.
+    fn update_config(&mut self, _config: &SchedulingConfig) {}
 }
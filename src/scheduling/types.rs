@@ -1,17 +1,90 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy)]
+use serde::Serialize;
+
+use crate::types::trading_hours::TimeOfDay;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
 pub enum ScheduleDecision {
     Evaluate,
+    /// Evaluates, but only to cancel: new orders are held for the reason given.
+    CancelsOnly(SkipReason),
     Skip(SkipReason),
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Serialized tagged with its [`code`](Self::code).
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
 pub enum SkipReason {
-    TooSoon { duration_since_last: Duration },
-    NoMeaningfulChange { best_bid: f64, best_ask: f64 },
+    TooSoon {
+        duration_since_last: Duration,
+    },
+    NoMeaningfulChange {
+        best_bid: f64,
+        best_ask: f64,
+    },
     NoBook,
     InFlight,
-    OutOfTradingHours { start_hour: u8, end_hour: u8 },
+    /// Outside every trading window, at `time` UTC.
+    OutOfTradingHours {
+        time: TimeOfDay,
+    },
     WeekendPause,
+    /// The session closes within the configured buffer: resting orders are pulled so
+    /// none are left over the close.
+    ClosingSoon {
+        minutes_to_close: u16,
+    },
+    /// The venue refused `count` orders in a row; quoting resumes at `until`.
+    RejectionBackoff {
+        count: usize,
+        #[serde(skip)]
+        until: Instant,
+    },
+    /// The venue's p95 placement ack latency is over the configured limit.
+    VenueDegraded {
+        ack_p95: Duration,
+        max_ack_p95: Duration,
+    },
+    /// Quoting has not started: the signals are not warm yet, or too little of the
+    /// market has been seen.
+    WarmingUp {
+        market_events: u64,
+        signals_warm: bool,
+    },
+}
+
+impl SkipReason {
+    /// Stable, low-cardinality identifier used for metric labels and log fields.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SkipReason::TooSoon { .. } => "too_soon",
+            SkipReason::NoMeaningfulChange { .. } => "no_meaningful_change",
+            SkipReason::NoBook => "no_book",
+            SkipReason::InFlight => "in_flight",
+            SkipReason::OutOfTradingHours { .. } => "out_of_trading_hours",
+            SkipReason::WeekendPause => "weekend_pause",
+            SkipReason::ClosingSoon { .. } => "closing_soon",
+            SkipReason::RejectionBackoff { .. } => "rejection_backoff",
+            SkipReason::VenueDegraded { .. } => "venue_degraded",
+            SkipReason::WarmingUp { .. } => "warming_up",
+        }
+    }
+
+    /// Whether only new orders are held for this reason, so an evaluation may still
+    /// cancel the orders it no longer wants.
+    pub fn holds_only_places(&self) -> bool {
+        matches!(self, SkipReason::InFlight)
+    }
+
+    /// Whether resting orders are pulled for this reason, rather than left as they are.
+    pub fn pulls_quotes(&self) -> bool {
+        matches!(
+            self,
+            SkipReason::OutOfTradingHours { .. }
+                | SkipReason::WeekendPause
+                | SkipReason::ClosingSoon { .. }
+        )
+    }
 }
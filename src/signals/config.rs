@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::app_config::ensure;
+use crate::scenario::strategies::StrategyKind;
+use crate::types::instrument::{Instrument, InstrumentConfig};
+
+/// EMA time constants in seconds and the update throttle. Unset values fall back to the
+/// defaults for the selected strategy.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignalsConfig {
+    pub fast_tau_secs: Option<f64>,
+    pub slow_tau_secs: Option<f64>,
+    pub vol_tau_secs: Option<f64>,
+    /// Market events closer together than this do not update the signals.
+    pub min_update_interval_ms: Option<u64>,
+    /// Overrides by strategy kind of the values above, for whichever kind is trading.
+    pub strategies: BTreeMap<StrategyKind, SignalOverrides>,
+    /// Overrides by pair, e.g. `SOL/GBP`, over those for the strategy kind.
+    pub pairs: BTreeMap<String, SignalOverrides>,
+    /// Pairs, by symbol, that take their fair price from another venue's book.
+    pub references: BTreeMap<String, ReferencePriceConfig>,
+}
+
+impl SignalsConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        self.overrides().validate(path)?;
+        for (kind, overrides) in &self.strategies {
+            overrides.validate(&format!("{path}.strategies.{kind}"))?;
+        }
+        for (symbol, overrides) in &self.pairs {
+            let path = format!("{path}.pairs.{symbol}");
+            ensure(
+                symbol.parse::<InstrumentConfig>().is_ok(),
+                &path,
+                "must be a BASE/QUOTE pair",
+            )?;
+            overrides.validate(&path)?;
+        }
+
+        for (symbol, reference) in &self.references {
+            let path = format!("{path}.references.{symbol}");
+            ensure(
+                symbol.parse::<InstrumentConfig>().is_ok(),
+                &path,
+                "must be a BASE/QUOTE pair",
+            )?;
+            reference.validate(&path)?;
+        }
+
+        Ok(())
+    }
+
+    /// The signals `kind` trades `instrument` on: `defaults`, with the values set here
+    /// for every strategy, then for `kind`, then for the pair written over them in turn.
+    pub fn params(
+        &self,
+        defaults: SignalParams,
+        kind: StrategyKind,
+        instrument: Option<&Instrument>,
+    ) -> SignalParams {
+        let pair = instrument.and_then(|instrument| {
+            self.pairs.iter().find_map(|(symbol, overrides)| {
+                symbol
+                    .parse::<InstrumentConfig>()
+                    .is_ok_and(|pair| pair.symbol() == instrument.to_string())
+                    .then_some(overrides)
+            })
+        });
+
+        [Some(&self.overrides()), self.strategies.get(&kind), pair]
+            .into_iter()
+            .flatten()
+            .fold(defaults, SignalParams::with_overrides)
+    }
+
+    fn overrides(&self) -> SignalOverrides {
+        SignalOverrides {
+            fast_tau_secs: self.fast_tau_secs,
+            slow_tau_secs: self.slow_tau_secs,
+            vol_tau_secs: self.vol_tau_secs,
+            min_update_interval_ms: self.min_update_interval_ms,
+        }
+    }
+}
+
+/// Signal settings for one strategy kind or pair; unset values keep the ones below.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignalOverrides {
+    pub fast_tau_secs: Option<f64>,
+    pub slow_tau_secs: Option<f64>,
+    pub vol_tau_secs: Option<f64>,
+    pub min_update_interval_ms: Option<u64>,
+}
+
+impl SignalOverrides {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        for (field, value) in [
+            ("fast_tau_secs", self.fast_tau_secs),
+            ("slow_tau_secs", self.slow_tau_secs),
+            ("vol_tau_secs", self.vol_tau_secs),
+        ] {
+            if let Some(value) = value {
+                ensure(value > 0.0, format!("{path}.{field}"), "must be > 0")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where a pair's fair price comes from when a more liquid book leads it, e.g. SOL/USD
+/// for SOL/GBP, converted through an FX pair when the quote currencies differ.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReferencePriceConfig {
+    /// The leading pair, e.g. `SOL/USD`; its base must be the quoted pair's.
+    pub instrument: String,
+    /// Converts the reference's quote currency into the quoted pair's, e.g. `GBP/USD`.
+    /// Either way round; required when the quote currencies differ.
+    pub fx: Option<String>,
+    /// Reference or FX books older than this are stale, and the local book prices
+    /// instead.
+    pub max_age_ms: u64,
+}
+
+impl Default for ReferencePriceConfig {
+    fn default() -> Self {
+        Self {
+            instrument: String::new(),
+            fx: None,
+            max_age_ms: 2_000,
+        }
+    }
+}
+
+impl ReferencePriceConfig {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.instrument.parse::<InstrumentConfig>().is_ok(),
+            format!("{path}.instrument"),
+            "must be a BASE/QUOTE pair",
+        )?;
+        if let Some(fx) = &self.fx {
+            ensure(
+                fx.parse::<InstrumentConfig>().is_ok(),
+                format!("{path}.fx"),
+                "must be a BASE/QUOTE pair",
+            )?;
+        }
+        ensure(
+            self.max_age_ms > 0,
+            format!("{path}.max_age_ms"),
+            "must be > 0",
+        )
+    }
+
+    pub fn max_age(&self) -> Duration {
+        Duration::from_millis(self.max_age_ms)
+    }
+}
+
+/// Everything a `SignalState` is built from: a strategy's defaults with any
+/// `SignalsConfig` overrides applied, as [`SignalsConfig::params`] gives them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalParams {
+    pub fast_tau_secs: f64,
+    pub slow_tau_secs: f64,
+    pub vol_tau_secs: f64,
+    pub min_update_interval: Duration,
+}
+
+impl SignalParams {
+    pub fn with_overrides(self, config: &SignalOverrides) -> Self {
+        Self {
+            fast_tau_secs: config.fast_tau_secs.unwrap_or(self.fast_tau_secs),
+            slow_tau_secs: config.slow_tau_secs.unwrap_or(self.slow_tau_secs),
+            vol_tau_secs: config.vol_tau_secs.unwrap_or(self.vol_tau_secs),
+            min_update_interval: config
+                .min_update_interval_ms
+                .map(Duration::from_millis)
+                .unwrap_or(self.min_update_interval),
+        }
+    }
+}
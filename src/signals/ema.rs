@@ -50,12 +50,8 @@ impl Ema {
     }
 
     pub fn warmed_value(&self) -> Option<f64> {
-        let Some(first) = self.first_update else {
-            return None;
-        };
-        let Some(last) = self.last_update else {
-            return None;
-        };
+        let first = self.first_update?;
+        let last = self.last_update?;
 
         if last.duration_since(first) < self.warmup_duration {
             return None;
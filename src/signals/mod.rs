@@ -1,2 +1,4 @@
+pub mod config;
 pub mod ema;
+pub mod reference_price;
 pub mod signal_state;
@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+
+use crate::events::MarketEvent;
+use crate::market::market_state::MarketState;
+use crate::signals::config::{ReferencePriceConfig, SignalsConfig};
+use crate::types::instrument::{Instrument, InstrumentConfig};
+
+/// How an FX mid turns a reference price into the quoted pair's currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Conversion {
+    /// The FX pair quotes the reference currency per quoted currency, e.g. GBP/USD for a
+    /// USD reference and a GBP pair.
+    Divide,
+    /// The FX pair quotes the quoted currency per reference currency, e.g. USD/GBP.
+    Multiply,
+}
+
+/// A fair price for one pair taken from another pair's book, e.g. SOL/GBP priced from
+/// SOL/USD and GBP/USD, each book kept in its own `MarketState`.
+#[derive(Debug, Clone)]
+pub struct ReferenceFairPrice {
+    reference: Instrument,
+    fx: Option<(Instrument, Conversion)>,
+    max_age: Duration,
+    reference_market: MarketState,
+    fx_market: MarketState,
+}
+
+impl ReferenceFairPrice {
+    /// The reference `config` gives `instrument`, if any.
+    pub fn for_instrument(config: &SignalsConfig, instrument: &Instrument) -> Result<Option<Self>> {
+        config
+            .references
+            .iter()
+            .find(|(symbol, _)| {
+                symbol
+                    .parse::<InstrumentConfig>()
+                    .is_ok_and(|pair| pair.symbol() == instrument.to_string())
+            })
+            .map(|(_, reference)| Self::new(reference, instrument))
+            .transpose()
+    }
+
+    /// Fails when the reference does not price `instrument`'s base, or when no FX pair
+    /// links the two quote currencies.
+    pub fn new(config: &ReferencePriceConfig, instrument: &Instrument) -> Result<Self> {
+        let reference = watched(&config.instrument)?;
+        if reference.base() != instrument.base() {
+            bail!(
+                "reference {reference} for {instrument} must have base {}",
+                instrument.base()
+            );
+        }
+
+        let fx = match &config.fx {
+            _ if reference.quote() == instrument.quote() => None,
+            None => bail!(
+                "reference {reference} for {instrument} needs an fx pair from {} to {}",
+                reference.quote(),
+                instrument.quote()
+            ),
+            Some(fx) => {
+                let fx = watched(fx)?;
+                let conversion =
+                    if fx.base() == instrument.quote() && fx.quote() == reference.quote() {
+                        Conversion::Divide
+                    } else if fx.base() == reference.quote() && fx.quote() == instrument.quote() {
+                        Conversion::Multiply
+                    } else {
+                        bail!(
+                            "fx {fx} for {instrument} must pair {} with {}",
+                            reference.quote(),
+                            instrument.quote()
+                        );
+                    };
+                Some((fx, conversion))
+            }
+        };
+
+        Ok(Self {
+            reference,
+            fx,
+            max_age: config.max_age(),
+            reference_market: MarketState::new(),
+            fx_market: MarketState::new(),
+        })
+    }
+
+    /// The pairs whose market data this needs.
+    pub fn instruments(&self) -> impl Iterator<Item = &Instrument> {
+        std::iter::once(&self.reference).chain(self.fx.as_ref().map(|(fx, _)| fx))
+    }
+
+    /// Applies `event` if it is for the reference or FX pair. Returns whether it was.
+    pub fn on_market_event(&mut self, event: &MarketEvent, now: Instant) -> bool {
+        let instrument = MarketEvent::instrument(event);
+        if *instrument == self.reference {
+            self.reference_market.on_market_event(event, now);
+        } else if self.fx.as_ref().is_some_and(|(fx, _)| fx == instrument) {
+            self.fx_market.on_market_event(event, now);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// The reference mid in the quoted pair's currency, or `None` while either book is
+    /// missing or older than `max_age_ms`.
+    pub fn fair_price(&self, now: Instant) -> Option<f64> {
+        if self.reference_market.is_stale(self.max_age, now) {
+            return None;
+        }
+        let mid = self.reference_market.mid_price()?.as_f64();
+
+        let Some((_, conversion)) = self.fx else {
+            return Some(mid);
+        };
+        if self.fx_market.is_stale(self.max_age, now) {
+            return None;
+        }
+        let rate = self.fx_market.mid_price()?.as_f64();
+        if rate <= 0.0 {
+            return None;
+        }
+
+        Some(match conversion {
+            Conversion::Divide => mid / rate,
+            Conversion::Multiply => mid * rate,
+        })
+    }
+}
+
+fn watched(symbol: &str) -> Result<Instrument> {
+    let pair: InstrumentConfig = symbol.parse()?;
+    Ok(Instrument::watched(pair.base, pair.quote))
+}
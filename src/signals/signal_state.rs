@@ -1,44 +1,64 @@
 use std::time::{Duration, Instant};
 
-use crate::market::market_state::MarketState;
+use crate::market::market_state::{MarketState, MidKind};
+use crate::signals::config::SignalParams;
 use crate::signals::ema::Ema;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SignalState {
     ema_mid: Ema,
     ema_mid_slow: Ema,
     ema_abs_mid_change: Ema,
+    /// Book imbalance, smoothed over the fast time constant.
+    ema_imbalance: Ema,
     last_ema_value: Option<f64>,
     last_ema_slow_value: Option<f64>,
     last_volatility: Option<f64>,
+    /// Whether the last book seen had sizes to judge its imbalance by.
+    has_imbalance: bool,
     last_mid: Option<f64>,
+    /// The pair's fair price from a reference venue, while that is fresh.
+    reference_fair: Option<f64>,
+    /// Whether the EMAs follow the mid or the microprice.
+    mid_kind: MidKind,
     last_update: Option<Instant>,
     min_update_interval: Duration,
 }
 
 impl SignalState {
-    pub fn new(fast_tau_seconds: f64, slow_tau_seconds: f64, vol_tau_seconds: f64) -> Self {
+    pub fn new(params: SignalParams) -> Self {
         Self {
-            ema_mid: Ema::new(fast_tau_seconds),
-            ema_mid_slow: Ema::new(slow_tau_seconds),
-            ema_abs_mid_change: Ema::new(vol_tau_seconds),
+            ema_mid: Ema::new(params.fast_tau_secs),
+            ema_mid_slow: Ema::new(params.slow_tau_secs),
+            ema_abs_mid_change: Ema::new(params.vol_tau_secs),
+            ema_imbalance: Ema::new(params.fast_tau_secs),
             last_ema_value: None,
             last_ema_slow_value: None,
             last_volatility: None,
+            has_imbalance: false,
             last_mid: None,
+            reference_fair: None,
+            mid_kind: MidKind::default(),
             last_update: None,
-            min_update_interval: Duration::from_millis(350),
+            min_update_interval: params.min_update_interval,
         }
     }
 
+    /// Updates on market events as close together as `interval`, e.g. more often than
+    /// the default for a strategy with short time constants.
+    pub fn with_min_update_interval(mut self, interval: Duration) -> Self {
+        self.min_update_interval = interval;
+        self
+    }
+
     pub fn update(&mut self, market_state: &MarketState, now: Instant) {
-        if let Some(last) = self.last_update {
-            if now.duration_since(last) < self.min_update_interval {
-                return;
-            }
+        if let Some(last) = self.last_update
+            && now.duration_since(last) < self.min_update_interval
+        {
+            return;
         }
 
-        if let Some(mid) = market_state.mid_price() {
+        if let Some(mid) = market_state.anchor(self.mid_kind) {
             let mid_value = mid.as_f64();
             if let Some(last_mid) = self.last_mid {
                 let abs_change = (mid_value - last_mid).abs();
@@ -51,10 +71,38 @@ impl SignalState {
             self.last_ema_value = Some(ema_fast);
             self.last_ema_slow_value = Some(ema_slow);
             self.last_mid = Some(mid_value);
+
+            self.has_imbalance = match market_state.book_imbalance() {
+                Some(imbalance) => {
+                    self.ema_imbalance.update(now, imbalance);
+                    true
+                }
+                None => false,
+            };
             self.last_update = Some(now);
         }
     }
 
+    /// Follows the microprice rather than the mid from the next update on. Takes effect
+    /// gradually, as the EMAs move.
+    pub fn set_mid_kind(&mut self, kind: MidKind) {
+        self.mid_kind = kind;
+    }
+
+    pub fn mid_kind(&self) -> MidKind {
+        self.mid_kind
+    }
+
+    /// Sets the reference venue's fair price for this cycle, `None` when there is no
+    /// reference or it is stale.
+    pub fn set_reference_fair(&mut self, fair: Option<f64>) {
+        self.reference_fair = fair;
+    }
+
+    pub fn reference_fair(&self) -> Option<f64> {
+        self.reference_fair
+    }
+
     pub fn ema_mid(&self) -> Option<f64> {
         self.ema_mid.warmed_value()
     }
@@ -63,7 +111,21 @@ impl SignalState {
         self.ema_mid_slow.warmed_value()
     }
 
+    /// Whether both mid EMAs have seen a time constant of samples.
+    pub fn is_warm(&self) -> bool {
+        self.ema_mid().is_some() && self.ema_mid_slow().is_some()
+    }
+
     pub fn volatility_mid(&self) -> Option<f64> {
         self.last_volatility
     }
+
+    /// Smoothed book imbalance in [-1, 1]; positive when more rests on the bid. `None`
+    /// until warmed up, and while the book carries no sizes.
+    pub fn imbalance(&self) -> Option<f64> {
+        if !self.has_imbalance {
+            return None;
+        }
+        self.ema_imbalance.warmed_value()
+    }
 }
@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::execution::order_action::{OrderAction, Side};
+use crate::execution::order_ids::ClientOrderId;
+
+/// Records kept before compaction is considered.
+const COMPACT_AFTER: usize = 1_000;
+
+/// One line of the intent log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum IntentRecord {
+    Place {
+        order_id: String,
+        instrument: String,
+        side: Side,
+        price: f64,
+        quantity: f64,
+        /// Engine cycle that decided the order; absent in logs from older versions.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cycle_id: Option<u64>,
+    },
+    Cancel {
+        order_id: String,
+        instrument: String,
+        side: Side,
+    },
+    Resolved {
+        order_id: String,
+    },
+    ResolvedAll,
+    /// Sequence number for the next client order id, kept through compaction.
+    NextSeq {
+        next_seq: u64,
+    },
+}
+
+/// An order that was sent, or was being cancelled, without a terminal report yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Intent {
+    pub order_id: String,
+    /// Instrument symbol, e.g. `SOL/GBP`.
+    pub instrument: String,
+    pub side: Side,
+    price: Option<f64>,
+    quantity: Option<f64>,
+    cycle_id: Option<u64>,
+}
+
+impl Intent {
+    fn record(&self) -> IntentRecord {
+        match (self.price, self.quantity) {
+            (Some(price), Some(quantity)) => IntentRecord::Place {
+                order_id: self.order_id.clone(),
+                instrument: self.instrument.clone(),
+                side: self.side,
+                price,
+                quantity,
+                cycle_id: self.cycle_id,
+            },
+            _ => IntentRecord::Cancel {
+                order_id: self.order_id.clone(),
+                instrument: self.instrument.clone(),
+                side: self.side,
+            },
+        }
+    }
+}
+
+/// Append-only JSON-lines log of order intents. Every place and cancel is appended and
+/// synced to disk before it is sent, and resolved once the order reaches a terminal
+/// state, so after a crash the unresolved entries are exactly the orders that may still
+/// be resting on the venue.
+///
+/// It also keeps the highest [`ClientOrderId`] sequence number placed, so ids are not
+/// reused after a restart.
+#[derive(Debug)]
+pub struct IntentLog {
+    path: PathBuf,
+    file: File,
+    open: BTreeMap<String, Intent>,
+    next_seq: u64,
+    records: usize,
+}
+
+impl IntentLog {
+    /// Opens `path`, replaying any existing log, and compacts it down to the entries
+    /// left unresolved by the previous run.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let (open, next_seq) = if path.exists() {
+            replay(&path)?
+        } else {
+            (BTreeMap::new(), 1)
+        };
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create intent log dir {}", dir.display()))?;
+        }
+
+        let mut log = Self {
+            file: append(&path)?,
+            path,
+            open,
+            next_seq,
+            records: 0,
+        };
+        log.compact()?;
+
+        if !log.open.is_empty() {
+            warn!(
+                path = %log.path.display(),
+                unresolved = log.open.len(),
+                "intent log has unresolved orders from a previous run"
+            );
+        }
+
+        Ok(log)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn unresolved(&self) -> Vec<Intent> {
+        self.open.values().cloned().collect()
+    }
+
+    /// First client order id sequence number no earlier run has placed.
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Durably records the places and cancels in `actions`; call before sending them.
+    pub fn record_actions(&mut self, actions: &[OrderAction]) -> Result<()> {
+        let mut wrote = false;
+
+        for action in actions {
+            let intent = match action {
+                OrderAction::Place(order) => Intent {
+                    order_id: order.order_id.clone(),
+                    instrument: order.instrument.to_string(),
+                    side: order.side,
+                    price: Some(order.price.as_f64()),
+                    quantity: Some(order.quantity.as_f64()),
+                    cycle_id: order.cycle_id,
+                },
+                OrderAction::Cancel {
+                    order_id,
+                    instrument,
+                    side,
+                } => match self.open.get(order_id) {
+                    // Already tracked; cancelling does not change what may be resting.
+                    Some(_) => continue,
+                    None => Intent {
+                        order_id: order_id.clone(),
+                        instrument: instrument.to_string(),
+                        side: *side,
+                        price: None,
+                        quantity: None,
+                        cycle_id: None,
+                    },
+                },
+                // Amends keep the order id already tracked.
+                OrderAction::Amend { .. } | OrderAction::CancelAll => continue,
+            };
+
+            self.write(&intent.record())?;
+            self.next_seq = self.next_seq.max(seq_after(&intent.order_id));
+            self.open.insert(intent.order_id.clone(), intent);
+            wrote = true;
+        }
+
+        if wrote {
+            self.file
+                .sync_data()
+                .with_context(|| format!("failed to sync {}", self.path.display()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Marks `order_id` as no longer able to rest on the venue. Not synced: losing a
+    /// resolution only costs one extra check at the next start.
+    pub fn resolve(&mut self, order_id: &str) -> Result<()> {
+        if self.open.remove(order_id).is_none() {
+            return Ok(());
+        }
+
+        self.write(&IntentRecord::Resolved {
+            order_id: order_id.to_string(),
+        })?;
+        self.maybe_compact()
+    }
+
+    /// Resolves everything, after the venue confirmed a cancel-all.
+    pub fn resolve_all(&mut self) -> Result<()> {
+        if self.open.is_empty() {
+            return Ok(());
+        }
+
+        self.open.clear();
+        self.write(&IntentRecord::ResolvedAll)?;
+        self.maybe_compact()
+    }
+
+    fn write(&mut self, record: &IntentRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        self.file
+            .write_all(line.as_bytes())
+            .with_context(|| format!("failed to append to {}", self.path.display()))?;
+        self.records += 1;
+
+        Ok(())
+    }
+
+    fn maybe_compact(&mut self) -> Result<()> {
+        if self.records >= COMPACT_AFTER && self.records > 4 * self.open.len() {
+            self.compact()?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the log as just the unresolved intents, through a synced temp file and a
+    /// rename so a crash mid-compaction leaves the old log intact.
+    fn compact(&mut self) -> Result<()> {
+        let temp = self.path.with_extension("tmp");
+        let mut contents = serde_json::to_string(&IntentRecord::NextSeq {
+            next_seq: self.next_seq,
+        })?;
+        contents.push('\n');
+        for intent in self.open.values() {
+            contents.push_str(&serde_json::to_string(&intent.record())?);
+            contents.push('\n');
+        }
+
+        let mut file =
+            File::create(&temp).with_context(|| format!("failed to create {}", temp.display()))?;
+        file.write_all(contents.as_bytes())
+            .and_then(|_| file.sync_all())
+            .with_context(|| format!("failed to write {}", temp.display()))?;
+        fs::rename(&temp, &self.path)
+            .with_context(|| format!("failed to replace {}", self.path.display()))?;
+
+        self.file = append(&self.path)?;
+        self.records = self.open.len() + 1;
+
+        Ok(())
+    }
+}
+
+fn append(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open intent log {}", path.display()))
+}
+
+/// The sequence number following `order_id`'s, or 1 for ids without one.
+fn seq_after(order_id: &str) -> u64 {
+    ClientOrderId::parse(order_id).map_or(1, |id| id.seq + 1)
+}
+
+/// Replays the log into the set of unresolved intents and the next client order id
+/// sequence number. A torn final line, the signature of a crash mid-append, is skipped.
+fn replay(path: &Path) -> Result<(BTreeMap<String, Intent>, u64)> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read intent log {}", path.display()))?;
+
+    let mut open = BTreeMap::new();
+    let mut next_seq = 1;
+    for (index, line) in raw.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = match serde_json::from_str::<IntentRecord>(line) {
+            Ok(record) => record,
+            Err(error) => {
+                warn!(path = %path.display(), line = index + 1, "skipping unreadable intent: {error}");
+                continue;
+            }
+        };
+
+        match record {
+            IntentRecord::Place {
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+                cycle_id,
+            } => {
+                next_seq = next_seq.max(seq_after(&order_id));
+                open.insert(
+                    order_id.clone(),
+                    Intent {
+                        order_id,
+                        instrument,
+                        side,
+                        price: Some(price),
+                        quantity: Some(quantity),
+                        cycle_id,
+                    },
+                );
+            }
+            IntentRecord::Cancel {
+                order_id,
+                instrument,
+                side,
+            } => {
+                next_seq = next_seq.max(seq_after(&order_id));
+                open.entry(order_id.clone()).or_insert(Intent {
+                    order_id,
+                    instrument,
+                    side,
+                    price: None,
+                    quantity: None,
+                    cycle_id: None,
+                });
+            }
+            IntentRecord::Resolved { order_id } => {
+                open.remove(&order_id);
+            }
+            IntentRecord::ResolvedAll => open.clear(),
+            IntentRecord::NextSeq { next_seq: seq } => next_seq = next_seq.max(seq),
+        }
+    }
+
+    info!(path = %path.display(), unresolved = open.len(), next_seq, "replayed intent log");
+
+    Ok((open, next_seq))
+}
@@ -0,0 +1,2 @@
+pub mod intent_log;
+pub mod store;
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::config::app_config::ensure;
+use crate::risk::checks::max_daily_loss::DailyLossState;
+use crate::scenario::venues::VenueKind;
+use crate::stats::trading_book::TradingBook;
+use crate::types::pnl::PnlState;
+
+/// Bumped whenever [`EngineState`] changes shape; files with another version are ignored.
+pub const STATE_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StateConfig {
+    /// Persist engine state to this JSON file; persistence is disabled when unset.
+    pub path: Option<PathBuf>,
+    /// Seconds between periodic saves. State is also saved at shutdown.
+    pub save_interval_secs: u64,
+    /// Append every place and cancel to this file before sending it, and check what a
+    /// crashed run left unresolved at startup; disabled when unset.
+    pub intent_log: Option<PathBuf>,
+    /// Take over the engine's own orders still resting on the venue at startup, rather
+    /// than cancelling every open order. Its own are those the intent log kept, or led
+    /// by `venue.order_id_prefix`.
+    pub adopt_open_orders: bool,
+}
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            save_interval_secs: 30,
+            intent_log: None,
+            adopt_open_orders: false,
+        }
+    }
+}
+
+impl StateConfig {
+    pub fn save_interval(&self) -> Duration {
+        Duration::from_secs(self.save_interval_secs)
+    }
+
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.save_interval_secs > 0,
+            format!("{path}.save_interval_secs"),
+            "must be > 0",
+        )
+    }
+}
+
+/// Everything that should survive a restart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineState {
+    pub venue: VenueKind,
+    pub saved_at: DateTime<Utc>,
+    pub kill_switch: bool,
+    /// Trading book per instrument symbol.
+    pub books: BTreeMap<String, TradingBook>,
+    /// The daily loss limit's count of the day per instrument symbol, so a restart
+    /// after the limit tripped does not trade again that day.
+    pub daily_loss: BTreeMap<String, DailyLossState>,
+    /// Position, entry and realized PnL per instrument symbol, so exits keep their
+    /// thresholds across a restart.
+    pub pnl: BTreeMap<String, PnlState>,
+    /// The venue's last trade id passed on per pair, so the trades it replays after a
+    /// restart are not counted again. Empty for venues without trade ids.
+    #[serde(default)]
+    pub last_trade_ids: BTreeMap<String, u64>,
+}
+
+/// On-disk envelope. The checksum covers the serialized `state`, so truncated or
+/// hand-edited files are detected rather than half-restored.
+#[derive(Debug, Serialize, Deserialize)]
+struct StateFile {
+    version: u32,
+    checksum: String,
+    state: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    path: PathBuf,
+}
+
+impl StateStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Restores the saved state if there is a valid file for `venue`. A missing file is
+    /// normal on first start; anything unreadable is logged and ignored so a bad file
+    /// can never stop the engine from starting.
+    pub fn load(&self, venue: VenueKind) -> Option<EngineState> {
+        if !self.path.exists() {
+            return None;
+        }
+
+        match self.read() {
+            Ok(state) if state.venue == venue => {
+                info!(
+                    path = %self.path.display(),
+                    saved_at = %state.saved_at,
+                    instruments = state.books.len(),
+                    "restored engine state"
+                );
+                Some(state)
+            }
+            Ok(state) => {
+                warn!(
+                    path = %self.path.display(),
+                    saved_venue = %state.venue,
+                    %venue,
+                    "ignoring engine state saved for another venue"
+                );
+                None
+            }
+            Err(error) => {
+                warn!(path = %self.path.display(), "ignoring engine state: {error:#}");
+                None
+            }
+        }
+    }
+
+    fn read(&self) -> Result<EngineState> {
+        let raw = fs::read_to_string(&self.path).context("failed to read state file")?;
+        let file: StateFile = serde_json::from_str(&raw).context("malformed state file")?;
+
+        if file.version != STATE_VERSION {
+            bail!(
+                "state version {} does not match expected {STATE_VERSION}",
+                file.version
+            );
+        }
+        if file.checksum != checksum(&file.state)? {
+            bail!("state checksum mismatch");
+        }
+
+        serde_json::from_value(file.state).context("malformed state")
+    }
+
+    /// Writes to a sibling temp file first and renames it over the old one, so a crash
+    /// mid-write leaves the previous state intact.
+    pub fn save(&self, state: &EngineState) -> Result<()> {
+        let state = serde_json::to_value(state)?;
+        let file = StateFile {
+            version: STATE_VERSION,
+            checksum: checksum(&state)?,
+            state,
+        };
+
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create state dir {}", dir.display()))?;
+        }
+
+        let temp = self.path.with_extension("json.tmp");
+        fs::write(&temp, serde_json::to_string_pretty(&file)?)
+            .with_context(|| format!("failed to write {}", temp.display()))?;
+        fs::rename(&temp, &self.path)
+            .with_context(|| format!("failed to replace {}", self.path.display()))?;
+
+        Ok(())
+    }
+}
+
+fn checksum(state: &Value) -> Result<String> {
+    let digest = Sha256::digest(serde_json::to_string(state)?.as_bytes());
+    Ok(general_purpose::STANDARD.encode(digest))
+}
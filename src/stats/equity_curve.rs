@@ -0,0 +1,179 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// One sample of the equity curve: gross PnL with the open position marked at the mid.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EquityPoint {
+    pub at: DateTime<Utc>,
+    pub equity_quote: f64,
+}
+
+/// Risk figures of a session's equity curve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct EquityStats {
+    pub equity_quote: Option<f64>,
+    /// Highest equity seen.
+    pub peak_quote: Option<f64>,
+    /// How far equity is below its peak now.
+    pub drawdown_quote: f64,
+    pub max_drawdown_quote: f64,
+    /// Longest time spent below a peak, counting the current drawdown.
+    pub max_drawdown_secs: f64,
+    /// Mean over standard deviation of the equity change per sample interval; not
+    /// annualized. `None` until two changes with some variance were sampled.
+    pub sharpe_like: Option<f64>,
+    pub samples: u64,
+}
+
+/// The equity time series of one instrument, sampled on every fill and every sample
+/// interval, and the drawdown and return statistics over it.
+///
+/// Statistics are updated as samples arrive, so they cover the whole session even
+/// though only `max_points` samples are kept: past that, the older half of the series is
+/// thinned to every other point.
+#[derive(Debug, Clone)]
+pub struct EquityCurve {
+    sample_interval: Duration,
+    max_points: usize,
+    points: Vec<EquityPoint>,
+    samples: u64,
+    last: Option<f64>,
+    /// The peak and when it was reached.
+    peak: Option<(f64, Instant)>,
+    max_drawdown: f64,
+    max_drawdown_duration: Duration,
+    /// Equity at the last interval sample, and the changes between interval samples.
+    last_interval_sample: Option<(Instant, f64)>,
+    changes: Welford,
+}
+
+impl EquityCurve {
+    pub fn new(sample_interval: Duration, max_points: usize) -> Self {
+        Self {
+            sample_interval,
+            max_points,
+            points: Vec::new(),
+            samples: 0,
+            last: None,
+            peak: None,
+            max_drawdown: 0.0,
+            max_drawdown_duration: Duration::ZERO,
+            last_interval_sample: None,
+            changes: Welford::default(),
+        }
+    }
+
+    /// Samples `equity` after a fill.
+    pub fn on_fill(&mut self, equity: f64, now: Instant, at: DateTime<Utc>) {
+        self.sample(equity, now, at);
+    }
+
+    /// Samples `equity` if a sample interval has passed since the last interval sample.
+    /// Called on every mark, so the interval is kept in engine-clock time.
+    pub fn on_mark(&mut self, equity: f64, now: Instant, at: DateTime<Utc>) {
+        let due = self
+            .last_interval_sample
+            .is_none_or(|(sampled, _)| now.duration_since(sampled) >= self.sample_interval);
+        if !due {
+            return;
+        }
+
+        if let Some((_, previous)) = self.last_interval_sample {
+            self.changes.push(equity - previous);
+        }
+        self.last_interval_sample = Some((now, equity));
+        self.sample(equity, now, at);
+    }
+
+    fn sample(&mut self, equity: f64, now: Instant, at: DateTime<Utc>) {
+        self.samples += 1;
+        let previous = self.last.replace(equity);
+
+        match self.peak {
+            Some((peak, reached)) => {
+                let underwater = previous.is_some_and(|previous| previous < peak);
+                if equity < peak {
+                    self.max_drawdown = self.max_drawdown.max(peak - equity);
+                }
+                // Below the peak, or just back from below it: the drawdown lasted until now.
+                if equity < peak || underwater {
+                    self.max_drawdown_duration =
+                        self.max_drawdown_duration.max(now.duration_since(reached));
+                }
+                if equity >= peak {
+                    self.peak = Some((equity, now));
+                }
+            }
+            None => self.peak = Some((equity, now)),
+        }
+
+        self.points.push(EquityPoint {
+            at,
+            equity_quote: equity,
+        });
+        if self.points.len() > self.max_points {
+            self.downsample();
+        }
+    }
+
+    /// Drops every other point of the older half.
+    fn downsample(&mut self) {
+        let older = self.points.len() / 2;
+        let mut index = 0;
+        self.points.retain(|_| {
+            let keep = index >= older || index % 2 == 0;
+            index += 1;
+            keep
+        });
+    }
+
+    /// The kept samples, oldest first.
+    pub fn points(&self) -> &[EquityPoint] {
+        &self.points
+    }
+
+    pub fn stats(&self) -> EquityStats {
+        let peak = self.peak.map(|(peak, _)| peak);
+
+        EquityStats {
+            equity_quote: self.last,
+            peak_quote: peak,
+            drawdown_quote: match (peak, self.last) {
+                (Some(peak), Some(last)) => peak - last,
+                _ => 0.0,
+            },
+            max_drawdown_quote: self.max_drawdown,
+            max_drawdown_secs: self.max_drawdown_duration.as_secs_f64(),
+            sharpe_like: self.changes.sharpe_like(),
+            samples: self.samples,
+        }
+    }
+}
+
+/// Running mean and variance.
+#[derive(Debug, Clone, Copy, Default)]
+struct Welford {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn sharpe_like(&self) -> Option<f64> {
+        if self.count < 2 {
+            return None;
+        }
+
+        let std_dev = (self.m2 / (self.count - 1) as f64).sqrt();
+        (std_dev > 0.0).then(|| self.mean / std_dev)
+    }
+}
@@ -0,0 +1,324 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use tracing::error;
+
+use crate::execution::order_action::{OrderAction, Side};
+use crate::execution::order_report::OrderReport;
+use crate::market::market_state::MarketState;
+use crate::signals::signal_state::SignalState;
+use crate::types::instrument::Instrument;
+use crate::types::price::Price;
+
+/// How long after a fill the mid is sampled, matching the `mid_*` columns of [`FillRow`].
+pub const MARK_OUT_HORIZONS: [Duration; 3] = [
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+    Duration::from_secs(60),
+];
+
+const MAX_DECISIONS: usize = 1_024;
+const DECISION_TTL: Duration = Duration::from_secs(3_600);
+
+const CSV_HEADER: &str = "timestamp,instrument,order_id,cycle_id,side,price,quantity,mid,ema,\
+deviation,volatility,mid_1s,mid_10s,mid_60s,partial";
+
+/// Signal values when the engine decided to place an order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecisionSignals {
+    pub ema: Option<f64>,
+    /// Mid minus the fast EMA.
+    pub deviation: Option<f64>,
+    pub volatility: Option<f64>,
+}
+
+impl DecisionSignals {
+    pub fn capture(market_state: &MarketState, signal_state: &SignalState) -> Self {
+        let ema = signal_state.ema_mid();
+
+        Self {
+            ema,
+            deviation: market_state
+                .mid_price()
+                .zip(ema)
+                .map(|(mid, ema)| mid.as_f64() - ema),
+            volatility: signal_state.volatility_mid(),
+        }
+    }
+}
+
+/// One line of the fill report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FillRow {
+    pub timestamp: DateTime<Utc>,
+    pub instrument: String,
+    pub order_id: String,
+    /// Engine cycle that placed the order, when known.
+    pub cycle_id: Option<u64>,
+    pub side: Side,
+    pub price: f64,
+    pub quantity: f64,
+    /// Mid when the fill was reported.
+    pub mid: Option<f64>,
+    pub ema: Option<f64>,
+    pub deviation: Option<f64>,
+    pub volatility: Option<f64>,
+    pub mid_1s: Option<f64>,
+    pub mid_10s: Option<f64>,
+    pub mid_60s: Option<f64>,
+    /// The session ended before the last horizon; unreached mark-outs are empty.
+    pub partial: bool,
+}
+
+impl FillRow {
+    fn csv(&self) -> String {
+        fn cell(value: Option<f64>) -> String {
+            value.map(|value| value.to_string()).unwrap_or_default()
+        }
+
+        [
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+            self.instrument.clone(),
+            self.order_id.clone(),
+            self.cycle_id.map(|id| id.to_string()).unwrap_or_default(),
+            self.side.to_string(),
+            self.price.to_string(),
+            self.quantity.to_string(),
+            cell(self.mid),
+            cell(self.ema),
+            cell(self.deviation),
+            cell(self.volatility),
+            cell(self.mid_1s),
+            cell(self.mid_10s),
+            cell(self.mid_60s),
+            self.partial.to_string(),
+        ]
+        .join(",")
+    }
+}
+
+/// Append-only fill report shared by every instrument of a session: CSV when the path
+/// ends in `.csv`, JSON lines otherwise.
+#[derive(Debug, Clone)]
+pub struct FillReport {
+    inner: Arc<Mutex<FillReportFile>>,
+}
+
+#[derive(Debug)]
+struct FillReportFile {
+    path: PathBuf,
+    file: File,
+    csv: bool,
+}
+
+impl FillReport {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create fill report dir {}", dir.display()))?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open fill report {}", path.display()))?;
+
+        let csv = path.extension().is_some_and(|extension| extension == "csv");
+        if csv && file.metadata()?.len() == 0 {
+            writeln!(file, "{CSV_HEADER}")?;
+        }
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(FillReportFile {
+                path: path.to_path_buf(),
+                file,
+                csv,
+            })),
+        })
+    }
+
+    fn write(&self, row: &FillRow) {
+        let mut report = self.inner.lock().unwrap();
+        let line = if report.csv {
+            Ok(row.csv())
+        } else {
+            serde_json::to_string(row)
+        };
+
+        let result = line
+            .map_err(anyhow::Error::from)
+            .and_then(|line| Ok(writeln!(report.file, "{line}")?));
+        if let Err(error) = result {
+            error!(path = %report.path.display(), "failed to write fill report row: {error:#}");
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingFill {
+    row: FillRow,
+    filled_at: Instant,
+    /// Mark-outs taken so far; horizons elapse in order.
+    mark_outs: Vec<Option<f64>>,
+}
+
+impl PendingFill {
+    fn into_row(mut self) -> FillRow {
+        self.row.partial = self.mark_outs.len() < MARK_OUT_HORIZONS.len();
+        self.mark_outs.resize(MARK_OUT_HORIZONS.len(), None);
+        [self.row.mid_1s, self.row.mid_10s, self.row.mid_60s] =
+            [self.mark_outs[0], self.mark_outs[1], self.mark_outs[2]];
+
+        self.row
+    }
+}
+
+/// Why and when an order was placed.
+#[derive(Debug, Clone, Copy)]
+struct Decision {
+    decided_at: Instant,
+    cycle_id: Option<u64>,
+    signals: DecisionSignals,
+}
+
+/// Joins one instrument's fills with the signals behind their orders and the mid at each
+/// of the [`MARK_OUT_HORIZONS`], writing a [`FillRow`] once the last horizon has passed.
+#[derive(Debug)]
+pub struct FillAnnotator {
+    instrument: Instrument,
+    report: FillReport,
+    decisions: HashMap<String, Decision>,
+    pending: VecDeque<PendingFill>,
+}
+
+impl FillAnnotator {
+    pub fn new(instrument: Instrument, report: FillReport) -> Self {
+        Self {
+            instrument,
+            report,
+            decisions: HashMap::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Remembers `signals` as the reason for every placement in `actions`.
+    pub fn on_actions(&mut self, actions: &[OrderAction], signals: DecisionSignals, now: Instant) {
+        if self.decisions.len() >= MAX_DECISIONS {
+            self.decisions
+                .retain(|_, decision| now - decision.decided_at < DECISION_TTL);
+        }
+
+        for action in actions {
+            if let OrderAction::Place(order) = action {
+                self.decisions.insert(
+                    order.order_id.clone(),
+                    Decision {
+                        decided_at: now,
+                        cycle_id: order.cycle_id,
+                        signals,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Starts the mark-outs of a fill; `mid` is the current mid.
+    pub fn on_report(
+        &mut self,
+        report: &OrderReport,
+        mid: Option<Price>,
+        now: Instant,
+        now_utc: DateTime<Utc>,
+    ) {
+        let (order_id, side, price, quantity) = match report {
+            OrderReport::PartiallyFilled {
+                order_id,
+                side,
+                price,
+                quantity,
+                ..
+            }
+            | OrderReport::Filled {
+                order_id,
+                side,
+                price,
+                quantity,
+                ..
+            } => (order_id, *side, *price, *quantity),
+            OrderReport::Rejected { order_id, .. } | OrderReport::Cancelled { order_id, .. } => {
+                self.decisions.remove(order_id);
+                return;
+            }
+            _ => return,
+        };
+
+        let decision = match report {
+            OrderReport::Filled { .. } => self.decisions.remove(order_id),
+            _ => self.decisions.get(order_id).copied(),
+        };
+        let signals = decision
+            .map(|decision| decision.signals)
+            .unwrap_or_default();
+
+        self.pending.push_back(PendingFill {
+            row: FillRow {
+                timestamp: now_utc,
+                instrument: self.instrument.to_string(),
+                order_id: order_id.clone(),
+                cycle_id: decision.and_then(|decision| decision.cycle_id),
+                side,
+                price: price.as_f64(),
+                quantity: quantity.as_f64(),
+                mid: mid.map(Price::as_f64),
+                ema: signals.ema,
+                deviation: signals.deviation,
+                volatility: signals.volatility,
+                mid_1s: None,
+                mid_10s: None,
+                mid_60s: None,
+                partial: false,
+            },
+            filled_at: now,
+            mark_outs: Vec::with_capacity(MARK_OUT_HORIZONS.len()),
+        });
+    }
+
+    /// Takes `mid` as the mark-out of every horizon that has passed by `now`. Call before
+    /// the market state applies a new event, so `mid` is still the mid as of each horizon.
+    pub fn sample(&mut self, mid: Option<Price>, now: Instant) {
+        for fill in &mut self.pending {
+            while let Some(horizon) = MARK_OUT_HORIZONS.get(fill.mark_outs.len())
+                && now.saturating_duration_since(fill.filled_at) >= *horizon
+            {
+                fill.mark_outs.push(mid.map(Price::as_f64));
+            }
+        }
+
+        while self
+            .pending
+            .front()
+            .is_some_and(|fill| fill.mark_outs.len() == MARK_OUT_HORIZONS.len())
+        {
+            let fill = self.pending.pop_front().unwrap();
+            self.report.write(&fill.into_row());
+        }
+    }
+
+    /// Writes every pending fill at shutdown. Horizons already passed take `mid`; rows
+    /// still short of the last horizon are marked partial.
+    pub fn finish(&mut self, mid: Option<Price>, now: Instant) {
+        self.sample(mid, now);
+
+        for fill in self.pending.drain(..) {
+            self.report.write(&fill.into_row());
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, SecondsFormat, Utc};
+
+use crate::execution::order_action::Side;
+
+/// Columns of the fill export. Amounts are in the currency their column names: the
+/// pair's base for the quantity, its quote for the price and value.
+pub const FILLS_CSV_HEADER: &str =
+    "date_utc,pair,side,quantity_base,price_quote,fee,fee_currency,value_quote,trade_id,order_id";
+
+/// One execution on the venue, as the accountant sees it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerFill {
+    pub time: DateTime<Utc>,
+    pub base: String,
+    pub quote: String,
+    pub side: Side,
+    pub quantity: f64,
+    pub price: f64,
+    pub fee: f64,
+    pub fee_currency: String,
+    /// Quantity times price, in the quote currency, as the venue reported it.
+    pub value: f64,
+    /// Venue trade id.
+    pub trade_id: String,
+    /// Venue id of the order that filled.
+    pub order_id: String,
+}
+
+impl LedgerFill {
+    fn csv(&self) -> String {
+        let price_decimals = decimals(&self.quote) + 4;
+
+        [
+            self.time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            format!("{}/{}", self.base, self.quote),
+            match self.side {
+                Side::Buy => "buy".to_string(),
+                Side::Sell => "sell".to_string(),
+            },
+            amount(self.quantity, &self.base),
+            fixed(self.price, price_decimals),
+            amount(self.fee, &self.fee_currency),
+            self.fee_currency.clone(),
+            amount(self.value, &self.quote),
+            self.trade_id.clone(),
+            self.order_id.clone(),
+        ]
+        .join(",")
+    }
+}
+
+/// Decimal places amounts of `currency` are written with: cents for fiat, satoshis for
+/// everything else.
+pub fn decimals(currency: &str) -> usize {
+    match currency {
+        "JPY" => 0,
+        "GBP" | "USD" | "EUR" | "CAD" | "AUD" | "CHF" => 2,
+        _ => 8,
+    }
+}
+
+fn amount(value: f64, currency: &str) -> String {
+    fixed(value, decimals(currency))
+}
+
+/// `value` rounded half away from zero, as on an invoice, rather than the half-to-even
+/// of float formatting.
+fn fixed(value: f64, decimals: usize) -> String {
+    let scale = 10f64.powi(decimals as i32);
+    let rounded = (value * scale).round() / scale;
+    format!("{rounded:.decimals$}")
+}
+
+/// UTC calendar days, both ends inclusive; an open end is unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// Start of the first day.
+    pub fn start(&self) -> Option<DateTime<Utc>> {
+        self.from
+            .map(|from| from.and_time(NaiveTime::MIN).and_utc())
+    }
+
+    /// Start of the day after the last, exclusive.
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.to
+            .and_then(|to| to.checked_add_days(Days::new(1)))
+            .map(|end| end.and_time(NaiveTime::MIN).and_utc())
+    }
+
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start().is_none_or(|start| time >= start) && self.end().is_none_or(|end| time < end)
+    }
+}
+
+/// Every fill of an account, in time order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FillLedger {
+    fills: Vec<LedgerFill>,
+}
+
+impl FillLedger {
+    /// Orders `fills` by time, then trade id, so exports are stable.
+    pub fn new(mut fills: Vec<LedgerFill>) -> Self {
+        fills.sort_by(|a, b| (a.time, &a.trade_id).cmp(&(b.time, &b.trade_id)));
+        Self { fills }
+    }
+
+    pub fn fills(&self) -> &[LedgerFill] {
+        &self.fills
+    }
+
+    /// The fills within `range`.
+    pub fn within(&self, range: DateRange) -> Self {
+        Self {
+            fills: self
+                .fills
+                .iter()
+                .filter(|fill| range.contains(fill.time))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// The fills of each calendar month, keyed `YYYY-MM`.
+    pub fn by_month(&self) -> BTreeMap<String, Self> {
+        let mut months: BTreeMap<String, Self> = BTreeMap::new();
+        for fill in &self.fills {
+            let month = format!("{}-{:02}", fill.time.year(), fill.time.month());
+            months.entry(month).or_default().fills.push(fill.clone());
+        }
+
+        months
+    }
+
+    /// The header row and a row per fill.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{FILLS_CSV_HEADER}\n");
+        for fill in &self.fills {
+            csv.push_str(&fill.csv());
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Writes the ledger to `path`, replacing any previous export.
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create export dir {}", dir.display()))?;
+        }
+
+        fs::write(path, self.to_csv())
+            .with_context(|| format!("failed to write fills export {}", path.display()))
+    }
+
+    /// Writes a `fills-YYYY-MM.csv` per month into `dir`, returning each path with its
+    /// number of fills.
+    pub fn write_monthly_csv(&self, dir: &Path) -> Result<Vec<(PathBuf, usize)>> {
+        self.by_month()
+            .into_iter()
+            .map(|(month, ledger)| {
+                let path = dir.join(format!("fills-{month}.csv"));
+                ledger.write_csv(&path)?;
+                Ok((path, ledger.fills.len()))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,6 @@
+pub mod equity_curve;
+pub mod fill_annotator;
+pub mod fill_ledger;
+pub mod session_stats;
+pub mod session_summary;
+pub mod trading_book;
@@ -0,0 +1,850 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tracing::{Instrument as _, info, info_span};
+
+use crate::clock::SharedClock;
+use crate::config::app_config::ensure;
+
+use crate::execution::order_action::Side;
+use crate::execution::order_report::OrderReport;
+use crate::scenario::strategies::StrategyKind;
+use crate::stats::equity_curve::{EquityCurve, EquityStats};
+use crate::stats::session_summary::{InstrumentSummary, ShadowSummary, SideActivity};
+use crate::stats::trading_book::TradingBook;
+use crate::telemetry::ack_latency::{AckLatency, AckLatencyTracker};
+use crate::telemetry::latency::{Stage, StageHistograms};
+use crate::telemetry::metrics;
+use crate::types::instrument::Instrument;
+use crate::types::inventory::Inventory;
+use crate::types::price::Price;
+use crate::types::quantity::Quantity;
+
+const STATS_CHANNEL_CAPACITY: usize = 4_096;
+const TOP_REASONS: usize = 3;
+const MAX_PENDING_FILLS: usize = 1_024;
+const PENDING_FILL_TTL: Duration = Duration::from_secs(3_600);
+/// Placements the logged ack latency is taken over.
+const ACK_LATENCY_WINDOW: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsConfig {
+    /// Seconds between periodic session statistics lines.
+    pub interval_secs: u64,
+    /// Directory the end-of-session summary is written to, as `<session_id>.json`.
+    pub reports_dir: PathBuf,
+    /// Dry-run only: append every hypothetical fill, with the signals behind its order
+    /// and mid mark-outs, to this file. CSV for a `.csv` path, JSON lines otherwise.
+    pub fill_report: Option<PathBuf>,
+    /// Seconds between equity curve samples; fills are sampled as well.
+    pub equity_sample_secs: u64,
+    /// Equity samples kept per instrument. Past this, older samples are thinned out; the
+    /// drawdown and Sharpe-like figures still cover the whole session.
+    pub equity_max_points: usize,
+    /// Seconds between each engine's PnL line: position, average entry, and realized
+    /// and unrealized PnL.
+    pub pnl_log_secs: u64,
+    /// Seconds between each engine's orders line: the state, quote and age of every
+    /// level it believes it has working.
+    pub orders_log_secs: u64,
+    /// Seconds between rewrites of the session summary while running, so a session that
+    /// dies without a clean exit still leaves one. Written only at exit when unset.
+    pub summary_write_secs: Option<u64>,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 60,
+            reports_dir: PathBuf::from("reports"),
+            fill_report: None,
+            equity_sample_secs: 10,
+            equity_max_points: 2_000,
+            pnl_log_secs: 60,
+            orders_log_secs: 60,
+            summary_write_secs: None,
+        }
+    }
+}
+
+impl StatsConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    pub fn pnl_log_interval(&self) -> Duration {
+        Duration::from_secs(self.pnl_log_secs)
+    }
+
+    pub fn orders_log_interval(&self) -> Duration {
+        Duration::from_secs(self.orders_log_secs)
+    }
+
+    pub fn summary_write_interval(&self) -> Option<Duration> {
+        self.summary_write_secs.map(Duration::from_secs)
+    }
+
+    /// An empty equity curve sampled as configured.
+    pub fn equity_curve(&self) -> EquityCurve {
+        EquityCurve::new(
+            Duration::from_secs(self.equity_sample_secs),
+            self.equity_max_points,
+        )
+    }
+
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            self.interval_secs > 0,
+            format!("{path}.interval_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.equity_sample_secs > 0,
+            format!("{path}.equity_sample_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.pnl_log_secs > 0,
+            format!("{path}.pnl_log_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.orders_log_secs > 0,
+            format!("{path}.orders_log_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            self.equity_max_points >= 2,
+            format!("{path}.equity_max_points"),
+            "must be >= 2",
+        )?;
+        ensure(
+            self.summary_write_secs.is_none_or(|secs| secs > 0),
+            format!("{path}.summary_write_secs"),
+            "must be > 0",
+        )?;
+        ensure(
+            !self.reports_dir.as_os_str().is_empty(),
+            format!("{path}.reports_dir"),
+            "must not be empty",
+        )
+    }
+}
+
+/// Engine-side observations. Sent with `try_send` so a slow stats task can never
+/// back-pressure the main loop; anything that does not fit is simply not counted.
+#[derive(Debug, Clone, Copy)]
+pub enum StatsEvent {
+    MarketEvent,
+    Skipped {
+        reason: &'static str,
+    },
+    NoQuote {
+        reason: &'static str,
+    },
+    /// The strategy's target for the cycle, before risk.
+    TargetComputed {
+        bid: Option<Price>,
+        ask: Option<Price>,
+    },
+    /// A fill of an order placed outside the engine. The fill itself reaches the book
+    /// with the other reports; this only flags it.
+    ExternalFill,
+    RiskHold {
+        reason: &'static str,
+    },
+    RiskRejected {
+        reason: &'static str,
+    },
+    /// Balances and the mid at the start of a cycle, with the engine's realized PnL and,
+    /// given a mid, the unrealized PnL of its open position.
+    Snapshot {
+        inventory: Inventory,
+        mid: Option<Price>,
+        realized_pnl: f64,
+        unrealized_pnl: Option<f64>,
+    },
+    Latency {
+        stage: Stage,
+        elapsed: Duration,
+    },
+    Shadow {
+        strategy: StrategyKind,
+        outcome: ShadowOutcome,
+    },
+}
+
+/// One cycle of a shadow strategy, which never trades.
+#[derive(Debug, Clone, Copy)]
+pub enum ShadowOutcome {
+    Target,
+    NoQuote {
+        reason: &'static str,
+    },
+    /// The market crossed a quote the shadow strategy had resting.
+    Fill {
+        side: Side,
+        price: Price,
+        quantity: Quantity,
+    },
+}
+
+/// Requests answered by the stats task from its current state.
+#[derive(Debug)]
+pub enum StatsQuery {
+    Summary(oneshot::Sender<InstrumentSummary>),
+    Book(oneshot::Sender<TradingBook>),
+}
+
+#[derive(Debug, Clone)]
+pub struct StatsHandle {
+    sink: StatsSink,
+}
+
+#[derive(Debug, Clone)]
+enum StatsSink {
+    Task {
+        events: mpsc::Sender<StatsEvent>,
+        queries: mpsc::Sender<StatsQuery>,
+    },
+    /// Applied on the caller's thread, so a replay sees every event in order.
+    Inline {
+        instrument: Instrument,
+        stats: Arc<Mutex<SessionStats>>,
+    },
+}
+
+impl StatsHandle {
+    /// Handle that records straight into `stats`; the owner feeds it order reports.
+    pub fn inline(instrument: &Instrument, stats: Arc<Mutex<SessionStats>>) -> Self {
+        Self {
+            sink: StatsSink::Inline {
+                instrument: instrument.clone(),
+                stats,
+            },
+        }
+    }
+
+    pub fn record(&self, event: StatsEvent) {
+        match &self.sink {
+            StatsSink::Task { events, .. } => {
+                let _ = events.try_send(event);
+            }
+            StatsSink::Inline { stats, .. } => stats.lock().unwrap().on_event(event),
+        }
+    }
+
+    /// Whole-session summary; `None` if the stats task has already stopped.
+    pub async fn summary(&self) -> Option<InstrumentSummary> {
+        match &self.sink {
+            StatsSink::Task { queries, .. } => {
+                let (reply, response) = oneshot::channel();
+                queries.send(StatsQuery::Summary(reply)).await.ok()?;
+                response.await.ok()
+            }
+            StatsSink::Inline { instrument, stats } => {
+                Some(stats.lock().unwrap().instrument_summary(instrument))
+            }
+        }
+    }
+
+    /// The trading book as it stands, for the state store.
+    pub async fn book(&self) -> Option<TradingBook> {
+        match &self.sink {
+            StatsSink::Task { queries, .. } => {
+                let (reply, response) = oneshot::channel();
+                queries.send(StatsQuery::Book(reply)).await.ok()?;
+                response.await.ok()
+            }
+            StatsSink::Inline { stats, .. } => Some(stats.lock().unwrap().book().clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Counters {
+    pub events: u64,
+    pub targets: u64,
+    pub placed: u64,
+    pub accepted: u64,
+    pub filled: u64,
+    pub partially_filled: u64,
+    pub cancelled: u64,
+    pub rejected: u64,
+    pub external_fills: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatsSummary {
+    pub uptime: Duration,
+    pub window: Duration,
+    pub totals: Counters,
+    pub counters: Counters,
+    pub inventory: Inventory,
+    pub exposure_quote: Option<f64>,
+    pub mid: Option<Price>,
+    /// Gross PnL of the trading book at `mid`.
+    pub pnl_quote: Option<f64>,
+    /// This session's realized and unrealized PnL, from the engine's
+    /// [`PnlTracker`](crate::types::pnl::PnlTracker) as of the last snapshot.
+    pub realized_pnl_quote: f64,
+    pub unrealized_pnl_quote: Option<f64>,
+    pub equity: EquityStats,
+    /// One per shadow strategy, in [`StrategyKind`] order.
+    pub shadows: Vec<ShadowSummary>,
+    pub top_reasons: Vec<(&'static str, u64)>,
+    /// Per-stage p50/p95/p99 over the window; empty when latency tracking is off.
+    pub latency: String,
+    /// Placement ack p50/p95/max over the last placements answered, not just the window.
+    pub ack_latency: Option<AckLatency>,
+}
+
+#[derive(Debug)]
+pub struct SessionStats {
+    clock: SharedClock,
+    started: Instant,
+    window_started: Instant,
+    totals: Counters,
+    window: Counters,
+    reasons: HashMap<&'static str, u64>,
+    latency: StageHistograms,
+    ack_latency: AckLatencyTracker,
+    inventory: Inventory,
+    mid: Option<Price>,
+    realized_pnl: f64,
+    unrealized_pnl: Option<f64>,
+    book: TradingBook,
+    equity: EquityCurve,
+    session: SessionTotals,
+    shadows: BTreeMap<StrategyKind, ShadowSession>,
+}
+
+/// Whole-session figures that, unlike the window counters, are never reset.
+#[derive(Debug, Default)]
+struct SessionTotals {
+    fills: u64,
+    volume_base: f64,
+    volume_quote: f64,
+    max_exposure: f64,
+    placed_at: HashMap<String, Instant>,
+    quote_to_fill: Duration,
+    quote_to_fill_count: u32,
+    risk_rejections: BTreeMap<&'static str, u64>,
+    risk_holds: BTreeMap<&'static str, u64>,
+    no_quotes: BTreeMap<&'static str, u64>,
+    skips: BTreeMap<&'static str, u64>,
+    bids: SideActivity,
+    asks: SideActivity,
+    quoted_spread_bps: f64,
+    quoted_spreads: u64,
+    captured_spread_bps: f64,
+    captured_spreads: u64,
+    /// Orders the venue accepted that are still resting.
+    live: HashSet<String>,
+    quoting: Duration,
+    quoting_since: Option<Instant>,
+}
+
+impl SessionTotals {
+    fn side(&mut self, side: Side) -> &mut SideActivity {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    /// Time with at least one order resting, up to `now`.
+    fn quoting(&self, now: Instant) -> Duration {
+        self.quoting
+            + self
+                .quoting_since
+                .map_or(Duration::ZERO, |since| now.duration_since(since))
+    }
+}
+
+/// A shadow strategy's hypothetical session, kept apart from the real book.
+#[derive(Debug)]
+struct ShadowSession {
+    strategy: StrategyKind,
+    targets: u64,
+    no_quotes: u64,
+    fills: u64,
+    volume_base: f64,
+    volume_quote: f64,
+    book: TradingBook,
+}
+
+impl ShadowSession {
+    fn new(strategy: StrategyKind) -> Self {
+        Self {
+            strategy,
+            targets: 0,
+            no_quotes: 0,
+            fills: 0,
+            volume_base: 0.0,
+            volume_quote: 0.0,
+            book: TradingBook::default(),
+        }
+    }
+
+    fn summary(&self, mid: Option<Price>) -> ShadowSummary {
+        ShadowSummary {
+            strategy: self.strategy,
+            targets: self.targets,
+            no_quotes: self.no_quotes,
+            fills: self.fills,
+            volume_base: self.volume_base,
+            volume_quote: self.volume_quote,
+            gross_pnl_quote: mid.map(|mid| self.book.pnl(mid)),
+            max_drawdown_quote: self.book.max_drawdown,
+        }
+    }
+}
+
+impl SessionStats {
+    /// Stats starting from `book`, which is restored state or an empty book, sampling
+    /// its equity into `equity`.
+    pub fn new(clock: SharedClock, book: TradingBook, equity: EquityCurve) -> Self {
+        let now = clock.now_instant();
+
+        Self {
+            clock,
+            started: now,
+            window_started: now,
+            totals: Counters::default(),
+            window: Counters::default(),
+            reasons: HashMap::new(),
+            latency: StageHistograms::default(),
+            ack_latency: AckLatencyTracker::new(ACK_LATENCY_WINDOW),
+            inventory: Inventory::default(),
+            mid: None,
+            realized_pnl: 0.0,
+            unrealized_pnl: None,
+            book,
+            equity,
+            session: SessionTotals::default(),
+            shadows: BTreeMap::new(),
+        }
+    }
+
+    pub fn on_event(&mut self, event: StatsEvent) {
+        match event {
+            StatsEvent::MarketEvent => self.count(|counters| counters.events += 1),
+            StatsEvent::TargetComputed { bid, ask } => {
+                self.count(|counters| counters.targets += 1);
+                if let (Some(bid), Some(ask), Some(mid)) = (bid, ask, self.mid) {
+                    let spread = (ask.as_f64() - bid.as_f64()) / mid.as_f64() * 10_000.0;
+                    self.session.quoted_spread_bps += spread;
+                    self.session.quoted_spreads += 1;
+                }
+            }
+            StatsEvent::ExternalFill => self.count(|counters| counters.external_fills += 1),
+            StatsEvent::Skipped { reason } => {
+                *self.reasons.entry(reason).or_default() += 1;
+                *self.session.skips.entry(reason).or_default() += 1;
+            }
+            StatsEvent::NoQuote { reason } => {
+                *self.reasons.entry(reason).or_default() += 1;
+                *self.session.no_quotes.entry(reason).or_default() += 1;
+            }
+            StatsEvent::RiskHold { reason } => {
+                *self.reasons.entry(reason).or_default() += 1;
+                *self.session.risk_holds.entry(reason).or_default() += 1;
+            }
+            StatsEvent::RiskRejected { reason } => {
+                *self.reasons.entry(reason).or_default() += 1;
+                *self.session.risk_rejections.entry(reason).or_default() += 1;
+            }
+            StatsEvent::Snapshot {
+                inventory,
+                mid,
+                realized_pnl,
+                unrealized_pnl,
+            } => {
+                self.inventory = inventory;
+                self.realized_pnl = realized_pnl;
+                self.unrealized_pnl = unrealized_pnl;
+                if mid.is_some() {
+                    self.mid = mid;
+                }
+                if let Some(mid) = self.mid {
+                    let exposure = inventory.exposure_quote(mid).abs();
+                    self.session.max_exposure = self.session.max_exposure.max(exposure);
+                }
+                if let Some(mid) = self.mid {
+                    let today = self.clock.now_utc().date_naive();
+                    self.book.mark(mid, today);
+                    for shadow in self.shadows.values_mut() {
+                        shadow.book.mark(mid, today);
+                    }
+                }
+                self.sample_equity();
+            }
+            StatsEvent::Latency { stage, elapsed } => self.latency.record(stage, elapsed),
+            StatsEvent::Shadow { strategy, outcome } => {
+                let shadow = self
+                    .shadows
+                    .entry(strategy)
+                    .or_insert_with(|| ShadowSession::new(strategy));
+
+                match outcome {
+                    ShadowOutcome::Target => shadow.targets += 1,
+                    ShadowOutcome::NoQuote { .. } => shadow.no_quotes += 1,
+                    ShadowOutcome::Fill {
+                        side,
+                        price,
+                        quantity,
+                    } => {
+                        shadow.fills += 1;
+                        shadow.volume_base += quantity.as_f64();
+                        shadow.volume_quote += quantity.as_f64() * price.as_f64();
+                        shadow.book.on_fill(side, price, quantity);
+                        if let Some(mid) = self.mid {
+                            shadow.book.mark(mid, self.clock.now_utc().date_naive());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn on_report(&mut self, report: &OrderReport) {
+        let now = self.clock.now_instant();
+        self.ack_latency.on_report(report, now);
+
+        match report {
+            OrderReport::Placed { order_id, side, .. } => {
+                self.count(|counters| counters.placed += 1);
+                self.session.side(*side).placed += 1;
+
+                let placed_at = &mut self.session.placed_at;
+                if placed_at.len() >= MAX_PENDING_FILLS {
+                    placed_at.retain(|_, placed| now.duration_since(*placed) < PENDING_FILL_TTL);
+                }
+                placed_at.insert(order_id.clone(), now);
+            }
+            OrderReport::Accepted { order_id, .. } => {
+                self.count(|counters| counters.accepted += 1);
+                self.session.live.insert(order_id.clone());
+            }
+            OrderReport::Amended { side, .. } => self.session.side(*side).replaced += 1,
+            OrderReport::Filled { order_id, .. } => {
+                self.count(|counters| counters.filled += 1);
+                self.session.live.remove(order_id);
+                self.on_fill(report, now);
+            }
+            OrderReport::PartiallyFilled { .. } => {
+                self.count(|counters| counters.partially_filled += 1);
+                self.on_fill(report, now);
+            }
+            OrderReport::Cancelled { order_id, side, .. } => {
+                self.count(|counters| counters.cancelled += 1);
+                self.session.side(*side).cancelled += 1;
+                self.session.placed_at.remove(order_id);
+                self.session.live.remove(order_id);
+            }
+            OrderReport::Rejected { order_id, .. } => {
+                self.count(|counters| counters.rejected += 1);
+                self.session.placed_at.remove(order_id);
+            }
+            OrderReport::CancelledAll { .. } => self.session.live.clear(),
+            _ => {}
+        }
+
+        let session = &mut self.session;
+        match session.quoting_since {
+            None if !session.live.is_empty() => session.quoting_since = Some(now),
+            Some(since) if session.live.is_empty() => {
+                session.quoting += now.duration_since(since);
+                session.quoting_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn on_fill(&mut self, report: &OrderReport, now: Instant) {
+        let (Some(order_id), Some(side), Some(price), Some(quantity)) = (
+            report.order_id(),
+            report.side(),
+            report.price(),
+            report.quantity(),
+        ) else {
+            return;
+        };
+
+        let session = &mut self.session;
+        session.fills += 1;
+        session.volume_base += quantity.as_f64();
+        session.volume_quote += quantity.as_f64() * price.as_f64();
+
+        let activity = session.side(side);
+        activity.fills += 1;
+        activity.volume_base += quantity.as_f64();
+
+        // Twice the edge over the mid, to compare with the full quoted spread.
+        if let Some(mid) = self.mid {
+            let edge = side.signed(mid.as_f64() - price.as_f64());
+            session.captured_spread_bps += 2.0 * edge / mid.as_f64() * 10_000.0;
+            session.captured_spreads += 1;
+        }
+
+        // Time to the first fill only; later partial fills of the same order are not quotes.
+        if let Some(placed) = session.placed_at.remove(order_id) {
+            session.quote_to_fill += now.duration_since(placed);
+            session.quote_to_fill_count += 1;
+        }
+
+        self.book.on_fill(side, price, quantity);
+        if let Some(instrument) = report.instrument() {
+            let fee_bps = instrument.trading_rules().maker_fee_bps;
+            self.book.fees_quote += quantity.as_f64() * price.as_f64() * fee_bps / 10_000.0;
+        }
+        if let Some(mid) = self.mid {
+            let at = self.clock.now_utc();
+            self.book.mark(mid, at.date_naive());
+            self.equity.on_fill(self.book.pnl(mid), now, at);
+        }
+    }
+
+    /// Samples equity at the last mid if a sample interval has passed.
+    pub fn sample_equity(&mut self) {
+        if let Some(mid) = self.mid {
+            let pnl = self.book.pnl(mid);
+            self.equity
+                .on_mark(pnl, self.clock.now_instant(), self.clock.now_utc());
+        }
+    }
+
+    pub fn book(&self) -> &TradingBook {
+        &self.book
+    }
+
+    pub fn equity(&self) -> &EquityCurve {
+        &self.equity
+    }
+
+    pub fn instrument_summary(&self, instrument: &Instrument) -> InstrumentSummary {
+        let session = &self.session;
+        let acknowledged = self.totals.accepted + self.totals.rejected;
+        let now = self.clock.now_instant();
+        let quoting = session.quoting(now);
+        let average = |sum: f64, count: u64| (count > 0).then(|| sum / count as f64);
+
+        InstrumentSummary {
+            instrument: instrument.to_string(),
+            fills: session.fills,
+            external_fills: self.totals.external_fills,
+            volume_base: session.volume_base,
+            volume_quote: session.volume_quote,
+            gross_pnl_quote: self.mid.map(|mid| self.book.pnl(mid)),
+            fees_quote: self.book.fees_quote,
+            net_pnl_quote: self
+                .mid
+                .map(|mid| self.book.pnl(mid) - self.book.fees_quote),
+            max_drawdown_quote: self.book.max_drawdown,
+            equity: self.equity.stats(),
+            max_exposure_quote: session.max_exposure,
+            placed: self.totals.placed,
+            accepted: self.totals.accepted,
+            rejected: self.totals.rejected,
+            acceptance_rate: (acknowledged > 0)
+                .then(|| self.totals.accepted as f64 / acknowledged as f64),
+            avg_quote_to_fill_ms: (session.quote_to_fill_count > 0).then(|| {
+                session.quote_to_fill.as_secs_f64() * 1_000.0
+                    / f64::from(session.quote_to_fill_count)
+            }),
+            bids: session.bids,
+            asks: session.asks,
+            avg_quoted_spread_bps: average(session.quoted_spread_bps, session.quoted_spreads),
+            avg_captured_spread_bps: average(session.captured_spread_bps, session.captured_spreads),
+            quoting_secs: quoting.as_secs_f64(),
+            idle_secs: now
+                .duration_since(self.started)
+                .saturating_sub(quoting)
+                .as_secs_f64(),
+            no_quotes: session.no_quotes.clone(),
+            skips: session.skips.clone(),
+            risk_holds: session.risk_holds.clone(),
+            risk_rejections: session.risk_rejections.clone(),
+            shadows: self.shadow_summaries(),
+            orders: None,
+        }
+    }
+
+    pub fn summary(&self) -> StatsSummary {
+        let now = self.clock.now_instant();
+        let mut top_reasons: Vec<(&'static str, u64)> =
+            self.reasons.iter().map(|(code, n)| (*code, *n)).collect();
+        top_reasons.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        top_reasons.truncate(TOP_REASONS);
+
+        StatsSummary {
+            uptime: now.duration_since(self.started),
+            window: now.duration_since(self.window_started),
+            totals: self.totals,
+            counters: self.window,
+            inventory: self.inventory,
+            exposure_quote: self.mid.map(|mid| self.inventory.exposure_quote(mid)),
+            mid: self.mid,
+            pnl_quote: self.mid.map(|mid| self.book.pnl(mid)),
+            realized_pnl_quote: self.realized_pnl,
+            unrealized_pnl_quote: self.unrealized_pnl,
+            equity: self.equity.stats(),
+            shadows: self.shadow_summaries(),
+            top_reasons,
+            latency: self.latency.describe(),
+            ack_latency: self.ack_latency.summary(),
+        }
+    }
+
+    fn shadow_summaries(&self) -> Vec<ShadowSummary> {
+        self.shadows
+            .values()
+            .map(|shadow| shadow.summary(self.mid))
+            .collect()
+    }
+
+    pub fn reset_window(&mut self) {
+        self.window_started = self.clock.now_instant();
+        self.window = Counters::default();
+        self.reasons.clear();
+        self.latency = StageHistograms::default();
+    }
+
+    fn count(&mut self, apply: impl Fn(&mut Counters)) {
+        apply(&mut self.totals);
+        apply(&mut self.window);
+    }
+
+    /// Spawns the aggregation task for one instrument and returns the handle its engine
+    /// records into. Reports for other instruments are ignored.
+    pub fn spawn(
+        config: &StatsConfig,
+        instrument: &Instrument,
+        book: TradingBook,
+        clock: SharedClock,
+        mut reports: broadcast::Receiver<OrderReport>,
+    ) -> StatsHandle {
+        let (sender, mut events) = mpsc::channel(STATS_CHANNEL_CAPACITY);
+        let (queries, mut pending_queries) = mpsc::channel(1);
+        let span = info_span!("stats", instrument = %instrument);
+        let instrument = instrument.clone();
+        let interval = config.interval();
+        let equity_interval = Duration::from_secs(config.equity_sample_secs);
+        let equity = config.equity_curve();
+
+        tokio::spawn(
+            async move {
+                let mut stats = SessionStats::new(clock, book, equity);
+                let mut ticker =
+                    tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+                // Samples equity when the market is quiet and no snapshots arrive.
+                let mut equity_ticker = tokio::time::interval_at(
+                    tokio::time::Instant::now() + equity_interval,
+                    equity_interval,
+                );
+
+                loop {
+                    tokio::select! {
+                        Some(event) = events.recv() => stats.on_event(event),
+                        Some(query) = pending_queries.recv() => match query {
+                            StatsQuery::Summary(reply) => {
+                                let _ = reply.send(stats.instrument_summary(&instrument));
+                            }
+                            StatsQuery::Book(reply) => {
+                                let _ = reply.send(stats.book().clone());
+                            }
+                        },
+                        report = reports.recv() => match report {
+                            Ok(report) if report.concerns(&instrument) => {
+                                stats.on_report(&report);
+                            }
+                            Ok(_) => {}
+                            Err(broadcast::error::RecvError::Lagged(n)) => {
+                                metrics::reports_lagged("stats", n);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        },
+                        _ = ticker.tick() => {
+                            stats.summary().log();
+                            stats.reset_window();
+                        }
+                        _ = equity_ticker.tick() => stats.sample_equity(),
+                    }
+                }
+            }
+            .instrument(span),
+        );
+
+        StatsHandle {
+            sink: StatsSink::Task {
+                events: sender,
+                queries,
+            },
+        }
+    }
+}
+
+impl StatsSummary {
+    pub fn log(&self) {
+        let top_reasons = self
+            .top_reasons
+            .iter()
+            .map(|(code, n)| format!("{code}={n}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let shadows = self
+            .shadows
+            .iter()
+            .map(|shadow| {
+                format!(
+                    "{}:targets={},filled={},pnl={}",
+                    shadow.strategy,
+                    shadow.targets,
+                    shadow.fills,
+                    shadow
+                        .gross_pnl_quote
+                        .map_or("-".to_string(), |pnl| format!("{pnl:.2}"))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        info!(
+            uptime_secs = self.uptime.as_secs(),
+            window_secs = self.window.as_secs(),
+            events = self.counters.events,
+            targets = self.counters.targets,
+            placed = self.counters.placed,
+            filled = self.counters.filled,
+            partially_filled = self.counters.partially_filled,
+            cancelled = self.counters.cancelled,
+            rejected = self.counters.rejected,
+            external_fills = self.counters.external_fills,
+            total_events = self.totals.events,
+            total_filled = self.totals.filled,
+            inventory_base = self.inventory.base,
+            inventory_quote = self.inventory.quote,
+            exposure_quote = self.exposure_quote,
+            mid = self.mid.map(|mid| mid.as_f64()),
+            pnl = self.pnl_quote,
+            realized = self.realized_pnl_quote,
+            unrealized = self.unrealized_pnl_quote,
+            drawdown = self.equity.drawdown_quote,
+            max_drawdown = self.equity.max_drawdown_quote,
+            max_drawdown_secs = self.equity.max_drawdown_secs.round() as u64,
+            sharpe_like = self.equity.sharpe_like,
+            shadows = %shadows,
+            top_reasons = %top_reasons,
+            latency = %self.latency,
+            ack_latency = self.ack_latency.map(|latency| latency.to_string()),
+            "session stats"
+        );
+    }
+}
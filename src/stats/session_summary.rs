@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::info;
+
+use crate::execution::types::OrderManagerSnapshot;
+use crate::scenario::strategies::StrategyKind;
+use crate::stats::equity_curve::EquityStats;
+
+/// What one instrument did over the whole session, produced by its stats task on shutdown.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstrumentSummary {
+    pub instrument: String,
+    pub fills: u64,
+    /// Of `fills`, those of orders placed outside the engine.
+    pub external_fills: u64,
+    pub volume_base: f64,
+    pub volume_quote: f64,
+    /// Cash flow from fills plus the traded position marked at the last mid, gross of
+    /// fees. Includes earlier runs when the book was restored from the state store.
+    pub gross_pnl_quote: Option<f64>,
+    /// Fees on the same fills. The venue feed does not report them, so each fill is
+    /// charged the pair's `maker_fee_bps` of its notional.
+    pub fees_quote: f64,
+    /// `gross_pnl_quote` less `fees_quote`.
+    pub net_pnl_quote: Option<f64>,
+    pub max_drawdown_quote: f64,
+    /// This session's sampled equity curve; unlike `max_drawdown_quote`, never includes
+    /// restored state.
+    pub equity: EquityStats,
+    pub max_exposure_quote: f64,
+    pub placed: u64,
+    pub accepted: u64,
+    pub rejected: u64,
+    /// Accepted over accepted plus rejected; `None` until the venue acknowledged an order.
+    pub acceptance_rate: Option<f64>,
+    pub avg_quote_to_fill_ms: Option<f64>,
+    pub bids: SideActivity,
+    pub asks: SideActivity,
+    /// Full spread of the strategy's two-sided targets, in bps of the mid, averaged over
+    /// the cycles that produced one.
+    pub avg_quoted_spread_bps: Option<f64>,
+    /// Twice each fill's edge over the mid at the time, in bps of the mid, averaged over
+    /// fills: the part of the quoted spread fills actually earned.
+    pub avg_captured_spread_bps: Option<f64>,
+    /// Time with at least one order resting on the venue, and the rest of the session.
+    pub quoting_secs: f64,
+    pub idle_secs: f64,
+    /// Counts by reason code, over the whole session.
+    pub no_quotes: BTreeMap<&'static str, u64>,
+    pub skips: BTreeMap<&'static str, u64>,
+    pub risk_holds: BTreeMap<&'static str, u64>,
+    pub risk_rejections: BTreeMap<&'static str, u64>,
+    /// One per shadow strategy, in [`StrategyKind`] order.
+    pub shadows: Vec<ShadowSummary>,
+    /// What the engine believed it had working when the summary was taken.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orders: Option<OrderManagerSnapshot>,
+}
+
+/// Orders one side of the book saw over the session. Replaced orders are those amended
+/// in place; a cancel and a fresh place count once each.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SideActivity {
+    pub placed: u64,
+    pub replaced: u64,
+    pub cancelled: u64,
+    pub fills: u64,
+    pub volume_base: f64,
+}
+
+/// What a shadow strategy would have done this session, had its quotes filled whenever
+/// the market crossed them. Never includes restored state.
+#[derive(Debug, Clone, Serialize)]
+pub struct ShadowSummary {
+    pub strategy: StrategyKind,
+    pub targets: u64,
+    pub no_quotes: u64,
+    pub fills: u64,
+    pub volume_base: f64,
+    pub volume_quote: f64,
+    pub gross_pnl_quote: Option<f64>,
+    pub max_drawdown_quote: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub duration_secs: f64,
+    pub instruments: Vec<InstrumentSummary>,
+}
+
+impl SessionSummary {
+    pub fn new(
+        session_id: &str,
+        started_at: DateTime<Utc>,
+        ended_at: DateTime<Utc>,
+        mut instruments: Vec<InstrumentSummary>,
+    ) -> Self {
+        instruments.sort_by(|a, b| a.instrument.cmp(&b.instrument));
+
+        Self {
+            session_id: session_id.to_string(),
+            started_at,
+            ended_at,
+            duration_secs: (ended_at - started_at).num_milliseconds() as f64 / 1_000.0,
+            instruments,
+        }
+    }
+
+    pub fn log(&self) {
+        info!("session summary\n{self}");
+    }
+
+    /// Writes `<dir>/<session_id>.json`, creating the directory if needed.
+    pub fn write(&self, dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create reports dir {}", dir.display()))?;
+
+        let path = dir.join(format!("{}.json", self.session_id));
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&path, json)
+            .with_context(|| format!("failed to write session summary {}", path.display()))?;
+
+        Ok(path)
+    }
+}
+
+impl fmt::Display for SessionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  session    {}", self.session_id)?;
+        writeln!(
+            f,
+            "  duration   {:.0}s ({} -> {})",
+            self.duration_secs,
+            self.started_at.format("%Y-%m-%d %H:%M:%S"),
+            self.ended_at.format("%Y-%m-%d %H:%M:%S"),
+        )?;
+
+        for summary in &self.instruments {
+            write!(f, "{summary}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for InstrumentSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn or_dash(value: Option<f64>, precision: usize) -> String {
+            value.map_or_else(|| "-".to_string(), |value| format!("{value:.precision$}"))
+        }
+
+        fn counts(counts: &BTreeMap<&'static str, u64>) -> String {
+            if counts.is_empty() {
+                return "-".to_string();
+            }
+            counts
+                .iter()
+                .map(|(code, n)| format!("{code}={n}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        fn side(activity: &SideActivity) -> String {
+            format!(
+                "{} placed, {} replaced, {} cancelled, {} filled ({:.8} base)",
+                activity.placed,
+                activity.replaced,
+                activity.cancelled,
+                activity.fills,
+                activity.volume_base
+            )
+        }
+
+        writeln!(f, "  [{}]", self.instrument)?;
+        write!(
+            f,
+            "    fills        {} ({:.8} base, {:.2} quote)",
+            self.fills, self.volume_base, self.volume_quote
+        )?;
+        if self.external_fills > 0 {
+            write!(f, ", {} external", self.external_fills)?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "    pnl          {} gross, {:.2} fees, {} net, max drawdown {:.2}",
+            or_dash(self.gross_pnl_quote, 2),
+            self.fees_quote,
+            or_dash(self.net_pnl_quote, 2),
+            self.max_drawdown_quote
+        )?;
+        writeln!(
+            f,
+            "    equity       peak {}, max drawdown {:.2} over {:.0}s, sharpe-like {} ({} samples)",
+            or_dash(self.equity.peak_quote, 2),
+            self.equity.max_drawdown_quote,
+            self.equity.max_drawdown_secs,
+            or_dash(self.equity.sharpe_like, 2),
+            self.equity.samples,
+        )?;
+        writeln!(f, "    max exposure {:.2}", self.max_exposure_quote)?;
+        writeln!(
+            f,
+            "    orders       {} placed, {} accepted, {} rejected (acceptance {})",
+            self.placed,
+            self.accepted,
+            self.rejected,
+            self.acceptance_rate
+                .map_or_else(|| "-".to_string(), |rate| format!("{:.1}%", rate * 100.0))
+        )?;
+        writeln!(
+            f,
+            "    quote->fill  {} ms avg",
+            or_dash(self.avg_quote_to_fill_ms, 0)
+        )?;
+
+        writeln!(f, "    bids         {}", side(&self.bids))?;
+        writeln!(f, "    asks         {}", side(&self.asks))?;
+        writeln!(
+            f,
+            "    spread       {} bps quoted, {} bps captured (avg)",
+            or_dash(self.avg_quoted_spread_bps, 1),
+            or_dash(self.avg_captured_spread_bps, 1)
+        )?;
+        let session_secs = self.quoting_secs + self.idle_secs;
+        writeln!(
+            f,
+            "    time         {:.0}s quoting, {:.0}s idle ({} quoting)",
+            self.quoting_secs,
+            self.idle_secs,
+            if session_secs > 0.0 {
+                format!("{:.1}%", self.quoting_secs / session_secs * 100.0)
+            } else {
+                "-".to_string()
+            }
+        )?;
+        writeln!(f, "    no quotes    {}", counts(&self.no_quotes))?;
+        writeln!(f, "    skips        {}", counts(&self.skips))?;
+        writeln!(f, "    risk holds   {}", counts(&self.risk_holds))?;
+        writeln!(f, "    risk rejects {}", counts(&self.risk_rejections))?;
+        if let Some(orders) = &self.orders {
+            writeln!(f, "    working      {orders}")?;
+        }
+
+        for shadow in &self.shadows {
+            writeln!(
+                f,
+                "    shadow       {} vs actual: fills {} vs {}, pnl {} vs {} gross, \
+                 max drawdown {:.2} vs {:.2} ({} targets, {} no-quotes)",
+                shadow.strategy,
+                shadow.fills,
+                self.fills,
+                or_dash(shadow.gross_pnl_quote, 2),
+                or_dash(self.gross_pnl_quote, 2),
+                shadow.max_drawdown_quote,
+                self.max_drawdown_quote,
+                shadow.targets,
+                shadow.no_quotes,
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::execution::order_action::Side;
+use crate::types::price::Price;
+use crate::types::quantity::Quantity;
+
+/// Net traded position and the quote paid for it, which is enough to mark gross PnL at
+/// any mid. Persisted by the state store so PnL and drawdown survive restarts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradingBook {
+    /// Base bought minus base sold.
+    pub traded_base: f64,
+    /// Quote received minus quote paid; negative while net bought.
+    pub traded_quote: f64,
+    /// Fees on the fills, estimated at the pair's maker fee since the venue feed does
+    /// not report them.
+    #[serde(default)]
+    pub fees_quote: f64,
+    pub peak_pnl: Option<f64>,
+    pub max_drawdown: f64,
+    /// Gross PnL made on each UTC day, marked at the latest mid.
+    pub pnl_by_day: BTreeMap<NaiveDate, f64>,
+    /// The day currently accruing, and the PnL it opened at.
+    day_open: Option<(NaiveDate, f64)>,
+    last_pnl: Option<f64>,
+}
+
+impl TradingBook {
+    pub fn on_fill(&mut self, side: Side, price: Price, quantity: Quantity) {
+        self.traded_base += side.signed(quantity.as_f64());
+        self.traded_quote -= side.signed(quantity.as_f64()) * price.as_f64();
+    }
+
+    pub fn pnl(&self, mid: Price) -> f64 {
+        self.traded_quote + self.traded_base * mid.as_f64()
+    }
+
+    /// Marks the book at `mid`, updating the running peak, the deepest fall from it and
+    /// the PnL of `today`, the current UTC day.
+    pub fn mark(&mut self, mid: Price, today: NaiveDate) {
+        let pnl = self.pnl(mid);
+        let previous = self.last_pnl.replace(pnl);
+
+        let peak = self.peak_pnl.map_or(pnl, |peak| peak.max(pnl));
+        self.peak_pnl = Some(peak);
+        self.max_drawdown = self.max_drawdown.max(peak - pnl);
+
+        let (day, open) = match self.day_open {
+            Some((day, open)) if day == today => (day, open),
+            // A new day opens at the previous mark, so it includes any move since then.
+            _ => (today, previous.unwrap_or(pnl)),
+        };
+        self.day_open = Some((day, open));
+        self.pnl_by_day.insert(day, pnl - open);
+    }
+}
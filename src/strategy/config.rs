@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use crate::config::app_config::ensure;
+use crate::market::market_state::MidKind;
+use crate::scenario::strategies::StrategyKind;
+use crate::strategy::exit::ExitParams;
+use crate::strategy::strategies::{
+    avellaneda_stoikov::AvellanedaStoikovParams, inventory_target::InventoryTargetParams,
+    layered_mm::LayeredMarketMakerParams, mean_reversion::MeanReversionParams,
+    regime_switch::RegimeSwitchParams, simple_mm::SimpleMarketMakerParams,
+    trend_following::TrendFollowingParams,
+};
+use crate::types::instrument::{Instrument, InstrumentConfig};
+
+/// Most levels a layered strategy may quote per side; each is an order of its own.
+const MAX_LADDER_LEVELS: usize = 10;
+
+/// Strategy selection plus parameters for every strategy. Only the section for `kind` is used,
+/// except regime switch which also builds its legs from `mean_reversion` and `trend_following`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrategyConfig {
+    pub kind: StrategyKind,
+    pub simple_mm: SimpleMarketMakerParams,
+    pub mean_reversion: MeanReversionParams,
+    pub trend_following: TrendFollowingParams,
+    pub regime_switch: RegimeSwitchParams,
+    pub avellaneda_stoikov: AvellanedaStoikovParams,
+    pub layered_mm: LayeredMarketMakerParams,
+    pub inventory_target: InventoryTargetParams,
+    /// Take profit and stop loss for whatever position the strategy builds.
+    pub exit: ExitParams,
+    /// The touch price fair value and the signal EMAs are taken from.
+    pub mid_kind: MidKind,
+    /// Overrides by pair, e.g. `SOL/GBP`, of any parameters above but `kind`. Fields left
+    /// out keep the values above.
+    pub pairs: BTreeMap<String, Value>,
+}
+
+impl StrategyConfig {
+    /// The parameters `instrument` trades with: these, with its entry in `pairs` applied.
+    pub fn for_instrument(&self, instrument: &Instrument) -> Result<Self> {
+        let overrides = self.pairs.iter().find(|(pair, _)| {
+            pair.parse::<InstrumentConfig>().is_ok_and(|pair| {
+                pair.base == instrument.base() && pair.quote == instrument.quote()
+            })
+        });
+        match overrides {
+            Some((_, overrides)) => self.with_overrides(overrides),
+            None => Ok(self.clone()),
+        }
+    }
+
+    fn with_overrides(&self, overrides: &Value) -> Result<Self> {
+        let mut merged = serde_yaml::to_value(Self {
+            pairs: BTreeMap::new(),
+            ..self.clone()
+        })?;
+        merge(&mut merged, overrides);
+
+        Ok(serde_yaml::from_value(merged)?)
+    }
+
+    pub fn validate(&self, path: &str) -> Result<()> {
+        self.validate_params(path)?;
+
+        for (pair, overrides) in &self.pairs {
+            let pair_path = format!("{path}.pairs.{pair}");
+            pair.parse::<InstrumentConfig>()
+                .with_context(|| format!("{pair_path}: not a BASE/QUOTE pair"))?;
+            for key in ["kind", "pairs"] {
+                ensure(
+                    overrides.get(key).is_none(),
+                    format!("{pair_path}.{key}"),
+                    "cannot be set per pair",
+                )?;
+            }
+
+            self.with_overrides(overrides)
+                .with_context(|| pair_path.clone())?
+                .validate_params(&pair_path)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_params(&self, path: &str) -> Result<()> {
+        ensure(
+            self.simple_mm.max_skew_bps >= 0.0,
+            format!("{path}.simple_mm.max_skew_bps"),
+            "must be >= 0",
+        )?;
+        ensure(
+            self.simple_mm.imbalance_skew_bps >= 0.0,
+            format!("{path}.simple_mm.imbalance_skew_bps"),
+            "must be >= 0",
+        )?;
+        for (field, value) in [
+            (
+                "simple_mm.volatility_reference_ticks",
+                self.simple_mm.volatility_reference_ticks,
+            ),
+            (
+                "mean_reversion.volatility_reference_ticks",
+                self.mean_reversion.volatility_reference_ticks,
+            ),
+        ] {
+            ensure(value > 0.0, format!("{path}.{field}"), "must be > 0")?;
+        }
+
+        let (mean_reversion, trend_following) = (&self.mean_reversion, &self.trend_following);
+        // A zero threshold would trade on every tick of deviation.
+        for (field, value) in [
+            (
+                "mean_reversion.entry_threshold_ticks",
+                mean_reversion.entry_threshold_ticks,
+            ),
+            (
+                "trend_following.entry_threshold_ticks",
+                trend_following.entry_threshold_ticks,
+            ),
+            (
+                "regime_switch.trend_enter_threshold_ticks",
+                self.regime_switch.trend_enter_threshold_ticks,
+            ),
+        ] {
+            ensure(value > 0.0, format!("{path}.{field}"), "must be > 0")?;
+        }
+
+        for (field, value) in [
+            ("trend_filter_ticks", mean_reversion.trend_filter_ticks),
+            (
+                "counter_trend_multiplier",
+                mean_reversion.counter_trend_multiplier,
+            ),
+            ("inventory_penalty", mean_reversion.inventory_penalty),
+        ] {
+            ensure(
+                value >= 0.0,
+                format!("{path}.mean_reversion.{field}"),
+                "must be >= 0",
+            )?;
+        }
+
+        for (field, value) in [
+            (
+                "volatility_entry_multiplier",
+                trend_following.volatility_entry_multiplier,
+            ),
+            (
+                "slope_threshold_ticks",
+                trend_following.slope_threshold_ticks,
+            ),
+            (
+                "pullback_tolerance_ticks",
+                trend_following.pullback_tolerance_ticks,
+            ),
+        ] {
+            ensure(
+                value >= 0.0,
+                format!("{path}.trend_following.{field}"),
+                "must be >= 0",
+            )?;
+        }
+
+        let avellaneda_stoikov = &self.avellaneda_stoikov;
+        for (field, value) in [
+            ("risk_aversion", avellaneda_stoikov.risk_aversion),
+            ("horizon_secs", avellaneda_stoikov.horizon_secs),
+            (
+                "order_book_liquidity",
+                avellaneda_stoikov.order_book_liquidity,
+            ),
+        ] {
+            ensure(
+                value > 0.0,
+                format!("{path}.avellaneda_stoikov.{field}"),
+                "must be > 0",
+            )?;
+        }
+
+        let layered_mm = &self.layered_mm;
+        ensure(
+            (1..=MAX_LADDER_LEVELS).contains(&layered_mm.levels),
+            format!("{path}.layered_mm.levels"),
+            "must be between 1 and 10",
+        )?;
+        ensure(
+            layered_mm.level_spacing_ticks > 0,
+            format!("{path}.layered_mm.level_spacing_ticks"),
+            "must be > 0",
+        )?;
+        ensure(
+            layered_mm.size_decay > 0.0 && layered_mm.size_decay <= 1.0,
+            format!("{path}.layered_mm.size_decay"),
+            "must be > 0 and <= 1",
+        )?;
+
+        let inventory_target = &self.inventory_target;
+        ensure(
+            inventory_target.target_base.is_finite(),
+            format!("{path}.inventory_target.target_base"),
+            "must be a number",
+        )?;
+        ensure(
+            inventory_target.tolerance >= 0.0,
+            format!("{path}.inventory_target.tolerance"),
+            "must be >= 0",
+        )?;
+
+        let regime_switch = &self.regime_switch;
+        ensure(
+            regime_switch.trend_exit_threshold_ticks <= regime_switch.trend_enter_threshold_ticks,
+            format!("{path}.regime_switch.trend_exit_threshold_ticks"),
+            "must be <= trend_enter_threshold_ticks",
+        )?;
+
+        self.exit.validate(&format!("{path}.exit"))
+    }
+}
+
+/// Writes `overrides` over `base`, recursing into mappings so only the keys given change.
+fn merge(base: &mut Value, overrides: &Value) {
+    match (base, overrides) {
+        (Value::Mapping(base), Value::Mapping(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides.clone(),
+    }
+}
@@ -0,0 +1,178 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    config::app_config::ensure,
+    execution::order_action::Side,
+    market::market_state::MarketState,
+    types::{
+        instrument::Instrument,
+        pnl::PnlTracker,
+        price::Price,
+        quantity::Quantity,
+        quote::Quote,
+        quote_target::{NoQuoteReason, QuoteTarget},
+    },
+};
+
+/// Exits for an open position, in ticks from its average entry. Off while both are unset.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExitParams {
+    /// Rests the exit this far in profit.
+    pub take_profit_ticks: Option<f64>,
+    /// Once the touch is this far in loss, chases it with the exit until flat.
+    pub stop_loss_ticks: Option<f64>,
+    /// Sends a hit stop's exit immediate-or-cancel at the far touch, crossing the spread
+    /// to cut the position at once, instead of resting it post-only at the near touch.
+    pub stop_loss_immediate: bool,
+}
+
+impl ExitParams {
+    pub fn validate(&self, path: &str) -> Result<()> {
+        for (field, value) in [
+            ("take_profit_ticks", self.take_profit_ticks),
+            ("stop_loss_ticks", self.stop_loss_ticks),
+        ] {
+            ensure(
+                value.is_none_or(|ticks| ticks > 0.0),
+                format!("{path}.{field}"),
+                "must be > 0",
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn enabled(&self) -> bool {
+        self.take_profit_ticks.is_some() || self.stop_loss_ticks.is_some()
+    }
+}
+
+/// Overrides the side of the strategy's target that reduces the open position with an
+/// exit for all of it, one `max_order_notional` clip at a time, so the position is closed
+/// without waiting for an opposite signal. Exits are post-only unless a hit stop is sent
+/// immediate-or-cancel. The other side keeps the strategy's quote. The position and entry come from the fills, so partial fills resize the exit,
+/// and once flat the strategy's target goes through untouched, replacing the exit.
+#[derive(Debug, Clone)]
+pub struct ExitManager {
+    params: ExitParams,
+    /// Exit side of the position the stop was hit for, until it is flat or flips.
+    stopped: Option<Side>,
+}
+
+impl ExitManager {
+    pub fn new(params: &ExitParams) -> Self {
+        Self {
+            params: params.clone(),
+            stopped: None,
+        }
+    }
+
+    pub fn update_params(&mut self, params: &ExitParams) {
+        self.params = params.clone();
+    }
+
+    pub fn apply(
+        &mut self,
+        instrument: &Instrument,
+        market_state: &MarketState,
+        pnl: &PnlTracker,
+        target: Result<QuoteTarget, NoQuoteReason>,
+    ) -> Result<QuoteTarget, NoQuoteReason> {
+        let rules = instrument.trading_rules();
+        // A position that is no number has nothing to exit.
+        let position = Quantity::new(pnl.position().abs()).map_or(Quantity::ZERO, |position| {
+            rules.round_quantity_to_step(position)
+        });
+        if position.is_zero() {
+            self.stopped = None;
+            return target;
+        }
+        if !self.params.enabled() {
+            return target;
+        }
+
+        let (Some(entry), Some(best_bid), Some(best_ask)) = (
+            pnl.average_entry(),
+            market_state.best_bid(),
+            market_state.best_ask(),
+        ) else {
+            return target;
+        };
+
+        // Long positions exit with a sell; `away` is the direction of profit in price.
+        let (side, touch, away) = if pnl.position() > 0.0 {
+            (Side::Sell, best_ask, 1.0)
+        } else {
+            (Side::Buy, best_bid, -1.0)
+        };
+        // The price the position could be closed at right now, crossing the spread.
+        let close = match side {
+            Side::Sell => best_bid,
+            Side::Buy => best_ask,
+        };
+        let tick = rules.price_tick;
+        if self.stopped.is_some_and(|stopped| stopped != side) {
+            self.stopped = None;
+        }
+
+        if let Some(ticks) = self.params.stop_loss_ticks
+            && self.stopped.is_none()
+        {
+            let stop = entry.as_f64() - away * ticks * tick;
+            if (close.as_f64() - stop) * away <= 0.0 {
+                self.stopped = Some(side);
+                info!(
+                    entry = entry.as_f64(),
+                    stop,
+                    close = close.as_f64(),
+                    "stop loss hit; exiting at the touch"
+                );
+            }
+        }
+
+        let immediate = self.stopped.is_some() && self.params.stop_loss_immediate;
+        let price = if immediate {
+            close
+        } else if self.stopped.is_some() {
+            touch
+        } else if let Some(ticks) = self.params.take_profit_ticks {
+            let take_profit = take_profit_price(entry, away * ticks * tick, tick, side);
+            // Post-only: a take profit the market already passed rests at the touch.
+            match side {
+                Side::Sell if take_profit <= best_bid => best_ask,
+                Side::Buy if take_profit >= best_ask => best_bid,
+                _ => take_profit,
+            }
+        } else {
+            return target;
+        };
+
+        let clip = rules.quantity_from_notional(rules.max_order_notional, price.as_f64());
+        let quantity = position.min(clip);
+        if quantity.is_zero() {
+            return target;
+        }
+
+        // The other side keeps whatever the strategy quoted on it, every level.
+        let mut target = target.unwrap_or_else(|_| QuoteTarget::none());
+        target.replace_side(side, Some(Quote { price, quantity }));
+        if immediate {
+            target.immediate = Some(side);
+        }
+        Ok(target)
+    }
+}
+
+/// `entry` moved by `offset`, on a tick at least that far in profit for an exit on `side`.
+fn take_profit_price(entry: Price, offset: f64, tick: f64, side: Side) -> Price {
+    let ticks = (entry.as_f64() + offset) / tick;
+    // Absorbs float noise, e.g. 93.05 / 0.01 landing just above 9305.
+    let ticks = match side {
+        Side::Sell => (ticks - 1e-9).ceil(),
+        Side::Buy => (ticks + 1e-9).floor(),
+    };
+    Price::new(ticks * tick)
+}
@@ -0,0 +1,53 @@
+use crate::{
+    execution::order_action::Side,
+    market::market_state::MarketState,
+    types::{
+        instrument::Instrument,
+        inventory::Inventory,
+        quote::Quote,
+        quote_target::{NoQuoteReason, QuoteTarget},
+    },
+};
+
+/// Reduce-only target used while the engine is flattening: joins the touch on the side that
+/// moves inventory towards flat, one `max_order_notional` clip at a time so it stays post-only
+/// and within the usual per-order size.
+pub fn flatten_target(
+    instrument: &Instrument,
+    market_state: &MarketState,
+    inventory: Inventory,
+) -> Result<QuoteTarget, NoQuoteReason> {
+    let rules = instrument.trading_rules();
+    let position = inventory
+        .base_size()
+        .map_err(|_| NoQuoteReason::InvalidQuantity)?;
+    let position = rules.round_quantity_to_step(position);
+    if position.is_zero() {
+        return Err(NoQuoteReason::AlreadyFlat);
+    }
+
+    let side = if Side::Buy.is_reducing_for(inventory.base) {
+        Side::Buy
+    } else {
+        Side::Sell
+    };
+
+    let price = match side {
+        Side::Buy => market_state.best_bid(),
+        Side::Sell => market_state.best_ask(),
+    }
+    .ok_or(NoQuoteReason::MissingTopOfBook)?;
+
+    let clip = rules.quantity_from_notional(rules.max_order_notional, price.as_f64());
+    let quantity = position.min(clip);
+    if quantity.is_zero() {
+        return Err(NoQuoteReason::InvalidQuantity);
+    }
+
+    let quote = Some(Quote { price, quantity });
+
+    Ok(match side {
+        Side::Buy => QuoteTarget::new(quote, None),
+        Side::Sell => QuoteTarget::new(None, quote),
+    })
+}
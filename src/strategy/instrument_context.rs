@@ -1,17 +1,33 @@
+use crate::types::fx::ExposureCurrency;
 use crate::types::instrument::Instrument;
+use crate::types::quote_target::NoQuoteReason;
 
 #[derive(Debug, Clone)]
 pub struct InstrumentContext {
     pub instrument: Instrument,
+    pub exposure_currency: ExposureCurrency,
 }
 
 impl InstrumentContext {
     pub fn new(instrument: &Instrument) -> Self {
         Self {
             instrument: instrument.clone(),
+            exposure_currency: ExposureCurrency::default(),
         }
     }
 
+    /// `max_exposure`, given in the exposure currency, in the pair's quote currency, so
+    /// exposure in the quote currency can be compared with it directly.
+    pub fn max_exposure_in_quote(&self, max_exposure: f64) -> Result<f64, NoQuoteReason> {
+        self.exposure_currency
+            .rate(&self.instrument)
+            .map(|rate| max_exposure / rate)
+            .map_err(|missing| NoQuoteReason::MissingFxRate {
+                from: missing.from,
+                to: missing.to,
+            })
+    }
+
     pub fn tick(&self) -> f64 {
         self.rules().price_tick
     }
@@ -24,11 +40,12 @@ impl InstrumentContext {
         self.rules().min_half_spread
     }
 
-    pub fn rules(&self) -> &crate::types::trading_rules::TradingRules {
+    pub fn rules(&self) -> crate::types::trading_rules::TradingRules {
         self.instrument.trading_rules()
     }
 }
 
 pub trait WithContext {
     fn ctx(&self) -> &InstrumentContext;
+    fn ctx_mut(&mut self) -> &mut InstrumentContext;
 }
@@ -1,4 +1,9 @@
+pub mod config;
+pub mod exit;
+pub mod flatten;
 pub mod instrument_context;
+pub mod shadow;
 pub mod strategies;
+#[allow(clippy::module_inception)]
 pub mod strategy;
 pub mod strategy_helpers;
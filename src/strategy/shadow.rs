@@ -0,0 +1,115 @@
+use crate::events::MarketEvent;
+use crate::execution::order_action::Side;
+use crate::market::market_state::MarketState;
+use crate::scenario::scenario::Scenario;
+use crate::scenario::strategies::StrategyKind;
+use crate::signals::signal_state::SignalState;
+use crate::stats::session_stats::{ShadowOutcome, StatsEvent, StatsHandle};
+use crate::strategy::config::StrategyConfig;
+use crate::strategy::strategy::Strategy;
+use crate::types::fx::ExposureCurrency;
+use crate::types::instrument::Instrument;
+use crate::types::inventory::Inventory;
+use crate::types::quote_target::{NoQuoteReason, QuoteTarget};
+
+/// A candidate strategy run on the primary's inputs, in the engine's loop right after the
+/// primary decides. Its latest target rests on a hypothetical book that fills when the
+/// market crosses it, like the dry-run venue; every outcome goes to the stats, and
+/// nothing it decides reaches a venue.
+///
+/// It owns a strategy built apart from the primary's and sees the market and signals
+/// only by shared reference, so whatever state it keeps, such as the regime switch's
+/// current regime, it cannot change what the primary decides.
+pub struct ShadowStrategy {
+    kind: StrategyKind,
+    strategy: Box<dyn Strategy>,
+    resting: QuoteTarget,
+    stats: StatsHandle,
+}
+
+impl ShadowStrategy {
+    /// `kind` built from the parameters in `config`, whatever its own `kind`, measuring
+    /// exposure in `exposure_currency` as the primary strategy does.
+    pub fn new(
+        kind: StrategyKind,
+        config: &StrategyConfig,
+        instrument: &Instrument,
+        exposure_currency: &ExposureCurrency,
+        stats: StatsHandle,
+    ) -> Self {
+        let config = StrategyConfig {
+            kind,
+            ..config.clone()
+        };
+        let mut strategy = Scenario::strategy(&config, instrument);
+        strategy.set_exposure_currency(exposure_currency);
+
+        Self {
+            kind,
+            strategy,
+            resting: QuoteTarget::none(),
+            stats,
+        }
+    }
+
+    pub fn kind(&self) -> StrategyKind {
+        self.kind
+    }
+
+    pub fn update_params(&mut self, config: &StrategyConfig) {
+        self.strategy.update_params(config);
+    }
+
+    /// Fills the resting quotes `event` crosses.
+    pub fn on_market_event(&mut self, event: &MarketEvent) {
+        for (side, resting) in [
+            (Side::Buy, &mut self.resting.bid),
+            (Side::Sell, &mut self.resting.ask),
+        ] {
+            if let Some(quote) = *resting
+                && event.crosses(side, quote.price)
+            {
+                *resting = None;
+                self.stats.record(StatsEvent::Shadow {
+                    strategy: self.kind,
+                    outcome: ShadowOutcome::Fill {
+                        side,
+                        price: quote.price,
+                        quantity: quote.quantity,
+                    },
+                });
+            }
+        }
+    }
+
+    /// Decides on the primary's inputs and rests the new target in place of the last.
+    pub fn evaluate(
+        &mut self,
+        market_state: &MarketState,
+        signal_state: &SignalState,
+        inventory: Inventory,
+    ) -> Result<QuoteTarget, NoQuoteReason> {
+        let decision = self
+            .strategy
+            .compute_target(market_state, signal_state, inventory);
+
+        let outcome = match &decision {
+            Ok(target) => {
+                self.resting = target.clone();
+                ShadowOutcome::Target
+            }
+            Err(reason) => {
+                self.resting = QuoteTarget::none();
+                ShadowOutcome::NoQuote {
+                    reason: reason.code(),
+                }
+            }
+        };
+        self.stats.record(StatsEvent::Shadow {
+            strategy: self.kind,
+            outcome,
+        });
+
+        decision
+    }
+}
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    market::market_state::MarketState,
+    signals::signal_state::SignalState,
+    strategy::{
+        config::StrategyConfig,
+        instrument_context::{InstrumentContext, WithContext},
+        strategy::Strategy,
+        strategy_helpers::StrategyHelpers,
+    },
+    types::{
+        instrument::Instrument,
+        inventory::Inventory,
+        quote::Quote,
+        quote_target::{NoQuoteReason, QuoteTarget},
+    },
+};
+
+/// Quotes both sides around an inventory-adjusted reservation price, after Avellaneda and
+/// Stoikov: with inventory `q` in order-size lots, volatility `σ` and horizon `T`,
+///
+/// ```text
+/// reservation = mid - q·γ·σ²·T
+/// spread      = γ·σ²·T + (2/γ)·ln(1 + γ/κ)
+/// ```
+///
+/// so a long position lowers both quotes to sell it down, and volatility widens them.
+/// Volatility is the mid volatility signal, taken as the mid's move over a second.
+#[derive(Debug, Clone)]
+pub struct AvellanedaStoikovStrategy {
+    ctx: InstrumentContext,
+    pub max_exposure_in_quote: f64,
+    pub risk_aversion: f64,
+    pub horizon_secs: f64,
+    pub order_book_liquidity: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AvellanedaStoikovParams {
+    /// `γ`: how far inventory moves the reservation price, and the volatility term of
+    /// the spread.
+    pub risk_aversion: f64,
+    /// `T`: seconds the position is expected to be held before it can be unwound.
+    pub horizon_secs: f64,
+    /// `κ`: how quickly fills fall off with distance from the mid, per unit of price.
+    /// Higher is a deeper book and a tighter spread.
+    pub order_book_liquidity: f64,
+}
+
+impl Default for AvellanedaStoikovParams {
+    fn default() -> Self {
+        Self {
+            risk_aversion: 0.1,
+            horizon_secs: 60.0,
+            order_book_liquidity: 100.0,
+        }
+    }
+}
+
+impl AvellanedaStoikovStrategy {
+    pub fn new(instrument: &Instrument, params: &AvellanedaStoikovParams) -> Self {
+        let mut strategy = Self {
+            ctx: InstrumentContext::new(instrument),
+            max_exposure_in_quote: instrument.trading_rules().max_exposure_in_quote,
+            risk_aversion: 0.0,
+            horizon_secs: 0.0,
+            order_book_liquidity: 0.0,
+        };
+        strategy.set_params(params);
+        strategy
+    }
+
+    fn set_params(&mut self, params: &AvellanedaStoikovParams) {
+        self.risk_aversion = params.risk_aversion;
+        self.horizon_secs = params.horizon_secs;
+        self.order_book_liquidity = params.order_book_liquidity;
+    }
+
+    /// Reservation price and half-spread around `mid`, for `lots` of inventory.
+    pub fn reservation(&self, mid: f64, volatility: f64, lots: f64) -> (f64, f64) {
+        let gamma = self.risk_aversion;
+        let risk = gamma * volatility.powi(2) * self.horizon_secs;
+        let spread = risk + (2.0 / gamma) * (1.0 + gamma / self.order_book_liquidity).ln();
+
+        (mid - lots * risk, spread / 2.0)
+    }
+}
+
+impl WithContext for AvellanedaStoikovStrategy {
+    fn ctx(&self) -> &InstrumentContext {
+        &self.ctx
+    }
+
+    fn ctx_mut(&mut self) -> &mut InstrumentContext {
+        &mut self.ctx
+    }
+}
+
+impl Strategy for AvellanedaStoikovStrategy {
+    fn update_params(&mut self, config: &StrategyConfig) {
+        self.max_exposure_in_quote = self.ctx().rules().max_exposure_in_quote;
+        self.set_params(&config.avellaneda_stoikov);
+    }
+
+    fn compute_target(
+        &self,
+        market_state: &MarketState,
+        signal_state: &SignalState,
+        inventory: Inventory,
+    ) -> Result<QuoteTarget, NoQuoteReason> {
+        let (best_bid, best_ask) =
+            Self::best_bid_ask(market_state).ok_or(NoQuoteReason::MissingTopOfBook)?;
+        let mid = Self::anchor(market_state, signal_state).ok_or(NoQuoteReason::MissingMid)?;
+        // The reservation price is this strategy's fair price, and needs the volatility.
+        let volatility = signal_state
+            .volatility_mid()
+            .ok_or(NoQuoteReason::MissingFairPrice)?;
+
+        let rules = self.ctx().rules();
+        let tick = self.ctx().tick();
+
+        let order_quantity = self
+            .size_from_notional(mid)
+            .ok_or(NoQuoteReason::InvalidQuantity)?;
+
+        let lots = inventory.base / order_quantity.as_f64();
+        let (reservation, half_spread) = self.reservation(mid, volatility, lots);
+        let half_spread = half_spread.max(self.ctx().min_half_spread());
+
+        let desired_bid = self.clamp_bid(reservation - half_spread, best_ask);
+        let desired_ask = self.clamp_ask(reservation + half_spread, best_bid);
+        if desired_bid > best_ask - tick || desired_ask < best_bid + tick {
+            return Err(NoQuoteReason::WouldCrossPostOnly);
+        }
+
+        // Drop a side whose fill would take the position past the exposure limit.
+        let max_exposure = self
+            .ctx()
+            .max_exposure_in_quote(self.max_exposure_in_quote)?;
+        let exposure_after = |base: f64| base * mid;
+        let bid =
+            (exposure_after(inventory.base + order_quantity.as_f64()) <= max_exposure).then(|| {
+                Quote {
+                    price: rules.round_price_to_tick(desired_bid),
+                    quantity: order_quantity,
+                }
+            });
+        let ask = (exposure_after(inventory.base - order_quantity.as_f64()) >= -max_exposure).then(
+            || Quote {
+                price: rules.round_price_to_tick(desired_ask),
+                quantity: order_quantity,
+            },
+        );
+
+        if bid.is_none() && ask.is_none() {
+            return Err(NoQuoteReason::BothSidesSuppressedByExposure);
+        }
+
+        Ok(QuoteTarget::new(bid, ask))
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    market::market_state::MarketState,
+    signals::signal_state::SignalState,
+    strategy::{
+        config::StrategyConfig,
+        instrument_context::{InstrumentContext, WithContext},
+        strategy::Strategy,
+        strategy_helpers::StrategyHelpers,
+    },
+    types::{
+        instrument::Instrument,
+        inventory::Inventory,
+        price::Price,
+        quantity::Quantity,
+        quote::Quote,
+        quote_target::{NoQuoteReason, QuoteTarget},
+    },
+};
+
+/// Works the base inventory towards `target_base` instead of keeping it flat: bids at
+/// the touch while short of the target, asks while over it, and nothing once within
+/// `tolerance` of it. Each quote is the distance left to go, capped at the largest
+/// order the notional rules allow, so the last fill lands on the target rather than past
+/// it.
+///
+/// Quotes step up to `max_chase_ticks` inside the touch to fill sooner, never so far
+/// they would cross. The risk checks still apply, so a target beyond the exposure limit
+/// stops at the limit.
+#[derive(Debug, Clone)]
+pub struct InventoryTargetStrategy {
+    ctx: InstrumentContext,
+    pub target_base: f64,
+    pub tolerance: f64,
+    pub max_chase_ticks: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InventoryTargetParams {
+    /// Base inventory to work towards, e.g. 50.0 to accumulate 50 SOL.
+    pub target_base: f64,
+    /// How far from the target, in base, counts as there.
+    pub tolerance: f64,
+    /// Most ticks a quote steps inside the touch; 0 joins it.
+    pub max_chase_ticks: u32,
+}
+
+impl Default for InventoryTargetParams {
+    fn default() -> Self {
+        Self {
+            target_base: 0.0,
+            tolerance: 0.0,
+            max_chase_ticks: 1,
+        }
+    }
+}
+
+impl InventoryTargetStrategy {
+    pub fn new(instrument: &Instrument, params: &InventoryTargetParams) -> Self {
+        let mut strategy = Self {
+            ctx: InstrumentContext::new(instrument),
+            target_base: 0.0,
+            tolerance: 0.0,
+            max_chase_ticks: 0,
+        };
+        strategy.set_params(params);
+        strategy
+    }
+
+    fn set_params(&mut self, params: &InventoryTargetParams) {
+        self.target_base = params.target_base;
+        self.tolerance = params.tolerance;
+        self.max_chase_ticks = params.max_chase_ticks;
+    }
+}
+
+impl WithContext for InventoryTargetStrategy {
+    fn ctx(&self) -> &InstrumentContext {
+        &self.ctx
+    }
+
+    fn ctx_mut(&mut self) -> &mut InstrumentContext {
+        &mut self.ctx
+    }
+}
+
+impl Strategy for InventoryTargetStrategy {
+    fn update_params(&mut self, config: &StrategyConfig) {
+        self.set_params(&config.inventory_target);
+    }
+
+    fn compute_target(
+        &self,
+        market_state: &MarketState,
+        _signal_state: &SignalState,
+        inventory: Inventory,
+    ) -> Result<QuoteTarget, NoQuoteReason> {
+        let rules = self.ctx().rules();
+        let shortfall = self.target_base - inventory.base;
+        // Inventory is a sum of fills, so the distance left carries float noise that a
+        // millionth of a step absorbs.
+        let noise = rules.quantity_step * 1e-6;
+        if shortfall.abs() - noise <= self.tolerance {
+            return Err(NoQuoteReason::AtInventoryTarget);
+        }
+
+        let (best_bid, best_ask) =
+            Self::best_bid_ask(market_state).ok_or(NoQuoteReason::MissingTopOfBook)?;
+        let tick = self.ctx().tick();
+        let chase = f64::from(self.max_chase_ticks) * tick;
+
+        let desired = if shortfall > 0.0 {
+            self.clamp_bid(best_bid + chase, best_ask)
+        } else {
+            self.clamp_ask(best_ask - chase, best_bid)
+        };
+        // Sanity: a locked or crossed book leaves no post-only price.
+        if shortfall > 0.0 && desired > best_ask - tick
+            || shortfall < 0.0 && desired < best_bid + tick
+        {
+            return Err(NoQuoteReason::WouldCrossPostOnly);
+        }
+        // The touch is on a tick, so rounding to the nearest one only undoes float error
+        // that rounding down would turn into a tick off.
+        let price = Price::new((desired / tick).round() * tick);
+
+        // A remainder smaller than the venue's smallest order cannot be closed: that is
+        // as near the target as it gets.
+        let remaining =
+            Quantity::new(shortfall.abs() + noise).map_err(|_| NoQuoteReason::InvalidQuantity)?;
+        let remaining = rules.round_quantity_to_step(remaining);
+        if remaining.is_zero() {
+            return Err(NoQuoteReason::AtInventoryTarget);
+        }
+        let quantity = self
+            .size_from_notional(price.as_f64())
+            .ok_or(NoQuoteReason::InvalidQuantity)?
+            .min(remaining);
+
+        let quote = Some(Quote { price, quantity });
+        Ok(if shortfall > 0.0 {
+            QuoteTarget::new(quote, None)
+        } else {
+            QuoteTarget::new(None, quote)
+        })
+    }
+}
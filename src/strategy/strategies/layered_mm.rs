@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    execution::order_action::Side,
+    market::market_state::MarketState,
+    signals::signal_state::SignalState,
+    strategy::{
+        config::StrategyConfig,
+        instrument_context::{InstrumentContext, WithContext},
+        strategy::Strategy,
+        strategy_helpers::StrategyHelpers,
+    },
+    types::{
+        instrument::Instrument,
+        inventory::Inventory,
+        price::Price,
+        quantity::Quantity,
+        quote::Quote,
+        quote_target::{NoQuoteReason, QuoteTarget},
+    },
+};
+
+/// Layers liquidity on both sides: a quote at the touch, or the minimum half-spread from
+/// the mid if that is wider, and further levels behind it `level_spacing_ticks` apart,
+/// each `size_decay` times the size of the one before.
+///
+/// A side quotes only as many levels as could all fill without taking the position past
+/// the exposure limit, and none if even the first could not.
+#[derive(Debug, Clone)]
+pub struct LayeredMarketMakerStrategy {
+    ctx: InstrumentContext,
+    pub max_exposure_in_quote: f64,
+    pub levels: usize,
+    pub level_spacing_ticks: u32,
+    pub size_decay: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayeredMarketMakerParams {
+    /// Levels quoted on each side, the first nearest the touch.
+    pub levels: usize,
+    /// Ticks between one level's price and the next.
+    pub level_spacing_ticks: u32,
+    /// Each level's size as a share of the level before it; 1 quotes every level at the
+    /// full order size.
+    pub size_decay: f64,
+}
+
+impl Default for LayeredMarketMakerParams {
+    fn default() -> Self {
+        Self {
+            levels: 3,
+            level_spacing_ticks: 2,
+            size_decay: 0.7,
+        }
+    }
+}
+
+impl LayeredMarketMakerStrategy {
+    pub fn new(instrument: &Instrument, params: &LayeredMarketMakerParams) -> Self {
+        let mut strategy = Self {
+            ctx: InstrumentContext::new(instrument),
+            max_exposure_in_quote: instrument.trading_rules().max_exposure_in_quote,
+            levels: 0,
+            level_spacing_ticks: 0,
+            size_decay: 0.0,
+        };
+        strategy.set_params(params);
+        strategy
+    }
+
+    fn set_params(&mut self, params: &LayeredMarketMakerParams) {
+        self.levels = params.levels;
+        self.level_spacing_ticks = params.level_spacing_ticks;
+        self.size_decay = params.size_decay;
+    }
+
+    /// The ladder on `side` from `nearest`, level 0 first, each level further from the
+    /// touch. Stops at the first level too small to place or whose fill, with every level
+    /// before it, would take the position past `max_exposure`, in the quote currency.
+    fn ladder(
+        &self,
+        side: Side,
+        nearest: f64,
+        order_quantity: Quantity,
+        inventory: Inventory,
+        mid: f64,
+        max_exposure: f64,
+    ) -> Vec<Quote> {
+        let rules = self.ctx().rules();
+        let tick = self.ctx().tick();
+        // Stepped in whole ticks, so no level lands a tick off through rounding.
+        let nearest_ticks = (rules.round_price_to_tick(nearest).as_f64() / tick).round();
+        let spacing_ticks = f64::from(self.level_spacing_ticks);
+        let smallest = rules.quantity_step.max(rules.min_order_quantity);
+
+        let mut ladder = Vec::new();
+        let mut position = inventory.base;
+        for level in 0..self.levels {
+            let quantity =
+                Quantity::new(order_quantity.as_f64() * self.size_decay.powi(level as i32))
+                    .map_or(Quantity::ZERO, |quantity| {
+                        rules.round_quantity_to_step(quantity)
+                    });
+            position += side.signed(quantity.as_f64());
+            if quantity.as_f64() < smallest || side.sign() * position * mid > max_exposure {
+                break;
+            }
+
+            ladder.push(Quote {
+                price: Price::new(
+                    (nearest_ticks - side.sign() * spacing_ticks * level as f64) * tick,
+                ),
+                quantity,
+            });
+        }
+        ladder
+    }
+}
+
+impl WithContext for LayeredMarketMakerStrategy {
+    fn ctx(&self) -> &InstrumentContext {
+        &self.ctx
+    }
+
+    fn ctx_mut(&mut self) -> &mut InstrumentContext {
+        &mut self.ctx
+    }
+}
+
+impl Strategy for LayeredMarketMakerStrategy {
+    fn update_params(&mut self, config: &StrategyConfig) {
+        self.max_exposure_in_quote = self.ctx().rules().max_exposure_in_quote;
+        self.set_params(&config.layered_mm);
+    }
+
+    fn compute_target(
+        &self,
+        market_state: &MarketState,
+        signal_state: &SignalState,
+        inventory: Inventory,
+    ) -> Result<QuoteTarget, NoQuoteReason> {
+        let (best_bid, best_ask) =
+            Self::best_bid_ask(market_state).ok_or(NoQuoteReason::MissingTopOfBook)?;
+        let mid = Self::anchor(market_state, signal_state).ok_or(NoQuoteReason::MissingMid)?;
+        let tick = self.ctx().tick();
+
+        let half_spread = self.ctx().min_half_spread();
+        let nearest_bid = self.clamp_bid(best_bid.min(mid - half_spread), best_ask);
+        let nearest_ask = self.clamp_ask(best_ask.max(mid + half_spread), best_bid);
+        if nearest_bid > best_ask - tick || nearest_ask < best_bid + tick {
+            return Err(NoQuoteReason::WouldCrossPostOnly);
+        }
+
+        let order_quantity = self
+            .size_from_notional(mid)
+            .ok_or(NoQuoteReason::InvalidQuantity)?;
+
+        let max_exposure = self
+            .ctx()
+            .max_exposure_in_quote(self.max_exposure_in_quote)?;
+        let mut bids = self
+            .ladder(
+                Side::Buy,
+                nearest_bid,
+                order_quantity,
+                inventory,
+                mid,
+                max_exposure,
+            )
+            .into_iter();
+        let mut asks = self
+            .ladder(
+                Side::Sell,
+                nearest_ask,
+                order_quantity,
+                inventory,
+                mid,
+                max_exposure,
+            )
+            .into_iter();
+
+        let target = QuoteTarget {
+            bid: bids.next(),
+            ask: asks.next(),
+            deeper_bids: bids.collect(),
+            deeper_asks: asks.collect(),
+            ..QuoteTarget::none()
+        };
+        if target.bid.is_none() && target.ask.is_none() {
+            return Err(NoQuoteReason::BothSidesSuppressedByExposure);
+        }
+
+        Ok(target)
+    }
+}
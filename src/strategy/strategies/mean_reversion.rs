@@ -1,10 +1,13 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     market::market_state::MarketState,
     signals::signal_state::SignalState,
     strategy::{
+        config::StrategyConfig,
         instrument_context::{InstrumentContext, WithContext},
         strategy::Strategy,
-        strategy_helpers::StrategyHelpers,
+        strategy_helpers::{StrategyHelpers, VolatilityScaling},
     },
     types::{
         instrument::Instrument,
@@ -22,7 +25,7 @@ use crate::{
 pub struct MakerOnlyMeanReversionStrategy {
     ctx: InstrumentContext,
 
-    /// Maximum absolute exposure in quote currency
+    /// Maximum absolute exposure, in the exposure currency when one is configured
     pub max_exposure_in_quote: f64,
 
     /// Improve by 1 tick if spread allows
@@ -39,30 +42,92 @@ pub struct MakerOnlyMeanReversionStrategy {
 
     /// Additional threshold multiplier (0..n) based on exposure in the trade direction
     pub inventory_penalty: f64,
+
+    /// Quote smaller while mid volatility is above `volatility_reference_ticks`
+    pub volatility_sizing_enabled: bool,
+
+    /// Mid volatility (in ticks) above which size shrinks
+    pub volatility_reference_ticks: f64,
+
+    /// How size shrinks above the reference
+    pub volatility_scaling: VolatilityScaling,
 }
 
-impl MakerOnlyMeanReversionStrategy {
-    pub fn for_instrument(instrument: &Instrument) -> Self {
-        let max_exposure_in_quote = instrument.trading_rules().max_exposure_in_quote;
+/// Tunable parameters; see the matching fields on [`MakerOnlyMeanReversionStrategy`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MeanReversionParams {
+    pub improve_if_possible: bool,
+    pub entry_threshold_ticks: f64,
+    pub trend_filter_ticks: f64,
+    pub counter_trend_multiplier: f64,
+    pub inventory_penalty: f64,
+    pub volatility_sizing_enabled: bool,
+    pub volatility_reference_ticks: f64,
+    pub volatility_scaling: VolatilityScaling,
+}
+
+impl Default for MeanReversionParams {
+    fn default() -> Self {
         Self {
-            ctx: InstrumentContext::new(instrument),
-            max_exposure_in_quote,
-            entry_threshold_ticks: 3.0,
             improve_if_possible: true,
+            entry_threshold_ticks: 3.0,
             trend_filter_ticks: 2.0,
             counter_trend_multiplier: 1.5,
             inventory_penalty: 1.0,
+            volatility_sizing_enabled: false,
+            volatility_reference_ticks: 2.0,
+            volatility_scaling: VolatilityScaling::Linear,
         }
     }
 }
 
+impl MakerOnlyMeanReversionStrategy {
+    pub fn new(instrument: &Instrument, params: &MeanReversionParams) -> Self {
+        let max_exposure_in_quote = instrument.trading_rules().max_exposure_in_quote;
+        Self {
+            ctx: InstrumentContext::new(instrument),
+            max_exposure_in_quote,
+            entry_threshold_ticks: params.entry_threshold_ticks,
+            improve_if_possible: params.improve_if_possible,
+            trend_filter_ticks: params.trend_filter_ticks,
+            counter_trend_multiplier: params.counter_trend_multiplier,
+            inventory_penalty: params.inventory_penalty,
+            volatility_sizing_enabled: params.volatility_sizing_enabled,
+            volatility_reference_ticks: params.volatility_reference_ticks,
+            volatility_scaling: params.volatility_scaling,
+        }
+    }
+
+    pub fn for_instrument(instrument: &Instrument) -> Self {
+        Self::new(instrument, &MeanReversionParams::default())
+    }
+}
+
 impl WithContext for MakerOnlyMeanReversionStrategy {
     fn ctx(&self) -> &InstrumentContext {
         &self.ctx
     }
+
+    fn ctx_mut(&mut self) -> &mut InstrumentContext {
+        &mut self.ctx
+    }
 }
 
 impl Strategy for MakerOnlyMeanReversionStrategy {
+    fn update_params(&mut self, config: &StrategyConfig) {
+        let params = &config.mean_reversion;
+        self.max_exposure_in_quote = self.ctx().rules().max_exposure_in_quote;
+        self.improve_if_possible = params.improve_if_possible;
+        self.entry_threshold_ticks = params.entry_threshold_ticks;
+        self.trend_filter_ticks = params.trend_filter_ticks;
+        self.counter_trend_multiplier = params.counter_trend_multiplier;
+        self.inventory_penalty = params.inventory_penalty;
+        self.volatility_sizing_enabled = params.volatility_sizing_enabled;
+        self.volatility_reference_ticks = params.volatility_reference_ticks;
+        self.volatility_scaling = params.volatility_scaling;
+    }
+
     fn compute_target(
         &self,
         market_state: &MarketState,
@@ -71,14 +136,14 @@ impl Strategy for MakerOnlyMeanReversionStrategy {
     ) -> Result<QuoteTarget, NoQuoteReason> {
         let (best_bid, best_ask) =
             Self::best_bid_ask(market_state).ok_or(NoQuoteReason::MissingTopOfBook)?;
+        if !signal_state.is_warm() {
+            return Err(NoQuoteReason::SignalsWarmingUp);
+        }
 
         let rules = self.ctx().rules();
         let tick = self.ctx().tick();
 
-        let mid = market_state
-            .mid_price()
-            .map(|p| p.as_f64())
-            .ok_or(NoQuoteReason::MissingMid)?;
+        let mid = Self::anchor(market_state, signal_state).ok_or(NoQuoteReason::MissingMid)?;
 
         let ema = signal_state.ema_mid().ok_or(NoQuoteReason::MissingEma)?;
         let ema_slow = signal_state.ema_mid_slow().unwrap_or(ema);
@@ -88,18 +153,28 @@ impl Strategy for MakerOnlyMeanReversionStrategy {
         let trend = mid - ema_slow;
         let trend_deadband = self.trend_filter_ticks * tick;
 
-        let quantity = self
-            .size_from_notional(ema)
-            .ok_or(NoQuoteReason::InvalidQuantity)?;
-        if quantity <= 0.0 {
+        let quantity = if self.volatility_sizing_enabled {
+            self.size_from_notional_scaled(
+                ema,
+                signal_state.volatility_mid(),
+                self.volatility_reference_ticks * tick,
+                self.volatility_scaling,
+            )
+        } else {
+            self.size_from_notional(ema)
+        }
+        .ok_or(NoQuoteReason::InvalidQuantity)?;
+        if quantity.is_zero() {
             return Err(NoQuoteReason::InvalidQuantity);
         }
 
         let spread = best_ask - best_bid;
         let can_improve = self.improve_if_possible && spread >= 2.0 * tick;
         let exposure_quote = inventory.base * mid;
-        let exposure_norm =
-            (exposure_quote / self.max_exposure_in_quote.max(1e-12)).clamp(-1.0, 1.0);
+        let max_exposure = self
+            .ctx()
+            .max_exposure_in_quote(self.max_exposure_in_quote)?;
+        let exposure_norm = (exposure_quote / max_exposure.max(1e-12)).clamp(-1.0, 1.0);
 
         if deviation > 0.0 {
             let is_counter_trend = trend > trend_deadband;
@@ -134,13 +209,13 @@ impl Strategy for MakerOnlyMeanReversionStrategy {
                 return Err(NoQuoteReason::WouldCrossPostOnly);
             }
 
-            Ok(QuoteTarget {
-                bid: None,
-                ask: Some(Quote {
+            Ok(QuoteTarget::new(
+                None,
+                Some(Quote {
                     price: ask_price,
                     quantity,
                 }),
-            })
+            ))
         } else {
             let is_counter_trend = trend < -trend_deadband;
             let mut threshold_ticks = self.entry_threshold_ticks;
@@ -174,13 +249,13 @@ impl Strategy for MakerOnlyMeanReversionStrategy {
                 return Err(NoQuoteReason::WouldCrossPostOnly);
             }
 
-            Ok(QuoteTarget {
-                bid: Some(Quote {
+            Ok(QuoteTarget::new(
+                Some(Quote {
                     price: bid_price,
                     quantity,
                 }),
-                ask: None,
-            })
+                None,
+            ))
         }
     }
 }
@@ -1,4 +1,7 @@
+pub mod avellaneda_stoikov;
+pub mod inventory_target;
+pub mod layered_mm;
 pub mod mean_reversion;
 pub mod regime_switch;
 pub mod simple_mm;
-pub mod trend_following;
\ No newline at end of file
+pub mod trend_following;
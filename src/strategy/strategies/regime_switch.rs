@@ -1,13 +1,18 @@
 use std::cell::Cell;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     market::market_state::MarketState,
     signals::signal_state::SignalState,
     strategy::{
+        config::StrategyConfig,
         instrument_context::{InstrumentContext, WithContext},
         strategy::Strategy,
+        strategy_helpers::StrategyHelpers,
     },
     types::{
+        fx::ExposureCurrency,
         instrument::Instrument,
         inventory::Inventory,
         quote_target::{NoQuoteReason, QuoteTarget},
@@ -15,8 +20,8 @@ use crate::{
 };
 
 use super::{
-    mean_reversion::MakerOnlyMeanReversionStrategy,
-    trend_following::MakerOnlyTrendFollowingStrategy,
+    mean_reversion::{MakerOnlyMeanReversionStrategy, MeanReversionParams},
+    trend_following::{MakerOnlyTrendFollowingStrategy, TrendFollowingParams},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,11 +33,10 @@ enum Regime {
 /// Regime switcher:
 /// - Use slow EMA trend strength to choose between
 ///   mean reversion (weak trend) and trend following (strong trend)
-#[derive(Debug, Clone)]
 pub struct RegimeSwitchStrategy {
     ctx: InstrumentContext,
-    mean_reversion: MakerOnlyMeanReversionStrategy,
-    trend_following: MakerOnlyTrendFollowingStrategy,
+    mean_reversion: Box<dyn Strategy>,
+    trend_following: Box<dyn Strategy>,
     current_regime: Cell<Regime>,
     ticks_in_regime: Cell<u64>,
     /// Minimum ticks to stay in a regime before switching again.
@@ -47,14 +51,20 @@ pub struct RegimeSwitchStrategy {
     pub trend_strength_multiplier: f64,
 }
 
-impl RegimeSwitchStrategy {
-    pub fn for_instrument(instrument: &Instrument) -> Self {
+/// Tunable parameters for the switch itself; the legs use their own strategy params.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegimeSwitchParams {
+    pub min_regime_ticks: u64,
+    pub trend_enter_threshold_ticks: f64,
+    pub trend_exit_threshold_ticks: f64,
+    pub trend_slope_threshold_ticks: f64,
+    pub trend_strength_multiplier: f64,
+}
+
+impl Default for RegimeSwitchParams {
+    fn default() -> Self {
         Self {
-            ctx: InstrumentContext::new(instrument),
-            mean_reversion: MakerOnlyMeanReversionStrategy::for_instrument(instrument),
-            trend_following: MakerOnlyTrendFollowingStrategy::for_instrument(instrument),
-            current_regime: Cell::new(Regime::MeanReversion),
-            ticks_in_regime: Cell::new(0),
             min_regime_ticks: 20,
             trend_enter_threshold_ticks: 6.0,
             trend_exit_threshold_ticks: 4.0,
@@ -64,23 +74,97 @@ impl RegimeSwitchStrategy {
     }
 }
 
+impl RegimeSwitchStrategy {
+    pub fn new(
+        instrument: &Instrument,
+        params: &RegimeSwitchParams,
+        mean_reversion: &MeanReversionParams,
+        trend_following: &TrendFollowingParams,
+    ) -> Self {
+        Self::with_legs(
+            instrument,
+            params,
+            Box::new(MakerOnlyMeanReversionStrategy::new(
+                instrument,
+                mean_reversion,
+            )),
+            Box::new(MakerOnlyTrendFollowingStrategy::new(
+                instrument,
+                trend_following,
+            )),
+        )
+    }
+
+    /// Switches between any two strategies: `mean_reversion` while the trend is weak,
+    /// `trend_following` once it is strong.
+    pub fn with_legs(
+        instrument: &Instrument,
+        params: &RegimeSwitchParams,
+        mean_reversion: Box<dyn Strategy>,
+        trend_following: Box<dyn Strategy>,
+    ) -> Self {
+        Self {
+            ctx: InstrumentContext::new(instrument),
+            mean_reversion,
+            trend_following,
+            current_regime: Cell::new(Regime::MeanReversion),
+            ticks_in_regime: Cell::new(0),
+            min_regime_ticks: params.min_regime_ticks,
+            trend_enter_threshold_ticks: params.trend_enter_threshold_ticks,
+            trend_exit_threshold_ticks: params.trend_exit_threshold_ticks,
+            trend_slope_threshold_ticks: params.trend_slope_threshold_ticks,
+            trend_strength_multiplier: params.trend_strength_multiplier,
+        }
+    }
+
+    pub fn for_instrument(instrument: &Instrument) -> Self {
+        Self::new(
+            instrument,
+            &RegimeSwitchParams::default(),
+            &MeanReversionParams::default(),
+            &TrendFollowingParams::default(),
+        )
+    }
+}
+
 impl WithContext for RegimeSwitchStrategy {
     fn ctx(&self) -> &InstrumentContext {
         &self.ctx
     }
+
+    fn ctx_mut(&mut self) -> &mut InstrumentContext {
+        &mut self.ctx
+    }
 }
 
 impl Strategy for RegimeSwitchStrategy {
+    fn update_params(&mut self, config: &StrategyConfig) {
+        self.mean_reversion.update_params(config);
+        self.trend_following.update_params(config);
+
+        // The current regime and its age carry over.
+        let params = &config.regime_switch;
+        self.min_regime_ticks = params.min_regime_ticks;
+        self.trend_enter_threshold_ticks = params.trend_enter_threshold_ticks;
+        self.trend_exit_threshold_ticks = params.trend_exit_threshold_ticks;
+        self.trend_slope_threshold_ticks = params.trend_slope_threshold_ticks;
+        self.trend_strength_multiplier = params.trend_strength_multiplier;
+    }
+
+    fn set_exposure_currency(&mut self, exposure_currency: &ExposureCurrency) {
+        self.ctx.exposure_currency = exposure_currency.clone();
+        self.mean_reversion.set_exposure_currency(exposure_currency);
+        self.trend_following
+            .set_exposure_currency(exposure_currency);
+    }
+
     fn compute_target(
         &self,
         market_state: &MarketState,
         signal_state: &SignalState,
         inventory: Inventory,
     ) -> Result<QuoteTarget, NoQuoteReason> {
-        let mid = market_state
-            .mid_price()
-            .map(|p| p.as_f64())
-            .ok_or(NoQuoteReason::MissingMid)?;
+        let mid = Self::anchor(market_state, signal_state).ok_or(NoQuoteReason::MissingMid)?;
 
         let ema_fast = signal_state.ema_mid().ok_or(NoQuoteReason::MissingEma)?;
         let ema_slow = signal_state
@@ -107,14 +191,13 @@ impl Strategy for RegimeSwitchStrategy {
         let wants_mr = trend_abs <= exit_threshold || slope_abs < slope_threshold_abs;
 
         let mut next_regime = current_regime;
+        let can_switch = ticks_in_regime >= self.min_regime_ticks;
         if current_regime == Regime::MeanReversion && wants_trend {
-            if ticks_in_regime >= self.min_regime_ticks {
+            if can_switch {
                 next_regime = Regime::TrendFollowing;
             }
-        } else if current_regime == Regime::TrendFollowing && wants_mr {
-            if ticks_in_regime >= self.min_regime_ticks {
-                next_regime = Regime::MeanReversion;
-            }
+        } else if current_regime == Regime::TrendFollowing && wants_mr && can_switch {
+            next_regime = Regime::MeanReversion;
         }
 
         if next_regime != current_regime {
@@ -1,10 +1,13 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     market::market_state::MarketState,
     signals::signal_state::SignalState,
     strategy::{
+        config::StrategyConfig,
         instrument_context::{InstrumentContext, WithContext},
         strategy::Strategy,
-        strategy_helpers::StrategyHelpers,
+        strategy_helpers::{StrategyHelpers, VolatilityScaling},
     },
     types::{
         instrument::Instrument,
@@ -19,23 +22,72 @@ pub struct SimpleMarketMakerStrategy {
     ctx: InstrumentContext,
     pub max_exposure_in_quote: f64,
     pub max_skew_bps: f64,
+    /// Quote smaller while mid volatility is above `volatility_reference_ticks`.
+    pub volatility_sizing_enabled: bool,
+    pub volatility_reference_ticks: f64,
+    pub volatility_scaling: VolatilityScaling,
+    /// Bps the fair price is shaded towards the heavier side of the book per unit of
+    /// imbalance; 0 leaves it unshaded.
+    pub imbalance_skew_bps: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimpleMarketMakerParams {
+    pub max_skew_bps: f64,
+    pub volatility_sizing_enabled: bool,
+    pub volatility_reference_ticks: f64,
+    pub volatility_scaling: VolatilityScaling,
+    /// Bps to shade the fair price per unit of book imbalance, on top of the inventory
+    /// skew. The two together never exceed `max_skew_bps`.
+    pub imbalance_skew_bps: f64,
+}
+
+impl Default for SimpleMarketMakerParams {
+    fn default() -> Self {
+        Self {
+            max_skew_bps: 10.0,
+            volatility_sizing_enabled: false,
+            volatility_reference_ticks: 2.0,
+            volatility_scaling: VolatilityScaling::Linear,
+            imbalance_skew_bps: 0.0,
+        }
+    }
 }
 
 impl SimpleMarketMakerStrategy {
     pub fn new(instrument: &Instrument, max_exposure_in_quote: f64, max_skew_bps: f64) -> Self {
+        let defaults = SimpleMarketMakerParams::default();
         Self {
             ctx: InstrumentContext::new(instrument),
             max_exposure_in_quote,
             max_skew_bps,
+            volatility_sizing_enabled: defaults.volatility_sizing_enabled,
+            volatility_reference_ticks: defaults.volatility_reference_ticks,
+            volatility_scaling: defaults.volatility_scaling,
+            imbalance_skew_bps: defaults.imbalance_skew_bps,
         }
     }
 
-    pub fn for_instrument(instrument: &Instrument) -> Self {
-        Self::new(
+    pub fn from_params(instrument: &Instrument, params: &SimpleMarketMakerParams) -> Self {
+        let mut strategy = Self::new(
             instrument,
             instrument.trading_rules().max_exposure_in_quote,
-            10.0,
-        )
+            params.max_skew_bps,
+        );
+        strategy.set_params(params);
+        strategy
+    }
+
+    fn set_params(&mut self, params: &SimpleMarketMakerParams) {
+        self.volatility_sizing_enabled = params.volatility_sizing_enabled;
+        self.volatility_reference_ticks = params.volatility_reference_ticks;
+        self.volatility_scaling = params.volatility_scaling;
+        self.imbalance_skew_bps = params.imbalance_skew_bps;
+    }
+
+    pub fn for_instrument(instrument: &Instrument) -> Self {
+        Self::from_params(instrument, &SimpleMarketMakerParams::default())
     }
 }
 
@@ -43,9 +95,19 @@ impl WithContext for SimpleMarketMakerStrategy {
     fn ctx(&self) -> &InstrumentContext {
         &self.ctx
     }
+
+    fn ctx_mut(&mut self) -> &mut InstrumentContext {
+        &mut self.ctx
+    }
 }
 
 impl Strategy for SimpleMarketMakerStrategy {
+    fn update_params(&mut self, config: &StrategyConfig) {
+        self.max_exposure_in_quote = self.ctx().rules().max_exposure_in_quote;
+        self.max_skew_bps = config.simple_mm.max_skew_bps;
+        self.set_params(&config.simple_mm);
+    }
+
     fn compute_target(
         &self,
         market_state: &MarketState,
@@ -64,27 +126,41 @@ impl Strategy for SimpleMarketMakerStrategy {
 
         // ----- inventory-aware fair price -----
         let exposure_quote = inventory.base * fair;
+        let max_exposure = self
+            .ctx()
+            .max_exposure_in_quote(self.max_exposure_in_quote)?;
 
         // Normalize exposure into [-1, 1] relative to max exposure cap.
-        let denom = self.max_exposure_in_quote.max(1e-12);
+        let denom = max_exposure.max(1e-12);
         let norm = (exposure_quote / denom).clamp(-1.0, 1.0);
 
-        // Positive exposure => skew fair downward to encourage sells.
-        let skew_bps = norm * self.max_skew_bps;
+        // Positive exposure => skew fair downward to encourage sells; a heavier bid =>
+        // skew it upward, ahead of the move the book leans towards.
+        let imbalance = signal_state.imbalance().unwrap_or(0.0);
+        let skew_bps = (norm * self.max_skew_bps - imbalance * self.imbalance_skew_bps)
+            .clamp(-self.max_skew_bps, self.max_skew_bps);
         let skew = fair * (skew_bps / 10_000.0);
         let skewed_fair = fair - skew;
 
         // ----- size (quote currency notional cap) -----
-        let order_quantity = self
-            .size_from_notional(skewed_fair)
-            .ok_or(NoQuoteReason::InvalidQuantity)?;
-        if order_quantity <= 0.0 {
+        let order_quantity = if self.volatility_sizing_enabled {
+            self.size_from_notional_scaled(
+                skewed_fair,
+                signal_state.volatility_mid(),
+                self.volatility_reference_ticks * tick,
+                self.volatility_scaling,
+            )
+        } else {
+            self.size_from_notional(skewed_fair)
+        }
+        .ok_or(NoQuoteReason::InvalidQuantity)?;
+        if order_quantity.is_zero() {
             return Err(NoQuoteReason::InvalidQuantity);
         }
 
         // ----- one-sided quoting if exposure is too large -----
-        let too_long = exposure_quote > self.max_exposure_in_quote;
-        let too_short = exposure_quote < -self.max_exposure_in_quote;
+        let too_long = exposure_quote > max_exposure;
+        let too_short = exposure_quote < -max_exposure;
 
         // If you prefer hard-stop reasons (instead of just suppressing one side),
         // you can return these immediately:
@@ -157,6 +233,6 @@ impl Strategy for SimpleMarketMakerStrategy {
             return Err(NoQuoteReason::BothSidesSuppressedByExposure);
         }
 
-        Ok(QuoteTarget { bid, ask })
+        Ok(QuoteTarget::new(bid, ask))
     }
 }
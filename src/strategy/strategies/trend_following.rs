@@ -1,7 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     market::market_state::MarketState,
     signals::signal_state::SignalState,
     strategy::{
+        config::StrategyConfig,
         instrument_context::{InstrumentContext, WithContext},
         strategy::Strategy,
         strategy_helpers::StrategyHelpers,
@@ -44,29 +47,73 @@ pub struct MakerOnlyTrendFollowingStrategy {
     pub pullback_tolerance_ticks: f64,
 }
 
-impl MakerOnlyTrendFollowingStrategy {
-    pub fn for_instrument(instrument: &Instrument) -> Self {
-        let max_exposure_in_quote = instrument.trading_rules().max_exposure_in_quote;
+/// Tunable parameters; see the matching fields on [`MakerOnlyTrendFollowingStrategy`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrendFollowingParams {
+    pub improve_if_possible: bool,
+    pub entry_threshold_ticks: f64,
+    pub volatility_entry_multiplier: f64,
+    pub slope_threshold_ticks: f64,
+    pub require_pullback: bool,
+    pub pullback_tolerance_ticks: f64,
+}
+
+impl Default for TrendFollowingParams {
+    fn default() -> Self {
         Self {
-            ctx: InstrumentContext::new(instrument),
-            max_exposure_in_quote,
+            improve_if_possible: true,
             entry_threshold_ticks: 3.0,
             volatility_entry_multiplier: 1.0,
             slope_threshold_ticks: 2.0,
-            improve_if_possible: true,
             require_pullback: true,
             pullback_tolerance_ticks: 2.0,
         }
     }
 }
 
+impl MakerOnlyTrendFollowingStrategy {
+    pub fn new(instrument: &Instrument, params: &TrendFollowingParams) -> Self {
+        let max_exposure_in_quote = instrument.trading_rules().max_exposure_in_quote;
+        Self {
+            ctx: InstrumentContext::new(instrument),
+            max_exposure_in_quote,
+            entry_threshold_ticks: params.entry_threshold_ticks,
+            volatility_entry_multiplier: params.volatility_entry_multiplier,
+            slope_threshold_ticks: params.slope_threshold_ticks,
+            improve_if_possible: params.improve_if_possible,
+            require_pullback: params.require_pullback,
+            pullback_tolerance_ticks: params.pullback_tolerance_ticks,
+        }
+    }
+
+    pub fn for_instrument(instrument: &Instrument) -> Self {
+        Self::new(instrument, &TrendFollowingParams::default())
+    }
+}
+
 impl WithContext for MakerOnlyTrendFollowingStrategy {
     fn ctx(&self) -> &InstrumentContext {
         &self.ctx
     }
+
+    fn ctx_mut(&mut self) -> &mut InstrumentContext {
+        &mut self.ctx
+    }
 }
 
 impl Strategy for MakerOnlyTrendFollowingStrategy {
+    fn update_params(&mut self, config: &StrategyConfig) {
+        let params = &config.trend_following;
+        self.max_exposure_in_quote = self.ctx().rules().max_exposure_in_quote;
+        self.improve_if_possible = params.improve_if_possible;
+        self.entry_threshold_ticks = params.entry_threshold_ticks;
+        self.volatility_entry_multiplier = params.volatility_entry_multiplier;
+        self.slope_threshold_ticks = params.slope_threshold_ticks;
+        self.require_pullback = params.require_pullback;
+        self.pullback_tolerance_ticks = params.pullback_tolerance_ticks;
+    }
+
     fn compute_target(
         &self,
         market_state: &MarketState,
@@ -75,24 +122,22 @@ impl Strategy for MakerOnlyTrendFollowingStrategy {
     ) -> Result<QuoteTarget, NoQuoteReason> {
         let (best_bid, best_ask) =
             Self::best_bid_ask(market_state).ok_or(NoQuoteReason::MissingTopOfBook)?;
+        if !signal_state.is_warm() {
+            return Err(NoQuoteReason::SignalsWarmingUp);
+        }
 
         let rules = self.ctx().rules();
         let tick = self.ctx().tick();
 
-        let mid = market_state
-            .mid_price()
-            .map(|p| p.as_f64())
-            .ok_or(NoQuoteReason::MissingMid)?;
+        let mid = Self::anchor(market_state, signal_state).ok_or(NoQuoteReason::MissingMid)?;
 
         let ema_fast = signal_state.ema_mid().ok_or(NoQuoteReason::MissingEma)?;
         let ema_slow = signal_state.ema_mid_slow().unwrap_or(ema_fast);
 
         let trend = mid - ema_slow;
         let trend_abs = trend.abs();
-        let vol_threshold = signal_state
-            .volatility_mid()
-            .unwrap_or(0.0)
-            * self.volatility_entry_multiplier;
+        let vol_threshold =
+            signal_state.volatility_mid().unwrap_or(0.0) * self.volatility_entry_multiplier;
         let threshold_abs = self.entry_threshold_ticks * tick + vol_threshold;
         if trend_abs < threshold_abs {
             return Err(NoQuoteReason::BelowEntryThreshold {
@@ -113,7 +158,7 @@ impl Strategy for MakerOnlyTrendFollowingStrategy {
         let quantity = self
             .size_from_notional(ema_fast)
             .ok_or(NoQuoteReason::InvalidQuantity)?;
-        if quantity <= 0.0 {
+        if quantity.is_zero() {
             return Err(NoQuoteReason::InvalidQuantity);
         }
 
@@ -142,13 +187,13 @@ impl Strategy for MakerOnlyTrendFollowingStrategy {
                 return Err(NoQuoteReason::WouldCrossPostOnly);
             }
 
-            Ok(QuoteTarget {
-                bid: Some(Quote {
+            Ok(QuoteTarget::new(
+                Some(Quote {
                     price: bid_price,
                     quantity,
                 }),
-                ask: None,
-            })
+                None,
+            ))
         } else {
             // Downtrend → SELL on pullback
             if self.require_pullback && mid < ema_fast - pullback_tolerance {
@@ -170,13 +215,13 @@ impl Strategy for MakerOnlyTrendFollowingStrategy {
                 return Err(NoQuoteReason::WouldCrossPostOnly);
             }
 
-            Ok(QuoteTarget {
-                bid: None,
-                ask: Some(Quote {
+            Ok(QuoteTarget::new(
+                None,
+                Some(Quote {
                     price: ask_price,
                     quantity,
                 }),
-            })
+            ))
         }
     }
 }
@@ -1,18 +1,117 @@
 use crate::{
     market::market_state::MarketState,
     signals::signal_state::SignalState,
-    strategy::instrument_context::WithContext,
+    strategy::{
+        config::StrategyConfig,
+        instrument_context::{InstrumentContext, WithContext},
+    },
     types::{
+        fx::ExposureCurrency,
         inventory::Inventory,
         quote_target::{NoQuoteReason, QuoteTarget},
     },
 };
 
-pub trait Strategy: WithContext {
+/// Decides, each cycle, the quotes an instrument should have resting.
+///
+/// ```
+/// use accumulator::market::market_state::MarketState;
+/// use accumulator::signals::signal_state::SignalState;
+/// use accumulator::strategy::config::StrategyConfig;
+/// use accumulator::strategy::instrument_context::{InstrumentContext, WithContext};
+/// use accumulator::strategy::strategy::Strategy;
+/// use accumulator::strategy::strategy_helpers::StrategyHelpers;
+/// use accumulator::types::inventory::Inventory;
+/// use accumulator::types::price::Price;
+/// use accumulator::types::quantity::Quantity;
+/// use accumulator::types::quote::Quote;
+/// use accumulator::types::quote_target::{NoQuoteReason, QuoteTarget};
+///
+/// /// Joins the touch on both sides with the smallest order the rules allow.
+/// struct JoinTheTouch {
+///     ctx: InstrumentContext,
+/// }
+///
+/// impl WithContext for JoinTheTouch {
+///     fn ctx(&self) -> &InstrumentContext {
+///         &self.ctx
+///     }
+///
+///     fn ctx_mut(&mut self) -> &mut InstrumentContext {
+///         &mut self.ctx
+///     }
+/// }
+///
+/// impl Strategy for JoinTheTouch {
+///     fn compute_target(
+///         &self,
+///         market_state: &MarketState,
+///         _signal_state: &SignalState,
+///         _inventory: Inventory,
+///     ) -> Result<QuoteTarget, NoQuoteReason> {
+///         let (bid, ask) =
+///             Self::best_bid_ask(market_state).ok_or(NoQuoteReason::MissingTopOfBook)?;
+///         let size = Quantity::new(self.ctx.rules().min_order_quantity)
+///             .map_err(|_| NoQuoteReason::InvalidQuantity)?;
+///         if size.is_zero() {
+///             return Err(NoQuoteReason::InvalidQuantity);
+///         }
+///
+///         Ok(QuoteTarget {
+///             bid: Some(Quote { price: Price::new(bid), quantity: size }),
+///             ask: Some(Quote { price: Price::new(ask), quantity: size }),
+///             ..QuoteTarget::default()
+///         })
+///     }
+///
+///     fn update_params(&mut self, _config: &StrategyConfig) {}
+/// }
+/// ```
+pub trait Strategy: WithContext + Send {
     fn compute_target(
         &self,
         market_state: &MarketState,
         signal_state: &SignalState,
         inventory: Inventory,
     ) -> Result<QuoteTarget, NoQuoteReason>;
+
+    /// Takes new parameters on a config reload, keeping any state built up so far.
+    /// Limits taken from the trading rules are read again too, as they may have been
+    /// reloaded with them.
+    fn update_params(&mut self, config: &StrategyConfig);
+
+    /// Compares exposure with its limits in `exposure_currency` from now on.
+    fn set_exposure_currency(&mut self, exposure_currency: &ExposureCurrency) {
+        self.ctx_mut().exposure_currency = exposure_currency.clone();
+    }
+}
+
+/// Lets composite strategies hold their legs as trait objects.
+impl WithContext for Box<dyn Strategy> {
+    fn ctx(&self) -> &InstrumentContext {
+        (**self).ctx()
+    }
+
+    fn ctx_mut(&mut self) -> &mut InstrumentContext {
+        (**self).ctx_mut()
+    }
+}
+
+impl Strategy for Box<dyn Strategy> {
+    fn compute_target(
+        &self,
+        market_state: &MarketState,
+        signal_state: &SignalState,
+        inventory: Inventory,
+    ) -> Result<QuoteTarget, NoQuoteReason> {
+        (**self).compute_target(market_state, signal_state, inventory)
+    }
+
+    fn update_params(&mut self, config: &StrategyConfig) {
+        (**self).update_params(config);
+    }
+
+    fn set_exposure_currency(&mut self, exposure_currency: &ExposureCurrency) {
+        (**self).set_exposure_currency(exposure_currency);
+    }
 }
@@ -1,8 +1,34 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     market::market_state::MarketState, signals::signal_state::SignalState,
-    strategy::instrument_context::WithContext,
+    strategy::instrument_context::WithContext, types::quantity::Quantity,
 };
 
+/// How quote size shrinks as volatility rises past its reference level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VolatilityScaling {
+    /// Shrinks by the excess over the reference: gone at twice the reference.
+    #[default]
+    Linear,
+    /// Divides by volatility over the reference: half size at twice the reference.
+    Inverse,
+}
+
+impl VolatilityScaling {
+    /// Share of the full size to quote at `ratio`, volatility over its reference.
+    pub fn factor(self, ratio: f64) -> f64 {
+        if ratio <= 1.0 {
+            return 1.0;
+        }
+        match self {
+            VolatilityScaling::Linear => (2.0 - ratio).max(0.0),
+            VolatilityScaling::Inverse => 1.0 / ratio,
+        }
+    }
+}
+
 pub trait StrategyHelpers: WithContext {
     fn best_bid_ask(market_state: &MarketState) -> Option<(f64, f64)> {
         Some((
@@ -11,16 +37,52 @@ pub trait StrategyHelpers: WithContext {
         ))
     }
 
+    /// The mid or microprice at the touch, whichever the signals follow.
+    fn anchor(market_state: &MarketState, signal_state: &SignalState) -> Option<f64> {
+        market_state
+            .anchor(signal_state.mid_kind())
+            .map(|p| p.as_f64())
+    }
+
+    /// The reference venue's price while it is fresh, else the local smoothed or raw
+    /// anchor.
     fn fair_price(market_state: &MarketState, signal_state: &SignalState) -> Option<f64> {
         signal_state
-            .ema_mid()
-            .or_else(|| market_state.mid_price().map(|p| p.as_f64()))
+            .reference_fair()
+            .or_else(|| signal_state.ema_mid())
+            .or_else(|| Self::anchor(market_state, signal_state))
     }
 
-    fn size_from_notional(&self, price: f64) -> Option<f64> {
+    fn size_from_notional(&self, price: f64) -> Option<Quantity> {
         let rules = self.ctx().rules();
         let q = rules.quantity_from_notional(self.ctx().max_order_notional(), price);
-        (q > 0.0).then_some(q)
+        (!q.is_zero()).then_some(q)
+    }
+
+    /// [`size_from_notional`](Self::size_from_notional), shrunk by `scaling` while
+    /// `volatility` is above `reference_vol`, both in price units. Never below the
+    /// smallest order the venue takes, one step or its minimum size. Unscaled until the
+    /// volatility is known.
+    fn size_from_notional_scaled(
+        &self,
+        price: f64,
+        volatility: Option<f64>,
+        reference_vol: f64,
+        scaling: VolatilityScaling,
+    ) -> Option<Quantity> {
+        let full = self.size_from_notional(price)?;
+        let Some(volatility) = volatility.filter(|_| reference_vol > 0.0) else {
+            return Some(full);
+        };
+
+        let rules = self.ctx().rules();
+        let floor = Quantity::new(rules.quantity_step.max(rules.min_order_quantity))
+            .map_or(full, |smallest| full.min(smallest));
+        let scaled = Quantity::new(full.as_f64() * scaling.factor(volatility / reference_vol))
+            .map_or(Quantity::ZERO, |scaled| {
+                rules.round_quantity_to_step(scaled)
+            });
+        Some(scaled.max(floor))
     }
 
     fn clamp_bid(&self, bid: f64, best_ask: f64) -> f64 {
@@ -0,0 +1,108 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::execution::order_report::OrderReport;
+
+const MAX_PENDING_ACKS: usize = 1_024;
+const PENDING_ACK_TTL: Duration = Duration::from_secs(60);
+
+/// Pairs each placement with the venue's accept or reject of it by order id, and keeps
+/// the time between for the last `window` placements answered.
+#[derive(Debug, Clone)]
+pub struct AckLatencyTracker {
+    window: usize,
+    placed_at: HashMap<String, Instant>,
+    /// When each ack arrived and how long it took, oldest first.
+    samples: VecDeque<(Instant, Duration)>,
+}
+
+impl AckLatencyTracker {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            placed_at: HashMap::new(),
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Keeps the last `window` acks from now on, dropping the oldest beyond it.
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(1);
+        while self.samples.len() > self.window {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Takes `report`, seen at `now`, and returns the latency it closed, if any.
+    pub fn on_report(&mut self, report: &OrderReport, now: Instant) -> Option<Duration> {
+        match report {
+            OrderReport::Placed { order_id, .. } => {
+                if self.placed_at.len() >= MAX_PENDING_ACKS {
+                    self.placed_at.retain(|_, placed| {
+                        now.saturating_duration_since(*placed) < PENDING_ACK_TTL
+                    });
+                }
+                self.placed_at.insert(order_id.clone(), now);
+                None
+            }
+            OrderReport::Accepted { order_id, .. } | OrderReport::Rejected { order_id, .. } => {
+                let placed = self.placed_at.remove(order_id)?;
+                let elapsed = now.saturating_duration_since(placed);
+                if self.samples.len() == self.window {
+                    self.samples.pop_front();
+                }
+                self.samples.push_back((now, elapsed));
+                Some(elapsed)
+            }
+            _ => None,
+        }
+    }
+
+    /// Drops acks that arrived before `cutoff`, so an old slow spell stops counting.
+    pub fn forget_before(&mut self, cutoff: Instant) {
+        while self.samples.front().is_some_and(|(at, _)| *at < cutoff) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Percentiles over the acks kept, or `None` before any.
+    pub fn summary(&self) -> Option<AckLatency> {
+        let mut sorted: Vec<Duration> = self.samples.iter().map(|(_, elapsed)| *elapsed).collect();
+        sorted.sort_unstable();
+        let max = *sorted.last()?;
+        let rank = |q: f64| {
+            let index = (q * sorted.len() as f64).ceil().max(1.0) as usize - 1;
+            sorted[index.min(sorted.len() - 1)]
+        };
+
+        Some(AckLatency {
+            p50: rank(0.50),
+            p95: rank(0.95),
+            max,
+            samples: sorted.len(),
+        })
+    }
+}
+
+/// Placement acknowledgement latency over a tracker's window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AckLatency {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+    pub samples: usize,
+}
+
+impl fmt::Display for AckLatency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}ms n={}",
+            self.p50.as_millis(),
+            self.p95.as_millis(),
+            self.max.as_millis(),
+            self.samples
+        )
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Ids for engine cycles, one per market event handled. Shared by every instrument engine
+/// of a process, so an id names exactly one cycle in its logs.
+#[derive(Debug, Clone, Default)]
+pub struct CycleIds(Arc<AtomicU64>);
+
+impl CycleIds {
+    /// The next id, counting from 1.
+    pub fn next_id(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+tokio::task_local! {
+    static CURRENT: u64;
+}
+
+/// Runs `cycle` with `cycle_id` as the current cycle, for code far below the engine, like
+/// the venue capture, that tags what it records with it.
+pub async fn scope<F: Future>(cycle_id: u64, cycle: F) -> F::Output {
+    CURRENT.scope(cycle_id, cycle).await
+}
+
+/// The cycle the calling task is running, if any.
+pub fn current() -> Option<u64> {
+    CURRENT.try_with(|cycle_id| *cycle_id).ok()
+}
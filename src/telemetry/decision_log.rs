@@ -0,0 +1,179 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::error;
+
+use crate::execution::order_action::OrderAction;
+use crate::execution::types::OrderManagerSnapshot;
+use crate::market::market_state::MarketState;
+use crate::risk::decision::RiskDecision;
+use crate::scenario::strategies::StrategyKind;
+use crate::scheduling::types::ScheduleDecision;
+use crate::signals::signal_state::SignalState;
+use crate::types::instrument::Instrument;
+use crate::types::price::Price;
+use crate::types::quote::Quote;
+use crate::types::quote_target::{NoQuoteReason, QuoteTarget};
+
+/// What one engine cycle saw and decided, as written to the decision log. Stages the
+/// cycle did not reach are left empty.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionRecord {
+    pub at: DateTime<Utc>,
+    pub instrument: String,
+    pub cycle_id: u64,
+    /// Kind of market event that started the cycle.
+    pub event: &'static str,
+    pub best_bid: Option<Price>,
+    pub best_ask: Option<Price>,
+    pub ema_fast: Option<f64>,
+    pub ema_slow: Option<f64>,
+    pub volatility: Option<f64>,
+    pub schedule: ScheduleDecision,
+    /// What the engine believed it had working going into the cycle.
+    pub orders: OrderManagerSnapshot,
+    /// The strategy's target, after exits, or the reason it had none.
+    pub target: Option<QuoteTarget>,
+    pub no_quote: Option<NoQuoteReason>,
+    pub risk: Option<RiskDecision>,
+    pub actions: Vec<OrderAction>,
+    /// What each shadow strategy decided on the same inputs, next to `target`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shadows: Vec<ShadowDecision>,
+}
+
+impl DecisionRecord {
+    /// A record of a cycle decided `schedule` with the book and signals as they stand.
+    pub fn new(
+        at: DateTime<Utc>,
+        instrument: &Instrument,
+        cycle_id: u64,
+        event: &'static str,
+        market_state: &MarketState,
+        signal_state: &SignalState,
+        schedule: ScheduleDecision,
+    ) -> Self {
+        Self {
+            at,
+            instrument: instrument.to_string(),
+            cycle_id,
+            event,
+            best_bid: market_state.best_bid(),
+            best_ask: market_state.best_ask(),
+            ema_fast: signal_state.ema_mid(),
+            ema_slow: signal_state.ema_mid_slow(),
+            volatility: signal_state.volatility_mid(),
+            schedule,
+            orders: OrderManagerSnapshot::default(),
+            target: None,
+            no_quote: None,
+            risk: None,
+            actions: Vec::new(),
+            shadows: Vec::new(),
+        }
+    }
+
+    /// Records the orders the cycle started with.
+    pub fn with_orders(mut self, orders: OrderManagerSnapshot) -> Self {
+        self.orders = orders;
+        self
+    }
+}
+
+/// A shadow strategy's decision for a cycle and whether it matches the live one's.
+/// Sides agree when neither quotes, or both quote within half a tick of each other.
+#[derive(Debug, Clone, Serialize)]
+pub struct ShadowDecision {
+    pub strategy: StrategyKind,
+    pub target: Option<QuoteTarget>,
+    pub no_quote: Option<NoQuoteReason>,
+    /// Both quoted, or neither did.
+    pub agrees_to_quote: bool,
+    pub bid_agrees: bool,
+    pub ask_agrees: bool,
+}
+
+impl ShadowDecision {
+    /// `strategy`'s decision `shadow` set against the live decision `live`, on an
+    /// instrument quoted in steps of `price_tick`.
+    pub fn compare(
+        strategy: StrategyKind,
+        shadow: &Result<QuoteTarget, NoQuoteReason>,
+        live: &Result<QuoteTarget, NoQuoteReason>,
+        price_tick: f64,
+    ) -> Self {
+        let side_agrees = |shadow: Option<Quote>, live: Option<Quote>| match (shadow, live) {
+            (None, None) => true,
+            (Some(shadow), Some(live)) => {
+                (shadow.price.as_f64() - live.price.as_f64()).abs() < price_tick / 2.0
+            }
+            _ => false,
+        };
+        let quotes = |decision: &Result<QuoteTarget, NoQuoteReason>| {
+            decision
+                .as_ref()
+                .map_or((None, None), |target| (target.bid, target.ask))
+        };
+        let (shadow_bid, shadow_ask) = quotes(shadow);
+        let (live_bid, live_ask) = quotes(live);
+
+        Self {
+            strategy,
+            target: shadow.as_ref().ok().cloned(),
+            no_quote: shadow.as_ref().err().cloned(),
+            agrees_to_quote: shadow.is_ok() == live.is_ok(),
+            bid_agrees: side_agrees(shadow_bid, live_bid),
+            ask_agrees: side_agrees(shadow_ask, live_ask),
+        }
+    }
+}
+
+/// Append-only JSON lines file of [`DecisionRecord`]s, shared by every instrument of a
+/// session, for replaying the engine's reasoning offline.
+#[derive(Debug, Clone)]
+pub struct DecisionLog {
+    inner: Arc<Mutex<DecisionLogFile>>,
+}
+
+#[derive(Debug)]
+struct DecisionLogFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl DecisionLog {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create decision log dir {}", dir.display()))?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open decision log {}", path.display()))?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(DecisionLogFile {
+                path: path.to_path_buf(),
+                file,
+            })),
+        })
+    }
+
+    pub fn write(&self, record: &DecisionRecord) {
+        let mut log = self.inner.lock().unwrap();
+        let result = serde_json::to_string(record)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| Ok(writeln!(log.file, "{line}")?));
+        if let Err(error) = result {
+            error!(path = %log.path.display(), "failed to write decision record: {error:#}");
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::execution::order_action::OrderAction;
+use crate::execution::order_report::OrderReport;
+use crate::stats::session_stats::{StatsEvent, StatsHandle};
+use crate::telemetry::metrics;
+
+const MAX_PENDING_ACKS: usize = 1_024;
+const PENDING_ACK_TTL: Duration = Duration::from_secs(60);
+
+/// Pipeline stages a market event passes through on its way to an acknowledged order.
+/// Each is timed from the end of the one before; `Total` runs from receipt to the ack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Schedule,
+    Strategy,
+    Risk,
+    Execute,
+    Ack,
+    Total,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 6] = [
+        Stage::Schedule,
+        Stage::Strategy,
+        Stage::Risk,
+        Stage::Execute,
+        Stage::Ack,
+        Stage::Total,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::Schedule => "schedule",
+            Stage::Strategy => "strategy",
+            Stage::Risk => "risk",
+            Stage::Execute => "execute",
+            Stage::Ack => "ack",
+            Stage::Total => "total",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// One market event in flight: its correlation id, when it was pulled off the channel
+/// and when its last stage ended.
+#[derive(Debug, Clone, Copy)]
+pub struct EventTrace {
+    pub id: u64,
+    received: Instant,
+    last: Instant,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PendingAck {
+    trace_id: u64,
+    received: Instant,
+    executed: Instant,
+}
+
+/// Times one instrument's pipeline with wall-clock `Instant` math and publishes each
+/// stage to the metrics endpoint and the stats line.
+///
+/// A disabled tracker hands out no traces, so every call is a branch on `None`.
+#[derive(Debug)]
+pub struct LatencyTracker {
+    enabled: bool,
+    next_id: u64,
+    pending: HashMap<String, PendingAck>,
+    stats: StatsHandle,
+}
+
+impl LatencyTracker {
+    pub fn new(enabled: bool, stats: StatsHandle) -> Self {
+        Self {
+            enabled,
+            next_id: 0,
+            pending: HashMap::new(),
+            stats,
+        }
+    }
+
+    /// Starts the trace of an event just taken off the channel.
+    pub fn begin(&mut self) -> Option<EventTrace> {
+        if !self.enabled {
+            return None;
+        }
+
+        self.next_id += 1;
+        let now = Instant::now();
+
+        Some(EventTrace {
+            id: self.next_id,
+            received: now,
+            last: now,
+        })
+    }
+
+    /// Ends `stage` of `trace`.
+    pub fn stage(&self, trace: &mut Option<EventTrace>, stage: Stage) {
+        let Some(trace) = trace else {
+            return;
+        };
+
+        let now = Instant::now();
+        self.record(stage, now - trace.last);
+        trace.last = now;
+
+        if stage == Stage::Risk {
+            metrics::event_to_decision(now - trace.received);
+        }
+    }
+
+    /// Ends the execute stage and waits for the venue to acknowledge each placement.
+    pub fn executed(&mut self, trace: &mut Option<EventTrace>, actions: &[OrderAction]) {
+        self.stage(trace, Stage::Execute);
+
+        let Some(trace) = trace else {
+            return;
+        };
+
+        if self.pending.len() >= MAX_PENDING_ACKS {
+            self.pending
+                .retain(|_, pending| trace.last - pending.executed < PENDING_ACK_TTL);
+        }
+
+        for action in actions {
+            if let OrderAction::Place(order) = action {
+                self.pending.insert(
+                    order.order_id.clone(),
+                    PendingAck {
+                        trace_id: trace.id,
+                        received: trace.received,
+                        executed: trace.last,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Closes the loop on an accepted or rejected placement.
+    pub fn on_report(&mut self, report: &OrderReport) {
+        let (OrderReport::Accepted { order_id, .. } | OrderReport::Rejected { order_id, .. }) =
+            report
+        else {
+            return;
+        };
+        let Some(pending) = self.pending.remove(order_id) else {
+            return;
+        };
+
+        let now = Instant::now();
+        self.record(Stage::Ack, now - pending.executed);
+        self.record(Stage::Total, now - pending.received);
+
+        tracing::debug!(
+            trace_id = pending.trace_id,
+            order_id = %order_id,
+            total_us = (now - pending.received).as_micros() as u64,
+            "event acknowledged"
+        );
+    }
+
+    fn record(&self, stage: Stage, elapsed: Duration) {
+        metrics::stage_latency(stage, elapsed);
+        self.stats.record(StatsEvent::Latency { stage, elapsed });
+    }
+}
+
+/// Quarter-octave buckets from 1µs to ~16s: about 19% resolution, fixed size and
+/// allocation-free to record into.
+const BUCKETS: usize = 96;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LatencyHistogram {
+    counts: [u32; BUCKETS],
+    total: u32,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: [0; BUCKETS],
+            total: 0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, elapsed: Duration) {
+        let micros = elapsed.as_secs_f64() * 1e6;
+        let bucket = (micros.max(1.0).log2() * 4.0) as usize;
+
+        self.counts[bucket.min(BUCKETS - 1)] += 1;
+        self.total += 1;
+    }
+
+    /// Upper bound of the bucket holding quantile `q`, or `None` before any samples.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        if self.total == 0 {
+            return None;
+        }
+
+        let rank = (q * f64::from(self.total)).ceil().max(1.0) as u32;
+        let mut seen = 0;
+        let bucket = self
+            .counts
+            .iter()
+            .position(|count| {
+                seen += count;
+                seen >= rank
+            })
+            .unwrap_or(BUCKETS - 1);
+
+        Some(Duration::from_secs_f64(
+            2f64.powf((bucket + 1) as f64 / 4.0) / 1e6,
+        ))
+    }
+
+    pub fn percentiles(&self) -> Option<Percentiles> {
+        Some(Percentiles {
+            p50: self.quantile(0.50)?,
+            p95: self.quantile(0.95)?,
+            p99: self.quantile(0.99)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Percentiles {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl fmt::Display for Percentiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}us",
+            self.p50.as_micros(),
+            self.p95.as_micros(),
+            self.p99.as_micros()
+        )
+    }
+}
+
+/// One histogram per stage.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StageHistograms {
+    stages: [LatencyHistogram; Stage::ALL.len()],
+}
+
+impl StageHistograms {
+    pub fn record(&mut self, stage: Stage, elapsed: Duration) {
+        self.stages[stage.index()].record(elapsed);
+    }
+
+    /// `stage=p50/p95/p99us` for every stage with samples, e.g. for the stats line.
+    pub fn describe(&self) -> String {
+        Stage::ALL
+            .iter()
+            .filter_map(|stage| {
+                let percentiles = self.stages[stage.index()].percentiles()?;
+                Some(format!("{}={percentiles}", stage.label()))
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
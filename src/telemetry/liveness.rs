@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::telemetry::metrics::{self, Feed};
+
+/// Unix millis of the last message seen per feed; zero until the first one arrives.
+/// Process-wide, like the metrics recorder, so the alerter and the watchdog see every
+/// feed without it being threaded through the venues.
+static LAST_SEEN: [AtomicU64; 3] = [const { AtomicU64::new(0) }; 3];
+static RECONNECTS: [AtomicU64; 3] = [const { AtomicU64::new(0) }; 3];
+/// Unix millis the engine loop last completed an iteration; zero until it starts.
+static LOOP_COMPLETED: AtomicU64 = AtomicU64::new(0);
+
+fn index(feed: Feed) -> usize {
+    match feed {
+        Feed::Market => 0,
+        Feed::Executions => 1,
+        Feed::Inventory => 2,
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Records a message on `feed`, for liveness checks and the metrics gauge.
+pub(crate) fn feed_event(feed: Feed) {
+    LAST_SEEN[index(feed)].store(unix_millis(), Ordering::Relaxed);
+    metrics::feed_event(feed);
+}
+
+pub(crate) fn feed_reconnect(feed: Feed) {
+    RECONNECTS[index(feed)].fetch_add(1, Ordering::Relaxed);
+    metrics::feed_reconnect(feed);
+}
+
+/// Time since the last message on `feed`, or `None` if it has never delivered one.
+pub(crate) fn silent_for(feed: Feed) -> Option<Duration> {
+    since(&LAST_SEEN[index(feed)])
+}
+
+/// Records that the engine loop completed an iteration.
+pub(crate) fn loop_completed() {
+    LOOP_COMPLETED.store(unix_millis(), Ordering::Relaxed);
+}
+
+/// Time since the engine loop last completed an iteration, or `None` before it starts.
+pub(crate) fn loop_idle_for() -> Option<Duration> {
+    since(&LOOP_COMPLETED)
+}
+
+fn since(unix_millis_at: &AtomicU64) -> Option<Duration> {
+    let at = unix_millis_at.load(Ordering::Relaxed);
+    if at == 0 {
+        return None;
+    }
+
+    Some(Duration::from_millis(unix_millis().saturating_sub(at)))
+}
+
+/// Reconnect attempts on `feed` since the process started.
+pub(crate) fn reconnects(feed: Feed) -> u64 {
+    RECONNECTS[index(feed)].load(Ordering::Relaxed)
+}
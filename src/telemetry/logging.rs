@@ -0,0 +1,82 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use tracing_subscriber::EnvFilter;
+
+use crate::execution::order_report::OrderReport;
+use crate::risk::decision::RiskReason;
+use crate::types::quantity::Quantity;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Pretty,
+    Json,
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pretty => write!(f, "pretty"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    pub format: LogFormat,
+    /// Append one JSON line per engine cycle to this file: the book, signals, schedule,
+    /// strategy and risk decisions, and the actions sent.
+    pub decision_log: Option<PathBuf>,
+}
+
+pub fn init(format: LogFormat) {
+    let filter = EnvFilter::from_default_env()
+        .add_directive("accumulator=info".parse().unwrap())
+        .add_directive("market=info".parse().unwrap())
+        .add_directive("execution=info".parse().unwrap());
+
+    match format {
+        LogFormat::Pretty => tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_target(false)
+            .with_thread_ids(true)
+            .init(),
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .with_env_filter(filter)
+            .with_target(false)
+            .init(),
+    }
+}
+
+/// Logs the key fields of a report individually so they stay queryable in JSON output.
+pub(crate) fn order_report(report: &OrderReport) {
+    info!(
+        kind = report.kind(),
+        order_id = report.order_id(),
+        side = report.side().map(tracing::field::display),
+        price = report.price().map(|price| price.as_f64()),
+        quantity = report.quantity().map(Quantity::as_f64),
+        reason = report.reason(),
+        "order report"
+    );
+}
+
+/// Comma-separated reason codes, e.g. `churn_throttle_bid,insufficient_edge`.
+pub(crate) fn reason_codes(reasons: &[RiskReason]) -> String {
+    reasons
+        .iter()
+        .map(RiskReason::code)
+        .collect::<Vec<_>>()
+        .join(",")
+}
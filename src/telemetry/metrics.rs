@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use metrics::{
+    Unit, counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram,
+};
+use metrics_exporter_prometheus::PrometheusBuilder;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::engine::supervisor::{RestartPolicy, Supervisor};
+use crate::execution::ReportSender;
+use crate::execution::order_report::OrderReport;
+use crate::risk::decision::RiskDecision;
+use crate::scheduling::types::SkipReason;
+use crate::telemetry::latency::Stage;
+use crate::types::instrument::Instrument;
+use crate::types::inventory::Inventory;
+use crate::types::price::Price;
+
+pub const ORDER_REPORTS: &str = "accumulator_order_reports_total";
+pub const EXTERNAL_REPORTS: &str = "accumulator_external_order_reports_total";
+pub const RISK_DECISIONS: &str = "accumulator_risk_decisions_total";
+pub const RISK_REASONS: &str = "accumulator_risk_reasons_total";
+pub const SCHEDULE_SKIPS: &str = "accumulator_schedule_skips_total";
+pub const FEED_RECONNECTS: &str = "accumulator_feed_reconnects_total";
+pub const FEED_LAST_EVENT: &str = "accumulator_feed_last_event_timestamp_seconds";
+pub const INVENTORY_BASE: &str = "accumulator_inventory_base";
+pub const INVENTORY_QUOTE: &str = "accumulator_inventory_quote";
+pub const EXPOSURE_QUOTE: &str = "accumulator_exposure_quote";
+pub const OPEN_ORDERS: &str = "accumulator_open_orders";
+pub const MID_PRICE: &str = "accumulator_mid_price";
+pub const EVENT_TO_DECISION: &str = "accumulator_event_to_decision_seconds";
+pub const DECISION_TO_ACK: &str = "accumulator_decision_to_ack_seconds";
+pub const ACK_LATENCY_P95: &str = "accumulator_ack_latency_p95_seconds";
+pub const STAGE_LATENCY: &str = "accumulator_pipeline_stage_seconds";
+pub const CHANNEL_DEPTH: &str = "accumulator_channel_depth";
+pub const CHANNEL_DROPPED: &str = "accumulator_channel_dropped_total";
+pub const TASK_RESTARTS: &str = "accumulator_task_restarts_total";
+pub const TASK_RUNNING: &str = "accumulator_task_running";
+pub const LOOP_IDLE: &str = "accumulator_engine_loop_idle_seconds";
+pub const LOOP_STALLS: &str = "accumulator_engine_loop_stalls_total";
+
+const MAX_PENDING_ACKS: usize = 1_024;
+const PENDING_ACK_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// Serve Prometheus metrics on this port; metrics and pipeline latency tracking are
+    /// disabled when unset.
+    pub port: Option<u16>,
+
+    /// Address the scrape endpoint listens on. Localhost unless a scraper on another host
+    /// needs it, e.g. `0.0.0.0`.
+    pub bind: IpAddr,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            port: None,
+            bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feed {
+    Market,
+    Executions,
+    Inventory,
+}
+
+impl Feed {
+    pub const ALL: [Feed; 3] = [Feed::Market, Feed::Executions, Feed::Inventory];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Feed::Market => "market",
+            Feed::Executions => "executions",
+            Feed::Inventory => "inventory",
+        }
+    }
+}
+
+/// Installs the global recorder and serves the Prometheus scrape endpoint on `bind:port`.
+///
+/// Until this is called every recording function below is a no-op, so the hot path
+/// pays nothing when metrics are disabled.
+pub fn install(bind: IpAddr, port: u16) -> Result<()> {
+    let address = SocketAddr::from((bind, port));
+    PrometheusBuilder::new()
+        .with_http_listener(address)
+        .install()
+        .context("failed to install prometheus exporter")?;
+
+    describe();
+
+    tracing::info!(%address, "prometheus metrics endpoint listening");
+
+    Ok(())
+}
+
+fn describe() {
+    describe_counter!(ORDER_REPORTS, "Order reports received, by report kind");
+    describe_counter!(
+        EXTERNAL_REPORTS,
+        "Reports for orders the engine did not place, by instrument and report kind"
+    );
+    describe_counter!(RISK_DECISIONS, "Risk engine decisions, by outcome");
+    describe_counter!(
+        RISK_REASONS,
+        "Risk hold/reject reasons, by outcome and reason code"
+    );
+    describe_counter!(SCHEDULE_SKIPS, "Scheduler skips, by reason code");
+    describe_counter!(FEED_RECONNECTS, "Websocket reconnect attempts, by feed");
+    describe_gauge!(
+        FEED_LAST_EVENT,
+        Unit::Seconds,
+        "Unix time of the last message received, by feed"
+    );
+    describe_gauge!(INVENTORY_BASE, "Latest base asset inventory, by instrument");
+    describe_gauge!(
+        INVENTORY_QUOTE,
+        "Latest quote asset inventory, by instrument"
+    );
+    describe_gauge!(
+        EXPOSURE_QUOTE,
+        "Base exposure marked at mid, in quote currency, by instrument"
+    );
+    describe_gauge!(
+        OPEN_ORDERS,
+        "Orders the order manager believes are open, by instrument"
+    );
+    describe_gauge!(MID_PRICE, "Latest top-of-book mid price, by instrument");
+    describe_histogram!(
+        EVENT_TO_DECISION,
+        Unit::Seconds,
+        "Time from market event receipt to risk decision"
+    );
+    describe_histogram!(
+        DECISION_TO_ACK,
+        Unit::Seconds,
+        "Time from order placement to venue accept/reject"
+    );
+    describe_gauge!(
+        ACK_LATENCY_P95,
+        Unit::Seconds,
+        "p95 time from placement to venue accept/reject over the recent window, by instrument"
+    );
+    describe_histogram!(
+        STAGE_LATENCY,
+        Unit::Seconds,
+        "Time spent in each pipeline stage from market event to order ack, by stage"
+    );
+    describe_gauge!(
+        CHANNEL_DEPTH,
+        "Messages queued for the engine when it last received, by channel"
+    );
+    describe_counter!(
+        CHANNEL_DROPPED,
+        "Messages a consumer never processed, by channel, consumer and reason"
+    );
+    describe_counter!(TASK_RESTARTS, "Supervised task restarts, by task");
+    describe_gauge!(
+        TASK_RUNNING,
+        "Whether each supervised background task is running (1) or stopped (0)"
+    );
+    describe_gauge!(
+        LOOP_IDLE,
+        Unit::Seconds,
+        "Time since the engine loop last completed an iteration"
+    );
+    describe_counter!(
+        LOOP_STALLS,
+        "Times the engine loop went longer than the stall threshold without an iteration"
+    );
+}
+
+pub(crate) fn feed_event(feed: Feed) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+
+    gauge!(FEED_LAST_EVENT, "feed" => feed.label()).set(now);
+}
+
+pub(crate) fn feed_reconnect(feed: Feed) {
+    counter!(FEED_RECONNECTS, "feed" => feed.label()).increment(1);
+}
+
+pub(crate) fn loop_idle(idle_for: Duration) {
+    gauge!(LOOP_IDLE).set(idle_for.as_secs_f64());
+}
+
+pub(crate) fn loop_stalled() {
+    counter!(LOOP_STALLS).increment(1);
+}
+
+pub(crate) fn channel_depth(channel: &'static str, depth: usize) {
+    gauge!(CHANNEL_DEPTH, "channel" => channel).set(depth as f64);
+}
+
+/// Books dropped from the market event backlog in favour of later ones.
+pub(crate) fn market_events_coalesced(count: usize) {
+    counter!(
+        CHANNEL_DROPPED,
+        "channel" => "market_events",
+        "consumer" => "engine",
+        "reason" => "coalesced"
+    )
+    .increment(count as u64);
+}
+
+/// Fills the fill log dropped because its write queue was full.
+pub(crate) fn fills_dropped() {
+    counter!(
+        CHANNEL_DROPPED,
+        "channel" => "fill_log",
+        "consumer" => "fill_recorder",
+        "reason" => "full"
+    )
+    .increment(1);
+}
+
+/// Order reports `consumer` missed because it fell more than the capacity behind.
+pub(crate) fn reports_lagged(consumer: &'static str, count: u64) {
+    counter!(
+        CHANNEL_DROPPED,
+        "channel" => "order_reports",
+        "consumer" => consumer,
+        "reason" => "lagged"
+    )
+    .increment(count);
+}
+
+pub(crate) fn task_restart(task: &str) {
+    counter!(TASK_RESTARTS, "task" => task.to_string()).increment(1);
+}
+
+pub(crate) fn task_running(task: &str, running: bool) {
+    gauge!(TASK_RUNNING, "task" => task.to_string()).set(if running { 1.0 } else { 0.0 });
+}
+
+pub(crate) fn schedule_skip(reason: &SkipReason) {
+    counter!(SCHEDULE_SKIPS, "reason" => reason.code()).increment(1);
+}
+
+pub(crate) fn risk_decision(decision: &RiskDecision) {
+    let (outcome, reasons) = match decision {
+        RiskDecision::Approved(approval) if approval.stripped.is_empty() => ("approved", &[][..]),
+        RiskDecision::Approved(approval) => ("partially_approved", approval.stripped.as_slice()),
+        RiskDecision::Hold(hold) => ("hold", hold.reasons.as_slice()),
+        RiskDecision::Rejected(rejection) => ("rejected", rejection.reasons.as_slice()),
+    };
+
+    counter!(RISK_DECISIONS, "outcome" => outcome).increment(1);
+
+    for reason in reasons {
+        counter!(RISK_REASONS, "outcome" => outcome, "reason" => reason.code()).increment(1);
+    }
+}
+
+pub(crate) fn event_to_decision(elapsed: Duration) {
+    histogram!(EVENT_TO_DECISION).record(elapsed.as_secs_f64());
+}
+
+pub(crate) fn ack_latency_p95(instrument: &Instrument, p95: Duration) {
+    gauge!(ACK_LATENCY_P95, "instrument" => instrument.to_string()).set(p95.as_secs_f64());
+}
+
+pub(crate) fn stage_latency(stage: Stage, elapsed: Duration) {
+    histogram!(STAGE_LATENCY, "stage" => stage.label()).record(elapsed.as_secs_f64());
+}
+
+pub(crate) fn market(instrument: &Instrument, mid: Option<Price>) {
+    if let Some(mid) = mid {
+        gauge!(MID_PRICE, "instrument" => instrument.to_string()).set(mid.as_f64());
+    }
+}
+
+pub(crate) fn inventory(instrument: &Instrument, inventory: Inventory, mid: Option<Price>) {
+    let label = instrument.to_string();
+    gauge!(INVENTORY_BASE, "instrument" => label.clone()).set(inventory.base);
+    gauge!(INVENTORY_QUOTE, "instrument" => label.clone()).set(inventory.quote);
+
+    if let Some(mid) = mid {
+        gauge!(EXPOSURE_QUOTE, "instrument" => label).set(inventory.exposure_quote(mid));
+    }
+}
+
+pub(crate) fn external_report(instrument: &Instrument, kind: &'static str) {
+    counter!(EXTERNAL_REPORTS, "instrument" => instrument.to_string(), "kind" => kind).increment(1);
+}
+
+pub(crate) fn open_orders(instrument: &Instrument, count: usize) {
+    gauge!(OPEN_ORDERS, "instrument" => instrument.to_string()).set(count as f64);
+}
+
+/// Counts order reports and measures placement acknowledgement latency off the hot path.
+pub(crate) fn spawn_report_metrics(supervisor: &Supervisor, reports: &ReportSender) {
+    let reports = reports.clone();
+    supervisor.spawn("report_metrics", RestartPolicy::restart(), move || {
+        report_metrics(reports.subscribe())
+    });
+}
+
+async fn report_metrics(mut receiver: broadcast::Receiver<OrderReport>) {
+    let mut placed_at: HashMap<String, Instant> = HashMap::new();
+
+    loop {
+        let report = match receiver.recv().await {
+            Ok(report) => report,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                reports_lagged("metrics", n);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        counter!(ORDER_REPORTS, "kind" => report.kind()).increment(1);
+
+        match &report {
+            OrderReport::Placed { order_id, .. } => {
+                if placed_at.len() >= MAX_PENDING_ACKS {
+                    placed_at.retain(|_, placed| placed.elapsed() < PENDING_ACK_TTL);
+                }
+                placed_at.insert(order_id.clone(), Instant::now());
+            }
+            OrderReport::Accepted { order_id, .. } | OrderReport::Rejected { order_id, .. } => {
+                if let Some(placed) = placed_at.remove(order_id) {
+                    histogram!(DECISION_TO_ACK).record(placed.elapsed().as_secs_f64());
+                }
+            }
+            _ => {}
+        }
+    }
+}
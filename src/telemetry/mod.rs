@@ -0,0 +1,7 @@
+pub mod ack_latency;
+pub mod cycles;
+pub mod decision_log;
+pub mod latency;
+pub mod liveness;
+pub mod logging;
+pub mod metrics;
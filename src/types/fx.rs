@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::types::instrument::Instrument;
+
+/// Exchange rates between currencies.
+pub trait FxRates: Send + Sync {
+    /// Units of `to` one unit of `from` is worth, or `None` when the rate is unknown.
+    fn rate(&self, from: &str, to: &str) -> Option<f64>;
+}
+
+pub type SharedFxRates = Arc<dyn FxRates>;
+
+/// Fixed rates, keyed `FROM/TO`. Each also gives the inverse rate.
+#[derive(Debug, Clone, Default)]
+pub struct StaticFxRates {
+    rates: BTreeMap<String, f64>,
+}
+
+impl StaticFxRates {
+    pub fn new(rates: &BTreeMap<String, f64>) -> Self {
+        Self {
+            rates: rates
+                .iter()
+                .map(|(pair, rate)| (pair.to_uppercase(), *rate))
+                .collect(),
+        }
+    }
+}
+
+impl FxRates for StaticFxRates {
+    fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        self.rates
+            .get(&format!("{from}/{to}"))
+            .copied()
+            .or_else(|| {
+                self.rates
+                    .get(&format!("{to}/{from}"))
+                    .map(|rate| 1.0 / rate)
+            })
+    }
+}
+
+/// The currency exposure is measured in, and the rates into it. Without one, each
+/// instrument's exposure stays in its quote currency.
+#[derive(Clone, Default)]
+pub struct ExposureCurrency {
+    currency: Option<(String, SharedFxRates)>,
+}
+
+impl ExposureCurrency {
+    pub fn new(currency: &str, rates: SharedFxRates) -> Self {
+        Self {
+            currency: Some((currency.to_uppercase(), rates)),
+        }
+    }
+
+    pub fn currency(&self) -> Option<&str> {
+        self.currency
+            .as_ref()
+            .map(|(currency, _)| currency.as_str())
+    }
+
+    /// Units of the exposure currency one unit of `instrument`'s quote currency is
+    /// worth; 1 when none is configured.
+    pub fn rate(&self, instrument: &Instrument) -> Result<f64, MissingFxRate> {
+        let Some((currency, rates)) = &self.currency else {
+            return Ok(1.0);
+        };
+        rates
+            .rate(instrument.quote(), currency)
+            .filter(|rate| rate.is_finite() && *rate > 0.0)
+            .ok_or_else(|| MissingFxRate {
+                from: instrument.quote().to_string(),
+                to: currency.clone(),
+            })
+    }
+}
+
+/// No known rate from `from` into `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingFxRate {
+    pub from: String,
+    pub to: String,
+}
+
+impl fmt::Debug for ExposureCurrency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExposureCurrency")
+            .field("currency", &self.currency())
+            .finish()
+    }
+}
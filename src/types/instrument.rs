@@ -1,14 +1,97 @@
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize, Serializer};
+use tokio::sync::watch;
 
+use crate::config::app_config::ensure;
 use crate::types::trading_rules::TradingRules;
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InstrumentConfig {
+    pub base: String,
+    pub quote: String,
+
+    /// Inline trading rules; when unset they are looked up in `trading_rules.yml`.
+    pub trading_rules: Option<TradingRules>,
+}
+
+impl Default for InstrumentConfig {
+    fn default() -> Self {
+        Self {
+            base: "SOL".to_string(),
+            quote: "GBP".to_string(),
+            trading_rules: None,
+        }
+    }
+}
+
+/// Parses `BASE/QUOTE`, as accepted by `--instruments`.
+impl FromStr for InstrumentConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(symbol: &str) -> Result<Self> {
+        let Some((base, quote)) = symbol.split_once('/') else {
+            anyhow::bail!("invalid instrument symbol: {symbol}, expected BASE/QUOTE");
+        };
+
+        Ok(Self {
+            base: base.trim().to_uppercase(),
+            quote: quote.trim().to_uppercase(),
+            trading_rules: None,
+        })
+    }
+}
+
+impl InstrumentConfig {
+    pub fn symbol(&self) -> String {
+        format!("{}/{}", self.base.to_uppercase(), self.quote.to_uppercase())
+    }
+
+    pub fn load(&self) -> Result<Instrument> {
+        match &self.trading_rules {
+            Some(trading_rules) => Ok(Instrument::new(
+                self.base.clone(),
+                self.quote.clone(),
+                trading_rules.clone(),
+            )),
+            None => Instrument::load(self.base.clone(), self.quote.clone()),
+        }
+    }
+
+    pub fn validate(&self, path: &str) -> Result<()> {
+        ensure(
+            !self.base.is_empty(),
+            format!("{path}.base"),
+            "must not be empty",
+        )?;
+        ensure(
+            !self.quote.is_empty(),
+            format!("{path}.quote"),
+            "must not be empty",
+        )?;
+
+        if let Some(trading_rules) = &self.trading_rules {
+            trading_rules
+                .validate()
+                .map_err(|error| anyhow!("{path}.trading_rules: {error}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A traded pair and its trading rules. Clones share the rules, so rules reloaded
+/// through any clone apply everywhere the instrument went, from the next read.
 #[derive(Clone)]
 pub struct Instrument {
     base: String,
     quote: String,
-    trading_rules: TradingRules,
+    trading_rules: Arc<watch::Sender<TradingRules>>,
 }
 
 impl Instrument {
@@ -16,24 +99,25 @@ impl Instrument {
         Self {
             base: base.to_uppercase(),
             quote: quote.to_uppercase(),
-            trading_rules,
+            trading_rules: Arc::new(watch::Sender::new(trading_rules)),
         }
     }
 
-    pub fn from_str(symbol: &str) -> Result<Self> {
-        if let Some((base, quote)) = symbol.split_once('/') {
-            return Self::load(base.to_string(), quote.to_string());
-        }
-
-        anyhow::bail!("invalid instrument symbol: {symbol}");
-    }
-
     pub fn load(base: String, quote: String) -> Result<Self> {
         let trading_rules = TradingRules::from_config(base.as_str(), quote.as_str())?;
 
         Ok(Self::new(base, quote, trading_rules))
     }
 
+    /// A pair only watched for market data, e.g. a reference for another pair's fair
+    /// price. It keeps its rules from `trading_rules.yml` when it has any.
+    pub fn watched(base: String, quote: String) -> Self {
+        let trading_rules = TradingRules::from_config(base.as_str(), quote.as_str())
+            .unwrap_or_else(|_| TradingRules::untraded());
+
+        Self::new(base, quote, trading_rules)
+    }
+
     pub fn base(&self) -> &str {
         &self.base
     }
@@ -42,8 +126,19 @@ impl Instrument {
         &self.quote
     }
 
-    pub fn trading_rules(&self) -> &TradingRules {
-        &self.trading_rules
+    /// The rules as they are now; read them each cycle rather than keeping a copy.
+    pub fn trading_rules(&self) -> TradingRules {
+        self.trading_rules.borrow().clone()
+    }
+
+    /// Replaces the rules for every clone of this instrument. Returns whether they
+    /// changed.
+    pub fn set_trading_rules(&self, trading_rules: TradingRules) -> bool {
+        self.trading_rules.send_if_modified(|current| {
+            let changed = *current != trading_rules;
+            *current = trading_rules;
+            changed
+        })
     }
 }
 
@@ -53,6 +148,42 @@ impl fmt::Display for Instrument {
     }
 }
 
+/// Parses `BASE/QUOTE` and loads the pair's trading rules.
+impl FromStr for Instrument {
+    type Err = anyhow::Error;
+
+    fn from_str(symbol: &str) -> Result<Self> {
+        if let Some((base, quote)) = symbol.split_once('/') {
+            return Self::load(base.to_string(), quote.to_string());
+        }
+
+        anyhow::bail!("invalid instrument symbol: {symbol}");
+    }
+}
+
+/// Instruments are identified by their pair; trading rules do not take part.
+impl PartialEq for Instrument {
+    fn eq(&self, other: &Self) -> bool {
+        self.base == other.base && self.quote == other.quote
+    }
+}
+
+impl Eq for Instrument {}
+
+impl Hash for Instrument {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.hash(state);
+        self.quote.hash(state);
+    }
+}
+
+/// Serialized as its pair, e.g. `"SOL/GBP"`.
+impl Serialize for Instrument {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl fmt::Debug for Instrument {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Instrument({})", self)
@@ -1,6 +1,9 @@
-use crate::types::price::Price;
+use anyhow::Result;
+use serde::Serialize;
 
-#[derive(Debug, Clone, Copy, Default)]
+use crate::types::{price::Price, quantity::Quantity};
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
 pub struct Inventory {
     /// Base asset position (e.g., BTC). Positive = long BTC, negative = short BTC.
     pub base: f64,
@@ -13,6 +16,12 @@ impl Inventory {
         Self { base, quote }
     }
 
+    /// Size of the base position, long or short; an error when fills have left it no
+    /// number at all.
+    pub fn base_size(&self) -> Result<Quantity> {
+        Quantity::new(self.base.abs())
+    }
+
     /// Mark-to-market value in quote currency using mid price.
     pub fn mtm_quote(&self, mid: Price) -> f64 {
         self.quote + self.base * mid.as_f64()
@@ -1,6 +1,9 @@
+pub mod fx;
 pub mod instrument;
 pub mod inventory;
+pub mod pnl;
 pub mod price;
+pub mod quantity;
 pub mod quote;
 pub mod quote_target;
 pub mod trading_hours;
@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::execution::order_action::Side;
+use crate::execution::order_report::OrderReport;
+use crate::types::price::Price;
+use crate::types::quantity::Quantity;
+
+/// Positions smaller than this are flat; fills are never this small.
+const FLAT: f64 = 1e-12;
+
+/// Finished orders whose filled quantity is remembered, so a report replayed after the
+/// order finished applies nothing; far more than are reported on again.
+const FINISHED_CAPACITY: usize = 10_000;
+
+/// Position, average entry price and realized PnL, built from fill reports, so the open
+/// position can be marked at any mid. Amounts are in the quote currency and gross of
+/// fees. Saved with the engine state through [`PnlState`], so a restart carries on with
+/// the position and its entry rather than from flat.
+#[derive(Debug, Clone, Default)]
+pub struct PnlTracker {
+    /// Signed base position: positive long, negative short.
+    position: f64,
+    /// Average price the open position was entered at; zero when flat.
+    average_entry: f64,
+    realized: f64,
+    /// Cumulative quantity applied per order, so each report only applies what it adds
+    /// and a repeated report applies nothing, even once the order has finished.
+    filled: HashMap<String, Applied>,
+    /// Finished orders in `filled`, oldest first, for forgetting the oldest.
+    finished: VecDeque<String>,
+}
+
+/// What a [`PnlTracker`] needs to carry on after a restart.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PnlState {
+    pub position: f64,
+    pub average_entry: f64,
+    pub realized: f64,
+    /// Quantity applied so far per order still working, so its later fills only add
+    /// what is new. Finished orders are not kept.
+    pub working: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Applied {
+    cum_quantity: Quantity,
+    finished: bool,
+}
+
+impl PnlTracker {
+    /// Carries on from a state [`state`](Self::state) saved.
+    pub fn restore(state: PnlState) -> Self {
+        let filled = state
+            .working
+            .into_iter()
+            .filter_map(|(order_id, cum_quantity)| {
+                let applied = Applied {
+                    cum_quantity: Quantity::new(cum_quantity).ok()?,
+                    finished: false,
+                };
+                Some((order_id, applied))
+            })
+            .collect();
+
+        Self {
+            position: state.position,
+            average_entry: state.average_entry,
+            realized: state.realized,
+            filled,
+            finished: VecDeque::new(),
+        }
+    }
+
+    /// The position, entry and realized PnL, to save with the engine state.
+    pub fn state(&self) -> PnlState {
+        PnlState {
+            position: self.position,
+            average_entry: self.average_entry,
+            realized: self.realized,
+            working: self
+                .filled
+                .iter()
+                .filter(|(_, applied)| !applied.finished)
+                .map(|(order_id, applied)| (order_id.clone(), applied.cum_quantity.as_f64()))
+                .collect(),
+        }
+    }
+
+    /// Applies the fill in `report`, if any: the quantity it adds to what earlier
+    /// reports of the same order filled, by `cum_quantity`.
+    pub fn on_report(&mut self, report: &OrderReport) {
+        match report {
+            OrderReport::PartiallyFilled {
+                order_id,
+                side,
+                price,
+                cum_quantity,
+                ..
+            }
+            | OrderReport::Filled {
+                order_id,
+                side,
+                price,
+                cum_quantity,
+                ..
+            } => {
+                let applied = self.filled.entry(order_id.clone()).or_default();
+                let quantity = cum_quantity.saturating_sub(applied.cum_quantity);
+                applied.cum_quantity = applied.cum_quantity.max(*cum_quantity);
+                if quantity.as_f64() > FLAT {
+                    self.on_fill(*side, *price, quantity);
+                }
+
+                if let OrderReport::Filled { .. } = report {
+                    self.finish(order_id);
+                }
+            }
+            OrderReport::Cancelled { order_id, .. } | OrderReport::Rejected { order_id, .. }
+                if self.filled.contains_key(order_id) =>
+            {
+                self.finish(order_id);
+            }
+            OrderReport::CancelledAll { .. } => {
+                let working: Vec<String> = self
+                    .filled
+                    .iter()
+                    .filter(|(_, applied)| !applied.finished)
+                    .map(|(order_id, _)| order_id.clone())
+                    .collect();
+                for order_id in working {
+                    self.finish(&order_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Marks `order_id` finished, keeping what it filled, and forgets the oldest
+    /// finished order past [`FINISHED_CAPACITY`].
+    fn finish(&mut self, order_id: &str) {
+        let applied = self.filled.entry(order_id.to_string()).or_default();
+        if applied.finished {
+            return;
+        }
+        applied.finished = true;
+        self.finished.push_back(order_id.to_string());
+        if self.finished.len() > FINISHED_CAPACITY
+            && let Some(oldest) = self.finished.pop_front()
+        {
+            self.filled.remove(&oldest);
+        }
+    }
+
+    /// Applies a fill of `quantity` at `price`. Whatever part of it reduces the position
+    /// realizes PnL against the average entry; the rest adds to the position, or opens
+    /// one on the other side when the fill flips it.
+    pub fn on_fill(&mut self, side: Side, price: Price, quantity: Quantity) {
+        let price = price.as_f64();
+        let reducing = side.is_reducing_for(self.position);
+
+        let closed = match Quantity::new(self.position.abs()) {
+            Ok(position) if reducing => quantity.min(position),
+            _ => Quantity::ZERO,
+        };
+        if !closed.is_zero() {
+            self.realized +=
+                closed.as_f64() * (price - self.average_entry) * self.position.signum();
+            self.position += side.signed(closed.as_f64());
+            if self.position.abs() < FLAT {
+                self.position = 0.0;
+                self.average_entry = 0.0;
+            }
+        }
+
+        let opened = quantity.saturating_sub(closed);
+        if opened.as_f64() > FLAT {
+            let size = self.position.abs();
+            let opened = opened.as_f64();
+            self.average_entry = (self.average_entry * size + price * opened) / (size + opened);
+            self.position += side.signed(opened);
+        }
+    }
+
+    /// Signed base position: positive long, negative short.
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Average price of the open position; `None` when flat.
+    pub fn average_entry(&self) -> Option<Price> {
+        (self.position != 0.0).then(|| Price::new(self.average_entry))
+    }
+
+    /// PnL locked in by fills that reduced the position.
+    pub fn realized(&self) -> f64 {
+        self.realized
+    }
+
+    /// PnL of the open position were it closed at `mid`.
+    pub fn unrealized(&self, mid: Price) -> f64 {
+        self.position * (mid.as_f64() - self.average_entry)
+    }
+}
@@ -1,7 +1,9 @@
 use std::fmt;
 use std::ops::{Add, Sub};
 
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+use serde::Serialize;
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Serialize)]
 pub struct Price(f64);
 
 impl Price {
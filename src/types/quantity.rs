@@ -0,0 +1,84 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+
+/// An order size in the base asset: finite and never negative. Made only through
+/// [`new`](Self::new), so a NaN or negative size out of a bad division is refused where
+/// it is computed, not by the venue. Arithmetic stays within those bounds; comparing two
+/// sizes for equality is the trading rules' job, to the quantity step.
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd, Serialize)]
+pub struct Quantity(f64);
+
+impl Quantity {
+    pub const ZERO: Self = Quantity(0.0);
+
+    pub fn new(value: f64) -> Result<Self> {
+        if !value.is_finite() {
+            bail!("quantity {value} is not a finite number");
+        }
+        if value < 0.0 {
+            bail!("quantity {value} is negative");
+        }
+        // Adding zero turns -0.0 into 0.0.
+        Ok(Quantity(value + 0.0))
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0.0
+    }
+
+    /// Rounded down to a whole number of `step`s; unchanged when `step` is not positive.
+    pub fn round_to_step(self, step: f64) -> Self {
+        if step <= 0.0 || !step.is_finite() {
+            return self;
+        }
+        Quantity((self.0 / step).floor() * step)
+    }
+
+    /// `self` less `other`, or zero when `other` is the larger.
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Quantity((self.0 - other.0).max(0.0))
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        Quantity(self.0.min(other.0))
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Quantity(self.0.max(other.0))
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, formatter)
+    }
+}
+
+/// Saturates at the largest finite size rather than overflowing to infinity.
+impl Add for Quantity {
+    type Output = Quantity;
+
+    fn add(self, rhs: Quantity) -> Quantity {
+        Quantity((self.0 + rhs.0).min(f64::MAX))
+    }
+}
+
+impl AddAssign for Quantity {
+    fn add_assign(&mut self, rhs: Quantity) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for Quantity {
+    fn sum<I: Iterator<Item = Quantity>>(iter: I) -> Quantity {
+        iter.fold(Quantity::ZERO, Add::add)
+    }
+}
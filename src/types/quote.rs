@@ -1,7 +1,9 @@
-use crate::types::price::Price;
+use serde::Serialize;
 
-#[derive(Debug, Copy, Clone)]
+use crate::types::{price::Price, quantity::Quantity};
+
+#[derive(Debug, Copy, Clone, Serialize)]
 pub struct Quote {
     pub price: Price,
-    pub quantity: f64,
+    pub quantity: Quantity,
 }
@@ -1,27 +1,138 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::execution::order_action::{OrderType, Side};
+use crate::types::quantity::Quantity;
 use crate::types::quote::Quote;
 
-#[derive(Debug, Clone)]
+/// The quotes a strategy wants resting: `bid` and `ask` at level 0, and for strategies
+/// that layer their liquidity, further levels behind them. The order manager keeps one
+/// order per level and moves each on its own.
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct QuoteTarget {
     pub bid: Option<Quote>,
     pub ask: Option<Quote>,
+    /// Bids behind `bid`, level 1 first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deeper_bids: Vec<Quote>,
+    /// Asks behind `ask`, level 1 first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deeper_asks: Vec<Quote>,
+    /// Side whose level 0 quote is sent immediate-or-cancel, taking liquidity now instead
+    /// of resting post-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub immediate: Option<Side>,
+    /// Sides that quote nothing new but keep whatever already rests there, rather than
+    /// cancelling it.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub held: Vec<Side>,
 }
 
 impl QuoteTarget {
-    pub fn none() -> Self {
+    /// A single bid and ask, with no deeper levels.
+    pub fn new(bid: Option<Quote>, ask: Option<Quote>) -> Self {
         Self {
-            bid: None,
-            ask: None,
+            bid,
+            ask,
+            ..Self::default()
+        }
+    }
+
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// The level 0 quote on `side`.
+    pub fn quote(&self, side: Side) -> Option<Quote> {
+        match side {
+            Side::Buy => self.bid,
+            Side::Sell => self.ask,
+        }
+    }
+
+    fn deeper(&self, side: Side) -> &[Quote] {
+        match side {
+            Side::Buy => &self.deeper_bids,
+            Side::Sell => &self.deeper_asks,
+        }
+    }
+
+    /// The quote at `level` on `side`, 0 being the nearest the touch.
+    pub fn level(&self, side: Side, level: usize) -> Option<Quote> {
+        match level {
+            0 => self.quote(side),
+            level => self.deeper(side).get(level - 1).copied(),
+        }
+    }
+
+    /// What the quote at `level` on `side` is placed as.
+    pub fn order_type(&self, side: Side, level: usize) -> OrderType {
+        if level == 0 && self.immediate == Some(side) {
+            OrderType::ImmediateOrCancel
+        } else {
+            OrderType::POST_ONLY
+        }
+    }
+
+    /// How many levels `side` has, quoted or not: 1 for a single quote.
+    pub fn depth(&self, side: Side) -> usize {
+        1 + self.deeper(side).len()
+    }
+
+    /// Every quote on `side`, level 0 first.
+    pub fn quotes(&self, side: Side) -> impl Iterator<Item = Quote> + '_ {
+        self.quote(side)
+            .into_iter()
+            .chain(self.deeper(side).iter().copied())
+    }
+
+    /// The quantity of every quote on `side` together, for checks on what filling all of
+    /// them would do.
+    pub fn total_quantity(&self, side: Side) -> Quantity {
+        self.quotes(side).map(|quote| quote.quantity).sum()
+    }
+
+    /// Quotes `side` with `quote` alone and post-only, dropping its deeper levels.
+    pub fn replace_side(&mut self, side: Side, quote: Option<Quote>) {
+        if self.immediate == Some(side) {
+            self.immediate = None;
         }
+        self.held.retain(|held| *held != side);
+        match side {
+            Side::Buy => {
+                self.bid = quote;
+                self.deeper_bids.clear();
+            }
+            Side::Sell => {
+                self.ask = quote;
+                self.deeper_asks.clear();
+            }
+        }
+    }
+
+    /// Quotes nothing new on `side` while leaving the orders resting there alone.
+    pub fn hold_side(&mut self, side: Side) {
+        self.replace_side(side, None);
+        self.held.push(side);
+    }
+
+    pub fn is_held(&self, side: Side) -> bool {
+        self.held.contains(&side)
     }
 }
 
-#[derive(Debug, Clone)]
+/// Serialized tagged with its [`code`](Self::code).
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
 pub enum NoQuoteReason {
     MissingTopOfBook,
     MissingFairPrice,
     MissingMid,
     MissingEma,
     MissingSlowEma,
+    /// Either mid EMA has not yet seen a time constant of samples.
+    SignalsWarmingUp,
     BelowEntryThreshold {
         deviation_ticks: f64,
         threshold_ticks: f64,
@@ -34,4 +145,77 @@ pub enum NoQuoteReason {
     WouldCrossPostOnly,
     BothSidesSuppressedByExposure,
     PullbackNotMet,
+    AlreadyFlat,
+    /// Inventory is within tolerance of the strategy's target.
+    AtInventoryTarget,
+    /// No rate converts the pair's quote currency into the exposure currency.
+    MissingFxRate {
+        from: String,
+        to: String,
+    },
+}
+
+impl NoQuoteReason {
+    /// Stable, low-cardinality identifier used for metric labels and log fields.
+    pub fn code(&self) -> &'static str {
+        match self {
+            NoQuoteReason::MissingTopOfBook => "missing_top_of_book",
+            NoQuoteReason::MissingFairPrice => "missing_fair_price",
+            NoQuoteReason::MissingMid => "missing_mid",
+            NoQuoteReason::MissingEma => "missing_ema",
+            NoQuoteReason::MissingSlowEma => "missing_slow_ema",
+            NoQuoteReason::SignalsWarmingUp => "signals_warming_up",
+            NoQuoteReason::BelowEntryThreshold { .. } => "below_entry_threshold",
+            NoQuoteReason::BelowTrendSlopeThreshold { .. } => "below_trend_slope_threshold",
+            NoQuoteReason::InvalidQuantity => "invalid_quantity",
+            NoQuoteReason::WouldCrossPostOnly => "would_cross_post_only",
+            NoQuoteReason::BothSidesSuppressedByExposure => "both_sides_suppressed_by_exposure",
+            NoQuoteReason::PullbackNotMet => "pullback_not_met",
+            NoQuoteReason::AlreadyFlat => "already_flat",
+            NoQuoteReason::AtInventoryTarget => "at_inventory_target",
+            NoQuoteReason::MissingFxRate { .. } => "missing_fx_rate",
+        }
+    }
 }
+
+impl fmt::Display for NoQuoteReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoQuoteReason::MissingTopOfBook => write!(f, "no best bid and ask yet"),
+            NoQuoteReason::MissingFairPrice => write!(f, "no fair price yet"),
+            NoQuoteReason::MissingMid => write!(f, "no mid price yet"),
+            NoQuoteReason::MissingEma => write!(f, "fast EMA still warming up"),
+            NoQuoteReason::MissingSlowEma => write!(f, "slow EMA still warming up"),
+            NoQuoteReason::SignalsWarmingUp => write!(f, "signals still warming up"),
+            NoQuoteReason::BelowEntryThreshold {
+                deviation_ticks,
+                threshold_ticks,
+            } => write!(
+                f,
+                "deviation of {deviation_ticks:.2} ticks below the {threshold_ticks:.2} tick entry threshold"
+            ),
+            NoQuoteReason::BelowTrendSlopeThreshold {
+                slope_ticks,
+                threshold_ticks,
+            } => write!(
+                f,
+                "trend slope of {slope_ticks:.2} ticks below the {threshold_ticks:.2} tick threshold"
+            ),
+            NoQuoteReason::InvalidQuantity => {
+                write!(f, "order size rounds to nothing under the trading rules")
+            }
+            NoQuoteReason::WouldCrossPostOnly => write!(f, "post-only quote would cross the book"),
+            NoQuoteReason::BothSidesSuppressedByExposure => {
+                write!(f, "exposure limits leave neither side to quote")
+            }
+            NoQuoteReason::PullbackNotMet => write!(f, "waiting for a pullback to enter the trend"),
+            NoQuoteReason::AlreadyFlat => write!(f, "nothing left to flatten"),
+            NoQuoteReason::AtInventoryTarget => write!(f, "inventory is at its target"),
+            NoQuoteReason::MissingFxRate { from, to } => {
+                write!(f, "no {from}/{to} rate to measure exposure in {to}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NoQuoteReason {}
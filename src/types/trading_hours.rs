@@ -1,23 +1,182 @@
-use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Copy, Clone, Deserialize)]
-pub struct TradingHours {
-    /// Start hour in UTC (inclusive), 0–23
-    pub start_hour: u8,
+use anyhow::{Result, anyhow, bail};
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// A time of day in UTC to the minute, written `HH:MM`. `24:00` is the end of the day,
+/// for windows that run to midnight.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay {
+    /// Minutes since midnight, 0 to 1440.
+    minutes: u16,
+}
+
+impl TimeOfDay {
+    pub fn new(hour: u16, minute: u16) -> Result<Self> {
+        if hour > 24 || minute >= 60 || hour * 60 + minute > MINUTES_PER_DAY {
+            bail!("{hour:02}:{minute:02} is not a time of day");
+        }
+        Ok(Self {
+            minutes: hour * 60 + minute,
+        })
+    }
+
+    /// The hour and minute of `time`.
+    pub fn of(time: &impl Timelike) -> Self {
+        Self {
+            minutes: (time.hour() * 60 + time.minute()) as u16,
+        }
+    }
+
+    /// Minutes since midnight.
+    pub fn minutes(self) -> u16 {
+        self.minutes
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (hour, minute) = s
+            .split_once(':')
+            .filter(|(hour, minute)| !hour.is_empty() && minute.len() == 2)
+            .ok_or_else(|| anyhow!("{s:?} is not a HH:MM time"))?;
+        let parse = |part: &str| {
+            part.parse::<u16>()
+                .map_err(|_| anyhow!("{s:?} is not a HH:MM time"))
+        };
+        Self::new(parse(hour)?, parse(minute)?)
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> Self {
+        time.to_string()
+    }
+}
 
-    /// End hour in UTC (exclusive), 1–24
-    pub end_hour: u8,
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+/// A daily session from `start`, inclusive, to `end`, exclusive. A window that ends
+/// before it starts runs overnight, past midnight.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TradingWindow {
+    pub start: TimeOfDay,
+    pub end: TimeOfDay,
+}
+
+impl TradingWindow {
+    pub fn contains(&self, minute_of_day: u16) -> bool {
+        let (start, end) = (self.start.minutes, self.end.minutes);
+        if start <= end {
+            minute_of_day >= start && minute_of_day < end
+        } else {
+            minute_of_day >= start || minute_of_day < end
+        }
+    }
+
+    /// Minutes from `minute_of_day`, inside the window, until it ends.
+    fn minutes_left(&self, minute_of_day: u16) -> u16 {
+        match (self.end.minutes + MINUTES_PER_DAY - minute_of_day) % MINUTES_PER_DAY {
+            0 => MINUTES_PER_DAY,
+            left => left,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TradingHours {
+    /// Sessions each day, in UTC. Windows that overlap or follow on from each other
+    /// trade as one session.
+    pub windows: Vec<TradingWindow>,
 
     /// Whether to pause trading on Saturday/Sunday
     pub weekend_pause: bool,
+
+    /// Minutes before a session closes to stop quoting and cancel resting orders, so
+    /// nothing rests over the closed period. 0 quotes until the close.
+    #[serde(default)]
+    pub close_buffer_mins: u16,
 }
 
 impl Default for TradingHours {
     fn default() -> Self {
         Self {
-            start_hour: 8, // 08:00 UTC
-            end_hour: 20,  // 20:00 UTC
+            // 08:00 to 20:00 UTC
+            windows: vec![TradingWindow {
+                start: TimeOfDay { minutes: 8 * 60 },
+                end: TimeOfDay { minutes: 20 * 60 },
+            }],
             weekend_pause: false,
+            close_buffer_mins: 0,
+        }
+    }
+}
+
+impl TradingHours {
+    pub fn is_open(&self, minute_of_day: u16) -> bool {
+        self.windows
+            .iter()
+            .any(|window| window.contains(minute_of_day))
+    }
+
+    /// Minutes from `minute_of_day` until the session open then closes, following it
+    /// into any window it runs into. `None` when closed then, or when the windows
+    /// cover the whole day and the session never closes.
+    pub fn minutes_to_close(&self, minute_of_day: u16) -> Option<u16> {
+        let mut left = 0;
+        let mut at = minute_of_day;
+        while let Some(next) = self
+            .windows
+            .iter()
+            .filter(|window| window.contains(at))
+            .map(|window| window.minutes_left(at))
+            .max()
+        {
+            left += next;
+            if left >= MINUTES_PER_DAY {
+                return None;
+            }
+            at = (at + next) % MINUTES_PER_DAY;
+        }
+        (left > 0).then_some(left)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.windows.is_empty() {
+            bail!("windows must not be empty");
+        }
+        for (index, window) in self.windows.iter().enumerate() {
+            if window.start.minutes >= MINUTES_PER_DAY {
+                bail!("windows[{index}].start must be before 24:00");
+            }
+            if window.start == window.end {
+                bail!("windows[{index}]: start and end must differ");
+            }
+        }
+        if self.close_buffer_mins >= MINUTES_PER_DAY {
+            bail!("close_buffer_mins must be < 1440");
         }
+        Ok(())
     }
 }
@@ -1,13 +1,14 @@
 use crate::types::price::Price;
+use crate::types::quantity::Quantity;
 use crate::types::trading_hours::TradingHours;
 
-use anyhow::{anyhow, bail, Context, Result};
-use once_cell::sync::OnceCell;
-use serde::Deserialize;
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::time::Duration;
 
-#[derive(Debug, Copy, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TradingRules {
     /// Minimum price increment in quote currency (GBP).
     pub price_tick: f64,
@@ -15,47 +16,150 @@ pub struct TradingRules {
     /// Minimum quantity increment in base currency (e.g. BTC).
     pub quantity_step: f64,
 
+    /// Smallest order the venue accepts in base currency, Kraken's `ordermin`.
+    #[serde(default)]
+    pub min_order_quantity: f64,
+
     /// Minimum half-spread in quote currency (GBP). Acts as a floor.
     pub min_half_spread: f64,
 
+    /// Venue fee on maker fills, in basis points of notional. Quoting needs this much
+    /// half-spread on top of `min_half_spread`.
+    #[serde(default)]
+    pub maker_fee_bps: f64,
+
     /// Max notional per order in quote currency (GBP). Keeps risk stable as price moves.
     pub max_order_notional: f64,
 
-    /// Max absolute exposure in quote currency (GBP).
+    /// Max absolute exposure in quote currency (GBP), or in `risk.exposure_currency`
+    /// when that is set.
     pub max_exposure_in_quote: f64,
 
     /// Optional trading hours restriction (UTC)
     #[serde(default)]
     pub trading_hours: Option<TradingHours>,
+
+    /// Longest an order rests, since it was accepted or last filled, before it is
+    /// re-placed at the current target however little that moved. No limit when unset.
+    #[serde(default)]
+    pub max_order_lifetime_ms: Option<u64>,
 }
 
 impl TradingRules {
+    /// The pair's rules in `trading_rules.yml`, read afresh so a reload sees edits.
     pub fn from_config(base: &str, quote: &str) -> Result<Self> {
         let key = format!("{base}_{quote}");
         Config::load()?
             .trading_rules
-            .get(&key)
+            .remove(&key)
             .ok_or_else(|| anyhow!("unsupported trading pair, missing trading rules for \"{key}\""))
-            .map(|rules| *rules)
     }
 
-    pub fn round_price_to_tick(self, price: f64) -> Price {
+    /// Rules for a pair only watched for its prices: no order fits them.
+    pub fn untraded() -> Self {
+        Self {
+            price_tick: 0.0,
+            quantity_step: 0.0,
+            min_order_quantity: 0.0,
+            min_half_spread: 0.0,
+            maker_fee_bps: 0.0,
+            max_order_notional: 0.0,
+            max_exposure_in_quote: 0.0,
+            trading_hours: None,
+            max_order_lifetime_ms: None,
+        }
+    }
+
+    pub fn max_order_lifetime(&self) -> Option<Duration> {
+        self.max_order_lifetime_ms.map(Duration::from_millis)
+    }
+
+    pub fn round_price_to_tick(&self, price: f64) -> Price {
         Price::new(round_down_to_step(price, self.price_tick))
     }
 
-    pub fn round_quantity_to_step(self, quantity_base: f64) -> f64 {
-        round_down_to_step(quantity_base, self.quantity_step)
+    /// `quantity` rounded down to the step, or 0 when that is below the minimum order
+    /// size.
+    pub fn round_quantity_to_step(&self, quantity: Quantity) -> Quantity {
+        let quantity = quantity.round_to_step(self.quantity_step);
+        if self.below_min_quantity(quantity) {
+            return Quantity::ZERO;
+        }
+        quantity
+    }
+
+    /// Whether `a` and `b` are the same order size on this pair, to half a step.
+    pub fn quantity_eq(&self, a: Quantity, b: Quantity) -> bool {
+        qty_eq(a, b, self.quantity_step)
     }
 
-    pub fn quantity_from_notional(self, notional: f64, price_per_base: f64) -> f64 {
+    /// Decimal places a price on this pair's tick is written to, e.g. 2 for 0.01 and 1
+    /// for 0.5.
+    pub fn price_decimals(&self) -> u32 {
+        step_decimals(self.price_tick)
+    }
+
+    pub fn quantity_decimals(&self) -> u32 {
+        step_decimals(self.quantity_step)
+    }
+
+    /// `price` as the venue takes it: rounded half to even at the tick's decimals and
+    /// written out without float artifacts.
+    pub fn format_price(&self, price: Price) -> String {
+        format_decimal(price.as_f64(), self.price_decimals())
+    }
+
+    /// `quantity` as the venue takes it, like [`format_price`](Self::format_price).
+    pub fn format_quantity(&self, quantity: Quantity) -> String {
+        format_decimal(quantity.as_f64(), self.quantity_decimals())
+    }
+
+    fn below_min_quantity(&self, quantity: Quantity) -> bool {
+        let quantity = quantity.as_f64();
+        quantity < self.min_order_quantity
+            && (quantity - self.min_order_quantity).abs() >= self.quantity_step * 0.5
+    }
+
+    /// Checks an order against these rules before it is sent: a positive price on a
+    /// tick, and a quantity on a step and at least the minimum order size.
+    pub fn check_order(&self, price: Price, quantity: Quantity) -> Result<()> {
+        let price = price.as_f64();
+        if !price.is_finite() || price <= 0.0 {
+            bail!("price {price} is not positive");
+        }
+        if !on_step(price, self.price_tick) {
+            bail!("price {price} is not on a {} tick", self.price_tick);
+        }
+        if quantity.is_zero() {
+            bail!("quantity {quantity} is not positive");
+        }
+        if !on_step(quantity.as_f64(), self.quantity_step) {
+            bail!(
+                "quantity {quantity} is not on a {} step",
+                self.quantity_step
+            );
+        }
+        if self.below_min_quantity(quantity) {
+            bail!(
+                "quantity {quantity} is below the minimum order size {}",
+                self.min_order_quantity
+            );
+        }
+        Ok(())
+    }
+
+    /// The most `notional` buys at `price_per_base`, on a step; 0 when that is below the
+    /// minimum order size or the price is no price at all.
+    pub fn quantity_from_notional(&self, notional: f64, price_per_base: f64) -> Quantity {
         if price_per_base <= 0.0 || !price_per_base.is_finite() {
-            return 0.0;
+            return Quantity::ZERO;
         }
-        let raw_quantity = notional / price_per_base;
-        self.round_quantity_to_step(raw_quantity)
+        Quantity::new(notional / price_per_base).map_or(Quantity::ZERO, |quantity| {
+            self.round_quantity_to_step(quantity)
+        })
     }
 
-    fn validate(&self) -> Result<()> {
+    pub fn validate(&self) -> Result<()> {
         if self.price_tick <= 0.0 {
             bail!("price_tick must be > 0");
         }
@@ -65,16 +169,76 @@ impl TradingRules {
         if self.min_half_spread < 0.0 {
             bail!("min_half_spread must be >= 0");
         }
+        if self.min_order_quantity < 0.0 {
+            bail!("min_order_quantity must be >= 0");
+        }
+        if !(0.0..100.0).contains(&self.maker_fee_bps) {
+            bail!("maker_fee_bps must be >= 0 and < 100");
+        }
         if self.max_order_notional <= 0.0 {
             bail!("max_order_notional must be > 0");
         }
         if self.max_exposure_in_quote <= 0.0 {
             bail!("max_exposure_in_quote must be > 0");
         }
+        if self.max_order_lifetime_ms == Some(0) {
+            bail!("max_order_lifetime_ms must be > 0");
+        }
+        if let Some(trading_hours) = &self.trading_hours {
+            trading_hours.validate().context("trading_hours")?;
+        }
         Ok(())
     }
 }
 
+/// Whether two quantities are the same order size: within half a `step` of each other.
+/// Quantities come out of notional division and rounding, so exact comparison trips on
+/// last-bit differences, while anything under a step is not a size the venue can take.
+pub fn qty_eq(a: Quantity, b: Quantity, step: f64) -> bool {
+    (a.as_f64() - b.as_f64()).abs() < step * 0.5
+}
+
+/// Whether `value` is a whole number of `step`s, give or take float noise.
+fn on_step(value: f64, step: f64) -> bool {
+    let steps = value / step;
+    (steps - steps.round()).abs() < 1e-6
+}
+
+/// Most decimal places a price or quantity is written to, for steps finer than any
+/// venue's.
+const MAX_DECIMALS: u32 = 12;
+
+/// Fewest decimal places that hold a whole number of `step`s.
+fn step_decimals(step: f64) -> u32 {
+    if step <= 0.0 || !step.is_finite() {
+        return MAX_DECIMALS;
+    }
+    (0..MAX_DECIMALS)
+        .find(|decimals| {
+            let scaled = step * 10f64.powi(*decimals as i32);
+            scaled.round() >= 1.0 && on_step(scaled, 1.0)
+        })
+        .unwrap_or(MAX_DECIMALS)
+}
+
+/// `value` rounded half to even at `decimals` places, written with exactly that many,
+/// from the rounded integer so that no binary float noise reaches the digits.
+fn format_decimal(value: f64, decimals: u32) -> String {
+    let scaled = (value * 10f64.powi(decimals as i32)).round_ties_even();
+    let digits = format!(
+        "{:0>width$}",
+        scaled.abs() as u128,
+        width = decimals as usize + 1
+    );
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let sign = if scaled < 0.0 { "-" } else { "" };
+    if fraction.is_empty() {
+        format!("{sign}{whole}")
+    } else {
+        format!("{sign}{whole}.{fraction}")
+    }
+}
+
 fn round_down_to_step(value: f64, step: f64) -> f64 {
     if step <= 0.0 || !value.is_finite() || !step.is_finite() {
         return value;
@@ -88,25 +252,21 @@ struct Config {
     pub trading_rules: HashMap<String, TradingRules>,
 }
 
-static CONFIG: OnceCell<Config> = OnceCell::new();
-
 impl Config {
     const FILE_NAME: &'static str = "trading_rules.yml";
 
-    fn load() -> Result<&'static Config> {
-        CONFIG.get_or_try_init(|| {
-            let raw = fs::read_to_string(Self::FILE_NAME)
-                .with_context(|| format!("failed to read trading rules {}", Self::FILE_NAME))?;
+    fn load() -> Result<Config> {
+        let raw = fs::read_to_string(Self::FILE_NAME)
+            .with_context(|| format!("failed to read trading rules {}", Self::FILE_NAME))?;
 
-            let config: Config = serde_yaml::from_str::<Config>(&raw)
-                .with_context(|| format!("failed to parse trading rules {}", Self::FILE_NAME))?;
+        let config: Config = serde_yaml::from_str::<Config>(&raw)
+            .with_context(|| format!("failed to parse trading rules {}", Self::FILE_NAME))?;
 
-            config
-                .validate()
-                .context("trading rules config validation failed")?;
+        config
+            .validate()
+            .context("trading rules config validation failed")?;
 
-            Ok(config)
-        })
+        Ok(config)
     }
 
     fn validate(&self) -> Result<()> {
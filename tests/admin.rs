@@ -0,0 +1,246 @@
+mod common;
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use tokio::sync::{broadcast, mpsc};
+
+use accumulator::admin::server::AdminConfig;
+use accumulator::config::app_config::AppConfig;
+use accumulator::engine::engine::{Connections, Engine};
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::OrderAction;
+use accumulator::market::market_source::MarketDataSource;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+
+use common::{INITIAL, MockVenue};
+
+const TOKEN: &str = "s3cret";
+
+/// Market data that never arrives; the admin API answers without it.
+struct Quiet;
+
+#[async_trait]
+impl MarketDataSource for Quiet {
+    async fn subscribe(
+        &self,
+        _instrument: &Instrument,
+        _channel: mpsc::Sender<MarketEvent>,
+    ) -> Result<()> {
+        std::future::pending().await
+    }
+}
+
+/// A port nothing is listening on, for the engine to serve its admin API from.
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+struct Admin {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl Admin {
+    async fn get(&self, path: &str, token: Option<&str>) -> (StatusCode, Value) {
+        self.send(self.client.get(format!("{}{path}", self.url)), token)
+            .await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        let request = self.client.post(format!("{}{path}", self.url)).json(&body);
+        self.send(request, Some(TOKEN)).await
+    }
+
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        token: Option<&str>,
+    ) -> (StatusCode, Value) {
+        let request = match token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        let response = request.send().await.unwrap();
+        let status = response.status();
+        let body = response.text().await.unwrap();
+        (status, serde_json::from_str(&body).unwrap_or(Value::Null))
+    }
+}
+
+fn cancel_alls(venue: &MockVenue) -> usize {
+    venue
+        .actions()
+        .iter()
+        .filter(|action| matches!(action, OrderAction::CancelAll))
+        .count()
+}
+
+/// Runs an engine serving its admin API behind [`TOKEN`] until `script` is done.
+async fn with_admin<F: Future<Output = ()>>(script: impl FnOnce(Admin, MockVenue) -> F) {
+    let port = free_port();
+    let mut config = AppConfig {
+        admin: AdminConfig {
+            port: Some(port),
+            token: Some(TOKEN.to_string()),
+        },
+        ..AppConfig::default()
+    };
+    config.watchdog.exit = false;
+    config.stats.reports_dir = std::env::temp_dir().join("accumulator-admin");
+
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let (reports, _) = broadcast::channel(config.channels.order_reports);
+    let venue = MockVenue::new(reports.clone(), INITIAL);
+    let connections = Connections {
+        venue: Box::new(venue.clone()),
+        reports,
+        market: Arc::new(Quiet),
+    };
+
+    let engine = Engine::start("admin", config, vec![instrument], connections)
+        .await
+        .unwrap();
+    let admin = Admin {
+        client: reqwest::Client::new(),
+        url: format!("http://127.0.0.1:{port}"),
+    };
+
+    // The engine is not Send, so it runs on this task until the script is done.
+    tokio::select! {
+        result = engine.run() => panic!("engine stopped: {result:?}"),
+        () = script(admin, venue) => {}
+    }
+}
+
+#[tokio::test]
+async fn refuses_a_request_without_the_token() {
+    with_admin(|admin, _| async move {
+        for path in ["/status", "/config"] {
+            assert_eq!(admin.get(path, None).await.0, StatusCode::UNAUTHORIZED);
+            assert_eq!(
+                admin.get(path, Some("wrong")).await.0,
+                StatusCode::UNAUTHORIZED
+            );
+        }
+
+        let response = admin
+            .client
+            .post(format!("{}/kill-switch", admin.url))
+            .bearer_auth("wrong")
+            .json(&json!({ "engaged": true }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let (status, body) = admin.get("/status", Some(TOKEN)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["kill_switch"], false,
+            "a refused request changed nothing"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn serves_status_and_the_config_without_secrets() {
+    with_admin(|admin, _| async move {
+        let (status, body) = admin.get("/status", Some(TOKEN)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["kill_switch"], false);
+        assert_eq!(body["instruments"][0]["instrument"], "SOL/GBP");
+        assert_eq!(body["instruments"][0]["flattening"], false);
+
+        let (status, body) = admin.get("/config", Some(TOKEN)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["watchdog"]["exit"], false);
+        assert!(
+            !body.to_string().contains(TOKEN),
+            "token served in the config: {body}"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn the_kill_switch_cancels_everything_and_stays_until_released() {
+    with_admin(|admin, venue| async move {
+        let before = cancel_alls(&venue);
+
+        let (status, body) = admin.post("/kill-switch", json!({ "engaged": true })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "kill_switch": true }));
+        assert_eq!(cancel_alls(&venue), before + 1);
+        assert_eq!(
+            admin.get("/status", Some(TOKEN)).await.1["kill_switch"],
+            true
+        );
+
+        let (status, body) = admin
+            .post("/kill-switch", json!({ "engaged": false }))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "kill_switch": false }));
+        assert_eq!(cancel_alls(&venue), before + 1, "releasing cancels nothing");
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn a_kill_switch_whose_cancel_fails_reports_it_and_stays_engaged() {
+    with_admin(|admin, venue| async move {
+        venue.execute_down(true);
+
+        let (status, body) = admin.post("/kill-switch", json!({ "engaged": true })).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["kill_switch"], true);
+        assert_eq!(body["error"], "venue unreachable");
+
+        // The engine carries on serving, with the switch engaged.
+        venue.execute_down(false);
+        let (status, body) = admin.get("/status", Some(TOKEN)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["kill_switch"], true);
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn cancel_all_reports_whether_the_venue_took_it() {
+    with_admin(|admin, venue| async move {
+        let before = cancel_alls(&venue);
+
+        let (status, body) = admin.post("/cancel-all", Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "ok": true }));
+        assert_eq!(cancel_alls(&venue), before + 1);
+
+        venue.execute_down(true);
+        let (status, body) = admin.post("/cancel-all", Value::Null).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body, json!({ "ok": false, "error": "venue unreachable" }));
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn flatten_starts_flattening_every_instrument() {
+    with_admin(|admin, _| async move {
+        let (status, body) = admin.post("/flatten", Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "ok": true }));
+
+        let (_, body) = admin.get("/status", Some(TOKEN)).await;
+        assert_eq!(body["instruments"][0]["flattening"], true);
+    })
+    .await;
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{Value, json};
+use tokio::sync::broadcast;
+
+use accumulator::alerts::alert::Alert;
+use accumulator::alerts::alerter::{Alerter, AlerterConfig};
+use accumulator::alerts::webhook::{Webhook, WebhookKind};
+use accumulator::execution::order_action::Side::Buy;
+use accumulator::execution::order_report::{OrderReport, RejectKind};
+use accumulator::types::instrument::Instrument;
+use accumulator::types::price::Price;
+
+use common::qty;
+
+/// Stands in for the webhook on a free local port, keeping every body posted to it.
+struct Receiver {
+    url: String,
+    posted: Arc<Mutex<Vec<Value>>>,
+}
+
+impl Receiver {
+    async fn serve() -> Self {
+        let posted = Arc::new(Mutex::new(Vec::new()));
+        let kept = posted.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move |Json(body): Json<Value>| async move {
+                kept.lock().unwrap().push(body);
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        Self {
+            url: format!("http://{address}/hook"),
+            posted,
+        }
+    }
+
+    /// Waits for `count` posts, then a little longer to catch any beyond them.
+    async fn wait_for(&self, count: usize) -> Vec<Value> {
+        for _ in 0..500 {
+            if self.posted.lock().unwrap().len() >= count {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        self.posted.lock().unwrap().clone()
+    }
+}
+
+/// Alerts on three rejections in a row, each at most once every five minutes.
+fn config(url: &str, kind: WebhookKind, chat_id: Option<&str>) -> AlerterConfig {
+    AlerterConfig {
+        webhook: Webhook::new(kind, url.to_string(), chat_id.map(str::to_string)).unwrap(),
+        reject_threshold: 3,
+        feed_down_after: Duration::from_secs(30),
+        loop_stall_after: Duration::from_secs(15),
+        cooldown: Duration::from_secs(300),
+    }
+}
+
+fn sol() -> Instrument {
+    "SOL/GBP".parse().unwrap()
+}
+
+fn filled(order_id: &str) -> OrderReport {
+    OrderReport::Filled {
+        order_id: order_id.to_string(),
+        instrument: sol(),
+        side: Buy,
+        price: Price::new(93.00),
+        quantity: qty(0.05),
+        cum_quantity: qty(0.05),
+    }
+}
+
+fn rejected(reason: &str) -> OrderReport {
+    OrderReport::Rejected {
+        order_id: "o1".to_string(),
+        instrument: sol(),
+        side: Buy,
+        kind: RejectKind::Other,
+        reason: reason.to_string(),
+    }
+}
+
+fn texts(posted: &[Value]) -> Vec<&str> {
+    posted
+        .iter()
+        .map(|body| body["text"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn posts_slack_messages_for_fills_rejection_runs_and_the_kill_switch() {
+    let receiver = Receiver::serve().await;
+    let (reports, _) = broadcast::channel(64);
+    let handle = Alerter::spawn(
+        config(&receiver.url, WebhookKind::Slack, None),
+        reports.subscribe(),
+    );
+
+    reports.send(filled("o7")).unwrap();
+    for reason in [
+        "EOrder:Insufficient funds",
+        "EOrder:Post only order",
+        "EOrder:Rate limit",
+    ] {
+        reports.send(rejected(reason)).unwrap();
+    }
+    receiver.wait_for(2).await;
+    handle.raise(Alert::KillSwitch { engaged: true });
+
+    // The first two rejections alerted nothing; the third reports the run.
+    assert_eq!(
+        receiver.wait_for(3).await,
+        [
+            json!({ "text": "Filled BUY 0.05 SOL/GBP @ 93.00 (o7)" }),
+            json!({ "text": "3 consecutive order rejections; last: EOrder:Rate limit" }),
+            json!({ "text": "Kill switch engaged" }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn posts_telegram_messages_to_the_configured_chat() {
+    let receiver = Receiver::serve().await;
+    let (reports, _) = broadcast::channel(64);
+    let handle = Alerter::spawn(
+        config(&receiver.url, WebhookKind::Telegram, Some("-100123")),
+        reports.subscribe(),
+    );
+
+    handle.raise(Alert::KillSwitch { engaged: false });
+
+    assert_eq!(
+        receiver.wait_for(1).await,
+        [json!({ "chat_id": "-100123", "text": "Kill switch disengaged" })]
+    );
+}
+
+#[tokio::test]
+async fn repeats_are_held_back_and_bursts_capped() {
+    let receiver = Receiver::serve().await;
+    let (reports, _) = broadcast::channel(64);
+    let handle = Alerter::spawn(
+        config(&receiver.url, WebhookKind::Slack, None),
+        reports.subscribe(),
+    );
+
+    // The same alert again inside the cooldown is not sent twice.
+    handle.raise(Alert::KillSwitch { engaged: true });
+    handle.raise(Alert::KillSwitch { engaged: true });
+    assert_eq!(
+        receiver.wait_for(1).await,
+        [json!({ "text": "Kill switch engaged" })]
+    );
+
+    // Nor is more than twenty a minute, however different they are.
+    for index in 0..30 {
+        reports.send(filled(&format!("o{index}"))).unwrap();
+    }
+
+    let posted = receiver.wait_for(20).await;
+    let texts = texts(&posted);
+    assert_eq!(texts.len(), 20, "{texts:?}");
+    assert!(
+        texts[1..].iter().all(|text| text.starts_with("Filled")),
+        "{texts:?}"
+    );
+}
+
+#[test]
+fn the_cooldown_and_the_rate_window_both_pass() {
+    let mut alerter = Alerter::new(config("http://127.0.0.1:9/", WebhookKind::Slack, None));
+    let start = Instant::now();
+    let engaged = Alert::KillSwitch { engaged: true };
+
+    assert!(alerter.admit(&engaged, start));
+    assert!(!alerter.admit(&engaged, start + Duration::from_secs(299)));
+    assert!(alerter.admit(&engaged, start + Duration::from_secs(300)));
+
+    let fill = |index: usize| Alert::Fill {
+        order_id: format!("o{index}"),
+        instrument: sol(),
+        side: Buy,
+        price: Price::new(93.00),
+        quantity: qty(0.05),
+    };
+    let later = start + Duration::from_secs(300);
+    for index in 0..19 {
+        assert!(alerter.admit(&fill(index), later), "{index}");
+    }
+    assert!(!alerter.admit(&fill(19), later + Duration::from_secs(59)));
+    assert!(alerter.admit(&fill(19), later + Duration::from_secs(60)));
+}
@@ -0,0 +1,190 @@
+use std::path::PathBuf;
+
+use accumulator::kraken::capture::{self, Capture, CaptureConfig, Direction};
+use accumulator::telemetry::cycles;
+
+const API_KEY: &str = "kraken-api-key-0123456789";
+const API_SECRET: &str = "a3Jha2VuLXNlY3JldA==";
+const WS_TOKEN: &str = "Tx5kIC8N0a9RFMzXrXgHCgmSyc5hx3yP";
+
+fn capture_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("accumulator-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn capture(config: CaptureConfig) -> Capture {
+    let capture = Capture::new(&config).unwrap();
+    for secret in [API_KEY, API_SECRET, WS_TOKEN] {
+        capture.add_secret(secret);
+    }
+    capture
+}
+
+fn assert_no_secrets(text: &str) {
+    for secret in [API_KEY, API_SECRET, WS_TOKEN, "c2lnbmF0dXJl"] {
+        assert!(!text.contains(secret), "{secret} leaked into {text}");
+    }
+}
+
+#[test]
+fn redacts_signed_rest_requests() {
+    let capture = capture(CaptureConfig::default());
+
+    capture.record(
+        Direction::Sent,
+        "/0/private/AddOrder",
+        [
+            ("API-Key", API_KEY),
+            ("api-sign", "c2lnbmF0dXJl"),
+            ("Content-Type", "application/x-www-form-urlencoded"),
+        ],
+        "nonce=1704283200000&ordertype=limit&type=buy&pair=SOLGBP&cl_ord_id=abc",
+    );
+
+    let entry = &capture.recent(1)[0];
+    assert_eq!(entry.direction, Direction::Sent);
+    assert_eq!(entry.endpoint, "/0/private/AddOrder");
+    assert_eq!(
+        entry.headers,
+        [
+            ("API-Key".to_string(), "<redacted>".to_string()),
+            ("api-sign".to_string(), "<redacted>".to_string()),
+            (
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded".to_string()
+            ),
+        ]
+    );
+    // Everything but secrets is kept byte for byte.
+    assert_eq!(
+        entry.payload,
+        "nonce=1704283200000&ordertype=limit&type=buy&pair=SOLGBP&cl_ord_id=abc"
+    );
+    assert_no_secrets(&serde_json::to_string(entry).unwrap());
+}
+
+#[test]
+fn redacts_tokens_in_websocket_and_token_payloads() {
+    let capture = capture(CaptureConfig::default());
+
+    capture.record(
+        Direction::Sent,
+        "executions",
+        [],
+        &format!(
+            r#"{{"method":"subscribe","params":{{"channel":"executions","token": "{WS_TOKEN}"}}}}"#
+        ),
+    );
+    capture.record(
+        Direction::Received,
+        "/0/private/GetWebSocketsToken",
+        [],
+        &format!(r#"{{"error":[],"result":{{"token":"{WS_TOKEN}","expires":900}}}}"#),
+    );
+    // A token the capture was never told about is still redacted by its field name.
+    capture.record(
+        Direction::Sent,
+        "balances",
+        [],
+        r#"{"params":{"token":"unregistered\"token","channel":"balances"}}"#,
+    );
+    capture.record(Direction::Sent, "form", [], "nonce=1&token=unregistered");
+    // And a known secret is scrubbed wherever it appears.
+    capture.record(
+        Direction::Received,
+        "executions",
+        [],
+        &format!(r#"{{"echo":"{API_KEY}"}}"#),
+    );
+
+    let payloads: Vec<_> = capture
+        .recent(10)
+        .into_iter()
+        .rev()
+        .map(|entry| entry.payload)
+        .collect();
+    assert_eq!(
+        payloads,
+        [
+            r#"{"method":"subscribe","params":{"channel":"executions","token": "<redacted>"}}"#,
+            r#"{"error":[],"result":{"token":"<redacted>","expires":900}}"#,
+            r#"{"params":{"token":"<redacted>","channel":"balances"}}"#,
+            "nonce=1&token=<redacted>",
+            r#"{"echo":"<redacted>"}"#,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn tags_entries_with_the_current_cycle() {
+    let capture = capture(CaptureConfig::default());
+
+    cycles::scope(42, async {
+        capture.record(Direction::Sent, "/0/private/AddOrder", [], "nonce=1");
+    })
+    .await;
+    capture.record(Direction::Received, "executions", [], "{}");
+
+    let entries = capture.recent(2);
+    assert_eq!(entries[0].cycle_id, None);
+    assert_eq!(entries[1].cycle_id, Some(42));
+}
+
+#[test]
+fn keeps_only_the_latest_entries() {
+    let capture = capture(CaptureConfig {
+        capacity: 3,
+        ..CaptureConfig::default()
+    });
+
+    for index in 0..5 {
+        capture.record(Direction::Received, "market", [], &index.to_string());
+    }
+
+    let payloads: Vec<_> = capture
+        .recent(10)
+        .into_iter()
+        .map(|entry| entry.payload)
+        .collect();
+    assert_eq!(payloads, ["4", "3", "2"]);
+}
+
+#[test]
+fn rotates_the_capture_file_at_its_size_cap() {
+    let path = capture_path("capture.jsonl");
+    let rotated = PathBuf::from(format!("{}.1", path.display()));
+    let _ = std::fs::remove_file(&rotated);
+
+    let capture = capture(CaptureConfig {
+        enabled: true,
+        path: Some(path.clone()),
+        max_file_bytes: 600,
+        ..CaptureConfig::default()
+    });
+    for index in 0..6 {
+        capture.record(
+            Direction::Sent,
+            "executions",
+            [],
+            &format!(r#"{{"index":{index},"token":"{WS_TOKEN}"}}"#),
+        );
+    }
+
+    let current = std::fs::read_to_string(&path).unwrap();
+    let previous = std::fs::read_to_string(&rotated).unwrap();
+    assert!(current.len() <= 600 && previous.len() <= 600);
+    assert_eq!(current.lines().count() + previous.lines().count(), 6);
+    assert!(current.lines().last().unwrap().contains(r#"\"index\":5"#));
+    assert_no_secrets(&current);
+    assert_no_secrets(&previous);
+}
+
+#[test]
+fn capture_is_a_no_op_until_installed() {
+    capture::sent("/0/private/AddOrder", [("API-Key", API_KEY)], "nonce=1");
+    capture::received("executions", "{}");
+
+    assert!(!capture::is_enabled());
+    assert_eq!(capture::recent(10), None);
+}
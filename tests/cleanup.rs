@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::sync::broadcast;
+
+use accumulator::cli::cleanup::cleanup;
+use accumulator::cli::output::{CancelOutcome, Output, OutputFormat};
+use accumulator::engine::supervisor::Supervisor;
+use accumulator::execution::dry_run::DryRunExecutionVenue;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::execution::order_action::{Order, OrderAction, OrderType, Side};
+use accumulator::execution::types::OpenOrder;
+use accumulator::execution::{DynamicInventorySource, ExecutionVenue, ReportSender};
+use accumulator::inventory::simulated::SimulatedInventory;
+use accumulator::random::SeededRng;
+use accumulator::scenario::scenario::DynamicVenue;
+use accumulator::scenario::venues::VenueConfig;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+
+/// Seed whose first two placements are both accepted.
+const SEED_TWO_ACCEPTED: u64 = 1;
+
+fn order(order_id: &str, instrument: &Instrument, side: Side, price: f64) -> Order {
+    Order {
+        order_id: order_id.to_string(),
+        instrument: instrument.clone(),
+        side,
+        price: Price::new(price),
+        quantity: Quantity::new(0.05).unwrap(),
+        order_type: OrderType::POST_ONLY,
+        cycle_id: None,
+    }
+}
+
+fn dry_run_config(paper: Inventory) -> VenueConfig {
+    let mut config = VenueConfig::default();
+    config.inventory.paper_base = paper.base;
+    config.inventory.paper_quote = paper.quote;
+    config
+}
+
+#[tokio::test]
+async fn cancels_every_open_order_and_reports_the_balances() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let paper = Inventory::new(1.0, 500.0);
+    let (reports, _) = broadcast::channel(64);
+    let venue: DynamicVenue = Box::new(
+        DryRunExecutionVenue::new(reports.clone(), SeededRng::new(SEED_TWO_ACCEPTED))
+            .with_paper_inventory(paper),
+    );
+    venue
+        .execute(&[
+            OrderAction::Place(order("b1", &instrument, Buy, 92.90)),
+            OrderAction::Place(order("s1", &instrument, Sell, 93.10)),
+        ])
+        .await
+        .unwrap();
+
+    let summary = cleanup(
+        &venue,
+        &reports,
+        std::slice::from_ref(&instrument),
+        &dry_run_config(paper),
+        Duration::from_secs(1),
+    )
+    .await
+    .unwrap();
+
+    assert!(!summary.failed(), "{summary:?}");
+    assert!(venue.open_orders(&instrument).await.unwrap().is_empty());
+    let json: serde_json::Value =
+        serde_json::from_str(&Output::Cleanup(summary).render(OutputFormat::Json).unwrap())
+            .unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "orders": [
+                {
+                    "order_id": "b1",
+                    "instrument": "SOL/GBP",
+                    "side": "buy",
+                    "price": 92.9,
+                    "remaining": 0.05,
+                    "outcome": "cancelled",
+                },
+                {
+                    "order_id": "s1",
+                    "instrument": "SOL/GBP",
+                    "side": "sell",
+                    "price": 93.1,
+                    "remaining": 0.05,
+                    "outcome": "cancelled",
+                },
+            ],
+            "inventories": [{ "instrument": "SOL/GBP", "base": 1.0, "quote": 500.0 }],
+            "errors": [],
+        })
+    );
+}
+
+/// Lists one resting order and never answers its cancel.
+struct SilentVenue {
+    resting: Order,
+}
+
+#[async_trait]
+impl ExecutionVenue for SilentVenue {
+    async fn execute(&self, _actions: &[OrderAction]) -> Result<()> {
+        Ok(())
+    }
+
+    async fn open_orders(&self, _instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        Ok(vec![OpenOrder::resting(&self.resting, Quantity::ZERO)])
+    }
+
+    async fn spawn_reports(
+        &self,
+        _on_report: ReportSender,
+        _supervisor: &Supervisor,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        _supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        Ok(Box::new(SimulatedInventory::new(
+            instrument,
+            Inventory::default(),
+        )))
+    }
+}
+
+#[tokio::test]
+async fn an_unanswered_cancel_fails_the_cleanup() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let (reports, _) = broadcast::channel(64);
+    let venue: DynamicVenue = Box::new(SilentVenue {
+        resting: order("b1", &instrument, Buy, 92.90),
+    });
+
+    let summary = cleanup(
+        &venue,
+        &reports,
+        std::slice::from_ref(&instrument),
+        &VenueConfig::default(),
+        Duration::from_millis(50),
+    )
+    .await
+    .unwrap();
+
+    assert!(summary.failed());
+    assert_eq!(summary.orders[0].outcome, CancelOutcome::Unconfirmed);
+    let table = Output::Cleanup(summary)
+        .render(OutputFormat::Table)
+        .unwrap();
+    assert!(table.contains("b1"), "{table}");
+    assert!(table.contains("unconfirmed"), "{table}");
+}
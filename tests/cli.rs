@@ -0,0 +1,443 @@
+use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate};
+use serde_json::{Value, json};
+
+use accumulator::cli::args::{
+    Cli, Command, ConfigCommand, FillsCommand, OrderCommand, VenueCommand,
+};
+use accumulator::cli::commands::{self, VenueOps};
+use accumulator::cli::output::{Balance, OpenOrderInfo, OutputFormat, PlacedOrder};
+use accumulator::config::app_config::AppConfig;
+use accumulator::execution::order_action::{Order, Side};
+use accumulator::market::market_source::MarketSourceKind;
+use accumulator::scenario::venues::VenueKind;
+use accumulator::stats::fill_ledger::{DateRange, FillLedger, LedgerFill};
+
+fn parse(args: &[&str]) -> Cli {
+    Cli::try_parse_args(std::iter::once("accumulator").chain(args.iter().copied()))
+        .unwrap_or_else(|error| panic!("{args:?}: {error}"))
+}
+
+fn parse_error(args: &[&str]) -> String {
+    Cli::try_parse_args(std::iter::once("accumulator").chain(args.iter().copied()))
+        .expect_err("should not parse")
+        .to_string()
+}
+
+#[test]
+fn runs_the_engine_without_a_subcommand() {
+    let Command::Run(args) = parse(&["--venue", "kraken", "--seed", "7"]).into_command() else {
+        panic!("expected run");
+    };
+    assert_eq!(args.venue, Some(VenueKind::Kraken));
+    assert_eq!(args.seed, Some(7));
+
+    let Command::Run(args) = parse(&["run", "--seed", "7"]).into_command() else {
+        panic!("expected run");
+    };
+    assert_eq!(args.seed, Some(7));
+}
+
+#[test]
+fn prices_off_another_source_than_the_venue() {
+    let Command::Run(args) =
+        parse(&["--venue", "kraken", "--market-source", "coinbase"]).into_command()
+    else {
+        panic!("expected run");
+    };
+    let mut config = AppConfig::default();
+    args.apply(&mut config);
+    assert_eq!(config.venue.kind, VenueKind::Kraken);
+    assert_eq!(config.market.source, MarketSourceKind::Coinbase);
+    config.validate().unwrap();
+
+    // Depth is a Kraken subscription.
+    config.market.book_depth = Some(10);
+    assert_eq!(
+        config.validate().unwrap_err().to_string(),
+        "market.book_depth: only available with the kraken source"
+    );
+}
+
+#[test]
+fn serves_metrics_on_localhost_unless_bound_elsewhere() {
+    let mut config = AppConfig::default();
+    assert_eq!(config.metrics.bind, IpAddr::from([127, 0, 0, 1]));
+
+    let Command::Run(args) =
+        parse(&["--metrics-port", "9100", "--metrics-bind", "0.0.0.0"]).into_command()
+    else {
+        panic!("expected run");
+    };
+    args.apply(&mut config);
+    assert_eq!(config.metrics.port, Some(9100));
+    assert_eq!(config.metrics.bind, IpAddr::from([0, 0, 0, 0]));
+}
+
+#[test]
+fn leads_order_ids_with_a_prefix_from_the_command_line() {
+    let mut config = AppConfig::default();
+    let Command::Run(args) = parse(&["--order-id-prefix", "3f9c"]).into_command() else {
+        panic!("expected run");
+    };
+    args.apply(&mut config);
+    assert_eq!(config.venue.order_id_prefix.as_deref(), Some("3f9c"));
+    config.validate().unwrap();
+
+    let Command::Run(args) = parse(&["--order-id-prefix", "ACC-1"]).into_command() else {
+        panic!("expected run");
+    };
+    args.apply(&mut config);
+    assert_eq!(
+        config.validate().unwrap_err().to_string(),
+        "venue.order_id_prefix: must be 1 to 4 lowercase letters or digits"
+    );
+}
+
+#[test]
+fn parses_one_shot_commands_with_global_flags_on_either_side() {
+    let cli = parse(&["--output", "json", "--config", "prod.yml", "cancel-all"]);
+    assert_eq!(cli.output, OutputFormat::Json);
+    assert_eq!(cli.config.as_deref(), Some("prod.yml".as_ref()));
+    assert!(matches!(
+        cli.into_command(),
+        Command::Venue(VenueCommand::CancelAll)
+    ));
+
+    let Command::Cleanup(args) =
+        parse(&["--output", "json", "cleanup", "--venue", "kraken"]).into_command()
+    else {
+        panic!("expected cleanup");
+    };
+    assert_eq!(args.venue, Some(VenueKind::Kraken));
+
+    let cli = parse(&["config", "check", "--output", "json"]);
+    assert_eq!(cli.output, OutputFormat::Json);
+    assert!(matches!(
+        cli.into_command(),
+        Command::Config(ConfigCommand::Check)
+    ));
+
+    let Command::Venue(VenueCommand::Order(OrderCommand::Place {
+        instrument,
+        side,
+        price,
+        quantity,
+        order_id,
+    })) = parse(&[
+        "order",
+        "place",
+        "--instrument",
+        "sol/gbp",
+        "--side",
+        "sell",
+        "--price",
+        "93.5",
+        "--quantity",
+        "0.1",
+    ])
+    .into_command()
+    else {
+        panic!("expected order place");
+    };
+    assert_eq!(instrument.symbol(), "SOL/GBP");
+    assert_eq!(
+        (side, price, quantity, order_id),
+        (Side::Sell, 93.5, 0.1, None)
+    );
+}
+
+#[test]
+fn rejects_engine_flags_with_a_subcommand() {
+    assert!(
+        parse_error(&["--venue", "kraken", "balances"]).contains("--venue only applies to `run`")
+    );
+    assert!(parse_error(&["--seed", "1", "run"]).contains("--seed must come after `run`"));
+    assert!(
+        parse_error(&["--venue", "kraken", "cleanup"])
+            .contains("--venue must come after `cleanup`")
+    );
+    assert!(parse_error(&["order", "place", "--side", "buy"]).contains("--instrument"));
+}
+
+struct MockVenue;
+
+#[async_trait]
+impl VenueOps for MockVenue {
+    async fn cancel_all(&self) -> Result<i64> {
+        Ok(2)
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        Ok(vec![
+            Balance {
+                asset: "SOL".to_string(),
+                balance: 1.5,
+            },
+            Balance {
+                asset: "ZGBP".to_string(),
+                balance: 250.0,
+            },
+        ])
+    }
+
+    async fn open_orders(&self) -> Result<Vec<OpenOrderInfo>> {
+        Ok(vec![OpenOrderInfo {
+            txid: "OABC-1".to_string(),
+            order_id: Some("cl-1".to_string()),
+            pair: "SOLGBP".to_string(),
+            side: Side::Buy,
+            price: 93.0,
+            quantity: 0.05,
+            filled: 0.0,
+        }])
+    }
+
+    async fn place(&self, order: &Order) -> Result<PlacedOrder> {
+        Ok(PlacedOrder {
+            order_id: order.order_id.clone(),
+            txid: vec!["OABC-2".to_string()],
+            description: format!(
+                "{} {} {} @ limit {}",
+                order.side, order.quantity, order.instrument, order.price
+            ),
+        })
+    }
+
+    async fn cancel(&self, _order_id: &str) -> Result<i64> {
+        Ok(1)
+    }
+
+    async fn fills(&self, _range: DateRange) -> Result<FillLedger> {
+        Ok(ledger())
+    }
+}
+
+fn fill(
+    time: &str,
+    pair: &str,
+    side: Side,
+    (quantity, price, fee, value): (f64, f64, f64, f64),
+    trade_id: &str,
+) -> LedgerFill {
+    let (base, quote) = pair.split_once('/').unwrap();
+    LedgerFill {
+        time: DateTime::parse_from_rfc3339(time).unwrap().to_utc(),
+        base: base.to_string(),
+        quote: quote.to_string(),
+        side,
+        quantity,
+        price,
+        fee,
+        fee_currency: quote.to_string(),
+        value,
+        trade_id: trade_id.to_string(),
+        order_id: format!("O{trade_id}"),
+    }
+}
+
+/// Two months of fills, out of order, with one either side of January and February.
+fn ledger() -> FillLedger {
+    FillLedger::new(vec![
+        fill(
+            "2024-02-01T00:00:00Z",
+            "BTC/GBP",
+            Side::Buy,
+            (0.00012, 41_250.5, 0.0198, 4.95006),
+            "T4",
+        ),
+        fill(
+            "2024-01-15T10:30:00.250Z",
+            "SOL/GBP",
+            Side::Buy,
+            (0.05, 93.12, 0.0186, 4.656),
+            "T2",
+        ),
+        fill(
+            "2023-12-31T23:59:59Z",
+            "SOL/GBP",
+            Side::Sell,
+            (0.05, 92.5, 0.0185, 4.625),
+            "T1",
+        ),
+        fill(
+            "2024-03-01T00:00:00Z",
+            "SOL/GBP",
+            Side::Sell,
+            (0.1, 101.5, 0.0406, 10.15),
+            "T5",
+        ),
+        fill(
+            "2024-01-31T23:59:59.900Z",
+            "SOL/GBP",
+            Side::Sell,
+            (0.05, 93.4, 0.01868, 4.67),
+            "T3",
+        ),
+    ])
+}
+
+fn export_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("accumulator-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+async fn json_output(command: VenueCommand) -> Value {
+    let output = commands::execute(&command, &MockVenue).await.unwrap();
+    serde_json::from_str(&output.render(OutputFormat::Json).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn prints_venue_results_as_json() {
+    assert_eq!(
+        json_output(VenueCommand::CancelAll).await,
+        json!({ "cancelled": 2 })
+    );
+    assert_eq!(
+        json_output(VenueCommand::Balances).await,
+        json!([
+            { "asset": "SOL", "balance": 1.5 },
+            { "asset": "ZGBP", "balance": 250.0 },
+        ])
+    );
+    assert_eq!(
+        json_output(VenueCommand::OpenOrders).await,
+        json!([{
+            "txid": "OABC-1",
+            "order_id": "cl-1",
+            "pair": "SOLGBP",
+            "side": "buy",
+            "price": 93.0,
+            "quantity": 0.05,
+            "filled": 0.0,
+        }])
+    );
+    assert_eq!(
+        json_output(VenueCommand::Order(OrderCommand::Cancel {
+            order_id: "cl-1".to_string()
+        }))
+        .await,
+        json!({ "order_id": "cl-1", "cancelled": 1 })
+    );
+}
+
+#[tokio::test]
+async fn rounds_manual_orders_to_the_trading_rules() {
+    let placed = json_output(VenueCommand::Order(OrderCommand::Place {
+        instrument: "SOL/GBP".parse().unwrap(),
+        side: Side::Buy,
+        price: 93.129,
+        quantity: 0.057,
+        order_id: Some("manual-1".to_string()),
+    }))
+    .await;
+
+    assert_eq!(
+        placed,
+        json!({
+            "order_id": "manual-1",
+            "txid": ["OABC-2"],
+            "description": "BUY 0.05 SOL/GBP @ limit 93.12",
+        })
+    );
+}
+
+#[test]
+fn prints_a_config_summary() {
+    let output = commands::check_config(&AppConfig::default()).unwrap();
+
+    let table = output.render(OutputFormat::Table).unwrap();
+    assert!(table.starts_with("CONFIG"), "{table}");
+    assert!(table.contains("instruments  SOL/GBP"), "{table}");
+
+    let json: Value = serde_json::from_str(&output.render(OutputFormat::Json).unwrap()).unwrap();
+    assert_eq!(json["venue"], "dry-run");
+    assert_eq!(json["instruments"], json!(["SOL/GBP"]));
+    assert!(json["config"].is_object());
+}
+
+#[test]
+fn renders_the_ledger_as_the_golden_csv() {
+    assert_eq!(ledger().to_csv(), include_str!("golden/fills.csv"));
+}
+
+#[test]
+fn parses_fills_export() {
+    let Command::Venue(VenueCommand::Fills(FillsCommand::Export {
+        from,
+        to,
+        out,
+        by_month,
+    })) = parse(&[
+        "fills",
+        "export",
+        "--from",
+        "2024-01-01",
+        "--to",
+        "2024-02-29",
+        "--out",
+        "exports",
+        "--by-month",
+    ])
+    .into_command()
+    else {
+        panic!("expected fills export");
+    };
+
+    assert_eq!(from, NaiveDate::from_ymd_opt(2024, 1, 1));
+    assert_eq!(to, NaiveDate::from_ymd_opt(2024, 2, 29));
+    assert_eq!(out, PathBuf::from("exports"));
+    assert!(by_month);
+}
+
+#[tokio::test]
+async fn exports_fills_in_range_split_by_month() {
+    let dir = export_dir("fills-by-month");
+    let command = VenueCommand::Fills(FillsCommand::Export {
+        from: NaiveDate::from_ymd_opt(2024, 1, 1),
+        to: NaiveDate::from_ymd_opt(2024, 2, 29),
+        out: dir.clone(),
+        by_month: true,
+    });
+
+    let output = commands::execute(&command, &MockVenue).await.unwrap();
+    let json: Value = serde_json::from_str(&output.render(OutputFormat::Json).unwrap()).unwrap();
+    assert_eq!(
+        json,
+        json!([
+            { "path": dir.join("fills-2024-01.csv").display().to_string(), "fills": 2 },
+            { "path": dir.join("fills-2024-02.csv").display().to_string(), "fills": 1 },
+        ])
+    );
+
+    assert_eq!(
+        fs::read_to_string(dir.join("fills-2024-01.csv")).unwrap(),
+        include_str!("golden/fills-2024-01.csv")
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("fills-2024-02.csv")).unwrap(),
+        include_str!("golden/fills-2024-02.csv")
+    );
+}
+
+#[tokio::test]
+async fn rejects_an_inverted_date_range() {
+    let command = VenueCommand::Fills(FillsCommand::Export {
+        from: NaiveDate::from_ymd_opt(2024, 2, 1),
+        to: NaiveDate::from_ymd_opt(2024, 1, 1),
+        out: export_dir("fills-inverted").join("fills.csv"),
+        by_month: false,
+    });
+
+    let error = commands::execute(&command, &MockVenue).await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "--from 2024-02-01 is after --to 2024-01-01"
+    );
+}
@@ -0,0 +1,209 @@
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+use accumulator::coinbase::coinbase_market::{CoinbaseMarket, CoinbaseStream};
+use accumulator::events::MarketEvent;
+use accumulator::market::market_source::{MarketDataSource, SubscriptionRejected};
+use accumulator::types::instrument::Instrument;
+use accumulator::types::price::Price;
+
+fn sol() -> Instrument {
+    "SOL/GBP".parse().unwrap()
+}
+
+fn ticker(sequence: u64, product_id: &str, bid: &str, ask: &str) -> String {
+    json!({
+        "channel": "ticker",
+        "client_id": "",
+        "timestamp": "2024-01-03T12:00:00.250Z",
+        "sequence_num": sequence,
+        "events": [{
+            "type": "update",
+            "tickers": [{
+                "type": "ticker",
+                "product_id": product_id,
+                "price": "93.05",
+                "best_bid": bid,
+                "best_bid_quantity": "12.5",
+                "best_ask": ask,
+                "best_ask_quantity": "3.0",
+            }],
+        }],
+    })
+    .to_string()
+}
+
+fn trades(sequence: u64, kind: &str) -> String {
+    json!({
+        "channel": "market_trades",
+        "client_id": "",
+        "timestamp": "2024-01-03T12:00:01.000Z",
+        "sequence_num": sequence,
+        "events": [{
+            "type": kind,
+            // Newest first, as Coinbase sends them.
+            "trades": [
+                { "trade_id": "2", "product_id": "SOL-GBP", "price": "93.10", "size": "0.5", "side": "BUY", "time": "2024-01-03T12:00:00.900Z" },
+                { "trade_id": "1", "product_id": "SOL-GBP", "price": "93.00", "size": "1.25", "side": "SELL", "time": "2024-01-03T12:00:00.500Z" },
+            ],
+        }],
+    })
+    .to_string()
+}
+
+#[test]
+fn subscribes_to_the_ticker_trades_and_heartbeats_of_the_product() {
+    let subscriptions = CoinbaseMarket::default().subscriptions(&sol());
+    assert_eq!(
+        subscriptions[0],
+        json!({"type": "subscribe", "product_ids": ["SOL-GBP"], "channel": "ticker"})
+    );
+    let channels: Vec<_> = subscriptions
+        .iter()
+        .map(|subscription| subscription["channel"].clone())
+        .collect();
+    assert_eq!(channels, ["ticker", "market_trades", "heartbeats"]);
+}
+
+#[test]
+fn parses_the_ticker_as_the_top_of_the_book() {
+    let mut stream = CoinbaseStream::new();
+
+    let events = stream
+        .parse(&sol(), &ticker(0, "SOL-GBP", "93.00", "93.10"))
+        .unwrap();
+    assert!(
+        matches!(
+            events[..],
+            [MarketEvent::TopOfBook { best_bid, best_ask, timestamp_ms: 1_704_283_200_250, .. }]
+                if best_bid == Price::new(93.00) && best_ask == Price::new(93.10)
+        ),
+        "{events:?}"
+    );
+
+    let other = stream
+        .parse(&sol(), &ticker(1, "ETH-GBP", "2000.00", "2000.10"))
+        .unwrap();
+    assert!(other.is_empty(), "{other:?}");
+}
+
+#[test]
+fn parses_new_trades_oldest_first_and_skips_the_snapshot() {
+    let mut stream = CoinbaseStream::new();
+
+    assert!(
+        stream
+            .parse(&sol(), &trades(0, "snapshot"))
+            .unwrap()
+            .is_empty()
+    );
+
+    let events = stream.parse(&sol(), &trades(1, "update")).unwrap();
+    let parsed: Vec<_> = events
+        .iter()
+        .map(|event| match event {
+            MarketEvent::Trade {
+                price,
+                quantity,
+                timestamp_ms,
+                ..
+            } => (price.as_f64(), *quantity, *timestamp_ms),
+            other => panic!("expected a trade, got {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        parsed,
+        [
+            (93.00, 1.25, 1_704_283_200_500),
+            (93.10, 0.5, 1_704_283_200_900)
+        ]
+    );
+}
+
+#[test]
+fn a_gap_in_the_sequence_is_an_error() {
+    let mut stream = CoinbaseStream::new();
+    let heartbeat = |sequence: u64| {
+        json!({ "channel": "heartbeats", "sequence_num": sequence, "events": [] }).to_string()
+    };
+
+    stream.parse(&sol(), &heartbeat(7)).unwrap();
+    stream.parse(&sol(), &heartbeat(8)).unwrap();
+    let error = stream.parse(&sol(), &heartbeat(10)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Coinbase websocket skipped messages: expected sequence 9, received 10"
+    );
+}
+
+#[test]
+fn an_error_message_rejects_the_subscription() {
+    let error = CoinbaseStream::new()
+        .parse(
+            &sol(),
+            r#"{"type":"error","message":"failure to subscribe"}"#,
+        )
+        .unwrap_err();
+
+    assert_eq!(
+        error.downcast_ref::<SubscriptionRejected>(),
+        Some(&SubscriptionRejected {
+            pair: "SOL-GBP".to_string(),
+            channel: "market data".to_string(),
+            reason: "failure to subscribe".to_string(),
+        })
+    );
+}
+
+/// A stand-in for Coinbase that sends `messages` once all three subscriptions are in,
+/// then keeps the connection open. Returns its url.
+async fn feed(messages: Vec<String>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+        for _ in 0..3 {
+            let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+            let request: Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(request["type"], "subscribe");
+        }
+        for message in messages {
+            ws.send(Message::Text(message)).await.unwrap();
+        }
+        while ws.next().await.is_some() {}
+    });
+
+    format!("ws://{address}")
+}
+
+#[tokio::test]
+async fn reconnects_when_messages_go_missing() {
+    let url = feed(vec![
+        ticker(0, "SOL-GBP", "93.00", "93.10"),
+        ticker(2, "SOL-GBP", "93.05", "93.15"),
+    ])
+    .await;
+    let market = CoinbaseMarket::new(url);
+    let (sender, mut events) = mpsc::channel(16);
+
+    let result = tokio::time::timeout(Duration::from_secs(2), market.subscribe(&sol(), sender))
+        .await
+        .expect("the gap ends the subscription");
+
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("skipped messages"), "{error:#}");
+    // Not a rejection, so the engine reconnects rather than shutting down.
+    assert!(error.downcast_ref::<SubscriptionRejected>().is_none());
+
+    let kinds: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+        .map(|event| event.kind())
+        .collect();
+    assert_eq!(kinds, ["reconnected", "book", "disconnected"]);
+}
@@ -0,0 +1,819 @@
+//! Scripted end-to-end harness: one [`InstrumentEngine`] against a [`MockVenue`], driven
+//! on a [`SimClock`] by a list of timed steps.
+//!
+//! A script is a sequence of `(ms, Step)`: market events and venue responses are applied
+//! at `ms` after the start. Action expectations check what the engine sent since the
+//! previous action expectation; state expectations check the engine's status.
+
+#![allow(dead_code)]
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use tokio::sync::{broadcast, watch};
+
+use accumulator::clock::SimClock;
+use accumulator::config::app_config::AppConfig;
+use accumulator::engine::heartbeat::Heartbeat;
+use accumulator::engine::instrument_engine::{InstrumentEngine, SharedContext};
+use accumulator::engine::supervisor::Supervisor;
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::{Order, OrderAction, Side};
+use accumulator::execution::order_history::OrderLifecycle;
+use accumulator::execution::order_ids::OrderIds;
+use accumulator::execution::order_report::{OrderReport, RejectKind};
+use accumulator::execution::types::{OpenOrder, OrderSideState};
+use accumulator::execution::{DynamicInventorySource, ExecutionVenue, ReportSender};
+use accumulator::inventory::InventorySource;
+use accumulator::risk::checks::kill_switch::KillSwitch;
+use accumulator::risk::checks::max_daily_loss::DailyLossState;
+use accumulator::scenario::scenario::DynamicVenue;
+use accumulator::scenario::strategies::StrategyKind;
+use accumulator::stats::fill_annotator::FillReport;
+use accumulator::stats::session_stats::{SessionStats, StatsHandle, StatsSummary};
+use accumulator::stats::session_summary::InstrumentSummary;
+use accumulator::stats::trading_book::TradingBook;
+use accumulator::telemetry::cycles::CycleIds;
+use accumulator::telemetry::decision_log::DecisionLog;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::pnl::PnlState;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+use accumulator::types::trading_rules::TradingRules;
+
+/// Wednesday 2024-01-03 12:00:00 UTC, inside every instrument's trading hours.
+pub const START_MS: u64 = 1_704_283_200_000;
+
+pub const INITIAL: Inventory = Inventory {
+    base: 1.0,
+    quote: 1_000.0,
+};
+
+/// `value` as a [`Quantity`], for sizes written out in a test.
+pub fn qty(value: f64) -> Quantity {
+    Quantity::new(value).unwrap()
+}
+
+/// One step of a script.
+#[derive(Debug, Clone)]
+pub enum Step {
+    Book {
+        bid: f64,
+        ask: f64,
+    },
+    Trade {
+        price: f64,
+        quantity: f64,
+    },
+    /// The market feed disconnects, or goes quiet and is dropped.
+    FeedLost,
+    /// The market feed connects again, before its first book.
+    FeedRestored,
+    /// The engine's evaluation timer fires.
+    Timer,
+    /// The venue accepts the order placed on this side.
+    Accept(Side),
+    Reject(Side),
+    /// The venue refuses the post-only place on this side for crossing its book.
+    RejectCrossing(Side),
+    /// The resting order on this side fills completely.
+    Fill(Side),
+    /// Part of the resting order on this side fills, as reported by the venue: the
+    /// latest fill and the order's cumulative filled quantity.
+    PartialFill {
+        side: Side,
+        quantity: f64,
+        cum_quantity: f64,
+    },
+    /// The venue drops every resting order, e.g. on a session reset.
+    VenueCancelAll,
+    /// The order on this side is cancelled without a report reaching the engine, e.g. by
+    /// hand on the venue's website while the executions feed was down.
+    SilentCancel(Side),
+    /// The engine compares its orders with the venue's open orders.
+    Reconcile,
+    /// A burst of this many reports for orders the engine never placed.
+    FloodReports(usize),
+    /// The venue's open orders query fails from now on, or works again.
+    OpenOrdersDown(bool),
+    /// Someone else's order on this side of the account is accepted, e.g. one placed by
+    /// hand on the venue's website.
+    ForeignAccept(Side),
+    /// That order fills completely.
+    ForeignFill(Side),
+    /// Venue responses sent back to back, with no report delivered in between.
+    Burst(Vec<Step>),
+    KillSwitch(bool),
+    /// What the engine does on shutdown, short of cancelling orders.
+    Shutdown,
+    Expect(Expect),
+}
+
+#[derive(Debug, Clone)]
+pub enum Expect {
+    /// Exactly these actions, in order, since the previous action expectation.
+    Actions(Vec<Act>),
+    /// No actions since the previous expectation.
+    Nothing,
+    MarketStale(bool),
+    /// Whether each side (bid, ask) has an order placing, resting or cancelling.
+    Working {
+        bid: bool,
+        ask: bool,
+    },
+    /// Quantity the engine believes is still resting on this side.
+    Remaining(Side, f64),
+}
+
+/// An action reduced to what a script asserts on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Act {
+    Place(Side),
+    Cancel(Side),
+    CancelAll,
+    Amend(Side),
+}
+
+impl Act {
+    fn of(action: &OrderAction) -> Self {
+        match action {
+            OrderAction::Place(order) => Act::Place(order.side),
+            OrderAction::Cancel { side, .. } => Act::Cancel(*side),
+            OrderAction::CancelAll => Act::CancelAll,
+            OrderAction::Amend { side, .. } => Act::Amend(*side),
+        }
+    }
+}
+
+pub struct Harness {
+    config: AppConfig,
+    engine: InstrumentEngine,
+    mock: MockVenue,
+    venue: DynamicVenue,
+    clock: SimClock,
+    reports: broadcast::Receiver<OrderReport>,
+    stats: Arc<Mutex<SessionStats>>,
+    kill_switch: KillSwitch,
+    checked: usize,
+}
+
+impl Harness {
+    /// A simple_mm engine for the first of `config.instruments`, SOL/GBP unless
+    /// `configure` changes them, with `configure` applied to the default config. Order
+    /// ids are `sim-<n>` unless `venue.order_id_prefix` is set.
+    /// Signal time constants are cut to one second so scripts warm up after a second of
+    /// books.
+    pub async fn new(configure: impl FnOnce(&mut AppConfig)) -> Result<Self> {
+        let mut config = AppConfig::default();
+        config.strategy.kind = StrategyKind::SimpleMarketMaker;
+        config.signals.fast_tau_secs = Some(1.0);
+        config.signals.slow_tau_secs = Some(1.0);
+        config.signals.vol_tau_secs = Some(1.0);
+        configure(&mut config);
+
+        let instrument = config.instruments[0].load()?;
+        let (sender, reports) = broadcast::channel(config.channels.order_reports);
+        let mock = MockVenue::new(sender.clone(), INITIAL);
+        let venue: DynamicVenue = Box::new(mock.clone());
+        let clock = SimClock::from_timestamp_ms(START_MS);
+
+        let shared = SharedContext {
+            kill_switch: KillSwitch::new(false),
+            portfolio: None,
+            scoped_cancels: false,
+            clock: clock.shared(),
+            order_ids: match &config.venue.order_id_prefix {
+                Some(prefix) => OrderIds::with_prefix(prefix, 1),
+                None => OrderIds::sequential(),
+            },
+            cycle_ids: CycleIds::default(),
+            supervisor: Supervisor::new().0,
+            fill_report: config
+                .stats
+                .fill_report
+                .as_deref()
+                .map(FillReport::open)
+                .transpose()?,
+            decision_log: config
+                .logging
+                .decision_log
+                .as_deref()
+                .map(DecisionLog::open)
+                .transpose()?,
+        };
+        let stats = Arc::new(Mutex::new(SessionStats::new(
+            clock.shared(),
+            TradingBook::default(),
+            config.stats.equity_curve(),
+        )));
+
+        let engine = InstrumentEngine::build(
+            &config,
+            instrument.clone(),
+            &venue,
+            &sender,
+            &shared,
+            StatsHandle::inline(&instrument, Arc::clone(&stats)),
+        )
+        .await?;
+
+        Ok(Self {
+            config,
+            engine,
+            mock,
+            venue,
+            clock,
+            reports,
+            stats,
+            kill_switch: shared.kill_switch,
+            checked: 0,
+        })
+    }
+
+    pub async fn run(&mut self, script: &[(u64, Step)]) -> Result<()> {
+        for (index, (at_ms, step)) in script.iter().enumerate() {
+            let timestamp_ms = START_MS + at_ms;
+            self.clock.set_timestamp_ms(timestamp_ms);
+
+            match step {
+                Step::Book { bid, ask } => {
+                    let event = MarketEvent::TopOfBook {
+                        instrument: self.engine.instrument().clone(),
+                        best_bid: Price::new(*bid),
+                        best_ask: Price::new(*ask),
+                        bid_size: None,
+                        ask_size: None,
+                        timestamp_ms,
+                    };
+                    self.venue.on_market_event(&event);
+                    self.engine.on_market_event(&event, &self.venue).await?;
+                }
+                Step::Trade { price, quantity } => {
+                    let event = MarketEvent::Trade {
+                        instrument: self.engine.instrument().clone(),
+                        price: Price::new(*price),
+                        quantity: *quantity,
+                        timestamp_ms,
+                    };
+                    self.venue.on_market_event(&event);
+                    self.engine.on_market_event(&event, &self.venue).await?;
+                }
+                Step::FeedLost => {
+                    let event = MarketEvent::SourceDisconnected {
+                        instrument: self.engine.instrument().clone(),
+                        timestamp_ms,
+                    };
+                    self.engine.on_market_event(&event, &self.venue).await?;
+                }
+                Step::FeedRestored => {
+                    let event = MarketEvent::SourceReconnected {
+                        instrument: self.engine.instrument().clone(),
+                        timestamp_ms,
+                    };
+                    self.engine.on_market_event(&event, &self.venue).await?;
+                }
+                Step::Timer => self.engine.on_timer(&self.venue).await?,
+                Step::Accept(_)
+                | Step::Reject(_)
+                | Step::RejectCrossing(_)
+                | Step::Fill(_)
+                | Step::PartialFill { .. }
+                | Step::VenueCancelAll
+                | Step::SilentCancel(_)
+                | Step::FloodReports(_)
+                | Step::OpenOrdersDown(_)
+                | Step::ForeignAccept(_)
+                | Step::ForeignFill(_) => self.mock.respond(step),
+                Step::Burst(steps) => steps.iter().for_each(|step| self.mock.respond(step)),
+                Step::KillSwitch(engaged) => self.kill_switch.set(*engaged),
+                Step::Shutdown => self.engine.finish_fill_report(),
+                Step::Reconcile => self.engine.reconcile(&self.venue).await?,
+                Step::Expect(expect) => self.check(index, *at_ms, expect),
+            }
+
+            self.deliver_reports().await;
+        }
+
+        Ok(())
+    }
+
+    /// Reloads the engine with `configure` applied to its current config.
+    pub fn reload(&mut self, configure: impl FnOnce(&mut AppConfig)) {
+        configure(&mut self.config);
+        self.engine.reload(&self.config);
+    }
+
+    /// Edits the instrument's trading rules and reloads, as a changed
+    /// `trading_rules.yml` would.
+    pub fn reload_trading_rules(&mut self, configure: impl FnOnce(&mut TradingRules)) {
+        let instrument = self.engine.instrument();
+        let mut rules = instrument.trading_rules();
+        configure(&mut rules);
+        instrument.set_trading_rules(rules);
+        self.engine.reload(&self.config);
+    }
+
+    pub fn actions(&self) -> Vec<OrderAction> {
+        self.mock.actions()
+    }
+
+    /// Leaves `order` working on the venue without the engine knowing of it.
+    pub fn rest(&self, order: Order) {
+        self.mock.rest(order);
+    }
+
+    /// Slows every action down by `latency` of real time, see [`MockVenue::execute_latency`].
+    pub fn venue_latency(&self, latency: Duration) {
+        self.mock.execute_latency(latency);
+    }
+
+    /// The stats window so far, as the periodic stats line would log it.
+    pub fn stats(&self) -> StatsSummary {
+        self.stats.lock().unwrap().summary()
+    }
+
+    /// Leaves `orders` resting on the venue, as a previous run would have, and has the
+    /// engine adopt them as at startup, with `logged` the ids its intent log left
+    /// unresolved, or `None` without an intent log.
+    pub async fn adopt(&mut self, orders: &[Order], logged: Option<&[&str]>) -> Result<()> {
+        for order in orders {
+            self.mock.rest(order.clone());
+        }
+        let logged: Option<HashSet<String>> =
+            logged.map(|logged| logged.iter().map(|order_id| order_id.to_string()).collect());
+        self.engine.adopt(&self.venue, logged.as_ref()).await
+    }
+
+    /// Lists `order` as open on the venue, as an earlier run would have left it.
+    pub fn leave_open(&self, order: OpenOrder) {
+        self.mock.leave_open(order);
+    }
+
+    /// The daily loss limit's count of the day, as the engine saves it.
+    pub fn daily_loss(&self) -> Option<DailyLossState> {
+        self.engine.daily_loss()
+    }
+
+    /// Carries on a count [`daily_loss`](Self::daily_loss) saved, as at a restart.
+    pub fn restore_daily_loss(&self, state: DailyLossState) {
+        self.engine.restore_daily_loss(state);
+    }
+
+    /// The engine's position and PnL, as it saves them.
+    pub fn pnl_state(&self) -> PnlState {
+        self.engine.pnl().state()
+    }
+
+    /// Carries on from a position [`pnl_state`](Self::pnl_state) saved, as at a restart.
+    pub fn restore_pnl(&mut self, state: PnlState) {
+        self.engine.restore_pnl(state);
+    }
+
+    /// The engine's heartbeat at the time of the last step.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.engine.heartbeat()
+    }
+
+    /// The engine's order history, most recent first.
+    pub fn recent_orders(&self) -> Vec<OrderLifecycle> {
+        self.engine.recent_orders(usize::MAX)
+    }
+
+    pub async fn summary(&self) -> InstrumentSummary {
+        self.engine
+            .summary()
+            .await
+            .expect("inline stats always answer")
+    }
+
+    /// Hands pending reports to the engine and lets background report consumers run.
+    /// Like the engine, resyncs orders from the venue when reports were missed.
+    async fn deliver_reports(&mut self) {
+        loop {
+            loop {
+                match self.reports.try_recv() {
+                    Ok(report) => {
+                        self.stats.lock().unwrap().on_report(&report);
+                        self.engine.on_report(report);
+                        self.engine.on_reprice(&self.venue).await.unwrap();
+                    }
+                    // A failed resync is left for the next cycle to retry, as in the
+                    // engine.
+                    Err(broadcast::error::TryRecvError::Lagged(_)) => {
+                        let _ = self.engine.resync(&self.venue).await;
+                    }
+                    Err(_) => break,
+                }
+            }
+            tokio::task::yield_now().await;
+            if self.reports.is_empty() {
+                break;
+            }
+        }
+    }
+
+    fn check(&mut self, index: usize, at_ms: u64, expect: &Expect) {
+        let context = format!("step {index} at {at_ms}ms");
+
+        match expect {
+            Expect::Actions(expected) => {
+                let new = self.take_new_actions();
+                assert_eq!(&new, expected, "{context}: unexpected actions");
+            }
+            Expect::Nothing => {
+                let new = self.take_new_actions();
+                assert!(
+                    new.is_empty(),
+                    "{context}: expected no actions, got {new:?}"
+                );
+            }
+            Expect::MarketStale(stale) => {
+                let status = self.engine.status();
+                assert_eq!(status.market.stale, *stale, "{context}: market staleness");
+            }
+            Expect::Working { bid, ask } => {
+                let status = self.engine.status();
+                let working = |state: &OrderSideState| !matches!(state, OrderSideState::NoOrder);
+                assert_eq!(
+                    (working(&status.orders.bid), working(&status.orders.ask)),
+                    (*bid, *ask),
+                    "{context}: working sides, orders {:?}",
+                    status.orders
+                );
+            }
+            Expect::Remaining(side, expected) => {
+                let status = self.engine.status();
+                let state = match side {
+                    Side::Buy => &status.orders.bid,
+                    Side::Sell => &status.orders.ask,
+                };
+                let remaining = match state {
+                    OrderSideState::Live { resting, .. }
+                    | OrderSideState::Cancelling { resting, .. } => resting.quantity,
+                    state => panic!("{context}: no {side} order resting: {state:?}"),
+                };
+                assert!(
+                    (remaining.as_f64() - expected).abs() < 1e-9,
+                    "{context}: {side} remaining {remaining}, expected {expected}"
+                );
+            }
+        }
+    }
+
+    fn take_new_actions(&mut self) -> Vec<Act> {
+        let actions = self.mock.actions();
+        let new = actions[self.checked..].iter().map(Act::of).collect();
+        self.checked = actions.len();
+        new
+    }
+}
+
+/// Venue that records every action and answers only when a script says so: places are
+/// acknowledged with `Placed` and then wait for an accept, reject or fill step. Cancels
+/// and amends succeed immediately.
+#[derive(Clone)]
+pub struct MockVenue {
+    reports: ReportSender,
+    state: Arc<Mutex<MockState>>,
+}
+
+struct MockState {
+    actions: Vec<OrderAction>,
+    /// Latest order placed on each side and not yet finished.
+    working: HashMap<Side, Order>,
+    /// Orders an earlier run left open, listed alongside `working` until cancelled.
+    left_open: Vec<OpenOrder>,
+    inventory: watch::Sender<Inventory>,
+    open_orders_down: bool,
+    execute_down: bool,
+    execute_latency: Duration,
+}
+
+impl MockVenue {
+    pub fn new(reports: ReportSender, initial: Inventory) -> Self {
+        Self {
+            reports,
+            state: Arc::new(Mutex::new(MockState {
+                actions: Vec::new(),
+                working: HashMap::new(),
+                left_open: Vec::new(),
+                inventory: watch::channel(initial).0,
+                open_orders_down: false,
+                execute_down: false,
+                execute_latency: Duration::ZERO,
+            })),
+        }
+    }
+
+    pub fn actions(&self) -> Vec<OrderAction> {
+        self.state.lock().unwrap().actions.clone()
+    }
+
+    /// Every action fails from now on, as if the venue were unreachable, or works again.
+    pub fn execute_down(&self, down: bool) {
+        self.state.lock().unwrap().execute_down = down;
+    }
+
+    /// Every action takes `latency` of real time to reach the venue from now on.
+    pub fn execute_latency(&self, latency: Duration) {
+        self.state.lock().unwrap().execute_latency = latency;
+    }
+
+    /// Leaves `order` working without it having been placed, or any reports sent.
+    pub fn rest(&self, order: Order) {
+        self.state.lock().unwrap().working.insert(order.side, order);
+    }
+
+    /// Lists `order` as open, as an earlier run would have left it, until it is cancelled.
+    /// Any number may share a side, unlike working orders.
+    pub fn leave_open(&self, order: OpenOrder) {
+        self.state.lock().unwrap().left_open.push(order);
+    }
+
+    fn working(&self, side: Side) -> Order {
+        self.state
+            .lock()
+            .unwrap()
+            .working
+            .get(&side)
+            .cloned()
+            .unwrap_or_else(|| panic!("no working {side} order"))
+    }
+
+    pub fn accept(&self, side: Side) {
+        let order = self.working(side);
+        self.send(OrderReport::Accepted {
+            order_id: order.order_id,
+            instrument: order.instrument,
+            side,
+            price: order.price,
+            quantity: order.quantity,
+        });
+    }
+
+    /// Accepts every order still working, placing or already accepted.
+    pub fn accept_working(&self) {
+        let sides: Vec<Side> = self.state.lock().unwrap().working.keys().copied().collect();
+        for side in sides {
+            self.accept(side);
+        }
+    }
+
+    pub fn reject(&self, side: Side) {
+        self.reject_as(side, RejectKind::Other, "rejected by script");
+    }
+
+    fn reject_as(&self, side: Side, kind: RejectKind, reason: &str) {
+        let order = self.working(side);
+        self.state.lock().unwrap().working.remove(&side);
+        self.send(OrderReport::Rejected {
+            order_id: order.order_id,
+            instrument: order.instrument,
+            side,
+            kind,
+            reason: reason.to_string(),
+        });
+    }
+
+    pub fn fill(&self, side: Side) {
+        let order = self.working(side);
+        {
+            let mut state = self.state.lock().unwrap();
+            state.working.remove(&side);
+            state.inventory.send_modify(|inventory| {
+                inventory.base += side.signed(order.quantity.as_f64());
+                inventory.quote -= side.signed(order.quantity.as_f64()) * order.price.as_f64();
+            });
+        }
+        self.send(OrderReport::Filled {
+            order_id: order.order_id,
+            instrument: order.instrument,
+            side,
+            price: order.price,
+            quantity: order.quantity,
+            cum_quantity: order.quantity,
+        });
+    }
+
+    /// Reports a partial fill without changing the mock's own balances.
+    pub fn partial_fill(&self, side: Side, quantity: f64, cum_quantity: f64) {
+        let order = self.working(side);
+        self.send(OrderReport::PartiallyFilled {
+            order_id: order.order_id,
+            instrument: order.instrument,
+            side,
+            price: order.price,
+            quantity: qty(quantity),
+            cum_quantity: qty(cum_quantity),
+        });
+    }
+
+    pub fn cancel_all(&self) {
+        let count = {
+            let mut state = self.state.lock().unwrap();
+            let count = (state.working.len() + state.left_open.len()) as i64;
+            state.working.clear();
+            state.left_open.clear();
+            count
+        };
+        self.send(OrderReport::CancelledAll { count });
+    }
+
+    /// Applies a venue response step.
+    pub fn respond(&self, step: &Step) {
+        match step {
+            Step::Accept(side) => self.accept(*side),
+            Step::Reject(side) => self.reject(*side),
+            Step::RejectCrossing(side) => self.reject_as(
+                *side,
+                RejectKind::PostOnlyWouldCross,
+                "EOrder:Post only order",
+            ),
+            Step::Fill(side) => self.fill(*side),
+            Step::PartialFill {
+                side,
+                quantity,
+                cum_quantity,
+            } => self.partial_fill(*side, *quantity, *cum_quantity),
+            Step::VenueCancelAll => self.cancel_all(),
+            Step::SilentCancel(side) => {
+                self.state.lock().unwrap().working.remove(side);
+            }
+            Step::FloodReports(count) => self.flood(*count),
+            Step::OpenOrdersDown(down) => self.state.lock().unwrap().open_orders_down = *down,
+            Step::ForeignAccept(side) => self.foreign(*side, false),
+            Step::ForeignFill(side) => self.foreign(*side, true),
+            step => panic!("{step:?} is not a venue response"),
+        }
+    }
+
+    pub fn flood(&self, count: usize) {
+        let instrument = InstrumentConfig::default().load().unwrap();
+        for index in 0..count {
+            self.send(OrderReport::Cancelled {
+                order_id: format!("flood-{index}"),
+                instrument: instrument.clone(),
+                side: Side::Buy,
+            });
+        }
+    }
+
+    /// Reports for `manual-<side>`, an order the engine never placed, a tick outside the
+    /// touch.
+    pub fn foreign(&self, side: Side, filled: bool) {
+        let order_id = format!("manual-{side}");
+        let instrument = InstrumentConfig::default().load().unwrap();
+        let (price, quantity) = (Price::new(92.50), qty(0.05));
+        self.send(if filled {
+            OrderReport::Filled {
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+                cum_quantity: quantity,
+            }
+        } else {
+            OrderReport::Accepted {
+                order_id,
+                instrument,
+                side,
+                price,
+                quantity,
+            }
+        });
+    }
+
+    fn send(&self, report: OrderReport) {
+        let _ = self.reports.send(report);
+    }
+}
+
+#[async_trait]
+impl ExecutionVenue for MockVenue {
+    async fn execute(&self, actions: &[OrderAction]) -> Result<()> {
+        let latency = self.state.lock().unwrap().execute_latency;
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.execute_down {
+                bail!("venue unreachable");
+            }
+            state.actions.extend_from_slice(actions);
+        }
+
+        for action in actions {
+            match action {
+                OrderAction::CancelAll => self.cancel_all(),
+                OrderAction::Cancel {
+                    order_id,
+                    instrument,
+                    side,
+                } => {
+                    {
+                        let mut state = self.state.lock().unwrap();
+                        if state
+                            .working
+                            .get(side)
+                            .is_some_and(|order| order.order_id == *order_id)
+                        {
+                            state.working.remove(side);
+                        }
+                        state.left_open.retain(|order| order.order_id != *order_id);
+                    }
+                    self.send(OrderReport::Cancel {
+                        order_id: order_id.clone(),
+                        instrument: instrument.clone(),
+                        side: *side,
+                    });
+                    self.send(OrderReport::Cancelled {
+                        order_id: order_id.clone(),
+                        instrument: instrument.clone(),
+                        side: *side,
+                    });
+                }
+                OrderAction::Place(order) => {
+                    self.state
+                        .lock()
+                        .unwrap()
+                        .working
+                        .insert(order.side, order.clone());
+                    self.send(OrderReport::Placed {
+                        order_id: order.order_id.clone(),
+                        instrument: order.instrument.clone(),
+                        side: order.side,
+                        price: order.price,
+                        quantity: order.quantity,
+                    });
+                }
+                OrderAction::Amend {
+                    order_id,
+                    instrument,
+                    side,
+                    new_price,
+                    new_quantity,
+                } => {
+                    if let Some(order) = self.state.lock().unwrap().working.get_mut(side) {
+                        order.price = *new_price;
+                        order.quantity = *new_quantity;
+                    }
+                    self.send(OrderReport::Amended {
+                        order_id: order_id.clone(),
+                        instrument: instrument.clone(),
+                        side: *side,
+                        price: *new_price,
+                        quantity: *new_quantity,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn open_orders(&self, _instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        let state = self.state.lock().unwrap();
+        if state.open_orders_down {
+            bail!("open orders query failed");
+        }
+        Ok(state
+            .working
+            .values()
+            .map(|order| OpenOrder::resting(order, Quantity::ZERO))
+            .chain(state.left_open.iter().cloned())
+            .collect())
+    }
+
+    async fn spawn_reports(
+        &self,
+        _on_report: ReportSender,
+        _supervisor: &Supervisor,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn spawn_inventory(
+        &self,
+        _instrument: &Instrument,
+        _supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        Ok(Box::new(MockInventory(
+            self.state.lock().unwrap().inventory.subscribe(),
+        )))
+    }
+}
+
+struct MockInventory(watch::Receiver<Inventory>);
+
+impl InventorySource for MockInventory {
+    fn subscribe(&self) -> watch::Receiver<Inventory> {
+        self.0.clone()
+    }
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+
+use common::{Harness, Step};
+
+#[derive(Debug, Clone, Default)]
+struct Fields(BTreeMap<String, String>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+/// An event's own fields, and those of the `cycle` span it was emitted in, if any.
+#[derive(Debug, Clone)]
+struct Captured {
+    fields: Fields,
+    cycle: Option<Fields>,
+}
+
+impl Captured {
+    fn message(&self) -> &str {
+        self.fields.0.get("message").map_or("", String::as_str)
+    }
+
+    fn cycle(&self, field: &str) -> &str {
+        let cycle = self
+            .cycle
+            .as_ref()
+            .unwrap_or_else(|| panic!("{:?} logged outside a cycle", self.message()));
+        cycle
+            .0
+            .get(field)
+            .unwrap_or_else(|| panic!("cycle span has no {field}"))
+    }
+}
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<Captured>>>);
+
+impl<S> Layer<S> for Capture
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        ctx.span(id).unwrap().extensions_mut().insert(fields);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+
+        let cycle = ctx.event_scope(event).and_then(|scope| {
+            scope
+                .into_iter()
+                .find(|span| span.name() == "cycle")
+                .and_then(|span| span.extensions().get::<Fields>().cloned())
+        });
+
+        self.0.lock().unwrap().push(Captured { fields, cycle });
+    }
+}
+
+impl Capture {
+    fn messages(&self, message: &str) -> Vec<Captured> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|captured| captured.message() == message)
+            .cloned()
+            .collect()
+    }
+}
+
+fn book(bid: f64, ask: f64) -> Step {
+    Step::Book { bid, ask }
+}
+
+/// Quotes on the first book, then a book and a trade arrive while the quotes are in
+/// flight, a 3-tick move replaces both sides and the new bid fills.
+fn script() -> Vec<(u64, Step)> {
+    vec![
+        (0, book(93.00, 93.10)),
+        (100, book(93.00, 93.10)),
+        (
+            200,
+            Step::Trade {
+                price: 93.05,
+                quantity: 0.1,
+            },
+        ),
+        (300, Step::Accept(Buy)),
+        (300, Step::Accept(Sell)),
+        (2_000, book(93.03, 93.13)),
+        (2_100, Step::Fill(Buy)),
+    ]
+}
+
+#[tokio::test]
+async fn events_logged_in_a_cycle_carry_its_span_fields() {
+    let capture = Capture::default();
+    let _guard = tracing::subscriber::set_default(Registry::default().with(capture.clone()));
+
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&script()).await.unwrap();
+
+    let skipped = capture.messages("scheduling skipped");
+    assert_eq!(skipped.len(), 2, "{skipped:#?}");
+    for (captured, (cycle_id, event)) in skipped.iter().zip([("2", "book"), ("3", "trade")]) {
+        assert_eq!(captured.cycle("cycle_id"), cycle_id);
+        assert_eq!(captured.cycle("event"), event);
+        assert_eq!(captured.cycle("instrument"), "SOL/GBP");
+    }
+
+    let replaced = capture.messages("ticks threshold triggered");
+    assert_eq!(replaced.len(), 2, "{replaced:#?}");
+    assert!(
+        replaced
+            .iter()
+            .all(|captured| captured.cycle("cycle_id") == "4")
+    );
+
+    // Reports arrive between cycles, so what they log is outside any cycle span.
+    let filled = capture.messages("order filled");
+    assert_eq!(filled.len(), 1, "{filled:#?}");
+    assert!(filled[0].cycle.is_none());
+}
+
+#[tokio::test]
+async fn placed_orders_carry_the_cycle_that_decided_them() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&script()).await.unwrap();
+
+    let placed: Vec<_> = harness
+        .actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            OrderAction::Place(order) => Some((order.side, order.cycle_id)),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        placed,
+        vec![
+            (Buy, Some(1)),
+            (Sell, Some(1)),
+            (Buy, Some(4)),
+            (Sell, Some(4)),
+        ]
+    );
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use accumulator::execution::order_action::Side::{Buy, Sell};
+
+use common::{Harness, Step};
+
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("accumulator-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn book(bid: f64, ask: f64) -> Step {
+    Step::Book { bid, ask }
+}
+
+#[tokio::test]
+async fn records_each_cycle_from_book_to_actions() {
+    let path = log_path("decisions.jsonl");
+    let mut harness = Harness::new(|config| config.logging.decision_log = Some(path.clone()))
+        .await
+        .unwrap();
+    harness
+        .run(&[
+            (0, book(93.00, 93.10)),
+            (300, book(92.90, 93.20)),
+            (310, Step::Accept(Buy)),
+            (310, Step::Accept(Sell)),
+            (500, Step::KillSwitch(true)),
+            (1_000, book(93.00, 93.10)),
+        ])
+        .await
+        .unwrap();
+
+    let records: Vec<Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 3, "{records:#?}");
+
+    // The first book is quoted on both sides; signals have not warmed up yet.
+    let quoted = &records[0];
+    assert_eq!(quoted["instrument"], "SOL/GBP");
+    assert_eq!(quoted["event"], "book");
+    assert_eq!(quoted["best_bid"], 93.00);
+    assert_eq!(quoted["best_ask"], 93.10);
+    assert!(quoted["ema_fast"].is_null());
+    assert_eq!(quoted["schedule"]["outcome"], "evaluate");
+    assert_eq!(quoted["target"]["bid"]["price"], 93.00);
+    assert_eq!(quoted["risk"]["outcome"], "approved");
+    let places: Vec<_> = quoted["actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|action| (action["action"].clone(), action["side"].clone()))
+        .collect();
+    assert_eq!(
+        places,
+        [
+            ("place".into(), "buy".into()),
+            ("place".into(), "sell".into())
+        ]
+    );
+
+    // While the places are in flight the target is only followed to cancel, and both
+    // sides are still waiting on the venue.
+    let held = &records[1];
+    assert_eq!(held["schedule"]["outcome"], "cancels_only");
+    assert_eq!(held["schedule"]["code"], "in_flight");
+    assert_eq!(held["orders"]["bids"][0]["state"], "placing");
+    assert_eq!(held["orders"]["asks"][0]["state"], "placing");
+    assert!(!held["target"].is_null() && !held["risk"].is_null());
+    assert_eq!(held["actions"], Value::Array(vec![]));
+
+    // The kill switch rejects the target and cancels everything.
+    let rejected = &records[2];
+    assert_eq!(rejected["ema_fast"], 93.05);
+    assert_eq!(rejected["risk"]["outcome"], "rejected");
+    assert_eq!(
+        rejected["risk"]["reasons"][0]["code"],
+        "kill_switch_enabled"
+    );
+    assert_eq!(rejected["actions"][0]["action"], "cancel_all");
+}
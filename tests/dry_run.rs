@@ -0,0 +1,476 @@
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+use accumulator::engine::supervisor::Supervisor;
+use accumulator::events::MarketEvent;
+use accumulator::execution::ExecutionVenue;
+use accumulator::execution::dry_run::{DryRunConfig, DryRunExecutionVenue};
+use accumulator::execution::order_action::Side::Buy;
+use accumulator::execution::order_action::{Order, OrderAction, OrderType};
+use accumulator::execution::order_report::OrderReport;
+use accumulator::random::SeededRng;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+
+/// Seed whose first placement is accepted rather than rejected.
+const SEED: u64 = 7;
+
+/// Seed whose first two placements are both accepted.
+const SEED_TWO_ACCEPTED: u64 = 1;
+
+struct Paper {
+    venue: DryRunExecutionVenue,
+    reports: broadcast::Receiver<OrderReport>,
+    instrument: Instrument,
+}
+
+impl Paper {
+    fn new(seed: u64, lifecycle: DryRunConfig) -> Self {
+        let (sender, reports) = broadcast::channel(1_000);
+        Self {
+            venue: DryRunExecutionVenue::new(sender, SeededRng::new(seed))
+                .with_lifecycle(lifecycle),
+            reports,
+            instrument: InstrumentConfig::default().load().unwrap(),
+        }
+    }
+
+    async fn bid(&self, order_id: &str) {
+        self.bid_as(order_id, OrderType::POST_ONLY).await;
+    }
+
+    async fn bid_as(&self, order_id: &str, order_type: OrderType) {
+        let order = Order {
+            order_id: order_id.to_string(),
+            instrument: self.instrument.clone(),
+            side: Buy,
+            price: Price::new(93.00),
+            quantity: Quantity::new(0.05).unwrap(),
+            order_type,
+            cycle_id: None,
+        };
+        self.venue
+            .execute(&[OrderAction::Place(order)])
+            .await
+            .unwrap();
+    }
+
+    async fn cancel(&self, order_id: &str) {
+        self.venue
+            .execute(&[OrderAction::Cancel {
+                order_id: order_id.to_string(),
+                instrument: self.instrument.clone(),
+                side: Buy,
+            }])
+            .await
+            .unwrap();
+    }
+
+    async fn amend(&self, order_id: &str, price: f64, quantity: f64) {
+        self.venue
+            .execute(&[OrderAction::Amend {
+                order_id: order_id.to_string(),
+                instrument: self.instrument.clone(),
+                side: Buy,
+                new_price: Price::new(price),
+                new_quantity: Quantity::new(quantity).unwrap(),
+            }])
+            .await
+            .unwrap();
+    }
+
+    fn trade(&self, price: f64, quantity: f64) {
+        self.venue.on_market_event(&MarketEvent::Trade {
+            instrument: self.instrument.clone(),
+            price: Price::new(price),
+            quantity,
+            timestamp_ms: 0,
+        });
+    }
+
+    /// A book the bid does not cross.
+    fn book(&self) {
+        self.venue.on_market_event(&MarketEvent::TopOfBook {
+            instrument: self.instrument.clone(),
+            best_bid: Price::new(92.50),
+            best_ask: Price::new(93.50),
+            bid_size: None,
+            ask_size: None,
+            timestamp_ms: 0,
+        });
+    }
+
+    /// A book whose ask the bid reaches.
+    fn crossed_book(&self) {
+        self.venue.on_market_event(&MarketEvent::TopOfBook {
+            instrument: self.instrument.clone(),
+            best_bid: Price::new(92.90),
+            best_ask: Price::new(92.95),
+            bid_size: None,
+            ask_size: None,
+            timestamp_ms: 0,
+        });
+    }
+
+    /// Reports sent so far, as `kind` or `kind quantity/cum_quantity` for fills.
+    fn drain(&mut self) -> Vec<String> {
+        std::iter::from_fn(|| self.reports.try_recv().ok())
+            .map(|report| match report {
+                OrderReport::PartiallyFilled {
+                    quantity,
+                    cum_quantity,
+                    ..
+                }
+                | OrderReport::Filled {
+                    quantity,
+                    cum_quantity,
+                    ..
+                } => format!("{} {quantity:.2}/{cum_quantity:.2}", report.kind()),
+                report => report.kind().to_string(),
+            })
+            .collect()
+    }
+}
+
+fn lifecycle(fill_probability: f64, partial_fill_probability: f64) -> DryRunConfig {
+    DryRunConfig {
+        fill_probability,
+        fill_delay_ms: 0,
+        partial_fill_probability,
+        expiry_ms: None,
+    }
+}
+
+#[tokio::test]
+async fn fills_in_halves_until_the_rest_is_one_step() {
+    let mut paper = Paper::new(SEED, lifecycle(1.0, 1.0));
+
+    paper.bid("bid-1").await;
+    assert_eq!(paper.drain(), ["placed", "accepted"]);
+
+    for _ in 0..5 {
+        paper.book();
+    }
+    assert_eq!(
+        paper.drain(),
+        [
+            "partially_filled 0.02/0.02",
+            "partially_filled 0.01/0.03",
+            "partially_filled 0.01/0.04",
+            "filled 0.01/0.05",
+        ]
+    );
+    assert!(
+        paper
+            .venue
+            .open_orders(&paper.instrument)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn waits_out_the_fill_delay() {
+    let mut paper = Paper::new(
+        SEED,
+        DryRunConfig {
+            fill_delay_ms: 50,
+            ..lifecycle(1.0, 0.0)
+        },
+    );
+
+    paper.bid("bid-1").await;
+    paper.book();
+    assert_eq!(paper.drain(), ["placed", "accepted"]);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    paper.book();
+    assert_eq!(paper.drain(), ["filled 0.05/0.05"]);
+}
+
+#[tokio::test]
+async fn expires_orders_left_resting() {
+    let mut paper = Paper::new(
+        SEED,
+        DryRunConfig {
+            expiry_ms: Some(50),
+            ..lifecycle(0.0, 0.0)
+        },
+    );
+
+    paper.bid("bid-1").await;
+    paper.book();
+    assert_eq!(paper.drain(), ["placed", "accepted"]);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    paper.book();
+    assert_eq!(paper.drain(), ["cancelled"]);
+}
+
+#[tokio::test]
+async fn a_cancel_after_the_fill_fails() {
+    let mut paper = Paper::new(SEED, lifecycle(1.0, 0.0));
+
+    paper.bid("bid-1").await;
+    paper.book();
+    paper.cancel("bid-1").await;
+    assert_eq!(
+        paper.drain(),
+        [
+            "placed",
+            "accepted",
+            "filled 0.05/0.05",
+            "cancel",
+            "cancel_failed"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn a_cancel_before_the_fill_wins() {
+    let mut paper = Paper::new(SEED, lifecycle(1.0, 0.0));
+
+    paper.bid("bid-1").await;
+    paper.cancel("bid-1").await;
+    paper.book();
+    assert_eq!(paper.drain(), ["placed", "accepted", "cancel", "cancelled"]);
+}
+
+#[tokio::test]
+async fn the_same_seed_gives_the_same_lifecycles() {
+    async fn run(seed: u64) -> Vec<String> {
+        let mut paper = Paper::new(seed, lifecycle(0.5, 0.5));
+        for index in 0..20 {
+            paper.bid(&format!("bid-{index}")).await;
+            paper.book();
+            paper.book();
+        }
+        paper.drain()
+    }
+
+    let reports = run(2).await;
+    assert_eq!(reports, run(2).await);
+    for kind in ["rejected", "partially_filled", "filled"] {
+        assert!(
+            reports.iter().any(|report| report.starts_with(kind)),
+            "no {kind} in {reports:?}"
+        );
+    }
+    // Some orders were decided against and still rest.
+    assert!(
+        reports
+            .iter()
+            .filter(|report| *report == "accepted")
+            .count()
+            > reports
+                .iter()
+                .filter(|report| report.starts_with("filled"))
+                .count()
+    );
+}
+
+#[tokio::test]
+async fn a_trade_through_fills_no_more_than_it_printed() {
+    let mut paper = Paper::new(SEED_TWO_ACCEPTED, lifecycle(0.0, 0.0));
+
+    paper.bid("bid-1").await;
+    paper.bid("bid-2").await;
+    paper.trade(92.90, 0.03);
+    paper.trade(93.50, 1.0);
+    paper.trade(92.90, 0.06);
+    assert_eq!(
+        paper.drain(),
+        [
+            "placed",
+            "accepted",
+            "placed",
+            "accepted",
+            "partially_filled 0.03/0.03",
+            "filled 0.02/0.05",
+            "partially_filled 0.04/0.04",
+        ]
+    );
+    assert_eq!(
+        paper
+            .venue
+            .open_orders(&paper.instrument)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn cancel_all_reports_each_resting_order() {
+    let mut paper = Paper::new(SEED_TWO_ACCEPTED, lifecycle(0.0, 0.0));
+
+    paper.bid("bid-1").await;
+    paper.bid("bid-2").await;
+    paper
+        .venue
+        .execute(&[OrderAction::CancelAll])
+        .await
+        .unwrap();
+    assert_eq!(
+        paper.drain(),
+        [
+            "placed",
+            "accepted",
+            "placed",
+            "accepted",
+            "cancelled",
+            "cancelled",
+            "cancelled_all",
+        ]
+    );
+}
+
+/// The startup `CancelAll` is answered even when nothing rests, and leaves nothing open.
+#[tokio::test]
+async fn cancel_all_with_nothing_resting_still_reports() {
+    let mut paper = Paper::new(SEED_TWO_ACCEPTED, lifecycle(0.0, 0.0));
+
+    paper.bid("bid-1").await;
+    for _ in 0..2 {
+        paper
+            .venue
+            .execute(&[OrderAction::CancelAll])
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        paper.drain(),
+        [
+            "placed",
+            "accepted",
+            "cancelled",
+            "cancelled_all",
+            "cancelled_all"
+        ]
+    );
+    assert!(
+        paper
+            .venue
+            .open_orders(&paper.instrument)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn paper_balances_move_with_the_fills() {
+    let (sender, reports) = broadcast::channel(1_000);
+    let mut paper = Paper {
+        venue: DryRunExecutionVenue::new(sender, SeededRng::new(SEED))
+            .with_paper_inventory(Inventory::new(1.0, 500.0)),
+        reports,
+        instrument: InstrumentConfig::default().load().unwrap(),
+    };
+    let (supervisor, _escalations) = Supervisor::new();
+    let inventory = paper
+        .venue
+        .spawn_inventory(&paper.instrument, &supervisor)
+        .await
+        .unwrap();
+    let mut balances = inventory.subscribe();
+    assert_eq!(balances.borrow().quote, 500.0);
+
+    paper.bid("bid-1").await;
+    paper.trade(92.90, 0.02);
+    assert_eq!(paper.drain()[2], "partially_filled 0.02/0.02");
+
+    tokio::time::timeout(Duration::from_secs(1), balances.changed())
+        .await
+        .unwrap()
+        .unwrap();
+    let balances = *balances.borrow();
+    assert!((balances.base - 1.02).abs() < 1e-9, "{balances:?}");
+    assert!(
+        (balances.quote - (500.0 - 0.02 * 93.00)).abs() < 1e-9,
+        "{balances:?}"
+    );
+}
+
+#[tokio::test]
+async fn amends_a_resting_order_in_place() {
+    let mut paper = Paper::new(SEED, lifecycle(0.0, 0.0));
+
+    paper.bid("bid-1").await;
+    paper.amend("bid-1", 92.98, 0.05).await;
+    paper.amend("bid-2", 92.98, 0.05).await;
+    assert_eq!(
+        paper.drain(),
+        ["placed", "accepted", "amended", "amend_rejected"]
+    );
+
+    let open = paper.venue.open_orders(&paper.instrument).await.unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].order_id, "bid-1");
+    assert_eq!(open[0].price, Price::new(92.98));
+
+    // Trading through the old price no longer fills it.
+    paper.trade(92.99, 1.0);
+    assert!(paper.drain().is_empty());
+    paper.trade(92.97, 1.0);
+    assert_eq!(paper.drain(), ["filled 0.05/0.05"]);
+}
+
+#[tokio::test]
+async fn an_order_reaching_the_touch_is_refused_if_post_only_and_filled_otherwise() {
+    let mut paper = Paper::new(SEED_TWO_ACCEPTED, DryRunConfig::default());
+    paper.crossed_book();
+
+    paper.bid("b1").await;
+    assert_eq!(paper.drain(), ["placed", "rejected"]);
+
+    paper
+        .bid_as("b2", OrderType::Limit { post_only: false })
+        .await;
+    assert_eq!(paper.drain(), ["placed", "accepted", "filled 0.05/0.05"]);
+    assert!(
+        paper
+            .venue
+            .open_orders(&paper.instrument)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn an_immediate_or_cancel_order_never_rests() {
+    let mut paper = Paper::new(SEED_TWO_ACCEPTED, DryRunConfig::default());
+
+    paper.book();
+    paper.bid_as("b1", OrderType::ImmediateOrCancel).await;
+    assert_eq!(paper.drain(), ["placed", "accepted", "cancelled"]);
+
+    paper.crossed_book();
+    paper.bid_as("b2", OrderType::ImmediateOrCancel).await;
+    let reports: Vec<_> = std::iter::from_fn(|| paper.reports.try_recv().ok()).collect();
+    assert!(
+        matches!(
+            &reports[..],
+            [
+                OrderReport::Placed { .. },
+                OrderReport::Accepted { .. },
+                OrderReport::Filled { price, .. },
+            ] if *price == Price::new(92.95)
+        ),
+        "{reports:?}"
+    );
+    assert!(
+        paper
+            .venue
+            .open_orders(&paper.instrument)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
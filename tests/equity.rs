@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use accumulator::clock::SimClock;
+use accumulator::execution::order_action::Side::{self, Buy, Sell};
+use accumulator::execution::order_report::OrderReport;
+use accumulator::stats::equity_curve::EquityCurve;
+use accumulator::stats::session_stats::{SessionStats, StatsEvent};
+use accumulator::stats::trading_book::TradingBook;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+
+const START_MS: u64 = 1_700_000_000_000;
+
+struct Session {
+    clock: SimClock,
+    instrument: Instrument,
+    stats: SessionStats,
+}
+
+impl Session {
+    fn new(max_points: usize) -> Self {
+        let clock = SimClock::from_timestamp_ms(START_MS);
+        let stats = SessionStats::new(
+            clock.shared(),
+            TradingBook::default(),
+            EquityCurve::new(Duration::from_secs(10), max_points),
+        );
+
+        Self {
+            clock,
+            instrument: InstrumentConfig::default().load().unwrap(),
+            stats,
+        }
+    }
+
+    fn mark(&mut self, at_secs: u64, mid: f64) {
+        self.clock.set_timestamp_ms(START_MS + at_secs * 1_000);
+        self.stats.on_event(StatsEvent::Snapshot {
+            inventory: Inventory::default(),
+            mid: Some(Price::new(mid)),
+            realized_pnl: 0.0,
+            unrealized_pnl: None,
+        });
+    }
+
+    fn fill(&mut self, at_secs: u64, side: Side, price: f64) {
+        self.clock.set_timestamp_ms(START_MS + at_secs * 1_000);
+        self.stats.on_report(&OrderReport::Filled {
+            order_id: format!("fill-{at_secs}"),
+            instrument: self.instrument.clone(),
+            side,
+            price: Price::new(price),
+            quantity: Quantity::new(1.0).unwrap(),
+            cum_quantity: Quantity::new(1.0).unwrap(),
+        });
+    }
+}
+
+#[test]
+fn tracks_peak_drawdown_and_its_duration_over_a_round_trip() {
+    let mut session = Session::new(1_000);
+
+    session.mark(0, 100.0);
+    session.fill(0, Buy, 100.0);
+    // Not yet a sample interval since the last one.
+    session.mark(5, 80.0);
+    session.mark(10, 110.0); // peak 10
+    session.mark(20, 95.0); // 15 below it
+    session.mark(30, 100.0);
+    session.mark(40, 112.0); // new peak, 30s after the last
+    session.fill(45, Sell, 112.0);
+    session.mark(50, 90.0); // flat again, so unaffected
+
+    let stats = session.stats.equity().stats();
+    assert_eq!(stats.samples, 8);
+    assert_eq!(stats.equity_quote, Some(12.0));
+    assert_eq!(stats.peak_quote, Some(12.0));
+    assert_eq!(stats.drawdown_quote, 0.0);
+    assert_eq!(stats.max_drawdown_quote, 15.0);
+    assert_eq!(stats.max_drawdown_secs, 30.0);
+
+    // Changes between interval samples: +10, -15, +5, +12, 0.
+    let changes = [10.0, -15.0, 5.0, 12.0, 0.0];
+    let mean = changes.iter().sum::<f64>() / 5.0;
+    let variance = changes.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / 4.0;
+    let sharpe_like = stats.sharpe_like.unwrap();
+    assert!((sharpe_like - mean / variance.sqrt()).abs() < 1e-9);
+
+    let summary = session.stats.instrument_summary(&session.instrument);
+    assert_eq!(summary.equity, stats);
+    assert!(
+        summary
+            .to_string()
+            .contains("equity       peak 12.00, max drawdown 15.00 over 30s")
+    );
+    assert_eq!(session.stats.summary().equity, stats);
+}
+
+#[test]
+fn an_open_drawdown_counts_towards_the_longest() {
+    let mut session = Session::new(1_000);
+
+    session.mark(0, 100.0);
+    session.fill(0, Buy, 100.0);
+    session.mark(10, 104.0);
+    session.mark(20, 101.0);
+    session.mark(70, 99.0);
+
+    let stats = session.stats.equity().stats();
+    assert_eq!(stats.drawdown_quote, 5.0);
+    assert_eq!(stats.max_drawdown_quote, 5.0);
+    assert_eq!(stats.max_drawdown_secs, 60.0);
+}
+
+#[test]
+fn thins_old_samples_but_keeps_whole_session_statistics() {
+    let mut session = Session::new(10);
+
+    session.mark(0, 100.0);
+    session.fill(0, Buy, 100.0);
+    // An early 50 drawdown, then a steady climb.
+    session.mark(10, 50.0);
+    for step in 2..200 {
+        session.mark(step * 10, 100.0 + step as f64);
+    }
+
+    let equity = session.stats.equity();
+    let points = equity.points();
+    assert!(points.len() <= 10, "{} points kept", points.len());
+    assert!(points.windows(2).all(|pair| pair[0].at < pair[1].at));
+    assert_eq!(points.first().unwrap().equity_quote, 0.0);
+    assert_eq!(points.last().unwrap().equity_quote, 199.0);
+
+    let stats = equity.stats();
+    assert_eq!(stats.samples, 201);
+    assert_eq!(stats.max_drawdown_quote, 50.0);
+    assert_eq!(stats.max_drawdown_secs, 20.0);
+}
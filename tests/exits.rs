@@ -0,0 +1,205 @@
+mod common;
+
+use std::time::Instant;
+
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::OrderType;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::execution::order_report::OrderReport;
+use accumulator::market::market_state::MarketState;
+use accumulator::strategy::exit::{ExitManager, ExitParams};
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::pnl::{PnlState, PnlTracker};
+use accumulator::types::price::Price;
+use accumulator::types::quote::Quote;
+use accumulator::types::quote_target::{NoQuoteReason, QuoteTarget};
+
+use common::qty;
+
+fn book(instrument: &Instrument, bid: f64, ask: f64) -> MarketState {
+    let mut market = MarketState::new();
+    market.on_market_event(
+        &MarketEvent::TopOfBook {
+            instrument: instrument.clone(),
+            best_bid: Price::new(bid),
+            best_ask: Price::new(ask),
+            bid_size: None,
+            ask_size: None,
+            timestamp_ms: 0,
+        },
+        Instant::now(),
+    );
+    market
+}
+
+fn quote(price: f64, quantity: f64) -> Option<Quote> {
+    Some(Quote {
+        price: Price::new(price),
+        quantity: qty(quantity),
+    })
+}
+
+fn strategy_target() -> Result<QuoteTarget, NoQuoteReason> {
+    Ok(QuoteTarget::new(quote(92.99, 0.05), quote(93.03, 0.05)))
+}
+
+/// `(price, quantity)` of each side, for comparing targets.
+fn sides(target: &Result<QuoteTarget, NoQuoteReason>) -> [Option<(f64, f64)>; 2] {
+    let target = target.as_ref().expect("a target");
+    [target.bid, target.ask].map(|quote| {
+        quote.map(|quote| {
+            (
+                (quote.price.as_f64() * 100.0).round() / 100.0,
+                (quote.quantity.as_f64() * 100.0).round() / 100.0,
+            )
+        })
+    })
+}
+
+fn exits(take_profit_ticks: Option<f64>, stop_loss_ticks: Option<f64>) -> ExitManager {
+    ExitManager::new(&ExitParams {
+        take_profit_ticks,
+        stop_loss_ticks,
+        ..ExitParams::default()
+    })
+}
+
+#[test]
+fn rests_a_take_profit_that_tracks_the_filled_position() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut exits = exits(Some(5.0), Some(3.0));
+    let mut pnl = PnlTracker::default();
+    let market = book(&instrument, 93.00, 93.02);
+
+    // Flat: the strategy's target goes through untouched.
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target), [Some((92.99, 0.05)), Some((93.03, 0.05))]);
+
+    pnl.on_fill(Buy, Price::new(93.00), qty(0.05));
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target), [Some((92.99, 0.05)), Some((93.05, 0.05))]);
+
+    // Part of the exit filled; the rest stays at the same price.
+    pnl.on_fill(Sell, Price::new(93.05), qty(0.02));
+    let target = exits.apply(&instrument, &market, &pnl, Err(NoQuoteReason::MissingEma));
+    assert_eq!(sides(&target), [None, Some((93.05, 0.03))]);
+
+    pnl.on_fill(Sell, Price::new(93.05), qty(0.03));
+    assert!(matches!(
+        exits.apply(&instrument, &market, &pnl, Err(NoQuoteReason::MissingEma)),
+        Err(NoQuoteReason::MissingEma)
+    ));
+}
+
+#[test]
+fn exits_keep_their_thresholds_across_a_restart() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let fill = |cum_quantity: f64| OrderReport::PartiallyFilled {
+        order_id: "sim-1".to_string(),
+        instrument: instrument.clone(),
+        side: Buy,
+        price: Price::new(93.00),
+        quantity: qty(0.05),
+        cum_quantity: qty(cum_quantity),
+    };
+    let mut pnl = PnlTracker::default();
+    pnl.on_report(&fill(0.05));
+
+    // Saved and read back as the state store does, by a run whose exits start afresh.
+    let saved = serde_json::to_string(&pnl.state()).unwrap();
+    let restored: PnlState = serde_json::from_str(&saved).unwrap();
+    let mut pnl = PnlTracker::restore(restored);
+    let mut exits = exits(Some(5.0), Some(3.0));
+
+    // The take profit is still 5 ticks over the entry from before the restart.
+    let market = book(&instrument, 93.00, 93.02);
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target), [Some((92.99, 0.05)), Some((93.05, 0.05))]);
+
+    // The order fills further at the same price; only what it adds counts.
+    pnl.on_report(&fill(0.08));
+    assert!((pnl.position() - 0.08).abs() < 1e-12, "{}", pnl.position());
+
+    // And the stop is still 3 ticks under the entry: one tick short of it the take profit
+    // holds, on it the exit chases the touch.
+    let market = book(&instrument, 92.98, 93.00);
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target)[1].map(|(price, _)| price), Some(93.05));
+    let market = book(&instrument, 92.97, 92.99);
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target)[1].map(|(price, _)| price), Some(92.99));
+}
+
+#[test]
+fn a_hit_stop_chases_the_touch_until_flat() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut exits = exits(Some(5.0), Some(3.0));
+    let mut pnl = PnlTracker::default();
+    pnl.on_fill(Sell, Price::new(93.00), qty(0.05));
+
+    // Short: the take profit is a bid below the entry.
+    let market = book(&instrument, 93.00, 93.02);
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target), [Some((92.95, 0.05)), Some((93.03, 0.05))]);
+
+    // Buying back would now cost more than the stop's 3 ticks.
+    let market = book(&instrument, 93.02, 93.04);
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target), [Some((93.02, 0.05)), Some((93.03, 0.05))]);
+
+    // Still stopped after the market comes back.
+    let market = book(&instrument, 92.98, 93.00);
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target)[0], Some((92.98, 0.05)));
+
+    // A new position after going flat starts with its take profit again.
+    pnl.on_fill(Buy, Price::new(92.98), qty(0.05));
+    exits
+        .apply(&instrument, &market, &pnl, strategy_target())
+        .unwrap();
+    pnl.on_fill(Sell, Price::new(93.00), qty(0.05));
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target)[0], Some((92.95, 0.05)));
+}
+
+#[test]
+fn an_immediate_stop_crosses_the_spread() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut exits = ExitManager::new(&ExitParams {
+        take_profit_ticks: Some(5.0),
+        stop_loss_ticks: Some(3.0),
+        stop_loss_immediate: true,
+    });
+    let mut pnl = PnlTracker::default();
+    pnl.on_fill(Buy, Price::new(93.00), qty(0.05));
+
+    // Before the stop the take profit rests post-only as usual.
+    let market = book(&instrument, 93.00, 93.02);
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(target.as_ref().unwrap().immediate, None);
+    assert_eq!(target.unwrap().order_type(Sell, 0), OrderType::POST_ONLY);
+
+    // Selling now would lose more than 3 ticks: the exit hits the bid.
+    let market = book(&instrument, 92.97, 92.99);
+    let target = exits.apply(&instrument, &market, &pnl, strategy_target());
+    assert_eq!(sides(&target), [Some((92.99, 0.05)), Some((92.97, 0.05))]);
+    let target = target.unwrap();
+    assert_eq!(target.order_type(Sell, 0), OrderType::ImmediateOrCancel);
+    assert_eq!(target.order_type(Buy, 0), OrderType::POST_ONLY);
+}
+
+#[test]
+fn passes_targets_through_when_off() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut exits = exits(None, None);
+    let mut pnl = PnlTracker::default();
+    pnl.on_fill(Buy, Price::new(93.00), qty(0.05));
+
+    let target = exits.apply(
+        &instrument,
+        &book(&instrument, 93.00, 93.02),
+        &pnl,
+        strategy_target(),
+    );
+    assert_eq!(sides(&target), [Some((92.99, 0.05)), Some((93.03, 0.05))]);
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use tokio::sync::broadcast;
+
+use accumulator::execution::fill_recorder::{FillLogConfig, FillRecord, FillRecorder};
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::execution::order_report::OrderReport;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+
+use common::qty;
+
+fn instrument() -> Instrument {
+    InstrumentConfig::default().load().unwrap()
+}
+
+#[test]
+fn names_a_file_per_day() {
+    let date = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+
+    assert_eq!(
+        FillRecorder::dated_path(Path::new("logs/fills.jsonl"), date),
+        Path::new("logs/fills-2024-01-03.jsonl")
+    );
+    assert_eq!(
+        FillRecorder::dated_path(Path::new("fills"), date),
+        Path::new("fills-2024-01-03")
+    );
+}
+
+#[tokio::test]
+async fn appends_every_fill_and_nothing_else() {
+    let dir = std::env::temp_dir().join(format!("accumulator-{}-fill-log", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config = FillLogConfig {
+        enabled: true,
+        path: dir.join("fills.jsonl"),
+        ..FillLogConfig::default()
+    };
+
+    let (reports, _) = broadcast::channel(64);
+    FillRecorder::spawn(&config, reports.subscribe());
+
+    reports
+        .send(OrderReport::Accepted {
+            order_id: "3f9c-SOLGBP-b1".to_string(),
+            instrument: instrument(),
+            side: Buy,
+            price: Price::new(93.00),
+            quantity: qty(0.05),
+        })
+        .unwrap();
+    reports
+        .send(OrderReport::PartiallyFilled {
+            order_id: "3f9c-SOLGBP-b1".to_string(),
+            instrument: instrument(),
+            side: Buy,
+            price: Price::new(93.00),
+            quantity: qty(0.02),
+            cum_quantity: qty(0.02),
+        })
+        .unwrap();
+    reports
+        .send(OrderReport::Filled {
+            order_id: "3f9c-SOLGBP-s2".to_string(),
+            instrument: instrument(),
+            side: Sell,
+            price: Price::new(93.10),
+            quantity: qty(0.05),
+            cum_quantity: qty(0.05),
+        })
+        .unwrap();
+
+    let path = FillRecorder::dated_path(&config.path, Utc::now().date_naive());
+    let mut lines = Vec::new();
+    for _ in 0..100 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let written = std::fs::read_to_string(&path).unwrap_or_default();
+        lines = written.lines().map(str::to_string).collect();
+        if lines.len() >= 2 {
+            break;
+        }
+    }
+
+    let fills: Vec<FillRecord> = lines
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(fills.len(), 2, "{lines:?}");
+
+    assert_eq!(fills[0].instrument, "SOL/GBP");
+    assert_eq!(fills[0].side, Buy);
+    assert_eq!(fills[0].order_id, "3f9c-SOLGBP-b1");
+    assert_eq!((fills[0].quantity, fills[0].cum_quantity), (0.02, 0.02));
+    assert!(!fills[0].complete);
+
+    assert_eq!(fills[1].side, Sell);
+    assert_eq!(fills[1].price, 93.10);
+    assert_eq!((fills[1].quantity, fills[1].cum_quantity), (0.05, 0.05));
+    assert!(fills[1].complete);
+    assert!(fills[0].at <= fills[1].at);
+}
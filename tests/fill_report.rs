@@ -0,0 +1,120 @@
+mod common;
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use accumulator::execution::order_action::Side::{Buy, Sell};
+
+use common::{Harness, Step};
+
+fn report_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("accumulator-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn book(bid: f64, ask: f64) -> Step {
+    Step::Book { bid, ask }
+}
+
+/// Quotes at 1s, fills the bid at 2s and the ask at 4s, then moves the mid around the
+/// mark-out horizons and shuts down 60.5s after the first fill.
+fn script() -> Vec<(u64, Step)> {
+    vec![
+        (0, book(93.00, 93.10)),
+        (1_000, book(93.00, 93.10)),
+        (1_010, Step::Accept(Buy)),
+        (1_010, Step::Accept(Sell)),
+        (2_000, Step::Fill(Buy)),
+        (2_500, book(93.10, 93.20)),
+        (3_500, book(93.20, 93.30)),
+        (4_000, Step::Fill(Sell)),
+        (12_500, book(93.00, 93.10)),
+        (62_500, book(92.90, 93.00)),
+        (62_500, Step::Shutdown),
+    ]
+}
+
+fn assert_close(row: &Value, field: &str, expected: f64) {
+    let actual = row[field]
+        .as_f64()
+        .unwrap_or_else(|| panic!("{field} missing in {row}"));
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "{field}: {actual} != {expected}"
+    );
+}
+
+#[tokio::test]
+async fn marks_out_fills_as_of_each_horizon() {
+    let path = report_path("fills.jsonl");
+    let mut harness = Harness::new(|config| config.stats.fill_report = Some(path.clone()))
+        .await
+        .unwrap();
+    harness.run(&script()).await.unwrap();
+
+    let rows: Vec<Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 2, "{rows:#?}");
+
+    // The bid filled at a 93.05 mid; each mark-out is the mid as of its horizon, not
+    // the mid of the event that revealed it. It was quoted before the EMA warmed up.
+    let bid = &rows[0];
+    assert_eq!(bid["side"], "buy");
+    assert_eq!(bid["partial"], false);
+    assert!(bid["ema"].is_null() && bid["deviation"].is_null());
+    assert_close(bid, "mid", 93.05);
+    assert_close(bid, "mid_1s", 93.15);
+    assert_close(bid, "mid_10s", 93.25);
+    assert_close(bid, "mid_60s", 93.05);
+
+    // The ask was requoted at 2.5s on a 93.15 mid, and its 60s horizon falls after
+    // shutdown.
+    let ask = &rows[1];
+    assert_eq!(ask["side"], "sell");
+    assert_eq!(ask["partial"], true);
+    let ema = ask["ema"].as_f64().unwrap();
+    assert!(ema > 93.05 && ema < 93.15, "ema {ema}");
+    assert_close(ask, "deviation", 93.15 - ema);
+    assert!(ask["volatility"].as_f64().unwrap() > 0.0);
+    assert_close(ask, "mid", 93.25);
+    assert_close(ask, "mid_1s", 93.25);
+    assert_close(ask, "mid_10s", 93.05);
+    assert!(ask["mid_60s"].is_null());
+
+    // Each row names the cycle that placed its order.
+    let cycle_id = |row: &Value| row["cycle_id"].as_u64().unwrap();
+    assert!(cycle_id(ask) > cycle_id(bid), "{rows:#?}");
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn writes_csv_for_a_csv_path() {
+    let path = report_path("fills.csv");
+    let mut harness = Harness::new(|config| config.stats.fill_report = Some(path.clone()))
+        .await
+        .unwrap();
+    harness.run(&script()).await.unwrap();
+
+    let csv = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "timestamp,instrument,order_id,cycle_id,side,price,quantity,mid,ema,deviation,\
+         volatility,mid_1s,mid_10s,mid_60s,partial"
+    );
+    assert_eq!(lines.len(), 3);
+    assert!(
+        lines[1].ends_with(",93.15,93.25,93.05,false"),
+        "{}",
+        lines[1]
+    );
+    assert!(lines[2].ends_with(",93.25,93.05,,true"), "{}", lines[2]);
+
+    let _ = std::fs::remove_file(&path);
+}
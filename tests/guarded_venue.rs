@@ -0,0 +1,257 @@
+mod common;
+
+use tokio::sync::broadcast::{self, Receiver};
+
+use accumulator::events::MarketEvent;
+use accumulator::execution::ExecutionVenue;
+use accumulator::execution::guarded_venue::{GuardedVenue, OrderGuardConfig};
+use accumulator::execution::order_action::Side::{self, Buy, Sell};
+use accumulator::execution::order_action::{Order, OrderAction, OrderType};
+use accumulator::execution::order_report::OrderReport;
+use accumulator::market::market_state::MarketState;
+use accumulator::scenario::scenario::Scenario;
+use accumulator::scenario::strategies::StrategyKind;
+use accumulator::signals::config::SignalsConfig;
+use accumulator::signals::signal_state::SignalState;
+use accumulator::strategy::config::StrategyConfig;
+use accumulator::strategy::instrument_context::{InstrumentContext, WithContext};
+use accumulator::strategy::strategy::Strategy;
+use accumulator::strategy::strategy_helpers::StrategyHelpers;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+use accumulator::types::quote::Quote;
+use accumulator::types::quote_target::{NoQuoteReason, QuoteTarget};
+
+use common::{INITIAL, MockVenue};
+
+/// Quotes both sides at the mid times `price_factor`, as a strategy with a slipped
+/// decimal point would.
+struct FatFingered {
+    ctx: InstrumentContext,
+    price_factor: f64,
+    quantity: Quantity,
+}
+
+impl WithContext for FatFingered {
+    fn ctx(&self) -> &InstrumentContext {
+        &self.ctx
+    }
+
+    fn ctx_mut(&mut self) -> &mut InstrumentContext {
+        &mut self.ctx
+    }
+}
+
+impl Strategy for FatFingered {
+    fn compute_target(
+        &self,
+        market_state: &MarketState,
+        _signal_state: &SignalState,
+        _inventory: Inventory,
+    ) -> Result<QuoteTarget, NoQuoteReason> {
+        let (bid, ask) = Self::best_bid_ask(market_state).ok_or(NoQuoteReason::MissingTopOfBook)?;
+        let price = Price::new((bid + ask) / 2.0 * self.price_factor);
+        let quote = Quote {
+            price,
+            quantity: self.quantity,
+        };
+
+        Ok(QuoteTarget {
+            bid: Some(quote),
+            ask: Some(quote),
+            ..QuoteTarget::default()
+        })
+    }
+
+    fn update_params(&mut self, _config: &StrategyConfig) {}
+}
+
+fn sol_gbp() -> Instrument {
+    InstrumentConfig::default().load().unwrap()
+}
+
+fn book(instrument: &Instrument) -> MarketEvent {
+    MarketEvent::TopOfBook {
+        instrument: instrument.clone(),
+        best_bid: Price::new(92.95),
+        best_ask: Price::new(93.05),
+        bid_size: None,
+        ask_size: None,
+        timestamp_ms: 0,
+    }
+}
+
+fn place(order_id: &str, side: Side, price: f64, quantity: f64) -> OrderAction {
+    OrderAction::Place(Order {
+        order_id: order_id.to_string(),
+        instrument: sol_gbp(),
+        side,
+        price: Price::new(price),
+        quantity: Quantity::new(quantity).unwrap(),
+        order_type: OrderType::POST_ONLY,
+        cycle_id: None,
+    })
+}
+
+/// The places `strategy` asks for on the book [`book`] gives.
+fn places(strategy: &FatFingered) -> Vec<OrderAction> {
+    let mut market = MarketState::new();
+    market.on_market_event(&book(&sol_gbp()), std::time::Instant::now());
+    let target = strategy
+        .compute_target(
+            &market,
+            &Scenario::signals(StrategyKind::SimpleMarketMaker, &SignalsConfig::default()),
+            INITIAL,
+        )
+        .unwrap();
+
+    [("b1", Buy, target.bid), ("s1", Sell, target.ask)]
+        .into_iter()
+        .filter_map(|(order_id, side, quote)| {
+            let quote = quote?;
+            Some(place(
+                order_id,
+                side,
+                quote.price.as_f64(),
+                quote.quantity.as_f64(),
+            ))
+        })
+        .collect()
+}
+
+fn guarded() -> (GuardedVenue<MockVenue>, MockVenue, Receiver<OrderReport>) {
+    let (sender, reports) = broadcast::channel(64);
+    let mock = MockVenue::new(sender.clone(), INITIAL);
+    let venue = GuardedVenue::new(mock.clone(), OrderGuardConfig::default(), sender);
+    (venue, mock, reports)
+}
+
+/// Ids of the places that reached the venue, in order.
+fn passed(venue: &MockVenue) -> Vec<String> {
+    venue
+        .actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            OrderAction::Place(order) => Some(order.order_id),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Ids and reasons of the rejections sent so far.
+fn rejections(reports: &mut Receiver<OrderReport>) -> Vec<(String, String)> {
+    std::iter::from_fn(|| reports.try_recv().ok())
+        .filter_map(|report| match report {
+            OrderReport::Rejected {
+                order_id, reason, ..
+            } => Some((order_id, reason)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn absurd_prices_from_a_broken_strategy_never_reach_the_venue() {
+    let (venue, mock, mut reports) = guarded();
+    venue.on_market_event(&book(&sol_gbp()));
+
+    let strategy = FatFingered {
+        ctx: InstrumentContext::new(&sol_gbp()),
+        price_factor: 0.01,
+        quantity: Quantity::new(0.05).unwrap(),
+    };
+    let mut actions = places(&strategy);
+    actions.push(OrderAction::Cancel {
+        order_id: "b0".to_string(),
+        instrument: sol_gbp(),
+        side: Buy,
+    });
+    venue.execute(&actions).await.unwrap();
+
+    assert!(passed(&mock).is_empty());
+    assert!(
+        mock.actions().iter().any(
+            |action| matches!(action, OrderAction::Cancel { order_id, .. } if order_id == "b0")
+        ),
+        "cancels always go through"
+    );
+    let rejected = rejections(&mut reports);
+    assert_eq!(rejected.len(), 2, "{rejected:?}");
+    for (order_id, reason) in &rejected {
+        assert!(
+            reason.starts_with("fat-finger guard: "),
+            "{order_id}: {reason}"
+        );
+        assert!(reason.contains("from the mid"), "{order_id}: {reason}");
+    }
+
+    // The same strategy, fixed, is let through.
+    let strategy = FatFingered {
+        price_factor: 1.0,
+        ..strategy
+    };
+    venue.execute(&places(&strategy)).await.unwrap();
+    assert_eq!(passed(&mock), ["b1", "s1"]);
+    assert!(rejections(&mut reports).is_empty());
+}
+
+#[tokio::test]
+async fn each_check_rejects_only_the_places_that_fail_it() {
+    let (venue, mock, mut reports) = guarded();
+
+    venue
+        .execute(&[place("b1", Buy, 93.00, 0.05)])
+        .await
+        .unwrap();
+    let rejected = rejections(&mut reports);
+    assert!(rejected[0].1.contains("no mid price"), "{rejected:?}");
+
+    venue.on_market_event(&book(&sol_gbp()));
+    venue
+        .execute(&[
+            place("b2", Buy, 93.00, 0.05),
+            place("b3", Buy, 93.00, 0.0),
+            place("s1", Sell, 93.00, 1.0),
+            place("s2", Sell, 103.00, 0.05),
+            place("s3", Sell, 99.00, 0.05),
+        ])
+        .await
+        .unwrap();
+
+    // 1.0 SOL is 93.00, beyond twice SOL/GBP's max_order_notional of 5.00; 103.00 is
+    // more than 10% above the mid.
+    assert_eq!(passed(&mock), ["b2", "s3"]);
+    let rejected = rejections(&mut reports);
+    let reasons: Vec<(&str, &str)> = rejected
+        .iter()
+        .map(|(order_id, reason)| (order_id.as_str(), reason.as_str()))
+        .collect();
+    assert!(
+        matches!(
+            &reasons[..],
+            [
+                ("b3", zero),
+                ("s1", notional),
+                ("s2", deviation),
+            ] if zero.contains("quantity 0 is not a positive number")
+                && notional.contains("notional 93.00 exceeds 10.00")
+                && deviation.contains("from the mid")
+        ),
+        "{reasons:?}"
+    );
+}
+
+#[test]
+fn the_guard_must_allow_at_least_the_max_order_notional() {
+    let config = OrderGuardConfig {
+        notional_factor: 0.5,
+        ..OrderGuardConfig::default()
+    };
+    let error = config.validate("venue.guard").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "venue.guard.notional_factor: must be >= 1"
+    );
+}
@@ -0,0 +1,357 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::{broadcast, mpsc};
+
+use accumulator::alerts::alert::Alert;
+use accumulator::alerts::alerter::{Alerter, AlerterConfig};
+use accumulator::alerts::webhook::{Webhook, WebhookKind};
+use accumulator::config::app_config::AppConfig;
+use accumulator::engine::engine::{Connections, Engine, Shutdown};
+use accumulator::engine::supervisor::Supervisor;
+use accumulator::engine::watchdog::{DeadFeed, Watchdog, WatchdogConfig};
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::execution::types::OpenOrder;
+use accumulator::execution::{DynamicInventorySource, ExecutionVenue, ReportSender};
+use accumulator::market::market_source::MarketDataSource;
+use accumulator::scenario::venues::VenueKind;
+use accumulator::telemetry::metrics::Feed;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+
+use common::{Harness, INITIAL, MockVenue, Step};
+
+fn alerter() -> Alerter {
+    Alerter::new(AlerterConfig {
+        webhook: Webhook::new(WebhookKind::Slack, "http://localhost/hook".into(), None).unwrap(),
+        reject_threshold: 3,
+        feed_down_after: Duration::from_secs(30),
+        loop_stall_after: Duration::from_secs(15),
+        cooldown: Duration::from_secs(300),
+    })
+}
+
+#[tokio::test]
+async fn heartbeat_reports_the_instrument_loop_state() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+
+    harness
+        .run(&[
+            (
+                0,
+                Step::Book {
+                    bid: 93.00,
+                    ask: 93.10,
+                },
+            ),
+            // Both quotes are still placing, so this one may only cancel, and has
+            // nothing to cancel.
+            (
+                300,
+                Step::Book {
+                    bid: 93.20,
+                    ask: 93.30,
+                },
+            ),
+            (400, Step::Accept(Buy)),
+        ])
+        .await
+        .unwrap();
+
+    let heartbeat = harness.heartbeat();
+    assert_eq!(heartbeat.instrument, "SOL/GBP");
+    assert_eq!(
+        heartbeat.since_market_event,
+        Some(Duration::from_millis(100))
+    );
+    assert_eq!(heartbeat.since_evaluation, Some(Duration::from_millis(400)));
+    assert_eq!(heartbeat.last_skip, Some("in_flight"));
+    // The ask is still waiting on the venue.
+    assert_eq!(heartbeat.in_flight_for, Some(Duration::from_millis(400)));
+
+    harness.run(&[(500, Step::Accept(Sell))]).await.unwrap();
+    assert_eq!(harness.heartbeat().in_flight_for, None);
+}
+
+#[tokio::test]
+async fn watchdog_reports_a_stalled_loop_past_its_threshold() {
+    let watchdog = Watchdog::new(
+        &WatchdogConfig::default(),
+        VenueKind::DryRun,
+        Instant::now(),
+    );
+
+    assert_eq!(watchdog.check_loop(None), None, "loop not started yet");
+    assert_eq!(watchdog.check_loop(Some(Duration::from_secs(5))), None);
+    assert_eq!(
+        watchdog.check_loop(Some(Duration::from_secs(16))),
+        Some(Duration::from_secs(16))
+    );
+
+    let disabled = WatchdogConfig {
+        loop_stall_secs: None,
+        ..WatchdogConfig::default()
+    };
+    let watchdog = Watchdog::new(&disabled, VenueKind::DryRun, Instant::now());
+    assert_eq!(watchdog.check_loop(Some(Duration::from_secs(600))), None);
+}
+
+#[test]
+fn watchdog_reports_the_first_feed_silent_past_its_threshold() {
+    let config = WatchdogConfig {
+        market_dead_secs: Some(120),
+        executions_dead_secs: Some(60),
+        ..WatchdogConfig::default()
+    };
+    let started = Instant::now();
+    let watchdog = Watchdog::new(&config, VenueKind::Kraken, started);
+
+    let silent = |market: u64, executions: u64| {
+        move |feed| match feed {
+            Feed::Market => Some(Duration::from_secs(market)),
+            Feed::Executions => Some(Duration::from_secs(executions)),
+            Feed::Inventory => None,
+        }
+    };
+
+    assert_eq!(watchdog.check(started, silent(119, 59)), None);
+    assert_eq!(
+        watchdog.check(started, silent(120, 59)),
+        Some(DeadFeed {
+            feed: Feed::Market,
+            silent_for: Duration::from_secs(120),
+        })
+    );
+    assert_eq!(
+        watchdog.check(started, silent(5, 61)),
+        Some(DeadFeed {
+            feed: Feed::Executions,
+            silent_for: Duration::from_secs(61),
+        })
+    );
+
+    // Only a venue that streams executions has that feed checked.
+    let dry_run = Watchdog::new(&config, VenueKind::DryRun, started);
+    assert_eq!(dry_run.check(started, silent(5, 600)), None);
+}
+
+#[test]
+fn watchdog_counts_a_feed_that_never_spoke_as_silent_since_start() {
+    let started = Instant::now();
+    let watchdog = Watchdog::new(&WatchdogConfig::default(), VenueKind::DryRun, started);
+
+    assert_eq!(
+        watchdog.check(started + Duration::from_secs(119), |_| None),
+        None
+    );
+    assert_eq!(
+        watchdog.check(started + Duration::from_secs(121), |_| None),
+        Some(DeadFeed {
+            feed: Feed::Market,
+            silent_for: Duration::from_secs(121),
+        })
+    );
+}
+
+/// A watchdog started long enough ago that the market feed, which has never spoken in
+/// this process, is already dead.
+fn long_dead_watchdog() -> Watchdog {
+    let config = WatchdogConfig {
+        market_dead_secs: Some(1),
+        loop_stall_secs: None,
+        ..WatchdogConfig::default()
+    };
+    let started = Instant::now().checked_sub(Duration::from_secs(10)).unwrap();
+    Watchdog::new(&config, VenueKind::DryRun, started)
+}
+
+#[tokio::test]
+async fn watchdog_sends_a_dead_feed_when_set_to_exit() {
+    let mut dead_feeds = long_dead_watchdog().spawn(true);
+
+    let dead = tokio::time::timeout(Duration::from_secs(5), dead_feeds.recv())
+        .await
+        .expect("dead feed reported")
+        .unwrap();
+    assert_eq!(dead.feed, Feed::Market);
+}
+
+#[tokio::test]
+async fn watchdog_only_logs_a_dead_feed_in_alert_only_mode() {
+    let mut dead_feeds = long_dead_watchdog().spawn(false);
+
+    // Past a couple of checks, each of which sees the feed dead.
+    let received = tokio::time::timeout(Duration::from_millis(2_500), dead_feeds.recv()).await;
+    assert!(
+        received.is_err(),
+        "alert-only watchdog asked for a shutdown"
+    );
+}
+
+/// Market data that never arrives, so the watchdog finds the feed dead.
+struct Silent;
+
+#[async_trait]
+impl MarketDataSource for Silent {
+    async fn subscribe(
+        &self,
+        _instrument: &Instrument,
+        _channel: mpsc::Sender<MarketEvent>,
+    ) -> Result<()> {
+        std::future::pending().await
+    }
+}
+
+/// The mock venue, noting at each cancel-all whether the state file and the session
+/// summary had been written yet.
+struct Watched {
+    venue: MockVenue,
+    state: PathBuf,
+    summary: PathBuf,
+    cancel_alls: Arc<Mutex<Vec<(bool, bool)>>>,
+}
+
+#[async_trait]
+impl ExecutionVenue for Watched {
+    async fn execute(&self, actions: &[OrderAction]) -> Result<()> {
+        if actions
+            .iter()
+            .any(|action| matches!(action, OrderAction::CancelAll))
+        {
+            self.cancel_alls
+                .lock()
+                .unwrap()
+                .push((self.state.exists(), self.summary.exists()));
+        }
+        self.venue.execute(actions).await
+    }
+
+    async fn open_orders(&self, instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        self.venue.open_orders(instrument).await
+    }
+
+    async fn spawn_reports(&self, on_report: ReportSender, supervisor: &Supervisor) -> Result<()> {
+        self.venue.spawn_reports(on_report, supervisor).await
+    }
+
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        self.venue.spawn_inventory(instrument, supervisor).await
+    }
+}
+
+fn modified(path: &Path) -> std::time::SystemTime {
+    std::fs::metadata(path).unwrap().modified().unwrap()
+}
+
+#[tokio::test]
+async fn a_dead_feed_cancels_all_then_saves_state_then_summarizes() {
+    let dir = std::env::temp_dir().join(format!("accumulator-{}-feed-dead", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut config = AppConfig::default();
+    config.watchdog.market_dead_secs = Some(1);
+    config.watchdog.exit = true;
+    config.state.path = Some(dir.join("state.json"));
+    config.stats.reports_dir = dir.join("reports");
+
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let (reports, _) = broadcast::channel(config.channels.order_reports);
+    let cancel_alls = Arc::new(Mutex::new(Vec::new()));
+    let venue = Watched {
+        venue: MockVenue::new(reports.clone(), INITIAL),
+        state: dir.join("state.json"),
+        summary: dir.join("reports").join("feed-dead.json"),
+        cancel_alls: cancel_alls.clone(),
+    };
+    let connections = Connections {
+        venue: Box::new(venue),
+        reports,
+        market: Arc::new(Silent),
+    };
+
+    let engine = Engine::start("feed-dead", config, vec![instrument], connections)
+        .await
+        .unwrap();
+    let shutdown = tokio::time::timeout(Duration::from_secs(10), engine.run())
+        .await
+        .expect("engine shut down")
+        .unwrap();
+
+    assert!(matches!(
+        shutdown,
+        Shutdown::FeedDead(DeadFeed {
+            feed: Feed::Market,
+            ..
+        })
+    ));
+    assert_eq!(Shutdown::FEED_DEAD_EXIT_CODE, 3);
+    assert_eq!(
+        shutdown.exit_code(),
+        std::process::ExitCode::from(Shutdown::FEED_DEAD_EXIT_CODE)
+    );
+
+    // The startup cancel-all, then the one before exit; neither file was written yet.
+    assert_eq!(
+        *cancel_alls.lock().unwrap(),
+        [(false, false), (false, false)]
+    );
+    let state = dir.join("state.json");
+    let summary = dir.join("reports").join("feed-dead.json");
+    assert!(modified(&state) <= modified(&summary));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn alerts_when_the_loop_stalls() {
+    let alerter = alerter();
+
+    assert!(
+        alerter
+            .liveness_alerts(|_| None, Some(Duration::from_secs(14)))
+            .is_empty()
+    );
+
+    let alerts = alerter.liveness_alerts(
+        |feed| (feed == Feed::Market).then_some(Duration::from_secs(45)),
+        Some(Duration::from_secs(20)),
+    );
+    assert_eq!(
+        alerts.iter().map(Alert::key).collect::<Vec<_>>(),
+        ["feed_down:market", "loop_stalled"]
+    );
+    assert!(matches!(
+        alerts[1],
+        Alert::LoopStalled { stalled_for } if stalled_for == Duration::from_secs(20)
+    ));
+    assert_eq!(
+        alerts[1].message(),
+        "Engine loop has not completed an iteration for 20s"
+    );
+}
+
+#[test]
+fn stall_thresholds_must_outlast_the_heartbeat() {
+    let error = AppConfig::from_yaml("watchdog:\n  heartbeat_secs: 15\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "watchdog.loop_stall_secs: must be > heartbeat_secs"
+    );
+
+    let error = AppConfig::from_yaml("alerts:\n  loop_stall_secs: 5\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "alerts.loop_stall_secs: must be > watchdog.heartbeat_secs"
+    );
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use tokio::sync::broadcast;
+
+use accumulator::execution::logged_venue::IntentLoggedVenue;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::execution::order_action::{Order, OrderAction, OrderType, Side};
+use accumulator::execution::order_ids::OrderIds;
+use accumulator::execution::{ExecutionVenue, ReportSender};
+use accumulator::state::intent_log::IntentLog;
+use accumulator::types::instrument::Instrument;
+use accumulator::types::price::Price;
+
+use common::{INITIAL, MockVenue, qty};
+
+fn sol() -> Instrument {
+    "SOL/GBP".parse().unwrap()
+}
+
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("accumulator-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn place(order_id: &str, side: Side) -> OrderAction {
+    OrderAction::Place(Order {
+        order_id: order_id.to_string(),
+        instrument: sol(),
+        side,
+        price: Price::new(93.0),
+        quantity: qty(0.05),
+        order_type: OrderType::POST_ONLY,
+        cycle_id: None,
+    })
+}
+
+fn cancelled(venue: &MockVenue) -> Vec<String> {
+    venue
+        .actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            OrderAction::Cancel { order_id, .. } => Some(order_id),
+            _ => None,
+        })
+        .collect()
+}
+
+fn unresolved(log: &IntentLog) -> Vec<String> {
+    let mut ids: Vec<_> = log
+        .unresolved()
+        .into_iter()
+        .map(|intent| intent.order_id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// The first run: both places are logged, the bid reaches the venue and the process
+/// dies before the ask is sent or either is resolved. Someone also rests an order of
+/// their own on the account.
+async fn crash(path: &Path, venue: &MockVenue) {
+    let mut log = IntentLog::open(path).unwrap();
+    log.record_actions(&[place("3f9c-SOLGBP-b1", Buy), place("3f9c-SOLGBP-s2", Sell)])
+        .unwrap();
+    venue
+        .execute(&[place("3f9c-SOLGBP-b1", Buy)])
+        .await
+        .unwrap();
+    drop(log);
+
+    venue.execute(&[place("manual-sell", Sell)]).await.unwrap();
+}
+
+/// Restarts on the log at `path` under a new session, which cannot tell the first run's
+/// ids from anyone else's without the log, recovering what the crash left. Returns the
+/// log as the next start would find it.
+async fn restart(
+    path: &Path,
+    venue: &MockVenue,
+    reports: &ReportSender,
+    cancel_resting: bool,
+) -> IntentLog {
+    let log = IntentLog::open(path).unwrap();
+    assert_eq!(unresolved(&log), ["3f9c-SOLGBP-b1", "3f9c-SOLGBP-s2"]);
+    assert_eq!(log.next_seq(), 3);
+
+    let logged = IntentLoggedVenue::new(Box::new(venue.clone()), log);
+    logged.spawn_resolver(reports.subscribe());
+    logged
+        .recover(
+            &[sol()],
+            &OrderIds::structured("7a0e5511", 3),
+            cancel_resting,
+        )
+        .await
+        .unwrap();
+
+    // Lets the resolver take the cancel reports before the log is reopened.
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+    drop(logged);
+
+    IntentLog::open(path).unwrap()
+}
+
+#[tokio::test]
+async fn a_crash_before_resolution_cancels_what_was_left_resting() {
+    let path = log_path("intent-log-crash.jsonl");
+    let (reports, _) = broadcast::channel(64);
+    let venue = MockVenue::new(reports.clone(), INITIAL);
+    crash(&path, &venue).await;
+
+    // The process also died halfway through appending a third intent.
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    write!(file, r#"{{"place":{{"order_id":"3f9c-SOLGBP-b3","instr"#).unwrap();
+    drop(file);
+
+    let log = restart(&path, &venue, &reports, true).await;
+
+    // Only the logged order that was resting is cancelled; the one never sent is
+    // resolved, and the order the engine never placed is left alone.
+    assert_eq!(cancelled(&venue), ["3f9c-SOLGBP-b1"]);
+    assert!(unresolved(&log).is_empty(), "{:?}", unresolved(&log));
+    assert_eq!(log.next_seq(), 3);
+}
+
+#[tokio::test]
+async fn a_crash_before_resolution_leaves_resting_orders_to_adopt() {
+    let path = log_path("intent-log-adopt.jsonl");
+    let (reports, _) = broadcast::channel(64);
+    let venue = MockVenue::new(reports.clone(), INITIAL);
+    crash(&path, &venue).await;
+
+    let log = restart(&path, &venue, &reports, false).await;
+
+    assert!(cancelled(&venue).is_empty());
+    assert_eq!(unresolved(&log), ["3f9c-SOLGBP-b1"]);
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use accumulator::inventory::InventorySource;
+use accumulator::inventory::readiness::await_first_snapshot;
+use accumulator::scenario::venues::VenueKind;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+
+const TIMEOUT: Duration = Duration::from_millis(100);
+const PAPER: Inventory = Inventory {
+    base: 2.0,
+    quote: 500.0,
+};
+
+/// Reads as zeros, like a balances feed, until `deliver` is called.
+#[derive(Clone)]
+struct SlowInventory {
+    tx: watch::Sender<Inventory>,
+    ready: Arc<AtomicBool>,
+}
+
+impl SlowInventory {
+    fn new() -> Self {
+        Self {
+            tx: watch::channel(Inventory::default()).0,
+            ready: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn deliver(&self, inventory: Inventory) {
+        self.ready.store(true, Ordering::Release);
+        self.tx.send_replace(inventory);
+    }
+}
+
+impl InventorySource for SlowInventory {
+    fn subscribe(&self) -> watch::Receiver<Inventory> {
+        self.tx.subscribe()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+}
+
+fn instrument() -> Instrument {
+    InstrumentConfig::default().load().unwrap()
+}
+
+#[tokio::test]
+async fn waits_for_the_first_snapshot() {
+    let source = SlowInventory::new();
+    let feed = source.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        feed.deliver(Inventory::new(1.5, 250.0));
+    });
+
+    for venue in [VenueKind::Kraken, VenueKind::DryRun] {
+        let paper = await_first_snapshot(&source, &instrument(), venue, TIMEOUT * 10, PAPER)
+            .await
+            .unwrap();
+        assert!(paper.is_none());
+    }
+    assert_eq!(source.subscribe().borrow().quote, 250.0);
+}
+
+#[tokio::test]
+async fn a_live_venue_refuses_to_trade_without_a_snapshot() {
+    let error = await_first_snapshot(
+        &SlowInventory::new(),
+        &instrument(),
+        VenueKind::Kraken,
+        TIMEOUT,
+        PAPER,
+    )
+    .await
+    .unwrap_err();
+
+    assert!(
+        error.to_string().contains("no inventory snapshot"),
+        "{error:#}"
+    );
+}
+
+#[tokio::test]
+async fn a_dry_run_falls_back_to_paper_balances() {
+    let paper = await_first_snapshot(
+        &SlowInventory::new(),
+        &instrument(),
+        VenueKind::DryRun,
+        TIMEOUT,
+        PAPER,
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert_eq!((paper.base, paper.quote), (2.0, 500.0));
+}
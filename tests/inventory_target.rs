@@ -0,0 +1,179 @@
+mod common;
+
+use std::time::Instant;
+
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::Buy;
+use accumulator::market::market_state::MarketState;
+use accumulator::scenario::scenario::Scenario;
+use accumulator::scenario::strategies::StrategyKind;
+use accumulator::signals::config::SignalsConfig;
+use accumulator::strategy::strategies::inventory_target::{
+    InventoryTargetParams, InventoryTargetStrategy,
+};
+use accumulator::strategy::strategy::Strategy;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::price::Price;
+use accumulator::types::quote_target::{NoQuoteReason, QuoteTarget};
+
+use common::{Act, Expect, Harness, INITIAL, Step};
+
+fn sol_gbp() -> Instrument {
+    InstrumentConfig::default().load().unwrap()
+}
+
+fn target(
+    params: &InventoryTargetParams,
+    bid: f64,
+    ask: f64,
+    inventory: Inventory,
+) -> Result<QuoteTarget, NoQuoteReason> {
+    let instrument = sol_gbp();
+    let mut market = MarketState::new();
+    let event = MarketEvent::TopOfBook {
+        instrument: instrument.clone(),
+        best_bid: Price::new(bid),
+        best_ask: Price::new(ask),
+        bid_size: None,
+        ask_size: None,
+        timestamp_ms: 0,
+    };
+    market.on_market_event(&event, Instant::now());
+    let signals = Scenario::signals(StrategyKind::Accumulate, &SignalsConfig::default());
+
+    InventoryTargetStrategy::new(&instrument, params).compute_target(&market, &signals, inventory)
+}
+
+fn accumulate(target_base: f64) -> InventoryTargetParams {
+    InventoryTargetParams {
+        target_base,
+        tolerance: 0.01,
+        max_chase_ticks: 1,
+    }
+}
+
+#[test]
+fn bids_fill_inventory_from_nothing_up_to_the_target_and_stop_there() {
+    let params = accumulate(0.22);
+    let mut inventory = Inventory::new(0.0, 1_000.0);
+    let mut sizes = Vec::new();
+
+    loop {
+        match target(&params, 93.00, 93.10, inventory) {
+            Ok(target) => {
+                assert!(target.ask.is_none(), "{target:?}");
+                let bid = target.bid.unwrap();
+                // A tick inside the touch, still behind the ask.
+                assert_eq!(bid.price.to_string(), "93.01");
+                let quantity = bid.quantity.as_f64();
+                inventory.base += quantity;
+                inventory.quote -= quantity * bid.price.as_f64();
+                sizes.push(quantity);
+            }
+            Err(NoQuoteReason::AtInventoryTarget) => break,
+            Err(reason) => panic!("{reason:?}"),
+        }
+        assert!(sizes.len() < 10, "{sizes:?}");
+    }
+
+    // 5.00 of notional buys 0.05 at a time, and the last order only the remainder.
+    assert_eq!(sizes.len(), 5, "{sizes:?}");
+    assert!((sizes[4] - 0.02).abs() < 1e-9, "{sizes:?}");
+    assert!((inventory.base - 0.22).abs() < 1e-9, "{}", inventory.base);
+}
+
+#[test]
+fn above_the_target_only_asks_and_within_tolerance_nothing() {
+    let params = accumulate(0.5);
+
+    let over = target(&params, 93.00, 93.10, Inventory::new(0.6, 0.0)).unwrap();
+    assert!(over.bid.is_none(), "{over:?}");
+    let ask = over.ask.unwrap();
+    assert_eq!(ask.price.to_string(), "93.09");
+    assert!((ask.quantity.as_f64() - 0.05).abs() < 1e-9, "{ask:?}");
+
+    for base in [0.49, 0.5, 0.51] {
+        assert!(
+            matches!(
+                target(&params, 93.00, 93.10, Inventory::new(base, 0.0)),
+                Err(NoQuoteReason::AtInventoryTarget)
+            ),
+            "{base}"
+        );
+    }
+}
+
+#[test]
+fn chasing_never_crosses_the_book() {
+    let params = InventoryTargetParams {
+        max_chase_ticks: 5,
+        ..accumulate(1.0)
+    };
+
+    // A tick wide: the bid can only join the touch.
+    let bid = target(&params, 93.00, 93.01, Inventory::new(0.0, 0.0))
+        .unwrap()
+        .bid
+        .unwrap();
+    assert_eq!(bid.price.to_string(), "93.00");
+
+    let bid = target(&params, 93.00, 93.10, Inventory::new(0.0, 0.0))
+        .unwrap()
+        .bid
+        .unwrap();
+    assert_eq!(bid.price.to_string(), "93.05");
+}
+
+#[tokio::test]
+async fn the_engine_buys_up_to_the_target_through_fills() {
+    let mut harness = Harness::new(|config| {
+        config.strategy.kind = StrategyKind::Accumulate;
+        config.strategy.inventory_target = InventoryTargetParams {
+            target_base: INITIAL.base + 0.1,
+            ..accumulate(0.0)
+        };
+    })
+    .await
+    .unwrap();
+
+    let book = || Step::Book {
+        bid: 93.00,
+        ask: 93.10,
+    };
+    harness
+        .run(&[
+            (0, book()),
+            (0, Step::Expect(Expect::Actions(vec![Act::Place(Buy)]))),
+            (10, Step::Accept(Buy)),
+            (20, Step::Fill(Buy)),
+            (1_000, book()),
+            (1_000, Step::Expect(Expect::Actions(vec![Act::Place(Buy)]))),
+            (1_010, Step::Accept(Buy)),
+            (1_020, Step::Fill(Buy)),
+            (2_000, book()),
+            (3_000, book()),
+            (3_000, Step::Expect(Expect::Nothing)),
+        ])
+        .await
+        .unwrap();
+
+    let placed: Vec<_> = harness
+        .actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            OrderAction::Place(order) => Some((order.side, order.quantity.as_f64())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(placed, [(Buy, 0.05), (Buy, 0.05)]);
+
+    let summary = harness.summary().await;
+    assert_eq!(summary.bids.fills, 2);
+    assert!(
+        summary.no_quotes.contains_key("at_inventory_target"),
+        "{:?}",
+        summary.no_quotes
+    );
+}
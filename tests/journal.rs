@@ -0,0 +1,135 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use accumulator::backtest::journal::{self, JournalRecorder};
+use accumulator::events::{BookLevel, MarketEvent};
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+
+fn journal_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("accumulator-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn sol() -> Instrument {
+    InstrumentConfig::default().load().unwrap()
+}
+
+fn btc() -> Instrument {
+    "BTC/GBP".parse().unwrap()
+}
+
+fn book(instrument: &Instrument, bid: f64, ask: f64, timestamp_ms: u64) -> MarketEvent {
+    MarketEvent::TopOfBook {
+        instrument: instrument.clone(),
+        best_bid: Price::new(bid),
+        best_ask: Price::new(ask),
+        bid_size: None,
+        ask_size: None,
+        timestamp_ms,
+    }
+}
+
+/// `(kind, timestamp, instrument)` of each event, for comparing replays.
+fn summary(events: &[MarketEvent]) -> Vec<(&'static str, u64, String)> {
+    events
+        .iter()
+        .map(|event| {
+            (
+                event.kind(),
+                event.timestamp_ms(),
+                event.instrument().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn replays_what_the_recorder_wrote() {
+    let path = journal_path("journal.jsonl");
+    let mut recorder = JournalRecorder::spawn(&path).unwrap();
+    let (sol, btc) = (sol(), btc());
+
+    for event in [
+        book(&sol, 93.00, 93.10, 1_000),
+        MarketEvent::BookUpdate {
+            instrument: sol.clone(),
+            bids: vec![BookLevel {
+                price: Price::new(92.90),
+                quantity: 4.0,
+            }],
+            asks: vec![],
+            snapshot: false,
+            timestamp_ms: 1_100,
+        },
+        book(&btc, 35_000.0, 35_010.0, 1_200),
+        MarketEvent::Trade {
+            instrument: sol.clone(),
+            price: Price::new(93.10),
+            quantity: 0.5,
+            timestamp_ms: 1_300,
+        },
+    ] {
+        recorder.record(&event);
+    }
+
+    // Depth updates are not recorded.
+    let mut written = 0;
+    for _ in 0..100 {
+        written = std::fs::read_to_string(&path).unwrap().lines().count();
+        if written == 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(written, 3);
+
+    let replayed = journal::read(&path, &[sol.clone(), btc.clone()]).unwrap();
+    assert_eq!(
+        summary(&replayed),
+        [
+            ("book", 1_000, "SOL/GBP".to_string()),
+            ("book", 1_200, "BTC/GBP".to_string()),
+            ("trade", 1_300, "SOL/GBP".to_string()),
+        ]
+    );
+    match &replayed[2] {
+        MarketEvent::Trade {
+            price, quantity, ..
+        } => assert_eq!((price.as_f64(), *quantity), (93.10, 0.5)),
+        other => panic!("expected a trade, got {other:?}"),
+    }
+
+    // Other instruments' events are skipped.
+    let sol_only = journal::read(&path, &[sol]).unwrap();
+    assert_eq!(summary(&sol_only).len(), 2);
+}
+
+#[test]
+fn reads_csv_journals_and_names_missing_fields() {
+    let path = journal_path("journal.csv");
+    std::fs::write(
+        &path,
+        format!(
+            "{}\n1000,SOL/GBP,book,93.00,93.10,,\n1250,SOL/GBP,trade,,,93.05,0.5\n",
+            journal::JOURNAL_HEADER
+        ),
+    )
+    .unwrap();
+    let events = journal::read(&path, &[sol()]).unwrap();
+    assert_eq!(
+        summary(&events),
+        [
+            ("book", 1_000, "SOL/GBP".to_string()),
+            ("trade", 1_250, "SOL/GBP".to_string()),
+        ]
+    );
+
+    std::fs::write(&path, "1000,SOL/GBP,book,93.00,,,\n").unwrap();
+    let error = journal::read(&path, &[sol()]).unwrap_err();
+    assert!(
+        format!("{error:#}").contains("best_ask: missing"),
+        "{error:#}"
+    );
+}
@@ -0,0 +1,410 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::Router;
+use axum::http::StatusCode;
+use axum::routing::post;
+use futures_util::future::join_all;
+
+use accumulator::engine::supervisor::Supervisor;
+use accumulator::execution::order_action::OrderType;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::execution::order_report::RejectKind;
+use accumulator::inventory::InventorySource;
+use accumulator::kraken::kraken_client::{KrakenClient, RetryConfig, reject_kind};
+use accumulator::kraken::kraken_config::KrakenConfig;
+use accumulator::kraken::kraken_inventory::KrakenInventory;
+use accumulator::kraken::kraken_order_socket::OrderSocketConfig;
+use accumulator::kraken::rate_limit::RateLimitConfig;
+use accumulator::random::SeededRng;
+use accumulator::types::instrument::Instrument;
+use accumulator::types::price::Price;
+
+use common::qty;
+
+const PLACED: &str = r#"{"error":[],"result":{"txid":["OABC12-DEF34-GHI56"],"descr":{"order":"buy 0.05 SOLGBP @ limit 93.00"}}}"#;
+
+/// Serves `app` on a free local port as a stand-in for Kraken, returning its url.
+async fn serve(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{address}")
+}
+
+fn client(base_url: &str, rate_limit: RateLimitConfig) -> KrakenClient {
+    KrakenClient::new(
+        KrakenConfig {
+            api_key: "key".to_string(),
+            api_secret: "c2VjcmV0".to_string(),
+            rate_limit,
+            retry: RetryConfig {
+                max_attempts: 3,
+                initial_backoff_ms: 10,
+                max_backoff_ms: 40,
+            },
+            order_socket: OrderSocketConfig::default(),
+        },
+        SeededRng::new(7).stream("kraken_retry"),
+    )
+    .with_base_url(base_url)
+}
+
+/// Answers each call to `path` with `respond(n)`, `n` counting calls from 0.
+fn route(
+    path: &str,
+    calls: &Arc<AtomicUsize>,
+    respond: impl Fn(usize) -> (StatusCode, String) + Clone + Send + Sync + 'static,
+) -> Router {
+    let calls = calls.clone();
+    Router::new().route(
+        path,
+        post(move || {
+            let response = respond(calls.fetch_add(1, Ordering::SeqCst));
+            async move { response }
+        }),
+    )
+}
+
+fn sol() -> Instrument {
+    "SOL/GBP".parse().unwrap()
+}
+
+#[tokio::test]
+async fn a_burst_of_orders_is_paced_by_the_trading_counter() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let url = serve(route("/0/private/AddOrder", &calls, |_| {
+        (StatusCode::OK, PLACED.to_string())
+    }))
+    .await;
+    let client = client(
+        &url,
+        RateLimitConfig {
+            trading_capacity: 2.0,
+            trading_decay_per_sec: 20.0,
+            ..RateLimitConfig::default()
+        },
+    );
+
+    let sol = sol();
+    let start = Instant::now();
+    let ids: Vec<String> = (1..=20).map(|seq| format!("3f9c-SOLGBP-b{seq}")).collect();
+    let results = join_all(ids.iter().map(|id| {
+        client.limit_order(
+            &sol,
+            Buy,
+            Price::new(93.00),
+            qty(0.05),
+            OrderType::POST_ONLY,
+            id,
+        )
+    }))
+    .await;
+
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(calls.load(Ordering::SeqCst), 20);
+    // Two go at once; the other 18 wait for the counter to lose a point, 50ms each.
+    assert!(
+        start.elapsed() >= Duration::from_millis(850),
+        "{:?}",
+        start.elapsed()
+    );
+    assert!(client.rate_limits().trading <= 2.0 + 1e-9);
+}
+
+#[tokio::test]
+async fn retries_transient_errors_with_backoff() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let url = serve(route("/0/private/Balance", &calls, |n| match n {
+        0 => (
+            StatusCode::OK,
+            r#"{"error":["EAPI:Rate limit exceeded"]}"#.to_string(),
+        ),
+        1 => (StatusCode::SERVICE_UNAVAILABLE, "down".to_string()),
+        _ => (
+            StatusCode::OK,
+            r#"{"error":[],"result":{"ZGBP":"100.0"}}"#.to_string(),
+        ),
+    }))
+    .await;
+
+    let balance = client(&url, RateLimitConfig::default())
+        .balance()
+        .await
+        .unwrap();
+    assert_eq!(balance["ZGBP"], "100.0");
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn gives_up_on_errors_that_are_not_transient() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let url = serve(route("/0/private/Balance", &calls, |_| {
+        (
+            StatusCode::OK,
+            r#"{"error":["EAPI:Invalid key"]}"#.to_string(),
+        )
+    }))
+    .await;
+
+    let error = client(&url, RateLimitConfig::default())
+        .balance()
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("EAPI:Invalid key"), "{error:#}");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+/// Kraken failing every place with a 502, and listing `open` as open orders and `closed`
+/// as closed ones.
+async fn ambiguous_kraken(
+    add_orders: &Arc<AtomicUsize>,
+    open: &'static str,
+    closed: &'static str,
+) -> String {
+    let lookups = Arc::new(AtomicUsize::new(0));
+    serve(
+        route("/0/private/AddOrder", add_orders, |_| {
+            (StatusCode::BAD_GATEWAY, "bad gateway".to_string())
+        })
+        .merge(route("/0/private/OpenOrders", &lookups, move |_| {
+            (
+                StatusCode::OK,
+                format!(r#"{{"error":[],"result":{{"open":{open}}}}}"#),
+            )
+        }))
+        .merge(route("/0/private/ClosedOrders", &lookups, move |_| {
+            (
+                StatusCode::OK,
+                format!(r#"{{"error":[],"result":{{"closed":{closed}}}}}"#),
+            )
+        })),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn an_ambiguous_place_is_looked_up_rather_than_sent_again() {
+    let add_orders = Arc::new(AtomicUsize::new(0));
+    let url = ambiguous_kraken(
+        &add_orders,
+        r#"{"OABC12-DEF34-GHI56":{"cl_ord_id":"3f9c-SOLGBP-b1","status":"open","descr":{"pair":"SOLGBP","type":"buy","price":"93.00"},"vol":"0.05","vol_exec":"0"}}"#,
+        "{}",
+    )
+    .await;
+
+    let placed = client(&url, RateLimitConfig::default())
+        .limit_order(
+            &sol(),
+            Buy,
+            Price::new(93.00),
+            qty(0.05),
+            OrderType::POST_ONLY,
+            "3f9c-SOLGBP-b1",
+        )
+        .await
+        .unwrap();
+    assert_eq!(placed.txid, ["OABC12-DEF34-GHI56"]);
+    assert_eq!(add_orders.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn an_ambiguous_place_that_filled_at_once_is_found_among_closed_orders() {
+    let add_orders = Arc::new(AtomicUsize::new(0));
+    let url = ambiguous_kraken(
+        &add_orders,
+        "{}",
+        r#"{"OABC12-DEF34-GHI56":{"cl_ord_id":"3f9c-SOLGBP-b1","status":"closed","descr":{"pair":"SOLGBP","type":"buy","price":"93.00"},"vol":"0.05","vol_exec":"0.05"}}"#,
+    )
+    .await;
+
+    let placed = client(&url, RateLimitConfig::default())
+        .limit_order(
+            &sol(),
+            Buy,
+            Price::new(93.00),
+            qty(0.05),
+            OrderType::ImmediateOrCancel,
+            "3f9c-SOLGBP-b1",
+        )
+        .await
+        .unwrap();
+    assert_eq!(placed.txid, ["OABC12-DEF34-GHI56"]);
+    assert_eq!(placed.status.as_deref(), Some("closed"));
+    assert_eq!(add_orders.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn an_ambiguous_place_not_found_fails_without_resending() {
+    let add_orders = Arc::new(AtomicUsize::new(0));
+    let url = ambiguous_kraken(&add_orders, "{}", "{}").await;
+
+    let error = client(&url, RateLimitConfig::default())
+        .limit_order(
+            &sol(),
+            Buy,
+            Price::new(93.00),
+            qty(0.05),
+            OrderType::POST_ONLY,
+            "3f9c-SOLGBP-b1",
+        )
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("502"), "{error:#}");
+    assert_eq!(add_orders.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn amends_by_client_order_id() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::new(std::sync::Mutex::new(String::new()));
+    let app = {
+        let (calls, seen) = (calls.clone(), seen.clone());
+        Router::new().route(
+            "/0/private/AmendOrder",
+            post(move |body: String| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                *seen.lock().unwrap() = body;
+                async { r#"{"error":[],"result":{"amend_id":"TZ63HS-YBD4M-3RDG7H"}}"# }
+            }),
+        )
+    };
+    let url = serve(app).await;
+
+    let amended = client(&url, RateLimitConfig::default())
+        .amend_order(&sol(), "3f9c-SOLGBP-b1", Price::new(92.97), qty(0.05))
+        .await
+        .unwrap();
+    assert_eq!(amended.amend_id, "TZ63HS-YBD4M-3RDG7H");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let body = seen.lock().unwrap().clone();
+    for param in [
+        "cl_ord_id=3f9c-SOLGBP-b1",
+        "limit_price=92.97",
+        "order_qty=0.05",
+        "post_only=true",
+    ] {
+        assert!(body.contains(param), "{param} missing from {body}");
+    }
+}
+
+#[tokio::test]
+async fn places_each_order_type_with_its_flags() {
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let app = {
+        let seen = seen.clone();
+        Router::new().route(
+            "/0/private/AddOrder",
+            post(move |body: String| {
+                seen.lock().unwrap().push(body);
+                async { PLACED }
+            }),
+        )
+    };
+    let client = client(&serve(app).await, RateLimitConfig::default());
+
+    for (order_type, id) in [
+        (OrderType::POST_ONLY, "3f9c-SOLGBP-s1"),
+        (OrderType::Limit { post_only: false }, "3f9c-SOLGBP-s2"),
+        (OrderType::ImmediateOrCancel, "3f9c-SOLGBP-s3"),
+    ] {
+        client
+            .limit_order(&sol(), Sell, Price::new(92.98), qty(0.05), order_type, id)
+            .await
+            .unwrap();
+    }
+
+    // Everything but the nonce, in the order sent.
+    let params: Vec<Vec<String>> = seen
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|body| {
+            body.split('&')
+                .filter(|param| !param.starts_with("nonce="))
+                .map(String::from)
+                .collect()
+        })
+        .collect();
+    let common = [
+        "ordertype=limit",
+        "type=sell",
+        "pair=SOLGBP",
+        "price=92.98",
+        "volume=0.05",
+    ];
+    let expected = |flag: Option<&str>, id: &str| -> Vec<String> {
+        common
+            .iter()
+            .copied()
+            .chain(flag)
+            .map(String::from)
+            .chain([format!("cl_ord_id={id}")])
+            .collect()
+    };
+    assert_eq!(
+        params,
+        [
+            expected(Some("oflags=post"), "3f9c-SOLGBP-s1"),
+            expected(None, "3f9c-SOLGBP-s2"),
+            expected(Some("timeinforce=IOC"), "3f9c-SOLGBP-s3"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn inventory_starts_from_the_rest_balances() {
+    let (balances, tokens) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    let url = serve(
+        route("/0/private/Balance", &balances, |_| {
+            (
+                StatusCode::OK,
+                r#"{"error":[],"result":{"SOL":"2.5","ZGBP":"310.20","XXBT":"0.1"}}"#.to_string(),
+            )
+        })
+        .merge(route("/0/private/GetWebSocketsToken", &tokens, |_| {
+            (
+                StatusCode::OK,
+                r#"{"error":[],"result":{"token":"token","expires":900}}"#.to_string(),
+            )
+        })),
+    )
+    .await;
+    let (supervisor, _escalations) = Supervisor::new();
+
+    // Nothing listens on the websocket, so any balances can only have come over REST.
+    let inventory = KrakenInventory::spawn_at(
+        &client(&url, RateLimitConfig::default()),
+        "ws://127.0.0.1:9",
+        &sol(),
+        &supervisor,
+    )
+    .await
+    .unwrap();
+
+    assert!(inventory.is_ready());
+    let held = *inventory.subscribe().borrow();
+    assert_eq!((held.base, held.quote), (2.5, 310.20));
+    assert_eq!(balances.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn refusals_are_read_from_kraken_error_codes() {
+    assert_eq!(
+        reject_kind("EOrder:Post only order"),
+        RejectKind::PostOnlyWouldCross
+    );
+    assert_eq!(
+        reject_kind("EOrder:Insufficient funds"),
+        RejectKind::InsufficientFunds
+    );
+    assert_eq!(
+        reject_kind("EAPI:Rate limit exceeded"),
+        RejectKind::RateLimited
+    );
+    assert_eq!(reject_kind("EOrder:Invalid price"), RejectKind::Other);
+}
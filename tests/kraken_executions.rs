@@ -0,0 +1,176 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::Buy;
+use accumulator::execution::order_ids::OrderIds;
+use accumulator::execution::order_report::OrderReport;
+use accumulator::execution::order_side_manager::{OrderSideManager, SideInputs};
+use accumulator::execution::report_sequencer::ReportSequencer;
+use accumulator::execution::trade_checkpoint::TradeCheckpoint;
+use accumulator::execution::types::OrderSideState;
+use accumulator::kraken::kraken_executions::frame_reports;
+use accumulator::types::instrument::InstrumentConfig;
+use accumulator::types::price::Price;
+use accumulator::types::quote::Quote;
+
+use common::qty;
+
+/// A bid of 0.05 SOL/GBP at 93.00 as the executions channel reports it once the venue
+/// accepted it: part filled, with the fill delivered twice, then filled further while
+/// the socket is down. Each reconnect replays the open orders and the trades so far.
+const SESSION: [&str; 8] = [
+    r#"{"channel":"executions","type":"snapshot","data":[]}"#,
+    r#"{"channel":"executions","type":"update","data":[{"exec_type":"trade","cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","last_qty":0.02,"last_price":93.0,"avg_price":93.0,"cum_qty":0.02,"order_qty":0.05,"order_status":"partially_filled"}]}"#,
+    r#"{"channel":"executions","type":"update","data":[{"exec_type":"trade","cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","last_qty":0.02,"last_price":93.0,"avg_price":93.0,"cum_qty":0.02,"order_qty":0.05,"order_status":"partially_filled"}]}"#,
+    r#"{"channel":"executions","type":"snapshot","data":[{"cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","order_qty":0.05,"limit_price":93.0,"cum_qty":0.03,"order_status":"partially_filled"},{"exec_type":"trade","cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","last_qty":0.02,"last_price":93.0,"avg_price":93.0,"cum_qty":0.02,"order_qty":0.05},{"exec_type":"trade","cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","last_qty":0.01,"last_price":93.0,"avg_price":93.0,"cum_qty":0.03,"order_qty":0.05}]}"#,
+    r#"{"channel":"executions","type":"update","data":[{"exec_type":"trade","cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","last_qty":0.01,"last_price":93.0,"avg_price":93.0,"cum_qty":0.03,"order_qty":0.05,"order_status":"partially_filled"}]}"#,
+    r#"{"channel":"executions","type":"update","data":[{"exec_type":"filled","cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","last_qty":0.02,"last_price":93.0,"avg_price":93.0,"cum_qty":0.05,"order_qty":0.05,"order_status":"filled"}]}"#,
+    // The order is no longer open, but its trades are still replayed.
+    r#"{"channel":"executions","type":"snapshot","data":[{"exec_type":"trade","cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","last_qty":0.02,"last_price":93.0,"avg_price":93.0,"cum_qty":0.05,"order_qty":0.05}]}"#,
+    r#"{"channel":"executions","type":"update","data":[{"exec_type":"filled","cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","last_qty":0.02,"last_price":93.0,"avg_price":93.0,"cum_qty":0.05,"order_qty":0.05,"order_status":"filled"}]}"#,
+];
+
+fn placing_bid(start: Instant) -> OrderSideManager {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut manager = OrderSideManager::for_side(Buy, OrderIds::sequential());
+    let target = Quote {
+        price: Price::new(93.00),
+        quantity: qty(0.05),
+    };
+    let tick = instrument.trading_rules().price_tick;
+    let actions =
+        manager.actions_for_target(SideInputs::new(&instrument, start, tick, Some(target)));
+    assert!(matches!(actions[..], [OrderAction::Place(_)]));
+    manager
+}
+
+#[test]
+fn a_replayed_session_reaches_each_state_once() {
+    let start = Instant::now();
+    let mut manager = placing_bid(start);
+    let mut sequencer = ReportSequencer::new();
+    // Accepted by the REST reply, as the venue reports it.
+    manager.on_report(
+        &OrderReport::Accepted {
+            order_id: "sim-1".to_string(),
+            instrument: InstrumentConfig::default().load().unwrap(),
+            side: Buy,
+            price: Price::new(93.00),
+            quantity: qty(0.05),
+        },
+        start,
+    );
+
+    let mut kinds = Vec::new();
+    let mut states = Vec::new();
+    for (i, text) in SESSION.iter().enumerate() {
+        for report in frame_reports(text, &mut sequencer, &TradeCheckpoint::new()) {
+            kinds.push(report.kind());
+            manager.on_report(&report, start + Duration::from_millis(i as u64));
+            states.push(manager.state().clone());
+        }
+    }
+
+    assert_eq!(
+        kinds,
+        ["partially_filled", "snapshot", "filled"],
+        "{states:?}"
+    );
+    assert!(matches!(
+        &states[1],
+        OrderSideState::Live { resting, filled, .. }
+            if (resting.quantity.as_f64() - 0.02).abs() < 1e-9
+                && (filled.as_f64() - 0.03).abs() < 1e-9
+    ));
+    assert!(matches!(manager.state(), OrderSideState::NoOrder));
+}
+
+#[test]
+fn a_snapshot_settles_an_accept_missed_while_disconnected() {
+    let start = Instant::now();
+    let mut manager = placing_bid(start);
+    let mut sequencer = ReportSequencer::new();
+
+    let snapshot = r#"{"channel":"executions","type":"snapshot","data":[{"cl_ord_id":"sim-1","symbol":"SOL/GBP","side":"buy","order_qty":0.05,"limit_price":93.0,"cum_qty":0,"order_status":"new"}]}"#;
+    let reports = frame_reports(snapshot, &mut sequencer, &TradeCheckpoint::new());
+    assert!(matches!(
+        &reports[..],
+        [OrderReport::Snapshot { order_id, cum_quantity, .. }]
+            if order_id == "sim-1" && cum_quantity.is_zero()
+    ));
+    manager.on_report(&reports[0], start);
+    assert!(matches!(
+        manager.state(),
+        OrderSideState::Live { resting, filled, .. }
+            if filled.is_zero() && resting.quantity == qty(0.05)
+    ));
+
+    // The same order restated by the next reconnect tells the engine nothing.
+    assert!(frame_reports(snapshot, &mut sequencer, &TradeCheckpoint::new()).is_empty());
+}
+
+/// A trade of `sim-2`, a bid of 0.05 SOL/GBP at 93.00, as the executions channel gives it.
+fn trade(trade_id: u64, cum_qty: f64) -> String {
+    format!(
+        r#"{{"exec_type":"trade","trade_id":{trade_id},"cl_ord_id":"sim-2","symbol":"SOL/GBP","side":"buy","last_qty":0.01,"last_price":93.0,"avg_price":93.0,"cum_qty":{cum_qty},"order_qty":0.05}}"#
+    )
+}
+
+fn frame(kind: &str, trades: &[String]) -> String {
+    format!(
+        r#"{{"channel":"executions","type":"{kind}","data":[{}]}}"#,
+        trades.join(",")
+    )
+}
+
+fn filled_so_far(reports: &[OrderReport]) -> Vec<f64> {
+    reports
+        .iter()
+        .map(|report| match report {
+            OrderReport::PartiallyFilled { cum_quantity, .. } => cum_quantity.as_f64(),
+            other => panic!("expected a fill, got {other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn a_restored_checkpoint_skips_replayed_trades_and_catches_up_missed_ones() {
+    let checkpoint = TradeCheckpoint::new();
+    let mut sequencer = ReportSequencer::new();
+
+    // First start: the snapshot's trades are history and only set the checkpoint.
+    let snapshot = frame("snapshot", &[trade(11, 0.02), trade(10, 0.01)]);
+    assert!(frame_reports(&snapshot, &mut sequencer, &checkpoint).is_empty());
+    let update = frame("update", &[trade(12, 0.03)]);
+    assert_eq!(
+        filled_so_far(&frame_reports(&update, &mut sequencer, &checkpoint)),
+        [0.03]
+    );
+    let saved = checkpoint.saved();
+    assert_eq!(saved.get("SOL/GBP"), Some(&12));
+
+    // Restarted from the saved state, after two more trades while down. The snapshot
+    // lists the newest first.
+    let checkpoint = TradeCheckpoint::new();
+    checkpoint.restore(saved);
+    let mut sequencer = ReportSequencer::new();
+    let snapshot = frame(
+        "snapshot",
+        &[
+            trade(14, 0.05),
+            trade(13, 0.04),
+            trade(12, 0.03),
+            trade(11, 0.02),
+        ],
+    );
+    assert_eq!(
+        filled_so_far(&frame_reports(&snapshot, &mut sequencer, &checkpoint)),
+        [0.04, 0.05]
+    );
+    assert_eq!(checkpoint.last("SOL/GBP"), Some(14));
+
+    // A reconnect replays the same trades; nothing is new.
+    assert!(frame_reports(&snapshot, &mut sequencer, &checkpoint).is_empty());
+}
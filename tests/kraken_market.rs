@@ -0,0 +1,347 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+use accumulator::config::app_config::AppConfig;
+use accumulator::events::{BookLevel, MarketEvent};
+use accumulator::kraken::kraken_book::KrakenBook;
+use accumulator::kraken::kraken_market::KrakenMarket;
+use accumulator::kraken::symbols::{self, WsVersion, kraken_pair, ws_instrument, ws_pair};
+use accumulator::market::market_source::{MarketDataSource, SubscriptionRejected};
+use accumulator::market::market_state::MarketState;
+use accumulator::types::instrument::Instrument;
+use accumulator::types::price::Price;
+
+fn instrument(name: &str) -> Instrument {
+    name.parse().unwrap()
+}
+
+#[test]
+fn spells_pairs_the_way_each_api_expects() {
+    let btc = instrument("BTC/GBP");
+    assert_eq!(kraken_pair(&btc).unwrap(), "XBTGBP");
+    assert_eq!(ws_pair(&btc, WsVersion::V1).unwrap(), "XBT/GBP");
+    assert_eq!(ws_pair(&btc, WsVersion::V2).unwrap(), "BTC/GBP");
+
+    let sol = instrument("SOL/GBP");
+    assert_eq!(kraken_pair(&sol).unwrap(), "SOLGBP");
+    assert_eq!(ws_pair(&sol, WsVersion::V1).unwrap(), "SOL/GBP");
+    assert_eq!(ws_pair(&sol, WsVersion::V2).unwrap(), "SOL/GBP");
+
+    let doge = Instrument::watched("DOGE".to_string(), "USD".to_string());
+    assert_eq!(kraken_pair(&doge).unwrap(), "XDGUSD");
+    assert_eq!(ws_pair(&doge, WsVersion::V1).unwrap(), "XDG/USD");
+    assert_eq!(ws_pair(&doge, WsVersion::V2).unwrap(), "DOGE/USD");
+    assert_eq!(ws_instrument("BTC/GBP", WsVersion::V2).unwrap(), btc);
+}
+
+#[test]
+fn pairs_without_known_names_are_refused_up_front() {
+    let unknown = Instrument::watched("FOO".to_string(), "GBP".to_string());
+    let error = KrakenMarket::default()
+        .check_instrument(&unknown)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Kraken has no known names for FOO/GBP; add them under venue.kraken.pairs"
+    );
+    assert!(KrakenMarket::default().subscriptions(&unknown).is_err());
+    assert!(
+        KrakenMarket::default()
+            .check_instrument(&instrument("SOL/GBP"))
+            .is_ok()
+    );
+
+    let error = ws_instrument("FOO/GBP", WsVersion::V2).unwrap_err();
+    assert!(error.to_string().contains("FOO/GBP"), "{error}");
+}
+
+#[test]
+fn subscribes_with_the_v1_websocket_pair() {
+    let market = KrakenMarket::default();
+
+    for (name, pair) in [("BTC/GBP", "XBT/GBP"), ("SOL/GBP", "SOL/GBP")] {
+        assert_eq!(
+            market.subscriptions(&instrument(name)).unwrap(),
+            [
+                json!({"event": "subscribe", "pair": [pair], "subscription": {"name": "trade"}}),
+                json!({"event": "subscribe", "pair": [pair], "subscription": {"name": "spread"}}),
+            ]
+        );
+    }
+}
+
+#[test]
+fn reads_frames_for_the_subscribed_pair_only() {
+    let btc = instrument("BTC/GBP");
+    let spread = |pair: &str| {
+        format!(r#"[340,["52000.1","52000.2","1704283200.123","0.1","0.2"],"spread","{pair}"]"#)
+    };
+
+    let event = KrakenMarket::parse_market_event_from_text(&btc, &spread("XBT/GBP")).unwrap();
+    assert!(matches!(
+        event,
+        Some(MarketEvent::TopOfBook { instrument, best_bid, best_ask, bid_size, ask_size, timestamp_ms })
+            if instrument == btc
+                && best_bid == Price::new(52000.1)
+                && best_ask == Price::new(52000.2)
+                && bid_size == Some(0.1)
+                && ask_size == Some(0.2)
+                && timestamp_ms == 1_704_283_200_123
+    ));
+
+    // A spread without volumes still gives the touch, with no sizes.
+    let bare = r#"[340,["52000.1","52000.2","1704283200.123"],"spread","XBT/GBP"]"#;
+    assert!(matches!(
+        KrakenMarket::parse_market_event_from_text(&btc, bare).unwrap(),
+        Some(MarketEvent::TopOfBook {
+            bid_size: None,
+            ask_size: None,
+            ..
+        })
+    ));
+
+    for other in ["BTC/GBP", "SOL/GBP"] {
+        let event = KrakenMarket::parse_market_event_from_text(&btc, &spread(other)).unwrap();
+        assert!(event.is_none(), "{other} frame taken for XBT/GBP");
+    }
+
+    let sol = instrument("SOL/GBP");
+    let trade = r#"[337,[["93.05","1.5","1704283200.5","b","l",""]],"trade","SOL/GBP"]"#;
+    assert!(matches!(
+        KrakenMarket::parse_market_event_from_text(&sol, trade).unwrap(),
+        Some(MarketEvent::Trade { quantity, .. }) if quantity == 1.5
+    ));
+}
+
+#[test]
+fn a_rejected_subscription_is_an_error() {
+    let btc = instrument("BTC/GBP");
+
+    let subscribed = r#"{"channelID":340,"channelName":"spread","event":"subscriptionStatus","pair":"XBT/GBP","status":"subscribed","subscription":{"name":"spread"}}"#;
+    assert!(
+        KrakenMarket::parse_market_event_from_text(&btc, subscribed)
+            .unwrap()
+            .is_none()
+    );
+
+    let rejected = r#"{"errorMessage":"Currency pair not supported BTC/GBP","event":"subscriptionStatus","pair":"BTC/GBP","status":"error","subscription":{"name":"spread"}}"#;
+    let error = KrakenMarket::parse_market_event_from_text(&btc, rejected).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<SubscriptionRejected>(),
+        Some(&SubscriptionRejected {
+            pair: "BTC/GBP".to_string(),
+            channel: "spread".to_string(),
+            reason: "Currency pair not supported BTC/GBP".to_string(),
+        })
+    );
+}
+
+#[test]
+fn subscribes_to_the_book_when_a_depth_is_set() {
+    let market = KrakenMarket::default().with_book_depth(Some(10));
+
+    assert_eq!(
+        market.subscriptions(&instrument("SOL/GBP")).unwrap()[2],
+        json!({"event": "subscribe", "pair": ["SOL/GBP"], "subscription": {"name": "book", "depth": 10}})
+    );
+    assert_eq!(
+        KrakenMarket::default()
+            .subscriptions(&instrument("SOL/GBP"))
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+const BOOK_SNAPSHOT: &str = r#"[336,{"as":[["93.10","1.50000000","1704283200.1"],["93.20","2.00000000","1704283200.1"]],"bs":[["93.00","0.75000000","1704283200.1"],["92.90","3.00000000","1704283200.1"]]},"book-10","SOL/GBP"]"#;
+
+/// Takes the best ask and adds a bid level, in Kraken's two-payload form.
+fn book_update(checksum: &str) -> String {
+    format!(
+        r#"[336,{{"a":[["93.10","0.00000000","1704283201.5"]]}},{{"b":[["92.95","1.25000000","1704283201.5"]],"c":"{checksum}"}},"book-10","SOL/GBP"]"#
+    )
+}
+
+fn level(price: f64, quantity: f64) -> BookLevel {
+    BookLevel {
+        price: Price::new(price),
+        quantity,
+    }
+}
+
+#[test]
+fn keeps_the_depth_ladder_from_snapshot_and_updates() {
+    let sol = instrument("SOL/GBP");
+    let mut book = KrakenBook::new(10);
+    let mut market = MarketState::new();
+
+    for text in [BOOK_SNAPSHOT.to_string(), book_update("4025279607")] {
+        let event = KrakenMarket::parse_market_event_with_book(&sol, &text, &mut book)
+            .unwrap()
+            .unwrap();
+        market.on_market_event(&event, Instant::now());
+    }
+
+    let (bids, asks) = market.top_n_levels(10);
+    assert_eq!(
+        bids,
+        [level(93.00, 0.75), level(92.95, 1.25), level(92.90, 3.0)]
+    );
+    assert_eq!(asks, [level(93.20, 2.0)]);
+    assert_eq!(market.top_n_levels(1).0, [level(93.00, 0.75)]);
+    assert_eq!(market.depth_at(Price::new(92.95)), 1.25);
+    assert_eq!(market.depth_at(Price::new(93.10)), 0.0);
+
+    // Without a book to apply them to, book frames are skipped.
+    assert!(
+        KrakenMarket::parse_market_event_from_text(&sol, BOOK_SNAPSHOT)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn a_checksum_mismatch_is_an_error() {
+    let sol = instrument("SOL/GBP");
+    let mut book = KrakenBook::new(10);
+
+    KrakenMarket::parse_market_event_with_book(&sol, BOOK_SNAPSHOT, &mut book).unwrap();
+    let error =
+        KrakenMarket::parse_market_event_with_book(&sol, &book_update("1317266004"), &mut book)
+            .unwrap_err();
+    assert!(error.to_string().contains("checksum mismatch"), "{error:#}");
+}
+
+#[test]
+fn drops_levels_pushed_out_of_the_subscribed_depth() {
+    let sol = instrument("SOL/GBP");
+    let mut book = KrakenBook::new(2);
+    let mut market = MarketState::new();
+
+    for text in [BOOK_SNAPSHOT.to_string(), book_update("3488250399")] {
+        let event = KrakenMarket::parse_market_event_with_book(&sol, &text, &mut book);
+        market.on_market_event(&event.unwrap().unwrap(), Instant::now());
+    }
+
+    assert_eq!(
+        market.top_n_levels(10).0,
+        [level(93.00, 0.75), level(92.95, 1.25)]
+    );
+}
+
+/// A market feed that sends nothing but, when `pongs` is set, a pong for each ping, and
+/// keeps every message it was sent. Returns its url.
+async fn quiet_feed(received: &Arc<Mutex<Vec<String>>>, pongs: bool) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let received = received.clone();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+        while let Some(Ok(message)) = ws.next().await {
+            let text = message.into_text().unwrap();
+            let ping = text.contains(r#""event":"ping""#);
+            received.lock().unwrap().push(text);
+            if pongs && ping {
+                let pong = json!({ "event": "pong" }).to_string();
+                ws.send(Message::Text(pong)).await.unwrap();
+            }
+        }
+    });
+
+    format!("ws://{address}")
+}
+
+fn pings(received: &Arc<Mutex<Vec<String>>>) -> usize {
+    received
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|text| text.contains(r#""event":"ping""#))
+        .count()
+}
+
+#[tokio::test]
+async fn drops_a_feed_that_stays_quiet_after_a_ping() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let market = KrakenMarket::new(quiet_feed(&received, false).await)
+        .with_idle_timeout(Duration::from_millis(100), Duration::from_millis(100));
+    let (sender, mut events) = mpsc::channel(16);
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(2),
+        market.subscribe(&instrument("SOL/GBP"), sender),
+    )
+    .await
+    .expect("a quiet feed is dropped");
+
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("of a ping"), "{error:#}");
+    assert_eq!(pings(&received), 1);
+
+    let kinds: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+        .map(|event| event.kind())
+        .collect();
+    assert_eq!(kinds, ["reconnected", "disconnected"]);
+}
+
+#[tokio::test]
+async fn keeps_a_quiet_feed_that_answers_pings() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let market = KrakenMarket::new(quiet_feed(&received, true).await)
+        .with_idle_timeout(Duration::from_millis(100), Duration::from_millis(100));
+    let (sender, mut events) = mpsc::channel(16);
+
+    let result = tokio::time::timeout(
+        Duration::from_millis(700),
+        market.subscribe(&instrument("SOL/GBP"), sender),
+    )
+    .await;
+
+    assert!(result.is_err(), "still subscribed");
+    assert!(pings(&received) >= 2, "{:?}", received.lock().unwrap());
+    assert_eq!(events.try_recv().unwrap().kind(), "reconnected");
+    assert!(events.try_recv().is_err());
+}
+
+#[test]
+fn the_config_adds_names_for_pairs_the_table_lacks() {
+    let config = AppConfig::from_yaml(
+        "venue:
+  kraken:
+    pairs:
+      DOT/GBP: { rest: DOTGBP, ws_v1: DOT/GBP, ws_v2: DOT/GBP }
+",
+    )
+    .unwrap();
+    symbols::install(&config.venue.kraken.pairs);
+
+    let dot = Instrument::watched("DOT".to_string(), "GBP".to_string());
+    assert_eq!(kraken_pair(&dot).unwrap(), "DOTGBP");
+    assert_eq!(ws_pair(&dot, WsVersion::V1).unwrap(), "DOT/GBP");
+    // The built-in names are kept.
+    assert_eq!(
+        ws_pair(&instrument("BTC/GBP"), WsVersion::V1).unwrap(),
+        "XBT/GBP"
+    );
+
+    let error = AppConfig::from_yaml(
+        "venue:
+  kraken:
+    pairs:
+      DOTGBP: { rest: DOTGBP, ws_v1: DOT/GBP, ws_v2: DOT/GBP }
+",
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "venue.kraken.pairs.DOTGBP: must be a BASE/QUOTE pair"
+    );
+}
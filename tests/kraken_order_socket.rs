@@ -0,0 +1,361 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::Router;
+use axum::routing::post;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::Message;
+
+use accumulator::execution::ExecutionVenue;
+use accumulator::execution::order_action::Side::{self, Buy, Sell};
+use accumulator::execution::order_action::{Order, OrderAction, OrderType};
+use accumulator::execution::order_report::OrderReport;
+use accumulator::kraken::kraken_client::RetryConfig;
+use accumulator::kraken::kraken_config::KrakenConfig;
+use accumulator::kraken::kraken_order_socket::OrderSocketConfig;
+use accumulator::kraken::kraken_venue::KrakenExecutionVenue;
+use accumulator::kraken::rate_limit::RateLimitConfig;
+use accumulator::random::SeededRng;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+
+const PLACED: &str = r#"{"error":[],"result":{"txid":["OABC12-DEF34-GHI56"],"descr":{"order":"buy 0.05 SOLGBP @ limit 93.00"}}}"#;
+const NONE_OPEN: &str = r#"{"error":[],"result":{"open":{}}}"#;
+
+/// Kraken's REST API as far as the order socket uses it: tokens `token-1`, `token-2`, ...
+/// on each request for one, and `add_order` and `open_orders` answered as given.
+struct Rest {
+    url: String,
+    tokens: Arc<AtomicUsize>,
+    add_orders: Arc<AtomicUsize>,
+}
+
+async fn rest(open_orders: &'static str) -> Rest {
+    let tokens = Arc::new(AtomicUsize::new(0));
+    let add_orders = Arc::new(AtomicUsize::new(0));
+
+    let issued = tokens.clone();
+    let placed = add_orders.clone();
+    let app = Router::new()
+        .route(
+            "/0/private/GetWebSocketsToken",
+            post(move || {
+                let n = issued.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    json!({ "error": [], "result": { "token": format!("token-{n}"), "expires": 900 } })
+                        .to_string()
+                }
+            }),
+        )
+        .route(
+            "/0/private/AddOrder",
+            post(move || {
+                placed.fetch_add(1, Ordering::SeqCst);
+                async { PLACED }
+            }),
+        )
+        .route("/0/private/OpenOrders", post(move || async move { open_orders }))
+        .route(
+            "/0/private/ClosedOrders",
+            post(|| async { r#"{"error":[],"result":{"closed":{}}}"# }),
+        )
+        .route(
+            "/0/private/CancelOrder",
+            post(|| async { r#"{"error":[],"result":{"count":1}}"# }),
+        );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    Rest {
+        url: format!("http://{address}"),
+        tokens,
+        add_orders,
+    }
+}
+
+/// A stand-in for Kraken's authenticated websocket that sends `respond(request)` back for
+/// each request, in order, and keeps every request it was sent. Returns its url.
+async fn socket(
+    requests: &Arc<Mutex<Vec<Value>>>,
+    respond: impl FnMut(&Value) -> Vec<Value> + Send + 'static,
+) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let requests = requests.clone();
+    let respond = Arc::new(Mutex::new(respond));
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(message)) = ws.next().await {
+                let Ok(request) = serde_json::from_str::<Value>(&message.into_text().unwrap())
+                else {
+                    continue;
+                };
+                requests.lock().unwrap().push(request.clone());
+                let replies = (respond.lock().unwrap())(&request);
+                for reply in replies {
+                    ws.send(Message::Text(reply.to_string())).await.unwrap();
+                }
+            }
+        }
+    });
+
+    format!("ws://{address}")
+}
+
+/// Kraken's answer to `request`: its result, or `error`.
+fn ack(request: &Value, error: Option<&str>) -> Value {
+    let mut ack = json!({
+        "method": request["method"],
+        "req_id": request["req_id"],
+        "success": error.is_none(),
+    });
+    match error {
+        None => ack["result"] = json!({ "order_id": "OABC12-DEF34-GHI56" }),
+        Some(error) => ack["error"] = json!(error),
+    }
+    ack
+}
+
+fn venue(
+    rest: &Rest,
+    socket_url: &str,
+) -> (KrakenExecutionVenue, broadcast::Receiver<OrderReport>) {
+    let (sender, reports) = broadcast::channel(64);
+    let config = KrakenConfig {
+        api_key: "key".to_string(),
+        api_secret: "c2VjcmV0".to_string(),
+        rate_limit: RateLimitConfig::default(),
+        retry: RetryConfig {
+            max_attempts: 1,
+            ..RetryConfig::default()
+        },
+        order_socket: OrderSocketConfig {
+            enabled: false,
+            ack_timeout_ms: 200,
+            reconnect_delay_ms: 50,
+        },
+    };
+    let venue = KrakenExecutionVenue::new(config, sender, SeededRng::new(7))
+        .with_base_url(&rest.url)
+        .with_order_socket(socket_url);
+
+    (venue, reports)
+}
+
+async fn connected(venue: &KrakenExecutionVenue) {
+    for _ in 0..200 {
+        if venue.order_socket().unwrap().is_connected() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("order socket never connected");
+}
+
+fn place(order_id: &str, side: Side) -> OrderAction {
+    OrderAction::Place(Order {
+        order_id: order_id.to_string(),
+        instrument: "SOL/GBP".parse().unwrap(),
+        side,
+        price: Price::new(93.00),
+        quantity: Quantity::new(0.05).unwrap(),
+        order_type: OrderType::POST_ONLY,
+        cycle_id: None,
+    })
+}
+
+fn cancel(order_id: &str, side: Side) -> OrderAction {
+    OrderAction::Cancel {
+        order_id: order_id.to_string(),
+        instrument: "SOL/GBP".parse().unwrap(),
+        side,
+    }
+}
+
+/// Each report so far as its kind and order id, and its reason if it has one.
+fn reports(receiver: &mut broadcast::Receiver<OrderReport>) -> Vec<String> {
+    std::iter::from_fn(|| receiver.try_recv().ok())
+        .map(|report| {
+            let mut line = format!("{} {}", report.kind(), report.order_id().unwrap_or("-"));
+            if let Some(reason) = report.reason() {
+                line.push_str(&format!(": {reason}"));
+            }
+            line
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn places_and_cancels_over_the_socket_matching_acks_by_request_id() {
+    let rest = rest(NONE_OPEN).await;
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let mut held = Vec::new();
+    // Holds places until both arrive, then answers them in reverse order.
+    let url = socket(&requests, move |request| match request["method"].as_str() {
+        Some("add_order") => {
+            held.push(request.clone());
+            if held.len() < 2 {
+                return Vec::new();
+            }
+            held.drain(..)
+                .rev()
+                .map(|request| {
+                    let refused = request["params"]["cl_ord_id"] == "s1";
+                    ack(&request, refused.then_some("EOrder:Insufficient funds"))
+                })
+                .collect()
+        }
+        _ => vec![ack(request, None)],
+    })
+    .await;
+    let (venue, mut receiver) = venue(&rest, &url);
+    connected(&venue).await;
+
+    let (bid, ask) = ([place("b1", Buy)], [place("s1", Sell)]);
+    let (bid, ask) = tokio::join!(venue.execute(&bid), venue.execute(&ask));
+    bid.unwrap();
+    ask.unwrap();
+    venue.execute(&[cancel("b1", Buy)]).await.unwrap();
+
+    let mut reports = reports(&mut receiver);
+    reports[..4].sort();
+    assert_eq!(
+        reports,
+        [
+            "accepted b1",
+            "placed b1",
+            "placed s1",
+            "rejected s1: kraken api error: EOrder:Insufficient funds",
+            "cancel b1",
+            "cancelled b1",
+        ]
+    );
+    assert_eq!(rest.add_orders.load(Ordering::SeqCst), 0);
+
+    let requests = requests.lock().unwrap();
+    let place = requests
+        .iter()
+        .find(|request| request["params"]["cl_ord_id"] == "b1")
+        .unwrap();
+    assert_eq!(
+        place["params"],
+        json!({
+            "order_type": "limit",
+            "side": "buy",
+            "limit_price": 93.0,
+            "order_qty": 0.05,
+            "symbol": "SOL/GBP",
+            "post_only": true,
+            "cl_ord_id": "b1",
+            "token": "token-1",
+        })
+    );
+    assert_eq!(requests[2]["method"], "cancel_order");
+    assert_eq!(requests[2]["params"]["cl_ord_id"], json!(["b1"]));
+    let mut req_ids: Vec<_> = requests.iter().map(|r| r["req_id"].as_u64()).collect();
+    req_ids.sort();
+    req_ids.dedup();
+    assert_eq!(req_ids.len(), 3);
+}
+
+#[tokio::test]
+async fn an_immediate_or_cancel_place_sends_its_time_in_force() {
+    let rest = rest(NONE_OPEN).await;
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let url = socket(&requests, |request| vec![ack(request, None)]).await;
+    let (venue, _receiver) = venue(&rest, &url);
+    connected(&venue).await;
+
+    let OrderAction::Place(mut order) = place("s1", Sell) else {
+        unreachable!()
+    };
+    order.order_type = OrderType::ImmediateOrCancel;
+    venue.execute(&[OrderAction::Place(order)]).await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[0]["params"]["post_only"], false);
+    assert_eq!(requests[0]["params"]["time_in_force"], "ioc");
+}
+
+#[tokio::test]
+async fn an_unanswered_place_is_looked_up_rather_than_sent_again() {
+    const B1_OPEN: &str = r#"{"error":[],"result":{"open":{"OABC12-DEF34-GHI56":{"cl_ord_id":"b1","descr":{"pair":"SOLGBP","type":"buy","price":"93.00"},"vol":"0.05","vol_exec":"0"}}}}"#;
+
+    let rest = rest(B1_OPEN).await;
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let url = socket(&requests, |_| Vec::new()).await;
+    let (venue, mut receiver) = venue(&rest, &url);
+    connected(&venue).await;
+
+    venue
+        .execute(&[place("b1", Buy), place("b2", Buy)])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        reports(&mut receiver),
+        [
+            "placed b1",
+            "accepted b1",
+            "placed b2",
+            "rejected b2: kraken order socket request went unanswered; the order was not found",
+        ]
+    );
+    assert_eq!(requests.lock().unwrap().len(), 2);
+    assert_eq!(rest.add_orders.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn renews_an_expired_token_and_sends_the_request_again() {
+    let rest = rest(NONE_OPEN).await;
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let url = socket(&requests, |request| {
+        let expired = request["params"]["token"] == "token-1";
+        vec![ack(request, expired.then_some("EAPI:Invalid token"))]
+    })
+    .await;
+    let (venue, mut receiver) = venue(&rest, &url);
+    connected(&venue).await;
+
+    venue.execute(&[place("b1", Buy)]).await.unwrap();
+
+    assert_eq!(reports(&mut receiver), ["placed b1", "accepted b1"]);
+    assert_eq!(rest.tokens.load(Ordering::SeqCst), 2);
+    let tokens: Vec<_> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|request| request["params"]["token"].clone())
+        .collect();
+    assert_eq!(tokens, ["token-1", "token-2"]);
+}
+
+#[tokio::test]
+async fn falls_back_to_rest_while_the_socket_is_down() {
+    let rest = rest(NONE_OPEN).await;
+    // Nothing listens here once the listener is dropped.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    drop(listener);
+    let (venue, mut receiver) = venue(&rest, &url);
+
+    venue
+        .execute(&[place("b1", Buy), cancel("b1", Buy)])
+        .await
+        .unwrap();
+
+    assert!(!venue.order_socket().unwrap().is_connected());
+    assert_eq!(
+        reports(&mut receiver),
+        ["placed b1", "accepted b1", "cancel b1", "cancelled b1"]
+    );
+    assert_eq!(rest.add_orders.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::time::Duration;
+
+use accumulator::execution::order_action::Side::{Buy, Sell};
+
+use common::{Act, Expect, Harness, Step};
+
+const VENUE_LATENCY: Duration = Duration::from_millis(20);
+const ACK_DELAY: Duration = Duration::from_millis(30);
+
+fn book() -> Step {
+    Step::Book {
+        bid: 93.00,
+        ask: 93.10,
+    }
+}
+
+/// Median of `stage` in a stats line's `stage=p50/p95/p99us,...` latencies.
+fn p50(latency: &str, stage: &str) -> Option<Duration> {
+    latency.split(',').find_map(|entry| {
+        let percentiles = entry.strip_prefix(stage)?.strip_prefix('=')?;
+        let p50 = percentiles.split('/').next()?;
+        Some(Duration::from_micros(p50.parse().ok()?))
+    })
+}
+
+/// Quotes both sides through a venue that takes [`VENUE_LATENCY`] to answer, then
+/// acknowledges both orders [`ACK_DELAY`] later.
+async fn quote(harness: &mut Harness) {
+    harness.venue_latency(VENUE_LATENCY);
+    harness
+        .run(&[
+            (0, book()),
+            (1_000, book()),
+            (
+                1_000,
+                Step::Expect(Expect::Actions(vec![Act::Place(Buy), Act::Place(Sell)])),
+            ),
+        ])
+        .await
+        .unwrap();
+
+    tokio::time::sleep(ACK_DELAY).await;
+    harness
+        .run(&[(1_010, Step::Accept(Buy)), (1_010, Step::Accept(Sell))])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn time_spent_at_the_venue_is_put_down_to_execute_and_ack() {
+    let mut harness = Harness::new(|config| config.metrics.port = Some(9000))
+        .await
+        .unwrap();
+    quote(&mut harness).await;
+
+    let latency = harness.stats().latency;
+    let stage = |stage| p50(&latency, stage).unwrap_or_else(|| panic!("no {stage} in {latency}"));
+
+    // Bucket bounds only ever round up, so each stage is at least what was injected.
+    assert!(stage("execute") >= VENUE_LATENCY, "{latency}");
+    assert!(stage("ack") >= ACK_DELAY, "{latency}");
+    assert!(stage("total") >= VENUE_LATENCY + ACK_DELAY, "{latency}");
+
+    // The stages before the venue took none of it.
+    for before in ["schedule", "strategy", "risk"] {
+        assert!(stage(before) < VENUE_LATENCY, "{before}: {latency}");
+    }
+}
+
+#[tokio::test]
+async fn nothing_is_timed_without_metrics() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    quote(&mut harness).await;
+
+    assert_eq!(harness.stats().latency, "");
+}
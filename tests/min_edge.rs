@@ -0,0 +1,89 @@
+use std::time::Instant;
+
+use accumulator::events::MarketEvent;
+use accumulator::execution::types::OrderManagerSnapshot;
+use accumulator::market::market_state::MarketState;
+use accumulator::risk::checks::min_edge::MinEdgeCheck;
+use accumulator::risk::context::RiskContext;
+use accumulator::risk::decision::RiskReason;
+use accumulator::risk::engine::RiskCheck;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::pnl::PnlTracker;
+use accumulator::types::price::Price;
+use accumulator::types::quote_target::QuoteTarget;
+
+fn book(instrument: &Instrument, bid: f64, ask: f64) -> MarketState {
+    let mut market = MarketState::new();
+    market.on_market_event(
+        &MarketEvent::TopOfBook {
+            instrument: instrument.clone(),
+            best_bid: Price::new(bid),
+            best_ask: Price::new(ask),
+            bid_size: None,
+            ask_size: None,
+            timestamp_ms: 0,
+        },
+        Instant::now(),
+    );
+    market
+}
+
+fn evaluate(check: &mut MinEdgeCheck, bid: f64, ask: f64) -> Result<(), Vec<RiskReason>> {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let market_state = book(&instrument, bid, ask);
+    check.evaluate(&RiskContext {
+        instrument: &instrument,
+        market_state: &market_state,
+        target: &QuoteTarget::none(),
+        inventory: Inventory {
+            base: 0.0,
+            quote: 0.0,
+        },
+        orders: &OrderManagerSnapshot::default(),
+        pnl: &PnlTracker::default(),
+        now: Instant::now(),
+    })
+}
+
+#[test]
+fn quotes_when_the_fee_takes_exactly_the_half_spread() {
+    // 50bps of a 100.00 mid is 0.50, all of the half-spread.
+    let mut check = MinEdgeCheck::new(0.0, 50.0);
+    assert!(evaluate(&mut check, 99.50, 100.50).is_ok());
+
+    let reasons = evaluate(&mut check, 99.52, 100.48).unwrap_err();
+    assert!(
+        matches!(
+            reasons[..],
+            [RiskReason::InsufficientEdge { required, fee, .. }] if required == 0.5 && fee == 0.5
+        ),
+        "{reasons:?}"
+    );
+}
+
+#[test]
+fn requires_the_minimum_on_top_of_the_fee() {
+    let mut check = MinEdgeCheck::new(0.25, 50.0);
+    assert!(evaluate(&mut check, 99.25, 100.75).is_ok());
+
+    let reasons = evaluate(&mut check, 99.50, 100.50).unwrap_err();
+    assert!(
+        matches!(
+            reasons[..],
+            [RiskReason::InsufficientEdge { half_spread, required, .. }]
+                if half_spread == 0.5 && required == 0.75
+        ),
+        "{reasons:?}"
+    );
+}
+
+#[test]
+fn rejects_fees_outside_zero_to_a_hundred_bps() {
+    let rules = InstrumentConfig::default().load().unwrap().trading_rules();
+    for (fee, valid) in [(0.0, true), (99.9, true), (100.0, false), (-1.0, false)] {
+        let mut rules = rules.clone();
+        rules.maker_fee_bps = fee;
+        assert_eq!(rules.validate().is_ok(), valid, "{fee}");
+    }
+}
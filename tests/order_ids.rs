@@ -0,0 +1,279 @@
+mod common;
+
+use tokio::sync::broadcast;
+
+use accumulator::config::app_config::AppConfig;
+use accumulator::execution::ExecutionVenue;
+use accumulator::execution::logged_venue::IntentLoggedVenue;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::execution::order_action::{Order, OrderAction, OrderType, Side};
+use accumulator::execution::order_ids::{ClientOrderId, MAX_CLIENT_ORDER_ID_LEN, OrderIds};
+use accumulator::scenario::scenario::DynamicVenue;
+use accumulator::state::intent_log::IntentLog;
+use accumulator::types::instrument::Instrument;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+
+use common::{INITIAL, MockVenue};
+
+fn instrument(name: &str) -> Instrument {
+    name.parse().unwrap()
+}
+
+/// A pair without trading rules of its own, borrowing SOL/GBP's.
+fn any_pair(base: &str, quote: &str) -> Instrument {
+    let rules = instrument("SOL/GBP").trading_rules();
+    Instrument::new(base.to_string(), quote.to_string(), rules)
+}
+
+fn log_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("accumulator-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn place(order_id: &str, side: Side) -> OrderAction {
+    OrderAction::Place(Order {
+        order_id: order_id.to_string(),
+        instrument: instrument("SOL/GBP"),
+        side,
+        price: Price::new(93.0),
+        quantity: Quantity::new(0.05).unwrap(),
+        order_type: OrderType::POST_ONLY,
+        cycle_id: None,
+    })
+}
+
+#[test]
+fn encodes_session_pair_side_and_sequence() {
+    let ids = OrderIds::structured("3F9C21ab", 1);
+    let sol = instrument("SOL/GBP");
+
+    assert_eq!(ids.next_id(&sol, Buy), "3f9c-SOLGBP-b1");
+    assert_eq!(ids.next_id(&sol, Sell), "3f9c-SOLGBP-s2");
+    assert_eq!(ids.next_id(&instrument("BTC/GBP"), Buy), "3f9c-BTCGBP-b3");
+
+    let later = OrderIds::structured("3f9c21ab", 36 * 36);
+    assert_eq!(later.next_id(&sol, Sell), "3f9c-SOLGBP-s100");
+}
+
+#[test]
+fn parses_its_own_ids_back() {
+    let ids = OrderIds::structured("a1b2c3d4", 1_000);
+    let id = ids.next_id(&instrument("SOL/GBP"), Sell);
+
+    assert_eq!(
+        ClientOrderId::parse(&id),
+        Some(ClientOrderId {
+            session: "a1b2".to_string(),
+            pair: Some("SOLGBP".to_string()),
+            side: Sell,
+            seq: 1_000,
+        })
+    );
+}
+
+#[test]
+fn a_configured_prefix_leads_the_id_in_place_of_the_session() {
+    let ids = OrderIds::with_prefix("mm1", 11);
+    let id = ids.next_id(&instrument("SOL/GBP"), Buy);
+
+    assert_eq!(id, "mm1-SOLGBP-bb");
+    assert_eq!(
+        ClientOrderId::parse(&id),
+        Some(ClientOrderId {
+            session: "mm1".to_string(),
+            pair: Some("SOLGBP".to_string()),
+            side: Buy,
+            seq: 11,
+        })
+    );
+}
+
+#[test]
+fn rejects_prefixes_that_would_not_parse_back() {
+    for prefix in ["mm1", "a", "x9z0"] {
+        let config = format!("venue:\n  order_id_prefix: {prefix}\n");
+        assert!(AppConfig::from_yaml(&config).is_ok(), "{prefix}");
+    }
+    for prefix in ["''", "MM1", "mm-1", "toolong", "m_1"] {
+        let config = format!("venue:\n  order_id_prefix: {prefix}\n");
+        let error = format!("{:#}", AppConfig::from_yaml(&config).unwrap_err());
+        assert!(error.contains("venue.order_id_prefix"), "{prefix}: {error}");
+    }
+}
+
+#[test]
+fn does_not_mistake_other_ids_for_its_own() {
+    for id in [
+        "6d1b345e-2821-40e2-ad83-4ecb18a06876",
+        "da8e4ad59b78481c93e589746b0cf91f",
+        "manual-buy",
+        "sim-12",
+        "3f9c-SOLGBP-x1",
+        "3f9c-SOLGBP-b",
+        "3F9C-SOLGBP-b1",
+        "3f9c-solgbp-b1",
+        "3f9c-SOLGBP-b1-2",
+        "toolong-SOLGBP-b1",
+        "-SOLGBP-b1",
+    ] {
+        assert_eq!(ClientOrderId::parse(id), None, "{id}");
+    }
+}
+
+#[test]
+fn ids_fit_the_venue_length_limit() {
+    let ids = OrderIds::structured("ffffffff", u32::MAX as u64);
+
+    for (base, quote) in [
+        ("SOL", "GBP"),
+        ("BTC", "GBP"),
+        ("DOGE", "USDT"),
+        ("LONGBASE", "LONGQUOTE"),
+    ] {
+        let id = ids.next_id(&any_pair(base, quote), Buy);
+        assert!(id.len() <= MAX_CLIENT_ORDER_ID_LEN, "{id} is too long");
+        assert!(id.is_ascii());
+        assert!(ClientOrderId::parse(&id).is_some(), "{id}");
+    }
+
+    // The pair goes first when an id would not fit.
+    let id = ids.next_id(&any_pair("LONGBASE", "LONGQUOTE"), Sell);
+    assert_eq!(ClientOrderId::parse(&id).unwrap().pair, None);
+}
+
+#[test]
+fn sequence_numbers_continue_across_restarts() {
+    let path = log_path("order-ids-intents.jsonl");
+
+    let mut log = IntentLog::open(&path).unwrap();
+    assert_eq!(log.next_seq(), 1);
+    log.record_actions(&[place("3f9c-SOLGBP-b1", Buy), place("3f9c-SOLGBP-sa", Sell)])
+        .unwrap();
+    log.resolve("3f9c-SOLGBP-b1").unwrap();
+    log.resolve("3f9c-SOLGBP-sa").unwrap();
+    drop(log);
+
+    // Compaction on reopen drops the resolved places but keeps the sequence.
+    let log = IntentLog::open(&path).unwrap();
+    assert!(log.unresolved().is_empty());
+    assert_eq!(log.next_seq(), 11);
+    drop(log);
+
+    assert_eq!(IntentLog::open(&path).unwrap().next_seq(), 11);
+}
+
+#[tokio::test]
+async fn two_runs_under_one_prefix_without_an_intent_log_never_reuse_an_id() {
+    let (reports, _) = broadcast::channel(64);
+    let venue: DynamicVenue = Box::new(MockVenue::new(reports, INITIAL));
+    let instruments = [instrument("SOL/GBP")];
+    let sol = &instruments[0];
+
+    // The first run stops with both its orders still resting.
+    let first = OrderIds::with_prefix("mm1", 1);
+    first.skip_open_orders(&venue, &instruments).await.unwrap();
+    let first_ids = [first.next_id(sol, Buy), first.next_id(sol, Sell)];
+    venue
+        .execute(&[place(&first_ids[0], Buy), place(&first_ids[1], Sell)])
+        .await
+        .unwrap();
+
+    // Without an intent log the second run numbers from 1 again.
+    let second = OrderIds::with_prefix("mm1", 1);
+    second.skip_open_orders(&venue, &instruments).await.unwrap();
+    let second_ids = [second.next_id(sol, Buy), second.next_id(sol, Sell)];
+
+    assert_eq!(first_ids, ["mm1-SOLGBP-b1", "mm1-SOLGBP-s2"]);
+    assert_eq!(second_ids, ["mm1-SOLGBP-b3", "mm1-SOLGBP-s4"]);
+}
+
+#[tokio::test]
+async fn recovery_cancels_its_own_orders_and_leaves_foreign_ones() {
+    let (reports, _) = broadcast::channel(64);
+    let venue = MockVenue::new(reports.clone(), INITIAL);
+    venue
+        .execute(&[place("3f9c-SOLGBP-b7", Buy), place("manual-sell", Sell)])
+        .await
+        .unwrap();
+
+    let log = IntentLog::open(log_path("order-ids-recovery.jsonl")).unwrap();
+    let logged = IntentLoggedVenue::new(Box::new(venue.clone()), log);
+    logged.spawn_resolver(reports.subscribe());
+    logged
+        .recover(
+            &[instrument("SOL/GBP")],
+            &OrderIds::with_prefix("3f9c", 1),
+            true,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(cancelled(&venue), ["3f9c-SOLGBP-b7"]);
+}
+
+fn cancelled(venue: &MockVenue) -> Vec<String> {
+    venue
+        .actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            OrderAction::Cancel { order_id, .. } => Some(order_id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn only_ids_led_by_its_own_prefix_or_session_are_its_own() {
+    let sol = instrument("SOL/GBP");
+    let mm1 = OrderIds::with_prefix("mm1", 1);
+    let mm2 = OrderIds::with_prefix("mm2", 1);
+    let (first, second) = (mm1.next_id(&sol, Buy), mm2.next_id(&sol, Buy));
+
+    assert_eq!(mm1.prefix(), Some("mm1"));
+    assert!(mm1.is_own(&first) && mm1.is_own_from_any_run(&first));
+    assert!(!mm1.is_own(&second) && !mm2.is_own(&first));
+    assert!(!mm1.is_own("manual-sell"));
+
+    // A session id leads only this run's ids; an earlier run's cannot be told apart.
+    let session = OrderIds::structured("3f9c21ab", 1);
+    let id = session.next_id(&sol, Sell);
+    assert_eq!((session.session(), session.prefix()), (Some("3f9c"), None));
+    assert!(session.is_own(&id));
+    assert!(!session.is_own_from_any_run(&id));
+    assert!(!OrderIds::sequential().is_own(&id));
+}
+
+#[tokio::test]
+async fn two_prefixes_on_one_account_leave_each_others_orders_alone() {
+    let (reports, _) = broadcast::channel(64);
+    let venue = MockVenue::new(reports.clone(), INITIAL);
+    venue
+        .execute(&[place("mm1-SOLGBP-b7", Buy), place("mm2-SOLGBP-s9", Sell)])
+        .await
+        .unwrap();
+
+    // Each deployment restarts without adopting, cancelling what it left resting.
+    for prefix in ["mm1", "mm2"] {
+        let log = IntentLog::open(log_path(&format!("order-ids-{prefix}.jsonl"))).unwrap();
+        let logged = IntentLoggedVenue::new(Box::new(venue.clone()), log);
+        logged.spawn_resolver(reports.subscribe());
+        logged
+            .recover(
+                &[instrument("SOL/GBP")],
+                &OrderIds::with_prefix(prefix, 1),
+                true,
+            )
+            .await
+            .unwrap();
+
+        let ours = format!("{prefix}-");
+        let cancelled = cancelled(&venue);
+        assert!(
+            cancelled.last().is_some_and(|id| id.starts_with(&ours)),
+            "{prefix}: {cancelled:?}"
+        );
+    }
+    assert_eq!(cancelled(&venue), ["mm1-SOLGBP-b7", "mm2-SOLGBP-s9"]);
+}
@@ -0,0 +1,376 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use accumulator::execution::order_action::Side::{self, Buy, Sell};
+use accumulator::execution::order_action::{Order, OrderAction, OrderType};
+use accumulator::execution::order_ids::OrderIds;
+use accumulator::execution::order_manager::OrderManager;
+use accumulator::execution::order_report::OrderReport;
+use accumulator::execution::types::OrderSideState;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+use accumulator::types::quote::Quote;
+use accumulator::types::quote_target::QuoteTarget;
+
+use common::qty;
+
+fn quote(price: f64) -> Quote {
+    Quote {
+        price: Price::new(price),
+        quantity: qty(0.05),
+    }
+}
+
+/// Bids at `prices`, level 0 first, and no asks.
+fn bids(prices: &[f64]) -> QuoteTarget {
+    QuoteTarget {
+        bid: Some(quote(prices[0])),
+        deeper_bids: prices[1..].iter().map(|price| quote(*price)).collect(),
+        ..QuoteTarget::none()
+    }
+}
+
+fn placed(actions: &[OrderAction]) -> Vec<&Order> {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            OrderAction::Place(order) => Some(order),
+            _ => None,
+        })
+        .collect()
+}
+
+fn cancelled(actions: &[OrderAction]) -> Vec<&str> {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            OrderAction::Cancel { order_id, .. } => Some(order_id.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Plans towards `target` at `now` and has the venue accept every order placed.
+async fn quote_and_accept(
+    manager: &mut OrderManager,
+    instrument: &Instrument,
+    target: &QuoteTarget,
+    now: Instant,
+) -> Vec<OrderAction> {
+    let actions = manager
+        .actions_for_target(instrument, target, now)
+        .await
+        .unwrap();
+    for order in placed(&actions) {
+        manager.on_report(
+            OrderReport::Accepted {
+                order_id: order.order_id.clone(),
+                instrument: instrument.clone(),
+                side: order.side,
+                price: order.price,
+                quantity: order.quantity,
+            },
+            now,
+        );
+    }
+    actions
+}
+
+fn order_ids(manager: &OrderManager, side: Side) -> Vec<Option<String>> {
+    manager
+        .levels(side)
+        .iter()
+        .map(|level| level.state().order_id().map(String::from))
+        .collect()
+}
+
+#[tokio::test]
+async fn a_single_quote_target_keeps_one_order_a_side() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut manager = OrderManager::new(OrderIds::sequential());
+    let start = Instant::now();
+    let target = QuoteTarget::new(Some(quote(93.00)), Some(quote(93.10)));
+
+    let actions = quote_and_accept(&mut manager, &instrument, &target, start).await;
+    assert_eq!(placed(&actions).len(), 2, "{actions:?}");
+
+    let later = start + Duration::from_secs(1);
+    let actions = quote_and_accept(&mut manager, &instrument, &target, later).await;
+    assert!(actions.is_empty(), "{actions:?}");
+    assert_eq!(manager.levels(Buy).len(), 1);
+    assert_eq!(manager.levels(Sell).len(), 1);
+    assert_eq!(manager.open_order_count(), 2);
+}
+
+#[tokio::test]
+async fn a_held_side_keeps_its_resting_order_while_a_cleared_one_is_cancelled() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut manager = OrderManager::new(OrderIds::sequential());
+    let start = Instant::now();
+    let target = QuoteTarget::new(Some(quote(93.00)), Some(quote(93.10)));
+    quote_and_accept(&mut manager, &instrument, &target, start).await;
+
+    let mut moved = QuoteTarget::new(Some(quote(92.50)), Some(quote(93.60)));
+    moved.hold_side(Buy);
+    moved.replace_side(Sell, None);
+    let later = start + Duration::from_secs(10);
+    let actions = quote_and_accept(&mut manager, &instrument, &moved, later).await;
+
+    assert_eq!(cancelled(&actions), ["sim-2"], "{actions:?}");
+    assert!(placed(&actions).is_empty(), "{actions:?}");
+    assert_eq!(order_ids(&manager, Buy), [Some("sim-1".to_string())]);
+}
+
+#[tokio::test]
+async fn a_ladder_replaces_only_the_levels_that_moved() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut manager = OrderManager::new(OrderIds::sequential());
+    let start = Instant::now();
+
+    let actions = quote_and_accept(
+        &mut manager,
+        &instrument,
+        &bids(&[93.00, 92.98, 92.96]),
+        start,
+    )
+    .await;
+    let prices: Vec<_> = placed(&actions).iter().map(|order| order.price).collect();
+    assert_eq!(
+        prices,
+        [Price::new(93.00), Price::new(92.98), Price::new(92.96)]
+    );
+    assert_eq!(manager.open_order_count(), 3);
+    let first = order_ids(&manager, Buy);
+
+    // Level 1 moves five ticks, past the replace threshold; the others stay put.
+    let later = start + Duration::from_secs(1);
+    let actions = quote_and_accept(
+        &mut manager,
+        &instrument,
+        &bids(&[93.00, 92.93, 92.96]),
+        later,
+    )
+    .await;
+    assert_eq!(cancelled(&actions), [first[1].as_deref().unwrap()]);
+    assert!(
+        matches!(&placed(&actions)[..], [order] if order.price == Price::new(92.93)),
+        "{actions:?}"
+    );
+    let moved = order_ids(&manager, Buy);
+    assert_eq!((&moved[0], &moved[2]), (&first[0], &first[2]));
+    assert_ne!(moved[1], first[1]);
+
+    // Back to one level: the two behind it are cancelled.
+    let actions = quote_and_accept(&mut manager, &instrument, &bids(&[93.00]), later).await;
+    assert_eq!(
+        cancelled(&actions),
+        [moved[1].as_deref().unwrap(), moved[2].as_deref().unwrap()]
+    );
+    assert!(placed(&actions).is_empty(), "{actions:?}");
+}
+
+#[tokio::test]
+async fn a_fill_on_one_level_leaves_the_others_resting() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut manager = OrderManager::new(OrderIds::sequential());
+    let start = Instant::now();
+    quote_and_accept(&mut manager, &instrument, &bids(&[93.00, 92.98]), start).await;
+    let ids = order_ids(&manager, Buy);
+
+    manager.on_report(
+        OrderReport::Filled {
+            order_id: ids[1].clone().unwrap(),
+            instrument: instrument.clone(),
+            side: Buy,
+            price: Price::new(92.98),
+            quantity: qty(0.05),
+            cum_quantity: qty(0.05),
+        },
+        start,
+    );
+
+    let levels = manager.levels(Buy);
+    assert!(matches!(levels[0].state(), OrderSideState::Live { .. }));
+    assert!(matches!(levels[1].state(), OrderSideState::NoOrder));
+    assert_eq!(manager.open_order_count(), 1);
+}
+
+#[tokio::test]
+async fn only_the_immediate_side_is_placed_immediate_or_cancel() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut manager = OrderManager::new(OrderIds::sequential());
+    let target = QuoteTarget {
+        immediate: Some(Sell),
+        ..QuoteTarget::new(Some(quote(93.00)), Some(quote(92.98)))
+    };
+
+    let actions = manager
+        .actions_for_target(&instrument, &target, Instant::now())
+        .await
+        .unwrap();
+    let types: Vec<_> = placed(&actions)
+        .iter()
+        .map(|order| (order.side, order.order_type))
+        .collect();
+    assert_eq!(
+        types,
+        [
+            (Buy, OrderType::POST_ONLY),
+            (Sell, OrderType::ImmediateOrCancel)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn cancels_still_go_out_while_the_other_side_is_placing() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut manager = OrderManager::new(OrderIds::sequential());
+    let start = Instant::now();
+
+    // Only the ask is accepted; the bid is still placing.
+    let target = QuoteTarget::new(Some(quote(93.00)), Some(quote(93.10)));
+    let actions = manager
+        .actions_for_target(&instrument, &target, start)
+        .await
+        .unwrap();
+    let ask = placed(&actions)
+        .into_iter()
+        .find(|order| order.side == Sell)
+        .unwrap()
+        .clone();
+    manager.on_report(
+        OrderReport::Accepted {
+            order_id: ask.order_id.clone(),
+            instrument: instrument.clone(),
+            side: Sell,
+            price: ask.price,
+            quantity: ask.quantity,
+        },
+        start,
+    );
+    assert!(matches!(
+        manager.side(Buy).state(),
+        OrderSideState::Placing { .. }
+    ));
+
+    // The market gaps through the ask: it is cancelled, not replaced, and nothing is
+    // placed while the bid waits on the venue.
+    let later = start + Duration::from_secs(1);
+    let gapped = QuoteTarget::new(Some(quote(92.50)), Some(quote(92.60)));
+    let actions = manager
+        .cancels_for_target(&instrument, &gapped, later)
+        .await
+        .unwrap();
+    assert_eq!(cancelled(&actions), [ask.order_id.as_str()]);
+    assert!(placed(&actions).is_empty(), "{actions:?}");
+    assert!(matches!(
+        manager.side(Buy).state(),
+        OrderSideState::Placing { .. }
+    ));
+    assert!(matches!(
+        manager.side(Sell).state(),
+        OrderSideState::Cancelling { .. }
+    ));
+
+    // An empty side is not quoted either.
+    let mut idle = OrderManager::new(OrderIds::sequential());
+    let actions = idle
+        .cancels_for_target(&instrument, &target, later)
+        .await
+        .unwrap();
+    assert!(actions.is_empty(), "{actions:?}");
+}
+
+#[tokio::test]
+async fn the_snapshot_follows_each_level_through_its_reports() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut manager = OrderManager::new(OrderIds::sequential());
+    let start = Instant::now();
+    let states = |manager: &OrderManager, now| {
+        let snapshot = manager.snapshot(now);
+        (snapshot.bids[0].state, snapshot.asks[0].state)
+    };
+    assert_eq!(states(&manager, start), ("no_order", "no_order"));
+    assert!(manager.snapshot(start).bids[0].quote.is_none());
+
+    let target = QuoteTarget::new(Some(quote(93.00)), None);
+    let actions = manager
+        .actions_for_target(&instrument, &target, start)
+        .await
+        .unwrap();
+    let bid = placed(&actions)[0].clone();
+    let snapshot = manager.snapshot(start + Duration::from_millis(40));
+    assert_eq!(snapshot.bids[0].state, "placing");
+    assert_eq!(
+        snapshot.bids[0].order_id.as_deref(),
+        Some(bid.order_id.as_str())
+    );
+    assert_eq!(snapshot.bids[0].age_ms, Some(40.0));
+    assert_eq!(snapshot.asks[0].age_ms, None);
+
+    let accepted = start + Duration::from_millis(50);
+    manager.on_report(
+        OrderReport::Accepted {
+            order_id: bid.order_id.clone(),
+            instrument: instrument.clone(),
+            side: Buy,
+            price: bid.price,
+            quantity: bid.quantity,
+        },
+        accepted,
+    );
+    manager.on_report(
+        OrderReport::PartiallyFilled {
+            order_id: bid.order_id.clone(),
+            instrument: instrument.clone(),
+            side: Buy,
+            price: bid.price,
+            quantity: qty(0.02),
+            cum_quantity: qty(0.02),
+        },
+        accepted,
+    );
+    let snapshot = manager.snapshot(accepted + Duration::from_secs(1));
+    assert_eq!(snapshot.bids[0].state, "live");
+    let resting = snapshot.bids[0].quote.unwrap();
+    assert_eq!(resting.price, Price::new(93.00));
+    assert!((resting.quantity.as_f64() - 0.03).abs() < 1e-9);
+    assert_eq!(snapshot.bids[0].age_ms, Some(1_000.0));
+    assert!(snapshot.asks[0].quote.is_none());
+
+    let cancelled = accepted + Duration::from_secs(2);
+    manager.on_report(
+        OrderReport::Cancelled {
+            order_id: bid.order_id.clone(),
+            instrument: instrument.clone(),
+            side: Buy,
+        },
+        cancelled,
+    );
+    assert_eq!(states(&manager, cancelled), ("no_order", "no_order"));
+    assert!(manager.snapshot(cancelled).bids[0].quote.is_none());
+}
+
+#[tokio::test]
+async fn the_snapshot_lists_every_level_nearest_first() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut manager = OrderManager::new(OrderIds::sequential());
+
+    quote_and_accept(
+        &mut manager,
+        &instrument,
+        &bids(&[93.00, 92.98, 92.96]),
+        Instant::now(),
+    )
+    .await;
+
+    let snapshot = manager.snapshot(Instant::now());
+    let prices: Vec<_> = snapshot
+        .levels(Buy)
+        .iter()
+        .map(|level| level.quote.unwrap().price)
+        .collect();
+    assert_eq!(prices, [93.00, 92.98, 92.96].map(Price::new));
+    assert!(snapshot.levels(Sell)[0].quote.is_none());
+}
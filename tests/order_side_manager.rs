@@ -0,0 +1,481 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::Buy;
+use accumulator::execution::order_ids::OrderIds;
+use accumulator::execution::order_report::{OrderReport, RejectKind};
+use accumulator::execution::order_side_manager::{OrderSideManager, SideInputs};
+use accumulator::execution::types::OrderSideState;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+use accumulator::types::quote::Quote;
+
+use common::qty;
+
+const QUANTITY: f64 = 0.05;
+
+fn bid(quantity: f64) -> Quote {
+    Quote {
+        price: Price::new(93.00),
+        quantity: qty(quantity),
+    }
+}
+
+/// A manager with a bid of [`QUANTITY`] resting on the venue, placed at `start`.
+fn resting_bid(instrument: &Instrument, start: Instant) -> OrderSideManager {
+    let mut manager = OrderSideManager::for_side(Buy, OrderIds::sequential());
+    let actions = manager.actions_for_target(bid_inputs(instrument, start, QUANTITY));
+    assert!(matches!(actions[..], [OrderAction::Place(_)]));
+
+    manager.on_report(
+        &OrderReport::Accepted {
+            order_id: "sim-1".to_string(),
+            instrument: instrument.clone(),
+            side: Buy,
+            price: Price::new(93.00),
+            quantity: qty(QUANTITY),
+        },
+        start,
+    );
+    manager
+}
+
+fn bid_inputs(instrument: &Instrument, now: Instant, quantity: f64) -> SideInputs<'_> {
+    let tick = instrument.trading_rules().price_tick;
+    SideInputs::new(instrument, now, tick, Some(bid(quantity)))
+}
+
+#[test]
+fn a_sub_step_quantity_difference_does_not_replace() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let mut manager = resting_bid(&instrument, start);
+
+    let step = instrument.trading_rules().quantity_step;
+    let later = start + Duration::from_secs(1);
+    for quantity in [QUANTITY + 1e-13, QUANTITY - 1e-13, QUANTITY + step * 0.4] {
+        let actions = manager.actions_for_target(bid_inputs(&instrument, later, quantity));
+        assert!(actions.is_empty(), "replaced for {quantity}: {actions:?}");
+    }
+}
+
+#[test]
+fn a_full_step_quantity_difference_replaces() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let mut manager = resting_bid(&instrument, start);
+
+    let step = instrument.trading_rules().quantity_step;
+    let later = start + Duration::from_secs(1);
+    let actions = manager.actions_for_target(bid_inputs(&instrument, later, QUANTITY + step));
+    assert!(
+        matches!(
+            &actions[..],
+            [OrderAction::Cancel { order_id, .. }, OrderAction::Place(order)]
+                if order_id == "sim-1" && order.quantity == qty(QUANTITY + step)
+        ),
+        "{actions:?}"
+    );
+}
+
+/// SOL/GBP with orders re-placed after resting 5 seconds.
+fn short_lived() -> Instrument {
+    let sol = InstrumentConfig::default().load().unwrap();
+    let mut rules = sol.trading_rules();
+    rules.max_order_lifetime_ms = Some(5_000);
+    Instrument::new(sol.base().to_string(), sol.quote().to_string(), rules)
+}
+
+#[test]
+fn an_order_past_its_lifetime_is_replaced_at_the_same_target() {
+    let instrument = short_lived();
+    let start = Instant::now();
+    let mut manager = resting_bid(&instrument, start);
+
+    let early = start + Duration::from_millis(4_999);
+    assert!(
+        manager
+            .actions_for_target(bid_inputs(&instrument, early, QUANTITY))
+            .is_empty()
+    );
+
+    let late = start + Duration::from_secs(5);
+    let actions = manager.actions_for_target(bid_inputs(&instrument, late, QUANTITY));
+    assert!(
+        matches!(
+            &actions[..],
+            [OrderAction::Cancel { order_id, .. }, OrderAction::Place(order)]
+                if order_id == "sim-1"
+                    && order.price == Price::new(93.00)
+                    && order.quantity == qty(QUANTITY)
+        ),
+        "{actions:?}"
+    );
+}
+
+#[test]
+fn a_fill_restarts_the_lifetime() {
+    let instrument = short_lived();
+    let start = Instant::now();
+    let mut manager = resting_bid(&instrument, start);
+
+    manager.on_report(
+        &OrderReport::PartiallyFilled {
+            order_id: "sim-1".to_string(),
+            instrument: instrument.clone(),
+            side: Buy,
+            price: Price::new(93.00),
+            quantity: qty(0.02),
+            cum_quantity: qty(0.02),
+        },
+        start + Duration::from_secs(3),
+    );
+
+    let remaining = QUANTITY - 0.02;
+    let later = start + Duration::from_secs(6);
+    assert!(
+        manager
+            .actions_for_target(bid_inputs(&instrument, later, remaining))
+            .is_empty()
+    );
+
+    let expired = start + Duration::from_secs(8);
+    let actions = manager.actions_for_target(bid_inputs(&instrument, expired, remaining));
+    assert!(
+        matches!(
+            &actions[..],
+            [OrderAction::Cancel { .. }, OrderAction::Place(_)]
+        ),
+        "{actions:?}"
+    );
+}
+
+#[test]
+fn orders_rest_indefinitely_without_a_lifetime() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let mut manager = resting_bid(&instrument, start);
+
+    let much_later = start + Duration::from_secs(3_600);
+    assert!(
+        manager
+            .actions_for_target(bid_inputs(&instrument, much_later, QUANTITY))
+            .is_empty()
+    );
+}
+
+#[test]
+fn a_placement_never_answered_is_cancelled_and_forgotten() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let mut manager = OrderSideManager::for_side(Buy, OrderIds::sequential());
+    manager.set_in_flight_timeout(Duration::from_secs(10));
+    manager.actions_for_target(bid_inputs(&instrument, start, QUANTITY));
+
+    let waiting = start + Duration::from_millis(9_999);
+    assert!(!manager.in_flight_expired(waiting));
+    assert!(
+        manager
+            .actions_for_target(bid_inputs(&instrument, waiting, QUANTITY))
+            .is_empty()
+    );
+
+    let expired = start + Duration::from_secs(10);
+    assert!(manager.in_flight_expired(expired));
+    let actions = manager.actions_for_target(bid_inputs(&instrument, expired, QUANTITY));
+    assert!(
+        matches!(&actions[..], [OrderAction::Cancel { order_id, .. }] if order_id == "sim-1"),
+        "{actions:?}"
+    );
+    assert!(matches!(manager.state(), OrderSideState::NoOrder));
+    assert!(!manager.in_flight_expired(expired));
+
+    let actions = manager.actions_for_target(bid_inputs(&instrument, expired, QUANTITY));
+    assert!(
+        matches!(&actions[..], [OrderAction::Place(order)] if order.order_id == "sim-2"),
+        "{actions:?}"
+    );
+
+    // A late answer for the abandoned order does not touch the new one.
+    manager.on_report(
+        &OrderReport::Accepted {
+            order_id: "sim-1".to_string(),
+            instrument: instrument.clone(),
+            side: Buy,
+            price: Price::new(93.00),
+            quantity: qty(QUANTITY),
+        },
+        expired,
+    );
+    assert_eq!(manager.state().order_id(), Some("sim-2"));
+    assert!(manager.has_inflight_actions());
+}
+
+#[test]
+fn a_cancel_never_answered_is_resent_and_forgotten() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let mut manager = resting_bid(&instrument, start);
+    manager.set_in_flight_timeout(Duration::from_secs(10));
+
+    let tick = instrument.trading_rules().price_tick;
+    let cancelled = start + Duration::from_secs(1);
+    let actions = manager.actions_for_target(SideInputs::new(&instrument, cancelled, tick, None));
+    assert!(matches!(&actions[..], [OrderAction::Cancel { .. }]));
+    assert!(matches!(manager.state(), OrderSideState::Cancelling { .. }));
+
+    let expired = cancelled + Duration::from_secs(10);
+    let actions = manager.actions_for_target(SideInputs::new(&instrument, expired, tick, None));
+    assert!(
+        matches!(&actions[..], [OrderAction::Cancel { order_id, .. }] if order_id == "sim-1"),
+        "{actions:?}"
+    );
+    assert!(matches!(manager.state(), OrderSideState::NoOrder));
+}
+
+/// A manager amending price moves of up to 5 ticks, with a bid resting at 93.00.
+fn amending_bid(instrument: &Instrument, start: Instant) -> OrderSideManager {
+    let mut manager = resting_bid(instrument, start);
+    manager.set_amend_max_ticks(Some(5));
+    manager
+}
+
+fn bid_at(instrument: &Instrument, now: Instant, price: f64) -> SideInputs<'_> {
+    let tick = instrument.trading_rules().price_tick;
+    let quote = Quote {
+        price: Price::new(price),
+        quantity: qty(QUANTITY),
+    };
+    SideInputs::new(instrument, now, tick, Some(quote))
+}
+
+#[test]
+fn a_small_price_move_amends_the_order_in_place() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let mut manager = amending_bid(&instrument, start);
+
+    manager.on_report(
+        &OrderReport::PartiallyFilled {
+            order_id: "sim-1".to_string(),
+            instrument: instrument.clone(),
+            side: Buy,
+            price: Price::new(93.00),
+            quantity: qty(0.02),
+            cum_quantity: qty(0.02),
+        },
+        start,
+    );
+
+    // The rest of the order, 0.03, moves; the amend is for the whole order.
+    let later = start + Duration::from_secs(1);
+    let tick = instrument.trading_rules().price_tick;
+    let quote = Quote {
+        price: Price::new(92.96),
+        quantity: qty(0.03),
+    };
+    let actions =
+        manager.actions_for_target(SideInputs::new(&instrument, later, tick, Some(quote)));
+    assert!(
+        matches!(
+            &actions[..],
+            [OrderAction::Amend { order_id, new_price, new_quantity, .. }]
+                if order_id == "sim-1"
+                    && *new_price == Price::new(92.96)
+                    && (new_quantity.as_f64() - QUANTITY).abs() < 1e-9
+        ),
+        "{actions:?}"
+    );
+    assert!(manager.has_inflight_actions());
+
+    manager.on_report(
+        &OrderReport::Amended {
+            order_id: "sim-1".to_string(),
+            instrument: instrument.clone(),
+            side: Buy,
+            price: Price::new(92.96),
+            quantity: qty(QUANTITY),
+        },
+        later,
+    );
+    assert!(
+        matches!(
+            manager.state(),
+            OrderSideState::Live { order_id, resting, filled }
+                if order_id == "sim-1"
+                    && resting.price == Price::new(92.96)
+                    && (resting.quantity.as_f64() - 0.03).abs() < 1e-9
+                    && (filled.as_f64() - 0.02).abs() < 1e-9
+        ),
+        "{:?}",
+        manager.state()
+    );
+}
+
+#[test]
+fn moves_beyond_the_bound_or_in_quantity_replace() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let later = start + Duration::from_secs(1);
+
+    let mut manager = amending_bid(&instrument, start);
+    let actions = manager.actions_for_target(bid_at(&instrument, later, 92.94));
+    assert!(
+        matches!(
+            &actions[..],
+            [OrderAction::Cancel { .. }, OrderAction::Place(_)]
+        ),
+        "{actions:?}"
+    );
+
+    let mut manager = amending_bid(&instrument, start);
+    let step = instrument.trading_rules().quantity_step;
+    let actions = manager.actions_for_target(bid_inputs(&instrument, later, QUANTITY + step));
+    assert!(
+        matches!(
+            &actions[..],
+            [OrderAction::Cancel { .. }, OrderAction::Place(_)]
+        ),
+        "{actions:?}"
+    );
+}
+
+#[test]
+fn a_refused_amend_falls_back_to_replacing() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let mut manager = amending_bid(&instrument, start);
+
+    let later = start + Duration::from_secs(1);
+    let actions = manager.actions_for_target(bid_at(&instrument, later, 92.97));
+    assert!(matches!(&actions[..], [OrderAction::Amend { .. }]));
+    assert!(
+        manager
+            .actions_for_target(bid_at(&instrument, later, 92.97))
+            .is_empty()
+    );
+
+    manager.on_report(
+        &OrderReport::AmendRejected {
+            order_id: "sim-1".to_string(),
+            instrument: instrument.clone(),
+            side: Buy,
+            reason: "EOrder:Post only order".to_string(),
+        },
+        later,
+    );
+    assert!(matches!(
+        manager.state(),
+        OrderSideState::Live { resting, .. } if resting.price == Price::new(93.00)
+    ));
+
+    let actions = manager.actions_for_target(bid_at(&instrument, later, 92.97));
+    assert!(
+        matches!(
+            &actions[..],
+            [OrderAction::Cancel { order_id, .. }, OrderAction::Place(order)]
+                if order_id == "sim-1" && order.price == Price::new(92.97)
+        ),
+        "{actions:?}"
+    );
+}
+
+/// Refuses the bid `manager` is placing for crossing the book, and returns its re-plan
+/// on a book whose best ask is 93.10.
+fn cross(
+    manager: &mut OrderSideManager,
+    instrument: &Instrument,
+    now: Instant,
+) -> Vec<OrderAction> {
+    let order_id = manager.state().order_id().unwrap().to_string();
+    manager.on_report(
+        &OrderReport::Rejected {
+            order_id,
+            instrument: instrument.clone(),
+            side: Buy,
+            kind: RejectKind::PostOnlyWouldCross,
+            reason: "EOrder:Post only order".to_string(),
+        },
+        now,
+    );
+    manager.actions_for_target(
+        bid_inputs(instrument, now, QUANTITY).with_touch(Some(Price::new(93.10))),
+    )
+}
+
+/// The price of the single place in `actions`.
+fn placed_at(actions: &[OrderAction]) -> f64 {
+    match actions {
+        [OrderAction::Place(order)] => order.price.as_f64(),
+        _ => panic!("expected one place: {actions:?}"),
+    }
+}
+
+#[test]
+fn a_crossing_place_is_repriced_a_tick_further_each_time_then_held() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let mut manager = OrderSideManager::for_side(Buy, OrderIds::sequential());
+    manager.set_reprice_attempts(2);
+    let actions = manager.actions_for_target(bid_inputs(&instrument, start, QUANTITY));
+    assert_eq!(placed_at(&actions), 93.00);
+
+    // The venue's ask had already reached the bid: each re-plan steps back from it.
+    let actions = cross(&mut manager, &instrument, start);
+    assert!((placed_at(&actions) - 92.99).abs() < 1e-9, "{actions:?}");
+    let actions = cross(&mut manager, &instrument, start);
+    assert!((placed_at(&actions) - 92.98).abs() < 1e-9, "{actions:?}");
+
+    // Out of attempts: nothing more is placed for the same target.
+    let actions = cross(&mut manager, &instrument, start);
+    assert!(actions.is_empty(), "{actions:?}");
+    let later = start + Duration::from_secs(1);
+    let actions = manager.actions_for_target(bid_inputs(&instrument, later, QUANTITY));
+    assert!(actions.is_empty(), "{actions:?}");
+    assert!(matches!(manager.state(), OrderSideState::NoOrder));
+}
+
+#[test]
+fn a_held_target_is_placed_again_once_it_moves() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+    let mut manager = OrderSideManager::for_side(Buy, OrderIds::sequential());
+    manager.set_reprice_attempts(1);
+    manager.actions_for_target(bid_inputs(&instrument, start, QUANTITY));
+    cross(&mut manager, &instrument, start);
+    assert!(cross(&mut manager, &instrument, start).is_empty());
+
+    let later = start + Duration::from_secs(1);
+    let actions = manager.actions_for_target(bid_at(&instrument, later, 92.95));
+    assert!((placed_at(&actions) - 92.95).abs() < 1e-9, "{actions:?}");
+}
+
+#[test]
+fn other_rejections_and_repricing_off_leave_the_target_alone() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+
+    let mut off = OrderSideManager::for_side(Buy, OrderIds::sequential());
+    off.actions_for_target(bid_inputs(&instrument, start, QUANTITY));
+    assert_eq!(placed_at(&cross(&mut off, &instrument, start)), 93.00);
+    assert!(!off.take_reprice());
+
+    let mut manager = OrderSideManager::for_side(Buy, OrderIds::sequential());
+    manager.set_reprice_attempts(2);
+    manager.actions_for_target(bid_inputs(&instrument, start, QUANTITY));
+    manager.on_report(
+        &OrderReport::Rejected {
+            order_id: "sim-1".to_string(),
+            instrument: instrument.clone(),
+            side: Buy,
+            kind: RejectKind::InsufficientFunds,
+            reason: "EOrder:Insufficient funds".to_string(),
+        },
+        start,
+    );
+    assert!(!manager.take_reprice());
+    let actions = manager.actions_for_target(bid_inputs(&instrument, start, QUANTITY));
+    assert_eq!(placed_at(&actions), 93.00);
+}
@@ -0,0 +1,274 @@
+mod common;
+
+use chrono::DateTime;
+
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::execution::order_action::{Order, OrderAction, OrderType, Side};
+use accumulator::execution::order_history::{LifecycleState, OrderLifecycle};
+use accumulator::execution::types::OpenOrder;
+use accumulator::types::instrument::InstrumentConfig;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+
+use common::{Expect, Harness, START_MS, Step, qty};
+
+fn kinds(lifecycle: &OrderLifecycle) -> Vec<&'static str> {
+    lifecycle.events.iter().map(|event| event.kind).collect()
+}
+
+#[tokio::test]
+async fn assembles_each_order_from_its_place_and_reports() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+
+    harness
+        .run(&[
+            (
+                0,
+                Step::Book {
+                    bid: 93.00,
+                    ask: 93.10,
+                },
+            ),
+            (100, Step::Accept(Buy)),
+            (150, Step::Reject(Sell)),
+            (300, Step::Fill(Buy)),
+        ])
+        .await
+        .unwrap();
+
+    let orders = harness.recent_orders();
+    assert_eq!(orders.len(), 2);
+    let (ask, bid) = (&orders[0], &orders[1]);
+
+    assert_eq!(bid.side, Some(Buy));
+    assert_eq!(bid.state, LifecycleState::Filled);
+    assert_eq!(kinds(bid), ["place", "placed", "accepted", "filled"]);
+    assert_eq!(bid.cycle_id, Some(1));
+    assert_eq!(bid.filled_quantity, bid.quantity.unwrap());
+    assert_eq!(bid.ack_ms, Some(100.0));
+    assert_eq!(bid.resolved_ms, Some(300.0));
+    assert_eq!(bid.events[3].elapsed_ms, 300.0);
+
+    assert_eq!(ask.side, Some(Sell));
+    assert_eq!(ask.state, LifecycleState::Rejected);
+    assert_eq!(kinds(ask), ["place", "placed", "rejected"]);
+    assert_eq!(ask.ack_ms, None);
+    assert_eq!(ask.resolved_ms, Some(150.0));
+    assert_eq!(ask.events[2].reason.as_deref(), Some("rejected by script"));
+}
+
+#[tokio::test]
+async fn a_venue_wide_cancel_resolves_every_working_order() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+
+    harness
+        .run(&[
+            (
+                0,
+                Step::Book {
+                    bid: 93.00,
+                    ask: 93.10,
+                },
+            ),
+            (100, Step::Accept(Buy)),
+            (200, Step::VenueCancelAll),
+        ])
+        .await
+        .unwrap();
+
+    for order in harness.recent_orders() {
+        assert_eq!(order.state, LifecycleState::Cancelled);
+        assert_eq!(order.events.last().unwrap().kind, "cancelled_all");
+        assert_eq!(order.resolved_ms, Some(200.0));
+    }
+}
+
+#[tokio::test]
+async fn evicts_finished_orders_first_and_working_ones_only_at_the_hard_cap() {
+    let mut harness = Harness::new(|config| {
+        config.order_history.capacity = 2;
+        config.order_history.max_entries = 3;
+    })
+    .await
+    .unwrap();
+
+    harness
+        .run(&[
+            (
+                0,
+                Step::Book {
+                    bid: 93.00,
+                    ask: 93.10,
+                },
+            ),
+            // Cancels for five orders the engine never placed, each finished on arrival.
+            (100, Step::FloodReports(5)),
+        ])
+        .await
+        .unwrap();
+
+    let orders = harness.recent_orders();
+    let ids: Vec<_> = orders.iter().map(|order| order.order_id.as_str()).collect();
+    // Two finished orders fit; of the working ones, only the older bid goes, to the cap.
+    assert_eq!(ids[..2], ["flood-4", "flood-3"]);
+    assert_eq!(orders.len(), 3);
+    assert_eq!(orders[2].side, Some(Sell));
+    assert_eq!(orders[2].state, LifecycleState::Placing);
+}
+
+fn resting(order_id: &str, side: Side, price: f64) -> Order {
+    Order {
+        order_id: order_id.to_string(),
+        instrument: InstrumentConfig::default().load().unwrap(),
+        side,
+        price: Price::new(price),
+        quantity: qty(0.05),
+        order_type: OrderType::POST_ONLY,
+        cycle_id: None,
+    }
+}
+
+#[tokio::test]
+async fn adopts_its_own_resting_bid_and_leaves_foreign_orders() {
+    let mut harness = Harness::new(|config| config.venue.order_id_prefix = Some("3f9c".into()))
+        .await
+        .unwrap();
+
+    harness
+        .adopt(
+            &[
+                resting("3f9c-SOLGBP-b7", Buy, 93.00),
+                resting("manual-sell", Sell, 95.00),
+            ],
+            None,
+        )
+        .await
+        .unwrap();
+
+    harness
+        .run(&[
+            (
+                0,
+                Step::Expect(Expect::Working {
+                    bid: true,
+                    ask: false,
+                }),
+            ),
+            (0, Step::Expect(Expect::Remaining(Buy, 0.05))),
+            (0, Step::Expect(Expect::Nothing)),
+            // Reports for the adopted bid are its own, not a foreign order's.
+            (100, Step::Fill(Buy)),
+            (
+                100,
+                Step::Expect(Expect::Working {
+                    bid: false,
+                    ask: false,
+                }),
+            ),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn leaves_orders_resting_under_another_deployments_prefix() {
+    let mut harness = Harness::new(|config| config.venue.order_id_prefix = Some("3f9c".into()))
+        .await
+        .unwrap();
+
+    harness
+        .adopt(
+            &[
+                resting("mm2-SOLGBP-b7", Buy, 93.00),
+                resting("mm2-SOLGBP-s8", Sell, 95.00),
+            ],
+            None,
+        )
+        .await
+        .unwrap();
+
+    harness
+        .run(&[
+            (
+                0,
+                Step::Expect(Expect::Working {
+                    bid: false,
+                    ask: false,
+                }),
+            ),
+            (0, Step::Expect(Expect::Nothing)),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn without_a_prefix_adopts_only_orders_its_intent_log_kept() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+
+    // Both were placed under some session id; only the bid is in the intent log.
+    harness
+        .adopt(
+            &[
+                resting("3f9c-SOLGBP-b7", Buy, 93.00),
+                resting("a1b2-SOLGBP-s8", Sell, 95.00),
+            ],
+            Some(&["3f9c-SOLGBP-b7"]),
+        )
+        .await
+        .unwrap();
+
+    harness
+        .run(&[
+            (
+                0,
+                Step::Expect(Expect::Working {
+                    bid: true,
+                    ask: false,
+                }),
+            ),
+            (0, Step::Expect(Expect::Nothing)),
+        ])
+        .await
+        .unwrap();
+}
+
+/// An order an earlier run left open, opened `minutes_ago`.
+fn left_open(order_id: &str, side: Side, minutes_ago: i64) -> OpenOrder {
+    let mut order = OpenOrder::resting(&resting(order_id, side, 93.00), Quantity::ZERO);
+    order.opened_at = DateTime::from_timestamp_millis(START_MS as i64 - minutes_ago * 60_000);
+    order
+}
+
+fn cancelled(harness: &Harness) -> Vec<String> {
+    harness
+        .actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            OrderAction::Cancel { order_id, .. } => Some(order_id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn adopts_the_newest_order_by_sequence_only_when_the_intent_log_keeps_it() {
+    // Without an intent log the later run numbered from 1 again, so its bid has the
+    // lower sequence; the venue's open time tells the two apart.
+    for (logged, kept, dropped) in [
+        (None, "3f9c-SOLGBP-b2", "3f9c-SOLGBP-b9"),
+        (Some(&[][..]), "3f9c-SOLGBP-b9", "3f9c-SOLGBP-b2"),
+    ] {
+        let mut harness = Harness::new(|config| config.venue.order_id_prefix = Some("3f9c".into()))
+            .await
+            .unwrap();
+        harness.leave_open(left_open("3f9c-SOLGBP-b9", Buy, 120));
+        harness.leave_open(left_open("3f9c-SOLGBP-b2", Buy, 60));
+
+        harness.adopt(&[], logged).await.unwrap();
+
+        let orders = harness.summary().await.orders.unwrap();
+        assert_eq!(orders.bids[0].order_id.as_deref(), Some(kept));
+        assert_eq!(cancelled(&harness), [dropped]);
+    }
+}
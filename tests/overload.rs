@@ -0,0 +1,198 @@
+mod common;
+
+use std::str::FromStr;
+
+use tokio::sync::mpsc;
+
+use accumulator::engine::channels::coalesce;
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+
+use common::{Act, Expect, Harness, Step};
+
+fn instrument(symbol: &str) -> Instrument {
+    InstrumentConfig::from_str(symbol).unwrap().load().unwrap()
+}
+
+fn book(instrument: &Instrument, bid: f64, timestamp_ms: u64) -> MarketEvent {
+    MarketEvent::TopOfBook {
+        instrument: instrument.clone(),
+        best_bid: Price::new(bid),
+        best_ask: Price::new(bid + 0.1),
+        bid_size: None,
+        ask_size: None,
+        timestamp_ms,
+    }
+}
+
+fn trade(instrument: &Instrument, price: f64, timestamp_ms: u64) -> MarketEvent {
+    MarketEvent::Trade {
+        instrument: instrument.clone(),
+        price: Price::new(price),
+        quantity: 0.1,
+        timestamp_ms,
+    }
+}
+
+/// (kind, instrument, timestamp) for comparing events.
+fn key(event: &MarketEvent) -> (&'static str, String, u64) {
+    (
+        event.kind(),
+        event.instrument().to_string(),
+        event.timestamp_ms(),
+    )
+}
+
+#[tokio::test]
+async fn a_flooded_market_channel_coalesces_to_trades_and_the_latest_books() {
+    let sol = instrument("SOL/GBP");
+    let btc = instrument("BTC/GBP");
+    let (sender, mut receiver) = mpsc::channel(10_000);
+
+    for ms in 1..10_000u64 {
+        let event = match ms % 1_000 {
+            0 => trade(&sol, 93.0, ms),
+            ms_in_second if ms_in_second % 3 == 0 => book(&btc, 40_000.0, ms),
+            _ => book(&sol, 93.0, ms),
+        };
+        sender.try_send(event).unwrap();
+    }
+
+    let (events, dropped) = coalesce(book(&sol, 93.0, 0), &mut receiver);
+
+    assert!(receiver.is_empty());
+    assert_eq!(events.len() + dropped, 10_000);
+    assert_eq!(
+        events.iter().map(key).collect::<Vec<_>>(),
+        (1..10)
+            .map(|second| ("trade", "SOL/GBP".to_string(), second * 1_000))
+            .chain([
+                ("book", "SOL/GBP".to_string(), 9_998),
+                ("book", "BTC/GBP".to_string(), 9_999),
+            ])
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn lagged_reports_resync_orders_from_the_venue() {
+    let mut harness = Harness::new(|config| config.channels.order_reports = 16)
+        .await
+        .unwrap();
+
+    harness
+        .run(&[
+            (
+                0,
+                Step::Book {
+                    bid: 93.00,
+                    ask: 93.10,
+                },
+            ),
+            (
+                0,
+                Step::Expect(Expect::Actions(vec![Act::Place(Buy), Act::Place(Sell)])),
+            ),
+            // The accept and the fill are pushed out of the report channel by the flood,
+            // so only the venue knows the bid rests and the ask is gone.
+            (
+                100,
+                Step::Burst(vec![
+                    Step::Accept(Buy),
+                    Step::Fill(Sell),
+                    Step::FloodReports(64),
+                ]),
+            ),
+            (
+                100,
+                Step::Expect(Expect::Working {
+                    bid: true,
+                    ask: false,
+                }),
+            ),
+            // Both sides would still look in flight without the resync, and be skipped.
+            (
+                2_000,
+                Step::Book {
+                    bid: 93.03,
+                    ask: 93.13,
+                },
+            ),
+            (
+                2_000,
+                Step::Expect(Expect::Actions(vec![
+                    Act::Cancel(Buy),
+                    Act::Place(Buy),
+                    Act::Place(Sell),
+                ])),
+            ),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn a_failed_resync_holds_quoting_until_the_venue_answers() {
+    let mut harness = Harness::new(|config| config.channels.order_reports = 16)
+        .await
+        .unwrap();
+
+    harness
+        .run(&[
+            (
+                0,
+                Step::Book {
+                    bid: 93.00,
+                    ask: 93.10,
+                },
+            ),
+            (
+                0,
+                Step::Expect(Expect::Actions(vec![Act::Place(Buy), Act::Place(Sell)])),
+            ),
+            // The lag's resync cannot read the venue's open orders.
+            (100, Step::OpenOrdersDown(true)),
+            (
+                100,
+                Step::Burst(vec![
+                    Step::Accept(Buy),
+                    Step::Fill(Sell),
+                    Step::FloodReports(64),
+                ]),
+            ),
+            (
+                2_000,
+                Step::Book {
+                    bid: 93.03,
+                    ask: 93.13,
+                },
+            ),
+            (2_000, Step::Expect(Expect::Nothing)),
+            // Each cycle retries it, and the first to succeed quotes on the venue's
+            // state, well before the places would have timed out in flight: the bid is
+            // found resting and the filled ask is placed again.
+            (3_000, Step::OpenOrdersDown(false)),
+            (
+                3_000,
+                Step::Book {
+                    bid: 93.03,
+                    ask: 93.13,
+                },
+            ),
+            (3_000, Step::Expect(Expect::Actions(vec![Act::Place(Sell)]))),
+            (
+                3_000,
+                Step::Expect(Expect::Working {
+                    bid: true,
+                    ask: true,
+                }),
+            ),
+        ])
+        .await
+        .unwrap();
+
+    let summary = harness.summary().await;
+    assert_eq!(summary.skips.get("orders_unsynced"), Some(&1));
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use accumulator::execution::order_action::Side::{self, Buy, Sell};
+use accumulator::execution::order_report::OrderReport;
+use accumulator::types::instrument::InstrumentConfig;
+use accumulator::types::pnl::PnlTracker;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+
+use common::qty;
+
+fn close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "{actual} is not {expected}"
+    );
+}
+
+fn fill(order_id: &str, side: Side, price: f64, cum_quantity: f64, complete: bool) -> OrderReport {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let price = Price::new(price);
+    // Deliberately not the increment, which the tracker must derive from cum_quantity.
+    let quantity = Quantity::ZERO;
+    let cum_quantity = qty(cum_quantity);
+    if complete {
+        OrderReport::Filled {
+            order_id: order_id.to_string(),
+            instrument,
+            side,
+            price,
+            quantity,
+            cum_quantity,
+        }
+    } else {
+        OrderReport::PartiallyFilled {
+            order_id: order_id.to_string(),
+            instrument,
+            side,
+            price,
+            quantity,
+            cum_quantity,
+        }
+    }
+}
+
+#[test]
+fn averages_entries_and_realizes_on_reduction() {
+    let mut pnl = PnlTracker::default();
+    pnl.on_fill(Buy, Price::new(100.0), qty(1.0));
+    pnl.on_fill(Buy, Price::new(110.0), qty(1.0));
+
+    close(pnl.position(), 2.0);
+    assert_eq!(pnl.average_entry(), Some(Price::new(105.0)));
+    close(pnl.unrealized(Price::new(106.0)), 2.0);
+
+    pnl.on_fill(Sell, Price::new(108.0), qty(0.5));
+    close(pnl.realized(), 1.5);
+    close(pnl.position(), 1.5);
+    assert_eq!(pnl.average_entry(), Some(Price::new(105.0)));
+
+    pnl.on_fill(Sell, Price::new(104.0), qty(1.5));
+    close(pnl.realized(), 0.0);
+    close(pnl.position(), 0.0);
+    assert_eq!(pnl.average_entry(), None);
+    close(pnl.unrealized(Price::new(120.0)), 0.0);
+}
+
+#[test]
+fn a_fill_through_flat_opens_the_other_side_at_its_price() {
+    let mut pnl = PnlTracker::default();
+    pnl.on_fill(Buy, Price::new(100.0), qty(1.0));
+
+    pnl.on_fill(Sell, Price::new(102.0), qty(3.0));
+    close(pnl.realized(), 2.0);
+    close(pnl.position(), -2.0);
+    assert_eq!(pnl.average_entry(), Some(Price::new(102.0)));
+    // Short two from 102: a lower mid is a gain.
+    close(pnl.unrealized(Price::new(101.0)), 2.0);
+
+    pnl.on_fill(Buy, Price::new(103.0), qty(2.0));
+    close(pnl.realized(), 0.0);
+    close(pnl.position(), 0.0);
+}
+
+#[test]
+fn partial_fills_apply_what_each_adds_to_cum_quantity() {
+    let mut pnl = PnlTracker::default();
+
+    pnl.on_report(&fill("bid-1", Buy, 100.0, 0.02, false));
+    pnl.on_report(&fill("bid-1", Buy, 100.0, 0.03, false));
+    // Repeated: nothing new.
+    pnl.on_report(&fill("bid-1", Buy, 100.0, 0.03, false));
+    close(pnl.position(), 0.03);
+
+    pnl.on_report(&fill("bid-1", Buy, 100.0, 0.05, true));
+    close(pnl.position(), 0.05);
+
+    // Another order starts from nothing.
+    pnl.on_report(&fill("ask-1", Sell, 101.0, 0.05, true));
+    close(pnl.position(), 0.0);
+    close(pnl.realized(), 0.05);
+}
+
+#[test]
+fn a_fill_replayed_after_the_order_finished_applies_nothing() {
+    let mut pnl = PnlTracker::default();
+    pnl.on_report(&fill("bid-1", Buy, 100.0, 0.05, true));
+    pnl.on_report(&fill("ask-1", Sell, 102.0, 0.02, false));
+    pnl.on_report(&OrderReport::Cancelled {
+        order_id: "ask-1".to_string(),
+        instrument: InstrumentConfig::default().load().unwrap(),
+        side: Sell,
+    });
+
+    // A duplicated or replayed report, after each order finished.
+    pnl.on_report(&fill("bid-1", Buy, 100.0, 0.05, true));
+    pnl.on_report(&fill("ask-1", Sell, 102.0, 0.02, false));
+    close(pnl.position(), 0.03);
+    close(pnl.realized(), 0.04);
+}
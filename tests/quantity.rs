@@ -0,0 +1,41 @@
+use accumulator::types::inventory::Inventory;
+use accumulator::types::quantity::Quantity;
+
+#[test]
+fn refuses_a_size_that_is_no_number_or_negative() {
+    for (value, reason) in [
+        (f64::NAN, "not a finite number"),
+        (f64::INFINITY, "not a finite number"),
+        (-0.05, "is negative"),
+    ] {
+        let error = Quantity::new(value).unwrap_err().to_string();
+        assert!(error.contains(reason), "{value}: {error}");
+    }
+
+    let zero = Quantity::new(-0.0).unwrap();
+    assert_eq!(zero, Quantity::ZERO);
+    assert_eq!(zero.to_string(), "0");
+}
+
+#[test]
+fn arithmetic_stays_a_size() {
+    let small = Quantity::new(0.02).unwrap();
+    let large = Quantity::new(0.05).unwrap();
+
+    assert_eq!(small.saturating_sub(large), Quantity::ZERO);
+    assert!((large.saturating_sub(small).as_f64() - 0.03).abs() < 1e-12);
+    assert_eq!(small.min(large), small);
+    assert_eq!(small.max(large), large);
+    assert_eq!(
+        Quantity::new(f64::MAX).unwrap() + large,
+        Quantity::new(f64::MAX).unwrap()
+    );
+    assert!((Quantity::new(0.0599).unwrap().round_to_step(0.01).as_f64() - 0.05).abs() < 1e-12);
+    assert_eq!(large.round_to_step(0.0), large);
+}
+
+#[test]
+fn a_position_that_is_no_number_has_no_size() {
+    assert!((Inventory::new(-1.5, 0.0).base_size().unwrap().as_f64() - 1.5).abs() < 1e-12);
+    assert!(Inventory::new(f64::NAN, 0.0).base_size().is_err());
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::time::Duration;
+
+use tokio::sync::broadcast::{self, Receiver};
+
+use accumulator::clock::SimClock;
+use accumulator::engine::supervisor::Supervisor;
+use accumulator::execution::ExecutionVenue;
+use accumulator::execution::order_action::Side::{self, Buy, Sell};
+use accumulator::execution::order_action::{Order, OrderAction, OrderType};
+use accumulator::execution::order_report::OrderReport;
+use accumulator::execution::rate_limited_venue::RateLimitedVenue;
+use accumulator::types::instrument::InstrumentConfig;
+use accumulator::types::price::Price;
+
+use common::{INITIAL, MockVenue, qty};
+
+fn place(order_id: &str, side: Side) -> OrderAction {
+    OrderAction::Place(Order {
+        order_id: order_id.to_string(),
+        instrument: InstrumentConfig::default().load().unwrap(),
+        side,
+        price: Price::new(93.00),
+        quantity: qty(0.05),
+        order_type: OrderType::POST_ONLY,
+        cycle_id: None,
+    })
+}
+
+fn cancel(order_id: &str, side: Side) -> OrderAction {
+    OrderAction::Cancel {
+        order_id: order_id.to_string(),
+        instrument: InstrumentConfig::default().load().unwrap(),
+        side,
+    }
+}
+
+/// Ids of the places that reached the venue, in order.
+fn passed(venue: &MockVenue) -> Vec<String> {
+    venue
+        .actions()
+        .into_iter()
+        .filter_map(|action| match action {
+            OrderAction::Place(order) => Some(order.order_id),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Venue errors and rejections sent so far, leaving out the mock venue's own reports.
+fn limit_reports(reports: &mut Receiver<OrderReport>) -> Vec<OrderReport> {
+    std::iter::from_fn(|| reports.try_recv().ok())
+        .filter(|report| {
+            matches!(
+                report,
+                OrderReport::VenueError { .. } | OrderReport::Rejected { .. }
+            )
+        })
+        .collect()
+}
+
+async fn limited(
+    limit: u32,
+    clock: &SimClock,
+) -> (RateLimitedVenue, MockVenue, Receiver<OrderReport>) {
+    let (sender, reports) = broadcast::channel(64);
+    let mock = MockVenue::new(sender.clone(), INITIAL);
+    let venue = RateLimitedVenue::new(Box::new(mock.clone()), limit, clock.shared());
+    let (supervisor, _escalations) = Supervisor::new();
+    venue.spawn_reports(sender, &supervisor).await.unwrap();
+    (venue, mock, reports)
+}
+
+#[tokio::test]
+async fn places_past_the_limit_are_dropped_until_the_window_moves_on() {
+    let clock = SimClock::from_timestamp_ms(0);
+    let (venue, mock, mut reports) = limited(3, &clock).await;
+
+    venue
+        .execute(&[place("b1", Buy), place("s1", Sell), place("b2", Buy)])
+        .await
+        .unwrap();
+    assert_eq!(passed(&mock), ["b1", "s1", "b2"]);
+    assert!(limit_reports(&mut reports).is_empty());
+
+    // A millisecond short of a minute after the first three, the window is still full.
+    clock.advance(Duration::from_millis(59_999));
+    venue.execute(&[place("s2", Sell)]).await.unwrap();
+    assert_eq!(passed(&mock), ["b1", "s1", "b2"]);
+    let limit = limit_reports(&mut reports);
+    assert!(
+        matches!(
+            &limit[..],
+            [
+                OrderReport::VenueError { message },
+                OrderReport::Rejected { order_id, side: Sell, .. },
+            ] if message.contains("rate limit") && order_id == "s2"
+        ),
+        "{limit:?}"
+    );
+
+    // Cancels go through, and a second dropped place does not repeat the venue error.
+    venue
+        .execute(&[cancel("b1", Buy), place("b3", Buy)])
+        .await
+        .unwrap();
+    assert!(
+        mock.actions().iter().any(
+            |action| matches!(action, OrderAction::Cancel { order_id, .. } if order_id == "b1")
+        )
+    );
+    assert_eq!(passed(&mock), ["b1", "s1", "b2"]);
+    let limit = limit_reports(&mut reports);
+    assert!(
+        matches!(&limit[..], [OrderReport::Rejected { order_id, .. }] if order_id == "b3"),
+        "{limit:?}"
+    );
+
+    // Exactly a minute on, the first three have left the window.
+    clock.advance(Duration::from_millis(1));
+    venue.execute(&[place("b4", Buy)]).await.unwrap();
+    assert_eq!(passed(&mock), ["b1", "s1", "b2", "b4"]);
+    assert!(limit_reports(&mut reports).is_empty());
+}
+
+#[tokio::test]
+async fn the_window_slides_rather_than_resetting() {
+    let clock = SimClock::from_timestamp_ms(0);
+    let (venue, mock, mut reports) = limited(2, &clock).await;
+
+    venue.execute(&[place("b1", Buy)]).await.unwrap();
+    clock.advance(Duration::from_secs(30));
+    venue.execute(&[place("b2", Buy)]).await.unwrap();
+
+    // b1 has left the window but b2 has not, so one more fits and the next does not.
+    clock.advance(Duration::from_secs(30));
+    venue
+        .execute(&[place("b3", Buy), place("b4", Buy)])
+        .await
+        .unwrap();
+    assert_eq!(passed(&mock), ["b1", "b2", "b3"]);
+    let limit = limit_reports(&mut reports);
+    assert!(
+        matches!(
+            &limit[..],
+            [OrderReport::VenueError { .. }, OrderReport::Rejected { order_id, .. }]
+                if order_id == "b4"
+        ),
+        "{limit:?}"
+    );
+}
@@ -0,0 +1,153 @@
+use std::time::{Duration, Instant};
+
+use accumulator::events::MarketEvent;
+use accumulator::market::market_state::MarketState;
+use accumulator::scenario::scenario::Scenario;
+use accumulator::scenario::strategies::StrategyKind;
+use accumulator::signals::config::{ReferencePriceConfig, SignalsConfig};
+use accumulator::signals::reference_price::ReferenceFairPrice;
+use accumulator::strategy::strategies::simple_mm::SimpleMarketMakerStrategy;
+use accumulator::strategy::strategy_helpers::StrategyHelpers;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+
+fn book(symbol: &str, bid: f64, ask: f64) -> MarketEvent {
+    let pair: InstrumentConfig = symbol.parse().unwrap();
+    MarketEvent::TopOfBook {
+        instrument: Instrument::watched(pair.base, pair.quote),
+        best_bid: Price::new(bid),
+        best_ask: Price::new(ask),
+        bid_size: None,
+        ask_size: None,
+        timestamp_ms: 0,
+    }
+}
+
+fn reference(instrument: &str, fx: Option<&str>) -> ReferencePriceConfig {
+    ReferencePriceConfig {
+        instrument: instrument.to_string(),
+        fx: fx.map(str::to_string),
+        max_age_ms: 2_000,
+    }
+}
+
+#[test]
+fn the_reference_mid_is_converted_through_the_fx_pair_either_way_round() {
+    let sol_gbp = InstrumentConfig::default().load().unwrap();
+    let now = Instant::now();
+
+    let mut divided =
+        ReferenceFairPrice::new(&reference("SOL/USD", Some("GBP/USD")), &sol_gbp).unwrap();
+    assert!(divided.on_market_event(&book("SOL/USD", 149.9, 150.1), now));
+    assert_eq!(divided.fair_price(now), None, "no FX rate yet");
+    assert!(divided.on_market_event(&book("GBP/USD", 1.2499, 1.2501), now));
+    assert!((divided.fair_price(now).unwrap() - 120.0).abs() < 1e-9);
+
+    let mut multiplied =
+        ReferenceFairPrice::new(&reference("SOL/USD", Some("USD/GBP")), &sol_gbp).unwrap();
+    multiplied.on_market_event(&book("SOL/USD", 149.9, 150.1), now);
+    multiplied.on_market_event(&book("USD/GBP", 0.7999, 0.8001), now);
+    assert!((multiplied.fair_price(now).unwrap() - 120.0).abs() < 1e-9);
+
+    // Books for other pairs are not the reference's.
+    assert!(!multiplied.on_market_event(&book("SOL/GBP", 1.0, 2.0), now));
+    assert!((multiplied.fair_price(now).unwrap() - 120.0).abs() < 1e-9);
+}
+
+#[test]
+fn a_reference_in_the_same_quote_currency_needs_no_fx() {
+    let sol_gbp = InstrumentConfig::default().load().unwrap();
+    let now = Instant::now();
+
+    let mut fair = ReferenceFairPrice::new(&reference("SOL/GBP", None), &sol_gbp).unwrap();
+    assert_eq!(fair.instruments().count(), 1);
+    fair.on_market_event(&book("SOL/GBP", 119.9, 120.1), now);
+    assert!((fair.fair_price(now).unwrap() - 120.0).abs() < 1e-9);
+}
+
+#[test]
+fn references_that_cannot_price_the_pair_are_refused() {
+    let sol_gbp = InstrumentConfig::default().load().unwrap();
+
+    for (config, expected) in [
+        (reference("BTC/USD", Some("GBP/USD")), "must have base SOL"),
+        (
+            reference("SOL/USD", None),
+            "needs an fx pair from USD to GBP",
+        ),
+        (
+            reference("SOL/USD", Some("EUR/USD")),
+            "must pair USD with GBP",
+        ),
+    ] {
+        let error = ReferenceFairPrice::new(&config, &sol_gbp).unwrap_err();
+        assert!(error.to_string().contains(expected), "{error}");
+    }
+
+    let config = SignalsConfig {
+        references: [("SOL/GBP".to_string(), reference("SOLUSD", None))].into(),
+        ..SignalsConfig::default()
+    };
+    let error = config.validate("signals").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "signals.references.SOL/GBP.instrument: must be a BASE/QUOTE pair"
+    );
+}
+
+#[test]
+fn the_reference_goes_stale_when_either_book_stops_updating() {
+    let sol_gbp = InstrumentConfig::default().load().unwrap();
+    let start = Instant::now();
+
+    let mut fair =
+        ReferenceFairPrice::new(&reference("SOL/USD", Some("GBP/USD")), &sol_gbp).unwrap();
+    fair.on_market_event(&book("SOL/USD", 149.9, 150.1), start);
+    fair.on_market_event(&book("GBP/USD", 1.2499, 1.2501), start);
+    assert!(
+        fair.fair_price(start + Duration::from_millis(2_000))
+            .is_some()
+    );
+
+    let later = start + Duration::from_millis(2_500);
+    assert_eq!(fair.fair_price(later), None);
+
+    // A fresh reference book is not enough while the FX rate is old.
+    fair.on_market_event(&book("SOL/USD", 159.9, 160.1), later);
+    assert_eq!(fair.fair_price(later), None);
+    fair.on_market_event(&book("GBP/USD", 1.2799, 1.2801), later);
+    assert!((fair.fair_price(later).unwrap() - 125.0).abs() < 1e-9);
+
+    // A disconnect forgets the book at once.
+    fair.on_market_event(
+        &MarketEvent::SourceDisconnected {
+            instrument: Instrument::watched("GBP".to_string(), "USD".to_string()),
+            timestamp_ms: 0,
+        },
+        later,
+    );
+    assert_eq!(fair.fair_price(later), None);
+}
+
+#[test]
+fn strategies_price_from_a_fresh_reference_and_fall_back_to_the_local_book() {
+    let sol_gbp = InstrumentConfig::default().load().unwrap();
+    let now = Instant::now();
+    let mut market = MarketState::new();
+    market.on_market_event(&book("SOL/GBP", 117.9, 118.1), now);
+    let mut signals = Scenario::signals(StrategyKind::SimpleMarketMaker, &SignalsConfig::default());
+    signals.update(&market, now);
+
+    let mut fair =
+        ReferenceFairPrice::new(&reference("SOL/USD", Some("GBP/USD")), &sol_gbp).unwrap();
+    fair.on_market_event(&book("SOL/USD", 149.9, 150.1), now);
+    fair.on_market_event(&book("GBP/USD", 1.2499, 1.2501), now);
+
+    signals.set_reference_fair(fair.fair_price(now));
+    let price = SimpleMarketMakerStrategy::fair_price(&market, &signals).unwrap();
+    assert!((price - 120.0).abs() < 1e-9);
+
+    signals.set_reference_fair(fair.fair_price(now + Duration::from_secs(3)));
+    let price = SimpleMarketMakerStrategy::fair_price(&market, &signals).unwrap();
+    assert!((price - 118.0).abs() < 1e-9);
+}
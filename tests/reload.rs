@@ -0,0 +1,192 @@
+mod common;
+
+use accumulator::config::app_config::AppConfig;
+use accumulator::config::reload::{changed_sections, reloaded_trading_rules};
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::scenario::strategies::StrategyKind;
+use accumulator::scenario::venues::VenueKind;
+use accumulator::types::instrument::InstrumentConfig;
+
+use common::{Act, Expect, Harness, Step};
+
+fn book(bid: f64, ask: f64) -> Step {
+    Step::Book { bid, ask }
+}
+
+fn changed(configure: impl FnOnce(&mut AppConfig)) -> anyhow::Result<Vec<&'static str>> {
+    let current = AppConfig::default();
+    let mut new = current.clone();
+    configure(&mut new);
+    changed_sections(&current, &new)
+}
+
+#[test]
+fn reports_the_hot_swappable_sections_that_changed() {
+    assert_eq!(changed(|_| {}).unwrap(), Vec::<&str>::new());
+    assert_eq!(
+        changed(|config| {
+            config.strategy.mean_reversion.entry_threshold_ticks = 5.0;
+            config.risk.churn_min_interval_ms = 1_000;
+            config.scheduling.min_tick_move = 2.0;
+            config.alerts.cooldown_secs = 60;
+        })
+        .unwrap(),
+        ["strategy", "risk", "scheduling", "alerts"]
+    );
+}
+
+#[test]
+fn rejects_changes_that_need_a_restart() {
+    let error = changed(|config| {
+        config.venue.kind = VenueKind::Kraken;
+        config.strategy.kind = StrategyKind::TrendFollowing;
+        config.risk.max_exposure_in_quote = Some(10.0);
+    })
+    .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "venue, strategy.kind: only read at startup; restart to apply"
+    );
+}
+
+#[test]
+fn portfolio_caps_move_but_cannot_be_added() {
+    let error =
+        changed(|config| config.risk.max_portfolio_exposure_in_quote = Some(100.0)).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .starts_with("risk.max_portfolio_exposure_in_quote:")
+    );
+
+    let mut current = AppConfig::default();
+    current.risk.max_portfolio_exposure_in_quote = Some(100.0);
+    let mut new = current.clone();
+    new.risk.max_portfolio_exposure_in_quote = Some(50.0);
+    assert_eq!(changed_sections(&current, &new).unwrap(), ["risk"]);
+}
+
+#[tokio::test]
+async fn next_evaluation_uses_a_reloaded_risk_threshold() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness
+        .run(&[
+            (0, book(93.00, 93.10)),
+            (1_000, book(93.00, 93.10)),
+            (
+                1_000,
+                Step::Expect(Expect::Actions(vec![Act::Place(Buy), Act::Place(Sell)])),
+            ),
+            (1_010, Step::Accept(Buy)),
+            (1_010, Step::Accept(Sell)),
+        ])
+        .await
+        .unwrap();
+
+    // Both quotes were placed two seconds ago, inside the reloaded churn window, so
+    // they stay where they rest rather than following the book.
+    harness.reload(|config| config.risk.churn_min_interval_ms = 5_000);
+
+    harness
+        .run(&[
+            (3_000, book(93.05, 93.15)),
+            (3_000, Step::Expect(Expect::Nothing)),
+            (
+                3_000,
+                Step::Expect(Expect::Working {
+                    bid: true,
+                    ask: true,
+                }),
+            ),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn reloaded_scheduling_applies_to_the_next_event() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.reload(|config| config.scheduling.min_tick_move = 20.0);
+
+    harness
+        .run(&[
+            (0, book(93.00, 93.10)),
+            (
+                0,
+                Step::Expect(Expect::Actions(vec![Act::Place(Buy), Act::Place(Sell)])),
+            ),
+            (10, Step::Accept(Buy)),
+            (10, Step::Accept(Sell)),
+            // Three ticks would replace both quotes at the default one-tick move.
+            (900, book(93.03, 93.13)),
+            (900, Step::Expect(Expect::Nothing)),
+        ])
+        .await
+        .unwrap();
+}
+
+#[test]
+fn reloads_trading_rules_that_changed_and_keeps_invalid_ones_out() {
+    let config = AppConfig::default();
+    let instrument = config.instruments[0].load().unwrap();
+    let copy = instrument.clone();
+    assert!(
+        reloaded_trading_rules([&instrument], &config)
+            .unwrap()
+            .is_empty()
+    );
+
+    let mut rules = instrument.trading_rules();
+    rules.min_half_spread = 0.05;
+    let mut new = config.clone();
+    new.instruments = vec![InstrumentConfig {
+        trading_rules: Some(rules.clone()),
+        ..InstrumentConfig::default()
+    }];
+    // Inline rules are not a change of instruments.
+    assert_eq!(changed_sections(&config, &new).unwrap(), Vec::<&str>::new());
+
+    let reloaded = reloaded_trading_rules([&instrument], &new).unwrap();
+    assert_eq!(reloaded.len(), 1);
+    assert!(instrument.set_trading_rules(reloaded[0].1.clone()));
+    assert_eq!(copy.trading_rules().min_half_spread, 0.05);
+
+    rules.price_tick = 0.0;
+    new.instruments[0].trading_rules = Some(rules);
+    let error = reloaded_trading_rules([&instrument], &new).unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "trading rules for SOL/GBP not reloaded: price_tick must be > 0"
+    );
+    assert_eq!(copy.trading_rules().price_tick, 0.01);
+}
+
+#[tokio::test]
+async fn reloaded_min_half_spread_applies_to_the_next_evaluation() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    // A ten-tick book leaves 0.05 of half-spread, short of the new minimum.
+    harness.reload_trading_rules(|rules| rules.min_half_spread = 0.06);
+
+    harness
+        .run(&[
+            (0, book(93.00, 93.10)),
+            (1_000, book(93.00, 93.10)),
+            (1_000, Step::Expect(Expect::Nothing)),
+        ])
+        .await
+        .unwrap();
+
+    harness.reload_trading_rules(|rules| rules.min_half_spread = 0.01);
+
+    harness
+        .run(&[
+            (2_000, book(93.00, 93.10)),
+            (
+                2_000,
+                Step::Expect(Expect::Actions(vec![Act::Place(Buy), Act::Place(Sell)])),
+            ),
+        ])
+        .await
+        .unwrap();
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::{broadcast, mpsc};
+
+use accumulator::clock::SimClock;
+use accumulator::config::app_config::AppConfig;
+use accumulator::engine::engine::{Connections, Engine};
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::{self, Buy};
+use accumulator::market::market_source::MarketDataSource;
+use accumulator::scenario::strategies::StrategyKind;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+
+use common::{INITIAL, MockVenue, START_MS};
+
+/// Market data the test pushes straight into the engine's queue, so a book and a report
+/// can both be waiting when the engine loop next wakes.
+#[derive(Default, Clone)]
+struct ScriptedMarket {
+    queue: Arc<Mutex<Option<mpsc::Sender<MarketEvent>>>>,
+}
+
+impl ScriptedMarket {
+    fn book(&self, instrument: &Instrument, bid: f64) {
+        let queue = self.queue.lock().unwrap().clone().expect("subscribed");
+        queue
+            .try_send(MarketEvent::TopOfBook {
+                instrument: instrument.clone(),
+                best_bid: Price::new(bid),
+                best_ask: Price::new(bid + 0.10),
+                bid_size: None,
+                ask_size: None,
+                timestamp_ms: 0,
+            })
+            .unwrap();
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for ScriptedMarket {
+    async fn subscribe(
+        &self,
+        _instrument: &Instrument,
+        channel: mpsc::Sender<MarketEvent>,
+    ) -> Result<()> {
+        *self.queue.lock().unwrap() = Some(channel);
+        std::future::pending().await
+    }
+}
+
+async fn wait_for(what: &str, done: impl Fn() -> bool) {
+    for _ in 0..500 {
+        if done() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+    panic!("timed out waiting for {what}");
+}
+
+fn placed(venue: &MockVenue, side: Side) -> Vec<String> {
+    venue
+        .actions()
+        .iter()
+        .filter_map(|action| match action {
+            OrderAction::Place(order) if order.side == side => Some(order.order_id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn cancelled(venue: &MockVenue) -> Vec<String> {
+    venue
+        .actions()
+        .iter()
+        .filter_map(|action| match action {
+            OrderAction::Cancel { order_id, .. } => Some(order_id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A fill and a book move arrive together, round after round. Handling the book first
+/// would plan against the filled order as if it still rested, cancelling it and then
+/// placing on top of it; with reports drained first the engine only re-quotes the bid.
+#[tokio::test]
+async fn reports_are_applied_before_the_market_event_that_raced_them() {
+    let mut config = AppConfig::default();
+    config.strategy.kind = StrategyKind::SimpleMarketMaker;
+    config.scheduling.min_interval_ms = 0;
+    config.scheduling.min_tick_move = 0.0;
+    config.risk.churn_min_interval_ms = 0;
+    config.watchdog.exit = false;
+    config.stats.reports_dir = std::env::temp_dir().join("accumulator-report-priority");
+
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let (reports, _) = broadcast::channel(config.channels.order_reports);
+    let venue = MockVenue::new(reports.clone(), INITIAL);
+    let market = ScriptedMarket::default();
+    let connections = Connections {
+        venue: Box::new(venue.clone()),
+        reports,
+        market: Arc::new(market.clone()),
+    };
+
+    // Inside the pair's trading hours whenever the test runs.
+    let clock = SimClock::from_timestamp_ms(START_MS);
+    let engine = Engine::start_with_clock(
+        "report-priority",
+        config,
+        vec![instrument.clone()],
+        connections,
+        clock.shared(),
+    )
+    .await
+    .unwrap();
+    let script = async {
+        wait_for("market subscription", || {
+            market.queue.lock().unwrap().is_some()
+        })
+        .await;
+
+        let mut bid = 93.00;
+        market.book(&instrument, bid);
+
+        for round in 0..6 {
+            wait_for("a bid to rest", || placed(&venue, Buy).len() == round + 1).await;
+            venue.accept_working();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            // Past the bid's minimum lifetime, so a book move is enough to replace it.
+            clock.advance(Duration::from_millis(550));
+
+            let filled = placed(&venue, Buy).last().unwrap().clone();
+            bid += 0.05;
+            market.book(&instrument, bid);
+            venue.fill(Buy);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+
+            // A second book, so the bid is re-quoted whichever of the two went first.
+            market.book(&instrument, bid);
+            wait_for("the bid to be re-placed", || {
+                placed(&venue, Buy).len() == round + 2
+            })
+            .await;
+            assert!(
+                !cancelled(&venue).contains(&filled),
+                "round {round}: cancelled {filled}, which had already filled"
+            );
+        }
+    };
+
+    // The engine is not Send, so it runs on this task until the script is done.
+    tokio::select! {
+        result = engine.run() => panic!("engine stopped: {result:?}"),
+        () = script => {}
+    }
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+
+use common::{Act, Expect, Harness, Step};
+
+fn book(bid: f64, ask: f64) -> Step {
+    Step::Book { bid, ask }
+}
+
+fn expect(actions: Vec<Act>) -> Step {
+    Step::Expect(Expect::Actions(actions))
+}
+
+/// Prices of the bids placed so far, in order.
+fn bids(harness: &Harness) -> Vec<f64> {
+    harness
+        .actions()
+        .iter()
+        .filter_map(|action| match action {
+            OrderAction::Place(order) if order.side == Buy => Some(order.price.as_f64()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn a_crossing_bid_is_repriced_at_once_until_the_attempts_run_out() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness
+        .run(&[
+            (0, book(93.00, 93.10)),
+            (0, expect(vec![Act::Place(Buy), Act::Place(Sell)])),
+            (10, Step::Accept(Sell)),
+            // No market event in between: each refusal is answered straight away.
+            (50, Step::RejectCrossing(Buy)),
+            (50, expect(vec![Act::Place(Buy)])),
+            (60, Step::RejectCrossing(Buy)),
+            (60, expect(vec![Act::Place(Buy)])),
+            (70, Step::RejectCrossing(Buy)),
+            (70, Step::Expect(Expect::Nothing)),
+            // The same target on the next books is held.
+            (1_000, book(93.00, 93.10)),
+            (2_000, book(93.00, 93.10)),
+            (2_000, Step::Expect(Expect::Nothing)),
+        ])
+        .await
+        .unwrap();
+
+    let placed = bids(&harness);
+    assert_eq!(placed.len(), 3, "{placed:?}");
+    assert!((placed[1] - 92.99).abs() < 1e-9, "{placed:?}");
+    assert!((placed[2] - 92.98).abs() < 1e-9, "{placed:?}");
+
+    // A target that moves is placed again, once the rejections' backoff is over.
+    harness.run(&[(5_000, book(92.90, 93.10))]).await.unwrap();
+    let placed = bids(&harness);
+    assert_eq!(placed.len(), 4, "{placed:?}");
+    assert!(placed[3] < 93.00, "{placed:?}");
+}
+
+#[tokio::test]
+async fn other_rejections_wait_for_the_next_market_event() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness
+        .run(&[
+            (0, book(93.00, 93.10)),
+            (0, expect(vec![Act::Place(Buy), Act::Place(Sell)])),
+            (10, Step::Accept(Sell)),
+            (50, Step::Reject(Buy)),
+            (50, Step::Expect(Expect::Nothing)),
+            (1_000, book(93.00, 93.10)),
+            (1_000, expect(vec![Act::Place(Buy)])),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(bids(&harness), [93.00, 93.00]);
+}
+
+#[tokio::test]
+async fn repricing_can_be_turned_off() {
+    let mut harness = Harness::new(|config| config.scheduling.reprice_attempts = 0)
+        .await
+        .unwrap();
+    harness
+        .run(&[
+            (0, book(93.00, 93.10)),
+            (0, expect(vec![Act::Place(Buy), Act::Place(Sell)])),
+            (10, Step::Accept(Sell)),
+            (50, Step::RejectCrossing(Buy)),
+            (50, Step::Expect(Expect::Nothing)),
+        ])
+        .await
+        .unwrap();
+}
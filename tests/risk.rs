@@ -0,0 +1,716 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use accumulator::clock::{Clock, SimClock};
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::{self, Buy, Sell};
+use accumulator::execution::types::{OrderManagerSnapshot, OrderSideSnapshot};
+use accumulator::market::market_state::{MarketFeed, MarketState};
+use accumulator::risk::checks::churn_throttle::ChurnThrottleCheck;
+use accumulator::risk::checks::exposure_limit::ExposureLimitCheck;
+use accumulator::risk::checks::kill_switch::KillSwitch;
+use accumulator::risk::checks::market_freshness::MarketFreshnessCheck;
+use accumulator::risk::checks::max_daily_loss::DailyLoss;
+use accumulator::risk::checks::portfolio_exposure::PortfolioExposure;
+use accumulator::risk::config::RiskConfig;
+use accumulator::risk::context::RiskContext;
+use accumulator::risk::decision::{RiskDecision, RiskReason};
+use accumulator::risk::engine::{RiskCheck, RiskEngine};
+use accumulator::types::fx::{ExposureCurrency, FxRates, StaticFxRates};
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::pnl::PnlTracker;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+use accumulator::types::quote::Quote;
+use accumulator::types::quote_target::QuoteTarget;
+
+use common::qty;
+
+/// Every default check for SOL/GBP, the shared portfolio limit included.
+fn engine(instrument: &Instrument, clock: &SimClock) -> RiskEngine {
+    RiskEngine::with_default_checks(
+        &RiskConfig::default().limits(instrument),
+        KillSwitch::new(false),
+        Some((PortfolioExposure::default(), 1_000.0)),
+        ExposureCurrency::default(),
+        DailyLoss::default(),
+        clock.shared(),
+    )
+}
+
+fn book(instrument: &Instrument, bid: f64, ask: f64, now: Instant) -> MarketState {
+    let mut market = MarketState::new();
+    market.on_market_event(
+        &MarketEvent::TopOfBook {
+            instrument: instrument.clone(),
+            best_bid: Price::new(bid),
+            best_ask: Price::new(ask),
+            bid_size: None,
+            ask_size: None,
+            timestamp_ms: 0,
+        },
+        now,
+    );
+    market
+}
+
+fn two_sided(bid: f64, ask: f64) -> QuoteTarget {
+    let quote = |price| {
+        Some(Quote {
+            price: Price::new(price),
+            quantity: qty(0.05),
+        })
+    };
+    QuoteTarget::new(quote(bid), quote(ask))
+}
+
+/// Reason codes of `decision`, or `approved` when it was approved whole.
+fn codes(decision: &RiskDecision) -> Vec<&'static str> {
+    match decision {
+        RiskDecision::Approved(approval) if approval.stripped.is_empty() => vec!["approved"],
+        RiskDecision::Approved(approval) => approval
+            .stripped
+            .iter()
+            .map(|reason| reason.code())
+            .collect(),
+        RiskDecision::Hold(hold) => hold.reasons.iter().map(|reason| reason.code()).collect(),
+        RiskDecision::Rejected(rejection) => rejection
+            .reasons
+            .iter()
+            .map(|reason| reason.code())
+            .collect(),
+    }
+}
+
+fn evaluate(
+    engine: &mut RiskEngine,
+    instrument: &Instrument,
+    market: &MarketState,
+    target: QuoteTarget,
+    inventory: Inventory,
+    now: Instant,
+) -> RiskDecision {
+    engine.evaluate(
+        &RiskContext {
+            instrument,
+            market_state: market,
+            target: &target,
+            inventory,
+            orders: &OrderManagerSnapshot::default(),
+            pnl: &PnlTracker::default(),
+            now,
+        },
+        target.clone(),
+    )
+}
+
+#[test]
+fn default_checks_decide_without_panicking_on_missing_data() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let clock = SimClock::from_timestamp_ms(1_704_283_200_000);
+    let mut engine = engine(&instrument, &clock);
+    let now = clock.now_instant();
+
+    for target in [QuoteTarget::none(), two_sided(93.00, 93.02)] {
+        let decision = evaluate(
+            &mut engine,
+            &instrument,
+            &MarketState::new(),
+            target,
+            Inventory::default(),
+            now,
+        );
+        assert!(
+            matches!(decision, RiskDecision::Rejected(_)),
+            "{decision:?}"
+        );
+        assert!(codes(&decision).contains(&"missing_market_data"));
+    }
+
+    // A book but nothing to quote and nothing held.
+    let decision = evaluate(
+        &mut engine,
+        &instrument,
+        &book(&instrument, 93.00, 93.10, now),
+        QuoteTarget::none(),
+        Inventory::default(),
+        now,
+    );
+    assert_eq!(codes(&decision), ["approved"]);
+}
+
+#[test]
+fn exposure_and_inventory_checks_run_in_the_default_stack() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let clock = SimClock::from_timestamp_ms(1_704_283_200_000);
+    let mut engine = engine(&instrument, &clock);
+    let now = clock.now_instant();
+    let market = book(&instrument, 93.00, 93.10, now);
+
+    let decision = evaluate(
+        &mut engine,
+        &instrument,
+        &market,
+        two_sided(93.00, 93.10),
+        Inventory::new(1.0, 500.0),
+        now,
+    );
+    assert_eq!(codes(&decision), ["approved"]);
+
+    // Filling the bid on 3 SOL would pass SOL/GBP's 200.00 of exposure, and there is no
+    // cash to bid with. Both reasons are the bid's, so the ask still goes out.
+    let decision = evaluate(
+        &mut engine,
+        &instrument,
+        &market,
+        two_sided(93.00, 93.10),
+        Inventory::new(3.0, 0.0),
+        now,
+    );
+    let RiskDecision::Approved(approval) = &decision else {
+        panic!("expected the ask approved, got {decision:?}");
+    };
+    assert!(approval.target.bid.is_none());
+    assert_eq!(
+        approval.target.ask.map(|quote| quote.price),
+        Some(Price::new(93.10))
+    );
+    assert_eq!(
+        codes(&decision),
+        ["exposure_limit", "insufficient_inventory"]
+    );
+}
+
+/// Holds the first evaluation it sees, as if the edge were too thin, then passes.
+struct HoldOnce(bool);
+
+impl RiskCheck for HoldOnce {
+    fn name(&self) -> &'static str {
+        "HoldOnce"
+    }
+
+    fn evaluate(&mut self, _context: &RiskContext) -> Result<(), Vec<RiskReason>> {
+        if std::mem::replace(&mut self.0, true) {
+            return Ok(());
+        }
+        Err(vec![RiskReason::InsufficientEdge {
+            half_spread: 0.0,
+            required: 0.01,
+            fee: 0.0,
+        }])
+    }
+}
+
+#[test]
+fn a_held_target_does_not_start_the_churn_window() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let clock = SimClock::from_timestamp_ms(1_704_283_200_000);
+    let mut engine = RiskEngine::new(vec![
+        Box::new(ChurnThrottleCheck::new(Duration::from_millis(800))),
+        Box::new(HoldOnce(false)),
+    ]);
+    let market = book(&instrument, 93.00, 93.10, clock.now_instant());
+    let mut decide = |target: QuoteTarget, now: Instant| {
+        evaluate(
+            &mut engine,
+            &instrument,
+            &market,
+            target,
+            Inventory::new(1.0, 500.0),
+            now,
+        )
+    };
+
+    let start = clock.now_instant();
+    let held = decide(two_sided(93.00, 93.10), start);
+    assert_eq!(codes(&held), ["insufficient_edge"]);
+
+    // The same target 100ms later is inside the window, but nothing was sent.
+    let retried = decide(two_sided(93.00, 93.10), start + Duration::from_millis(100));
+    assert_eq!(codes(&retried), ["approved"]);
+
+    // Once that target is placed, moving it again inside the window is throttled.
+    engine.commit(&two_sided(93.00, 93.10), start + Duration::from_millis(100));
+    let moved = evaluate(
+        &mut engine,
+        &instrument,
+        &market,
+        two_sided(93.01, 93.09),
+        Inventory::new(1.0, 500.0),
+        start + Duration::from_millis(200),
+    );
+    assert_eq!(codes(&moved), ["churn_throttle_bid", "churn_throttle_ask"]);
+}
+
+#[test]
+fn a_placed_price_can_move_again_once_the_churn_window_passes() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let clock = SimClock::from_timestamp_ms(1_704_283_200_000);
+    let mut engine = RiskEngine::new(vec![Box::new(ChurnThrottleCheck::new(
+        Duration::from_millis(800),
+    ))]);
+    let market = book(&instrument, 93.00, 93.10, clock.now_instant());
+    let decide = |engine: &mut RiskEngine, target: QuoteTarget| {
+        evaluate(
+            engine,
+            &instrument,
+            &market,
+            target,
+            Inventory::new(1.0, 500.0),
+            clock.now_instant(),
+        )
+    };
+
+    let decision = decide(&mut engine, two_sided(93.00, 93.10));
+    assert_eq!(codes(&decision), ["approved"]);
+    engine.commit(&two_sided(93.00, 93.10), clock.now_instant());
+
+    // Inside the window only the side that moved is throttled; an unchanged price is not.
+    clock.advance(Duration::from_millis(799));
+    let decision = decide(&mut engine, two_sided(93.01, 93.10));
+    assert_eq!(codes(&decision), ["churn_throttle_bid"]);
+    let decision = decide(&mut engine, two_sided(93.00, 93.10));
+    assert_eq!(codes(&decision), ["approved"]);
+
+    clock.advance(Duration::from_millis(1));
+    let decision = decide(&mut engine, two_sided(93.01, 93.09));
+    assert_eq!(codes(&decision), ["approved"]);
+}
+
+fn trade(instrument: &Instrument, market: &mut MarketState, price: f64, now: Instant) {
+    market.on_market_event(
+        &MarketEvent::Trade {
+            instrument: instrument.clone(),
+            price: Price::new(price),
+            quantity: 0.1,
+            timestamp_ms: 0,
+        },
+        now,
+    );
+}
+
+#[test]
+fn trades_alone_do_not_keep_a_frozen_book_fresh() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let clock = SimClock::from_timestamp_ms(0);
+    let mut engine = engine(&instrument, &clock);
+    let start = clock.now_instant();
+    let mut market = book(&instrument, 93.00, 93.10, start);
+
+    // Trades keep printing every second while the book stays put past its 3s limit.
+    for secs in 1..=4 {
+        trade(
+            &instrument,
+            &mut market,
+            93.05,
+            start + Duration::from_secs(secs),
+        );
+    }
+    let now = start + Duration::from_secs(4);
+    assert_eq!(market.trade_age(now), Some(Duration::ZERO));
+    assert_eq!(market.book_age(now), Some(Duration::from_secs(4)));
+
+    let decision = evaluate(
+        &mut engine,
+        &instrument,
+        &market,
+        two_sided(93.00, 93.10),
+        Inventory::new(1.0, 500.0),
+        now,
+    );
+    let RiskDecision::Rejected(rejection) = &decision else {
+        panic!("expected a rejection, got {decision:?}");
+    };
+    assert!(
+        matches!(
+            &rejection.reasons[..],
+            [RiskReason::MarketDataStale {
+                feed: MarketFeed::Book,
+                age_ms: Some(4_000),
+            }]
+        ),
+        "{:?}",
+        rejection.reasons
+    );
+}
+
+#[test]
+fn a_trade_feed_with_no_book_is_stale_from_the_start() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let clock = SimClock::from_timestamp_ms(0);
+    let mut check = MarketFreshnessCheck::new(Duration::from_secs(3), None);
+    let now = clock.now_instant();
+    let mut market = MarketState::new();
+    trade(&instrument, &mut market, 93.05, now);
+
+    let target = two_sided(93.00, 93.10);
+    let reasons = check
+        .evaluate(&RiskContext {
+            instrument: &instrument,
+            market_state: &market,
+            target: &target,
+            inventory: Inventory::default(),
+            orders: &OrderManagerSnapshot::default(),
+            pnl: &PnlTracker::default(),
+            now,
+        })
+        .unwrap_err();
+    assert!(
+        matches!(
+            &reasons[..],
+            [RiskReason::MarketDataStale {
+                feed: MarketFeed::Book,
+                age_ms: None,
+            }]
+        ),
+        "{reasons:?}"
+    );
+}
+
+#[test]
+fn stale_trades_hold_without_cancelling() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let clock = SimClock::from_timestamp_ms(0);
+    let config = RiskConfig {
+        trade_max_age_ms: Some(10_000),
+        ..RiskConfig::default()
+    };
+    let mut engine = RiskEngine::with_default_checks(
+        &config.limits(&instrument),
+        KillSwitch::new(false),
+        None,
+        ExposureCurrency::default(),
+        DailyLoss::default(),
+        clock.shared(),
+    );
+    let start = clock.now_instant();
+    let mut market = MarketState::new();
+    trade(&instrument, &mut market, 93.05, start);
+
+    // The book is fresh; the last trade is 11s old.
+    let now = start + Duration::from_secs(11);
+    market.on_market_event(
+        &MarketEvent::TopOfBook {
+            instrument: instrument.clone(),
+            best_bid: Price::new(93.00),
+            best_ask: Price::new(93.10),
+            bid_size: None,
+            ask_size: None,
+            timestamp_ms: 0,
+        },
+        now,
+    );
+
+    let decision = evaluate(
+        &mut engine,
+        &instrument,
+        &market,
+        two_sided(93.00, 93.10),
+        Inventory::new(1.0, 500.0),
+        now,
+    );
+    let RiskDecision::Hold(hold) = &decision else {
+        panic!("expected a hold, got {decision:?}");
+    };
+    assert!(
+        matches!(
+            &hold.reasons[..],
+            [RiskReason::MarketDataStale {
+                feed: MarketFeed::Trades,
+                age_ms: Some(11_000),
+            }]
+        ),
+        "{:?}",
+        hold.reasons
+    );
+}
+
+/// Objects with the same reasons to every target.
+struct Always(Vec<RiskReason>);
+
+impl RiskCheck for Always {
+    fn name(&self) -> &'static str {
+        "Always"
+    }
+
+    fn evaluate(&mut self, _context: &RiskContext) -> Result<(), Vec<RiskReason>> {
+        Err(self.0.clone())
+    }
+}
+
+fn exposure(side: Side) -> RiskReason {
+    RiskReason::ExposureLimit {
+        side,
+        exposure_quote: 250.0,
+        max_exposure_in_quote: 200.0,
+        inventory: 2.5,
+        resting: Quantity::ZERO,
+        proposed: qty(0.2),
+    }
+}
+
+#[test]
+fn only_reasons_confined_to_one_side_let_the_other_side_through() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let now = Instant::now();
+    let market = book(&instrument, 93.00, 93.10, now);
+    let decide = |reasons: Vec<RiskReason>, target: QuoteTarget| {
+        let mut engine = RiskEngine::new(vec![Box::new(Always(reasons))]);
+        evaluate(
+            &mut engine,
+            &instrument,
+            &market,
+            target,
+            Inventory::new(1.0, 500.0),
+            now,
+        )
+    };
+
+    let decision = decide(
+        vec![RiskReason::ChurnThrottleAsk, exposure(Sell)],
+        two_sided(93.00, 93.10),
+    );
+    let RiskDecision::Approved(approval) = &decision else {
+        panic!("expected the bid approved, got {decision:?}");
+    };
+    assert!(approval.target.bid.is_some() && approval.target.ask.is_none());
+    // The exposure limit cancels the resting ask, throttled or not.
+    assert!(!approval.target.is_held(Sell));
+    assert_eq!(codes(&decision), ["churn_throttle_ask", "exposure_limit"]);
+
+    // A throttle alone keeps the resting ask rather than cancelling it.
+    let decision = decide(vec![RiskReason::ChurnThrottleAsk], two_sided(93.00, 93.10));
+    let RiskDecision::Approved(approval) = &decision else {
+        panic!("expected the bid approved, got {decision:?}");
+    };
+    assert!(approval.target.ask.is_none() && approval.target.is_held(Sell));
+    assert!(approval.target.bid.is_some() && !approval.target.is_held(Buy));
+
+    // Both sides objected to leaves nothing to quote.
+    let decision = decide(
+        vec![RiskReason::ChurnThrottleBid, exposure(Sell)],
+        two_sided(93.00, 93.10),
+    );
+    assert!(matches!(decision, RiskDecision::Hold(_)), "{decision:?}");
+
+    // As does stripping the only side the strategy quoted.
+    let bid_only = QuoteTarget {
+        ask: None,
+        ..two_sided(93.00, 93.10)
+    };
+    let decision = decide(vec![exposure(Buy)], bid_only);
+    assert!(matches!(decision, RiskDecision::Hold(_)), "{decision:?}");
+
+    // A reason about the whole target holds both sides.
+    let thin = RiskReason::InsufficientEdge {
+        half_spread: 0.0,
+        required: 0.01,
+        fee: 0.0,
+    };
+    let decision = decide(
+        vec![RiskReason::ChurnThrottleBid, thin],
+        two_sided(93.00, 93.10),
+    );
+    assert!(matches!(decision, RiskDecision::Hold(_)), "{decision:?}");
+
+    // And a hard rule cancels everything, however the rest are scoped.
+    let decision = decide(
+        vec![exposure(Buy), RiskReason::KillSwitchEnabled],
+        two_sided(93.00, 93.10),
+    );
+    let RiskDecision::Rejected(rejection) = &decision else {
+        panic!("expected a rejection, got {decision:?}");
+    };
+    assert!(matches!(
+        rejection.required_actions[..],
+        [OrderAction::CancelAll]
+    ));
+}
+
+/// Orders working at each level of one side, `None` for an empty level.
+fn working(quantities: &[Option<f64>]) -> Vec<OrderSideSnapshot> {
+    quantities
+        .iter()
+        .map(|quantity| OrderSideSnapshot {
+            state: if quantity.is_some() {
+                "live"
+            } else {
+                "no_order"
+            },
+            order_id: None,
+            quote: quantity.map(|quantity| Quote {
+                price: Price::new(92.90),
+                quantity: qty(quantity),
+            }),
+            age_ms: None,
+        })
+        .collect()
+}
+
+/// Exposure breakdowns, as `(side, inventory, resting, proposed)`.
+fn breakdowns(reasons: &[RiskReason]) -> Vec<(Side, f64, f64, f64)> {
+    reasons
+        .iter()
+        .map(|reason| match reason {
+            RiskReason::ExposureLimit {
+                side,
+                inventory,
+                resting,
+                proposed,
+                ..
+            } => (*side, *inventory, resting.as_f64(), proposed.as_f64()),
+            reason => panic!("unexpected {reason:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn exposure_counts_resting_orders_the_target_does_not_replace() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let now = Instant::now();
+    let market = book(&instrument, 93.00, 93.10, now);
+    let mut check = ExposureLimitCheck::new(200.0);
+    let mut evaluate = |target: QuoteTarget, orders: OrderManagerSnapshot| {
+        check.evaluate(&RiskContext {
+            instrument: &instrument,
+            market_state: &market,
+            target: &target,
+            // 186.10 of exposure at the mid.
+            inventory: Inventory::new(2.0, 500.0),
+            orders: &orders,
+            pnl: &PnlTracker::default(),
+            now,
+        })
+    };
+    let bid = |quantity| QuoteTarget {
+        bid: Some(Quote {
+            price: Price::new(93.00),
+            quantity: qty(quantity),
+        }),
+        ..two_sided(93.00, 93.10)
+    };
+    let bids = |quantities: &[Option<f64>]| OrderManagerSnapshot {
+        bids: working(quantities),
+        asks: Vec::new(),
+    };
+
+    // A new place counts in full.
+    assert!(evaluate(bid(0.05), OrderManagerSnapshot::default()).is_ok());
+    let reasons = evaluate(bid(0.2), OrderManagerSnapshot::default()).unwrap_err();
+    assert_eq!(breakdowns(&reasons), [(Buy, 2.0, 0.0, 0.2)]);
+    assert!(
+        matches!(
+            &reasons[..],
+            [RiskReason::ExposureLimit { exposure_quote, .. }]
+                if (exposure_quote - 204.71).abs() < 1e-9
+        ),
+        "{reasons:?}"
+    );
+
+    // A bigger bid the target replaces at its level is not counted on top.
+    assert!(evaluate(bid(0.05), bids(&[Some(0.2)])).is_ok());
+
+    // One at a level the target no longer quotes is, as it may fill before its cancel.
+    let reasons = evaluate(bid(0.05), bids(&[Some(0.05), Some(0.2)])).unwrap_err();
+    assert_eq!(breakdowns(&reasons), [(Buy, 2.0, 0.2, 0.05)]);
+    let reasons = evaluate(
+        QuoteTarget {
+            bid: None,
+            ..bid(0.05)
+        },
+        bids(&[Some(0.25)]),
+    )
+    .unwrap_err();
+    assert_eq!(breakdowns(&reasons), [(Buy, 2.0, 0.25, 0.0)]);
+
+    // Each side is projected on its own.
+    let both = OrderManagerSnapshot {
+        bids: working(&[None, Some(0.2)]),
+        asks: working(&[Some(1.0), Some(4.2)]),
+    };
+    let reasons = evaluate(bid(0.05), both).unwrap_err();
+    assert_eq!(
+        breakdowns(&reasons),
+        [(Buy, 2.0, 0.2, 0.05), (Sell, 2.0, 4.2, 0.05)]
+    );
+}
+
+/// GBP into EUR at a fixed rate; nothing else.
+struct GbpEur(f64);
+
+impl FxRates for GbpEur {
+    fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        (from == "GBP" && to == "EUR").then_some(self.0)
+    }
+}
+
+#[test]
+fn exposure_is_measured_in_the_exposure_currency() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let now = Instant::now();
+    let market = book(&instrument, 93.00, 93.10, now);
+    let target = two_sided(93.00, 93.10);
+    let orders = OrderManagerSnapshot::default();
+    let pnl = PnlTracker::default();
+    let context = RiskContext {
+        instrument: &instrument,
+        market_state: &market,
+        target: &target,
+        // 186.10 GBP of exposure at the mid.
+        inventory: Inventory::new(2.0, 500.0),
+        orders: &orders,
+        pnl: &pnl,
+        now,
+    };
+
+    let mut gbp = ExposureLimitCheck::new(200.0);
+    assert!(gbp.evaluate(&context).is_ok());
+
+    let mut eur = ExposureLimitCheck::new(200.0)
+        .with_exposure_currency(ExposureCurrency::new("EUR", Arc::new(GbpEur(1.2))));
+    let reasons = eur.evaluate(&context).unwrap_err();
+    assert!(
+        matches!(
+            &reasons[..],
+            [RiskReason::ExposureLimit { side: Buy, exposure_quote, .. }]
+                if (exposure_quote - 2.05 * 93.05 * 1.2).abs() < 1e-9
+        ),
+        "{reasons:?}"
+    );
+
+    // No rate into USD: the exposure cannot be judged, so nothing is quoted.
+    let mut usd = ExposureLimitCheck::new(200.0)
+        .with_exposure_currency(ExposureCurrency::new("USD", Arc::new(GbpEur(1.2))));
+    let reasons = usd.evaluate(&context).unwrap_err();
+    assert!(
+        matches!(
+            &reasons[..],
+            [RiskReason::MissingFxRate { from, to }] if from == "GBP" && to == "USD"
+        ),
+        "{reasons:?}"
+    );
+    assert!(reasons[0].is_hard());
+}
+
+#[test]
+fn static_rates_answer_either_way_round() {
+    let rates = StaticFxRates::new(&[("EUR/GBP".to_string(), 0.8)].into());
+
+    assert_eq!(rates.rate("EUR", "GBP"), Some(0.8));
+    assert_eq!(rates.rate("GBP", "EUR"), Some(1.25));
+    assert_eq!(rates.rate("GBP", "GBP"), Some(1.0));
+    assert_eq!(rates.rate("USD", "GBP"), None);
+
+    let config = RiskConfig {
+        fx_rates: [("EURGBP".to_string(), 0.8)].into(),
+        ..RiskConfig::default()
+    };
+    assert_eq!(
+        config.validate("risk").unwrap_err().to_string(),
+        "risk.fx_rates.EURGBP: must be keyed FROM/TO"
+    );
+}
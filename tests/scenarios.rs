@@ -0,0 +1,599 @@
+mod common;
+
+use accumulator::config::app_config::AppConfig;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::execution::order_action::{Order, OrderType, Side};
+use accumulator::types::instrument::InstrumentConfig;
+use accumulator::types::price::Price;
+use accumulator::types::trading_hours::{TradingHours, TradingWindow};
+
+use common::{Act, Expect, Harness, Step, qty};
+
+fn book(bid: f64, ask: f64) -> Step {
+    Step::Book { bid, ask }
+}
+
+fn expect(actions: &[Act]) -> Step {
+    Step::Expect(Expect::Actions(actions.to_vec()))
+}
+
+fn working(bid: bool, ask: bool) -> Step {
+    Step::Expect(Expect::Working { bid, ask })
+}
+
+#[tokio::test]
+async fn simple_mm_places_fills_and_requotes() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+
+    harness
+        .run(&[
+            (0, book(93.00, 93.10)),
+            (1_000, book(93.00, 93.10)),
+            (1_000, expect(&[Act::Place(Buy), Act::Place(Sell)])),
+            (1_010, Step::Accept(Buy)),
+            (1_010, Step::Accept(Sell)),
+            (1_010, working(true, true)),
+            (2_000, Step::Fill(Buy)),
+            (2_000, working(false, true)),
+            (3_000, book(93.00, 93.10)),
+            (3_000, expect(&[Act::Place(Buy)])),
+            (3_000, working(true, true)),
+        ])
+        .await
+        .unwrap();
+}
+
+/// Two quotes resting at the touch of a 10-tick book.
+fn quoted() -> Vec<(u64, Step)> {
+    vec![
+        (0, book(93.00, 93.10)),
+        (1_000, book(93.00, 93.10)),
+        (1_000, expect(&[Act::Place(Buy), Act::Place(Sell)])),
+        (1_010, Step::Accept(Buy)),
+        (1_010, Step::Accept(Sell)),
+        (1_010, working(true, true)),
+    ]
+}
+
+#[tokio::test]
+async fn cancels_a_moved_quote_while_the_other_side_is_placing() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (2_000, Step::Fill(Buy)),
+            (3_000, book(93.00, 93.10)),
+            (3_000, expect(&[Act::Place(Buy)])),
+            // The market gaps while the new bid waits on the venue: the ask is cancelled
+            // but not replaced, and the bid is left to its place.
+            (3_300, book(93.05, 93.15)),
+            (3_300, expect(&[Act::Cancel(Sell)])),
+            (3_300, working(true, false)),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn replaces_quotes_only_after_a_three_tick_move() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (2_000, book(93.02, 93.12)),
+            (2_000, Step::Expect(Expect::Nothing)),
+            (3_000, book(93.05, 93.15)),
+            (
+                3_000,
+                expect(&[
+                    Act::Cancel(Buy),
+                    Act::Place(Buy),
+                    Act::Cancel(Sell),
+                    Act::Place(Sell),
+                ]),
+            ),
+            (3_010, Step::Accept(Buy)),
+            (3_010, Step::Accept(Sell)),
+            (3_010, working(true, true)),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn amends_quotes_within_the_bound_and_replaces_beyond_it() {
+    let mut harness = Harness::new(|config| config.scheduling.amend_max_ticks = Some(5))
+        .await
+        .unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (2_000, book(93.03, 93.13)),
+            (2_000, expect(&[Act::Amend(Buy), Act::Amend(Sell)])),
+            (2_000, working(true, true)),
+            (3_000, book(93.10, 93.20)),
+            (
+                3_000,
+                expect(&[
+                    Act::Cancel(Buy),
+                    Act::Place(Buy),
+                    Act::Cancel(Sell),
+                    Act::Place(Sell),
+                ]),
+            ),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn risk_rejection_cancels_everything_until_cleared() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (2_000, Step::KillSwitch(true)),
+            (2_000, book(93.00, 93.10)),
+            (2_000, expect(&[Act::CancelAll])),
+            (2_000, working(false, false)),
+            (3_000, Step::KillSwitch(false)),
+            (3_000, book(93.00, 93.10)),
+            (3_000, expect(&[Act::Place(Buy), Act::Place(Sell)])),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn holds_through_a_stale_market_and_recovers() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (3_500, Step::Expect(Expect::MarketStale(false))),
+            (4_500, Step::Expect(Expect::MarketStale(true))),
+            (4_500, Step::Expect(Expect::Nothing)),
+            (5_000, book(93.05, 93.15)),
+            (5_000, Step::Expect(Expect::MarketStale(false))),
+            (
+                5_000,
+                expect(&[
+                    Act::Cancel(Buy),
+                    Act::Place(Buy),
+                    Act::Cancel(Sell),
+                    Act::Place(Sell),
+                ]),
+            ),
+        ])
+        .await
+        .unwrap();
+}
+
+/// SOL/GBP trades 09:00 to 17:00 UTC; the scripts start at noon.
+const CLOSE_MS: u64 = 5 * 60 * 60 * 1_000;
+
+#[tokio::test]
+async fn the_timer_pulls_quotes_when_trading_hours_end_in_a_quiet_market() {
+    // A book hours old still counts as fresh.
+    let mut harness = Harness::new(|config| config.risk.market_max_age_ms = 2 * CLOSE_MS)
+        .await
+        .unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            // Within an interval of the last book: the timer leaves it to the market.
+            (1_500, Step::Timer),
+            (1_500, Step::Expect(Expect::Nothing)),
+            // Quiet, but the same book wants the same quotes.
+            (2_200, Step::Timer),
+            (2_200, Step::Expect(Expect::Nothing)),
+            (CLOSE_MS - 1_000, Step::Timer),
+            (CLOSE_MS - 1_000, Step::Expect(Expect::Nothing)),
+            (CLOSE_MS, Step::Timer),
+            (CLOSE_MS, expect(&[Act::Cancel(Buy), Act::Cancel(Sell)])),
+            (CLOSE_MS + 1_000, Step::Timer),
+            (CLOSE_MS + 1_000, Step::Expect(Expect::Nothing)),
+            (CLOSE_MS + 1_500, book(93.00, 93.10)),
+            (CLOSE_MS + 1_500, Step::Expect(Expect::Nothing)),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn quotes_are_pulled_and_held_within_the_close_buffer() {
+    let mut harness = Harness::new(|config| {
+        config.risk.market_max_age_ms = 2 * CLOSE_MS;
+        let mut rules = config.instruments[0].load().unwrap().trading_rules();
+        if let Some(hours) = &mut rules.trading_hours {
+            hours.close_buffer_mins = 10;
+        }
+        config.instruments[0].trading_rules = Some(rules);
+    })
+    .await
+    .unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    let buffer_ms = 10 * 60 * 1_000;
+    harness
+        .run(&[
+            (CLOSE_MS - buffer_ms - 1_000, Step::Timer),
+            (CLOSE_MS - buffer_ms - 1_000, Step::Expect(Expect::Nothing)),
+            // Ten minutes before 17:00 nothing is left resting over the close.
+            (CLOSE_MS - buffer_ms, Step::Timer),
+            (
+                CLOSE_MS - buffer_ms,
+                expect(&[Act::Cancel(Buy), Act::Cancel(Sell)]),
+            ),
+            (CLOSE_MS - buffer_ms, working(false, false)),
+            (CLOSE_MS - buffer_ms + 1_000, book(93.00, 93.10)),
+            (CLOSE_MS - buffer_ms + 1_000, Step::Expect(Expect::Nothing)),
+        ])
+        .await
+        .unwrap();
+
+    let summary = harness.summary().await;
+    assert_eq!(summary.skips.get("closing_soon"), Some(&1));
+}
+
+#[tokio::test]
+async fn the_timer_cancels_quotes_once_the_book_goes_stale() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (3_500, Step::Timer),
+            (3_500, Step::Expect(Expect::Nothing)),
+            (4_500, Step::Timer),
+            (4_500, expect(&[Act::CancelAll])),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn a_lost_feed_cancels_quotes_until_books_return() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            // Stale at once, not a second and a half after the last book.
+            (1_500, Step::FeedLost),
+            (1_500, Step::Expect(Expect::MarketStale(true))),
+            (1_500, expect(&[Act::Cancel(Buy), Act::Cancel(Sell)])),
+            (1_500, working(false, false)),
+            (2_000, Step::FeedRestored),
+            (2_000, Step::Expect(Expect::MarketStale(true))),
+            (2_000, Step::Expect(Expect::Nothing)),
+            (2_500, book(93.00, 93.10)),
+            (2_500, expect(&[Act::Place(Buy), Act::Place(Sell)])),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn a_held_quote_does_not_consume_the_churn_window() {
+    let mut harness = Harness::new(|config| config.risk.min_half_spread = Some(0.02))
+        .await
+        .unwrap();
+
+    harness
+        .run(&[
+            // Too narrow to quote: MinEdge holds the whole evaluation.
+            (0, book(93.00, 93.01)),
+            (0, Step::Expect(Expect::Nothing)),
+            // Well inside the 800ms churn window of the held bid, which was never sent.
+            (200, book(93.05, 93.15)),
+            (200, expect(&[Act::Place(Buy), Act::Place(Sell)])),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn ignores_orders_placed_outside_the_engine() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (2_000, Step::ForeignAccept(Buy)),
+            (2_000, working(true, true)),
+            // Our bid is still the one tracked, so its fill frees the side.
+            (2_100, Step::Fill(Buy)),
+            (2_100, working(false, true)),
+            (2_200, Step::ForeignFill(Buy)),
+            (2_200, working(false, true)),
+            (3_000, book(93.00, 93.10)),
+            (3_000, expect(&[Act::Place(Buy)])),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(harness.summary().await.external_fills, 1);
+}
+
+fn partial(quantity: f64, cum_quantity: f64) -> Step {
+    Step::PartialFill {
+        side: Buy,
+        quantity,
+        cum_quantity,
+    }
+}
+
+#[tokio::test]
+async fn catches_up_on_partial_fills_it_missed() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    // The bid is for 0.05; the report of the 0.02 fill in between is lost.
+    harness
+        .run(&[
+            (2_000, Step::Expect(Expect::Remaining(Buy, 0.05))),
+            (2_000, partial(0.01, 0.01)),
+            (2_000, Step::Expect(Expect::Remaining(Buy, 0.04))),
+            (2_200, partial(0.01, 0.04)),
+            (2_200, Step::Expect(Expect::Remaining(Buy, 0.01))),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn ignores_duplicate_and_regressed_partial_fills() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (2_000, partial(0.02, 0.02)),
+            // Redelivered after a reconnect, then an older report arriving late.
+            (2_100, partial(0.02, 0.02)),
+            (2_100, partial(0.01, 0.01)),
+            (2_100, Step::Expect(Expect::Remaining(Buy, 0.03))),
+            (2_200, partial(0.01, 0.03)),
+            (2_200, Step::Expect(Expect::Remaining(Buy, 0.02))),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn resyncs_after_the_venue_drops_all_orders() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (2_000, Step::VenueCancelAll),
+            (2_000, working(false, false)),
+            (2_000, Step::Expect(Expect::Nothing)),
+            (3_000, book(93.00, 93.10)),
+            (3_000, expect(&[Act::Place(Buy), Act::Place(Sell)])),
+            (3_010, Step::Accept(Buy)),
+            (3_010, Step::Accept(Sell)),
+            (3_010, working(true, true)),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn requotes_once_reconciliation_finds_an_order_cancelled_by_hand() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    harness
+        .run(&[
+            (2_000, Step::SilentCancel(Buy)),
+            (2_000, book(93.00, 93.10)),
+            (2_000, working(true, true)),
+            (2_000, Step::Expect(Expect::Nothing)),
+            (2_500, Step::Reconcile),
+            (2_500, working(false, true)),
+            (2_500, Step::Expect(Expect::Nothing)),
+            (3_000, book(93.00, 93.10)),
+            (3_000, expect(&[Act::Place(Buy)])),
+        ])
+        .await
+        .unwrap();
+}
+
+fn stray(order_id: &str, side: Side) -> Order {
+    Order {
+        order_id: order_id.to_string(),
+        instrument: InstrumentConfig::default().load().unwrap(),
+        side,
+        price: Price::new(92.50),
+        quantity: qty(0.05),
+        order_type: OrderType::POST_ONLY,
+        cycle_id: None,
+    }
+}
+
+#[tokio::test]
+async fn reconciliation_cancels_only_its_own_untracked_orders() {
+    let mut harness = Harness::new(|config| config.venue.order_id_prefix = Some("3f9c".into()))
+        .await
+        .unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    // Each takes the place of the engine's order on its side of the mock venue.
+    harness.rest(stray("3f9c-SOLGBP-b7", Buy));
+    harness.rest(stray("manual-sell", Sell));
+
+    harness
+        .run(&[
+            (2_000, Step::Reconcile),
+            (2_000, expect(&[Act::Cancel(Buy)])),
+            (2_000, working(false, false)),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn reconciliation_leaves_another_deployments_orders_alone() {
+    let mut harness = Harness::new(|config| config.venue.order_id_prefix = Some("3f9c".into()))
+        .await
+        .unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    // Placed by a second deployment on the account, under its own prefix.
+    harness.rest(stray("mm2-SOLGBP-b7", Buy));
+
+    harness
+        .run(&[
+            (2_000, Step::Reconcile),
+            (2_000, Step::Expect(Expect::Nothing)),
+            (2_000, working(false, true)),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn a_day_of_losses_stops_quoting_until_the_next_utc_day() {
+    let mut harness = Harness::new(|config| config.risk.max_daily_loss_in_quote = Some(0.05))
+        .await
+        .unwrap();
+    harness.run(&quoted()).await.unwrap();
+
+    // Noon the next day, inside the instrument's trading hours.
+    const NEXT_DAY: u64 = 24 * 60 * 60 * 1_000;
+
+    // Bought at 93.00, then sold into a market two pounds lower: about 0.095 lost.
+    harness
+        .run(&[
+            (2_000, Step::Fill(Buy)),
+            (3_000, book(91.00, 91.10)),
+            (3_010, Step::Accept(Sell)),
+            (3_500, Step::Fill(Sell)),
+            (3_500, working(true, false)),
+            (
+                3_500,
+                expect(&[Act::Place(Buy), Act::Cancel(Sell), Act::Place(Sell)]),
+            ),
+            (3_510, Step::Accept(Buy)),
+            (4_000, book(91.00, 91.10)),
+            (4_000, expect(&[Act::CancelAll])),
+            (4_000, working(false, false)),
+            (5_000, book(91.00, 91.10)),
+            (5_000, expect(&[Act::CancelAll])),
+            (NEXT_DAY, book(91.00, 91.10)),
+            (NEXT_DAY, expect(&[Act::Place(Buy), Act::Place(Sell)])),
+        ])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn a_loss_just_after_midnight_counts_against_the_new_day() {
+    // Trading round the clock, so only the loss limit pulls the quotes after midnight.
+    let mut harness = Harness::new(|config| {
+        config.risk.max_daily_loss_in_quote = Some(0.05);
+        let mut rules = config.instruments[0].load().unwrap().trading_rules();
+        rules.trading_hours = Some(TradingHours {
+            windows: vec![TradingWindow {
+                start: "00:00".parse().unwrap(),
+                end: "24:00".parse().unwrap(),
+            }],
+            ..TradingHours::default()
+        });
+        config.instruments[0].trading_rules = Some(rules);
+    })
+    .await
+    .unwrap();
+
+    // The harness starts at noon UTC.
+    const MIDNIGHT: u64 = 12 * 60 * 60 * 1_000;
+
+    // Bought at 93.00 and re-quoted into a market two pounds lower before midnight; the
+    // ask fills just after it, ahead of any market data of the new day.
+    harness
+        .run(&[
+            (MIDNIGHT - 5_000, book(93.00, 93.10)),
+            (MIDNIGHT - 4_000, book(93.00, 93.10)),
+            (
+                MIDNIGHT - 4_000,
+                expect(&[Act::Place(Buy), Act::Place(Sell)]),
+            ),
+            (MIDNIGHT - 3_990, Step::Accept(Buy)),
+            (MIDNIGHT - 3_990, Step::Accept(Sell)),
+            (MIDNIGHT - 3_000, Step::Fill(Buy)),
+            (MIDNIGHT - 2_000, book(91.00, 91.10)),
+            (
+                MIDNIGHT - 2_000,
+                expect(&[Act::Place(Buy), Act::Cancel(Sell), Act::Place(Sell)]),
+            ),
+            (MIDNIGHT - 1_990, Step::Accept(Buy)),
+            (MIDNIGHT - 1_990, Step::Accept(Sell)),
+            (MIDNIGHT + 1, Step::Fill(Sell)),
+        ])
+        .await
+        .unwrap();
+    let day = harness.daily_loss().unwrap();
+    assert_eq!(day.day.to_string(), "2024-01-04");
+    assert_eq!(day.open, 0.0);
+
+    harness
+        .run(&[
+            (MIDNIGHT + 500, book(91.00, 91.10)),
+            (MIDNIGHT + 500, expect(&[Act::CancelAll])),
+        ])
+        .await
+        .unwrap();
+    assert!(harness.daily_loss().unwrap().tripped.is_some());
+}
+
+#[tokio::test]
+async fn a_restart_after_a_day_of_losses_keeps_the_limit_tripped() {
+    let configure = |config: &mut AppConfig| config.risk.max_daily_loss_in_quote = Some(0.05);
+    let mut harness = Harness::new(configure).await.unwrap();
+    harness.run(&quoted()).await.unwrap();
+    harness
+        .run(&[
+            (2_000, Step::Fill(Buy)),
+            (3_000, book(91.00, 91.10)),
+            (3_010, Step::Accept(Sell)),
+            (3_500, Step::Fill(Sell)),
+            (
+                3_500,
+                expect(&[Act::Place(Buy), Act::Cancel(Sell), Act::Place(Sell)]),
+            ),
+            (3_510, Step::Accept(Buy)),
+            (4_000, book(91.00, 91.10)),
+            (4_000, expect(&[Act::CancelAll])),
+        ])
+        .await
+        .unwrap();
+    let saved = harness.daily_loss().unwrap();
+    assert!(saved.tripped.is_some(), "{saved:?}");
+
+    // The new run carries on with the realized PnL and the day's loss.
+    let mut restarted = Harness::new(configure).await.unwrap();
+    restarted.restore_pnl(harness.pnl_state());
+    restarted.restore_daily_loss(saved);
+
+    const NEXT_DAY: u64 = 24 * 60 * 60 * 1_000;
+    restarted
+        .run(&[
+            (0, book(91.00, 91.10)),
+            (0, expect(&[Act::CancelAll])),
+            (1_000, book(91.00, 91.10)),
+            (1_000, expect(&[Act::CancelAll])),
+            (NEXT_DAY, book(91.00, 91.10)),
+            (NEXT_DAY, expect(&[Act::Place(Buy), Act::Place(Sell)])),
+        ])
+        .await
+        .unwrap();
+}
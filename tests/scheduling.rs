@@ -0,0 +1,571 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+use accumulator::clock::{Clock, SimClock};
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::Side::Buy;
+use accumulator::execution::order_ids::OrderIds;
+use accumulator::execution::order_manager::OrderManager;
+use accumulator::execution::order_report::{OrderReport, RejectKind};
+use accumulator::market::market_state::MarketState;
+use accumulator::scenario::scenario::Scenario;
+use accumulator::scenario::strategies::StrategyKind;
+use accumulator::scheduling::policies::rejection_backoff_policy::{
+    RejectionBackoffConfig, RejectionBackoffPolicy,
+};
+use accumulator::scheduling::policies::trading_hours_policy::TradingHoursPolicy;
+use accumulator::scheduling::policies::venue_latency_policy::{
+    VenueLatencyConfig, VenueLatencyPolicy,
+};
+use accumulator::scheduling::policies::warmup_policy::{WarmupConfig, WarmupPolicy};
+use accumulator::scheduling::schedule_context::{ScheduleContext, Trigger};
+use accumulator::scheduling::schedule_policy::SchedulePolicy;
+use accumulator::scheduling::types::SkipReason;
+use accumulator::signals::config::SignalsConfig;
+use accumulator::signals::signal_state::SignalState;
+use accumulator::telemetry::ack_latency::AckLatencyTracker;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+use accumulator::types::trading_hours::TradingHours;
+
+use common::qty;
+
+fn signals() -> SignalState {
+    Scenario::signals(StrategyKind::SimpleMarketMaker, &SignalsConfig::default())
+}
+
+/// A [`RejectionBackoffPolicy`] fed reports for SOL/GBP, on a clock the test moves.
+struct Backoff {
+    policy: RejectionBackoffPolicy,
+    reports: broadcast::Sender<OrderReport>,
+    clock: SimClock,
+    instrument: Instrument,
+    market: MarketState,
+    signals: SignalState,
+    orders: OrderManager,
+}
+
+impl Backoff {
+    fn new() -> Self {
+        let clock = SimClock::from_timestamp_ms(1_704_283_200_000);
+        let instrument = InstrumentConfig::default().load().unwrap();
+        let (reports, receiver) = broadcast::channel(64);
+        let policy = RejectionBackoffPolicy::new(
+            &RejectionBackoffConfig {
+                threshold: 3,
+                window_ms: 10_000,
+                initial_backoff_ms: 1_000,
+                max_backoff_ms: 3_000,
+            },
+            Arc::new(clock.clone()),
+        );
+        policy.on_report(&instrument, receiver);
+
+        Self {
+            policy,
+            reports,
+            clock,
+            instrument,
+            market: MarketState::new(),
+            signals: signals(),
+            orders: OrderManager::new(OrderIds::sequential()),
+        }
+    }
+
+    /// Sends `report` and lets the policy's task take it.
+    async fn report(&self, report: OrderReport) {
+        self.reports.send(report).unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    async fn reject(&self, instrument: &Instrument) {
+        self.report(OrderReport::Rejected {
+            order_id: "sim-1".to_string(),
+            instrument: instrument.clone(),
+            side: Buy,
+            kind: RejectKind::PostOnlyWouldCross,
+            reason: "EOrder:Post only order".to_string(),
+        })
+        .await;
+    }
+
+    async fn rejections(&self, count: usize) {
+        for _ in 0..count {
+            self.reject(&self.instrument.clone()).await;
+        }
+    }
+
+    async fn accept(&self) {
+        self.report(OrderReport::Accepted {
+            order_id: "sim-2".to_string(),
+            instrument: self.instrument.clone(),
+            side: Buy,
+            price: Price::new(93.00),
+            quantity: qty(0.05),
+        })
+        .await;
+    }
+
+    fn advance(&self, ms: u64) {
+        self.clock.advance(Duration::from_millis(ms));
+    }
+
+    /// The rejections counted and milliseconds left of the pause, if evaluation is held.
+    fn skip(&mut self) -> Option<(usize, u64)> {
+        let now = self.clock.now_instant();
+        let ctx = ScheduleContext {
+            now,
+            trigger: Trigger::MarketEvent,
+            now_utc: self.clock.now_utc(),
+            instrument: &self.instrument,
+            market_state: &self.market,
+            signal_state: &self.signals,
+            order_manager: &self.orders,
+        };
+        match self.policy.should_evaluate(&ctx)? {
+            SkipReason::RejectionBackoff { count, until } => {
+                Some((count, until.duration_since(now).as_millis() as u64))
+            }
+            reason => panic!("unexpected skip: {reason:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn pauses_for_longer_on_each_burst_of_rejections_until_an_accept() {
+    let mut backoff = Backoff::new();
+
+    backoff.rejections(2).await;
+    assert_eq!(backoff.skip(), None);
+    backoff.rejections(1).await;
+    assert_eq!(backoff.skip(), Some((3, 1_000)));
+
+    backoff.advance(1_000);
+    assert_eq!(backoff.skip(), None);
+
+    // Each further burst doubles the pause, up to the cap.
+    backoff.rejections(3).await;
+    assert_eq!(backoff.skip(), Some((6, 2_000)));
+    backoff.advance(2_000);
+    backoff.rejections(3).await;
+    assert_eq!(backoff.skip(), Some((9, 3_000)));
+
+    // An accept ends the pause and starts the doubling over.
+    backoff.accept().await;
+    assert_eq!(backoff.skip(), None);
+    backoff.rejections(3).await;
+    assert_eq!(backoff.skip(), Some((3, 1_000)));
+}
+
+#[tokio::test]
+async fn ignores_rejections_spread_beyond_the_window_or_for_other_pairs() {
+    let mut backoff = Backoff::new();
+
+    for _ in 0..5 {
+        backoff.rejections(1).await;
+        backoff.advance(5_000);
+    }
+    assert_eq!(backoff.skip(), None);
+
+    let btc: Instrument = "BTC/GBP".parse().unwrap();
+    for _ in 0..5 {
+        backoff.reject(&btc).await;
+    }
+    assert_eq!(backoff.skip(), None);
+}
+
+fn placed(instrument: &Instrument, order_id: &str) -> OrderReport {
+    OrderReport::Placed {
+        order_id: order_id.to_string(),
+        instrument: instrument.clone(),
+        side: Buy,
+        price: Price::new(93.00),
+        quantity: qty(0.05),
+    }
+}
+
+fn accepted(instrument: &Instrument, order_id: &str) -> OrderReport {
+    OrderReport::Accepted {
+        order_id: order_id.to_string(),
+        instrument: instrument.clone(),
+        side: Buy,
+        price: Price::new(93.00),
+        quantity: qty(0.05),
+    }
+}
+
+#[test]
+fn ack_latency_pairs_placements_with_their_answers_over_the_window() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let start = SimClock::from_timestamp_ms(0).now_instant();
+    let at = |ms: u64| start + Duration::from_millis(ms);
+    let mut tracker = AckLatencyTracker::new(4);
+    assert_eq!(tracker.summary(), None);
+
+    // Answers arrive out of order, and a reject closes a placement as an accept does.
+    tracker.on_report(&placed(&instrument, "a"), at(0));
+    tracker.on_report(&placed(&instrument, "b"), at(10));
+    assert_eq!(
+        tracker.on_report(&accepted(&instrument, "b"), at(30)),
+        Some(Duration::from_millis(20))
+    );
+    let rejected = OrderReport::Rejected {
+        order_id: "a".to_string(),
+        instrument: instrument.clone(),
+        side: Buy,
+        kind: RejectKind::PostOnlyWouldCross,
+        reason: "EOrder:Post only order".to_string(),
+    };
+    assert_eq!(
+        tracker.on_report(&rejected, at(40)),
+        Some(Duration::from_millis(40))
+    );
+    // An answer to nothing placed is not a sample.
+    assert_eq!(tracker.on_report(&accepted(&instrument, "x"), at(50)), None);
+
+    for (order_id, sent, answered) in [("c", 100, 110), ("d", 200, 300), ("e", 400, 405)] {
+        tracker.on_report(&placed(&instrument, order_id), at(sent));
+        tracker.on_report(&accepted(&instrument, order_id), at(answered));
+    }
+
+    // The first 20ms ack has left the window of four: 40, 10, 100 and 5ms remain.
+    let latency = tracker.summary().unwrap();
+    assert_eq!(latency.samples, 4);
+    assert_eq!(latency.p50, Duration::from_millis(10));
+    assert_eq!(latency.p95, Duration::from_millis(100));
+    assert_eq!(latency.max, Duration::from_millis(100));
+
+    tracker.forget_before(at(300));
+    assert_eq!(tracker.summary().unwrap().samples, 2);
+}
+
+/// A [`VenueLatencyPolicy`] fed reports for SOL/GBP, on a clock the test moves.
+struct Latency {
+    policy: VenueLatencyPolicy,
+    reports: broadcast::Sender<OrderReport>,
+    clock: SimClock,
+    instrument: Instrument,
+    market: MarketState,
+    signals: SignalState,
+    orders: OrderManager,
+    next_id: usize,
+}
+
+impl Latency {
+    fn new(max_ack_p95_ms: Option<u64>) -> Self {
+        let clock = SimClock::from_timestamp_ms(1_704_283_200_000);
+        let instrument = InstrumentConfig::default().load().unwrap();
+        let (reports, receiver) = broadcast::channel(64);
+        let policy = VenueLatencyPolicy::new(
+            &VenueLatencyConfig {
+                max_ack_p95_ms,
+                window: 10,
+                min_samples: 3,
+                max_sample_age_ms: 30_000,
+            },
+            Arc::new(clock.clone()),
+        );
+        policy.on_report(&instrument, receiver);
+
+        Self {
+            policy,
+            reports,
+            clock,
+            instrument,
+            market: MarketState::new(),
+            signals: signals(),
+            orders: OrderManager::new(OrderIds::sequential()),
+            next_id: 0,
+        }
+    }
+
+    /// Places an order and has the venue accept it `ms` later.
+    async fn round_trip(&mut self, instrument: &Instrument, ms: u64) {
+        self.next_id += 1;
+        let order_id = format!("sim-{}", self.next_id);
+        self.reports.send(placed(instrument, &order_id)).unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        self.clock.advance(Duration::from_millis(ms));
+        self.reports.send(accepted(instrument, &order_id)).unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    async fn round_trips(&mut self, ms: &[u64]) {
+        for ms in ms {
+            self.round_trip(&self.instrument.clone(), *ms).await;
+        }
+    }
+
+    /// The p95 ack latency in ms, if evaluation is skipped for it.
+    fn skip(&mut self) -> Option<u64> {
+        let ctx = ScheduleContext {
+            now: self.clock.now_instant(),
+            trigger: Trigger::MarketEvent,
+            now_utc: self.clock.now_utc(),
+            instrument: &self.instrument,
+            market_state: &self.market,
+            signal_state: &self.signals,
+            order_manager: &self.orders,
+        };
+        match self.policy.should_evaluate(&ctx)? {
+            SkipReason::VenueDegraded { ack_p95, .. } => Some(ack_p95.as_millis() as u64),
+            reason => panic!("unexpected skip: {reason:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn skips_while_the_venue_is_slow_to_acknowledge() {
+    let mut latency = Latency::new(Some(500));
+
+    // One slow ack, even with a fast one after it, is too few to judge the venue on.
+    latency.round_trips(&[900, 50]).await;
+    assert_eq!(latency.skip(), None);
+
+    // With a third, the slow one sets the p95.
+    latency.round_trips(&[80]).await;
+    assert_eq!(latency.skip(), Some(900));
+    assert_eq!(latency.policy.ack_latency().unwrap().samples, 3);
+
+    // With nothing new placed, the slow spell ages out and quoting resumes.
+    latency.clock.advance(Duration::from_secs(31));
+    assert_eq!(latency.skip(), None);
+}
+
+#[tokio::test]
+async fn venue_latency_is_off_without_a_threshold_and_ignores_other_pairs() {
+    let mut off = Latency::new(None);
+    off.round_trips(&[5_000, 5_000, 5_000]).await;
+    assert_eq!(off.skip(), None);
+
+    let mut latency = Latency::new(Some(500));
+    let btc: Instrument = "BTC/GBP".parse().unwrap();
+    for _ in 0..3 {
+        latency.round_trip(&btc, 5_000).await;
+    }
+    assert_eq!(latency.skip(), None);
+    assert_eq!(latency.policy.ack_latency(), None);
+}
+
+/// A [`WarmupPolicy`] asked once a second, after a book, with a simple market maker's
+/// signals: both EMAs are warm from the fourth book, three seconds in.
+struct Warmup {
+    policy: WarmupPolicy,
+    clock: SimClock,
+    instrument: Instrument,
+    market: MarketState,
+    signals: SignalState,
+    orders: OrderManager,
+}
+
+impl Warmup {
+    fn new(config: WarmupConfig) -> Self {
+        Self {
+            policy: WarmupPolicy::new(&config),
+            clock: SimClock::from_timestamp_ms(1_704_283_200_000),
+            instrument: InstrumentConfig::default().load().unwrap(),
+            market: MarketState::new(),
+            signals: signals(),
+            orders: OrderManager::new(OrderIds::sequential()),
+        }
+    }
+
+    /// The market events seen and whether the signals were warm, if evaluation is held
+    /// on the next book.
+    fn book(&mut self) -> Option<(u64, bool)> {
+        self.clock.advance(Duration::from_secs(1));
+        let now = self.clock.now_instant();
+        self.market.on_market_event(
+            &MarketEvent::TopOfBook {
+                instrument: self.instrument.clone(),
+                best_bid: Price::new(92.95),
+                best_ask: Price::new(93.05),
+                bid_size: None,
+                ask_size: None,
+                timestamp_ms: 0,
+            },
+            now,
+        );
+        self.signals.update(&self.market, now);
+
+        let ctx = ScheduleContext {
+            now,
+            trigger: Trigger::MarketEvent,
+            now_utc: self.clock.now_utc(),
+            instrument: &self.instrument,
+            market_state: &self.market,
+            signal_state: &self.signals,
+            order_manager: &self.orders,
+        };
+        match self.policy.should_evaluate(&ctx)? {
+            SkipReason::WarmingUp {
+                market_events,
+                signals_warm,
+            } => Some((market_events, signals_warm)),
+            reason => panic!("unexpected skip: {reason:?}"),
+        }
+    }
+
+    /// How many books it takes before evaluation is let through.
+    fn books_until_quoting(&mut self) -> u64 {
+        (1..=100).find(|_| self.book().is_none()).unwrap()
+    }
+}
+
+#[test]
+fn holds_the_first_quote_until_the_signals_are_warm_and_enough_events_are_seen() {
+    let mut warmup = Warmup::new(WarmupConfig {
+        enabled: true,
+        min_market_events: 10,
+        min_elapsed_ms: 60_000,
+    });
+
+    assert_eq!(warmup.book(), Some((1, false)));
+    assert_eq!(warmup.book(), Some((2, false)));
+    assert_eq!(warmup.book(), Some((3, false)));
+    assert_eq!(warmup.book(), Some((4, true)));
+    assert_eq!(warmup.books_until_quoting(), 6);
+
+    // Once warm it stays so, even as a reconnect starts the count over.
+    warmup.market.invalidate();
+    assert_eq!(warmup.book(), None);
+}
+
+#[test]
+fn enough_time_watching_the_market_stands_in_for_the_event_count() {
+    let mut warmup = Warmup::new(WarmupConfig {
+        enabled: true,
+        min_market_events: 1_000,
+        min_elapsed_ms: 5_000,
+    });
+    // Timed from the first book.
+    assert_eq!(warmup.books_until_quoting(), 6);
+
+    // The signals are waited on however quickly the market is seen.
+    let mut warmup = Warmup::new(WarmupConfig {
+        enabled: true,
+        min_market_events: 1,
+        min_elapsed_ms: 0,
+    });
+    assert_eq!(warmup.books_until_quoting(), 4);
+
+    let mut off = Warmup::new(WarmupConfig::default());
+    assert_eq!(off.books_until_quoting(), 1);
+}
+
+/// What a [`TradingHoursPolicy`] for `hours` says at each `HH:MM` of Wednesday
+/// 2024-01-03, as a skip reason code or "quote".
+fn trading_hours_at(hours: &str, times: &[&str]) -> Vec<String> {
+    let hours: TradingHours = serde_yaml::from_str(hours).unwrap();
+    hours.validate().unwrap();
+    let mut policy = TradingHoursPolicy::new(hours);
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let (market, signals) = (MarketState::new(), signals());
+    let orders = OrderManager::new(OrderIds::sequential());
+
+    times
+        .iter()
+        .map(|time| {
+            let (hour, minute) = time.split_once(':').unwrap();
+            let minutes: u64 = hour.parse::<u64>().unwrap() * 60 + minute.parse::<u64>().unwrap();
+            let clock = SimClock::from_timestamp_ms(1_704_240_000_000 + minutes * 60_000);
+            let ctx = ScheduleContext {
+                now: clock.now_instant(),
+                trigger: Trigger::Timer,
+                now_utc: clock.now_utc(),
+                instrument: &instrument,
+                market_state: &market,
+                signal_state: &signals,
+                order_manager: &orders,
+            };
+            match policy.should_evaluate(&ctx) {
+                Some(SkipReason::ClosingSoon { minutes_to_close }) => {
+                    format!("closing_soon {minutes_to_close}")
+                }
+                Some(reason) => reason.code().to_string(),
+                None => "quote".to_string(),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn trading_hours_follow_each_window_and_hold_quotes_before_each_close() {
+    let hours = "
+        windows:
+          - { start: '07:00', end: '11:00' }
+          - { start: '13:00', end: '21:00' }
+        weekend_pause: true
+        close_buffer_mins: 10
+    ";
+    assert_eq!(
+        trading_hours_at(
+            hours,
+            &[
+                "06:59", "07:00", "10:49", "10:50", "10:59", "11:00", "13:00", "20:55", "21:00"
+            ]
+        ),
+        [
+            "out_of_trading_hours",
+            "quote",
+            "quote",
+            "closing_soon 10",
+            "closing_soon 1",
+            "out_of_trading_hours",
+            "quote",
+            "closing_soon 5",
+            "out_of_trading_hours",
+        ]
+    );
+}
+
+#[test]
+fn overnight_windows_run_past_midnight() {
+    let hours = "
+        windows: [{ start: '22:00', end: '02:00' }]
+        weekend_pause: false
+        close_buffer_mins: 10
+    ";
+    assert_eq!(
+        trading_hours_at(
+            hours,
+            &["21:59", "22:00", "23:59", "00:00", "01:50", "02:00"]
+        ),
+        [
+            "out_of_trading_hours",
+            "quote",
+            "quote",
+            "quote",
+            "closing_soon 10",
+            "out_of_trading_hours",
+        ]
+    );
+
+    // Back to back across midnight, the windows are one session closing at 02:00.
+    let hours = "
+        windows:
+          - { start: '22:00', end: '24:00' }
+          - { start: '00:00', end: '02:00' }
+        weekend_pause: false
+        close_buffer_mins: 10
+    ";
+    assert_eq!(
+        trading_hours_at(hours, &["23:55", "01:52"]),
+        ["quote", "closing_soon 8"]
+    );
+
+    // A session that never closes is never closing soon.
+    let hours = "
+        windows: [{ start: '00:00', end: '24:00' }]
+        weekend_pause: false
+        close_buffer_mins: 10
+    ";
+    assert_eq!(
+        trading_hours_at(hours, &["23:55", "00:00"]),
+        ["quote", "quote"]
+    );
+}
@@ -0,0 +1,321 @@
+use std::time::Duration;
+
+use accumulator::clock::SimClock;
+use accumulator::execution::order_action::Side::{self, Buy, Sell};
+use accumulator::execution::order_report::{OrderReport, RejectKind};
+use accumulator::stats::equity_curve::EquityCurve;
+use accumulator::stats::session_stats::{SessionStats, StatsEvent};
+use accumulator::stats::trading_book::TradingBook;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+
+const START_MS: u64 = 1_700_000_000_000;
+
+struct Session {
+    clock: SimClock,
+    instrument: Instrument,
+    stats: SessionStats,
+}
+
+impl Session {
+    fn new() -> Self {
+        let clock = SimClock::from_timestamp_ms(START_MS);
+        let stats = SessionStats::new(
+            clock.shared(),
+            TradingBook::default(),
+            EquityCurve::new(Duration::from_secs(10), 100),
+        );
+
+        Self {
+            clock,
+            instrument: InstrumentConfig::default().load().unwrap(),
+            stats,
+        }
+    }
+
+    fn at(&self, ms: u64) -> &Self {
+        self.clock.set_timestamp_ms(START_MS + ms);
+        self
+    }
+
+    fn events(&mut self, events: &[StatsEvent]) {
+        for event in events {
+            self.stats.on_event(*event);
+        }
+    }
+
+    fn report(&mut self, report: OrderReport) {
+        self.stats.on_report(&report);
+    }
+
+    fn placed(&self, order_id: &str, side: Side, price: f64) -> OrderReport {
+        OrderReport::Placed {
+            order_id: order_id.to_string(),
+            instrument: self.instrument.clone(),
+            side,
+            price: Price::new(price),
+            quantity: Quantity::new(1.0).unwrap(),
+        }
+    }
+
+    fn accepted(&self, order_id: &str, side: Side, price: f64) -> OrderReport {
+        OrderReport::Accepted {
+            order_id: order_id.to_string(),
+            instrument: self.instrument.clone(),
+            side,
+            price: Price::new(price),
+            quantity: Quantity::new(1.0).unwrap(),
+        }
+    }
+
+    fn filled(&self, order_id: &str, side: Side, price: f64) -> OrderReport {
+        OrderReport::Filled {
+            order_id: order_id.to_string(),
+            instrument: self.instrument.clone(),
+            side,
+            price: Price::new(price),
+            quantity: Quantity::new(1.0).unwrap(),
+            cum_quantity: Quantity::new(1.0).unwrap(),
+        }
+    }
+
+    fn cancelled(&self, order_id: &str, side: Side) -> OrderReport {
+        OrderReport::Cancelled {
+            order_id: order_id.to_string(),
+            instrument: self.instrument.clone(),
+            side,
+        }
+    }
+
+    fn rejected(&self, order_id: &str, side: Side) -> OrderReport {
+        OrderReport::Rejected {
+            order_id: order_id.to_string(),
+            instrument: self.instrument.clone(),
+            side,
+            kind: RejectKind::PostOnlyWouldCross,
+            reason: "post only".to_string(),
+        }
+    }
+}
+
+fn snapshot(base: f64, mid: f64, realized_pnl: f64, unrealized_pnl: f64) -> StatsEvent {
+    StatsEvent::Snapshot {
+        inventory: Inventory::new(base, 1_000.0),
+        mid: Some(Price::new(mid)),
+        realized_pnl,
+        unrealized_pnl: Some(unrealized_pnl),
+    }
+}
+
+#[test]
+fn counts_each_window_and_resets_after_it() {
+    let mut session = Session::new();
+
+    session.events(&[
+        StatsEvent::MarketEvent,
+        StatsEvent::MarketEvent,
+        StatsEvent::TargetComputed {
+            bid: Some(Price::new(99.0)),
+            ask: Some(Price::new(101.0)),
+        },
+    ]);
+    let (bid, ask) = (
+        session.placed("b1", Buy, 99.0),
+        session.placed("s1", Sell, 101.0),
+    );
+    session.report(bid);
+    session.report(ask);
+    let accepted = session.accepted("b1", Buy, 99.0);
+    session.report(accepted);
+    let rejected = session.rejected("s1", Sell);
+    session.report(rejected);
+    let filled = session.at(5_000).filled("b1", Buy, 99.0);
+    session.report(filled);
+
+    let first = session.at(60_000).stats.summary();
+    assert_eq!(first.window, Duration::from_secs(60));
+    let counters = first.counters;
+    assert_eq!(
+        (
+            counters.events,
+            counters.targets,
+            counters.placed,
+            counters.accepted,
+            counters.rejected,
+            counters.filled,
+            counters.cancelled,
+        ),
+        (2, 1, 2, 1, 1, 1, 0)
+    );
+
+    session.stats.reset_window();
+    let empty = session.stats.summary();
+    assert_eq!(empty.window, Duration::ZERO);
+    assert_eq!(empty.counters.events, 0);
+    assert_eq!(empty.counters.placed, 0);
+    assert!(empty.top_reasons.is_empty());
+    // The session totals carry on across windows.
+    assert_eq!(empty.totals.events, 2);
+    assert_eq!(empty.totals.filled, 1);
+
+    session.events(&[StatsEvent::MarketEvent]);
+    let placed = session.at(61_000).placed("b2", Buy, 98.0);
+    session.report(placed);
+    let cancelled = session.at(62_000).cancelled("b2", Buy);
+    session.report(cancelled);
+
+    let second = session.at(120_000).stats.summary();
+    assert_eq!(second.window, Duration::from_secs(60));
+    assert_eq!(second.uptime, Duration::from_secs(120));
+    let counters = second.counters;
+    assert_eq!(
+        (
+            counters.events,
+            counters.placed,
+            counters.filled,
+            counters.cancelled
+        ),
+        (1, 1, 0, 1)
+    );
+    assert_eq!((second.totals.events, second.totals.placed), (3, 3));
+}
+
+#[test]
+fn reports_the_three_most_frequent_reasons_of_the_window() {
+    let mut session = Session::new();
+
+    session.events(&[
+        StatsEvent::Skipped {
+            reason: "in_flight",
+        },
+        StatsEvent::Skipped {
+            reason: "in_flight",
+        },
+        StatsEvent::Skipped {
+            reason: "in_flight",
+        },
+        StatsEvent::RiskHold {
+            reason: "churn_throttle",
+        },
+        StatsEvent::RiskHold {
+            reason: "churn_throttle",
+        },
+        StatsEvent::NoQuote {
+            reason: "spread_too_narrow",
+        },
+        StatsEvent::NoQuote {
+            reason: "spread_too_narrow",
+        },
+        StatsEvent::RiskRejected {
+            reason: "kill_switch_enabled",
+        },
+    ]);
+
+    // Ties are broken by code, and the single kill switch rejection falls out of the top
+    // three.
+    assert_eq!(
+        session.stats.summary().top_reasons,
+        [
+            ("in_flight", 3),
+            ("churn_throttle", 2),
+            ("spread_too_narrow", 2),
+        ]
+    );
+
+    session.stats.reset_window();
+    session.events(&[StatsEvent::RiskRejected {
+        reason: "kill_switch_enabled",
+    }]);
+    assert_eq!(
+        session.stats.summary().top_reasons,
+        [("kill_switch_enabled", 1)]
+    );
+}
+
+#[test]
+fn carries_the_engine_pnl_from_the_latest_snapshot() {
+    let mut session = Session::new();
+
+    let summary = session.stats.summary();
+    assert_eq!(summary.realized_pnl_quote, 0.0);
+    assert_eq!(summary.unrealized_pnl_quote, None);
+
+    session.events(&[snapshot(1.5, 100.0, 2.5, -0.75)]);
+    let summary = session.stats.summary();
+    assert_eq!(summary.realized_pnl_quote, 2.5);
+    assert_eq!(summary.unrealized_pnl_quote, Some(-0.75));
+    assert_eq!(summary.exposure_quote, Some(150.0));
+
+    // Like the inventory, the PnL is the engine's and is not reset with the window.
+    session.stats.reset_window();
+    assert_eq!(session.stats.summary().realized_pnl_quote, 2.5);
+}
+
+#[test]
+fn summarizes_fills_exposure_drawdown_and_acceptance() {
+    let mut session = Session::new();
+    let mut rules = session.instrument.trading_rules();
+    rules.maker_fee_bps = 10.0;
+    session.instrument.set_trading_rules(rules);
+
+    session.events(&[snapshot(1.0, 100.0, 0.0, 0.0)]);
+    let (bid, ask) = (
+        session.placed("b1", Buy, 99.0),
+        session.placed("s1", Sell, 101.0),
+    );
+    session.report(bid);
+    session.report(ask);
+    let accepted = session.at(100).accepted("b1", Buy, 99.0);
+    session.report(accepted);
+    let rejected = session.rejected("s1", Sell);
+    session.report(rejected);
+    let filled = session.at(2_000).filled("b1", Buy, 99.0);
+    session.report(filled);
+
+    // The mid falls three under the peak the bought unit marked at.
+    session.at(2_500);
+    session.events(&[snapshot(1.5, 97.0, 0.0, -2.0)]);
+
+    let placed = session.at(3_000).placed("s2", Sell, 101.0);
+    session.report(placed);
+    let accepted = session.at(3_100).accepted("s2", Sell, 101.0);
+    session.report(accepted);
+    let filled = session.at(4_000).filled("s2", Sell, 101.0);
+    session.report(filled);
+    session.events(&[snapshot(0.5, 100.0, 2.0, 0.0)]);
+
+    let summary = session.stats.instrument_summary(&session.instrument);
+    assert_eq!(summary.fills, 2);
+    assert_eq!((summary.volume_base, summary.volume_quote), (2.0, 200.0));
+    assert_eq!((summary.bids.placed, summary.bids.fills), (1, 1));
+    assert_eq!((summary.asks.placed, summary.asks.fills), (2, 1));
+    assert_eq!(summary.max_exposure_quote, 145.5);
+    assert_eq!(summary.gross_pnl_quote, Some(2.0));
+    assert!(
+        (summary.fees_quote - 0.2).abs() < 1e-9,
+        "{}",
+        summary.fees_quote
+    );
+    assert!((summary.net_pnl_quote.unwrap() - 1.8).abs() < 1e-9);
+    assert_eq!(summary.max_drawdown_quote, 3.0);
+    assert_eq!(
+        (summary.placed, summary.accepted, summary.rejected),
+        (3, 2, 1)
+    );
+    assert_eq!(summary.acceptance_rate, Some(2.0 / 3.0));
+    // Two seconds for the bid, one for the second ask; the rejected ask never filled.
+    assert_eq!(summary.avg_quote_to_fill_ms, Some(1_500.0));
+
+    let printed = summary.to_string();
+    for line in [
+        "    fills        2 (2.00000000 base, 200.00 quote)",
+        "    pnl          2.00 gross, 0.20 fees, 1.80 net, max drawdown 3.00",
+        "    max exposure 145.50",
+        "    orders       3 placed, 2 accepted, 1 rejected (acceptance 66.7%)",
+        "    quote->fill  1500 ms avg",
+    ] {
+        assert!(printed.contains(line), "{line:?} not in\n{printed}");
+    }
+}
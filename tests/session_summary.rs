@@ -0,0 +1,75 @@
+mod common;
+
+use accumulator::execution::order_action::Side::{Buy, Sell};
+
+use common::{Act, Expect, Harness, Step};
+
+fn book(bid: f64, ask: f64) -> Step {
+    Step::Book { bid, ask }
+}
+
+#[tokio::test]
+async fn summarizes_quoting_activity_spreads_and_reasons() {
+    let mut harness = Harness::new(|_| {}).await.unwrap();
+    harness
+        .run(&[
+            (0, book(93.00, 93.10)),
+            (1_000, book(93.00, 93.10)),
+            (
+                1_000,
+                Step::Expect(Expect::Actions(vec![Act::Place(Buy), Act::Place(Sell)])),
+            ),
+            (1_010, Step::Accept(Buy)),
+            (1_010, Step::Accept(Sell)),
+            (2_000, Step::Fill(Buy)),
+            (3_000, book(93.00, 93.10)),
+            (3_000, Step::Expect(Expect::Actions(vec![Act::Place(Buy)]))),
+            (3_010, Step::Accept(Buy)),
+            (4_000, Step::KillSwitch(true)),
+            (4_000, book(93.00, 93.10)),
+            (4_000, Step::Expect(Expect::Actions(vec![Act::CancelAll]))),
+            (5_000, book(93.00, 93.10)),
+        ])
+        .await
+        .unwrap();
+
+    let summary = harness.summary().await;
+    assert_eq!((summary.bids.placed, summary.bids.fills), (2, 1));
+    assert_eq!((summary.asks.placed, summary.asks.fills), (1, 0));
+
+    // Quoted a tick wide at the bottom of the book, and the bid filled five ticks under
+    // the mid.
+    let quoted = summary.avg_quoted_spread_bps.unwrap();
+    let captured = summary.avg_captured_spread_bps.unwrap();
+    assert!((quoted - 0.01 / 93.05 * 10_000.0).abs() < 1e-6, "{quoted}");
+    assert!(
+        (captured - 0.10 / 93.05 * 10_000.0).abs() < 1e-6,
+        "{captured}"
+    );
+
+    // Orders rested from the first accept until the cancel all.
+    assert!(
+        (summary.quoting_secs - 2.99).abs() < 1e-6,
+        "{}",
+        summary.quoting_secs
+    );
+    assert!(
+        (summary.idle_secs - 2.01).abs() < 1e-6,
+        "{}",
+        summary.idle_secs
+    );
+    assert_eq!(summary.risk_rejections.get("kill_switch_enabled"), Some(&2));
+    assert!(summary.no_quotes.is_empty());
+
+    let printed = summary.to_string();
+    for line in [
+        "    bids         2 placed, 0 replaced, 0 cancelled, 1 filled",
+        "    asks         1 placed, 0 replaced, 0 cancelled, 0 filled",
+        "    spread       1.1 bps quoted, 10.7 bps captured (avg)",
+        "    time         3s quoting, 2s idle (59.8% quoting)",
+        "    no quotes    -",
+        "    risk rejects kill_switch_enabled=2",
+    ] {
+        assert!(printed.contains(line), "{line:?} not in\n{printed}");
+    }
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::scenario::strategies::StrategyKind;
+
+use common::{Act, Expect, Harness, Step};
+
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("accumulator-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn book(bid: f64, ask: f64) -> Step {
+    Step::Book { bid, ask }
+}
+
+/// Quotes at the touch, then a rally through the ask.
+fn rally() -> Vec<(u64, Step)> {
+    vec![
+        (0, book(93.00, 93.10)),
+        (1_000, book(93.00, 93.10)),
+        (
+            1_000,
+            Step::Expect(Expect::Actions(vec![Act::Place(Buy), Act::Place(Sell)])),
+        ),
+        (1_010, Step::Accept(Buy)),
+        (1_010, Step::Accept(Sell)),
+        (2_000, book(93.30, 93.40)),
+    ]
+}
+
+/// A book a second apart for a minute, with a sharp drop half way for a regime switch
+/// to quote into.
+fn dip() -> Vec<(u64, Step)> {
+    (0..60u64)
+        .map(|second| {
+            let bid = if second < 30 { 93.00 } else { 92.50 };
+            (second * 1_000, book(bid, bid + 0.10))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn shadow_fills_hypothetically_without_touching_the_venue() {
+    let mut plain = Harness::new(|_| {}).await.unwrap();
+    plain.run(&rally()).await.unwrap();
+
+    let mut shadowed = Harness::new(|config| {
+        config.shadow_strategies = vec![StrategyKind::SimpleMarketMaker];
+    })
+    .await
+    .unwrap();
+    shadowed.run(&rally()).await.unwrap();
+
+    assert_eq!(
+        format!("{:?}", shadowed.actions()),
+        format!("{:?}", plain.actions())
+    );
+
+    let summary = shadowed.summary().await;
+    assert_eq!(summary.fills, 0);
+    let [shadow] = &summary.shadows[..] else {
+        panic!("{:?}", summary.shadows);
+    };
+    assert_eq!(shadow.strategy, StrategyKind::SimpleMarketMaker);
+    assert_eq!(shadow.targets, 3);
+    assert_eq!(shadow.fills, 1);
+    assert!(shadow.volume_base > 0.0);
+    // Sold at the old ask and marked at the new mid.
+    assert!(shadow.gross_pnl_quote.unwrap() < 0.0);
+
+    assert!(
+        summary
+            .to_string()
+            .contains("shadow       simple-mm vs actual: fills 1 vs 0")
+    );
+    assert!(plain.summary().await.shadows.is_empty());
+}
+
+#[tokio::test]
+async fn shadow_counts_cycles_it_would_not_quote() {
+    let mut harness = Harness::new(|config| {
+        config.shadow_strategies = vec![StrategyKind::TrendFollowing];
+    })
+    .await
+    .unwrap();
+    harness.run(&rally()).await.unwrap();
+
+    let shadow = &harness.summary().await.shadows[0];
+    assert_eq!(shadow.strategy, StrategyKind::TrendFollowing);
+    assert_eq!(shadow.targets + shadow.no_quotes, 3);
+    assert!(shadow.no_quotes > 0);
+}
+
+#[tokio::test]
+async fn each_shadow_keeps_its_own_session() {
+    let mut harness = Harness::new(|config| {
+        config.shadow_strategies = vec![
+            StrategyKind::TrendFollowing,
+            StrategyKind::SimpleMarketMaker,
+        ];
+    })
+    .await
+    .unwrap();
+    harness.run(&rally()).await.unwrap();
+
+    let summary = harness.summary().await;
+    let strategies: Vec<StrategyKind> = summary
+        .shadows
+        .iter()
+        .map(|shadow| shadow.strategy)
+        .collect();
+    assert_eq!(
+        strategies,
+        [
+            StrategyKind::SimpleMarketMaker,
+            StrategyKind::TrendFollowing
+        ]
+    );
+    assert_eq!(summary.shadows[0].fills, 1);
+    assert_eq!(summary.shadows[1].fills, 0);
+    assert!(
+        summary
+            .to_string()
+            .contains("shadow       trend-following vs actual")
+    );
+}
+
+#[tokio::test]
+async fn decision_log_compares_each_shadow_with_the_live_target() {
+    let path = log_path("shadow-decisions.jsonl");
+    let mut harness = Harness::new(|config| {
+        config.logging.decision_log = Some(path.clone());
+        config.shadow_strategies = vec![
+            StrategyKind::SimpleMarketMaker,
+            StrategyKind::TrendFollowing,
+        ];
+    })
+    .await
+    .unwrap();
+    harness.run(&rally()).await.unwrap();
+
+    let records: Vec<Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let first = &records[0];
+    assert_eq!(first["target"]["bid"]["price"], 93.00);
+
+    // The same strategy as the live one agrees on everything.
+    let same = &first["shadows"][0];
+    assert_eq!(same["strategy"], "simple-mm");
+    assert_eq!(same["target"]["bid"]["price"], 93.00);
+    assert_eq!(same["agrees_to_quote"], true);
+    assert_eq!(same["bid_agrees"], true);
+    assert_eq!(same["ask_agrees"], true);
+
+    // Trend following has nothing to follow before its signals warm up.
+    let trend = &first["shadows"][1];
+    assert_eq!(trend["strategy"], "trend-following");
+    assert!(trend["target"].is_null());
+    assert!(!trend["no_quote"].is_null());
+    assert_eq!(trend["agrees_to_quote"], false);
+    assert_eq!(trend["bid_agrees"], false);
+}
+
+#[tokio::test]
+async fn a_regime_switch_shadow_leaves_a_live_regime_switch_alone() {
+    let mut plain = Harness::new(|config| {
+        config.strategy.kind = StrategyKind::RegimeSwitch;
+    })
+    .await
+    .unwrap();
+    plain.run(&dip()).await.unwrap();
+
+    let mut shadowed = Harness::new(|config| {
+        config.strategy.kind = StrategyKind::RegimeSwitch;
+        config.shadow_strategies = vec![StrategyKind::RegimeSwitch];
+    })
+    .await
+    .unwrap();
+    shadowed.run(&dip()).await.unwrap();
+
+    assert_eq!(
+        format!("{:?}", shadowed.actions()),
+        format!("{:?}", plain.actions())
+    );
+    assert!(!plain.actions().is_empty());
+    let shadow = &shadowed.summary().await.shadows[0];
+    assert_eq!(shadow.strategy, StrategyKind::RegimeSwitch);
+    assert!(shadow.targets > 0);
+}
@@ -0,0 +1,55 @@
+use accumulator::execution::order_action::Side::{Buy, Sell};
+
+#[test]
+fn sides_mirror_each_other() {
+    assert_eq!(Buy.opposite(), Sell);
+    assert_eq!(Sell.opposite(), Buy);
+    for side in [Buy, Sell] {
+        assert_eq!(side.opposite().opposite(), side);
+        assert_eq!(side.opposite().sign(), -side.sign());
+    }
+
+    assert_eq!(Buy.sign(), 1.0);
+    assert_eq!(Sell.sign(), -1.0);
+    assert_eq!(Buy.signed(0.05), 0.05);
+    assert_eq!(Sell.signed(0.05), -0.05);
+}
+
+#[test]
+fn only_the_side_towards_flat_reduces() {
+    assert!(Sell.is_reducing_for(1.5));
+    assert!(!Buy.is_reducing_for(1.5));
+    assert!(Buy.is_reducing_for(-1.5));
+    assert!(!Sell.is_reducing_for(-1.5));
+
+    // The smallest position still has a side that reduces it.
+    assert!(Sell.is_reducing_for(f64::MIN_POSITIVE));
+    assert!(Buy.is_reducing_for(-f64::MIN_POSITIVE));
+}
+
+#[test]
+fn a_flat_position_has_nothing_to_reduce() {
+    for side in [Buy, Sell] {
+        for flat in [0.0, -0.0] {
+            assert!(!side.is_reducing_for(flat), "{side} at {flat}");
+        }
+        assert!(
+            !side.is_reducing_for(f64::NAN),
+            "{side} at an unknown position"
+        );
+    }
+}
+
+#[test]
+fn a_reducing_fill_moves_the_position_towards_zero() {
+    for inventory in [-1.5, -0.05, 0.05, 1.5] {
+        for side in [Buy, Sell] {
+            let after = inventory + side.signed(0.05);
+            assert_eq!(
+                side.is_reducing_for(inventory),
+                after.abs() < inventory.abs(),
+                "{side} at {inventory}"
+            );
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use accumulator::clock::{Clock, SimClock};
+
+use accumulator::events::MarketEvent;
+use accumulator::market::market_state::MarketState;
+use accumulator::scenario::scenario::Scenario;
+use accumulator::scenario::strategies::StrategyKind;
+use accumulator::signals::config::{SignalOverrides, SignalsConfig};
+use accumulator::signals::ema::Ema;
+use accumulator::signals::signal_state::SignalState;
+use accumulator::strategy::config::StrategyConfig;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+
+const START_MS: u64 = 1_704_283_200_000;
+
+/// Books around `mid` a second apart for a minute, then around `mid + step` for
+/// `after_secs` seconds.
+fn step(signals: &mut SignalState, instrument: &Instrument, mid: f64, step: f64, after_secs: u64) {
+    let clock = SimClock::from_timestamp_ms(START_MS);
+    let mut market = MarketState::new();
+    for second in 0..=60 + after_secs {
+        if second > 0 {
+            clock.advance(Duration::from_secs(1));
+        }
+        let now = clock.now_instant();
+        let mid = if second > 60 { mid + step } else { mid };
+        market.on_market_event(&book(instrument, mid), now);
+        signals.update(&market, now);
+    }
+}
+
+fn book(instrument: &Instrument, mid: f64) -> MarketEvent {
+    MarketEvent::TopOfBook {
+        instrument: instrument.clone(),
+        best_bid: Price::new(mid - 0.05),
+        best_ask: Price::new(mid + 0.05),
+        bid_size: None,
+        ask_size: None,
+        timestamp_ms: 0,
+    }
+}
+
+#[test]
+fn configured_taus_change_how_fast_the_emas_follow_a_step() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let strategy = StrategyConfig {
+        kind: StrategyKind::SimpleMarketMaker,
+        ..StrategyConfig::default()
+    };
+    let quick = SignalsConfig {
+        strategies: [(
+            StrategyKind::SimpleMarketMaker,
+            SignalOverrides {
+                fast_tau_secs: Some(1.0),
+                vol_tau_secs: Some(2.0),
+                ..SignalOverrides::default()
+            },
+        )]
+        .into(),
+        ..SignalsConfig::default()
+    };
+
+    let mut tuned = Scenario::signals_for(&strategy, &SignalsConfig::default(), &instrument);
+    let mut fast = Scenario::signals_for(&strategy, &quick, &instrument);
+    step(&mut tuned, &instrument, 93.0, 1.0, 3);
+    step(&mut fast, &instrument, 93.0, 1.0, 3);
+
+    // Three seconds after the step, a 1s EMA has all but caught up and a 3s one has
+    // gone about two thirds of the way.
+    let tuned_ema = tuned.ema_mid().unwrap();
+    let fast_ema = fast.ema_mid().unwrap();
+    assert!(
+        (tuned_ema - (94.0 - (-1.0f64).exp())).abs() < 1e-9,
+        "{tuned_ema}"
+    );
+    assert!(
+        (fast_ema - (94.0 - (-3.0f64).exp())).abs() < 1e-9,
+        "{fast_ema}"
+    );
+    // A 2s volatility EMA still weighs the jump more than a 10s one spreading it out.
+    assert!(fast.volatility_mid().unwrap() > tuned.volatility_mid().unwrap());
+}
+
+#[test]
+fn pair_settings_apply_over_the_strategy_kinds_over_everyones() {
+    let sol_gbp = InstrumentConfig::default().load().unwrap();
+    let config: SignalsConfig = serde_yaml::from_str(
+        "
+        fast_tau_secs: 5.0
+        slow_tau_secs: 50.0
+        strategies:
+          simple-mm: { fast_tau_secs: 2.0, min_update_interval_ms: 100 }
+        pairs:
+          SOL/GBP: { fast_tau_secs: 1.0 }
+        ",
+    )
+    .unwrap();
+    config.validate("signals").unwrap();
+
+    let defaults = Scenario::signal_params(StrategyKind::SimpleMarketMaker);
+    let params = config.params(defaults, StrategyKind::SimpleMarketMaker, Some(&sol_gbp));
+    assert_eq!(params.fast_tau_secs, 1.0);
+    assert_eq!(params.slow_tau_secs, 50.0);
+    assert_eq!(params.vol_tau_secs, defaults.vol_tau_secs);
+    assert_eq!(params.min_update_interval, Duration::from_millis(100));
+
+    let params = config.params(defaults, StrategyKind::SimpleMarketMaker, None);
+    assert_eq!(params.fast_tau_secs, 2.0);
+    let params = config.params(defaults, StrategyKind::MeanReversion, Some(&sol_gbp));
+    assert_eq!(params.fast_tau_secs, 1.0);
+    assert_eq!(params.min_update_interval, defaults.min_update_interval);
+
+    let config: SignalsConfig = serde_yaml::from_str("pairs: { SOLGBP: {} }").unwrap();
+    let error = config.validate("signals").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "signals.pairs.SOLGBP: must be a BASE/QUOTE pair"
+    );
+}
+
+#[test]
+fn a_shorter_update_interval_samples_events_the_default_skips() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let mut throttled =
+        Scenario::signals(StrategyKind::SimpleMarketMaker, &SignalsConfig::default());
+    let mut sampled = throttled
+        .clone()
+        .with_min_update_interval(Duration::from_millis(100));
+
+    // Books 100ms apart for five seconds, then a step the next 100ms later.
+    let clock = SimClock::from_timestamp_ms(START_MS);
+    let mut market = MarketState::new();
+    for tick in 0..=51u64 {
+        clock.set_timestamp_ms(START_MS + tick * 100);
+        let now = clock.now_instant();
+        let mid = if tick == 51 { 94.0 } else { 93.0 };
+        market.on_market_event(&book(&instrument, mid), now);
+        throttled.update(&market, now);
+        sampled.update(&market, now);
+    }
+
+    // The default 350ms throttle took every fourth book, and not the last.
+    assert_eq!(throttled.ema_mid(), Some(93.0));
+    assert!(sampled.ema_mid().unwrap() > 93.0);
+}
+
+#[test]
+fn an_ema_moves_by_the_time_between_samples_and_warms_up_over_its_tau() {
+    let clock = SimClock::from_timestamp_ms(START_MS);
+    let mut ema = Ema::new(2.0);
+
+    assert_eq!(ema.update(clock.now_instant(), 93.0), 93.0);
+    assert_eq!(ema.warmed_value(), None);
+
+    // One tau later it has gone 1 - 1/e of the way to a new sample.
+    clock.advance(Duration::from_secs(2));
+    let value = ema.update(clock.now_instant(), 94.0);
+    assert!((value - (94.0 - (-1.0f64).exp())).abs() < 1e-9, "{value}");
+    assert_eq!(ema.warmed_value(), Some(value));
+
+    // A sample at the same instant carries no weight.
+    assert_eq!(ema.update(clock.now_instant(), 100.0), value);
+}
+
+#[test]
+fn an_ema_is_not_warm_until_its_tau_has_passed() {
+    let clock = SimClock::from_timestamp_ms(START_MS);
+    let mut ema = Ema::new(2.0);
+
+    for _ in 0..20 {
+        ema.update(clock.now_instant(), 93.0);
+        clock.advance(Duration::from_millis(100));
+    }
+    // Twenty samples, but only 1.9s of them.
+    assert_eq!(ema.warmed_value(), None);
+
+    ema.update(clock.now_instant(), 93.0);
+    assert_eq!(ema.warmed_value(), Some(93.0));
+}
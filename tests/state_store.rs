@@ -0,0 +1,198 @@
+mod common;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate};
+use serde_json::Value;
+use tokio::sync::{broadcast, mpsc};
+
+use accumulator::config::app_config::AppConfig;
+use accumulator::engine::engine::{Connections, Engine};
+use accumulator::engine::supervisor::Supervisor;
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::Buy;
+use accumulator::execution::trade_checkpoint::TradeCheckpoint;
+use accumulator::execution::types::OpenOrder;
+use accumulator::execution::{DynamicInventorySource, ExecutionVenue, ReportSender};
+use accumulator::market::market_source::MarketDataSource;
+use accumulator::risk::checks::max_daily_loss::DailyLossState;
+use accumulator::scenario::venues::VenueKind;
+use accumulator::state::store::{EngineState, STATE_VERSION, StateStore};
+use accumulator::stats::trading_book::TradingBook;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::pnl::PnlState;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+
+use common::{INITIAL, MockVenue};
+
+fn store(name: &str) -> StateStore {
+    let path = std::env::temp_dir().join(format!("accumulator-{}-{name}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+    StateStore::new(path)
+}
+
+fn state() -> EngineState {
+    let mut book = TradingBook::default();
+    book.on_fill(Buy, Price::new(93.00), Quantity::new(0.05).unwrap());
+
+    EngineState {
+        venue: VenueKind::Kraken,
+        saved_at: DateTime::from_timestamp_millis(1_704_283_200_000).unwrap(),
+        kill_switch: true,
+        books: BTreeMap::from([("SOL/GBP".to_string(), book)]),
+        daily_loss: BTreeMap::from([(
+            "SOL/GBP".to_string(),
+            DailyLossState {
+                day: NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+                open: 0.04,
+                tripped: None,
+            },
+        )]),
+        pnl: BTreeMap::from([(
+            "SOL/GBP".to_string(),
+            PnlState {
+                position: 0.05,
+                average_entry: 93.00,
+                realized: 0.04,
+                working: BTreeMap::from([("sim-7".to_string(), 0.05)]),
+            },
+        )]),
+        last_trade_ids: BTreeMap::from([("SOL/GBP".to_string(), 41)]),
+    }
+}
+
+/// Rewrites the saved file with `edit` applied to its JSON.
+fn edit(path: &Path, edit: impl FnOnce(&mut Value)) {
+    let mut file: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    edit(&mut file);
+    fs::write(path, serde_json::to_string_pretty(&file).unwrap()).unwrap();
+}
+
+#[test]
+fn restores_what_it_saved() {
+    let store = store("state-round-trip");
+    assert_eq!(store.load(VenueKind::Kraken), None);
+
+    store.save(&state()).unwrap();
+    assert_eq!(store.load(VenueKind::Kraken), Some(state()));
+    // Saved for another venue: its books mean nothing here.
+    assert_eq!(store.load(VenueKind::DryRun), None);
+}
+
+#[test]
+fn ignores_a_file_of_another_version() {
+    let store = store("state-version");
+    store.save(&state()).unwrap();
+
+    edit(store.path(), |file| {
+        file["version"] = Value::from(STATE_VERSION + 1);
+    });
+    assert_eq!(store.load(VenueKind::Kraken), None);
+}
+
+#[test]
+fn ignores_a_file_whose_state_no_longer_matches_its_checksum() {
+    let store = store("state-checksum");
+    store.save(&state()).unwrap();
+
+    // Hand-edited to release the kill switch.
+    edit(store.path(), |file| {
+        file["state"]["kill_switch"] = Value::from(false);
+    });
+    assert_eq!(store.load(VenueKind::Kraken), None);
+
+    // Truncated mid-write.
+    let raw = fs::read_to_string(store.path()).unwrap();
+    fs::write(store.path(), &raw[..raw.len() / 2]).unwrap();
+    assert_eq!(store.load(VenueKind::Kraken), None);
+}
+
+/// Market data that never arrives; restoring state needs none.
+struct Quiet;
+
+#[async_trait]
+impl MarketDataSource for Quiet {
+    async fn subscribe(
+        &self,
+        _instrument: &Instrument,
+        _channel: mpsc::Sender<MarketEvent>,
+    ) -> Result<()> {
+        std::future::pending().await
+    }
+}
+
+/// The mock venue with a trade checkpoint, noting what it held when its report stream
+/// started.
+struct WithTrades {
+    venue: MockVenue,
+    trades: TradeCheckpoint,
+    at_stream_start: Arc<Mutex<Option<u64>>>,
+}
+
+#[async_trait]
+impl ExecutionVenue for WithTrades {
+    async fn execute(&self, actions: &[OrderAction]) -> Result<()> {
+        self.venue.execute(actions).await
+    }
+
+    async fn open_orders(&self, instrument: &Instrument) -> Result<Vec<OpenOrder>> {
+        self.venue.open_orders(instrument).await
+    }
+
+    async fn spawn_reports(&self, on_report: ReportSender, supervisor: &Supervisor) -> Result<()> {
+        *self.at_stream_start.lock().unwrap() = self.trades.last("SOL/GBP");
+        self.venue.spawn_reports(on_report, supervisor).await
+    }
+
+    fn trade_checkpoint(&self) -> Option<TradeCheckpoint> {
+        Some(self.trades.clone())
+    }
+
+    async fn spawn_inventory(
+        &self,
+        instrument: &Instrument,
+        supervisor: &Supervisor,
+    ) -> Result<DynamicInventorySource> {
+        self.venue.spawn_inventory(instrument, supervisor).await
+    }
+}
+
+#[tokio::test]
+async fn restores_the_trade_checkpoint_before_the_report_stream_starts() {
+    let store = store("state-trade-checkpoint");
+    store.save(&state()).unwrap();
+
+    let mut config = AppConfig::default();
+    config.venue.kind = VenueKind::Kraken;
+    config.watchdog.exit = false;
+    config.state.path = Some(store.path().to_path_buf());
+
+    let (reports, _) = broadcast::channel(config.channels.order_reports);
+    let at_stream_start = Arc::new(Mutex::new(None));
+    let trades = TradeCheckpoint::new();
+    let venue = WithTrades {
+        venue: MockVenue::new(reports.clone(), INITIAL),
+        trades: trades.clone(),
+        at_stream_start: at_stream_start.clone(),
+    };
+    let connections = Connections {
+        venue: Box::new(venue),
+        reports,
+        market: Arc::new(Quiet),
+    };
+
+    let instrument = InstrumentConfig::default().load().unwrap();
+    Engine::start("trade-checkpoint", config, vec![instrument], connections)
+        .await
+        .unwrap();
+
+    assert_eq!(*at_stream_start.lock().unwrap(), Some(41));
+    assert_eq!(trades.saved(), state().last_trade_ids);
+}
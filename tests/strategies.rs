@@ -0,0 +1,643 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+
+use accumulator::config::app_config::AppConfig;
+use accumulator::events::MarketEvent;
+use accumulator::execution::order_action::Side::{Buy, Sell};
+use accumulator::market::market_state::MarketState;
+use accumulator::scenario::scenario::Scenario;
+use accumulator::scenario::strategies::StrategyKind;
+use accumulator::signals::config::SignalsConfig;
+use accumulator::signals::signal_state::SignalState;
+use accumulator::strategy::config::StrategyConfig;
+use accumulator::strategy::strategies::avellaneda_stoikov::{
+    AvellanedaStoikovParams, AvellanedaStoikovStrategy,
+};
+use accumulator::strategy::strategies::layered_mm::{
+    LayeredMarketMakerParams, LayeredMarketMakerStrategy,
+};
+use accumulator::strategy::strategies::regime_switch::{RegimeSwitchParams, RegimeSwitchStrategy};
+use accumulator::strategy::strategies::simple_mm::{
+    SimpleMarketMakerParams, SimpleMarketMakerStrategy,
+};
+use accumulator::strategy::strategy::Strategy;
+use accumulator::strategy::strategy_helpers::{StrategyHelpers, VolatilityScaling};
+use accumulator::types::fx::{ExposureCurrency, StaticFxRates};
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::inventory::Inventory;
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+use accumulator::types::quote::Quote;
+use accumulator::types::quote_target::NoQuoteReason;
+
+/// The market and `kind`'s signals after half an hour of a drifting, oscillating market.
+fn warmed_up(kind: StrategyKind, instrument: &Instrument) -> (MarketState, SignalState) {
+    let start = Instant::now();
+    let mut signals = Scenario::signals(kind, &SignalsConfig::default());
+    let mut market = MarketState::new();
+
+    for second in 0..1_800u64 {
+        let t = second as f64;
+        let mid = 93.0 + t * 0.0005 + (t / 60.0).sin() * 0.25;
+        let now = start + Duration::from_secs(second);
+        let event = MarketEvent::TopOfBook {
+            instrument: instrument.clone(),
+            best_bid: Price::new(mid - 0.05),
+            best_ask: Price::new(mid + 0.05),
+            bid_size: None,
+            ask_size: None,
+            timestamp_ms: second * 1_000,
+        };
+        market.on_market_event(&event, now);
+        signals.update(&market, now);
+    }
+
+    (market, signals)
+}
+
+/// Every selectable strategy builds with its signals, warms up on half an hour of a
+/// drifting, oscillating market and then decides on a quote from warm signals.
+#[test]
+fn every_strategy_kind_runs_against_a_synthetic_market() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+
+    for &kind in StrategyKind::value_variants() {
+        let config = StrategyConfig {
+            kind,
+            ..StrategyConfig::default()
+        };
+        let strategy = Scenario::strategy(&config, &instrument);
+        let (market, signals) = warmed_up(kind, &instrument);
+
+        let target = strategy.compute_target(&market, &signals, Inventory::new(1.0, 500.0));
+        match target {
+            Ok(target) => assert!(
+                target.bid.is_some() || target.ask.is_some(),
+                "{kind}: empty target"
+            ),
+            Err(
+                reason @ (NoQuoteReason::MissingTopOfBook
+                | NoQuoteReason::MissingFairPrice
+                | NoQuoteReason::MissingMid
+                | NoQuoteReason::MissingEma
+                | NoQuoteReason::MissingSlowEma),
+            ) => panic!("{kind}: signals never warmed up: {reason:?}"),
+            Err(_) => {}
+        }
+    }
+}
+
+#[test]
+fn pair_overrides_change_only_the_fields_they_set() {
+    let config = AppConfig::from_yaml(
+        "strategy:
+  mean_reversion:
+    entry_threshold_ticks: 4.0
+    trend_filter_ticks: 1.0
+  pairs:
+    SOL/GBP:
+      mean_reversion:
+        entry_threshold_ticks: 6.0
+",
+    )
+    .unwrap();
+
+    let sol = config
+        .strategy
+        .for_instrument(&InstrumentConfig::default().load().unwrap())
+        .unwrap();
+    assert_eq!(sol.mean_reversion.entry_threshold_ticks, 6.0);
+    assert_eq!(sol.mean_reversion.trend_filter_ticks, 1.0);
+
+    let btc = config
+        .strategy
+        .for_instrument(&"BTC/GBP".parse().unwrap())
+        .unwrap();
+    assert_eq!(btc.mean_reversion.entry_threshold_ticks, 4.0);
+}
+
+#[test]
+fn rejects_nonsense_parameters_by_key() {
+    for (yaml, message) in [
+        (
+            "strategy:\n  mean_reversion:\n    entry_threshold_ticks: 0.0\n",
+            "strategy.mean_reversion.entry_threshold_ticks: must be > 0",
+        ),
+        (
+            "strategy:\n  pairs:\n    SOL/GBP:\n      trend_following:\n        entry_threshold_ticks: -1.0\n",
+            "strategy.pairs.SOL/GBP.trend_following.entry_threshold_ticks: must be > 0",
+        ),
+        (
+            "strategy:\n  pairs:\n    SOL/GBP:\n      kind: simple-mm\n",
+            "strategy.pairs.SOL/GBP.kind: cannot be set per pair",
+        ),
+        ("signals:\n  slow_tau_secs: -5.0\n", "signals.slow_tau_secs"),
+    ] {
+        let error = format!("{:#}", AppConfig::from_yaml(yaml).unwrap_err());
+        assert!(error.contains(message), "{yaml}: {error}");
+    }
+
+    let error = format!(
+        "{:#}",
+        AppConfig::from_yaml(
+            "strategy:\n  pairs:\n    SOL/GBP:\n      mean_reversion:\n        entry_threshold: 4.0\n"
+        )
+        .unwrap_err()
+    );
+    assert!(error.contains("strategy.pairs.SOL/GBP"), "{error}");
+    assert!(error.contains("entry_threshold"), "{error}");
+}
+
+#[test]
+fn does_not_quote_a_size_that_rounds_below_the_minimum_order() {
+    let sol = InstrumentConfig::default().load().unwrap();
+    let config = StrategyConfig {
+        kind: StrategyKind::SimpleMarketMaker,
+        ..StrategyConfig::default()
+    };
+
+    // 5.00 of notional at about 93 is 0.0537, which rounds down to 0.05.
+    for (min_order_quantity, quotes) in [(0.05, true), (0.06, false)] {
+        let mut rules = sol.trading_rules();
+        rules.min_order_quantity = min_order_quantity;
+        let instrument = Instrument::new(sol.base().to_string(), sol.quote().to_string(), rules);
+
+        let strategy = Scenario::strategy(&config, &instrument);
+        let (market, signals) = warmed_up(config.kind, &instrument);
+        let target = strategy.compute_target(&market, &signals, Inventory::new(1.0, 500.0));
+        if quotes {
+            assert!(target.is_ok(), "{min_order_quantity}: {target:?}");
+        } else {
+            assert!(
+                matches!(target, Err(NoQuoteReason::InvalidQuantity)),
+                "{min_order_quantity}: {target:?}"
+            );
+        }
+    }
+}
+
+/// SOL/GBP allowed 100.00 of notional an order, about 1.07 SOL.
+fn sol_with_room() -> Instrument {
+    let sol = InstrumentConfig::default().load().unwrap();
+    let mut rules = sol.trading_rules();
+    rules.max_order_notional = 100.0;
+    Instrument::new(sol.base().to_string(), sol.quote().to_string(), rules)
+}
+
+#[test]
+fn volatility_scaled_sizes_shrink_as_volatility_rises_and_stay_in_bounds() {
+    let instrument = sol_with_room();
+    let strategy = SimpleMarketMakerStrategy::for_instrument(&instrument);
+    let rules = instrument.trading_rules();
+    let full = strategy.size_from_notional(93.0).unwrap().as_f64();
+    let reference = 0.02;
+
+    for scaling in [VolatilityScaling::Linear, VolatilityScaling::Inverse] {
+        let sizes: Vec<f64> = (0..=40)
+            .map(|step| {
+                let volatility = reference * step as f64 / 10.0;
+                strategy
+                    .size_from_notional_scaled(93.0, Some(volatility), reference, scaling)
+                    .unwrap()
+                    .as_f64()
+            })
+            .collect();
+
+        assert_eq!(sizes[0], full, "{scaling:?}: unscaled below the reference");
+        assert_eq!(sizes[10], full, "{scaling:?}: unscaled at the reference");
+        assert!(
+            sizes.windows(2).all(|pair| pair[1] <= pair[0]),
+            "{scaling:?}: {sizes:?}"
+        );
+        assert!(
+            sizes
+                .iter()
+                .all(|size| *size >= rules.quantity_step && *size <= full),
+            "{scaling:?}: {sizes:?}"
+        );
+        assert!(sizes[40] < full / 2.0, "{scaling:?}: {sizes:?}");
+    }
+
+    assert_eq!(
+        strategy
+            .size_from_notional_scaled(93.0, None, reference, VolatilityScaling::Linear)
+            .map(Quantity::as_f64),
+        Some(full)
+    );
+}
+
+#[test]
+fn simple_mm_quotes_smaller_in_a_volatile_market_when_enabled() {
+    let instrument = sol_with_room();
+    let (market, signals) = warmed_up(StrategyKind::SimpleMarketMaker, &instrument);
+    let quantity = |volatility_sizing_enabled| {
+        let params = SimpleMarketMakerParams {
+            volatility_sizing_enabled,
+            // Far below the synthetic market's volatility.
+            volatility_reference_ticks: 0.01,
+            ..SimpleMarketMakerParams::default()
+        };
+        let target = SimpleMarketMakerStrategy::from_params(&instrument, &params)
+            .compute_target(&market, &signals, Inventory::new(0.0, 500.0))
+            .unwrap();
+        target.bid.unwrap().quantity.as_f64()
+    };
+
+    assert!(quantity(true) < quantity(false));
+    assert_eq!(quantity(true), instrument.trading_rules().quantity_step);
+}
+
+/// Five minutes of the mid swinging `amplitude` either side of 93.00 every second, in a
+/// book a whole 1.00 wide, ending on 93.00.
+fn choppy(instrument: &Instrument, amplitude: f64) -> (MarketState, SignalState) {
+    let start = Instant::now();
+    let mut signals = Scenario::signals(StrategyKind::AvellanedaStoikov, &SignalsConfig::default());
+    let mut market = MarketState::new();
+
+    for second in 0..=300u64 {
+        let mid = match second {
+            300 => 93.0,
+            second if second % 2 == 0 => 93.0 + amplitude,
+            _ => 93.0 - amplitude,
+        };
+        let now = start + Duration::from_secs(second);
+        let event = MarketEvent::TopOfBook {
+            instrument: instrument.clone(),
+            best_bid: Price::new(mid - 0.5),
+            best_ask: Price::new(mid + 0.5),
+            bid_size: None,
+            ask_size: None,
+            timestamp_ms: second * 1_000,
+        };
+        market.on_market_event(&event, now);
+        signals.update(&market, now);
+    }
+
+    (market, signals)
+}
+
+/// `(bid, ask)` prices quoted for `base` of inventory.
+fn avellaneda_stoikov_quotes(
+    market: &MarketState,
+    signals: &SignalState,
+    base: f64,
+) -> (Option<f64>, Option<f64>) {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let target = AvellanedaStoikovStrategy::new(&instrument, &AvellanedaStoikovParams::default())
+        .compute_target(market, signals, Inventory::new(base, 500.0))
+        .unwrap();
+    let price = |quote: Option<Quote>| quote.map(|quote| quote.price.as_f64());
+    (price(target.bid), price(target.ask))
+}
+
+#[test]
+fn avellaneda_stoikov_skews_both_quotes_against_the_inventory() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let (market, signals) = choppy(&instrument, 0.01);
+
+    let (flat_bid, flat_ask) = avellaneda_stoikov_quotes(&market, &signals, 0.0);
+    let (flat_bid, flat_ask) = (flat_bid.unwrap(), flat_ask.unwrap());
+    assert!(flat_bid < 93.0 && flat_ask > 93.0, "{flat_bid} {flat_ask}");
+
+    // Long: both quotes drop, to sell the position down. Short: both rise.
+    let (long_bid, long_ask) = avellaneda_stoikov_quotes(&market, &signals, 0.5);
+    assert!(long_bid.unwrap() < flat_bid && long_ask.unwrap() < flat_ask);
+    let (short_bid, short_ask) = avellaneda_stoikov_quotes(&market, &signals, -0.5);
+    assert!(short_bid.unwrap() > flat_bid && short_ask.unwrap() > flat_ask);
+
+    // Still under the 200.00 exposure limit, but a filled bid would take it over.
+    let (bid, ask) = avellaneda_stoikov_quotes(&market, &signals, 2.12);
+    assert_eq!(bid, None);
+    assert!(ask.is_some());
+}
+
+#[test]
+fn exposure_limits_are_in_the_exposure_currency_when_one_is_set() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let (market, signals) = choppy(&instrument, 0.01);
+    let rates = Arc::new(StaticFxRates::new(&[("GBP/EUR".to_string(), 1.2)].into()));
+    let quotes = |exposure_currency: &ExposureCurrency| {
+        let mut strategy: Box<dyn Strategy> = Box::new(AvellanedaStoikovStrategy::new(
+            &instrument,
+            &AvellanedaStoikovParams::default(),
+        ));
+        strategy.set_exposure_currency(exposure_currency);
+        strategy.compute_target(&market, &signals, Inventory::new(1.8, 500.0))
+    };
+
+    // About 172.00 GBP once a bid fills: under the 200.00 limit in GBP, over it in EUR.
+    let gbp = quotes(&ExposureCurrency::default()).unwrap();
+    assert!(gbp.bid.is_some() && gbp.ask.is_some());
+    let eur = quotes(&ExposureCurrency::new("EUR", rates.clone())).unwrap();
+    assert!(eur.bid.is_none() && eur.ask.is_some());
+
+    assert!(matches!(
+        quotes(&ExposureCurrency::new("USD", rates)),
+        Err(NoQuoteReason::MissingFxRate { from, to }) if from == "GBP" && to == "USD"
+    ));
+}
+
+#[test]
+fn avellaneda_stoikov_widens_its_spread_with_volatility() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let spread = |amplitude| {
+        let (market, signals) = choppy(&instrument, amplitude);
+        match avellaneda_stoikov_quotes(&market, &signals, 0.0) {
+            (Some(bid), Some(ask)) => ask - bid,
+            quotes => panic!("{amplitude}: {quotes:?}"),
+        }
+    };
+
+    let (calm, volatile) = (spread(0.01), spread(0.10));
+    assert!(volatile > calm * 5.0, "{calm} {volatile}");
+}
+
+/// `(price, quantity)` of each level on a side, level 0 first.
+type Levels = Vec<(String, f64)>;
+
+/// The bid and ask levels the layered strategy quotes in a 93.00 / 93.10 book for `base`
+/// of inventory.
+fn layered_levels(base: f64) -> (Levels, Levels) {
+    let instrument = sol_with_room();
+    let mut market = MarketState::new();
+    let event = MarketEvent::TopOfBook {
+        instrument: instrument.clone(),
+        best_bid: Price::new(93.00),
+        best_ask: Price::new(93.10),
+        bid_size: None,
+        ask_size: None,
+        timestamp_ms: 0,
+    };
+    market.on_market_event(&event, Instant::now());
+    let signals = Scenario::signals(StrategyKind::LayeredMarketMaker, &SignalsConfig::default());
+
+    let params = LayeredMarketMakerParams {
+        levels: 3,
+        level_spacing_ticks: 2,
+        size_decay: 0.5,
+    };
+    let target = LayeredMarketMakerStrategy::new(&instrument, &params)
+        .compute_target(&market, &signals, Inventory::new(base, 500.0))
+        .unwrap();
+
+    let levels = |side| {
+        target
+            .quotes(side)
+            .map(|quote| (quote.price.to_string(), quote.quantity.as_f64()))
+            .collect()
+    };
+    (levels(Buy), levels(Sell))
+}
+
+#[test]
+fn layered_mm_steps_each_level_away_from_the_touch_at_a_smaller_size() {
+    let (bids, asks) = layered_levels(0.0);
+
+    let levels = |prices: [&str; 3]| -> Levels {
+        prices
+            .into_iter()
+            .map(String::from)
+            .zip([1.07, 0.53, 0.26])
+            .collect()
+    };
+    assert_eq!(bids, levels(["93.00", "92.98", "92.96"]));
+    assert_eq!(asks, levels(["93.10", "93.12", "93.14"]));
+}
+
+#[test]
+fn layered_mm_drops_the_levels_that_would_breach_the_exposure_limit() {
+    // Long 0.5: all three bids filling would hold 2.36, about 220.00 against a 200.00
+    // limit, so the furthest goes. Every ask reduces the position.
+    let (bids, asks) = layered_levels(0.5);
+
+    assert_eq!(bids.len(), 2);
+    assert_eq!(asks.len(), 3);
+}
+
+/// A minute of a steady book 1.00 wide around 93.00, with `sizes` resting at the touch.
+fn leaning(instrument: &Instrument, sizes: Option<(f64, f64)>) -> (MarketState, SignalState) {
+    let start = Instant::now();
+    let mut signals = Scenario::signals(StrategyKind::SimpleMarketMaker, &SignalsConfig::default());
+    let mut market = MarketState::new();
+
+    for second in 0..60u64 {
+        let now = start + Duration::from_secs(second);
+        let event = MarketEvent::TopOfBook {
+            instrument: instrument.clone(),
+            best_bid: Price::new(92.5),
+            best_ask: Price::new(93.5),
+            bid_size: sizes.map(|(bid, _)| bid),
+            ask_size: sizes.map(|(_, ask)| ask),
+            timestamp_ms: second * 1_000,
+        };
+        market.on_market_event(&event, now);
+        signals.update(&market, now);
+    }
+
+    (market, signals)
+}
+
+#[test]
+fn book_imbalance_needs_sizes_on_both_sides() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+
+    let (market, signals) = leaning(&instrument, Some((3.0, 1.0)));
+    assert_eq!(market.book_imbalance(), Some(0.5));
+    assert!((signals.imbalance().unwrap() - 0.5).abs() < 1e-9);
+
+    let (market, _) = leaning(&instrument, Some((0.0, 2.0)));
+    assert_eq!(market.book_imbalance(), Some(-1.0));
+
+    for sizes in [Some((0.0, 0.0)), None] {
+        let (market, signals) = leaning(&instrument, sizes);
+        assert_eq!(market.book_imbalance(), None, "{sizes:?}");
+        assert_eq!(signals.imbalance(), None, "{sizes:?}");
+    }
+}
+
+#[test]
+fn the_microprice_leans_towards_the_thinner_side_and_falls_back_to_the_mid() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+
+    // Three on the bid to one on the ask: the next trade is likelier up.
+    let (market, _) = leaning(&instrument, Some((3.0, 1.0)));
+    assert!((market.microprice().unwrap().as_f64() - 93.25).abs() < 1e-9);
+    assert_eq!(market.mid_price(), Some(Price::new(93.0)));
+
+    // Nothing bid: all the weight is on the bid price.
+    let (market, _) = leaning(&instrument, Some((0.0, 2.0)));
+    assert!((market.microprice().unwrap().as_f64() - 92.5).abs() < 1e-9);
+
+    for sizes in [Some((0.0, 0.0)), None] {
+        let (market, _) = leaning(&instrument, sizes);
+        assert_eq!(market.microprice(), market.mid_price(), "{sizes:?}");
+    }
+    assert_eq!(MarketState::new().microprice(), None);
+}
+
+#[test]
+fn simple_mm_shades_its_quotes_towards_the_heavier_side_of_the_book() {
+    let instrument = sol_with_room();
+    let bid = |sizes, imbalance_skew_bps, base| {
+        let (market, signals) = leaning(&instrument, sizes);
+        let params = SimpleMarketMakerParams {
+            imbalance_skew_bps,
+            ..SimpleMarketMakerParams::default()
+        };
+        SimpleMarketMakerStrategy::from_params(&instrument, &params)
+            .compute_target(&market, &signals, Inventory::new(base, 500.0))
+            .unwrap()
+            .bid
+            .unwrap()
+            .price
+            .as_f64()
+    };
+
+    let tick = instrument.trading_rules().price_tick;
+    // Within a tick of `bps` of the fair price from the unshaded bid.
+    let near = |price: f64, bps: f64, unshaded: f64| {
+        (price - unshaded - 93.0 * bps / 10_000.0).abs() <= tick + 1e-9
+    };
+
+    let unshaded = bid(Some((3.0, 1.0)), 0.0, 0.0);
+    assert_eq!(bid(None, 12.0, 0.0), unshaded);
+
+    // Half a unit of imbalance at 12 bps a unit shades the fair price 6 bps.
+    let shaded = bid(Some((3.0, 1.0)), 12.0, 0.0);
+    assert!(near(shaded, 6.0, unshaded), "{unshaded} {shaded}");
+    let against = bid(Some((1.0, 3.0)), 12.0, 0.0);
+    assert!(near(against, -6.0, unshaded), "{unshaded} {against}");
+
+    // However heavy the book, the shade stops at max_skew_bps.
+    let capped = bid(Some((3.0, 1.0)), 1_000.0, 0.0);
+    assert_eq!(capped, bid(Some((3.0, 1.0)), 100.0, 0.0));
+    assert!(near(capped, 10.0, unshaded), "{unshaded} {capped}");
+
+    // A long position pulls the other way.
+    let long = bid(Some((3.0, 1.0)), 0.0, 1.0);
+    assert!(long < unshaded, "{unshaded} {long}");
+    assert!(bid(Some((3.0, 1.0)), 12.0, 1.0) > long);
+}
+
+/// Composite strategies take any strategy as a leg, boxed.
+#[test]
+fn regime_switch_quotes_from_boxed_legs() {
+    let instrument = InstrumentConfig::default().load().unwrap();
+    let (market, signals) = warmed_up(StrategyKind::RegimeSwitch, &instrument);
+    let inventory = Inventory::new(1.0, 500.0);
+
+    let leg = || -> Box<dyn Strategy> {
+        Box::new(SimpleMarketMakerStrategy::for_instrument(&instrument))
+    };
+    let regime_switch =
+        RegimeSwitchStrategy::with_legs(&instrument, &RegimeSwitchParams::default(), leg(), leg());
+
+    let expected = leg().compute_target(&market, &signals, inventory).unwrap();
+    let target = regime_switch
+        .compute_target(&market, &signals, inventory)
+        .unwrap();
+    for (quote, expected) in [(target.bid, expected.bid), (target.ask, expected.ask)] {
+        let (quote, expected) = (quote.unwrap(), expected.unwrap());
+        assert_eq!(quote.price, expected.price);
+        assert_eq!(quote.quantity, expected.quantity);
+    }
+}
+
+#[test]
+fn no_quote_reasons_read_as_errors() {
+    let reason = NoQuoteReason::BelowEntryThreshold {
+        deviation_ticks: 1.5,
+        threshold_ticks: 4.0,
+    };
+    assert_eq!(
+        reason.to_string(),
+        "deviation of 1.50 ticks below the 4.00 tick entry threshold"
+    );
+
+    let error = anyhow::Error::new(NoQuoteReason::MissingEma).context("no quote");
+    assert_eq!(format!("{error:#}"), "no quote: fast EMA still warming up");
+}
+
+/// Bids pile up at the touch after half an hour of a balanced book: only mean reversion
+/// anchored on the microprice sees the price stretched up, with nothing but config.
+#[test]
+fn mean_reversion_runs_on_the_microprice_by_config() {
+    let instrument = sol_with_room();
+    let target = |mid_kind: &str| {
+        let config = AppConfig::from_yaml(&format!(
+            "strategy:
+  kind: mean-reversion
+  mid_kind: {mid_kind}
+"
+        ))
+        .unwrap();
+        let strategy = Scenario::strategy(&config.strategy, &instrument);
+        let mut signals = Scenario::signals_for(&config.strategy, &config.signals, &instrument);
+        let mut market = MarketState::new();
+
+        let start = Instant::now();
+        for second in 0..=1_800u64 {
+            let now = start + Duration::from_secs(second);
+            let bid_size = if second < 1_800 { 1.0 } else { 9.0 };
+            let event = MarketEvent::TopOfBook {
+                instrument: instrument.clone(),
+                best_bid: Price::new(92.5),
+                best_ask: Price::new(93.5),
+                bid_size: Some(bid_size),
+                ask_size: Some(1.0),
+                timestamp_ms: second * 1_000,
+            };
+            market.on_market_event(&event, now);
+            signals.update(&market, now);
+        }
+
+        strategy.compute_target(&market, &signals, Inventory::new(0.0, 500.0))
+    };
+
+    assert!(matches!(
+        target("mid"),
+        Err(NoQuoteReason::BelowEntryThreshold { .. })
+    ));
+    let target = target("microprice").unwrap();
+    assert!(target.bid.is_none());
+    assert!(target.ask.is_some(), "sells into the stretch");
+}
+
+/// The EMAs are the mid itself after a single book, so the directional strategies wait
+/// for both to warm, here a minute for the fast and ten for the slow.
+#[test]
+fn directional_strategies_wait_for_warm_signals() {
+    let instrument = sol_with_room();
+    let start = Instant::now();
+
+    for kind in [StrategyKind::MeanReversion, StrategyKind::TrendFollowing] {
+        let config = StrategyConfig {
+            kind,
+            ..StrategyConfig::default()
+        };
+        let strategy = Scenario::strategy(&config, &instrument);
+        let mut signals = Scenario::signals(kind, &SignalsConfig::default());
+        let mut market = MarketState::new();
+
+        for second in [0, 1, 120, 600] {
+            let now = start + Duration::from_secs(second);
+            let event = MarketEvent::TopOfBook {
+                instrument: instrument.clone(),
+                best_bid: Price::new(92.95),
+                best_ask: Price::new(93.05),
+                bid_size: None,
+                ask_size: None,
+                timestamp_ms: second * 1_000,
+            };
+            market.on_market_event(&event, now);
+            signals.update(&market, now);
+
+            let target = strategy.compute_target(&market, &signals, Inventory::new(0.0, 500.0));
+            let warming = matches!(target, Err(NoQuoteReason::SignalsWarmingUp));
+            assert_eq!(warming, second < 600, "{kind} at {second}s: {target:?}");
+            assert_eq!(signals.is_warm(), second >= 600);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+
+use accumulator::engine::supervisor::{RestartPolicy, Supervisor, TaskHealth};
+
+const FAST_RESTART: RestartPolicy = RestartPolicy::Restart {
+    initial: Duration::from_millis(5),
+    max: Duration::from_millis(20),
+};
+
+/// A task that panics on each of its first `panics` runs, then runs until aborted.
+fn flaky(panics: u32) -> (Arc<AtomicU32>, impl Fn() -> BoxFuture<'static, ()>) {
+    let runs = Arc::new(AtomicU32::new(0));
+    let task = {
+        let runs = Arc::clone(&runs);
+        move || {
+            let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+            let future: BoxFuture<'static, ()> = Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                if run <= panics {
+                    panic!("scheduled failure {run}");
+                }
+                std::future::pending::<()>().await;
+            });
+            future
+        }
+    };
+
+    (runs, task)
+}
+
+async fn wait_for(supervisor: &Supervisor, done: impl Fn(&TaskHealth) -> bool) -> TaskHealth {
+    for _ in 0..500 {
+        if let Some(health) = supervisor.health().into_iter().find(|health| done(health)) {
+            return health;
+        }
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+    panic!("timed out: {:#?}", supervisor.health());
+}
+
+#[tokio::test]
+async fn restarts_a_panicking_task_and_counts_restarts() {
+    let (supervisor, mut escalations) = Supervisor::new();
+    let (runs, task) = flaky(3);
+    supervisor.spawn("flaky", FAST_RESTART, task);
+
+    let health = wait_for(&supervisor, |health| health.restarts == 3 && health.running).await;
+    assert_eq!(health.task, "flaky");
+    assert!(!health.critical);
+    assert!(health.last_restart.is_some());
+    assert_eq!(
+        health.last_failure.as_deref(),
+        Some("panicked: scheduled failure 3")
+    );
+    assert_eq!(runs.load(Ordering::SeqCst), 4);
+
+    // Restarted tasks never reach the engine.
+    assert!(escalations.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn a_task_that_returns_is_not_restarted() {
+    let (supervisor, _escalations) = Supervisor::new();
+    supervisor.spawn("oneshot", FAST_RESTART, || async {});
+
+    let health = wait_for(&supervisor, |health| !health.running).await;
+    assert_eq!(health.restarts, 0);
+    assert_eq!(health.last_failure, None);
+}
+
+#[tokio::test]
+async fn escalates_when_a_critical_task_panics() {
+    let (supervisor, mut escalations) = Supervisor::new();
+    let (runs, task) = flaky(1);
+    supervisor.spawn("executions", RestartPolicy::Escalate, task);
+
+    let escalation = tokio::time::timeout(Duration::from_secs(1), escalations.recv())
+        .await
+        .expect("escalation")
+        .unwrap();
+    assert_eq!(escalation.task, "executions");
+    assert_eq!(escalation.failure, "panicked: scheduled failure 1");
+
+    let health = supervisor.health();
+    assert_eq!(health.len(), 1);
+    assert!(health[0].critical && !health[0].running);
+    assert_eq!(health[0].restarts, 0);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn escalates_failures_reported_by_the_engine() {
+    let (supervisor, mut escalations) = Supervisor::new();
+    supervisor.escalate("order_reports", "report channel closed");
+
+    let escalation = escalations.recv().await.unwrap();
+    assert_eq!(escalation.task, "order_reports");
+    assert_eq!(escalation.failure, "report channel closed");
+    assert!(!supervisor.health()[0].running);
+}
+
+#[tokio::test]
+async fn shutdown_stops_tasks_without_restarting_or_escalating() {
+    let (supervisor, mut escalations) = Supervisor::new();
+    let (runs, task) = flaky(0);
+    supervisor.spawn("executions", RestartPolicy::Escalate, task);
+    let (_, task) = flaky(0);
+    supervisor.spawn("market", FAST_RESTART, task);
+    wait_for(&supervisor, |health| health.running).await;
+
+    supervisor.shutdown();
+    for _ in 0..500 {
+        if supervisor.health().iter().all(|health| !health.running) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+
+    for health in supervisor.health() {
+        assert!(!health.running, "{health:#?}");
+        assert_eq!(health.restarts, 0);
+        assert_eq!(health.last_failure, None);
+    }
+    assert!(escalations.try_recv().is_err());
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,181 @@
+mod common;
+
+use std::time::Instant;
+
+use accumulator::execution::order_action::OrderAction;
+use accumulator::execution::order_action::Side::Buy;
+use accumulator::execution::order_ids::OrderIds;
+use accumulator::execution::order_manager::OrderManager;
+use accumulator::types::instrument::{Instrument, InstrumentConfig};
+use accumulator::types::price::Price;
+use accumulator::types::quantity::Quantity;
+use accumulator::types::quote::Quote;
+use accumulator::types::quote_target::QuoteTarget;
+use accumulator::types::trading_hours::TradingHours;
+use accumulator::types::trading_rules::TradingRules;
+
+use common::qty;
+
+/// SOL/GBP with orders of at least 0.05.
+fn sol_with_minimum() -> Instrument {
+    let sol = InstrumentConfig::default().load().unwrap();
+    let mut rules = sol.trading_rules();
+    rules.min_order_quantity = 0.05;
+    Instrument::new(sol.base().to_string(), sol.quote().to_string(), rules)
+}
+
+#[test]
+fn a_notional_rounding_to_just_below_the_minimum_sizes_to_zero() {
+    let rules = sol_with_minimum().trading_rules();
+
+    assert!((rules.quantity_from_notional(5.00, 100.00).as_f64() - 0.05).abs() < 1e-12);
+    // 0.0499 rounds down to 0.04, under the minimum.
+    assert_eq!(rules.quantity_from_notional(5.00, 100.20), Quantity::ZERO);
+    assert_eq!(rules.round_quantity_to_step(qty(0.0499)), Quantity::ZERO);
+    assert!((rules.round_quantity_to_step(qty(0.0599)).as_f64() - 0.05).abs() < 1e-12);
+}
+
+#[test]
+fn checks_orders_against_the_rules() {
+    let rules = sol_with_minimum().trading_rules();
+
+    assert!(rules.check_order(Price::new(93.01), qty(0.05)).is_ok());
+    assert!(rules.check_order(Price::new(93.01), qty(0.07)).is_ok());
+    for (price, quantity, reason) in [
+        (93.01, 0.04, "below the minimum"),
+        (93.01, 0.055, "not on a 0.01 step"),
+        (93.005, 0.05, "not on a 0.01 tick"),
+        (0.0, 0.05, "not positive"),
+    ] {
+        let error = rules
+            .check_order(Price::new(price), qty(quantity))
+            .unwrap_err();
+        assert!(error.to_string().contains(reason), "{error}");
+    }
+}
+
+#[tokio::test]
+async fn quotes_the_venue_would_refuse_are_dropped_before_placement() {
+    let instrument = sol_with_minimum();
+    let mut manager = OrderManager::new(OrderIds::sequential());
+    let quote = |price: f64, quantity: f64| {
+        Some(Quote {
+            price: Price::new(price),
+            quantity: qty(quantity),
+        })
+    };
+
+    let target = QuoteTarget::new(quote(93.00, 0.04), quote(93.10, 0.05));
+    let actions = manager
+        .actions_for_target(&instrument, &target, Instant::now())
+        .await
+        .unwrap();
+
+    assert!(
+        matches!(&actions[..], [OrderAction::Place(order)] if order.price == Price::new(93.10)),
+        "{actions:?}"
+    );
+    assert_eq!(manager.order_id(Buy), None);
+}
+
+/// SOL/GBP rules with `price_tick` and `quantity_step` replaced.
+fn with_steps(price_tick: f64, quantity_step: f64) -> TradingRules {
+    TradingRules {
+        price_tick,
+        quantity_step,
+        ..InstrumentConfig::default().load().unwrap().trading_rules()
+    }
+}
+
+#[test]
+fn prices_are_written_to_the_ticks_precision_without_float_artifacts() {
+    for (tick, decimals, raw, expected) in [
+        (0.01, 2, 0.1 + 0.2, "0.30"),
+        (0.01, 2, 92.97, "92.97"),
+        (0.5, 1, 93.74, "93.5"),
+        (0.001, 3, 1.2345678, "1.234"),
+        (5e-7, 7, 0.123456789, "0.1234565"),
+        (1.0, 0, 64_012.9, "64012"),
+    ] {
+        let rules = with_steps(tick, 0.01);
+        assert_eq!(rules.price_decimals(), decimals, "{tick}");
+
+        let price = rules.round_price_to_tick(raw);
+        let formatted = rules.format_price(price);
+        assert_eq!(formatted, expected, "{tick} {raw}");
+        // What goes on the wire is the price the rules rounded to.
+        let wire: f64 = formatted.parse().unwrap();
+        assert!((wire - price.as_f64()).abs() < tick * 1e-6, "{tick} {raw}");
+    }
+}
+
+#[test]
+fn formatting_rounds_half_to_even_at_the_allowed_precision() {
+    let rules = with_steps(0.01, 0.001);
+
+    assert_eq!(rules.format_price(Price::new(0.125)), "0.12");
+    assert_eq!(rules.format_price(Price::new(0.375)), "0.38");
+    assert_eq!(rules.format_quantity(qty(0.0625)), "0.062");
+    assert_eq!(rules.format_quantity(qty(0.1 + 0.2)), "0.300");
+    assert_eq!(
+        with_steps(0.01, 5e-7).format_quantity(qty(3e-7)),
+        "0.0000003"
+    );
+}
+
+#[test]
+fn trading_hours_parse_windows_to_the_minute_and_reject_bad_ones() {
+    let rules: TradingRules = serde_yaml::from_str(
+        "
+        price_tick: 0.01
+        quantity_step: 0.01
+        min_half_spread: 0.01
+        max_order_notional: 5.0
+        max_exposure_in_quote: 200.0
+        trading_hours:
+          windows:
+            - { start: '07:30', end: '11:00' }
+            - { start: '22:15', end: '02:00' }
+          weekend_pause: true
+          close_buffer_mins: 15
+        ",
+    )
+    .unwrap();
+    rules.validate().unwrap();
+    let hours = rules.trading_hours.unwrap();
+    assert_eq!(hours.windows[0].start.minutes(), 7 * 60 + 30);
+    assert_eq!(hours.windows[1].end.to_string(), "02:00");
+    assert_eq!(hours.close_buffer_mins, 15);
+    // Closed between the windows and open on both sides of midnight.
+    assert!(!hours.is_open(12 * 60));
+    assert!(hours.is_open(23 * 60) && hours.is_open(60));
+
+    let parse = |hours: &str| serde_yaml::from_str::<TradingHours>(hours);
+    for bad in ["25:00", "7:5", "07:60", "0700", "24:01"] {
+        let error = parse(&format!(
+            "{{ windows: [{{ start: '{bad}', end: '12:00' }}], weekend_pause: false }}"
+        ))
+        .unwrap_err();
+        assert!(error.to_string().contains(bad), "{bad}: {error}");
+    }
+    // The old single window is no longer read, rather than silently ignored.
+    assert!(parse("{ start_hour: 8, end_hour: 20, weekend_pause: false }").is_err());
+
+    for (hours, reason) in [
+        (
+            "{ windows: [], weekend_pause: false }",
+            "windows must not be empty",
+        ),
+        (
+            "{ windows: [{ start: '09:00', end: '09:00' }], weekend_pause: false }",
+            "windows[0]: start and end must differ",
+        ),
+        (
+            "{ windows: [{ start: '24:00', end: '09:00' }], weekend_pause: false }",
+            "windows[0].start must be before 24:00",
+        ),
+    ] {
+        let error = parse(hours).unwrap().validate().unwrap_err();
+        assert_eq!(error.to_string(), reason, "{hours}");
+    }
+}